@@ -36,9 +36,12 @@ const MARKET_VAULT_SEED = Buffer.from('market_vault');
  * - resolution_time: i64
  * - finalization_deadline: i64
  * - creator_fee_bps: u16
+ * - challenge_window_secs: i64 (left 0 = global window)
+ * - category: u8 (left 0 = Other)
+ * - tags_hash: [u8; 32] (left zero = no tags)
  */
 function serializeCreateMarketArgs(questionHash, resolutionSpecHash, resolutionTime, finalizationDeadline, creatorFeeBps) {
-  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2); // 83 bytes
+  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2 + 8 + 1 + 32); // 124 bytes
   let offset = 0;
   
   // Instruction index = 2 (CreateMarket is the 3rd variant in the enum)
@@ -29,7 +29,7 @@ const MARKET_VAULT_SEED = Buffer.from('market_vault');
 const RELAYER_KEYPAIR = [9,201,67,159,134,166,247,250,175,67,60,55,49,132,104,141,207,35,62,44,129,223,128,15,8,206,189,184,216,157,244,27,16,42,227,1,241,96,112,131,253,96,7,205,80,14,207,215,38,236,183,121,99,16,116,102,82,186,3,234,3,4,107,113];
 
 function serializeCreateMarketArgs(questionHash, resolutionSpecHash, resolutionTime, finalizationDeadline, creatorFeeBps) {
-  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2);
+  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2 + 8 + 1 + 32); // challenge_window_secs, category, tags_hash left 0
   let offset = 0;
   buffer.writeUInt8(1, offset); offset += 1;
   questionHash.copy(buffer, offset); offset += 32;
@@ -40,7 +40,7 @@ function serializeCreateMarketArgs(resolutionTime, finalizationDeadline, creator
     .update('Immediate resolution test')
     .digest();
   
-  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2);
+  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2 + 8 + 1 + 32); // challenge_window_secs, category, tags_hash left 0
   let offset = 0;
   
   buffer.writeUInt8(CREATE_MARKET_IX, offset); offset += 1;
@@ -34,7 +34,7 @@ const MARKET_VAULT_SEED = Buffer.from('market_vault');
 const NEXT_MARKET_ID_OFFSET = 8 + 32 + 32 + 32 + 32 + 32; // = 168
 
 function serializeCreateMarketArgs(questionHash, resolutionSpecHash, resolutionTime, finalizationDeadline, creatorFeeBps) {
-  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2);
+  const buffer = Buffer.alloc(1 + 32 + 32 + 8 + 8 + 2 + 8 + 1 + 32); // challenge_window_secs, category, tags_hash left 0
   let offset = 0;
   buffer.writeUInt8(1, offset); offset += 1;
   questionHash.copy(buffer, offset); offset += 32;
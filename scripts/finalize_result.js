@@ -21,6 +21,12 @@ const PM_CONFIG_SEED = Buffer.from('pm_config');
 const MARKET_SEED = Buffer.from('market');
 const ORACLE_PROPOSAL_SEED = Buffer.from('oracle_proposal');
 
+// Vault Program accounts (proposer bond is released via CPI)
+const VAULT_PROGRAM_ID = config.VAULT_PROGRAM;
+const VAULT_CONFIG_PDA = new PublicKey(process.env.VAULT_CONFIG_PDA || 'rMLrkwxV4uNLKmL2vmP3CJbYPbKamjZD4wjeKZsCy1g');
+const USER_SEED = Buffer.from('user');
+const PM_USER_SEED = Buffer.from('prediction_market_user');
+
 // Instruction index (Initialize=0, ..., ProposeResult=14, ChallengeResult=15, FinalizeResult=16)
 const FINALIZE_RESULT_IX = 16;
 
@@ -55,20 +61,45 @@ async function main() {
   const instructionData = Buffer.from([FINALIZE_RESULT_IX]);
   console.log(`\nInstruction data: ${instructionData.toString('hex')}`);
   
+  // Proposer is stored after discriminator + market_id in OracleProposal
+  const proposalAccount = await connection.getAccountInfo(proposalPda);
+  if (!proposalAccount) {
+    console.error('❌ Proposal not found!');
+    return;
+  }
+  const proposer = new PublicKey(proposalAccount.data.slice(16, 48));
+  const [proposerVaultPda] = PublicKey.findProgramAddressSync(
+    [USER_SEED, proposer.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+  const [proposerPmUserPda] = PublicKey.findProgramAddressSync(
+    [PM_USER_SEED, proposer.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+  console.log(`  Proposer: ${proposer.toBase58()}`);
+  
   /**
-   * Accounts for FinalizeResult (from processor.rs):
+   * Accounts for FinalizeResult (from instruction.rs):
    * 0. [signer] Caller
-   * 1. [writable] Config
+   * 1. [] Config
    * 2. [writable] Market
    * 3. [writable] OracleProposal
+   * 4. [writable] Proposer's Vault UserAccount
+   * 5. [] Vault Config
+   * 6. [] Vault Program
+   * 7. [writable] Proposer's PMUserAccount
    */
   const finalizeIx = new TransactionInstruction({
     programId: PROGRAM_ID,
     keys: [
       { pubkey: caller.publicKey, isSigner: true, isWritable: true },
-      { pubkey: configPda, isSigner: false, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: false },
       { pubkey: marketPda, isSigner: false, isWritable: true },
       { pubkey: proposalPda, isSigner: false, isWritable: true },
+      { pubkey: proposerVaultPda, isSigner: false, isWritable: true },
+      { pubkey: VAULT_CONFIG_PDA, isSigner: false, isWritable: false },
+      { pubkey: VAULT_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: proposerPmUserPda, isSigner: false, isWritable: true },
     ],
     data: instructionData,
   });
@@ -24,6 +24,12 @@ const PM_CONFIG_SEED = Buffer.from('pm_config');
 const MARKET_SEED = Buffer.from('market');
 const PROPOSAL_SEED = Buffer.from('oracle_proposal');
 
+// Vault Program accounts (proposer bond is locked via CPI)
+const VAULT_PROGRAM_ID = config.VAULT_PROGRAM;
+const VAULT_CONFIG_PDA = new PublicKey(process.env.VAULT_CONFIG_PDA || 'rMLrkwxV4uNLKmL2vmP3CJbYPbKamjZD4wjeKZsCy1g');
+const USER_SEED = Buffer.from('user');
+const PM_USER_SEED = Buffer.from('prediction_market_user');
+
 // Instruction index (count from enum)
 // Initialize=0, CreateMarket=1, Activate=2, Pause=3, Resume=4, Cancel=5, Flag=6
 // MintComplete=7, RedeemComplete=8, PlaceOrder=9, CancelOrder=10
@@ -112,13 +118,27 @@ async function main() {
   const instructionData = serializeProposeResultArgs(marketId, result);
   console.log(`\nInstruction data: ${instructionData.toString('hex')}`);
   
+  // Proposer's Vault accounts (bond is locked from available balance)
+  const [userVaultPda] = PublicKey.findProgramAddressSync(
+    [USER_SEED, oracleAdmin.publicKey.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+  const [pmUserPda] = PublicKey.findProgramAddressSync(
+    [PM_USER_SEED, oracleAdmin.publicKey.toBuffer()],
+    VAULT_PROGRAM_ID
+  );
+  
   /**
-   * Accounts for ProposeResult (from processor.rs):
-   * 0. [signer] Oracle Admin
+   * Accounts for ProposeResult (from instruction.rs):
+   * 0. [signer, writable] Proposer (Oracle Admin)
    * 1. [] Config
    * 2. [writable] Market
    * 3. [writable] Proposal PDA
-   * 4. [] System Program
+   * 4. [writable] Proposer's Vault UserAccount
+   * 5. [] Vault Config
+   * 6. [] Vault Program
+   * 7. [] System Program
+   * 8. [writable] Proposer's PMUserAccount
    */
   const proposeResultIx = new TransactionInstruction({
     programId: PROGRAM_ID,
@@ -127,7 +147,11 @@ async function main() {
       { pubkey: configPda, isSigner: false, isWritable: false },
       { pubkey: marketPda, isSigner: false, isWritable: true },
       { pubkey: proposalPda, isSigner: false, isWritable: true },
+      { pubkey: userVaultPda, isSigner: false, isWritable: true },
+      { pubkey: VAULT_CONFIG_PDA, isSigner: false, isWritable: false },
+      { pubkey: VAULT_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: pmUserPda, isSigner: false, isWritable: true },
     ],
     data: instructionData,
   });
@@ -10,23 +10,183 @@
 use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
     msg,
-    program::{invoke, invoke_signed},
+    program::{get_return_data, invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
 };
 
 use crate::error::PredictionMarketError;
 
+// ============================================================================
+// Vault Program Instructions
+// ============================================================================
+
+/// Vault Program instructions invoked by this program
+/// 
+/// Wire format: the Vault instruction index as a tag byte, followed by the
+/// amounts as little-endian u64s (their borsh encoding), in field order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultInstruction {
+    /// Index 16: available_balance -> pm_locked
+    PredictionMarketLock { amount: u64 },
+    /// Index 17: pm_locked -> available_balance
+    PredictionMarketUnlock { amount: u64 },
+    /// Index 18: release `locked_amount`, credit `settlement_amount` to pm_pending_settlement
+    PredictionMarketSettle { locked_amount: u64, settlement_amount: u64 },
+    /// Index 21: lock with minting fee
+    PredictionMarketLockWithFee { gross_amount: u64 },
+    /// Index 22: unlock with redemption fee
+    PredictionMarketUnlockWithFee { gross_amount: u64 },
+    /// Index 24: settle with fee
+    PredictionMarketSettleWithFee { locked_amount: u64, settlement_amount: u64 },
+    /// Index 43: settle straight to available_balance
+    PredictionMarketSettleToAvailable { locked_amount: u64, settlement_amount: u64 },
+    /// Index 49: settle straight to available_balance with fee
+    PredictionMarketSettleToAvailableWithFee { locked_amount: u64, settlement_amount: u64 },
+}
+
+impl VaultInstruction {
+    pub const PREDICTION_MARKET_LOCK: u8 = 16;
+    pub const PREDICTION_MARKET_UNLOCK: u8 = 17;
+    pub const PREDICTION_MARKET_SETTLE: u8 = 18;
+    pub const PREDICTION_MARKET_LOCK_WITH_FEE: u8 = 21;
+    pub const PREDICTION_MARKET_UNLOCK_WITH_FEE: u8 = 22;
+    pub const PREDICTION_MARKET_SETTLE_WITH_FEE: u8 = 24;
+    pub const PREDICTION_MARKET_SETTLE_TO_AVAILABLE: u8 = 43;
+    pub const PREDICTION_MARKET_SETTLE_TO_AVAILABLE_WITH_FEE: u8 = 49;
+    
+    /// Vault instruction index
+    pub fn tag(&self) -> u8 {
+        match self {
+            Self::PredictionMarketLock { .. } => Self::PREDICTION_MARKET_LOCK,
+            Self::PredictionMarketUnlock { .. } => Self::PREDICTION_MARKET_UNLOCK,
+            Self::PredictionMarketSettle { .. } => Self::PREDICTION_MARKET_SETTLE,
+            Self::PredictionMarketLockWithFee { .. } => Self::PREDICTION_MARKET_LOCK_WITH_FEE,
+            Self::PredictionMarketUnlockWithFee { .. } => Self::PREDICTION_MARKET_UNLOCK_WITH_FEE,
+            Self::PredictionMarketSettleWithFee { .. } => Self::PREDICTION_MARKET_SETTLE_WITH_FEE,
+            Self::PredictionMarketSettleToAvailable { .. } => Self::PREDICTION_MARKET_SETTLE_TO_AVAILABLE,
+            Self::PredictionMarketSettleToAvailableWithFee { .. } => {
+                Self::PREDICTION_MARKET_SETTLE_TO_AVAILABLE_WITH_FEE
+            }
+        }
+    }
+    
+    /// Encode as Vault instruction data
+    pub fn pack(&self) -> Vec<u8> {
+        let amounts: &[u64] = match self {
+            Self::PredictionMarketLock { amount } | Self::PredictionMarketUnlock { amount } => {
+                &[*amount]
+            }
+            Self::PredictionMarketLockWithFee { gross_amount }
+            | Self::PredictionMarketUnlockWithFee { gross_amount } => &[*gross_amount],
+            Self::PredictionMarketSettle { locked_amount, settlement_amount }
+            | Self::PredictionMarketSettleWithFee { locked_amount, settlement_amount }
+            | Self::PredictionMarketSettleToAvailable { locked_amount, settlement_amount }
+            | Self::PredictionMarketSettleToAvailableWithFee { locked_amount, settlement_amount } => {
+                &[*locked_amount, *settlement_amount]
+            }
+        };
+        let mut data = Vec::with_capacity(1 + 8 * amounts.len());
+        data.push(self.tag());
+        for amount in amounts {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        data
+    }
+    
+    /// Decode Vault instruction data produced by `pack`
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+        let amount_at = |i: usize| -> Result<u64, ProgramError> {
+            rest.get(i * 8..(i + 1) * 8)
+                .and_then(|b| b.try_into().ok())
+                .map(u64::from_le_bytes)
+                .ok_or(ProgramError::InvalidInstructionData)
+        };
+        let (instruction, len) = match tag {
+            Self::PREDICTION_MARKET_LOCK => (Self::PredictionMarketLock { amount: amount_at(0)? }, 1),
+            Self::PREDICTION_MARKET_UNLOCK => (Self::PredictionMarketUnlock { amount: amount_at(0)? }, 1),
+            Self::PREDICTION_MARKET_LOCK_WITH_FEE => {
+                (Self::PredictionMarketLockWithFee { gross_amount: amount_at(0)? }, 1)
+            }
+            Self::PREDICTION_MARKET_UNLOCK_WITH_FEE => {
+                (Self::PredictionMarketUnlockWithFee { gross_amount: amount_at(0)? }, 1)
+            }
+            Self::PREDICTION_MARKET_SETTLE => (
+                Self::PredictionMarketSettle { locked_amount: amount_at(0)?, settlement_amount: amount_at(1)? },
+                2,
+            ),
+            Self::PREDICTION_MARKET_SETTLE_WITH_FEE => (
+                Self::PredictionMarketSettleWithFee { locked_amount: amount_at(0)?, settlement_amount: amount_at(1)? },
+                2,
+            ),
+            Self::PREDICTION_MARKET_SETTLE_TO_AVAILABLE => (
+                Self::PredictionMarketSettleToAvailable { locked_amount: amount_at(0)?, settlement_amount: amount_at(1)? },
+                2,
+            ),
+            Self::PREDICTION_MARKET_SETTLE_TO_AVAILABLE_WITH_FEE => (
+                Self::PredictionMarketSettleToAvailableWithFee {
+                    locked_amount: amount_at(0)?,
+                    settlement_amount: amount_at(1)?,
+                },
+                2,
+            ),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        if rest.len() != 8 * len {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(instruction)
+    }
+}
+
 // ============================================================================
 // Vault Program CPI
 // ============================================================================
 
+/// Build a Vault instruction, taking signer/writable flags from `accounts`
+fn vault_instruction(vault_program: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: *vault_program,
+        accounts: accounts
+            .iter()
+            .map(|a| AccountMeta {
+                pubkey: *a.key,
+                is_signer: a.is_signer,
+                is_writable: a.is_writable,
+            })
+            .collect(),
+        data,
+    }
+}
+
+/// Build the Vault PredictionMarketLock instruction
+/// 
+/// `accounts` are, in order: VaultConfig, UserAccount, PMUserAccount,
+/// Caller Program (PM Config PDA), Payer and System Program. The last two
+/// let the Vault auto-initialize a missing PMUserAccount.
+pub fn lock_for_prediction_instruction(
+    vault_program: &Pubkey,
+    accounts: &[AccountInfo; 6],
+    amount: u64,
+) -> Instruction {
+    vault_instruction(
+        vault_program,
+        accounts,
+        VaultInstruction::PredictionMarketLock { amount }.pack(),
+    )
+}
+
 /// Lock user funds for prediction market (CPI to Vault Program)
 /// 
 /// This moves USDC from available_balance to pm_locked in the user's Vault account.
 /// If PMUserAccount doesn't exist, it will be auto-initialized (requires payer and system_program).
 /// 
+/// Returns the amount actually locked, which is less than `amount` when the
+/// Vault clamps to the user's available balance.
+/// 
 /// Vault Instruction Index: 16 (PredictionMarketLock)
 pub fn cpi_lock_for_prediction<'a>(
     vault_program: &AccountInfo<'a>,
@@ -38,15 +198,11 @@ pub fn cpi_lock_for_prediction<'a>(
     system_program: &AccountInfo<'a>,
     amount: u64,
     signer_seeds: &[&[u8]],
-) -> ProgramResult {
+) -> Result<u64, ProgramError> {
     msg!("CPI: Lock {} for prediction market", amount);
     
-    // Instruction discriminator for PredictionMarketLock = index 16
-    let mut data = vec![16u8];
-    data.extend_from_slice(&amount.to_le_bytes());
-    
     // Include payer and system_program for auto-init if pm_user_account is empty
-    let accounts = vec![
+    let accounts = [
         vault_config.clone(),
         user_account.clone(),
         pm_user_account.clone(),
@@ -55,21 +211,37 @@ pub fn cpi_lock_for_prediction<'a>(
         system_program.clone(), // System program for auto-init
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = lock_for_prediction_instruction(vault_program.key, &accounts, amount);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
-    Ok(())
+    let locked = vault_moved_amount(vault_program.key, amount)?;
+    if locked != amount {
+        msg!("Vault locked {} of {} requested", locked, amount);
+    }
+    
+    Ok(locked)
+}
+
+/// Amount the Vault reports having moved in the CPI just made
+/// 
+/// The Vault sets its return data to the moved amount as a little-endian
+/// u64. A Vault that sets no return data moved exactly `requested`.
+fn vault_moved_amount(vault_program: &Pubkey, requested: u64) -> Result<u64, ProgramError> {
+    let data = match get_return_data() {
+        Some((program_id, data)) if program_id == *vault_program && !data.is_empty() => data,
+        _ => return Ok(requested),
+    };
+    
+    let moved = <[u8; 8]>::try_from(data.as_slice())
+        .map(u64::from_le_bytes)
+        .map_err(|_| PredictionMarketError::InvalidVaultReturnData)?;
+    if moved > requested {
+        msg!("Error: Vault reported {} moved for a request of {}", moved, requested);
+        return Err(PredictionMarketError::InvalidVaultReturnData.into());
+    }
+    
+    Ok(moved)
 }
 
 /// Release user funds from prediction market (CPI to Vault Program)
@@ -88,9 +260,7 @@ pub fn cpi_release_from_prediction<'a>(
 ) -> ProgramResult {
     msg!("CPI: Release {} from prediction market", amount);
     
-    // Instruction index for PredictionMarketUnlock = 17
-    let mut data = vec![17u8];
-    data.extend_from_slice(&amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketUnlock { amount }.pack();
     
     let accounts = vec![
         vault_config.clone(),
@@ -99,17 +269,7 @@ pub fn cpi_release_from_prediction<'a>(
         caller_program.clone(),
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
@@ -136,10 +296,7 @@ pub fn cpi_prediction_settle<'a>(
     msg!("CPI: Settle prediction market - locked: {}, settlement: {}", 
          locked_amount, settlement_amount);
     
-    // Instruction index for PredictionMarketSettle = 18
-    let mut data = vec![18u8];
-    data.extend_from_slice(&locked_amount.to_le_bytes());
-    data.extend_from_slice(&settlement_amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketSettle { locked_amount, settlement_amount }.pack();
     
     let accounts = vec![
         vault_config.clone(),
@@ -147,17 +304,7 @@ pub fn cpi_prediction_settle<'a>(
         caller_program.clone(),
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
@@ -182,9 +329,7 @@ pub fn cpi_prediction_settle_to_available<'a>(
 ) -> ProgramResult {
     msg!("CPI: SettleToAvailable - locked: {}, settlement: {}", locked_amount, settlement_amount);
     
-    let mut data = vec![43u8];
-    data.extend_from_slice(&locked_amount.to_le_bytes());
-    data.extend_from_slice(&settlement_amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketSettleToAvailable { locked_amount, settlement_amount }.pack();
     
     let accounts = vec![
         vault_config.clone(),
@@ -193,17 +338,7 @@ pub fn cpi_prediction_settle_to_available<'a>(
         caller_program.clone(),
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
@@ -227,9 +362,7 @@ pub fn cpi_settle_to_available_with_fee<'a>(
 ) -> ProgramResult {
     msg!("CPI: SettleToAvailableWithFee - locked: {}, settlement: {}", locked_amount, settlement_amount);
 
-    let mut data = vec![49u8];
-    data.extend_from_slice(&locked_amount.to_le_bytes());
-    data.extend_from_slice(&settlement_amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketSettleToAvailableWithFee { locked_amount, settlement_amount }.pack();
 
     let accounts = vec![
         vault_config.clone(),
@@ -239,17 +372,7 @@ pub fn cpi_settle_to_available_with_fee<'a>(
         pm_fee_config.clone(),
     ];
 
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
 
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
 
@@ -277,10 +400,7 @@ pub fn cpi_prediction_settle_with_auto_init<'a>(
     msg!("CPI: Settle prediction market (with auto-init) - locked: {}, settlement: {}", 
          locked_amount, settlement_amount);
     
-    // Instruction index for PredictionMarketSettle = 18
-    let mut data = vec![18u8];
-    data.extend_from_slice(&locked_amount.to_le_bytes());
-    data.extend_from_slice(&settlement_amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketSettle { locked_amount, settlement_amount }.pack();
     
     // Include optional accounts for auto-init
     let accounts = vec![
@@ -292,17 +412,7 @@ pub fn cpi_prediction_settle_with_auto_init<'a>(
         user_wallet.clone(),     // User wallet for PDA derivation
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
@@ -351,10 +461,7 @@ pub fn cpi_lock_for_prediction_with_fee<'a>(
 ) -> ProgramResult {
     msg!("CPI: Lock {} with fee for prediction market", gross_amount);
     
-    // Instruction index for PredictionMarketLockWithFee = 21
-    // (After RelayerDeposit=19, RelayerWithdraw=20)
-    let mut data = vec![21u8];
-    data.extend_from_slice(&gross_amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketLockWithFee { gross_amount }.pack();
     
     let accounts = vec![
         vault_config.clone(),
@@ -369,17 +476,7 @@ pub fn cpi_lock_for_prediction_with_fee<'a>(
         system_program.clone(),
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
@@ -416,9 +513,7 @@ pub fn cpi_release_from_prediction_with_fee<'a>(
 ) -> ProgramResult {
     msg!("CPI: Release {} with fee from prediction market", gross_amount);
     
-    // Instruction index for PredictionMarketUnlockWithFee = 22
-    let mut data = vec![22u8];
-    data.extend_from_slice(&gross_amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketUnlockWithFee { gross_amount }.pack();
     
     let accounts = vec![
         vault_config.clone(),
@@ -431,17 +526,7 @@ pub fn cpi_release_from_prediction_with_fee<'a>(
         token_program.clone(),
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
@@ -476,10 +561,7 @@ pub fn cpi_settle_with_fee<'a>(
 ) -> ProgramResult {
     msg!("CPI: Settle with fee locked={}, settlement={}", locked_amount, settlement_amount);
     
-    // Instruction index for PredictionMarketSettleWithFee = 24
-    let mut data = vec![24u8];
-    data.extend_from_slice(&locked_amount.to_le_bytes());
-    data.extend_from_slice(&settlement_amount.to_le_bytes());
+    let data = VaultInstruction::PredictionMarketSettleWithFee { locked_amount, settlement_amount }.pack();
     
     let accounts = vec![
         vault_config.clone(),
@@ -491,17 +573,7 @@ pub fn cpi_settle_with_fee<'a>(
         token_program.clone(),
     ];
     
-    let ix = solana_program::instruction::Instruction {
-        program_id: *vault_program.key,
-        accounts: accounts.iter().map(|a| {
-            solana_program::instruction::AccountMeta {
-                pubkey: *a.key,
-                is_signer: a.is_signer,
-                is_writable: a.is_writable,
-            }
-        }).collect(),
-        data,
-    };
+    let ix = vault_instruction(vault_program.key, &accounts, data);
     
     invoke_signed(&ix, &accounts, &[signer_seeds])?;
     
@@ -680,4 +752,85 @@ mod tests {
         // Random pubkey should be rejected
         assert!(verify_token_program(&Pubkey::new_unique()).is_err());
     }
+
+    #[test]
+    fn test_lock_for_prediction_instruction_accounts() {
+        let vault_program = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        // (key, is_signer, is_writable) in the order the Vault expects
+        let expected = [
+            (Pubkey::new_unique(), false, false), // VaultConfig
+            (Pubkey::new_unique(), false, true),  // UserAccount
+            (Pubkey::new_unique(), false, true),  // PMUserAccount
+            (Pubkey::new_unique(), false, false), // Caller Program (PM Config PDA)
+            (Pubkey::new_unique(), true, true),   // Payer
+            (solana_program::system_program::id(), false, false),
+        ];
+        let mut lamports = [0u64; 6];
+        let mut data = [[0u8; 0]; 6];
+        let infos: Vec<AccountInfo> = expected
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((meta, lamports), data)| {
+                AccountInfo::new(&meta.0, meta.1, meta.2, lamports, data, &owner, false, 0)
+            })
+            .collect();
+        let accounts: [AccountInfo; 6] = infos.try_into().unwrap();
+
+        let ix = lock_for_prediction_instruction(&vault_program, &accounts, 2_500_000);
+
+        assert_eq!(ix.program_id, vault_program);
+        let metas: Vec<_> = ix.accounts.iter().map(|m| (m.pubkey, m.is_signer, m.is_writable)).collect();
+        assert_eq!(metas, expected);
+        assert_eq!(
+            VaultInstruction::unpack(&ix.data),
+            Ok(VaultInstruction::PredictionMarketLock { amount: 2_500_000 })
+        );
+    }
+
+    #[test]
+    fn test_vault_lock_round_trip() {
+        let ix = VaultInstruction::PredictionMarketLock { amount: 6_500_000 };
+        let data = ix.pack();
+        assert_eq!(data[0], 16);
+        assert_eq!(data[1..], 6_500_000u64.to_le_bytes());
+        assert_eq!(VaultInstruction::unpack(&data), Ok(ix));
+    }
+
+    #[test]
+    fn test_vault_unlock_round_trip() {
+        let ix = VaultInstruction::PredictionMarketUnlock { amount: u64::MAX };
+        let data = ix.pack();
+        assert_eq!(data.len(), 9);
+        assert_eq!(data[0], 17);
+        assert_eq!(VaultInstruction::unpack(&data), Ok(ix));
+    }
+
+    #[test]
+    fn test_vault_settle_round_trip() {
+        let ix = VaultInstruction::PredictionMarketSettle {
+            locked_amount: 4_000_000,
+            settlement_amount: 10_000_000,
+        };
+        let data = ix.pack();
+        assert_eq!(data[0], 18);
+        assert_eq!(data[1..9], 4_000_000u64.to_le_bytes());
+        assert_eq!(data[9..17], 10_000_000u64.to_le_bytes());
+        assert_eq!(VaultInstruction::unpack(&data), Ok(ix));
+    }
+
+    #[test]
+    fn test_vault_unpack_rejects_malformed_data() {
+        assert!(VaultInstruction::unpack(&[]).is_err());
+        assert!(VaultInstruction::unpack(&[99, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        // Settle carries two amounts
+        let lock = VaultInstruction::PredictionMarketLock { amount: 1 }.pack();
+        let mut settle = lock.clone();
+        settle[0] = VaultInstruction::PREDICTION_MARKET_SETTLE;
+        assert!(VaultInstruction::unpack(&settle).is_err());
+        let mut trailing = lock;
+        trailing.push(0);
+        assert!(VaultInstruction::unpack(&trailing).is_err());
+    }
 }
@@ -60,6 +60,21 @@ pub enum PredictionMarketError {
     #[error("Instruction deprecated - use V2 version")]
     InstructionDeprecated = 15,
     
+    #[error("New admin is the same as the current admin")]
+    AdminUnchanged = 16,
+    
+    #[error("No pending admin transfer")]
+    NoPendingAdminTransfer = 17,
+    
+    #[error("Batch size out of range")]
+    BatchTooLarge = 18,
+    
+    #[error("Invalid Vault/Fund program configuration")]
+    InvalidProgramConfig = 19,
+    
+    #[error("Match-minting complete sets is disabled")]
+    MatchMintDisabled = 20,
+    
     // === Market Errors (100-199) ===
     
     #[error("Market not found")]
@@ -125,6 +140,33 @@ pub enum PredictionMarketError {
     #[error("Invalid market type")]
     InvalidMarketType = 120,
     
+    #[error("Cannot reset counters while markets exist")]
+    CannotResetWithActiveMarkets = 121,
+    
+    #[error("Market not activated")]
+    MarketNotActivated = 122,
+    
+    #[error("Market TVL cap exceeded")]
+    TvlCapExceeded = 123,
+    
+    #[error("Market creator PMUserAccount not provided")]
+    MissingCreatorAccount = 124,
+
+    #[error("Market question or resolution spec hash is empty")]
+    InvalidMarketMetadata = 125,
+
+    #[error("Market has no creation bond to release")]
+    NoCreationBond = 126,
+
+    #[error("Market reopen grace period has expired")]
+    ReopenWindowExpired = 127,
+
+    #[error("Cancelled market has already paid out refunds")]
+    RefundsAlreadyStarted = 128,
+
+    #[error("Market finalization deadline has not passed")]
+    FinalizationDeadlineNotReached = 129,
+    
     // === Order Errors (200-299) ===
     
     #[error("Order not found")]
@@ -190,6 +232,48 @@ pub enum PredictionMarketError {
     #[error("Missing expiration time")]
     MissingExpirationTime = 220,
     
+    #[error("Fill-or-kill order cannot be completely filled")]
+    FillOrKillNotFilled = 221,
+    
+    #[error("Order not expired")]
+    OrderNotExpired = 222,
+    
+    #[error("Order still holds margin, shares or escrow")]
+    OrderNotClosable = 223,
+    
+    #[error("Order belongs to a different market")]
+    OrderMarketMismatch = 224,
+    
+    #[error("Order below minimum size or notional")]
+    OrderBelowMinimum = 225,
+    
+    #[error("Mint price sum exceeds 1.0")]
+    MintPriceSumTooHigh = 226,
+    
+    #[error("Burn price sum is below 1.0")]
+    BurnPriceSumTooLow = 227,
+    
+    #[error("Maker orders are not in price-time priority")]
+    MakerOrdersOutOfPriority = 228,
+    
+    #[error("Reduce-only order would increase the position")]
+    ReduceOnlyViolation = 229,
+    
+    #[error("Market order was not completely filled in its transaction")]
+    MarketOrderNotFilled = 230,
+    
+    #[error("Post-only order would cross the book")]
+    PostOnlyWouldCross = 231,
+    
+    #[error("Book top bid is not below its ask")]
+    BookTopCrossed = 232,
+    
+    #[error("Book top depth is below its best order's remaining amount")]
+    BookTopDepthTooLow = 233,
+    
+    #[error("Mint cost exceeds the user's maximum")]
+    SlippageExceeded = 234,
+    
     // === Position Errors (300-399) ===
     
     #[error("Position not found")]
@@ -221,6 +305,9 @@ pub enum PredictionMarketError {
     #[error("Invalid mint amount")]
     InvalidMintAmount = 403,
     
+    #[error("Collateral transferred does not match complete sets minted")]
+    CollateralMismatch = 404,
+    
     // === Oracle Errors (500-599) ===
     
     #[error("Oracle result not available")]
@@ -259,6 +346,30 @@ pub enum PredictionMarketError {
     #[error("Proposal not disputed")]
     ProposalNotDisputed = 511,
     
+    #[error("Proposal is under dispute and cannot be finalized")]
+    ProposalUnderDispute = 512,
+    
+    #[error("Challenge window out of bounds (1 hour to 30 days)")]
+    InvalidChallengeWindow = 513,
+    
+    #[error("Proposer bond must be non-zero")]
+    InvalidProposerBond = 514,
+    
+    #[error("Oracle feed account is invalid")]
+    InvalidOracleFeed = 515,
+    
+    #[error("Oracle feed has not updated since the resolution time")]
+    StaleOracleFeed = 516,
+    
+    #[error("Market does not resolve from this oracle feed")]
+    ResolutionSourceMismatch = 517,
+    
+    #[error("Dispute resolution deadline has not passed")]
+    DisputeResolutionPending = 518,
+    
+    #[error("Dispute already has the maximum number of challengers")]
+    TooManyChallengers = 519,
+    
     // === Token Errors (600-699) ===
     
     // InvalidTokenMint moved to 119 in Market Errors section
@@ -287,6 +398,9 @@ pub enum PredictionMarketError {
     #[error("Escrow already exists")]
     EscrowAlreadyExists = 608,
     
+    #[error("Token mint uses an unsupported extension")]
+    UnsupportedTokenExtension = 609,
+    
     // === Multi-Outcome Errors (650-699) ===
     
     #[error("Too many outcomes (max 16 for matching)")]
@@ -307,6 +421,9 @@ pub enum PredictionMarketError {
     #[error("Missing outcome in order set")]
     MissingOutcomeOrder = 655,
     
+    #[error("Price sum deviation exceeds maximum (5¢)")]
+    PriceSumDeviationTooHigh = 656,
+    
     // === CPI Errors (700-799) ===
     
     #[error("Invalid CPI caller")]
@@ -321,9 +438,25 @@ pub enum PredictionMarketError {
     #[error("Fund program mismatch")]
     FundProgramMismatch = 703,
     
+    #[error("Authorized callers list is full")]
+    AuthorizedCallersFull = 704,
+    
+    #[error("Caller is already authorized")]
+    CallerAlreadyAuthorized = 705,
+    
+    #[error("Caller is not authorized")]
+    CallerNotAuthorized = 706,
+    
+    #[error("Invalid vault account")]
+    InvalidVaultAccount = 707,
+    
+    #[error("Invalid Vault return data")]
+    InvalidVaultReturnData = 708,
+    
     // === Fee Errors (800-899) ===
-    // Reserved for future V2 fee implementation in Vault Program layer
-    // These error codes are currently unused but reserved for the correct architecture
+    // 800-805 are reserved for the V2 fee implementation in Vault Program layer;
+    // 802 and 806-808 are used by the ExecuteTradeV2 trading fee, 809 by the
+    // MintCompleteSet fee
     
     #[error("PM Fee Config not initialized")]
     PMFeeConfigNotInitialized = 800,
@@ -342,6 +475,39 @@ pub enum PredictionMarketError {
     
     #[error("Invalid PM Fee Vault PDA")]
     InvalidPMFeeVault = 805,
+    
+    #[error("Trade fee exceeds maximum (5%)")]
+    TradeFeeTooHigh = 806,
+    
+    #[error("Fund PMUserAccount not provided")]
+    MissingFundAccount = 807,
+    
+    #[error("Maker rebate exceeds trade fee")]
+    MakerRebateTooHigh = 808,
+    
+    #[error("Mint fee exceeds maximum (5%)")]
+    MintFeeTooHigh = 809,
+    
+    #[error("Fee schedule entry exceeds maximum (10%)")]
+    FeeScheduleTooHigh = 810,
+    
+    #[error("Fee tier discount exceeds 100% of the fee")]
+    FeeDiscountTooHigh = 811,
+    
+    #[error("TraderStats account not provided")]
+    MissingTraderStats = 812,
+    
+    #[error("Referral share exceeds 100% of the fee")]
+    ReferralShareTooHigh = 813,
+    
+    #[error("A trader cannot refer themselves")]
+    InvalidReferrer = 814,
+    
+    #[error("UserMeta account not provided")]
+    MissingUserMeta = 815,
+    
+    #[error("Referrer PMUserAccount not provided")]
+    MissingReferrerAccount = 816,
 }
 
 impl From<PredictionMarketError> for ProgramError {
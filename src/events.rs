@@ -0,0 +1,159 @@
+//! Structured events for indexers
+//!
+//! Each event is logged with `sol_log_data` as a single field holding the
+//! borsh encoding of [`PredictionMarketEvent`]: a one-byte variant tag
+//! followed by the event struct. Clients decode the base64 `Program data:`
+//! log line with `PredictionMarketEvent::try_from_slice`.
+//!
+//! The human-readable `msg!` lines are still logged alongside.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey};
+
+use crate::state::{MarketResult, OrderSide};
+
+/// Events emitted by the program (variant order is part of the wire format)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum PredictionMarketEvent {
+    OrderPlaced(OrderPlaced),
+    OrderCancelled(OrderCancelled),
+    TradeExecuted(TradeExecuted),
+    CompleteSetMinted(CompleteSetMinted),
+    MarketResolved(MarketResolved),
+}
+
+impl PredictionMarketEvent {
+    /// Log the event with `sol_log_data`
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// A new order was placed
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OrderPlaced {
+    pub market_id: u64,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub outcome_index: u8,
+    pub price: u64,
+    pub amount: u64,
+}
+
+/// An order was cancelled, explicitly or as an IOC/dust remainder
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OrderCancelled {
+    pub market_id: u64,
+    pub order_id: u64,
+}
+
+/// A buy and a sell order traded against each other
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TradeExecuted {
+    pub market_id: u64,
+    pub outcome_index: u8,
+    pub buy_order_id: u64,
+    pub sell_order_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub amount: u64,
+}
+
+/// Complete sets were minted for a user (binary or multi-outcome)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CompleteSetMinted {
+    pub market_id: u64,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+/// A market was resolved
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MarketResolved {
+    pub market_id: u64,
+    pub final_result: Option<MarketResult>,
+    pub winning_outcome_index: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(event: PredictionMarketEvent, tag: u8) {
+        let data = event.try_to_vec().unwrap();
+        assert_eq!(data[0], tag);
+        assert_eq!(PredictionMarketEvent::try_from_slice(&data).unwrap(), event);
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        round_trip(
+            PredictionMarketEvent::OrderPlaced(OrderPlaced {
+                market_id: 7,
+                order_id: 3,
+                owner: Pubkey::new_unique(),
+                side: OrderSide::Sell,
+                outcome_index: 1,
+                price: 650_000,
+                amount: 1_000_000,
+            }),
+            0,
+        );
+        round_trip(
+            PredictionMarketEvent::OrderCancelled(OrderCancelled { market_id: 7, order_id: 3 }),
+            1,
+        );
+        round_trip(
+            PredictionMarketEvent::TradeExecuted(TradeExecuted {
+                market_id: 7,
+                outcome_index: 1,
+                buy_order_id: 3,
+                sell_order_id: 4,
+                buyer: Pubkey::new_unique(),
+                seller: Pubkey::new_unique(),
+                price: 650_000,
+                amount: 1_000_000,
+            }),
+            2,
+        );
+        round_trip(
+            PredictionMarketEvent::CompleteSetMinted(CompleteSetMinted {
+                market_id: 7,
+                owner: Pubkey::new_unique(),
+                amount: 1_000_000,
+            }),
+            3,
+        );
+        round_trip(
+            PredictionMarketEvent::MarketResolved(MarketResolved {
+                market_id: 7,
+                final_result: Some(MarketResult::No),
+                winning_outcome_index: None,
+            }),
+            4,
+        );
+    }
+
+    #[test]
+    fn test_order_placed_layout() {
+        let owner = Pubkey::new_unique();
+        let data = PredictionMarketEvent::OrderPlaced(OrderPlaced {
+            market_id: 7,
+            order_id: 3,
+            owner,
+            side: OrderSide::Buy,
+            outcome_index: 0,
+            price: 400_000,
+            amount: 5_000_000,
+        })
+        .try_to_vec()
+        .unwrap();
+        // tag + market_id + order_id + owner + side + outcome_index + price + amount
+        assert_eq!(data.len(), 1 + 8 + 8 + 32 + 1 + 1 + 8 + 8);
+        assert_eq!(&data[1..9], &7u64.to_le_bytes());
+        assert_eq!(&data[17..49], owner.as_ref());
+    }
+}
@@ -3,7 +3,7 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
-use crate::state::{MarketResult, OrderSide, OrderType, Outcome};
+use crate::state::{FeeSchedule, FeeTier, FEE_TIER_COUNT, MarketCategory, MarketResult, OrderSide, OrderType, Outcome, ResolutionSource};
 
 /// All instructions supported by the Prediction Market Program
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -50,9 +50,19 @@ pub enum PredictionMarketInstruction {
     /// 4. `[writable]` NO Token Mint PDA
     /// 5. `[writable]` Market Vault PDA
     /// 6. `[]` USDC Mint
-    /// 7. `[]` Token Program
+    /// 7. `[]` Token Program (SPL Token or Token-2022, for the YES/NO mints)
     /// 8. `[]` System Program
     /// 9. `[]` Rent Sysvar
+    /// 
+    /// The vault is created under the USDC mint's own token program. USDC
+    /// mints with a Token-2022 transfer hook are rejected.
+    /// 
+    /// When a market creation bond is configured, it is locked from the
+    /// creator's Vault account and recorded on the Market:
+    /// 10. `[]` Vault Program
+    /// 11. `[]` VaultConfig
+    /// 12. `[writable]` Creator's Vault UserAccount
+    /// 13. `[writable]` Creator's PMUserAccount
     CreateMarket(CreateMarketArgs),
     
     /// Activate a market (Admin only)
@@ -65,6 +75,8 @@ pub enum PredictionMarketInstruction {
     
     /// Pause a market (Admin only)
     /// 
+    /// The reason code is kept in `Market::pause_reason` until ResumeMarket.
+    /// 
     /// Accounts:
     /// 0. `[signer]` Admin
     /// 1. `[]` PredictionMarketConfig
@@ -101,9 +113,13 @@ pub enum PredictionMarketInstruction {
     
     /// Mint a complete set (1 USDC -> 1 YES + 1 NO)
     /// 
+    /// With `config.mint_fee_bps` set, the user additionally pays the fee
+    /// to the Fund Program's USDC account; the Market Vault receives exactly
+    /// the collateral.
+    /// 
     /// Accounts:
-    /// 0. `[signer]` User
-    /// 1. `[]` PredictionMarketConfig
+    /// 0. `[writable, signer]` User
+    /// 1. `[writable]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[writable]` Market Vault
     /// 4. `[writable]` User's USDC Account
@@ -112,16 +128,21 @@ pub enum PredictionMarketInstruction {
     /// 7. `[writable]` User's YES Token Account
     /// 8. `[writable]` User's NO Token Account
     /// 9. `[writable]` User Position PDA
-    /// 10. `[writable]` User Vault Account (Vault Program)
-    /// 11. `[]` Vault Config
-    /// 12. `[]` Vault Program
-    /// 13. `[]` Fund Program (for fees)
-    /// 14. `[]` Token Program
-    /// 15. `[]` System Program
+    /// 10. `[]` Token Program
+    /// 11. `[]` System Program
+    /// 12. `[writable]` Fund Program's USDC Account (required when the mint fee is non-zero)
+    /// 13. `[]` USDC Mint (required for Token-2022 transfer-fee USDC; the user
+    ///     then sends the collateral plus the transfer fee)
+    /// 14. `[]` Associated Token Account Program (required when account 7 or 8
+    ///     doesn't exist yet; it is then created as the user's ATA, rent paid
+    ///     by the user)
     MintCompleteSet(MintCompleteSetArgs),
     
     /// Redeem a complete set (1 YES + 1 NO -> 1 USDC)
     /// 
+    /// With the config's redeem fee set, the fee is withheld from the payout
+    /// and sent to the Fund Program's USDC account.
+    /// 
     /// Accounts:
     /// 0. `[signer]` User
     /// 1. `[]` PredictionMarketConfig
@@ -133,11 +154,11 @@ pub enum PredictionMarketInstruction {
     /// 7. `[writable]` User's YES Token Account
     /// 8. `[writable]` User's NO Token Account
     /// 9. `[writable]` User Position PDA
-    /// 10. `[writable]` User Vault Account (Vault Program)
-    /// 11. `[]` Vault Config
-    /// 12. `[]` Vault Program
-    /// 13. `[]` Fund Program (for fees)
-    /// 14. `[]` Token Program
+    /// 10. `[]` Token Program
+    /// 11. `[]` USDC Mint (required for Token-2022 transfer-fee USDC; the
+    ///     transfer fee comes out of the redeemed USDC)
+    /// 12. `[writable]` Fund Program's USDC Account (required when the redeem
+    ///     fee is non-zero; account 11 must then be passed as well)
     RedeemCompleteSet(RedeemCompleteSetArgs),
     
     // =========================================================================
@@ -246,8 +267,12 @@ pub enum PredictionMarketInstruction {
     
     /// Submit a result proposal (Oracle)
     /// 
+    /// Requires an Active market past its resolution time. Locks
+    /// `config.proposer_bond_e6` from the proposer and moves the market to
+    /// ResultProposed until `now + config.challenge_window_secs`.
+    /// 
     /// Accounts:
-    /// 0. `[signer]` Proposer (Oracle or authorized)
+    /// 0. `[signer, writable]` Proposer (oracle_admin or authorized caller)
     /// 1. `[]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[writable]` OracleProposal PDA
@@ -255,22 +280,36 @@ pub enum PredictionMarketInstruction {
     /// 5. `[]` Vault Config
     /// 6. `[]` Vault Program
     /// 7. `[]` System Program
+    /// 8. `[writable]` Proposer's PMUserAccount
+    /// 9. `[]` AuthorizedCallers PDA (optional, for non-oracle proposers)
     ProposeResult(ProposeResultArgs),
     
     /// Challenge a proposed result
     /// 
+    /// Only before the proposal's challenge deadline and with a different
+    /// result. Locks the challenger bond and marks the proposal Disputed.
+    /// Until the dispute's resolution deadline, up to
+    /// MAX_DISPUTE_CHALLENGERS - 1 more challengers may join it, each locking
+    /// twice the previous bond and recorded in a new ChallengerRecord.
+    /// 
     /// Accounts:
-    /// 0. `[signer]` Challenger
+    /// 0. `[signer, writable]` Challenger
     /// 1. `[]` PredictionMarketConfig
     /// 2. `[]` Market
     /// 3. `[writable]` OracleProposal
     /// 4. `[writable]` Challenger's Vault Account (for bond)
     /// 5. `[]` Vault Config
     /// 6. `[]` Vault Program
+    /// 7. `[]` System Program
+    /// 8. `[writable]` Challenger's PMUserAccount
+    /// 9. `[writable]` ChallengerRecord PDA (joining a dispute only)
     ChallengeResult(ChallengeResultArgs),
     
     /// Finalize a result after challenge window
     /// 
+    /// Resolves a ResultProposed market with the proposed result and
+    /// releases the proposer bond. Disputed proposals cannot be finalized.
+    /// 
     /// Accounts:
     /// 0. `[signer]` Anyone (permissionless)
     /// 1. `[]` PredictionMarketConfig
@@ -279,19 +318,28 @@ pub enum PredictionMarketInstruction {
     /// 4. `[writable]` Proposer's Vault Account (for bond return)
     /// 5. `[]` Vault Config
     /// 6. `[]` Vault Program
+    /// 7. `[writable]` Proposer's PMUserAccount
     FinalizeResult,
     
     /// Resolve a disputed proposal (Committee only)
     /// 
+    /// The sides matching the committee's result share all bonds pro rata
+    /// to their own; if none matches, all bonds are forfeited to the Fund.
+    /// Resolves the market.
+    /// 
     /// Accounts:
-    /// 0. `[signer]` Committee member
+    /// 0. `[signer]` Committee member (oracle_admin)
     /// 1. `[]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[writable]` OracleProposal
-    /// 4. `[writable]` Winner's Vault Account (bond return)
-    /// 5. `[writable]` Loser's Vault Account (bond forfeiture)
+    /// 4. `[writable]` Proposer's PMUserAccount (bond settlement)
+    /// 5. `[writable]` Challenger's PMUserAccount (bond settlement)
     /// 6. `[]` Vault Config
     /// 7. `[]` Vault Program
+    /// 8. `[]` ChallengerRecord then `[writable]` its challenger's
+    ///    PMUserAccount, a pair per escalated challenger in record order
+    /// 9. `[writable]` Fund PMUserAccount (after the pairs; required when
+    ///    any bond is forfeited)
     ResolveDispute(ResolveDisputeArgs),
     
     // =========================================================================
@@ -365,17 +413,25 @@ pub enum PredictionMarketInstruction {
     UpdateOracleConfig(UpdateOracleConfigArgs),
     
     /// Add authorized caller (matching engine)
+    /// Creates the AuthorizedCallers PDA on first use (admin pays rent)
+    /// Listed callers may sign relayer instructions when the PDA is passed
+    /// as an extra (read-only) account
     /// 
     /// Accounts:
-    /// 0. `[signer]` Admin
-    /// 1. `[writable]` PredictionMarketConfig
+    /// 0. `[signer, writable]` Admin
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` AuthorizedCallers PDA
+    /// 3. `[]` System Program
     AddAuthorizedCaller(AddAuthorizedCallerArgs),
     
     /// Remove authorized caller
     /// 
+    /// The registry account stays allocated even when it becomes empty.
+    /// 
     /// Accounts:
     /// 0. `[signer]` Admin
-    /// 1. `[writable]` PredictionMarketConfig
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` AuthorizedCallers PDA
     RemoveAuthorizedCaller(RemoveAuthorizedCallerArgs),
     
     // =========================================================================
@@ -394,6 +450,10 @@ pub enum PredictionMarketInstruction {
     /// 6. `[]` System Program
     /// 7. `[]` Rent Sysvar
     /// 8..8+n. `[writable]` Outcome Token Mints (n outcomes)
+    /// 
+    /// Followed by the creation bond accounts of CreateMarket (Vault
+    /// Program, VaultConfig, creator's UserAccount and PMUserAccount) when a
+    /// bond is configured.
     CreateMultiOutcomeMarket(CreateMultiOutcomeMarketArgs),
     
     /// Mint a complete set for multi-outcome market
@@ -449,14 +509,23 @@ pub enum PredictionMarketInstruction {
     /// Escrow PDA = [b"order_escrow", market_id, order_id]
     PlaceMultiOutcomeOrder(PlaceMultiOutcomeOrderArgs),
     
-    /// Propose result for multi-outcome market
+    /// Propose the winning outcome of a multi-outcome market
     /// 
-    /// Accounts:
-    /// 0. `[signer]` Oracle Admin
+    /// Same bond, challenge window and FinalizeResult flow as ProposeResult.
+    /// The proposal carries `proposed_result = Yes` and the outcome index;
+    /// challengers dispute it with any other result.
+    /// 
+    /// Accounts: as ProposeResult
+    /// 0. `[signer, writable]` Proposer (oracle_admin or authorized caller)
     /// 1. `[]` PredictionMarketConfig
     /// 2. `[writable]` Market
-    /// 3. `[writable]` Oracle Proposal PDA
-    /// 4. `[]` System Program
+    /// 3. `[writable]` OracleProposal PDA
+    /// 4. `[writable]` Proposer's Vault Account (for bond)
+    /// 5. `[]` Vault Config
+    /// 6. `[]` Vault Program
+    /// 7. `[]` System Program
+    /// 8. `[writable]` Proposer's PMUserAccount
+    /// 9. `[]` AuthorizedCallers PDA (optional, for non-oracle proposers)
     ProposeMultiOutcomeResult(ProposeMultiOutcomeResultArgs),
     
     /// Claim winnings from multi-outcome market
@@ -576,6 +645,8 @@ pub enum PredictionMarketInstruction {
     
     /// V2: RelayerMintCompleteSet (Vault CPI, no SPL Token)
     /// Uses Vault.PredictionMarketLock instead of SPL Token minting
+    /// Mints as many sets as the Vault actually locked.
+    /// Fails with `SlippageExceeded` if the cost is above `max_cost_e6`.
     /// 
     /// Accounts:
     /// 0. `[signer]` Relayer
@@ -599,7 +670,7 @@ pub enum PredictionMarketInstruction {
     /// 
     /// Accounts:
     /// 0. `[signer]` Relayer/Matcher
-    /// 1. `[]` PredictionMarketConfig
+    /// 1. `[writable]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[writable]` YES Buy Order
     /// 4. `[writable]` NO Buy Order
@@ -616,6 +687,11 @@ pub enum PredictionMarketInstruction {
     
     /// V2: MatchBurn (Vault CPI, no SPL Token)
     /// 
+    /// A price pair above 1.0 is scaled down to par, so the sellers' proceeds
+    /// never exceed the burned amount. The Positions and PM User Accounts
+    /// must belong to the sell orders' owners; one wallet may sell both
+    /// sides, passing its Position twice.
+    /// 
     /// Accounts: (same as MatchMintV2)
     MatchBurnV2(MatchBurnArgs),
     
@@ -625,11 +701,14 @@ pub enum PredictionMarketInstruction {
     /// Accounts:
     /// 0. `[signer]` Relayer
     /// 1. `[]` PredictionMarketConfig
-    /// 2. `[]` Market
+    /// 2. `[writable]` Market
     /// 3. `[writable]` Position PDA
     /// 4. `[writable]` PM User Account
     /// 5. `[]` Vault Config
     /// 6. `[]` Vault Program
+    /// 
+    /// If the market charges a creator fee (Yes/No resolution, fee > 0),
+    /// the creator's `[writable]` PM User Account must also be appended.
     RelayerClaimWinningsV2(RelayerClaimWinningsArgs),
     
     /// V2: ExecuteTrade (Vault CPI, no SPL Token)
@@ -639,22 +718,42 @@ pub enum PredictionMarketInstruction {
     /// - Buyer has USDC locked in pm_locked via Vault
     /// - Seller has virtual shares in Position PDA
     /// - Trade transfers USDC (buyer → seller) and shares (seller → buyer)
+    /// - If config.trade_fee_bps > 0, the buyer pays cost + fee, the seller
+    ///   receives cost - fee, and both fees go to the Fund Program's
+    ///   PMUserAccount (Vault PDA of config.fund_program). The buy order's
+    ///   margin includes the full fee, so a fill at its limit price fits.
+    /// - The maker (sell order) is rebated config.maker_rebate_bps of the
+    ///   cost out of those fees
+    /// - With fee tiers configured, the buyer (taker) fee is discounted by the
+    ///   tier its TraderStats volume reached before this fill, and the fill's
+    ///   cost is added to both traders' TraderStats
     /// 
     /// Accounts:
     /// 0. `[signer]` Relayer/Keeper
-    /// 1. `[]` PredictionMarketConfig
+    /// 1. `[writable]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[writable]` Buy Order PDA
     /// 4. `[writable]` Sell Order PDA
     /// 5. `[writable]` Buyer Position PDA
     /// 6. `[writable]` Seller Position PDA
     /// 7. `[writable]` Buyer UserAccount (Vault)
-    /// 8. `[writable]` Buyer PMUserAccount (Vault)
+    /// 8. `[writable]` Buyer PMUserAccount (Vault PDA of buy_order.owner)
     /// 9. `[writable]` Seller UserAccount (Vault)
-    /// 10. `[writable]` Seller PMUserAccount (Vault)
+    /// 10. `[writable]` Seller PMUserAccount (Vault PDA of sell_order.owner)
     /// 11. `[]` VaultConfig
-    /// 12. `[]` Vault Program
+    /// 12. `[]` Vault Program (must equal config.vault_program)
     /// 13. `[]` System Program
+    /// 14. `[]` Buyer Wallet (buy_order.owner)
+    /// 15. `[]` Seller Wallet (sell_order.owner)
+    /// 
+    /// When a trading fee applies, the Fund Program's `[writable]` PM User
+    /// Account must also be appended. With fee tiers configured, the buyer's
+    /// and seller's `[writable]` TraderStats PDAs must be appended as well
+    /// (created on first use, rent paid by the relayer, which must then be
+    /// writable). With a referral share configured, both traders' `[]`
+    /// UserMeta PDAs (the bare address if the trader has none) and the
+    /// `[writable]` PM User Account of each referrer must be appended too;
+    /// referrers are paid out of the fees the Fund would receive.
     ExecuteTradeV2(ExecuteTradeArgs),
     
     /// V2: MatchMintMulti (Vault CPI, no SPL Token)
@@ -665,7 +764,7 @@ pub enum PredictionMarketInstruction {
     /// 
     /// Accounts:
     /// 0. `[signer]` Relayer/Matcher
-    /// 1. `[]` PredictionMarketConfig
+    /// 1. `[writable]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[]` VaultConfig
     /// 4. `[]` Vault Program
@@ -685,7 +784,7 @@ pub enum PredictionMarketInstruction {
     /// 
     /// Accounts:
     /// 0. `[signer]` Relayer/Matcher
-    /// 1. `[]` PredictionMarketConfig
+    /// 1. `[writable]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[]` VaultConfig
     /// 4. `[]` Vault Program
@@ -699,6 +798,13 @@ pub enum PredictionMarketInstruction {
     
     /// V2: RelayerPlaceOrder (Vault CPI for margin lock)
     /// Place order on behalf of user with margin locked in Vault
+    /// A Buy order locks its cost plus the full taker fee at trade_fee_bps,
+    /// and is sized down to the margin the Vault actually locked.
+    /// A reduce-only order is checked against the user's Position first.
+    /// A Market Buy locks margin at its price, the slippage cap, and must be
+    /// taken by the next instruction (ExecuteTradeV2 or ExecuteTradeMultiV2)
+    /// and filled completely there. A PostOnly order is rejected if it would
+    /// cross the best opposing order passed in.
     /// 
     /// Accounts:
     /// 0. `[signer]` Relayer
@@ -711,6 +817,12 @@ pub enum PredictionMarketInstruction {
     /// 7. `[]` VaultConfig
     /// 8. `[]` Vault Program
     /// 9. `[]` System Program
+    /// 10. `[]` Instructions sysvar (Market orders only), or best opposing
+    ///     Order, empty if there is none (PostOnly orders only)
+    /// 
+    /// With `referrer` set, the user's `[writable]` UserMeta PDA must be
+    /// appended; it is created with the referrer on first use (rent paid by
+    /// the relayer, which must then be writable) and left as is afterwards.
     RelayerPlaceOrderV2(RelayerPlaceOrderV2Args),
     
     /// V2: RelayerCancelOrder (Vault CPI for margin unlock + Position share unlock)
@@ -721,7 +833,8 @@ pub enum PredictionMarketInstruction {
     /// 1. `[]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[writable]` Order PDA
-    /// 4. `[writable]` Position PDA (for Sell order share unlock)
+    /// 4. `[writable]` Position PDA, or MultiOutcomePosition PDA on a
+    ///    multi-outcome market (for Sell order share unlock)
     /// 5. `[writable]` UserAccount (Vault)
     /// 6. `[writable]` PMUserAccount (Vault)
     /// 7. `[]` VaultConfig
@@ -929,14 +1042,18 @@ pub enum PredictionMarketInstruction {
     /// V2: RelayerClaimMultiOutcomeWinnings (Vault CPI, no SPL Token)
     /// Claim winnings after market resolution for multi-outcome market
     /// 
+    /// Pays `holdings[winning_outcome_index]`; an Invalid resolution or a
+    /// cancelled market refunds the remaining cost instead.
+    /// 
     /// Accounts:
     /// 0. `[signer]` Relayer
     /// 1. `[]` PredictionMarketConfig
-    /// 2. `[]` Market (resolved)
+    /// 2. `[writable]` Market (resolved or cancelled)
     /// 3. `[writable]` MultiOutcomePosition PDA
     /// 4. `[writable]` PMUserAccount (Vault)
     /// 5. `[]` VaultConfig
     /// 6. `[]` Vault Program
+    /// 7. `[writable]` UserAccount (Vault, optional: settle to available balance)
     RelayerClaimMultiOutcomeWinningsV2(RelayerClaimMultiOutcomeWinningsArgs),
     
     // =========================================================================
@@ -1000,7 +1117,7 @@ pub enum PredictionMarketInstruction {
     /// 
     /// Accounts:
     /// 0. `[signer]` Relayer/Keeper
-    /// 1. `[]` PredictionMarketConfig
+    /// 1. `[writable]` PredictionMarketConfig
     /// 2. `[writable]` Market
     /// 3. `[writable]` Buy Order PDA
     /// 4. `[writable]` Sell Order PDA
@@ -1028,6 +1145,478 @@ pub enum PredictionMarketInstruction {
     /// 4. `[]` VaultConfig
     /// 5. `[]` Vault Program
     RelayerSettlePrediction(RelayerSettlePredictionArgs),
+
+    // =========================================================================
+    // Two-Step Admin Handover
+    // =========================================================================
+
+    /// Start an admin handover by recording a pending admin
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Current Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    InitiateAdminTransfer(InitiateAdminTransferArgs),
+
+    /// Complete an admin handover; the pending admin signs to promote itself
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Pending Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    AcceptAdminTransfer,
+
+    /// Cancel a pending admin handover
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Current Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    CancelAdminTransfer,
+
+    /// Repair outcome_index on a legacy binary-market order (permissionless)
+    /// Recomputes outcome_index from outcome (Yes→0, No→1)
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Caller
+    /// 1. `[]` Market PDA
+    /// 2. `[writable]` Order PDA
+    SyncOrderOutcomeIndex(SyncOrderOutcomeIndexArgs),
+
+    /// Set the price-sum tolerance for MatchMint/MatchBurn (admin only)
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    SetMaxPriceSumDeviation(SetMaxPriceSumDeviationArgs),
+
+    /// Mark past-expiration GTD orders as Expired (permissionless)
+    /// 
+    /// Bookkeeping only: no funds move. Margin and locked shares are
+    /// released later by cancelling the expired order or by ExpireOrder.
+    /// Orders that are inactive or not yet expired are skipped.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Caller (keeper)
+    /// 1..N. `[writable]` Order PDAs
+    SweepExpiredOrders,
+
+    /// V2: RelayerRefundCancelledMarket (Vault CPI, no SPL Token)
+    /// Refunds the USDC still locked for a Position in a cancelled market
+    /// via Vault.PredictionMarketSettle
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Relayer
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[writable]` Position PDA
+    /// 4. `[writable]` PM User Account
+    /// 5. `[]` Vault Config
+    /// 6. `[]` Vault Program
+    RelayerRefundCancelledMarketV2(RelayerRefundCancelledMarketArgs),
+
+    /// V2: Batch RelayerClaimWinnings (Vault CPI, no SPL Token)
+    /// Settles up to MAX_BATCH_CLAIM_USERS positions in one transaction;
+    /// already-settled positions are skipped
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Relayer
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[]` Vault Config
+    /// 4. `[]` Vault Program
+    /// 
+    /// Followed by a `[writable]` (Position PDA, PM User Account) pair per
+    /// user_wallets entry, then the creator's `[writable]` PM User Account
+    /// if the market charges a creator fee.
+    RelayerBatchClaimWinningsV2(RelayerBatchClaimWinningsArgs),
+
+    /// Set a market's TVL cap circuit breaker (admin only)
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    SetMarketTvlCap(SetMarketTvlCapArgs),
+
+    /// Set the ExecuteTradeV2 trading fee (admin only)
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    SetTradeFee(SetTradeFeeArgs),
+
+    /// Set the ExecuteTradeV2 maker rebate (admin only)
+    /// Must not exceed the configured trade fee
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    SetMakerRebate(SetMakerRebateArgs),
+
+    /// Allow or forbid MatchMintV2/MatchMintMultiV2 creating complete sets
+    /// (admin only). Direct mint instructions are unaffected.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    SetAllowMatchMint(SetAllowMatchMintArgs),
+
+    /// Expire a past-expiration GTD order and release its remaining margin
+    /// or locked shares (permissionless)
+    /// 
+    /// Also releases orders already marked Expired by SweepExpiredOrders.
+    /// Filled, cancelled and already released orders are rejected.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Caller (keeper)
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[]` Market
+    /// 3. `[writable]` Order PDA
+    /// 4. `[writable]` Owner's Position / MultiOutcomePosition PDA (Sell orders)
+    /// 5. `[writable]` Owner's Vault UserAccount (Buy orders)
+    /// 6. `[writable]` Owner's Vault PMUserAccount (Buy orders)
+    /// 7. `[]` VaultConfig
+    /// 8. `[]` Vault Program
+    ExpireOrder(ExpireOrderArgs),
+
+    /// Correct resolution_time and/or finalization_deadline of a Pending
+    /// market (creator only, rejected once activated)
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Market creator
+    /// 1. `[writable]` Market
+    UpdateMarketSchedule(UpdateMarketScheduleArgs),
+
+    /// V2: Amend the price and/or amount of an open order, keeping its order_id
+    /// 
+    /// Buy orders lock or release the margin delta via Vault CPI; Sell orders
+    /// lock or unlock the share delta in the Position (Binary markets only).
+    /// The amount must stay above filled_amount, and the remainder must pass
+    /// the placement minimums, post-only and reduce-only checks.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Relayer
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[]` Market
+    /// 3. `[writable]` Order PDA
+    /// 4. `[writable]` Position PDA (Sell and reduce-only orders)
+    /// 5. `[writable]` User Vault Account
+    /// 6. `[writable]` PM User Account of the order owner
+    /// 7. `[]` Vault Config
+    /// 8. `[]` Vault Program
+    /// 9. `[]` System Program
+    /// 10. `[]` Best opposing Order, empty if there is none (PostOnly orders
+    ///     only)
+    RelayerAmendOrderV2(RelayerAmendOrderV2Args),
+
+    /// Set the minimum order size (admin only, 0 = disabled)
+    /// Orders are placed with at least this amount, and a fill leaving less
+    /// than this on an order cancels the dust remainder and releases its
+    /// margin or locked shares.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    SetMinOrderSize(SetMinOrderSizeArgs),
+
+    /// Close a Filled, Cancelled or Expired order and return its rent to the
+    /// owner (owner or relayer)
+    /// 
+    /// Orders that still hold margin, shares or an open escrow are rejected.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Order owner or authorized relayer
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Order PDA
+    /// 3. `[writable]` Order owner (receives the rent)
+    CloseOrder(CloseOrderArgs),
+
+    /// Close an empty binary Position and return its rent to the owner
+    /// (owner or relayer)
+    /// 
+    /// The position must hold no shares and no locks. An unsettled position
+    /// can only be closed once the market is Resolved or Cancelled, and only
+    /// if a claim would neither release nor pay anything (e.g. no
+    /// `total_cost_e6 - settled_cost_e6` left to refund).
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Position owner or authorized relayer
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[]` Market
+    /// 3. `[writable]` Position PDA
+    /// 4. `[writable]` Position owner (receives the rent)
+    ClosePosition(ClosePositionArgs),
+
+    /// Set the minimum notional of a new order (admin only, 0 = disabled)
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    SetMinNotional(SetMinNotionalArgs),
+
+    /// Read-only market status query
+    /// 
+    /// Writes a borsh-encoded `MarketSummary` as return data; clients
+    /// simulate the transaction and decode it. No account is modified.
+    /// 
+    /// Accounts:
+    /// 0. `[]` Market
+    QueryMarket(QueryMarketArgs),
+
+    /// Push back resolution_time and finalization_deadline of an Active or
+    /// Pending market whose real-world event was postponed (admin only)
+    /// 
+    /// Both times move by `extension_secs` (at most
+    /// MAX_RESOLUTION_EXTENSION_SECS). Rejected once a result was proposed.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[]` OracleProposal PDA (must not exist)
+    ExtendResolutionTime(ExtendResolutionTimeArgs),
+
+    /// Set the MintCompleteSet minting fee (admin only)
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    SetMintFee(SetMintFeeArgs),
+
+    /// Set the bond locked from market creators (admin only)
+    /// 
+    /// Grows a config created before ConfigExtension existed; the admin
+    /// pays the extra rent.
+    /// 
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    /// 2. `[]` System Program
+    SetMarketCreationBond(SetMarketCreationBondArgs),
+    
+    /// Return or forfeit a market's creation bond (permissionless)
+    /// 
+    /// Resolved markets, and markets cancelled for any other reason, return
+    /// the bond to the creator. Markets cancelled as invalid or for
+    /// regulatory reasons forfeit it to the Fund Program's PMUserAccount.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Caller
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[]` Vault Program
+    /// 4. `[]` VaultConfig
+    /// 5. `[writable]` Creator's Vault UserAccount
+    /// 6. `[writable]` Creator's PMUserAccount
+    /// 7. `[writable]` Fund PMUserAccount (forfeit only)
+    ReleaseCreationBond(ReleaseCreationBondArgs),
+    
+    /// Revert an erroneous cancellation back to Paused (Admin only)
+    /// 
+    /// Only allowed within REOPEN_GRACE_PERIOD_SECS of CancelMarket and
+    /// before any refund or claim has been paid out of the market. Markets
+    /// created without the MarketExtension tail cannot be reopened.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    ReopenMarket(ReopenMarketArgs),
+    
+    /// Resolve a market as Invalid once its finalization deadline has passed
+    /// (permissionless)
+    /// 
+    /// Unlocks funds in markets nobody resolved in time: claims then refund
+    /// each position's remaining cost. A market whose undisputed proposal can
+    /// still be finalized with FinalizeResult is rejected.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Anyone (permissionless)
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[]` OracleProposal (required while ResultProposed)
+    AutoInvalidateMarket(AutoInvalidateMarketArgs),
+    
+    /// Resolve a market from its Switchboard feed (permissionless)
+    /// 
+    /// Only for markets created with a Switchboard resolution source. Once
+    /// resolution_time has passed, the aggregator's latest confirmed round
+    /// (opened at or after resolution_time) is compared against the market's
+    /// threshold: YES if the comparison holds, NO otherwise.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Anyone (permissionless)
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[]` Switchboard aggregator account
+    AutoResolveFromFeed(AutoResolveFromFeedArgs),
+    
+    /// Resolve a market from its Pyth price account (permissionless)
+    /// 
+    /// Only for markets created with a Pyth resolution source. The aggregate
+    /// price must be trading, published at or after resolution_time and no
+    /// older than PYTH_MAX_PRICE_AGE_SECS: YES if price >= threshold, NO
+    /// otherwise.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Anyone (permissionless)
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[]` Pyth price account
+    ResolveFromPyth(ResolveFromPythArgs),
+    
+    /// V2: Batch RelayerCancelOrder (Vault CPI, no SPL Token)
+    /// Cancels up to MAX_BATCH_CANCEL_ORDERS orders of one market, releasing
+    /// Buy margin via the Vault and Sell shares in the Position; orders that
+    /// are no longer cancellable are skipped
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Relayer
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[]` Vault Config
+    /// 4. `[]` Vault Program
+    /// 
+    /// Followed by a `[writable]` (Order PDA, owner's Position PDA, owner's
+    /// UserAccount, owner's PM User Account) group per order_ids entry; on a
+    /// multi-outcome market the owner's MultiOutcomePosition PDA takes the
+    /// Position's place.
+    RelayerBatchCancelOrdersV2(RelayerBatchCancelOrdersV2Args),
+    
+    /// V2: Fill one taker Buy order against up to MAX_TRADE_MAKERS resting
+    /// Sell orders (Vault CPI, no SPL Token)
+    /// 
+    /// Makers must be listed in price-time priority (ascending price, older
+    /// orders first at the same price) and each fills at its own price.
+    /// Matching stops once the taker (or `amount`) is filled or at the first
+    /// maker priced above the taker's limit; makers that are no longer active
+    /// are skipped. Fees and maker rebates apply per fill as in
+    /// ExecuteTradeV2, and the taker is settled once for all fills.
+    /// 
+    /// Accounts:
+    /// 0. `[writable, signer]` Relayer/Keeper
+    /// 1. `[writable]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[writable]` Taker (Buy) Order PDA
+    /// 4. `[writable]` Buyer Position PDA
+    /// 5. `[writable]` Buyer UserAccount (Vault)
+    /// 6. `[writable]` Buyer PMUserAccount (Vault PDA of the taker's owner)
+    /// 7. `[]` Buyer Wallet (taker's owner)
+    /// 8. `[]` VaultConfig
+    /// 9. `[]` Vault Program (must equal config.vault_program)
+    /// 10. `[]` System Program
+    /// 
+    /// Followed by a (`[writable]` Sell Order PDA, `[writable]` Seller
+    /// Position PDA, `[writable]` Seller PMUserAccount, `[]` Seller Wallet)
+    /// group per maker_order_ids entry. When a trading fee applies, the Fund
+    /// Program's `[writable]` PM User Account must also be appended. With fee
+    /// tiers configured, the `[writable]` TraderStats PDAs of the taker and
+    /// of every filled maker's owner must be appended as well; the taker fee
+    /// is discounted by the tier the taker reached before this instruction.
+    /// With a referral share configured, the `[]` UserMeta PDAs of the taker
+    /// and of every filled maker's owner and the `[writable]` PM User Account
+    /// of each referrer must be appended too, as for ExecuteTradeV2.
+    ExecuteTradeMultiV2(ExecuteTradeMultiArgs),
+    
+    /// Replace the mint, redeem, trade and maker rebate fees at once (admin only)
+    /// 
+    /// Each fee is at most MAX_FEE_SCHEDULE_BPS and the maker rebate at most
+    /// the trade fee. Grows a config created before ConfigExtension existed;
+    /// the admin pays the extra rent.
+    /// 
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    /// 2. `[]` System Program
+    UpdateFeeSchedule(UpdateFeeScheduleArgs),
+    
+    /// Set the volume tiers discounting the ExecuteTradeV2 taker fee (admin only)
+    /// 
+    /// Grows a config created before ConfigExtension existed; the admin
+    /// pays the extra rent.
+    /// 
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    /// 2. `[]` System Program
+    SetFeeTiers(SetFeeTiersArgs),
+    
+    /// Set the share of referred traders' ExecuteTradeV2 fees paid to their
+    /// referrers (admin only)
+    /// 
+    /// Grows a config created before ConfigExtension existed; the admin
+    /// pays the extra rent.
+    /// 
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin
+    /// 1. `[writable]` PredictionMarketConfig
+    /// 2. `[]` System Program
+    SetReferralShare(SetReferralShareArgs),
+    
+    /// Refresh the best bid/ask snapshot of a market outcome (relayer only)
+    /// 
+    /// Called by the relayer after each place/cancel that changes the top
+    /// of the book. The snapshot is a hint: its prices are read from the
+    /// best orders passed, and each depth must cover at least the remaining
+    /// amount of its best order. Creates the BookTop on first use.
+    /// 
+    /// Accounts:
+    /// 0. `[signer, writable]` Relayer (pays for creating the BookTop)
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[]` Market
+    /// 3. `[writable]` BookTop PDA
+    /// 4. `[]` Best bid Order (an empty account when there are no bids)
+    /// 5. `[]` Best ask Order (an empty account when there are no asks)
+    /// 6. `[]` System Program
+    UpdateBookTop(UpdateBookTopArgs),
+    
+    /// Expire a dispute the committee left unresolved (permissionless)
+    /// 
+    /// Once the proposal's resolution deadline has passed, resolves the
+    /// market as Invalid, returns every bond in full and marks the proposal
+    /// Rejected.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Caller (anyone)
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[writable]` OracleProposal
+    /// 4. `[writable]` Proposer's PMUserAccount (bond return)
+    /// 5. `[writable]` Challenger's PMUserAccount (bond return)
+    /// 6. `[]` Vault Config
+    /// 7. `[]` Vault Program
+    /// 8. `[]` ChallengerRecord then `[writable]` its challenger's
+    ///    PMUserAccount, a pair per escalated challenger in record order
+    ExpireDisputedProposal(ExpireDisputedProposalArgs),
+    
+    /// Read-only query of what settling a binary position would pay
+    /// 
+    /// Writes a borsh-encoded `ClaimableSummary` as return data for a
+    /// resolved or cancelled market; clients simulate the transaction and
+    /// decode it. A missing position has nothing to claim. No account is
+    /// modified.
+    /// 
+    /// Accounts:
+    /// 0. `[]` Market
+    /// 1. `[]` Position PDA
+    QueryClaimable(QueryClaimableArgs),
+    
+    /// Cancel any user's open order in a Paused or Cancelled market (admin only)
+    /// 
+    /// Incident tool for clearing a stuck book: returns the remaining margin
+    /// of a Buy order through the Vault and unlocks the remaining shares of a
+    /// Sell order, exactly like RelayerCancelOrderV2 but regardless of owner.
+    /// 
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[]` PredictionMarketConfig
+    /// 2. `[writable]` Market
+    /// 3. `[writable]` Order PDA
+    /// 4. `[writable]` Position PDA of the order owner, its
+    ///    MultiOutcomePosition PDA on a multi-outcome market (for Sell orders)
+    /// 5. `[writable]` UserAccount (Vault) of the order owner
+    /// 6. `[writable]` PMUserAccount (Vault) of the order owner
+    /// 7. `[]` VaultConfig
+    /// 8. `[]` Vault Program
+    ForceCancelOrder(ForceCancelOrderArgs),
 }
 
 // ============================================================================
@@ -1056,6 +1645,7 @@ pub struct ReinitializeConfigArgs {
     /// Proposer bond amount (e6)
     pub proposer_bond_e6: u64,
     /// Reset market counters (if true, resets next_market_id, total_markets, etc.)
+    /// Only allowed while no markets have been created (total_markets == 0)
     pub reset_counters: bool,
 }
 
@@ -1073,6 +1663,14 @@ pub struct CreateMarketArgs {
     pub finalization_deadline: i64,
     /// Creator fee in basis points (max 500 = 5%)
     pub creator_fee_bps: u16,
+    /// Challenge window for this market in seconds (0 = global window)
+    pub challenge_window_secs: i64,
+    /// Market category (an unknown variant byte fails deserialization)
+    pub category: MarketCategory,
+    /// Hash of the off-chain tag list (all zeros = no tags)
+    pub tags_hash: [u8; 32],
+    /// Where the result comes from (Manual = oracle proposal flow)
+    pub resolution_source: ResolutionSource,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -1085,6 +1683,8 @@ pub struct ActivateMarketArgs {
 pub struct PauseMarketArgs {
     /// Market ID
     pub market_id: u64,
+    /// Operator reason code, stored on the market until it is resumed
+    pub reason: u8,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -1115,7 +1715,7 @@ pub struct FlagMarketArgs {
 pub struct MintCompleteSetArgs {
     /// Market ID
     pub market_id: u64,
-    /// Amount to mint (in tokens, 1:1 with USDC)
+    /// Amount to mint in share base units (e6, 1_000_000 = 1 whole set = 1 USDC)
     pub amount: u64,
 }
 
@@ -1145,6 +1745,8 @@ pub struct PlaceOrderArgs {
     pub order_type: OrderType,
     /// Expiration time (for GTD orders)
     pub expiration_time: Option<i64>,
+    /// Only reduce an existing position, never grow or flip it
+    pub reduce_only: bool,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -1460,6 +2062,8 @@ pub struct RelayerMintCompleteSetArgs {
     pub market_id: u64,
     /// Amount to mint
     pub amount: u64,
+    /// Maximum total USDC (e6) the user pays, fees included
+    pub max_cost_e6: Option<u64>,
 }
 
 /// Relayer版本的RedeemCompleteSet
@@ -1516,7 +2120,7 @@ pub struct RelayerPlaceOrderV2Args {
     pub side: OrderSide,
     /// Outcome (YES/NO or outcome index)
     pub outcome: Outcome,
-    /// Price (e6)
+    /// Price (e6); the maximum price for Market orders
     pub price: u64,
     /// Amount in tokens
     pub amount: u64,
@@ -1524,6 +2128,10 @@ pub struct RelayerPlaceOrderV2Args {
     pub order_type: OrderType,
     /// Expiration time (for GTD orders)
     pub expiration_time: Option<i64>,
+    /// Only reduce an existing position, never grow or flip it
+    pub reduce_only: bool,
+    /// Wallet that referred the user; only the first one is recorded
+    pub referrer: Option<Pubkey>,
 }
 
 /// V2: Relayer版本的CancelOrder (with Vault CPI)
@@ -1590,6 +2198,15 @@ pub struct RelayerRefundCancelledMarketArgs {
     pub market_id: u64,
 }
 
+/// Batch version of RelayerClaimWinningsV2
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RelayerBatchClaimWinningsArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// User wallets to settle (max MAX_BATCH_CLAIM_USERS)
+    pub user_wallets: Vec<Pubkey>,
+}
+
 /// Relayer版本的MintMultiOutcomeCompleteSet
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct RelayerMintMultiOutcomeCompleteSetArgs {
@@ -1829,6 +2446,273 @@ pub struct RelayerSettlePredictionArgs {
     pub settlement_amount: u64,
 }
 
+/// Arguments for InitiateAdminTransfer
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct InitiateAdminTransferArgs {
+    /// Pubkey that must sign AcceptAdminTransfer
+    pub new_admin: Pubkey,
+}
+
+/// Arguments for SyncOrderOutcomeIndex
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SyncOrderOutcomeIndexArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Order ID
+    pub order_id: u64,
+}
+
+/// Arguments for SetMaxPriceSumDeviation
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetMaxPriceSumDeviationArgs {
+    /// Tolerance in e6 (10_000 = 1¢), at most MAX_PRICE_SUM_DEVIATION
+    pub max_deviation: u64,
+}
+
+/// Arguments for SetMarketTvlCap
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetMarketTvlCapArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Max locked collateral (e6), 0 disables the cap
+    pub max_tvl_e6: u64,
+}
+
+/// Arguments for SetTradeFee
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetTradeFeeArgs {
+    /// Fee per side in basis points, at most MAX_TRADE_FEE_BPS (0 disables)
+    pub trade_fee_bps: u16,
+}
+
+/// Arguments for SetMakerRebate
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetMakerRebateArgs {
+    /// Maker rebate in basis points of notional, at most trade_fee_bps
+    pub maker_rebate_bps: u16,
+}
+
+/// Arguments for SetAllowMatchMint
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetAllowMatchMintArgs {
+    /// false restricts complete-set creation to the mint instructions
+    pub allow_match_mint: bool,
+}
+
+/// Arguments for ExpireOrder
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ExpireOrderArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Order ID
+    pub order_id: u64,
+}
+
+/// Arguments for UpdateMarketSchedule
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct UpdateMarketScheduleArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// New resolution time (None keeps the current one)
+    pub resolution_time: Option<i64>,
+    /// New finalization deadline (None keeps the current one)
+    pub finalization_deadline: Option<i64>,
+}
+
+/// Arguments for RelayerAmendOrderV2
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RelayerAmendOrderV2Args {
+    /// Order owner's wallet
+    pub user_wallet: Pubkey,
+    /// Market ID
+    pub market_id: u64,
+    /// Order ID
+    pub order_id: u64,
+    /// New price (None keeps the current one)
+    pub new_price: Option<u64>,
+    /// New total amount including the filled part (None keeps the current one)
+    pub new_amount: Option<u64>,
+}
+
+/// Arguments for SetMinOrderSize
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetMinOrderSizeArgs {
+    /// Minimum amount (e6 shares) an order may be placed or left with
+    pub min_order_size: u64,
+}
+
+/// Arguments for CloseOrder
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CloseOrderArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Order ID
+    pub order_id: u64,
+}
+
+/// Arguments for ClosePosition
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ClosePositionArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Position owner's wallet
+    pub user_wallet: Pubkey,
+}
+
+/// Arguments for SetMinNotional
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetMinNotionalArgs {
+    /// Minimum notional (e6 USDC) of a new order
+    pub min_notional_e6: u64,
+}
+
+/// Arguments for QueryMarket
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct QueryMarketArgs {
+    /// Market ID
+    pub market_id: u64,
+}
+
+/// Arguments for ExtendResolutionTime
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ExtendResolutionTimeArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Seconds added to both resolution_time and finalization_deadline
+    pub extension_secs: i64,
+}
+
+/// Arguments for SetMintFee
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetMintFeeArgs {
+    /// Fee in basis points of the collateral, at most MAX_MINT_FEE_BPS (0 disables)
+    pub mint_fee_bps: u16,
+}
+
+/// Arguments for SetMarketCreationBond
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetMarketCreationBondArgs {
+    /// Bond (e6) locked from each market creator (0 disables)
+    pub market_creation_bond_e6: u64,
+}
+
+/// Arguments for ReleaseCreationBond
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ReleaseCreationBondArgs {
+    /// Market ID
+    pub market_id: u64,
+}
+
+/// Arguments for ReopenMarket
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ReopenMarketArgs {
+    /// Market ID
+    pub market_id: u64,
+}
+
+/// Arguments for AutoInvalidateMarket
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AutoInvalidateMarketArgs {
+    /// Market ID
+    pub market_id: u64,
+}
+
+/// Arguments for AutoResolveFromFeed
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AutoResolveFromFeedArgs {
+    /// Market ID
+    pub market_id: u64,
+}
+
+/// Arguments for ResolveFromPyth
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ResolveFromPythArgs {
+    /// Market ID
+    pub market_id: u64,
+}
+
+/// Batch version of RelayerCancelOrderV2
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RelayerBatchCancelOrdersV2Args {
+    /// Market ID
+    pub market_id: u64,
+    /// Orders to cancel (max MAX_BATCH_CANCEL_ORDERS)
+    pub order_ids: Vec<u64>,
+}
+
+/// Arguments for ExecuteTradeMultiV2
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ExecuteTradeMultiArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Taker (Buy) order ID
+    pub taker_order_id: u64,
+    /// Maker (Sell) order IDs in price-time priority (max MAX_TRADE_MAKERS)
+    pub maker_order_ids: Vec<u64>,
+    /// Maximum amount to fill for the taker
+    pub amount: u64,
+}
+
+/// Arguments for UpdateFeeSchedule
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct UpdateFeeScheduleArgs {
+    /// Fees replacing the current ones
+    pub fee_schedule: FeeSchedule,
+}
+
+/// Arguments for SetFeeTiers
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetFeeTiersArgs {
+    /// Tiers replacing the current ones (all zero disables the discounts)
+    pub fee_tiers: [FeeTier; FEE_TIER_COUNT],
+}
+
+/// Arguments for SetReferralShare
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetReferralShareArgs {
+    /// Share in basis points of the protocol fee, at most
+    /// MAX_REFERRAL_SHARE_BPS (0 disables)
+    pub referral_share_bps: u16,
+}
+
+/// Arguments for ForceCancelOrder
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ForceCancelOrderArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Order ID
+    pub order_id: u64,
+}
+
+/// Arguments for QueryClaimable
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct QueryClaimableArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Wallet owning the position
+    pub user_wallet: Pubkey,
+}
+
+/// Arguments for ExpireDisputedProposal
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ExpireDisputedProposalArgs {
+    /// Market ID
+    pub market_id: u64,
+}
+
+/// Arguments for UpdateBookTop
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct UpdateBookTopArgs {
+    /// Market ID
+    pub market_id: u64,
+    /// Outcome of the book
+    pub outcome: Outcome,
+    /// Shares bid at the best bid price (0 when there are no bids)
+    pub bid_depth: u64,
+    /// Shares offered at the best ask price (0 when there are no asks)
+    pub ask_depth: u64,
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1845,6 +2729,10 @@ mod tests {
             resolution_time: 1700000000,
             finalization_deadline: 1701000000,
             creator_fee_bps: 100,
+            challenge_window_secs: 0,
+            category: MarketCategory::Crypto,
+            tags_hash: [7u8; 32],
+            resolution_source: ResolutionSource::Manual,
         };
         let ix = PredictionMarketInstruction::CreateMarket(args);
         let serialized = ix.try_to_vec().unwrap();
@@ -1855,11 +2743,38 @@ mod tests {
         match deserialized {
             PredictionMarketInstruction::CreateMarket(a) => {
                 assert_eq!(a.creator_fee_bps, 100);
+                assert_eq!(a.category, MarketCategory::Crypto);
+                assert_eq!(a.tags_hash, [7u8; 32]);
             }
             _ => panic!("Wrong instruction type"),
         }
     }
 
+    #[test]
+    fn test_create_market_rejects_unknown_category() {
+        let args = CreateMarketArgs {
+            question_hash: [0u8; 32],
+            resolution_spec_hash: [0u8; 32],
+            resolution_time: 1700000000,
+            finalization_deadline: 1701000000,
+            creator_fee_bps: 100,
+            challenge_window_secs: 0,
+            category: MarketCategory::Entertainment,
+            tags_hash: [0u8; 32],
+            resolution_source: ResolutionSource::Manual,
+        };
+        let mut serialized = PredictionMarketInstruction::CreateMarket(args).try_to_vec().unwrap();
+        
+        // Category byte sits before tags_hash and the one-byte Manual source
+        let category_offset = serialized.len() - 34;
+        assert_eq!(serialized[category_offset], MarketCategory::Entertainment as u8);
+        serialized[category_offset] = 5;
+        
+        let result: Result<PredictionMarketInstruction, _> =
+            BorshDeserialize::try_from_slice(&serialized);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_place_order_serialization() {
         let args = PlaceOrderArgs {
@@ -1870,6 +2785,7 @@ mod tests {
             amount: 100,
             order_type: OrderType::GTC,
             expiration_time: None,
+            reduce_only: false,
         };
         let ix = PredictionMarketInstruction::PlaceOrder(args);
         let serialized = ix.try_to_vec().unwrap();
@@ -1978,5 +2894,21 @@ mod tests {
             _ => panic!("Wrong instruction type"),
         }
     }
+
+    #[test]
+    fn test_update_fee_schedule_serialization() {
+        let fee_schedule = FeeSchedule { mint_bps: 10, redeem_bps: 15, trade_bps: 30, maker_rebate_bps: 10 };
+        let ix = PredictionMarketInstruction::UpdateFeeSchedule(UpdateFeeScheduleArgs { fee_schedule });
+        let serialized = ix.try_to_vec().unwrap();
+        
+        let deserialized: PredictionMarketInstruction = 
+            BorshDeserialize::try_from_slice(&serialized).unwrap();
+        match deserialized {
+            PredictionMarketInstruction::UpdateFeeSchedule(a) => {
+                assert_eq!(a.fee_schedule, fee_schedule);
+            }
+            _ => panic!("Wrong instruction type"),
+        }
+    }
 }
 
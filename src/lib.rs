@@ -16,7 +16,9 @@
 //! - Market creation and lifecycle management
 
 pub mod error;
+pub mod events;
 pub mod instruction;
+pub mod oracle_feed;
 pub mod processor;
 pub mod state;
 pub mod token_compat;
@@ -0,0 +1,258 @@
+//! Readers for external oracle feed accounts
+//!
+//! Feed accounts are parsed from their raw layout instead of pulling in the
+//! oracle SDK crates, which pin their own solana-program versions. Only the
+//! fields AutoResolveFromFeed and ResolveFromPyth need are read.
+
+use std::cmp::Ordering;
+
+use solana_program::{account_info::AccountInfo, msg, pubkey::Pubkey};
+
+use crate::error::PredictionMarketError;
+
+/// Switchboard V2 Program ID
+pub const SWITCHBOARD_V2_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f");
+
+/// Anchor discriminator of `AggregatorAccountData`
+pub const AGGREGATOR_DISCRIMINATOR: [u8; 8] = [217, 230, 65, 101, 201, 162, 27, 125];
+
+/// Offsets into `AggregatorAccountData.latest_confirmed_round`
+pub const ROUND_NUM_SUCCESS_OFFSET: usize = 341;
+pub const ROUND_OPEN_TIMESTAMP_OFFSET: usize = 358;
+pub const ROUND_RESULT_MANTISSA_OFFSET: usize = 366;
+pub const ROUND_RESULT_SCALE_OFFSET: usize = 382;
+
+/// Minimum aggregator length covering the fields read here
+pub const AGGREGATOR_MIN_LEN: usize = ROUND_RESULT_SCALE_OFFSET + 4;
+
+/// Scale of market thresholds (1e9)
+pub const THRESHOLD_DECIMALS: u32 = 9;
+
+/// Latest confirmed round of a Switchboard aggregator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwitchboardRound {
+    /// When the round was opened (Unix timestamp)
+    pub round_open_timestamp: i64,
+    /// Result = mantissa / 10^scale
+    pub mantissa: i128,
+    pub scale: u32,
+}
+
+impl SwitchboardRound {
+    /// Parse the latest confirmed round from aggregator account data
+    pub fn parse(data: &[u8]) -> Result<Self, PredictionMarketError> {
+        if data.len() < AGGREGATOR_MIN_LEN || data[..8] != AGGREGATOR_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidOracleFeed);
+        }
+
+        let num_success = u32::from_le_bytes(read_bytes(data, ROUND_NUM_SUCCESS_OFFSET));
+        if num_success == 0 {
+            return Err(PredictionMarketError::InvalidOracleFeed);
+        }
+
+        Ok(Self {
+            round_open_timestamp: i64::from_le_bytes(read_bytes(data, ROUND_OPEN_TIMESTAMP_OFFSET)),
+            mantissa: i128::from_le_bytes(read_bytes(data, ROUND_RESULT_MANTISSA_OFFSET)),
+            scale: u32::from_le_bytes(read_bytes(data, ROUND_RESULT_SCALE_OFFSET)),
+        })
+    }
+
+    /// Compare the result against a threshold scaled by 1e9
+    pub fn cmp_threshold(&self, threshold: i128) -> Result<Ordering, PredictionMarketError> {
+        cmp_decimal(self.mantissa, self.scale, threshold)
+    }
+}
+
+/// Compare `mantissa / 10^scale` against a threshold scaled by 1e9
+fn cmp_decimal(mantissa: i128, scale: u32, threshold: i128) -> Result<Ordering, PredictionMarketError> {
+    // Bring both sides to the larger scale
+    let (value, threshold) = if scale >= THRESHOLD_DECIMALS {
+        let factor = 10i128.checked_pow(scale - THRESHOLD_DECIMALS);
+        (mantissa, factor.and_then(|f| threshold.checked_mul(f)))
+    } else {
+        let factor = 10i128.pow(THRESHOLD_DECIMALS - scale);
+        (mantissa.checked_mul(factor).ok_or(PredictionMarketError::ArithmeticOverflow)?, Some(threshold))
+    };
+    let threshold = threshold.ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    Ok(value.cmp(&threshold))
+}
+
+/// Read the latest confirmed round of a Switchboard V2 aggregator account
+pub fn read_switchboard_round(feed_info: &AccountInfo) -> Result<SwitchboardRound, PredictionMarketError> {
+    if *feed_info.owner != SWITCHBOARD_V2_PROGRAM_ID {
+        msg!("Error: Feed {} is not owned by Switchboard V2", feed_info.key);
+        return Err(PredictionMarketError::InvalidOracleFeed);
+    }
+    SwitchboardRound::parse(&feed_info.data.borrow())
+}
+
+/// Pyth oracle program ID (mainnet)
+pub const PYTH_ORACLE_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+/// Pyth account header: magic, version and account type
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_VERSION: u32 = 2;
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+
+/// Aggregate status of a price that is currently trading
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+/// Offsets into a Pyth price account
+pub const PYTH_MAGIC_OFFSET: usize = 0;
+pub const PYTH_VERSION_OFFSET: usize = 4;
+pub const PYTH_ACCOUNT_TYPE_OFFSET: usize = 8;
+pub const PYTH_EXPO_OFFSET: usize = 20;
+pub const PYTH_TIMESTAMP_OFFSET: usize = 96;
+pub const PYTH_AGG_PRICE_OFFSET: usize = 208;
+pub const PYTH_AGG_STATUS_OFFSET: usize = 224;
+
+/// Minimum price account length covering the fields read here
+pub const PYTH_PRICE_MIN_LEN: usize = PYTH_AGG_STATUS_OFFSET + 4;
+
+/// Aggregate price of a Pyth price account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PythPrice {
+    /// Price = price * 10^expo
+    pub price: i64,
+    pub expo: i32,
+    /// When the aggregate was published (Unix timestamp)
+    pub publish_time: i64,
+}
+
+impl PythPrice {
+    /// Parse the aggregate price; only trading prices are accepted
+    pub fn parse(data: &[u8]) -> Result<Self, PredictionMarketError> {
+        if data.len() < PYTH_PRICE_MIN_LEN
+            || u32::from_le_bytes(read_bytes(data, PYTH_MAGIC_OFFSET)) != PYTH_MAGIC
+            || u32::from_le_bytes(read_bytes(data, PYTH_VERSION_OFFSET)) != PYTH_VERSION
+            || u32::from_le_bytes(read_bytes(data, PYTH_ACCOUNT_TYPE_OFFSET)) != PYTH_PRICE_ACCOUNT_TYPE
+        {
+            return Err(PredictionMarketError::InvalidOracleFeed);
+        }
+
+        if u32::from_le_bytes(read_bytes(data, PYTH_AGG_STATUS_OFFSET)) != PYTH_STATUS_TRADING {
+            return Err(PredictionMarketError::InvalidOracleFeed);
+        }
+
+        Ok(Self {
+            price: i64::from_le_bytes(read_bytes(data, PYTH_AGG_PRICE_OFFSET)),
+            expo: i32::from_le_bytes(read_bytes(data, PYTH_EXPO_OFFSET)),
+            publish_time: i64::from_le_bytes(read_bytes(data, PYTH_TIMESTAMP_OFFSET)),
+        })
+    }
+
+    /// Compare the price against a threshold scaled by 1e9
+    pub fn cmp_threshold(&self, threshold: i128) -> Result<Ordering, PredictionMarketError> {
+        if self.expo >= 0 {
+            let factor = 10i128
+                .checked_pow(self.expo.unsigned_abs())
+                .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+            let price = (self.price as i128)
+                .checked_mul(factor)
+                .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+            cmp_decimal(price, 0, threshold)
+        } else {
+            cmp_decimal(self.price as i128, self.expo.unsigned_abs(), threshold)
+        }
+    }
+}
+
+/// Read the aggregate price of a Pyth price account
+pub fn read_pyth_price(feed_info: &AccountInfo) -> Result<PythPrice, PredictionMarketError> {
+    if *feed_info.owner != PYTH_ORACLE_PROGRAM_ID {
+        msg!("Error: Feed {} is not owned by the Pyth oracle", feed_info.key);
+        return Err(PredictionMarketError::InvalidOracleFeed);
+    }
+    PythPrice::parse(&feed_info.data.borrow())
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(&data[offset..offset + N]);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregator(num_success: u32, mantissa: i128, scale: u32) -> Vec<u8> {
+        let mut data = vec![0u8; AGGREGATOR_MIN_LEN];
+        data[..8].copy_from_slice(&AGGREGATOR_DISCRIMINATOR);
+        data[ROUND_NUM_SUCCESS_OFFSET..ROUND_NUM_SUCCESS_OFFSET + 4].copy_from_slice(&num_success.to_le_bytes());
+        data[ROUND_OPEN_TIMESTAMP_OFFSET..ROUND_OPEN_TIMESTAMP_OFFSET + 8].copy_from_slice(&42i64.to_le_bytes());
+        data[ROUND_RESULT_MANTISSA_OFFSET..ROUND_RESULT_MANTISSA_OFFSET + 16].copy_from_slice(&mantissa.to_le_bytes());
+        data[ROUND_RESULT_SCALE_OFFSET..ROUND_RESULT_SCALE_OFFSET + 4].copy_from_slice(&scale.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_aggregator() {
+        let round = SwitchboardRound::parse(&aggregator(3, 6_512_345, 2)).unwrap();
+        assert_eq!(round, SwitchboardRound { round_open_timestamp: 42, mantissa: 6_512_345, scale: 2 });
+
+        assert!(SwitchboardRound::parse(&aggregator(0, 1, 0)).is_err());
+        assert!(SwitchboardRound::parse(&aggregator(1, 1, 0)[..AGGREGATOR_MIN_LEN - 1]).is_err());
+        let mut data = aggregator(1, 1, 0);
+        data[0] ^= 1;
+        assert!(SwitchboardRound::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_cmp_threshold_across_scales() {
+        // 65123.45 vs 65000
+        let round = SwitchboardRound { round_open_timestamp: 0, mantissa: 6_512_345, scale: 2 };
+        assert_eq!(round.cmp_threshold(65_000_000_000_000), Ok(Ordering::Greater));
+        assert_eq!(round.cmp_threshold(65_123_450_000_000), Ok(Ordering::Equal));
+
+        // 0.5000000001 vs 0.5 at a finer scale than the threshold
+        let round = SwitchboardRound { round_open_timestamp: 0, mantissa: 5_000_000_001, scale: 10 };
+        assert_eq!(round.cmp_threshold(500_000_000), Ok(Ordering::Greater));
+
+        let round = SwitchboardRound { round_open_timestamp: 0, mantissa: -1, scale: 0 };
+        assert_eq!(round.cmp_threshold(0), Ok(Ordering::Less));
+
+        let round = SwitchboardRound { round_open_timestamp: 0, mantissa: i128::MAX, scale: 0 };
+        assert_eq!(round.cmp_threshold(0), Err(PredictionMarketError::ArithmeticOverflow));
+    }
+
+    fn pyth_price_account(price: i64, expo: i32, status: u32) -> Vec<u8> {
+        let mut data = vec![0u8; PYTH_PRICE_MIN_LEN];
+        data[PYTH_MAGIC_OFFSET..][..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[PYTH_VERSION_OFFSET..][..4].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+        data[PYTH_ACCOUNT_TYPE_OFFSET..][..4].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[PYTH_EXPO_OFFSET..][..4].copy_from_slice(&expo.to_le_bytes());
+        data[PYTH_TIMESTAMP_OFFSET..][..8].copy_from_slice(&42i64.to_le_bytes());
+        data[PYTH_AGG_PRICE_OFFSET..][..8].copy_from_slice(&price.to_le_bytes());
+        data[PYTH_AGG_STATUS_OFFSET..][..4].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_pyth_price() {
+        let price = PythPrice::parse(&pyth_price_account(6_512_345_000_000, -8, PYTH_STATUS_TRADING)).unwrap();
+        assert_eq!(price, PythPrice { price: 6_512_345_000_000, expo: -8, publish_time: 42 });
+
+        // Halted prices and non-price accounts are rejected
+        assert!(PythPrice::parse(&pyth_price_account(1, -8, 0)).is_err());
+        let mut data = pyth_price_account(1, -8, PYTH_STATUS_TRADING);
+        data[PYTH_ACCOUNT_TYPE_OFFSET] = 2;
+        assert!(PythPrice::parse(&data).is_err());
+        assert!(PythPrice::parse(&pyth_price_account(1, -8, PYTH_STATUS_TRADING)[..PYTH_PRICE_MIN_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn test_pyth_cmp_threshold() {
+        // $65,123.45 with expo -8 vs $65,000
+        let price = PythPrice { price: 6_512_345_000_000, expo: -8, publish_time: 0 };
+        assert_eq!(price.cmp_threshold(65_000_000_000_000), Ok(Ordering::Greater));
+        assert_eq!(price.cmp_threshold(65_123_450_000_000), Ok(Ordering::Equal));
+
+        // Positive exponent: 7 * 10^3
+        let price = PythPrice { price: 7, expo: 3, publish_time: 0 };
+        assert_eq!(price.cmp_threshold(7_000_000_000_000), Ok(Ordering::Equal));
+        assert_eq!(price.cmp_threshold(7_000_000_000_001), Ok(Ordering::Less));
+    }
+}
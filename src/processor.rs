@@ -5,31 +5,40 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program::{invoke, invoke_signed},
+    program::{invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
-    sysvar::Sysvar,
+    sysvar::{instructions as instructions_sysvar, Sysvar},
 };
 
 use crate::error::PredictionMarketError;
 use crate::instruction::PredictionMarketInstruction;
+use crate::events::{self, PredictionMarketEvent};
+use crate::oracle_feed::{read_pyth_price, read_switchboard_round};
 use crate::state::{
-    PredictionMarketConfig, Market, Order, Position, OracleProposal,
-    MarketType, MarketStatus, MarketResult, ReviewStatus, OrderStatus, ProposalStatus, Outcome,
+    PredictionMarketConfig, ConfigExtension, Market, MarketExtension, Order, Position, OracleProposal,
+    MarketType, MarketStatus, MarketResult, MarketCategory, ReviewStatus, OrderStatus, ProposalStatus, Outcome,
+    ResolutionSource, buy_margin, buy_amount_for_margin,
     PM_CONFIG_SEED, MARKET_SEED, ORDER_SEED, ORDER_ESCROW_SEED, POSITION_SEED, 
     MARKET_VAULT_SEED, YES_MINT_SEED, NO_MINT_SEED, ORACLE_PROPOSAL_SEED, OUTCOME_MINT_SEED,
     PM_CONFIG_DISCRIMINATOR, MARKET_DISCRIMINATOR, ORDER_DISCRIMINATOR, 
     POSITION_DISCRIMINATOR, ORACLE_PROPOSAL_DISCRIMINATOR,
-    PRICE_PRECISION, MIN_PRICE, MAX_PRICE, MAX_OUTCOMES,
+    PRICE_PRECISION, MIN_PRICE, MAX_PRICE, MAX_OUTCOMES, MAX_BATCH_CLAIM_USERS, MAX_BATCH_CANCEL_ORDERS, MAX_TRADE_MAKERS,
+    MAX_RESOLUTION_EXTENSION_SECS, REOPEN_GRACE_PERIOD_SECS, PYTH_MAX_PRICE_AGE_SECS, MIN_CHALLENGE_WINDOW_SECS, MAX_CHALLENGE_WINDOW_SECS,
+    DISPUTE_RESOLUTION_PERIOD_SECS,
 };
 use crate::utils::{
     check_signer, get_current_timestamp,
-    safe_add_u64,
+    safe_add_u64, mul_div_floor,
     validate_price, validate_price_pair,
-    deserialize_account,
+    calculate_complete_set_collateral, get_token_balance, get_token_mint_and_owner,
+    deserialize_account, try_deserialize_initialized,
+    validate_mint_price_sum, validate_burn_price_sum, par_burn_prices, validate_market_schedule,
+    validate_max_cost,
+    validate_market_metadata, require_resolvable,
 };
 use crate::cpi::{
     cpi_lock_for_prediction,
@@ -41,6 +50,8 @@ use crate::cpi::{
     cpi_lock_for_prediction_with_fee,
     cpi_release_from_prediction_with_fee,
     cpi_settle_with_fee,
+    verify_vault_program,
+    verify_token_program,
 };
 use crate::token_compat;
 
@@ -124,21 +135,21 @@ pub fn process_instruction(
         
         // === Oracle / Resolution ===
         // 注意：这些功能需要从链上 V7 程序调用，本地代码被意外删除
-        PredictionMarketInstruction::ProposeResult(_) => {
-            msg!("⚠️ ProposeResult: Use deployed V7 program");
-            Err(ProgramError::InvalidInstructionData)
+        PredictionMarketInstruction::ProposeResult(args) => {
+            msg!("Instruction: ProposeResult");
+            process_propose_result(program_id, accounts, args)
         }
-        PredictionMarketInstruction::ChallengeResult(_) => {
-            msg!("⚠️ ChallengeResult: Use deployed V7 program");
-            Err(ProgramError::InvalidInstructionData)
+        PredictionMarketInstruction::ChallengeResult(args) => {
+            msg!("Instruction: ChallengeResult");
+            process_challenge_result(program_id, accounts, args)
         }
         PredictionMarketInstruction::FinalizeResult => {
-            msg!("⚠️ FinalizeResult: Use deployed V7 program");
-            Err(ProgramError::InvalidInstructionData)
+            msg!("Instruction: FinalizeResult");
+            process_finalize_result(program_id, accounts)
         }
-        PredictionMarketInstruction::ResolveDispute(_) => {
-            msg!("⚠️ ResolveDispute: Use deployed V7 program");
-            Err(ProgramError::InvalidInstructionData)
+        PredictionMarketInstruction::ResolveDispute(args) => {
+            msg!("Instruction: ResolveDispute");
+            process_resolve_dispute(program_id, accounts, args)
         }
         
         // === Settlement ===
@@ -154,53 +165,19 @@ pub fn process_instruction(
         // === Admin Operations ===
         PredictionMarketInstruction::UpdateAdmin(args) => {
             msg!("Instruction: UpdateAdmin");
-            let account_info_iter = &mut accounts.iter();
-            let admin_info = next_account_info(account_info_iter)?;
-            let config_info = next_account_info(account_info_iter)?;
-            
-            if !admin_info.is_signer {
-                return Err(ProgramError::MissingRequiredSignature);
-            }
-            
-            let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
-            if config.admin != *admin_info.key {
-                msg!("Error: Only current admin can update admin");
-                return Err(PredictionMarketError::Unauthorized.into());
-            }
-            
-            config.admin = args.new_admin;
-            config.serialize(&mut *config_info.data.borrow_mut())?;
-            msg!("✅ Admin updated to: {}", args.new_admin);
-            Ok(())
+            process_update_admin(program_id, accounts, args)
         }
         PredictionMarketInstruction::UpdateOracleAdmin(args) => {
             msg!("Instruction: UpdateOracleAdmin");
-            let account_info_iter = &mut accounts.iter();
-            let admin_info = next_account_info(account_info_iter)?;
-            let config_info = next_account_info(account_info_iter)?;
-            
-            if !admin_info.is_signer {
-                return Err(ProgramError::MissingRequiredSignature);
-            }
-            
-            let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
-            if config.admin != *admin_info.key {
-                msg!("Error: Only admin can update oracle admin");
-                return Err(PredictionMarketError::Unauthorized.into());
-            }
-            
-            config.oracle_admin = args.new_oracle_admin;
-            config.serialize(&mut *config_info.data.borrow_mut())?;
-            msg!("✅ Oracle admin updated to: {}", args.new_oracle_admin);
-            Ok(())
+            process_update_oracle_admin(program_id, accounts, args)
         }
-        PredictionMarketInstruction::SetPaused(_) => {
-            msg!("⚠️ SetPaused: Not implemented in this version");
-            Err(ProgramError::InvalidInstructionData)
+        PredictionMarketInstruction::SetPaused(args) => {
+            msg!("Instruction: SetPaused");
+            process_set_paused(program_id, accounts, args)
         }
-        PredictionMarketInstruction::UpdateOracleConfig(_) => {
-            msg!("⚠️ UpdateOracleConfig: Use deployed V7 program");
-            Err(ProgramError::InvalidInstructionData)
+        PredictionMarketInstruction::UpdateOracleConfig(args) => {
+            msg!("Instruction: UpdateOracleConfig");
+            process_update_oracle_config(program_id, accounts, args)
         }
         PredictionMarketInstruction::AddAuthorizedCaller(args) => {
             msg!("Instruction: AddAuthorizedCaller");
@@ -228,9 +205,9 @@ pub fn process_instruction(
             msg!("⚠️ PlaceMultiOutcomeOrder: Use deployed V7 program");
             Err(ProgramError::InvalidInstructionData)
         }
-        PredictionMarketInstruction::ProposeMultiOutcomeResult(_) => {
-            msg!("⚠️ ProposeMultiOutcomeResult: Use deployed V7 program");
-            Err(ProgramError::InvalidInstructionData)
+        PredictionMarketInstruction::ProposeMultiOutcomeResult(args) => {
+            msg!("Instruction: ProposeMultiOutcomeResult");
+            process_propose_multi_outcome_result(program_id, accounts, args)
         }
         PredictionMarketInstruction::ClaimMultiOutcomeWinnings(_) => {
             msg!("⚠️ ClaimMultiOutcomeWinnings: Use deployed V7 program");
@@ -260,7 +237,7 @@ pub fn process_instruction(
             Err(PredictionMarketError::InstructionDeprecated.into())
         }
         PredictionMarketInstruction::RelayerRefundCancelledMarket(_) => {
-            msg!("⚠️ RelayerRefundCancelledMarket: Use deployed V7 program");
+            msg!("⚠️ RelayerRefundCancelledMarket: Use RelayerRefundCancelledMarketV2");
             Err(ProgramError::InvalidInstructionData)
         }
         PredictionMarketInstruction::RelayerMintMultiOutcomeCompleteSet(_) => {
@@ -425,6 +402,158 @@ pub fn process_instruction(
             msg!("Instruction: RelayerSettlePrediction");
             process_relayer_settle_prediction(program_id, accounts, args)
         }
+        
+        // === Two-Step Admin Handover ===
+        PredictionMarketInstruction::InitiateAdminTransfer(args) => {
+            msg!("Instruction: InitiateAdminTransfer");
+            process_initiate_admin_transfer(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::AcceptAdminTransfer => {
+            msg!("Instruction: AcceptAdminTransfer");
+            process_accept_admin_transfer(program_id, accounts)
+        }
+        PredictionMarketInstruction::CancelAdminTransfer => {
+            msg!("Instruction: CancelAdminTransfer");
+            process_cancel_admin_transfer(program_id, accounts)
+        }
+        
+        // === Maintenance ===
+        PredictionMarketInstruction::SyncOrderOutcomeIndex(args) => {
+            msg!("Instruction: SyncOrderOutcomeIndex");
+            process_sync_order_outcome_index(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetMaxPriceSumDeviation(args) => {
+            msg!("Instruction: SetMaxPriceSumDeviation");
+            process_set_max_price_sum_deviation(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SweepExpiredOrders => {
+            msg!("Instruction: SweepExpiredOrders");
+            process_sweep_expired_orders(program_id, accounts)
+        }
+        PredictionMarketInstruction::RelayerRefundCancelledMarketV2(args) => {
+            msg!("Instruction: RelayerRefundCancelledMarketV2");
+            process_relayer_refund_cancelled_market_v2(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::RelayerBatchClaimWinningsV2(args) => {
+            msg!("Instruction: RelayerBatchClaimWinningsV2");
+            process_relayer_batch_claim_winnings_v2(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetMarketTvlCap(args) => {
+            msg!("Instruction: SetMarketTvlCap");
+            process_set_market_tvl_cap(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetTradeFee(args) => {
+            msg!("Instruction: SetTradeFee");
+            process_set_trade_fee(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetMakerRebate(args) => {
+            msg!("Instruction: SetMakerRebate");
+            process_set_maker_rebate(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetAllowMatchMint(args) => {
+            msg!("Instruction: SetAllowMatchMint");
+            process_set_allow_match_mint(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ExpireOrder(args) => {
+            msg!("Instruction: ExpireOrder");
+            process_expire_order(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::UpdateMarketSchedule(args) => {
+            msg!("Instruction: UpdateMarketSchedule");
+            process_update_market_schedule(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::RelayerAmendOrderV2(args) => {
+            msg!("Instruction: RelayerAmendOrderV2");
+            process_relayer_amend_order_v2(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetMinOrderSize(args) => {
+            msg!("Instruction: SetMinOrderSize");
+            process_set_min_order_size(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::CloseOrder(args) => {
+            msg!("Instruction: CloseOrder");
+            process_close_order(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ClosePosition(args) => {
+            msg!("Instruction: ClosePosition");
+            process_close_position(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetMinNotional(args) => {
+            msg!("Instruction: SetMinNotional");
+            process_set_min_notional(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::QueryMarket(args) => {
+            msg!("Instruction: QueryMarket");
+            process_query_market(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ExtendResolutionTime(args) => {
+            msg!("Instruction: ExtendResolutionTime");
+            process_extend_resolution_time(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetMintFee(args) => {
+            msg!("Instruction: SetMintFee");
+            process_set_mint_fee(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetMarketCreationBond(args) => {
+            msg!("Instruction: SetMarketCreationBond");
+            process_set_market_creation_bond(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ReleaseCreationBond(args) => {
+            msg!("Instruction: ReleaseCreationBond");
+            process_release_creation_bond(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ReopenMarket(args) => {
+            msg!("Instruction: ReopenMarket");
+            process_reopen_market(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::AutoInvalidateMarket(args) => {
+            msg!("Instruction: AutoInvalidateMarket");
+            process_auto_invalidate_market(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::AutoResolveFromFeed(args) => {
+            msg!("Instruction: AutoResolveFromFeed");
+            process_auto_resolve_from_feed(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ResolveFromPyth(args) => {
+            msg!("Instruction: ResolveFromPyth");
+            process_resolve_from_pyth(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::RelayerBatchCancelOrdersV2(args) => {
+            msg!("Instruction: RelayerBatchCancelOrdersV2");
+            process_relayer_batch_cancel_orders_v2(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ExecuteTradeMultiV2(args) => {
+            msg!("Instruction: ExecuteTradeMultiV2");
+            process_execute_trade_multi_v2(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::UpdateFeeSchedule(args) => {
+            msg!("Instruction: UpdateFeeSchedule");
+            process_update_fee_schedule(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetFeeTiers(args) => {
+            msg!("Instruction: SetFeeTiers");
+            process_set_fee_tiers(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::SetReferralShare(args) => {
+            msg!("Instruction: SetReferralShare");
+            process_set_referral_share(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::UpdateBookTop(args) => {
+            msg!("Instruction: UpdateBookTop");
+            process_update_book_top(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ExpireDisputedProposal(args) => {
+            msg!("Instruction: ExpireDisputedProposal");
+            process_expire_disputed_proposal(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::QueryClaimable(args) => {
+            msg!("Instruction: QueryClaimable");
+            process_query_claimable(program_id, accounts, args)
+        }
+        PredictionMarketInstruction::ForceCancelOrder(args) => {
+            msg!("Instruction: ForceCancelOrder");
+            process_force_cancel_order(program_id, accounts, args)
+        }
     }
 }
 
@@ -466,6 +595,8 @@ fn process_initialize(
         return Err(ProgramError::IncorrectProgramId);
     }
     
+    validate_program_config(program_id, vault_program_info.key, fund_program_info.key)?;
+    
     // Calculate PDA and verify
     let (config_pda, config_bump) = Pubkey::find_program_address(
         &[PM_CONFIG_SEED],
@@ -556,6 +687,8 @@ fn process_reinitialize_config(
     // Account 4: Fund Program
     let fund_program_info = next_account_info(account_info_iter)?;
     
+    validate_program_config(program_id, vault_program_info.key, fund_program_info.key)?;
+    
     // Verify config PDA
     let (config_pda, config_bump) = Pubkey::find_program_address(
         &[PM_CONFIG_SEED],
@@ -582,6 +715,14 @@ fn process_reinitialize_config(
         return Err(PredictionMarketError::Unauthorized.into());
     }
     
+    // Resetting next_market_id with existing markets would make the next
+    // CreateMarket derive an already-existing Market PDA (market id 1)
+    if args.reset_counters && (existing_config.total_markets > 0 || existing_config.next_market_id > 1) {
+        msg!("Error: Cannot reset counters with {} existing markets (next_market_id={})", 
+             existing_config.total_markets, existing_config.next_market_id);
+        return Err(PredictionMarketError::CannotResetWithActiveMarkets.into());
+    }
+    
     // Create new config data
     let mut new_config = PredictionMarketConfig::new(
         *admin_info.key,
@@ -674,24 +815,39 @@ fn process_create_market(
         msg!("Error: USDC Mint mismatch");
         return Err(PredictionMarketError::InvalidUSDCMint.into());
     }
+    token_compat::check_supported_mint(usdc_mint_info)?;
+    
+    // Outcome mints are created under the passed Token-v1 or Token-2022 program
+    verify_token_program(token_program_info.key)?;
     
     // Validate market parameters
     let current_time = get_current_timestamp()?;
-    if args.resolution_time <= current_time {
-        msg!("Error: Resolution time must be in the future");
-        return Err(PredictionMarketError::InvalidResolutionTime.into());
-    }
-    
-    if args.finalization_deadline <= args.resolution_time {
-        msg!("Error: Finalization deadline must be after resolution time");
-        return Err(PredictionMarketError::InvalidFinalizationDeadline.into());
-    }
+    validate_market_schedule(args.resolution_time, args.finalization_deadline, current_time)?;
+    validate_market_metadata(&args.question_hash, &args.resolution_spec_hash)?;
     
     if args.creator_fee_bps > 500 {
         msg!("Error: Creator fee cannot exceed 5%");
         return Err(PredictionMarketError::CreatorFeeTooHigh.into());
     }
     
+    if args.challenge_window_secs != 0
+        && !(MIN_CHALLENGE_WINDOW_SECS..=MAX_CHALLENGE_WINDOW_SECS).contains(&args.challenge_window_secs)
+    {
+        msg!("Error: Challenge window {}s out of bounds [{}, {}]", 
+             args.challenge_window_secs, MIN_CHALLENGE_WINDOW_SECS, MAX_CHALLENGE_WINDOW_SECS);
+        return Err(PredictionMarketError::InvalidChallengeWindow.into());
+    }
+    
+    match args.resolution_source {
+        ResolutionSource::Switchboard { feed, .. } | ResolutionSource::Pyth { feed, .. }
+            if feed == Pubkey::default() =>
+        {
+            msg!("Error: Feed resolution source needs a feed account");
+            return Err(PredictionMarketError::InvalidOracleFeed.into());
+        }
+        _ => {}
+    }
+    
     // Allocate market_id
     let market_id = config.next_market_id;
     let market_id_bytes = market_id.to_le_bytes();
@@ -736,10 +892,19 @@ fn process_create_market(
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
+    // Accounts 10-13 (bond only): lock the creation bond
+    let creation_bond = lock_creation_bond(
+        account_info_iter,
+        &config,
+        config_info,
+        creator_info,
+        system_program_info,
+    )?;
+    
     let rent = Rent::get()?;
     
-    // Create Market account
-    let market_space = Market::SIZE;
+    // Create Market account (with room for MarketExtension and the resolution source)
+    let market_space = Market::SIZE + MarketExtension::SIZE + ResolutionSource::SIZE;
     let market_lamports = rent.minimum_balance(market_space);
     let market_seeds: &[&[u8]] = &[MARKET_SEED, &market_id_bytes, &[market_bump]];
     
@@ -774,7 +939,7 @@ fn process_create_market(
     
     // Initialize YES Mint (authority = Market PDA)
     invoke_signed(
-        &spl_token::instruction::initialize_mint(
+        &spl_token_2022::instruction::initialize_mint(
             token_program_info.key,
             yes_mint_info.key,
             market_info.key, // mint_authority
@@ -802,7 +967,7 @@ fn process_create_market(
     
     // Initialize NO Mint (authority = Market PDA)
     invoke_signed(
-        &spl_token::instruction::initialize_mint(
+        &spl_token_2022::instruction::initialize_mint(
             token_program_info.key,
             no_mint_info.key,
             market_info.key, // mint_authority
@@ -818,7 +983,7 @@ fn process_create_market(
     // On 1024Chain, USDC uses Token-2022. We use usdc_mint_info.owner to dynamically
     // determine the correct token program, making this work for both Token-v1 and Token-2022 mints.
     let usdc_token_program = usdc_mint_info.owner;
-    let vault_space = spl_token::state::Account::LEN;
+    let vault_space = token_compat::get_token_account_size_for_mint(usdc_mint_info)?;
     let vault_lamports = rent.minimum_balance(vault_space);
     let market_vault_seeds: &[&[u8]] = &[MARKET_VAULT_SEED, &market_id_bytes, &[market_vault_bump]];
     
@@ -877,15 +1042,23 @@ fn process_create_market(
         creator_fee_bps: args.creator_fee_bps,
         next_order_id: 1,
         bump: market_bump,
-        reserved: [0u8; 60],
+        max_tvl_e6: 0,
+        total_realized_pnl_e6: 0,
+        challenge_window_secs: args.challenge_window_secs,
+        category: args.category,
+        tags_hash: args.tags_hash,
+        pause_reason: 0,
+        reserved: [0u8; 2],
     };
     
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    store_creation_bond(market_info, creation_bond)?;
+    args.resolution_source.store(&mut market_info.data.borrow_mut())?;
     
     // Update config
     config.next_market_id += 1;
     config.total_markets += 1;
-    config.serialize(&mut *config_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     msg!("Market created successfully");
     msg!("Market ID: {}", market_id);
@@ -895,6 +1068,7 @@ fn process_create_market(
     msg!("Market Vault: {}", market_vault_info.key);
     msg!("Resolution Time: {}", args.resolution_time);
     msg!("Creator Fee: {} bps", args.creator_fee_bps);
+    msg!("Creation Bond: {}", creation_bond);
     
     msg!("market_created:{},{},{},{},{},{}", market_id, creator_info.key, yes_mint_info.key, no_mint_info.key, market_vault_info.key, args.resolution_time);
     
@@ -913,6 +1087,8 @@ fn process_create_market(
 /// 6. `[]` System Program
 /// 7. `[]` Rent Sysvar
 /// 8..8+n. `[writable]` Outcome Token Mints (n outcomes)
+/// 
+/// The creation bond accounts follow when a bond is configured.
 fn process_create_multi_outcome_market(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -962,6 +1138,10 @@ fn process_create_multi_outcome_market(
         msg!("Error: USDC Mint mismatch");
         return Err(PredictionMarketError::InvalidUSDCMint.into());
     }
+    token_compat::check_supported_mint(usdc_mint_info)?;
+    
+    // Outcome mints are created under the passed Token-v1 or Token-2022 program
+    verify_token_program(token_program_info.key)?;
     
     // Validate num_outcomes (2-32)
     if args.num_outcomes < 2 || args.num_outcomes as usize > MAX_OUTCOMES {
@@ -978,15 +1158,8 @@ fn process_create_multi_outcome_market(
     
     // Validate market parameters
     let current_time = get_current_timestamp()?;
-    if args.resolution_time <= current_time {
-        msg!("Error: Resolution time must be in the future");
-        return Err(PredictionMarketError::InvalidResolutionTime.into());
-    }
-    
-    if args.finalization_deadline <= args.resolution_time {
-        msg!("Error: Finalization deadline must be after resolution time");
-        return Err(PredictionMarketError::InvalidFinalizationDeadline.into());
-    }
+    validate_market_schedule(args.resolution_time, args.finalization_deadline, current_time)?;
+    validate_market_metadata(&args.question_hash, &args.resolution_spec_hash)?;
     
     if args.creator_fee_bps > 500 {
         msg!("Error: Creator fee cannot exceed 5%");
@@ -1019,8 +1192,8 @@ fn process_create_multi_outcome_market(
     
     let rent = Rent::get()?;
     
-    // Create Market account
-    let market_space = Market::SIZE;
+    // Create Market account (with room for MarketExtension)
+    let market_space = Market::SIZE + MarketExtension::SIZE;
     let market_lamports = rent.minimum_balance(market_space);
     let market_seeds: &[&[u8]] = &[MARKET_SEED, &market_id_bytes, &[market_bump]];
     
@@ -1039,7 +1212,7 @@ fn process_create_multi_outcome_market(
     // Create Market Vault (USDC Token Account)
     // Use usdc_mint_info.owner to get the correct token program (Token-v1 or Token-2022)
     let usdc_token_program = usdc_mint_info.owner;
-    let vault_space = spl_token::state::Account::LEN;
+    let vault_space = token_compat::get_token_account_size_for_mint(usdc_mint_info)?;
     let vault_lamports = rent.minimum_balance(vault_space);
     let vault_seeds: &[&[u8]] = &[MARKET_VAULT_SEED, &market_id_bytes, &[market_vault_bump]];
     
@@ -1117,7 +1290,7 @@ fn process_create_multi_outcome_market(
         
         // Initialize Outcome Mint (authority = Market PDA)
         invoke_signed(
-            &spl_token::instruction::initialize_mint(
+            &spl_token_2022::instruction::initialize_mint(
                 token_program_info.key,
                 outcome_mint_info.key,
                 market_info.key, // mint_authority
@@ -1141,6 +1314,15 @@ fn process_create_multi_outcome_market(
         msg!("Created Outcome {} Mint: {}", outcome_index, outcome_mint_info.key);
     }
     
+    // Accounts 8+n..12+n (bond only): lock the creation bond
+    let creation_bond = lock_creation_bond(
+        account_info_iter,
+        &config,
+        config_info,
+        creator_info,
+        system_program_info,
+    )?;
+    
     // Initialize market data
     let market = Market {
         discriminator: MARKET_DISCRIMINATOR,
@@ -1167,15 +1349,22 @@ fn process_create_multi_outcome_market(
         creator_fee_bps: args.creator_fee_bps,
         next_order_id: 1,
         bump: market_bump,
-        reserved: [0u8; 60],
+        max_tvl_e6: 0,
+        total_realized_pnl_e6: 0,
+        challenge_window_secs: 0,
+        category: MarketCategory::Other,
+        tags_hash: [0u8; 32],
+        pause_reason: 0,
+        reserved: [0u8; 2],
     };
     
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    store_creation_bond(market_info, creation_bond)?;
     
     // Update config
     config.next_market_id += 1;
     config.total_markets += 1;
-    config.serialize(&mut *config_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     msg!("✅ Multi-outcome market created successfully");
     msg!("Market ID: {}", market_id);
@@ -1184,12 +1373,73 @@ fn process_create_multi_outcome_market(
     msg!("Market Vault: {}", market_vault_info.key);
     msg!("Resolution Time: {}", args.resolution_time);
     msg!("Creator Fee: {} bps", args.creator_fee_bps);
+    msg!("Creation Bond: {}", creation_bond);
     
     msg!("multi_outcome_market_created:{},{},{},{}", market_id, creator_info.key, args.num_outcomes, args.resolution_time);
     
     Ok(())
 }
 
+/// Lock the configured market creation bond from the creator
+/// 
+/// Takes the Vault Program, VaultConfig and the creator's Vault UserAccount
+/// and PMUserAccount from `account_info_iter`. Returns 0 without consuming
+/// any accounts when no bond is configured.
+fn lock_creation_bond<'a, 'b>(
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    config: &PredictionMarketConfig,
+    config_info: &AccountInfo<'a>,
+    creator_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> Result<u64, ProgramError> {
+    let bond = ConfigExtension::load(&config_info.data.borrow()).market_creation_bond_e6;
+    if bond == 0 {
+        return Ok(0);
+    }
+    
+    let vault_program_info = next_account_info(account_info_iter)?;
+    let vault_config_info = next_account_info(account_info_iter)?;
+    let user_vault_info = next_account_info(account_info_iter)?;
+    let pm_user_account_info = next_account_info(account_info_iter)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_pm_user_account(pm_user_account_info, creator_info.key, vault_program_info.key)?;
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config.bump]];
+    msg!("CPI: Vault.PredictionMarketLock creation bond={}", bond);
+    let locked = cpi_lock_for_prediction(
+        vault_program_info,
+        vault_config_info,
+        user_vault_info,
+        pm_user_account_info,
+        config_info,
+        creator_info,
+        system_program_info,
+        bond,
+        config_seeds,
+    )?;
+    
+    // A partial lock is rolled back with the rest of the transaction
+    if locked != bond {
+        msg!("Error: Creator could only lock {} of the {} creation bond", locked, bond);
+        return Err(PredictionMarketError::InsufficientFunds.into());
+    }
+    
+    Ok(bond)
+}
+
+/// Record a locked creation bond on a newly created market
+fn store_creation_bond(market_info: &AccountInfo, creation_bond: u64) -> ProgramResult {
+    if creation_bond > 0 {
+        let extension = MarketExtension {
+            creation_bond_e6: creation_bond,
+            ..MarketExtension::default()
+        };
+        extension.store(&mut market_info.data.borrow_mut())?;
+    }
+    Ok(())
+}
+
 fn process_activate_market(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -1249,11 +1499,11 @@ fn process_activate_market(
     let current_time = get_current_timestamp()?;
     market.status = MarketStatus::Active;
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     // Update config
     config.active_markets += 1;
-    config.serialize(&mut *config_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     msg!("Market {} activated successfully", args.market_id);
     msg!("market_status_changed:{},{},{}", args.market_id, "Active", current_time);
@@ -1261,6 +1511,64 @@ fn process_activate_market(
     Ok(())
 }
 
+/// Correct the schedule of a market that has not been activated yet
+/// 
+/// Creator only. Unset fields keep their current value; the resulting
+/// schedule is validated as on creation.
+fn process_update_market_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateMarketScheduleArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Creator (signer)
+    let creator_info = next_account_info(account_info_iter)?;
+    check_signer(creator_info)?;
+    
+    // Account 1: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if *creator_info.key != market.creator {
+        msg!("Error: Only the market creator can update the schedule");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    if market.status != MarketStatus::Pending {
+        msg!("Error: Schedule can only be updated before activation");
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let resolution_time = args.resolution_time.unwrap_or(market.resolution_time);
+    let finalization_deadline = args.finalization_deadline.unwrap_or(market.finalization_deadline);
+    
+    let current_time = get_current_timestamp()?;
+    validate_market_schedule(resolution_time, finalization_deadline, current_time)?;
+    
+    market.resolution_time = resolution_time;
+    market.finalization_deadline = finalization_deadline;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Market {} schedule updated", args.market_id);
+    msg!("market_schedule_updated:{},{},{}", args.market_id, resolution_time, finalization_deadline);
+    
+    Ok(())
+}
+
 fn process_pause_market(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -1311,14 +1619,15 @@ fn process_pause_market(
     // Pause market
     let current_time = get_current_timestamp()?;
     market.status = MarketStatus::Paused;
+    market.pause_reason = args.reason;
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     // Update config
     config.active_markets = config.active_markets.saturating_sub(1);
-    config.serialize(&mut *config_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    msg!("Market {} paused successfully", args.market_id);
+    msg!("Market {} paused successfully. Reason: {}", args.market_id, args.reason);
     msg!("market_status_changed:{},{},{}", args.market_id, "Paused", current_time);
     
     Ok(())
@@ -1373,12 +1682,13 @@ fn process_resume_market(
     // Resume market
     let current_time = get_current_timestamp()?;
     market.status = MarketStatus::Active;
+    market.pause_reason = 0;
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     // Update config
     config.active_markets += 1;
-    config.serialize(&mut *config_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     msg!("Market {} resumed successfully", args.market_id);
     msg!("market_status_changed:{},{},{}", args.market_id, "Active", current_time);
@@ -1445,12 +1755,20 @@ fn process_cancel_market(
         _ => ReviewStatus::None,
     };
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    // Start the ReopenMarket grace period
+    if MarketExtension::fits(&market_info.data.borrow()) {
+        let mut extension = MarketExtension::load(&market_info.data.borrow());
+        extension.cancelled_at = current_time;
+        extension.cancelled_payouts = 0;
+        extension.store(&mut market_info.data.borrow_mut())?;
+    }
     
     // Update config if was active
     if was_active {
         config.active_markets = config.active_markets.saturating_sub(1);
-        config.serialize(&mut *config_info.data.borrow_mut())?;
+        config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     }
     
     msg!("Market {} cancelled successfully. Reason: {}", args.market_id, args.reason);
@@ -1459,6 +1777,92 @@ fn process_cancel_market(
     Ok(())
 }
 
+/// Revert a mistaken CancelMarket while no refunds have been paid
+/// 
+/// The market goes back to Paused rather than Active so the admin can
+/// review it and ResumeMarket explicitly. Neither Paused nor Cancelled
+/// markets count towards `active_markets`, so the config is untouched.
+fn process_reopen_market(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ReopenMarketArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
+    
+    let config_info = next_account_info(account_info_iter)?;
+    let market_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    if config_info.owner != program_id {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if *admin_info.key != config.admin {
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.status != MarketStatus::Cancelled {
+        msg!("Error: Only cancelled markets can be reopened ({:?})", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    // Without the tail there is no record of when or whether refunds began
+    if !MarketExtension::fits(&market_info.data.borrow()) {
+        msg!("Error: Market {} predates cancellation tracking", args.market_id);
+        return Err(PredictionMarketError::ReopenWindowExpired.into());
+    }
+    let mut extension = MarketExtension::load(&market_info.data.borrow());
+    
+    let current_time = get_current_timestamp()?;
+    let reopen_deadline = extension.cancelled_at.saturating_add(REOPEN_GRACE_PERIOD_SECS);
+    if extension.cancelled_at == 0 || current_time > reopen_deadline {
+        msg!("Error: Reopen window closed at {}", reopen_deadline);
+        return Err(PredictionMarketError::ReopenWindowExpired.into());
+    }
+    
+    if extension.cancelled_payouts > 0 {
+        msg!("Error: {} refunds already paid from market {}", extension.cancelled_payouts, args.market_id);
+        return Err(PredictionMarketError::RefundsAlreadyStarted.into());
+    }
+    
+    market.status = MarketStatus::Paused;
+    market.review_status = ReviewStatus::None;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    extension.cancelled_at = 0;
+    extension.store(&mut market_info.data.borrow_mut())?;
+    
+    msg!("Market {} reopened as Paused", args.market_id);
+    msg!("market_status_changed:{},{},{}", args.market_id, "Paused", current_time);
+    
+    Ok(())
+}
+
 fn process_flag_market(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -1510,6 +1914,40 @@ fn process_flag_market(
     Ok(())
 }
 
+/// USDC transfer for the complete-set paths
+/// 
+/// TransferChecked when the USDC mint is passed (required by Token-2022
+/// transfer-fee mints), plain Transfer otherwise.
+fn transfer_usdc<'a>(
+    token_program_info: &AccountInfo<'a>,
+    source_info: &AccountInfo<'a>,
+    usdc_mint_info: Option<&AccountInfo<'a>>,
+    destination_info: &AccountInfo<'a>,
+    authority_info: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: Option<&[&[u8]]>,
+) -> ProgramResult {
+    match usdc_mint_info {
+        Some(usdc_mint_info) => token_compat::transfer_checked(
+            token_program_info,
+            source_info,
+            usdc_mint_info,
+            destination_info,
+            authority_info,
+            amount,
+            signer_seeds,
+        ),
+        None => token_compat::transfer(
+            token_program_info,
+            source_info,
+            destination_info,
+            authority_info,
+            amount,
+            signer_seeds,
+        ),
+    }
+}
+
 fn process_mint_complete_set(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -1554,6 +1992,16 @@ fn process_mint_complete_set(
     // Account 11: System Program
     let system_program_info = next_account_info(account_info_iter)?;
     
+    // Account 12 (optional): Fund Program's USDC Account (mint fee)
+    let fund_usdc_info = next_account_info(account_info_iter).ok();
+    
+    // Account 13 (optional): USDC Mint (Token-2022 transfer-fee mints)
+    let usdc_mint_info = next_account_info(account_info_iter).ok();
+    
+    // Account 14 (optional): Associated Token Account Program (creates
+    // missing YES/NO accounts)
+    let ata_program_info = next_account_info(account_info_iter).ok();
+    
     // Load and validate config
     let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
@@ -1571,9 +2019,9 @@ fn process_mint_complete_set(
     }
     
     // Verify market is tradeable
-    if !market.is_tradeable() {
-        msg!("Error: Market is not tradeable");
-        return Err(PredictionMarketError::MarketNotTradeable.into());
+    if let Err(e) = market.check_tradeable() {
+        msg!("Error: Market is not tradeable ({:?})", market.status);
+        return Err(e.into());
     }
     
     // Verify market vault
@@ -1581,6 +2029,13 @@ fn process_mint_complete_set(
         return Err(PredictionMarketError::InvalidMarketVault.into());
     }
     
+    verify_token_program(token_program_info.key)?;
+    if let Some(usdc_mint_info) = usdc_mint_info {
+        if *usdc_mint_info.key != config.usdc_mint {
+            return Err(PredictionMarketError::InvalidUSDCMint.into());
+        }
+    }
+    
     // Verify mints
     if *yes_mint_info.key != market.yes_mint {
         return Err(PredictionMarketError::InvalidYesMint.into());
@@ -1594,51 +2049,114 @@ fn process_mint_complete_set(
         return Err(PredictionMarketError::InvalidAmount.into());
     }
     
+    market.check_tvl_cap(args.amount)?;
+    
+    // args.amount is in share base units (SHARE_PRECISION per whole share).
+    // 1 whole complete set = 1 USDC, so collateral (e6) == amount.
+    let collateral = calculate_complete_set_collateral(args.amount)?;
+    
     let current_time = get_current_timestamp()?;
     
     // Calculate market PDA seeds for signing
     let market_id_bytes = market.market_id.to_le_bytes();
     let market_seeds: &[&[u8]] = &[MARKET_SEED, &market_id_bytes, &[market.bump]];
     
-    // NOTE: Fee collection will be implemented in Vault Program layer (V2 architecture)
-    // This V1 instruction does not collect fees
+    // The minting fee is paid on top of the collateral, so the vault still
+    // holds exactly 1 USDC per set
+    let mint_fee = config.mint_fee_on(collateral)?;
+    if mint_fee > 0 {
+        let fund_usdc_info = fund_usdc_info.ok_or_else(|| {
+            msg!("Error: Fund USDC account required for a {} mint fee", mint_fee);
+            PredictionMarketError::MissingFundAccount
+        })?;
+        let (fund_mint, fund_owner) = get_token_mint_and_owner(fund_usdc_info)?;
+        if fund_mint != config.usdc_mint || fund_owner != config.fund_program {
+            msg!("Error: Fee account must be a USDC account of the Fund Program");
+            return Err(PredictionMarketError::InvalidTokenAccount.into());
+        }
+        
+        msg!("Mint fee: {} ({} bps)", mint_fee, config.mint_fee_bps);
+        transfer_usdc(
+            token_program_info,
+            user_usdc_info,
+            usdc_mint_info,
+            fund_usdc_info,
+            user_info,
+            mint_fee,
+            None,
+        )?;
+    }
+    
+    let vault_balance_before = get_token_balance(market_vault_info)?;
     
     // Transfer USDC from user to market vault - 使用 token_compat 支持 Token-2022
-    token_compat::transfer(
+    // With a transfer fee the user sends enough for the vault to net the collateral
+    let deposit = match usdc_mint_info {
+        Some(usdc_mint_info) => token_compat::get_gross_amount(usdc_mint_info, collateral)?,
+        None => collateral,
+    };
+    transfer_usdc(
         token_program_info,
         user_usdc_info,
+        usdc_mint_info,
         market_vault_info,
         user_info,
-        args.amount,
+        deposit,
         None, // 用户签名，不需要 PDA seeds
     )?;
     
-    // Mint YES tokens to user (Outcome Token 使用 Token-v1)
-    invoke_signed(
-        &spl_token::instruction::mint_to(
-            token_program_info.key,
-            yes_mint_info.key,
-            user_yes_info.key,
-            market_info.key, // mint authority
-            &[],
-            args.amount,
-        )?,
-        &[yes_mint_info.clone(), user_yes_info.clone(), market_info.clone(), token_program_info.clone()],
-        &[market_seeds],
+    // Verify the vault actually received the full collateral
+    // (guards against transfer-fee mints under-collateralizing the sets)
+    let vault_balance_after = get_token_balance(market_vault_info)?;
+    let received = vault_balance_after.saturating_sub(vault_balance_before);
+    if received != collateral {
+        msg!("Error: Vault received {} USDC, expected {} for {} sets", 
+             received, collateral, args.amount);
+        return Err(PredictionMarketError::CollateralMismatch.into());
+    }
+    
+    // First-time holders may pass their not-yet-created YES/NO ATAs
+    for (token_account_info, mint_info) in [
+        (user_yes_info, yes_mint_info),
+        (user_no_info, no_mint_info),
+    ] {
+        if !token_account_info.data_is_empty() {
+            continue;
+        }
+        let ata_program_info = ata_program_info.ok_or_else(|| {
+            msg!("Error: Associated Token Account Program required to create {}", token_account_info.key);
+            ProgramError::NotEnoughAccountKeys
+        })?;
+        msg!("Creating associated token account {}", token_account_info.key);
+        token_compat::create_associated_token_account(
+            user_info,
+            user_info,
+            token_account_info,
+            mint_info,
+            system_program_info,
+            token_program_info,
+            ata_program_info,
+        )?;
+    }
+    
+    // Mint YES tokens to user (mint authority = Market PDA)
+    token_compat::mint_to(
+        token_program_info,
+        yes_mint_info,
+        user_yes_info,
+        market_info,
+        args.amount,
+        Some(market_seeds),
     )?;
     
     // Mint NO tokens to user
-    invoke_signed(
-        &spl_token::instruction::mint_to(
-            token_program_info.key,
-            no_mint_info.key,
-            user_no_info.key,
-            market_info.key, // mint authority
-            &[],
-            args.amount,
-        )?,
-        &[no_mint_info.clone(), user_no_info.clone(), market_info.clone(), token_program_info.clone()],
-        &[market_seeds],
+    token_compat::mint_to(
+        token_program_info,
+        no_mint_info,
+        user_no_info,
+        market_info,
+        args.amount,
+        Some(market_seeds),
     )?;
     
     // Load or create Position
@@ -1701,8 +2219,8 @@ fn process_mint_complete_set(
     
     // For complete set, cost is at $0.50 each (1 USDC total for YES + NO)
     let half_price = PRICE_PRECISION / 2; // 500_000
-    position.add_tokens(crate::state::Outcome::Yes, args.amount, half_price, current_time);
-    position.add_tokens(crate::state::Outcome::No, args.amount, half_price, current_time);
+    position.add_tokens(crate::state::Outcome::Yes, args.amount, half_price, current_time)?;
+    position.add_tokens(crate::state::Outcome::No, args.amount, half_price, current_time)?;
     
     // Serialize position back to account
     let mut position_data = position_info.try_borrow_mut_data()?;
@@ -1711,12 +2229,13 @@ fn process_mint_complete_set(
     
     // Update market stats
     market.total_minted += args.amount;
+    market.increase_open_interest(args.amount)?;
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     // Update config stats
     config.total_minted_sets += args.amount;
-    config.serialize(&mut *config_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     msg!("Minted complete set successfully");
     msg!("Amount: {} (YES + NO)", args.amount);
@@ -1767,11 +2286,18 @@ fn process_redeem_complete_set(
     // Account 10: Token Program
     let token_program_info = next_account_info(account_info_iter)?;
     
+    // Account 11 (optional): USDC Mint (Token-2022 transfer-fee mints)
+    let usdc_mint_info = next_account_info(account_info_iter).ok();
+    
+    // Account 12 (optional): Fund Program's USDC Account (redeem fee)
+    let fund_usdc_info = next_account_info(account_info_iter).ok();
+    
     // Load and validate config
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
+    let config_extension = ConfigExtension::load(&config_info.data.borrow());
     
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
@@ -1784,9 +2310,16 @@ fn process_redeem_complete_set(
     }
     
     // Verify market is tradeable
-    if !market.is_tradeable() {
-        msg!("Error: Market is not tradeable");
-        return Err(PredictionMarketError::MarketNotTradeable.into());
+    if let Err(e) = market.check_tradeable() {
+        msg!("Error: Market is not tradeable ({:?})", market.status);
+        return Err(e.into());
+    }
+    
+    verify_token_program(token_program_info.key)?;
+    if let Some(usdc_mint_info) = usdc_mint_info {
+        if *usdc_mint_info.key != config.usdc_mint {
+            return Err(PredictionMarketError::InvalidUSDCMint.into());
+        }
     }
     
     // Verify addresses
@@ -1833,54 +2366,59 @@ fn process_redeem_complete_set(
     let market_seeds: &[&[u8]] = &[MARKET_SEED, &market_id_bytes, &[market.bump]];
     
     // Burn YES tokens from user
-    invoke(
-        &spl_token::instruction::burn(
-            token_program_info.key,
-            user_yes_info.key,
-            yes_mint_info.key,
-            user_info.key,
-            &[],
-            args.amount,
-        )?,
-        &[user_yes_info.clone(), yes_mint_info.clone(), user_info.clone(), token_program_info.clone()],
-    )?;
+    token_compat::burn(token_program_info, user_yes_info, yes_mint_info, user_info, args.amount, None)?;
     
     // Burn NO tokens from user
-    invoke(
-        &spl_token::instruction::burn(
-            token_program_info.key,
-            user_no_info.key,
-            no_mint_info.key,
-            user_info.key,
-            &[],
-            args.amount,
-        )?,
-        &[user_no_info.clone(), no_mint_info.clone(), user_info.clone(), token_program_info.clone()],
-    )?;
-    
-    // NOTE: Fee collection will be implemented in Vault Program layer (V2 architecture)
-    // This V1 instruction does not collect fees
+    token_compat::burn(token_program_info, user_no_info, no_mint_info, user_info, args.amount, None)?;
+    
+    // The redemption fee is withheld from the payout, so the vault still
+    // releases exactly 1 USDC per set
+    let redeem_fee = config_extension.redeem_fee_on(args.amount)?;
+    if redeem_fee > 0 {
+        let fund_usdc_info = fund_usdc_info.ok_or_else(|| {
+            msg!("Error: Fund USDC account required for a {} redeem fee", redeem_fee);
+            PredictionMarketError::MissingFundAccount
+        })?;
+        let (fund_mint, fund_owner) = get_token_mint_and_owner(fund_usdc_info)?;
+        if fund_mint != config.usdc_mint || fund_owner != config.fund_program {
+            msg!("Error: Fee account must be a USDC account of the Fund Program");
+            return Err(PredictionMarketError::InvalidTokenAccount.into());
+        }
+        
+        msg!("Redeem fee: {} ({} bps)", redeem_fee, config_extension.redeem_fee_bps);
+        transfer_usdc(
+            token_program_info,
+            market_vault_info,
+            usdc_mint_info,
+            fund_usdc_info,
+            market_info,
+            redeem_fee,
+            Some(market_seeds),
+        )?;
+    }
     
     // Transfer USDC from market vault to user - 使用 token_compat 支持 Token-2022
-    token_compat::transfer(
+    // Any transfer fee comes out of what the user receives
+    transfer_usdc(
         token_program_info,
         market_vault_info,
+        usdc_mint_info,
         user_usdc_info,
         market_info,
-        args.amount,
+        args.amount - redeem_fee,
         Some(market_seeds),
     )?;
     
-    // Update position
-    let half_price = PRICE_PRECISION / 2;
-    position.remove_tokens(crate::state::Outcome::Yes, args.amount, half_price, current_time);
-    position.remove_tokens(crate::state::Outcome::No, args.amount, half_price, current_time);
-    position.serialize(&mut *position_info.data.borrow_mut())?;
+    // Update position; the set redeems at par against its YES + NO cost basis
+    let pnl = position.redeem_complete_set(args.amount, current_time);
+    position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
     
     // Update market stats
+    market.record_realized_pnl(0, pnl)?;
     market.total_minted = market.total_minted.saturating_sub(args.amount);
+    market.decrease_open_interest(args.amount);
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     msg!("Redeemed complete set successfully");
     msg!("Amount: {}", args.amount);
@@ -1948,9 +2486,9 @@ fn process_place_order(
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if !market.is_tradeable() {
-        msg!("Error: Market is not tradeable");
-        return Err(PredictionMarketError::MarketNotTradeable.into());
+    if let Err(e) = market.check_tradeable() {
+        msg!("Error: Market is not tradeable ({:?})", market.status);
+        return Err(e.into());
     }
     
     // Validate order parameters
@@ -1960,6 +2498,15 @@ fn process_place_order(
         return Err(PredictionMarketError::InvalidAmount.into());
     }
     
+    config.check_order_minimum(args.amount, args.price)?;
+    
+    // A reduce-only Sell is covered by the tokens it escrows, but a Buy has
+    // no position here to show the opposite-side holdings it would unwind
+    if args.reduce_only && !is_sell_order {
+        msg!("Error: Reduce-only Buy orders require RelayerPlaceOrderV2");
+        return Err(PredictionMarketError::ReduceOnlyViolation.into());
+    }
+    
     let current_time = get_current_timestamp()?;
     
     // Validate expiration for GTD orders
@@ -1993,6 +2540,10 @@ fn process_place_order(
         crate::state::OrderType::GTC => {
             msg!("📝 GTC order: Good till cancelled");
         }
+        crate::state::OrderType::Market | crate::state::OrderType::PostOnly => {
+            msg!("Error: {:?} orders must be placed through RelayerPlaceOrderV2", args.order_type);
+            return Err(PredictionMarketError::InvalidOrderType.into());
+        }
     }
     
     // Allocate order_id
@@ -2035,6 +2586,8 @@ fn process_place_order(
         let token_program_info = next_account_info(account_info_iter)?;
         let rent_sysvar_info = next_account_info(account_info_iter)?;
         
+        verify_token_program(token_program_info.key)?;
+        
         // Verify token mint matches the outcome
         let expected_mint = match args.outcome {
             crate::state::Outcome::Yes => market.yes_mint,
@@ -2068,7 +2621,7 @@ fn process_place_order(
                 escrow_token_info.key,
                 lamports,
                 space as u64,
-                &spl_token::id(),
+                token_program_info.key,
             ),
             &[user_info.clone(), escrow_token_info.clone(), system_program_info.clone()],
             &[escrow_seeds],
@@ -2076,7 +2629,7 @@ fn process_place_order(
         
         // Initialize the escrow token account with order PDA as owner
         invoke(
-            &spl_token::instruction::initialize_account3(
+            &spl_token_2022::instruction::initialize_account3(
                 token_program_info.key,
                 escrow_token_info.key,
                 token_mint_info.key,
@@ -2087,12 +2640,11 @@ fn process_place_order(
         
         // Transfer tokens from user to escrow
         invoke(
-            &spl_token::instruction::transfer(
+            &token_compat::create_transfer_instruction(
                 token_program_info.key,
                 user_token_info.key,
                 escrow_token_info.key,
                 user_info.key,
-                &[],
                 args.amount,
             )?,
             &[user_token_info.clone(), escrow_token_info.clone(), user_info.clone(), token_program_info.clone()],
@@ -2129,7 +2681,11 @@ fn process_place_order(
         updated_at: current_time,
         bump: order_bump,
         escrow_token_account,
-        reserved: [0u8; 30],
+        locks_released: false,
+        margin_locked: false,
+        locked_margin_e6: 0,
+        reduce_only: args.reduce_only,
+        reserved: [0u8; 19],
     };
     
     order.serialize(&mut *order_info.data.borrow_mut())?;
@@ -2197,8 +2753,8 @@ fn process_cancel_order(
         return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Verify order is active
-    if !order.is_active() {
+    // Verify order is active (or expired with funds still locked)
+    if !order.is_cancellable() {
         msg!("Error: Order is not active");
         return Err(PredictionMarketError::OrderNotActive.into());
     }
@@ -2215,6 +2771,7 @@ fn process_cancel_order(
         let user_token_info = next_account_info(account_info_iter)?;
         let escrow_token_info = next_account_info(account_info_iter)?;
         let token_program_info = next_account_info(account_info_iter)?;
+        verify_token_program(token_program_info.key)?;
         
         // Verify escrow PDA
         let (escrow_pda, _) = Pubkey::find_program_address(
@@ -2230,12 +2787,11 @@ fn process_cancel_order(
         
         if remaining_amount > 0 {
             invoke_signed(
-                &spl_token::instruction::transfer(
+                &token_compat::create_transfer_instruction(
                     token_program_info.key,
                     escrow_token_info.key,
                     user_token_info.key,
                     order_info.key, // Order PDA is the owner
-                    &[],
                     remaining_amount,
                 )?,
                 &[escrow_token_info.clone(), user_token_info.clone(), order_info.clone(), token_program_info.clone()],
@@ -2247,7 +2803,7 @@ fn process_cancel_order(
         
         // Close escrow account and return lamports to user
         invoke_signed(
-            &spl_token::instruction::close_account(
+            &spl_token_2022::instruction::close_account(
                 token_program_info.key,
                 escrow_token_info.key,
                 user_info.key,
@@ -2316,7 +2872,9 @@ fn process_relayer_mint_complete_set_v2(
     }
     
     // Verify Relayer authority
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
     
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
@@ -2332,15 +2890,18 @@ fn process_relayer_mint_complete_set_v2(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    market.check_tradeable()?;
     
     // Validate amount
     if args.amount == 0 {
         return Err(PredictionMarketError::InvalidAmount.into());
     }
     
+    market.check_tvl_cap(args.amount)?;
+    
+    // The user pays exactly the locked amount; no mint fee is charged here
+    validate_max_cost(args.amount, args.max_cost_e6)?;
+    
     let current_time = get_current_timestamp()?;
     let market_id_bytes = market.market_id.to_le_bytes();
     
@@ -2359,7 +2920,7 @@ fn process_relayer_mint_complete_set_v2(
     // Step 1: CPI to Vault - PredictionMarketLock
     // Also passes relayer (payer) and system_program for auto-init of PMUserAccount
     msg!("CPI: Vault.PredictionMarketLock amount={}", args.amount);
-    cpi_lock_for_prediction(
+    let amount = cpi_lock_for_prediction(
         vault_program_info,
         vault_config_info,
         user_vault_info,
@@ -2371,6 +2932,11 @@ fn process_relayer_mint_complete_set_v2(
         config_seeds,
     )?;
     
+    // The Vault may clamp to the available balance; mint only what it locked
+    if amount == 0 {
+        return Err(PredictionMarketError::InsufficientFunds.into());
+    }
+    
     // Step 2: Create or update Position PDA
     let (position_pda, position_bump) = Pubkey::find_program_address(
         &[POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
@@ -2421,16 +2987,22 @@ fn process_relayer_mint_complete_set_v2(
         if pos.discriminator != POSITION_DISCRIMINATOR {
             return Err(PredictionMarketError::InvalidAccountData.into());
         }
+        // The PDA already binds the account to this market; the stored
+        // market_id must agree with it
+        if pos.market_id != args.market_id {
+            msg!("Error: Position belongs to market {}, not {}", pos.market_id, args.market_id);
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
         pos
     };
     
     // MintCompleteSet: add same amount to both YES and NO
     // avg_cost = 500_000 (0.5 USDC) because 1 USDC = 1 YES + 1 NO
-    position.yes_amount = safe_add_u64(position.yes_amount, args.amount)?;
-    position.no_amount = safe_add_u64(position.no_amount, args.amount)?;
+    position.yes_amount = safe_add_u64(position.yes_amount, amount)?;
+    position.no_amount = safe_add_u64(position.no_amount, amount)?;
     position.yes_avg_cost = 500_000;  // 0.5 USDC per token
     position.no_avg_cost = 500_000;   // 0.5 USDC per token
-    position.total_cost_e6 = safe_add_u64(position.total_cost_e6, args.amount)?;  // Total USDC spent
+    position.total_cost_e6 = safe_add_u64(position.total_cost_e6, amount)?;  // Total USDC spent
     position.updated_at = current_time;
     
     // Serialize directly to the account data slice
@@ -2438,16 +3010,22 @@ fn process_relayer_mint_complete_set_v2(
     drop(position_data); // Release mutable borrow
     
     // Step 3: Update Market
-    market.total_minted = safe_add_u64(market.total_minted, args.amount)?;
+    market.total_minted = safe_add_u64(market.total_minted, amount)?;
+    market.increase_open_interest(amount)?;
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     msg!("✅ RelayerMintCompleteSetV2 completed");
     msg!("User: {}", args.user_wallet);
-    msg!("Amount: {}", args.amount);
+    msg!("Amount: {}", amount);
     msg!("Position YES: {}, NO: {}", position.yes_amount, position.no_amount);
     msg!("Total Minted: {}", market.total_minted);
-    msg!("complete_set_minted:{},{},{},{}", args.market_id, args.user_wallet, args.amount, args.amount);
+    msg!("complete_set_minted:{},{},{},{}", args.market_id, args.user_wallet, amount, amount);
+    PredictionMarketEvent::CompleteSetMinted(events::CompleteSetMinted {
+        market_id: args.market_id,
+        owner: args.user_wallet,
+        amount,
+    }).emit()?;
     
     Ok(())
 }
@@ -2498,7 +3076,9 @@ fn process_relayer_redeem_complete_set_v2(
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
     
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
@@ -2570,19 +3150,20 @@ fn process_relayer_redeem_complete_set_v2(
     )?;
     
     // Step 2: Update Position - reduce YES and NO amounts + total_cost
-    position.yes_amount = position.yes_amount.saturating_sub(args.amount);
-    position.no_amount = position.no_amount.saturating_sub(args.amount);
+    let pnl = position.redeem_complete_set(args.amount, current_time);
+    market.record_realized_pnl(0, pnl)?;
     // Reduce total_cost to match reduced position (Bug #5 fix).
     // Redeem returns 1:1 USDC, so cost reduction = args.amount.
     position.total_cost_e6 = position.total_cost_e6.saturating_sub(args.amount);
     position.updated_at = current_time;
     
-    position.serialize(&mut *position_info.data.borrow_mut())?;
+    position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
     
     // Step 3: Update Market
     market.total_minted = market.total_minted.saturating_sub(args.amount);
+    market.decrease_open_interest(args.amount);
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     msg!("✅ RelayerRedeemCompleteSetV2 completed");
     msg!("User: {}", args.user_wallet);
@@ -2649,17 +3230,24 @@ fn process_match_mint_v2(
     let system_program_info = next_account_info(account_info_iter)?;
     
     // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
     
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
+    if !config.allow_match_mint() {
+        msg!("Error: Match-minting is disabled, use RelayerMintCompleteSetV2");
+        return Err(PredictionMarketError::MatchMintDisabled.into());
+    }
+    
     // Load and validate market
     let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
@@ -2670,19 +3258,19 @@ fn process_match_mint_v2(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    market.check_tradeable()?;
     
-    // Validate price pair for minting: yes_price + no_price == 1.0 (exactly 100¢)
-    // This ensures perfect fund balance: $1 locked = $1 settlement
-    // - < 100¢ would cause fund shortage at settlement
+    // Validate price pair for minting: 1.0 - dev <= yes_price + no_price <= 1.0
+    // Exactly 100¢ keeps perfect fund balance ($1 locked = $1 settlement);
+    // anything below is a settlement shortfall bounded by max_price_sum_deviation.
     // - > 100¢ would require complex excess fund handling
-    if args.yes_price + args.no_price != PRICE_PRECISION {
-        msg!("Price sum {} + {} != 1.0, not valid for minting (must be exactly 100¢)", 
-             args.yes_price, args.no_price);
-        return Err(PredictionMarketError::InvalidPricePair.into());
-    }
+    // Each leg must also lie within [MIN_PRICE, MAX_PRICE]
+    validate_price(args.yes_price)?;
+    validate_price(args.no_price)?;
+    validate_mint_price_sum(
+        args.yes_price.saturating_add(args.no_price),
+        config.max_price_sum_deviation,
+    )?;
     
     let current_time = get_current_timestamp()?;
     
@@ -2694,6 +3282,10 @@ fn process_match_mint_v2(
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
+    // Verify both orders belong to this market
+    verify_order_account(program_id, yes_order_info, &yes_order, args.market_id, args.yes_order_id)?;
+    verify_order_account(program_id, no_order_info, &no_order, args.market_id, args.no_order_id)?;
+    
     // Verify orders are Buy orders
     if yes_order.side != crate::state::OrderSide::Buy || no_order.side != crate::state::OrderSide::Buy {
         return Err(PredictionMarketError::InvalidOrderSide.into());
@@ -2709,6 +3301,13 @@ fn process_match_mint_v2(
         return Err(PredictionMarketError::OrderNotActive.into());
     }
     
+    // Buyers are never charged above their limit price
+    if args.yes_price > yes_order.price || args.no_price > no_order.price {
+        msg!("Error: Match prices {}/{} exceed buy limits {}/{}",
+             args.yes_price, args.no_price, yes_order.price, no_order.price);
+        return Err(PredictionMarketError::PriceMismatch.into());
+    }
+    
     // Calculate match amount
     let yes_remaining = yes_order.remaining_amount();
     let no_remaining = no_order.remaining_amount();
@@ -2718,9 +3317,20 @@ fn process_match_mint_v2(
         return Err(PredictionMarketError::NoMatchableAmount.into());
     }
     
+    market.check_tvl_cap(match_amount)?;
+    
+    // FOK orders must be filled completely by this match
+    yes_order.check_fill_or_kill(match_amount)?;
+    no_order.check_fill_or_kill(match_amount)?;
+    
+    // Record the fills; an IOC order's unfilled remainder (or any dust below
+    // min_order_size) is cancelled and its margin released below
+    let yes_cancelled = yes_order.record_fill(match_amount, config.min_order_size, current_time)?;
+    let no_cancelled = no_order.record_fill(match_amount, config.min_order_size, current_time)?;
+    
     // Calculate costs
-    let yes_cost = (match_amount as u128 * args.yes_price as u128 / PRICE_PRECISION as u128) as u64;
-    let no_cost = (match_amount as u128 * args.no_price as u128 / PRICE_PRECISION as u128) as u64;
+    let yes_cost = mul_div_floor(match_amount, args.yes_price, PRICE_PRECISION)?;
+    let no_cost = mul_div_floor(match_amount, args.no_price, PRICE_PRECISION)?;
     
     // Derive Config PDA for CPI signing
     let (config_pda, config_bump) = Pubkey::find_program_address(
@@ -2734,45 +3344,102 @@ fn process_match_mint_v2(
     
     let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
-    // Step 1: Settle YES buyer — consume PlaceOrder's locked margin (NOT Lock!)
-    // PlaceOrder already locked the margin via cpi_lock. MatchMint consumes it
-    // via Settle to avoid double-locking. settled_cost_e6 is updated in Step 3.
-    msg!("CPI: Settle YES buyer - consume {} from PlaceOrder margin", yes_cost);
-    cpi_prediction_settle(
-        vault_program_info,
-        vault_config_info,
-        yes_pm_user_info,
-        config_info,
-        yes_cost,           // locked_amount: consume from pm_locked
-        0,                  // settlement_amount: buyer gets no pending (shares instead)
-        config_seeds,
-    )?;
+    // Step 1: Settle both buyers from their locked margin
+    // RelayerPlaceOrderV2 already locked the margin (margin_locked), so the
+    // match consumes it via Settle instead of double-locking. Orders placed
+    // without a Vault lock are funded now, then settled the same way.
+    // settled_cost_e6 is updated in Step 3.
+    for (label, order, cost, vault_info, pm_user_info) in [
+        ("YES", &yes_order, yes_cost, yes_vault_info, yes_pm_user_info),
+        ("NO", &no_order, no_cost, no_vault_info, no_pm_user_info),
+    ] {
+        if !order.margin_locked {
+            msg!("CPI: Lock {} for {} buyer - order {} was not pre-locked", cost, label, order.order_id);
+            let locked = cpi_lock_for_prediction(
+                vault_program_info,
+                vault_config_info,
+                vault_info,
+                pm_user_info,
+                config_info,
+                relayer_info,
+                system_program_info,
+                cost,
+                config_seeds,
+            )?;
+            if locked != cost {
+                msg!("Error: {} buyer could only lock {} of the {} cost", label, locked, cost);
+                return Err(PredictionMarketError::InsufficientFunds.into());
+            }
+        }
+        
+        msg!("CPI: Settle {} buyer - consume {} from locked margin", label, cost);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            pm_user_info,
+            config_info,
+            cost,               // locked_amount: consume from pm_locked
+            0,                  // settlement_amount: buyer gets no pending (shares instead)
+            config_seeds,
+        )?;
+    }
     
-    // Step 2: Settle NO buyer — consume PlaceOrder's locked margin
-    msg!("CPI: Settle NO buyer - consume {} from PlaceOrder margin", no_cost);
-    cpi_prediction_settle(
-        vault_program_info,
-        vault_config_info,
-        no_pm_user_info,
-        config_info,
-        no_cost,            // locked_amount: consume from pm_locked
-        0,                  // settlement_amount: buyer gets no pending
-        config_seeds,
-    )?;
+    // Step 2: Return pre-locked margin the fill did not consume: the part
+    // above the match price, rounding, and cancelled IOC or dust remainders
+    for (order, cost, cancelled, vault_info, pm_user_info) in [
+        (&mut yes_order, yes_cost, yes_cancelled, yes_vault_info, yes_pm_user_info),
+        (&mut no_order, no_cost, no_cancelled, no_vault_info, no_pm_user_info),
+    ] {
+        let margin = if order.margin_locked {
+            order.settle_locked_margin(match_amount, cost, config.trade_fee_bps)?
+        } else {
+            0
+        };
+        if margin > 0 {
+            msg!("CPI: Release unused margin {} for order {} (IOC/dust remainder {})", margin, order.order_id, cancelled);
+            cpi_release_from_prediction(
+                vault_program_info,
+                vault_config_info,
+                vault_info,
+                pm_user_info,
+                config_info,
+                margin,
+                config_seeds,
+            )?;
+        }
+    }
     
     // Step 3: Create or update YES buyer position (Auto-init if needed)
     let market_id_bytes = args.market_id.to_le_bytes();
     let yes_buyer = yes_order.owner;
+    let no_buyer = no_order.owner;
     let (yes_position_pda, yes_position_bump) = Pubkey::find_program_address(
         &[POSITION_SEED, &market_id_bytes, yes_buyer.as_ref()],
         program_id,
     );
+    let (no_position_pda, no_position_bump) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market_id_bytes, no_buyer.as_ref()],
+        program_id,
+    );
     
     if *yes_position_info.key != yes_position_pda {
         msg!("Error: Invalid YES Position PDA");
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
+    if *no_position_info.key != no_position_pda {
+        msg!("Error: Invalid NO Position PDA");
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Self-mint: the same wallet bought both sides, so both position accounts
+    // are the same PDA. Credit YES and NO to that single position in one
+    // load/serialize instead of loading it twice.
+    let self_mint = yes_buyer == no_buyer;
+    if self_mint {
+        msg!("Self-mint: YES and NO buyer are the same wallet {}", yes_buyer);
+    }
+    
     let yes_is_new = yes_position_info.data_is_empty();
     
     if yes_is_new {
@@ -2813,25 +3480,19 @@ fn process_match_mint_v2(
             }
             pos
         };
-        yes_position.add_tokens(Outcome::Yes, match_amount, args.yes_price, current_time);
+        yes_position.add_tokens(Outcome::Yes, match_amount, args.yes_price, current_time)?;
         // Track that this cost was already settled from pm_locked (Step 1 CPI)
         yes_position.settled_cost_e6 = yes_position.settled_cost_e6.saturating_add(yes_cost);
+        if self_mint {
+            yes_position.add_tokens(Outcome::No, match_amount, args.no_price, current_time)?;
+            yes_position.settled_cost_e6 = yes_position.settled_cost_e6.saturating_add(no_cost);
+        }
         yes_position.serialize(&mut yes_position_data.as_mut())?;
     }
     
     // Step 4: Create or update NO buyer position (Auto-init if needed)
-    let no_buyer = no_order.owner;
-    let (no_position_pda, no_position_bump) = Pubkey::find_program_address(
-        &[POSITION_SEED, &market_id_bytes, no_buyer.as_ref()],
-        program_id,
-    );
-    
-    if *no_position_info.key != no_position_pda {
-        msg!("Error: Invalid NO Position PDA");
-        return Err(PredictionMarketError::InvalidPDA.into());
-    }
-    
-    let no_is_new = no_position_info.data_is_empty();
+    // Skipped for self-mint: the NO side was credited in Step 3.
+    let no_is_new = !self_mint && no_position_info.data_is_empty();
     
     if no_is_new {
         // Create new NO Position account
@@ -2860,7 +3521,7 @@ fn process_match_mint_v2(
     }
     
     // Update NO position
-    {
+    if !self_mint {
         let mut no_position_data = no_position_info.try_borrow_mut_data()?;
         let mut no_position = if no_is_new {
             Position::new(market.market_id, no_buyer, no_position_bump, current_time)
@@ -2871,41 +3532,34 @@ fn process_match_mint_v2(
             }
             pos
         };
-        no_position.add_tokens(Outcome::No, match_amount, args.no_price, current_time);
+        no_position.add_tokens(Outcome::No, match_amount, args.no_price, current_time)?;
         // Track that this cost was already settled from pm_locked (Step 2 CPI)
         no_position.settled_cost_e6 = no_position.settled_cost_e6.saturating_add(no_cost);
         no_position.serialize(&mut no_position_data.as_mut())?;
     }
     
-    // Step 5: Update orders
-    yes_order.filled_amount = safe_add_u64(yes_order.filled_amount, match_amount)?;
-    if yes_order.filled_amount >= yes_order.amount {
-        yes_order.status = OrderStatus::Filled;
-    } else {
-        yes_order.status = OrderStatus::PartialFilled;
-    }
-    yes_order.updated_at = current_time;
-    yes_order.serialize(&mut *yes_order_info.data.borrow_mut())?;
-    
-    no_order.filled_amount = safe_add_u64(no_order.filled_amount, match_amount)?;
-    if no_order.filled_amount >= no_order.amount {
-        no_order.status = OrderStatus::Filled;
-    } else {
-        no_order.status = OrderStatus::PartialFilled;
-    }
-    no_order.updated_at = current_time;
-    no_order.serialize(&mut *no_order_info.data.borrow_mut())?;
+    // Step 5: Update orders (fills were recorded above)
+    yes_order.serialize(&mut &mut yes_order_info.data.borrow_mut()[..])?;
+    no_order.serialize(&mut &mut no_order_info.data.borrow_mut()[..])?;
     
     // Step 6: Update market
     market.total_minted = safe_add_u64(market.total_minted, match_amount)?;
-    market.total_volume_e6 = market.total_volume_e6.saturating_add((yes_cost + no_cost) as i64);
+    market.increase_open_interest(match_amount)?;
+    accumulate_volume(&mut market, &mut config, safe_add_u64(yes_cost, no_cost)?);
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     msg!("✅ MatchMintV2 completed");
     msg!("Amount: {}", match_amount);
     msg!("YES cost: {}, NO cost: {}", yes_cost, no_cost);
     msg!("Total Minted: {}", market.total_minted);
+    for (order, cancelled) in [(&yes_order, yes_cancelled), (&no_order, no_cancelled)] {
+        if cancelled > 0 {
+            msg!("order_cancelled:{},{}", args.market_id, order.order_id);
+            PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: order.order_id }).emit()?;
+        }
+    }
     
     Ok(())
 }
@@ -2962,12 +3616,14 @@ fn process_match_burn_v2(
     let vault_program_info = next_account_info(account_info_iter)?;
     
     // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
     
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
@@ -2983,11 +3639,16 @@ fn process_match_burn_v2(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    // Validate price pair for burning: yes_price + no_price >= 1.0
-    if args.yes_price + args.no_price < PRICE_PRECISION {
-        msg!("Price sum {} + {} < 1.0, not valid for burning", args.yes_price, args.no_price);
-        return Err(PredictionMarketError::InvalidPricePair.into());
-    }
+    // Validate price pair for burning: 1.0 <= yes_price + no_price <= 1.0 + dev
+    // Each leg must also lie within [MIN_PRICE, MAX_PRICE]
+    validate_price(args.yes_price)?;
+    validate_price(args.no_price)?;
+    validate_burn_price_sum(
+        args.yes_price.saturating_add(args.no_price),
+        config.max_price_sum_deviation,
+    )?;
+    // Sellers are paid at most par for a burned set; see par_burn_prices
+    let (yes_price, no_price) = par_burn_prices(args.yes_price, args.no_price)?;
     
     let current_time = get_current_timestamp()?;
     
@@ -2995,6 +3656,14 @@ fn process_match_burn_v2(
     let mut yes_order = deserialize_account::<Order>(&yes_order_info.data.borrow())?;
     let mut no_order = deserialize_account::<Order>(&no_order_info.data.borrow())?;
     
+    if yes_order.discriminator != ORDER_DISCRIMINATOR || no_order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify both orders belong to this market
+    verify_order_account(program_id, yes_order_info, &yes_order, args.market_id, args.yes_order_id)?;
+    verify_order_account(program_id, no_order_info, &no_order, args.market_id, args.no_order_id)?;
+    
     // Verify orders are Sell orders
     if yes_order.side != crate::state::OrderSide::Sell || no_order.side != crate::state::OrderSide::Sell {
         return Err(PredictionMarketError::InvalidOrderSide.into());
@@ -3010,6 +3679,13 @@ fn process_match_burn_v2(
         return Err(PredictionMarketError::OrderNotActive.into());
     }
     
+    // Sellers never receive less than their limit price
+    if yes_price < yes_order.price || no_price < no_order.price {
+        msg!("Error: Match prices {}/{} below sell limits {}/{}",
+             yes_price, no_price, yes_order.price, no_order.price);
+        return Err(PredictionMarketError::PriceMismatch.into());
+    }
+    
     // Calculate match amount
     let yes_remaining = yes_order.remaining_amount();
     let no_remaining = no_order.remaining_amount();
@@ -3019,25 +3695,85 @@ fn process_match_burn_v2(
         return Err(PredictionMarketError::NoMatchableAmount.into());
     }
     
-    // Calculate proceeds
-    let yes_proceeds = (match_amount as u128 * args.yes_price as u128 / PRICE_PRECISION as u128) as u64;
-    let no_proceeds = (match_amount as u128 * args.no_price as u128 / PRICE_PRECISION as u128) as u64;
+    // FOK orders must be filled completely by this match
+    yes_order.check_fill_or_kill(match_amount)?;
+    no_order.check_fill_or_kill(match_amount)?;
     
-    // Load positions
-    let mut yes_position = deserialize_account::<Position>(&yes_position_info.data.borrow())?;
-    let mut no_position = deserialize_account::<Position>(&no_position_info.data.borrow())?;
+    // Record the fills; an IOC order's unfilled remainder (or any dust below
+    // min_order_size) is cancelled and its locked shares returned below
+    let yes_cancelled = yes_order.record_fill(match_amount, config.min_order_size, current_time)?;
+    let no_cancelled = no_order.record_fill(match_amount, config.min_order_size, current_time)?;
+    
+    // Verify the sellers' Position PDAs and Vault accounts before any CPI
+    let market_id_bytes = args.market_id.to_le_bytes();
+    for (position_info, pm_user_info, owner) in [
+        (yes_position_info, yes_pm_user_info, &yes_order.owner),
+        (no_position_info, no_pm_user_info, &no_order.owner),
+    ] {
+        let (position_pda, _) = Pubkey::find_program_address(
+            &[POSITION_SEED, &market_id_bytes, owner.as_ref()],
+            program_id,
+        );
+        if *position_info.key != position_pda {
+            msg!("Error: Position {} does not belong to {}", position_info.key, owner);
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        verify_pm_user_account(pm_user_info, owner, vault_program_info.key)?;
+    }
     
-    // Verify sellers have sufficient LOCKED shares (locked when Sell order was placed)
+    // Step 1: Consume locked shares (unlock + remove); proceeds paid below are
+    // valued at the same price as the realized PnL. Each side is written
+    // before the other is loaded: when one wallet sold both sides, both
+    // position accounts are the same PDA and the NO side must see the YES
+    // update instead of overwriting it.
+    let mut yes_position = deserialize_account::<Position>(&yes_position_info.data.borrow())?;
+    if yes_position.discriminator != POSITION_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     if yes_position.yes_locked < match_amount {
         msg!("Error: YES seller has insufficient locked shares: {} < {}", 
              yes_position.yes_locked, match_amount);
         return Err(PredictionMarketError::InsufficientPosition.into());
     }
+    let yes_pnl_before = yes_position.realized_pnl;
+    let yes_proceeds = yes_position
+        .consume_locked_for_proceeds(Outcome::Yes, match_amount, yes_price, current_time)?;
+    market.record_realized_pnl(yes_pnl_before, yes_position.realized_pnl)?;
+    debug_assert_eq!(yes_proceeds, Position::sale_proceeds(match_amount, yes_price));
+    
+    // Return the locked shares of a cancelled IOC or dust remainder
+    yes_position.unlock_shares(Outcome::Yes, yes_cancelled).map_err(|_| {
+        msg!("Error: Failed to unlock IOC/dust remainder shares");
+        PredictionMarketError::InsufficientPosition
+    })?;
+    yes_position.serialize(&mut &mut yes_position_info.data.borrow_mut()[..])?;
+    
+    let mut no_position = deserialize_account::<Position>(&no_position_info.data.borrow())?;
+    if no_position.discriminator != POSITION_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     if no_position.no_locked < match_amount {
         msg!("Error: NO seller has insufficient locked shares: {} < {}", 
              no_position.no_locked, match_amount);
         return Err(PredictionMarketError::InsufficientPosition.into());
     }
+    let no_pnl_before = no_position.realized_pnl;
+    let no_proceeds = no_position
+        .consume_locked_for_proceeds(Outcome::No, match_amount, no_price, current_time)?;
+    market.record_realized_pnl(no_pnl_before, no_position.realized_pnl)?;
+    debug_assert_eq!(no_proceeds, Position::sale_proceeds(match_amount, no_price));
+    
+    no_position.unlock_shares(Outcome::No, no_cancelled).map_err(|_| {
+        msg!("Error: Failed to unlock IOC/dust remainder shares");
+        PredictionMarketError::InsufficientPosition
+    })?;
+    no_position.serialize(&mut &mut no_position_info.data.borrow_mut()[..])?;
+    
+    // Invariant: the burned sets back at most match_amount USDC of proceeds
+    if safe_add_u64(yes_proceeds, no_proceeds)? > match_amount {
+        msg!("Error: Burn proceeds {} + {} exceed par {}", yes_proceeds, no_proceeds, match_amount);
+        return Err(PredictionMarketError::InvalidPricePair.into());
+    }
     
     // Derive Config PDA for CPI signing
     let (config_pda, config_bump) = Pubkey::find_program_address(
@@ -3051,7 +3787,7 @@ fn process_match_burn_v2(
     
     let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
-    // Step 1: Release funds for YES seller
+    // Step 2: Release funds for YES seller
     msg!("CPI: Release {} for YES seller", yes_proceeds);
     cpi_release_from_prediction(
         vault_program_info,
@@ -3063,7 +3799,7 @@ fn process_match_burn_v2(
         config_seeds,
     )?;
     
-    // Step 2: Release funds for NO seller
+    // Step 3: Release funds for NO seller
     msg!("CPI: Release {} for NO seller", no_proceeds);
     cpi_release_from_prediction(
         vault_program_info,
@@ -3075,52 +3811,30 @@ fn process_match_burn_v2(
         config_seeds,
     )?;
     
-    // Step 3: Update positions - consume locked shares (unlock + remove)
-    yes_position.consume_locked_shares(Outcome::Yes, match_amount, args.yes_price, current_time)
-        .map_err(|_| {
-            msg!("Error: Failed to consume YES locked shares");
-            PredictionMarketError::InsufficientPosition
-        })?;
-    yes_position.serialize(&mut *yes_position_info.data.borrow_mut())?;
-    
-    no_position.consume_locked_shares(Outcome::No, match_amount, args.no_price, current_time)
-        .map_err(|_| {
-            msg!("Error: Failed to consume NO locked shares");
-            PredictionMarketError::InsufficientPosition
-        })?;
-    no_position.serialize(&mut *no_position_info.data.borrow_mut())?;
-    
     msg!("📊 Burned {} complete sets (YES + NO)", match_amount);
     
-    // Step 4: Update orders
-    yes_order.filled_amount = safe_add_u64(yes_order.filled_amount, match_amount)?;
-    if yes_order.filled_amount >= yes_order.amount {
-        yes_order.status = OrderStatus::Filled;
-    } else {
-        yes_order.status = OrderStatus::PartialFilled;
-    }
-    yes_order.updated_at = current_time;
-    yes_order.serialize(&mut *yes_order_info.data.borrow_mut())?;
-    
-    no_order.filled_amount = safe_add_u64(no_order.filled_amount, match_amount)?;
-    if no_order.filled_amount >= no_order.amount {
-        no_order.status = OrderStatus::Filled;
-    } else {
-        no_order.status = OrderStatus::PartialFilled;
-    }
-    no_order.updated_at = current_time;
-    no_order.serialize(&mut *no_order_info.data.borrow_mut())?;
+    // Step 4: Update orders (fills were recorded above)
+    yes_order.serialize(&mut &mut yes_order_info.data.borrow_mut()[..])?;
+    no_order.serialize(&mut &mut no_order_info.data.borrow_mut()[..])?;
     
     // Step 5: Update market
     market.total_minted = market.total_minted.saturating_sub(match_amount);
-    market.total_volume_e6 = market.total_volume_e6.saturating_add((yes_proceeds + no_proceeds) as i64);
+    market.decrease_open_interest(match_amount);
+    accumulate_volume(&mut market, &mut config, safe_add_u64(yes_proceeds, no_proceeds)?);
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     msg!("✅ MatchBurnV2 completed");
     msg!("Amount: {}", match_amount);
     msg!("YES proceeds: {}, NO proceeds: {}", yes_proceeds, no_proceeds);
     msg!("Total Minted: {}", market.total_minted);
+    for (order, cancelled) in [(&yes_order, yes_cancelled), (&no_order, no_cancelled)] {
+        if cancelled > 0 {
+            msg!("order_cancelled:{},{}", args.market_id, order.order_id);
+            PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: order.order_id }).emit()?;
+        }
+    }
     
     Ok(())
 }
@@ -3146,7 +3860,7 @@ fn process_relayer_claim_winnings_v2(
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
     
-    // Account 2: Market
+    // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
     
     // Account 3: Position PDA (writable)
@@ -3161,19 +3875,20 @@ fn process_relayer_claim_winnings_v2(
     // Account 6: Vault Program
     let vault_program_info = next_account_info(account_info_iter)?;
 
-    // Account 7 (optional): UserAccount — if present, settle directly to available_balance
-    let user_vault_info = next_account_info(account_info_iter).ok();
-    
     // Load and validate config
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_settlement_vault(vault_config_info, vault_program_info.key)?;
+    verify_pm_user_account(pm_user_account_info, &args.user_wallet, vault_program_info.key)?;
     
     // Load and validate market
-    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
@@ -3210,35 +3925,28 @@ fn process_relayer_claim_winnings_v2(
         return Err(PredictionMarketError::AlreadySettled.into());
     }
     
-    // Calculate settlement amount based on result.
-    // CRITICAL: Use remaining_locked (= total_cost - settled_cost) instead of total_cost.
-    // settled_cost_e6 tracks how much pm_locked was already consumed during
-    // ExecuteTrade or MatchMint. Without this subtraction, ClaimWinnings would
-    // try to release pm_locked that was already consumed → "Insufficient" error.
-    let remaining_locked = position.total_cost_e6.saturating_sub(position.settled_cost_e6);
+    let (locked_amount, gross_settlement) = claim_settlement_amounts(&market, &position)?;
     
-    let (winning_amount, locked_amount, settlement_amount) = if market.status == MarketStatus::Cancelled {
-        // Cancelled: refund only the remaining locked portion.
-        // Funds already consumed via ExecuteTrade were paid to the counterparty
-        // and cannot be refunded (correct economic behavior).
-        (0u64, remaining_locked, remaining_locked)
+    // Creator fee on winning payouts goes to the creator's PMUserAccount,
+    // which may be appended after the fixed accounts and is skipped by the
+    // optional-account parsing below
+    let creator_fee = market.creator_fee_on(gross_settlement);
+    let creator_pm_user_info = if creator_fee > 0 {
+        Some(find_pm_user_account(
+            accounts,
+            &market.creator,
+            vault_program_info.key,
+            PredictionMarketError::MissingCreatorAccount,
+        )?)
     } else {
-        let final_result = market.final_result.ok_or(PredictionMarketError::MarketNotResolved)?;
-        
-        let win_amt = match final_result {
-            MarketResult::Yes => position.yes_amount,
-            MarketResult::No => position.no_amount,
-            MarketResult::Invalid => 0,
-        };
-        
-        let settle_amt = if final_result == MarketResult::Invalid {
-            remaining_locked // Refund remaining on invalid
-        } else {
-            win_amt  // Winning tokens pay out 1:1 (1 share = $1 USDC in e6)
-        };
-        
-        (win_amt, remaining_locked, settle_amt)
+        None
     };
+    let settlement_amount = gross_settlement - creator_fee;
+    let account_info_iter = &mut account_info_iter
+        .filter(|a| creator_pm_user_info.is_none_or(|c| c.key != a.key));
+    
+    // Account 7 (optional): UserAccount — if present, settle directly to available_balance
+    let user_vault_info = next_account_info(account_info_iter).ok();
     
     // Derive Config PDA for CPI signing
     let (config_pda, config_bump) = Pubkey::find_program_address(
@@ -3252,6 +3960,19 @@ fn process_relayer_claim_winnings_v2(
     
     let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
+    // Mark the Position settled and persist it before any Vault CPI, so a
+    // duplicate claim can never observe it unsettled. A failed CPI aborts
+    // the transaction and rolls this write back.
+    let pnl = (settlement_amount as i64) - (locked_amount as i64);
+    market.decrease_open_interest(settled_sets(&market, &position));
+    position.realized_pnl = position.realized_pnl.saturating_add(pnl);
+    position.settlement_amount = settlement_amount;
+    position.settled = true;
+    position.yes_amount = 0;
+    position.no_amount = 0;
+    position.updated_at = current_time;
+    position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+    
     // Settlement CPI — only if there's something to settle
     if locked_amount > 0 || settlement_amount > 0 {
         if let Some(uvi) = user_vault_info {
@@ -3324,22 +4045,356 @@ fn process_relayer_claim_winnings_v2(
         msg!("Loser/zero position: locked=0, settlement=0, skipping CPI");
     }
     
-    // Update Position
-    let pnl = (settlement_amount as i64) - (locked_amount as i64);
-    position.realized_pnl = position.realized_pnl.saturating_add(pnl);
-    position.settlement_amount = settlement_amount;
+    if let Some(creator_pm_user) = creator_pm_user_info {
+        msg!("CPI: Vault.Settle creator fee={}", creator_fee);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            creator_pm_user,
+            config_info,
+            0,
+            creator_fee,
+            config_seeds,
+        )?;
+    }
+    
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    record_cancelled_payouts(market_info, &market, 1)?;
+    
+    msg!("✅ RelayerClaimWinningsV2 completed");
+    msg!("User: {}", args.user_wallet);
+    msg!("Market status: {:?}", market.status);
+    msg!("Settlement: {}, Creator fee: {}, PnL: {}", settlement_amount, creator_fee, pnl);
+    msg!("winnings_claimed:{},{},{}", args.market_id, args.user_wallet, settlement_amount);
+    
+    Ok(())
+}
+
+/// Compute `(locked, settlement)` for settling a binary Position
+/// 
+/// CRITICAL: Uses remaining_locked (= total_cost - settled_cost) instead of total_cost.
+/// settled_cost_e6 tracks how much pm_locked was already consumed during
+/// ExecuteTrade or MatchMint. Without this subtraction, ClaimWinnings would
+/// try to release pm_locked that was already consumed → "Insufficient" error.
+fn claim_settlement_amounts(market: &Market, position: &Position) -> Result<(u64, u64), ProgramError> {
+    let remaining_locked = position.total_cost_e6.saturating_sub(position.settled_cost_e6);
+    
+    if market.status == MarketStatus::Cancelled {
+        // Cancelled: refund only the remaining locked portion.
+        // Funds already consumed via ExecuteTrade were paid to the counterparty
+        // and cannot be refunded (correct economic behavior).
+        return Ok((remaining_locked, remaining_locked));
+    }
+    
+    let final_result = market.final_result.ok_or(PredictionMarketError::MarketNotResolved)?;
+    let settlement_amount = match final_result {
+        // Winning tokens pay out 1:1 (1 share = $1 USDC in e6)
+        MarketResult::Yes => position.yes_amount,
+        MarketResult::No => position.no_amount,
+        // Refund remaining on invalid
+        MarketResult::Invalid => remaining_locked,
+    };
+    
+    Ok((remaining_locked, settlement_amount))
+}
+
+/// Complete sets a binary Position takes out of open interest when settled
+/// 
+/// Every outstanding set left exactly one winning share with some holder, so
+/// counting the winning side across all claims retires each set once. With
+/// no winner (cancelled or invalid) the YES side is counted instead.
+fn settled_sets(market: &Market, position: &Position) -> u64 {
+    match market.final_result {
+        Some(MarketResult::No) => position.no_amount,
+        _ => position.yes_amount,
+    }
+}
+
+/// Count payouts made out of a cancelled market so ReopenMarket can refuse
+/// to revert a cancellation that users have already been refunded under
+fn record_cancelled_payouts(market_info: &AccountInfo, market: &Market, payouts: u32) -> ProgramResult {
+    if market.status != MarketStatus::Cancelled
+        || payouts == 0
+        || !MarketExtension::fits(&market_info.data.borrow())
+    {
+        return Ok(());
+    }
+    let mut extension = MarketExtension::load(&market_info.data.borrow());
+    extension.cancelled_payouts = extension.cancelled_payouts.saturating_add(payouts);
+    extension.store(&mut market_info.data.borrow_mut())?;
+    Ok(())
+}
+
+/// V2: Batch RelayerClaimWinnings using Vault CPI (no SPL Token)
+/// 
+/// Settles up to MAX_BATCH_CLAIM_USERS positions of one resolved/cancelled
+/// market via Vault.PredictionMarketSettle. Already-settled positions are
+/// skipped so one stale entry does not abort the whole batch.
+fn process_relayer_batch_claim_winnings_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerBatchClaimWinningsArgs,
+) -> ProgramResult {
+    if args.user_wallets.is_empty() || args.user_wallets.len() > MAX_BATCH_CLAIM_USERS {
+        msg!("Error: Batch size {} out of range (1-{})", args.user_wallets.len(), MAX_BATCH_CLAIM_USERS);
+        return Err(PredictionMarketError::BatchTooLarge.into());
+    }
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_settlement_vault(vault_config_info, vault_program_info.key)?;
+    
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    if market.status != MarketStatus::Resolved && market.status != MarketStatus::Cancelled {
+        return Err(PredictionMarketError::MarketNotResolved.into());
+    }
+    
+    // Creator PMUserAccount, if the market charges a creator fee, follows the pairs
+    let creator_pm_user_info = if market.charges_creator_fee() {
+        Some(find_pm_user_account(
+            accounts,
+            &market.creator,
+            vault_program_info.key,
+            PredictionMarketError::MissingCreatorAccount,
+        )?)
+    } else {
+        None
+    };
+    
+    let market_id_bytes = market.market_id.to_le_bytes();
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    let current_time = get_current_timestamp()?;
+    let mut claimed = 0u32;
+    
+    // Accounts 5..: (Position PDA, PM User Account) per user, in args order
+    for user_wallet in &args.user_wallets {
+        let position_info = next_account_info(account_info_iter)?;
+        let pm_user_account_info = next_account_info(account_info_iter)?;
+        
+        let (position_pda, _) = Pubkey::find_program_address(
+            &[POSITION_SEED, &market_id_bytes, user_wallet.as_ref()],
+            program_id,
+        );
+        if *position_info.key != position_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        verify_pm_user_account(pm_user_account_info, user_wallet, vault_program_info.key)?;
+        
+        let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
+        if position.discriminator != POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        if position.settled {
+            msg!("claim_skipped:{},{},already_settled", args.market_id, user_wallet);
+            continue;
+        }
+        
+        let (locked_amount, gross_settlement) = claim_settlement_amounts(&market, &position)?;
+        let creator_fee = market.creator_fee_on(gross_settlement);
+        let settlement_amount = gross_settlement - creator_fee;
+        
+        // Mark the Position settled and persist it before the Vault CPIs,
+        // as in RelayerClaimWinningsV2
+        let pnl = (settlement_amount as i64) - (locked_amount as i64);
+        market.decrease_open_interest(settled_sets(&market, &position));
+        position.realized_pnl = position.realized_pnl.saturating_add(pnl);
+        position.settlement_amount = settlement_amount;
+        position.settled = true;
+        position.yes_amount = 0;
+        position.no_amount = 0;
+        position.updated_at = current_time;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        
+        if locked_amount > 0 || settlement_amount > 0 {
+            cpi_prediction_settle(
+                vault_program_info,
+                vault_config_info,
+                pm_user_account_info,
+                config_info,
+                locked_amount,
+                settlement_amount,
+                config_seeds,
+            )?;
+        }
+        if let Some(creator_pm_user) = creator_pm_user_info.filter(|_| creator_fee > 0) {
+            cpi_prediction_settle(
+                vault_program_info,
+                vault_config_info,
+                creator_pm_user,
+                config_info,
+                0,
+                creator_fee,
+                config_seeds,
+            )?;
+        }
+        claimed += 1;
+        
+        msg!("winnings_claimed:{},{},{}", args.market_id, user_wallet, settlement_amount);
+    }
+    
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    record_cancelled_payouts(market_info, &market, claimed)?;
+    
+    msg!("✅ RelayerBatchClaimWinningsV2 completed");
+    msg!("Claimed: {}, Skipped: {}", claimed, args.user_wallets.len() as u32 - claimed);
+    
+    Ok(())
+}
+
+/// V2: RelayerRefundCancelledMarket using Vault CPI (no SPL Token)
+/// 
+/// This function:
+/// 1. Validates market is cancelled
+/// 2. Refunds the still-locked cost (total_cost - settled_cost) 1:1
+/// 3. Calls Vault.PredictionMarketSettle with locked = settlement = refund
+/// 4. Zeroes the position and marks it settled (no second refund)
+/// 
+/// Cost already consumed by ExecuteTrade/MatchMint was paid to the
+/// counterparty and is not part of the refund.
+fn process_relayer_refund_cancelled_market_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerRefundCancelledMarketArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: Position PDA (writable)
+    let position_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: PM User Account (writable)
+    let pm_user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_settlement_vault(vault_config_info, vault_program_info.key)?;
+    verify_pm_user_account(pm_user_account_info, &args.user_wallet, vault_program_info.key)?;
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    if market.status != MarketStatus::Cancelled {
+        msg!("Error: Market is not cancelled ({:?})", market.status);
+        return Err(PredictionMarketError::MarketNotCancelled.into());
+    }
+    
+    // Verify Position PDA
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market.market_id.to_le_bytes(), args.user_wallet.as_ref()],
+        program_id,
+    );
+    if *position_info.key != position_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
+    if position.discriminator != POSITION_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if position.settled {
+        msg!("Error: Position already refunded/settled");
+        return Err(PredictionMarketError::AlreadySettled.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    let refund_amount = position.total_cost_e6.saturating_sub(position.settled_cost_e6);
+    
+    if refund_amount > 0 {
+        let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            pm_user_account_info,
+            config_info,
+            refund_amount,
+            refund_amount,
+            config_seeds,
+        )?;
+    } else {
+        msg!("Nothing left to refund, skipping CPI");
+    }
+    
+    // Update Position
+    market.decrease_open_interest(settled_sets(&market, &position));
+    position.settlement_amount = refund_amount;
     position.settled = true;
     position.yes_amount = 0;
     position.no_amount = 0;
     position.updated_at = current_time;
     
-    position.serialize(&mut *position_info.data.borrow_mut())?;
+    position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    record_cancelled_payouts(market_info, &market, 1)?;
     
-    msg!("✅ RelayerClaimWinningsV2 completed");
+    msg!("✅ RelayerRefundCancelledMarketV2 completed");
     msg!("User: {}", args.user_wallet);
-    msg!("Market status: {:?}", market.status);
-    msg!("Settlement: {}, PnL: {}", settlement_amount, pnl);
-    msg!("winnings_claimed:{},{},{}", args.market_id, args.user_wallet, settlement_amount);
+    msg!("Refund: {}", refund_amount);
+    msg!("market_refunded:{},{},{}", args.market_id, args.user_wallet, refund_amount);
     
     Ok(())
 }
@@ -3415,12 +4470,12 @@ fn process_execute_trade_v2(
     let seller_wallet_info = next_account_info(account_info_iter)?;
     
     // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
     
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
@@ -3442,8 +4497,12 @@ fn process_execute_trade_v2(
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
+    market.check_tradeable()?;
+    
+    // Binary markets only - multi-outcome markets use ExecuteMultiOutcomeTradeV2
+    if !market.is_binary() {
+        msg!("Error: ExecuteTradeV2 requires a Binary market, use ExecuteMultiOutcomeTradeV2");
+        return Err(PredictionMarketError::InvalidMarketType.into());
     }
     
     // Verify Order PDAs
@@ -3484,9 +4543,10 @@ fn process_execute_trade_v2(
         return Err(PredictionMarketError::InvalidOrderSide.into());
     }
     
-    // Verify same outcome
-    if buy_order.outcome != sell_order.outcome {
-        msg!("Error: Orders must be for the same outcome");
+    // Verify same outcome (outcome_index is authoritative across market types)
+    if !buy_order.is_same_outcome(&sell_order) {
+        msg!("Error: Orders must be for the same outcome (buy index {}, sell index {})",
+             buy_order.outcome_index, sell_order.outcome_index);
         return Err(PredictionMarketError::OutcomeMismatch.into());
     }
     
@@ -3498,6 +4558,15 @@ fn process_execute_trade_v2(
         return Err(PredictionMarketError::OrderNotActive.into());
     }
     
+    // Verify Vault accounts belong to the order owners before any CPI
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_pm_user_account(buyer_pm_user_info, &buy_order.owner, vault_program_info.key)?;
+    verify_pm_user_account(seller_pm_user_info, &sell_order.owner, vault_program_info.key)?;
+    if *buyer_wallet_info.key != buy_order.owner || *seller_wallet_info.key != sell_order.owner {
+        msg!("Error: Wallet accounts do not match order owners");
+        return Err(PredictionMarketError::OrderOwnerMismatch.into());
+    }
+    
     // Verify price compatibility (buy price >= sell price)
     if buy_order.price < sell_order.price {
         msg!("Error: Buy price {} must be >= sell price {}", buy_order.price, sell_order.price);
@@ -3513,10 +4582,21 @@ fn process_execute_trade_v2(
         return Err(PredictionMarketError::NoMatchableAmount.into());
     }
     
+    // FOK orders must be filled completely by this match
+    buy_order.check_fill_or_kill(match_amount)?;
+    sell_order.check_fill_or_kill(match_amount)?;
+    
     let current_time = get_current_timestamp()?;
     
+    // Record the fill up front: an IOC order's unfilled remainder (or any
+    // dust below min_order_size) is cancelled, and its margin/shares are
+    // released below
+    let buy_cancelled = buy_order.record_fill(match_amount, config.min_order_size, current_time)?;
+    let sell_cancelled = sell_order.record_fill(match_amount, config.min_order_size, current_time)?;
+    
     // Execution price (use provided price, should be <= buy_price and >= sell_price)
     let exec_price = args.price;
+    validate_price(exec_price)?;
     if exec_price < sell_order.price || exec_price > buy_order.price {
         msg!("Error: Execution price {} out of bounds [{}, {}]", 
              exec_price, sell_order.price, buy_order.price);
@@ -3524,14 +4604,78 @@ fn process_execute_trade_v2(
     }
     
     // Calculate trade cost: cost = amount * price / PRICE_PRECISION
-    let trade_cost = (match_amount as u128)
-        .checked_mul(exec_price as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
+    let trade_cost = mul_div_floor(match_amount, exec_price, PRICE_PRECISION)?;
     
     msg!("V2 Direct Trade: amount={}, price={}, cost={}", match_amount, exec_price, trade_cost);
     
+    // Volume tiers discount the buyer's (taker's) fee by the volume it had
+    // traded before this fill; both traders' stats are updated in Step 4b
+    let config_extension = ConfigExtension::load(&config_info.data.borrow());
+    let buyer_stats = find_trader_stats(program_id, accounts, &buy_order.owner);
+    let seller_stats = find_trader_stats(program_id, accounts, &sell_order.owner);
+    if config_extension.fee_tiers_enabled() && (buyer_stats.is_none() || seller_stats.is_none()) {
+        msg!("Error: Fee tiers require the buyer's and seller's TraderStats accounts");
+        return Err(PredictionMarketError::MissingTraderStats.into());
+    }
+    let buyer_volume = match buyer_stats {
+        Some((stats_info, _)) => trader_volume(stats_info)?,
+        None => 0,
+    };
+    
+    // Trading fee, charged on each side of the fill: the buyer pays
+    // trade_cost + taker fee out of pm_locked, the seller receives
+    // trade_cost - fee. The sell order is the resting maker and is rebated
+    // out of the fees; maker_rebate_bps <= trade_fee_bps keeps fund_fee
+    // non-negative.
+    let trade_fee = config.trade_fee_on(trade_cost)?;
+    let taker_fee = config_extension.discounted_taker_fee(trade_fee, buyer_volume)?;
+    let maker_rebate = config.maker_rebate_on(trade_cost)?;
+    let buyer_debit = trade_cost
+        .checked_add(taker_fee)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    let seller_credit = trade_cost
+        .checked_sub(trade_fee)
+        .and_then(|v| v.checked_add(maker_rebate))
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    
+    // Referrers get their referral share of the protocol fee each side
+    // pays (the taker fee, or the maker's fee less its rebate), taken out
+    // of what the Fund receives
+    let referral_rewards = referral_rewards(
+        program_id,
+        accounts,
+        &config_extension,
+        vault_program_info.key,
+        &[(buy_order.owner, taker_fee), (sell_order.owner, trade_fee.saturating_sub(maker_rebate))],
+    )?;
+    let referral_total = referral_rewards.iter().map(|(_, reward)| reward).sum::<u64>();
+    let fund_fee = trade_fee
+        .checked_add(taker_fee)
+        .and_then(|v| v.checked_sub(maker_rebate))
+        .and_then(|v| v.checked_sub(referral_total))
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    
+    // PlaceOrder locked margin at order_price plus the full taker fee on
+    // it; the buyer's debit must fit in it
+    let margin_at_order_price = buy_margin(match_amount, buy_order.price, config.trade_fee_bps)?;
+    if buyer_debit > margin_at_order_price {
+        msg!("Error: Trade cost {} + fee {} exceeds locked margin {}",
+             trade_cost, taker_fee, margin_at_order_price);
+        return Err(PredictionMarketError::InsufficientFundsForFee.into());
+    }
+    
+    // Fees are credited to the Fund Program's PMUserAccount
+    let fund_pm_user_info = if fund_fee > 0 {
+        Some(find_pm_user_account(
+            accounts,
+            &config.fund_program,
+            vault_program_info.key,
+            PredictionMarketError::MissingFundAccount,
+        )?)
+    } else {
+        None
+    };
+    
     // Verify Position PDAs
     let (buyer_position_pda, _) = Pubkey::find_program_address(
         &[POSITION_SEED, &market_id_bytes, buy_order.owner.as_ref()],
@@ -3583,9 +4727,9 @@ fn process_execute_trade_v2(
     
     let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
-    // Step 1a: CPI - Settle buyer (deduct trade_cost from pm_locked)
-    // locked=trade_cost, settlement=0
-    msg!("CPI: Settle buyer - deduct {} from pm_locked", trade_cost);
+    // Step 1a: CPI - Settle buyer (deduct trade_cost + fee from pm_locked)
+    // locked=buyer_debit, settlement=0
+    msg!("CPI: Settle buyer - deduct {} from pm_locked", buyer_debit);
     cpi_prediction_settle_with_auto_init(
         vault_program_info,
         vault_config_info,
@@ -3594,7 +4738,7 @@ fn process_execute_trade_v2(
         relayer_info,           // payer for auto-init
         system_program_info,    // system program for create_account
         buyer_wallet_info,      // buyer wallet for PDA derivation
-        trade_cost,             // locked_amount to deduct
+        buyer_debit,            // locked_amount to deduct
         0,                      // settlement_amount (none for buyer in trade)
         config_seeds,
     )?;
@@ -3602,25 +4746,21 @@ fn process_execute_trade_v2(
     // Step 1b: Release excess margin back to buyer's available_balance.
     // PlaceOrder locked margin at order_price, but ExecuteTrade fills at exec_price.
     // When exec_price < order_price (common for IOC/Market orders), the difference
-    // must be returned: pm_locked → available_balance.
-    let margin_at_order_price = (match_amount as u128)
-        .checked_mul(buy_order.price as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
-    
-    let excess_margin = margin_at_order_price.saturating_sub(trade_cost);
-    
-    if excess_margin > 0 {
-        msg!("CPI: Release excess margin {} (order_price={}, exec_price={}, margin_at_order={}, trade_cost={})",
-             excess_margin, buy_order.price, exec_price, margin_at_order_price, trade_cost);
+    // must be returned: pm_locked → available_balance. A cancelled IOC
+    // remainder returns its whole margin as well. The order's locked_margin_e6
+    // tracks what is left so a later cancel releases exactly the rest.
+    let release_amount = buy_order.settle_locked_margin(match_amount, buyer_debit, config.trade_fee_bps)?;
+    
+    if release_amount > 0 {
+        msg!("CPI: Release excess margin {} (IOC/dust remainder {}, order_price={}, exec_price={}, margin_at_order={}, trade_cost={}, fee={})",
+             release_amount, buy_cancelled, buy_order.price, exec_price, margin_at_order_price, trade_cost, taker_fee);
         cpi_release_from_prediction(
             vault_program_info,
             vault_config_info,
             buyer_vault_info,
             buyer_pm_user_info,
             config_info,
-            excess_margin,
+            release_amount,
             config_seeds,
         )?;
     } else {
@@ -3629,9 +4769,9 @@ fn process_execute_trade_v2(
     }
     
     // Step 2: CPI - Settle seller (add to pending_settlement)
-    // locked=0, settlement=trade_cost
+    // locked=0, settlement=trade_cost - fee + maker rebate
     // 使用支持自动创建 PMUserAccount 的版本
-    msg!("CPI: Settle seller - add {} to pending_settlement", trade_cost);
+    msg!("CPI: Settle seller - add {} to pending_settlement", seller_credit);
     cpi_prediction_settle_with_auto_init(
         vault_program_info,
         vault_config_info,
@@ -3641,22 +4781,52 @@ fn process_execute_trade_v2(
         system_program_info,    // system program for create_account
         seller_wallet_info,     // seller wallet for PDA derivation
         0,                      // locked_amount (seller didn't lock for sell order in V2)
-        trade_cost,             // settlement_amount
+        seller_credit,          // settlement_amount
         config_seeds,
     )?;
     
-    // Step 3: Update Positions - transfer shares (seller → buyer)
-    // Load or create buyer position (auto-init if empty)
-    let (_, buyer_position_bump) = Pubkey::find_program_address(
-        &[POSITION_SEED, &market_id_bytes, buy_order.owner.as_ref()],
-        program_id,
-    );
-    
-    let mut buyer_position = if buyer_position_info.data_is_empty() {
-        // Auto-create buyer Position PDA (like MintCompleteSet does)
-        msg!("Creating buyer Position PDA (auto-init for DirectTrade)");
-        
-        let rent = Rent::get()?;
+    // Step 2b: CPI - Credit both sides' fees, less the maker rebate, to the
+    // Fund Program. There is no PM → Fund CPI; fees accrue in the Fund's
+    // Vault PMUserAccount.
+    if let Some(fund_pm_user_info) = fund_pm_user_info {
+        msg!("CPI: Credit trade fee {} to Fund (maker rebate {})", fund_fee, maker_rebate);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            fund_pm_user_info,
+            config_info,
+            0,
+            fund_fee,
+            config_seeds,
+        )?;
+    }
+    
+    // Step 2c: CPI - Credit referral rewards to the referrers' PMUserAccounts
+    for (referrer_pm_user_info, reward) in referral_rewards.iter() {
+        msg!("CPI: Credit referral reward {} to {}", reward, referrer_pm_user_info.key);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            referrer_pm_user_info,
+            config_info,
+            0,
+            *reward,
+            config_seeds,
+        )?;
+    }
+    
+    // Step 3: Update Positions - transfer shares (seller → buyer)
+    // Load or create buyer position (auto-init if empty)
+    let (_, buyer_position_bump) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market_id_bytes, buy_order.owner.as_ref()],
+        program_id,
+    );
+    
+    let mut buyer_position = if buyer_position_info.data_is_empty() {
+        // Auto-create buyer Position PDA (like MintCompleteSet does)
+        msg!("Creating buyer Position PDA (auto-init for DirectTrade)");
+        
+        let rent = Rent::get()?;
         let space = Position::SIZE;
         let lamports = rent.minimum_balance(space);
         let position_seeds: &[&[u8]] = &[
@@ -3680,7 +4850,7 @@ fn process_execute_trade_v2(
         
         // Initialize new position
         let position = Position::new(market.market_id, buy_order.owner, buyer_position_bump, current_time);
-        position.serialize(&mut *buyer_position_info.data.borrow_mut())?;
+        position.serialize(&mut &mut buyer_position_info.data.borrow_mut()[..])?;
         
         msg!("✅ Buyer Position PDA created: {}", buyer_position_info.key);
         position
@@ -3691,17 +4861,28 @@ fn process_execute_trade_v2(
     // Consume locked shares from seller (this unlocks and removes in one step)
     // Note: For Direct Trade, seller doesn't receive USDC here (handled by CPI above)
     // We use exec_price for PnL calculation
+    let seller_pnl_before = seller_position.realized_pnl;
     seller_position.consume_locked_shares(outcome, match_amount, exec_price, current_time)
         .map_err(|_| {
             msg!("Error: Failed to consume locked shares from seller");
             PredictionMarketError::InsufficientPosition
         })?;
+    market.record_realized_pnl(seller_pnl_before, seller_position.realized_pnl)?;
+    
+    // A cancelled IOC sell returns its unfilled shares to the seller
+    if sell_cancelled > 0 {
+        seller_position.unlock_shares(outcome, sell_cancelled)
+            .map_err(|_| {
+                msg!("Error: Failed to unlock IOC/dust remainder for seller");
+                PredictionMarketError::InsufficientPosition
+            })?;
+    }
     
     // Add shares to buyer
-    buyer_position.add_tokens(outcome, match_amount, exec_price, current_time);
-    // Track that trade_cost was already settled from buyer's pm_locked (Step 1 CPI above).
+    buyer_position.add_tokens(outcome, match_amount, exec_price, current_time)?;
+    // Track that trade_cost + fee was already settled from buyer's pm_locked (Step 1 CPI above).
     // This prevents ClaimWinnings from double-releasing the same pm_locked.
-    buyer_position.settled_cost_e6 = buyer_position.settled_cost_e6.saturating_add(trade_cost);
+    buyer_position.settled_cost_e6 = buyer_position.settled_cost_e6.saturating_add(buyer_debit);
     
     // Migrate seller Position if needed (old 146 bytes → new 154 bytes)
     if seller_position_info.data_len() < Position::SIZE {
@@ -3739,251 +4920,759 @@ fn process_execute_trade_v2(
         }
     }
     
-    seller_position.serialize(&mut *seller_position_info.data.borrow_mut())?;
-    buyer_position.serialize(&mut *buyer_position_info.data.borrow_mut())?;
+    seller_position.serialize(&mut &mut seller_position_info.data.borrow_mut()[..])?;
+    buyer_position.serialize(&mut &mut buyer_position_info.data.borrow_mut()[..])?;
     
     msg!("📊 Shares transferred: {} {:?} from seller to buyer", match_amount, outcome);
     
-    // Step 4: Update Orders
-    buy_order.filled_amount += match_amount;
-    if buy_order.filled_amount >= buy_order.amount {
-        buy_order.status = OrderStatus::Filled;
-    } else {
-        buy_order.status = OrderStatus::PartialFilled;
-    }
-    buy_order.updated_at = current_time;
-    buy_order.serialize(&mut *buy_order_info.data.borrow_mut())?;
+    // Step 4: Update Orders (fills were recorded above)
+    buy_order.serialize(&mut &mut buy_order_info.data.borrow_mut()[..])?;
+    sell_order.serialize(&mut &mut sell_order_info.data.borrow_mut()[..])?;
     
-    sell_order.filled_amount += match_amount;
-    if sell_order.filled_amount >= sell_order.amount {
-        sell_order.status = OrderStatus::Filled;
-    } else {
-        sell_order.status = OrderStatus::PartialFilled;
+    // Step 4b: Add the fill to both traders' volume
+    for (stats, wallet) in [(buyer_stats, &buy_order.owner), (seller_stats, &sell_order.owner)] {
+        if let Some(stats) = stats {
+            record_trader_volume(program_id, stats, wallet, relayer_info, system_program_info, trade_cost, current_time)?;
+        }
     }
-    sell_order.updated_at = current_time;
-    sell_order.serialize(&mut *sell_order_info.data.borrow_mut())?;
     
     // Step 5: Update Market stats
-    market.total_volume_e6 = market.total_volume_e6.saturating_add(trade_cost as i64);
+    accumulate_volume(&mut market, &mut config, trade_cost);
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     // Emit success log
     msg!("✅ ExecuteTradeV2 completed");
     msg!("Market: {}, Outcome: {:?}", args.market_id, outcome);
     msg!("Buy Order: {}, Sell Order: {}", args.taker_order_id, args.maker_order_id);
-    msg!("Amount: {}, Price: {}, Cost: {}", match_amount, exec_price, trade_cost);
+    msg!("Amount: {}, Price: {}, Cost: {}, Fee: {} (taker {})", match_amount, exec_price, trade_cost, trade_fee, taker_fee);
     msg!("Buyer: {}", buy_order.owner);
     msg!("Seller: {}", sell_order.owner);
     let outcome_u8 = outcome as u8;
     msg!("trade_executed:{},{},{},{},{},{},{},{}", args.market_id, args.taker_order_id, args.maker_order_id, buy_order.owner, sell_order.owner, outcome_u8, exec_price, match_amount);
+    PredictionMarketEvent::TradeExecuted(events::TradeExecuted {
+        market_id: args.market_id,
+        outcome_index: outcome_u8,
+        buy_order_id: buy_order.order_id,
+        sell_order_id: sell_order.order_id,
+        buyer: buy_order.owner,
+        seller: sell_order.owner,
+        price: exec_price,
+        amount: match_amount,
+    }).emit()?;
+    if buy_cancelled > 0 {
+        msg!("order_cancelled:{},{}", args.market_id, args.taker_order_id);
+        PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.taker_order_id }).emit()?;
+    }
+    if sell_cancelled > 0 {
+        msg!("order_cancelled:{},{}", args.market_id, args.maker_order_id);
+        PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.maker_order_id }).emit()?;
+    }
+    if maker_rebate > 0 {
+        msg!("maker_rebate:{},{},{},{}", args.market_id, args.maker_order_id, sell_order.owner, maker_rebate);
+    }
+    for (referrer_pm_user_info, reward) in referral_rewards.iter() {
+        msg!("referral_reward:{},{},{}", args.market_id, referrer_pm_user_info.key, reward);
+    }
     
     Ok(())
 }
 
-/// V2: ExecuteMultiOutcomeTrade using Vault CPI (no SPL Token)
-/// 
-/// Direct trade between buyer and seller for multi-outcome markets:
-/// - Buyer has USDC locked in pm_locked (from RelayerPlaceMultiOutcomeOrderV2)
-/// - Seller has virtual shares in MultiOutcomePosition PDA
-/// - Trade transfers USDC (buyer → seller) and shares (seller → buyer)
-/// 
-/// Key differences from ExecuteTradeV2:
-/// 1. Uses MULTI_OUTCOME_POSITION_SEED for Position PDA derivation
-/// 2. Deserializes MultiOutcomePosition (893 bytes) instead of Position (154 bytes)
-/// 3. Uses holdings[outcome_index] / locked[outcome_index] instead of yes_amount/no_amount
+/// V2: ExecuteTradeMulti - fill one taker Buy order against several resting
+/// Sell orders using Vault CPI (no SPL Token)
 /// 
-/// Flow:
-/// 1. Validate orders (same outcome_index, price compatible, sufficient amounts)
-/// 2. Validate seller has sufficient locked shares in MultiOutcomePosition
-/// 3. CPI: Settle buyer (locked=cost, settlement=0) - deduct from buyer's pm_locked
-/// 4. CPI: Settle seller (locked=0, settlement=cost) - add to seller's pending_settlement  
-/// 5. Update MultiOutcomePositions: transfer shares from seller to buyer
-/// 6. Update Orders: mark filled/partial_filled
-fn process_execute_multi_outcome_trade_v2(
+/// Makers are filled in the given order, which must be price-time priority,
+/// each at its own price. Matching stops once the taker (or args.amount) is
+/// filled or at the first maker priced above the taker's limit. Makers that
+/// are no longer active are skipped. Each maker is settled and written as it
+/// fills; the buyer's settle, margin release and Position update run once for
+/// the whole sweep.
+fn process_execute_trade_multi_v2(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: ExecuteMultiOutcomeTradeV2Args,
+    args: ExecuteTradeMultiArgs,
 ) -> ProgramResult {
-    use crate::state::{MULTI_OUTCOME_POSITION_SEED};
+    if args.maker_order_ids.is_empty() || args.maker_order_ids.len() > MAX_TRADE_MAKERS {
+        msg!("Error: Maker count {} out of range (1-{})", args.maker_order_ids.len(), MAX_TRADE_MAKERS);
+        return Err(PredictionMarketError::BatchTooLarge.into());
+    }
     
     let account_info_iter = &mut accounts.iter();
     
+    // Account 0: Relayer/Keeper (signer)
     let relayer_info = next_account_info(account_info_iter)?;
     check_signer(relayer_info)?;
     
+    // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: Taker (Buy) Order (writable)
     let buy_order_info = next_account_info(account_info_iter)?;
-    let sell_order_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: Buyer Position PDA (writable)
     let buyer_position_info = next_account_info(account_info_iter)?;
-    let seller_position_info = next_account_info(account_info_iter)?;
-    let buyer_vault_info = next_account_info(account_info_iter)?;  // Account 7: for excess margin refund
+    
+    // Account 5: Buyer UserAccount (Vault, writable) - used for excess margin refund
+    let buyer_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Buyer PMUserAccount (Vault, writable)
     let buyer_pm_user_info = next_account_info(account_info_iter)?;
-    let _seller_vault_info = next_account_info(account_info_iter)?;
-    let seller_pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Buyer Wallet (for PMUserAccount auto-init)
+    let buyer_wallet_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: VaultConfig
     let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 9: Vault Program
     let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 10: System Program
     let system_program_info = next_account_info(account_info_iter)?;
-    let buyer_wallet_info = next_account_info(account_info_iter)?;
-    let seller_wallet_info = next_account_info(account_info_iter)?;
     
-    // Load config (small struct, ok on stack)
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
-    verify_relayer(&config, relayer_info.key)?;
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
-    // Verify Market PDA and load market in a scope to limit lifetime
+    // Verify Market PDA
     let market_id_bytes = args.market_id.to_le_bytes();
-    let (market_pda, _) = Pubkey::find_program_address(&[MARKET_SEED, &market_id_bytes], program_id);
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &market_id_bytes],
+        program_id,
+    );
     if *market_info.key != market_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Extract market info we need, then drop the large struct
-    let (market_id, num_outcomes, is_tradeable, is_multi_outcome) = {
-        let market = deserialize_account::<Market>(&market_info.data.borrow())?;
-        if market.discriminator != MARKET_DISCRIMINATOR {
-            return Err(PredictionMarketError::InvalidAccountData.into());
-        }
-        (market.market_id, market.num_outcomes, market.is_tradeable(), market.market_type == MarketType::MultiOutcome)
-    };
-    
-    if !is_tradeable {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
     }
-    if !is_multi_outcome {
+    
+    market.check_tradeable()?;
+    
+    if !market.is_binary() {
+        msg!("Error: ExecuteTradeMultiV2 requires a Binary market");
         return Err(PredictionMarketError::InvalidMarketType.into());
     }
-    if args.outcome_index >= num_outcomes {
-        return Err(PredictionMarketError::InvalidOutcome.into());
-    }
     
-    // Verify Order PDAs
-    let buy_order_id_bytes = args.buy_order_id.to_le_bytes();
-    let (buy_order_pda, _) = Pubkey::find_program_address(&[ORDER_SEED, &market_id_bytes, &buy_order_id_bytes], program_id);
+    // Load the taker order
+    let (buy_order_pda, _) = Pubkey::find_program_address(
+        &[ORDER_SEED, &market_id_bytes, &args.taker_order_id.to_le_bytes()],
+        program_id,
+    );
     if *buy_order_info.key != buy_order_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let sell_order_id_bytes = args.sell_order_id.to_le_bytes();
-    let (sell_order_pda, _) = Pubkey::find_program_address(&[ORDER_SEED, &market_id_bytes, &sell_order_id_bytes], program_id);
-    if *sell_order_info.key != sell_order_pda {
+    let mut buy_order = deserialize_account::<Order>(&buy_order_info.data.borrow())?;
+    if buy_order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if buy_order.side != crate::state::OrderSide::Buy {
+        msg!("Error: Taker order {} is not a buy order", args.taker_order_id);
+        return Err(PredictionMarketError::InvalidOrderSide.into());
+    }
+    if !buy_order.is_active() {
+        return Err(PredictionMarketError::OrderNotActive.into());
+    }
+    
+    let outcome = buy_order.outcome;
+    
+    // Verify the buyer's Vault accounts before any CPI
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_pm_user_account(buyer_pm_user_info, &buy_order.owner, vault_program_info.key)?;
+    if *buyer_wallet_info.key != buy_order.owner {
+        msg!("Error: Buyer wallet does not match the taker order owner");
+        return Err(PredictionMarketError::OrderOwnerMismatch.into());
+    }
+    
+    let (buyer_position_pda, buyer_position_bump) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market_id_bytes, buy_order.owner.as_ref()],
+        program_id,
+    );
+    if *buyer_position_info.key != buyer_position_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load orders and extract what we need (including buy_order_price for excess margin calc)
-    let (buyer_owner, seller_owner, match_amount, exec_price, trade_cost, buy_order_price) = {
-        let buy_order = deserialize_account::<Order>(&buy_order_info.data.borrow())?;
-        let sell_order = deserialize_account::<Order>(&sell_order_info.data.borrow())?;
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    let current_time = get_current_timestamp()?;
+    
+    // Volume tiers discount the taker's fee by the volume it had traded
+    // before this sweep; every fill is added to both traders' stats
+    let config_extension = ConfigExtension::load(&config_info.data.borrow());
+    let buyer_stats = find_trader_stats(program_id, accounts, &buy_order.owner);
+    if config_extension.fee_tiers_enabled() && buyer_stats.is_none() {
+        msg!("Error: Fee tiers require the buyer's TraderStats account");
+        return Err(PredictionMarketError::MissingTraderStats.into());
+    }
+    let buyer_volume = match buyer_stats {
+        Some((stats_info, _)) => trader_volume(stats_info)?,
+        None => 0,
+    };
+    
+    let mut unfilled = args.amount.min(buy_order.remaining_amount());
+    let mut fills: Vec<(u64, u64)> = Vec::with_capacity(args.maker_order_ids.len());
+    let mut total_cost = 0u64;
+    let mut buyer_debit = 0u64;
+    let mut fund_fee = 0u64;
+    let mut referral_payouts: Vec<(&AccountInfo, u64)> = Vec::new();
+    let mut last_priority: Option<(u64, i64, u64)> = None;
+    
+    // Accounts 11..: (Sell Order, Seller Position, Seller PMUserAccount,
+    // Seller Wallet) per maker, in priority order
+    for maker_order_id in &args.maker_order_ids {
+        if unfilled == 0 {
+            break;
+        }
         
-        if buy_order.discriminator != ORDER_DISCRIMINATOR || sell_order.discriminator != ORDER_DISCRIMINATOR {
-            return Err(PredictionMarketError::InvalidAccountData.into());
+        let sell_order_info = next_account_info(account_info_iter)?;
+        let seller_position_info = next_account_info(account_info_iter)?;
+        let seller_pm_user_info = next_account_info(account_info_iter)?;
+        let seller_wallet_info = next_account_info(account_info_iter)?;
+        
+        let (sell_order_pda, _) = Pubkey::find_program_address(
+            &[ORDER_SEED, &market_id_bytes, &maker_order_id.to_le_bytes()],
+            program_id,
+        );
+        if *sell_order_info.key != sell_order_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
         }
-        if buy_order.side != crate::state::OrderSide::Buy {
-            return Err(PredictionMarketError::InvalidOrderSide.into());
+        
+        let mut sell_order = deserialize_account::<Order>(&sell_order_info.data.borrow())?;
+        if sell_order.discriminator != ORDER_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
         }
         if sell_order.side != crate::state::OrderSide::Sell {
+            msg!("Error: Maker order {} is not a sell order", maker_order_id);
             return Err(PredictionMarketError::InvalidOrderSide.into());
         }
-        if buy_order.outcome_index != sell_order.outcome_index || buy_order.outcome_index != args.outcome_index {
+        if !buy_order.is_same_outcome(&sell_order) {
+            msg!("Error: Maker order {} is for outcome index {}, taker for {}",
+                 maker_order_id, sell_order.outcome_index, buy_order.outcome_index);
             return Err(PredictionMarketError::OutcomeMismatch.into());
         }
-        if !buy_order.is_active() || !sell_order.is_active() {
-            return Err(PredictionMarketError::OrderNotActive.into());
+        if !sell_order.is_active() {
+            msg!("maker_skipped:{},{},{:?}", args.market_id, maker_order_id, sell_order.status);
+            continue;
         }
-        if buy_order.price < sell_order.price {
-            return Err(PredictionMarketError::PriceMismatch.into());
+        
+        // Best price first, then the older order at the same price
+        let priority = (sell_order.price, sell_order.created_at, sell_order.order_id);
+        if last_priority.is_some_and(|last| priority < last) {
+            msg!("Error: Maker order {} (price {}) is out of price-time priority",
+                 maker_order_id, sell_order.price);
+            return Err(PredictionMarketError::MakerOrdersOutOfPriority.into());
         }
+        last_priority = Some(priority);
         
-        let match_amt = args.amount.min(buy_order.remaining_amount()).min(sell_order.remaining_amount());
-        if match_amt == 0 {
-            return Err(PredictionMarketError::NoMatchableAmount.into());
+        // Makers are sorted, so no later one crosses the taker's limit either
+        if sell_order.price > buy_order.price {
+            break;
         }
         
-        let price = args.price;
-        if price < sell_order.price || price > buy_order.price {
-            return Err(PredictionMarketError::InvalidExecutionPrice.into());
+        verify_pm_user_account(seller_pm_user_info, &sell_order.owner, vault_program_info.key)?;
+        if *seller_wallet_info.key != sell_order.owner {
+            msg!("Error: Seller wallet does not match maker order {} owner", maker_order_id);
+            return Err(PredictionMarketError::OrderOwnerMismatch.into());
+        }
+        let (seller_position_pda, _) = Pubkey::find_program_address(
+            &[POSITION_SEED, &market_id_bytes, sell_order.owner.as_ref()],
+            program_id,
+        );
+        if *seller_position_info.key != seller_position_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        let seller_stats = find_trader_stats(program_id, accounts, &sell_order.owner);
+        if config_extension.fee_tiers_enabled() && seller_stats.is_none() {
+            msg!("Error: Fee tiers require the TraderStats of maker order {}'s owner", maker_order_id);
+            return Err(PredictionMarketError::MissingTraderStats.into());
+        }
+        
+        let match_amount = unfilled.min(sell_order.remaining_amount());
+        sell_order.check_fill_or_kill(match_amount)?;
+        let sell_cancelled = sell_order.record_fill(match_amount, config.min_order_size, current_time)?;
+        
+        // Each level fills at the maker's price, with fees as in ExecuteTradeV2
+        let exec_price = sell_order.price;
+        let trade_cost = mul_div_floor(match_amount, exec_price, PRICE_PRECISION)?;
+        let trade_fee = config.trade_fee_on(trade_cost)?;
+        let taker_fee = config_extension.discounted_taker_fee(trade_fee, buyer_volume)?;
+        let maker_rebate = config.maker_rebate_on(trade_cost)?;
+        let seller_credit = trade_cost
+            .checked_sub(trade_fee)
+            .and_then(|v| v.checked_add(maker_rebate))
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        
+        // Referrers are paid out of each level's fees as in ExecuteTradeV2,
+        // one payout per referrer for the whole sweep
+        let level_rewards = referral_rewards(
+            program_id,
+            accounts,
+            &config_extension,
+            vault_program_info.key,
+            &[(buy_order.owner, taker_fee), (sell_order.owner, trade_fee.saturating_sub(maker_rebate))],
+        )?;
+        let level_referral_total = level_rewards.iter().map(|(_, reward)| reward).sum::<u64>();
+        let level_fund_fee = trade_fee
+            .checked_add(taker_fee)
+            .and_then(|v| v.checked_sub(maker_rebate))
+            .and_then(|v| v.checked_sub(level_referral_total))
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        for (referrer_pm_user_info, reward) in level_rewards {
+            match referral_payouts.iter_mut().find(|(info, _)| info.key == referrer_pm_user_info.key) {
+                Some((_, total)) => {
+                    *total = total.checked_add(reward).ok_or(PredictionMarketError::ArithmeticOverflow)?;
+                }
+                None => referral_payouts.push((referrer_pm_user_info, reward)),
+            }
         }
         
-        let cost = ((match_amt as u128) * (price as u128) / (PRICE_PRECISION as u128)) as u64;
+        let mut seller_position = deserialize_account::<Position>(&seller_position_info.data.borrow())?;
+        if seller_position.discriminator != POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
         
-        (buy_order.owner, sell_order.owner, match_amt, price, cost, buy_order.price)
-    };
-    
-    let current_time = get_current_timestamp()?;
-    
-    msg!("V2 MultiOutcome DirectTrade: m={}, o={}, amt={}, cost={}", 
-         args.market_id, args.outcome_index, match_amount, trade_cost);
-    
-    // Verify Position PDAs
-    let (buyer_position_pda, buyer_position_bump) = Pubkey::find_program_address(
-        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, buyer_owner.as_ref()],
-        program_id,
-    );
-    if *buyer_position_info.key != buyer_position_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
+        msg!("CPI: Settle seller - add {} to pending_settlement", seller_credit);
+        cpi_prediction_settle_with_auto_init(
+            vault_program_info,
+            vault_config_info,
+            seller_pm_user_info,
+            config_info,
+            relayer_info,
+            system_program_info,
+            seller_wallet_info,
+            0,
+            seller_credit,
+            config_seeds,
+        )?;
+        
+        let seller_pnl_before = seller_position.realized_pnl;
+        seller_position.consume_locked_shares(outcome, match_amount, exec_price, current_time)
+            .map_err(|_| {
+                msg!("Error: Seller of maker order {} has insufficient locked shares", maker_order_id);
+                PredictionMarketError::InsufficientPosition
+            })?;
+        market.record_realized_pnl(seller_pnl_before, seller_position.realized_pnl)?;
+        if sell_cancelled > 0 {
+            seller_position.unlock_shares(outcome, sell_cancelled)
+                .map_err(|_| {
+                    msg!("Error: Failed to unlock IOC/dust remainder for seller");
+                    PredictionMarketError::InsufficientPosition
+                })?;
+        }
+        
+        seller_position.serialize(&mut &mut seller_position_info.data.borrow_mut()[..])?;
+        sell_order.serialize(&mut &mut sell_order_info.data.borrow_mut()[..])?;
+        if let Some(stats) = seller_stats {
+            record_trader_volume(program_id, stats, &sell_order.owner, relayer_info, system_program_info, trade_cost, current_time)?;
+        }
+        
+        unfilled -= match_amount;
+        fills.push((match_amount, exec_price));
+        total_cost = total_cost
+            .checked_add(trade_cost)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        buyer_debit = trade_cost
+            .checked_add(taker_fee)
+            .and_then(|v| v.checked_add(buyer_debit))
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        fund_fee = fund_fee
+            .checked_add(level_fund_fee)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        
+        let outcome_u8 = outcome as u8;
+        msg!("trade_executed:{},{},{},{},{},{},{},{}", args.market_id, args.taker_order_id, maker_order_id, buy_order.owner, sell_order.owner, outcome_u8, exec_price, match_amount);
+        PredictionMarketEvent::TradeExecuted(events::TradeExecuted {
+            market_id: args.market_id,
+            outcome_index: outcome_u8,
+            buy_order_id: buy_order.order_id,
+            sell_order_id: sell_order.order_id,
+            buyer: buy_order.owner,
+            seller: sell_order.owner,
+            price: exec_price,
+            amount: match_amount,
+        }).emit()?;
+        if sell_cancelled > 0 {
+            msg!("order_cancelled:{},{}", args.market_id, maker_order_id);
+            PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: *maker_order_id }).emit()?;
+        }
+        if maker_rebate > 0 {
+            msg!("maker_rebate:{},{},{},{}", args.market_id, maker_order_id, sell_order.owner, maker_rebate);
+        }
     }
     
-    let (seller_position_pda, _) = Pubkey::find_program_address(
-        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, seller_owner.as_ref()],
-        program_id,
-    );
-    if *seller_position_info.key != seller_position_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
+    let filled: u64 = fills.iter().map(|(amount, _)| amount).sum();
+    if filled == 0 {
+        return Err(PredictionMarketError::NoMatchableAmount.into());
     }
     
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
-    if *config_info.key != config_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
+    // The taker is filled once for the whole sweep
+    buy_order.check_fill_or_kill(filled)?;
+    let buy_cancelled = buy_order.record_fill(filled, config.min_order_size, current_time)?;
+    
+    // PlaceOrder locked margin at the taker's price plus the full taker fee
+    // on it; the debit must fit in it
+    let margin_at_order_price = buy_margin(filled, buy_order.price, config.trade_fee_bps)?;
+    if buyer_debit > margin_at_order_price {
+        msg!("Error: Trade cost {} + fees exceed locked margin {}", total_cost, margin_at_order_price);
+        return Err(PredictionMarketError::InsufficientFundsForFee.into());
     }
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
-    // Step 1a: CPI - Settle buyer (deduct trade_cost from pm_locked)
-    msg!("CPI: MultiOutcome settle buyer - deduct {} from pm_locked", trade_cost);
+    msg!("CPI: Settle buyer - deduct {} from pm_locked", buyer_debit);
     cpi_prediction_settle_with_auto_init(
-        vault_program_info, vault_config_info, buyer_pm_user_info, config_info,
-        relayer_info, system_program_info, buyer_wallet_info,
-        trade_cost, 0, config_seeds,
+        vault_program_info,
+        vault_config_info,
+        buyer_pm_user_info,
+        config_info,
+        relayer_info,
+        system_program_info,
+        buyer_wallet_info,
+        buyer_debit,
+        0,
+        config_seeds,
     )?;
     
-    // Step 1b: Release excess margin back to buyer's available_balance.
-    // PlaceOrder locked margin at buy_order_price, but ExecuteTrade fills at exec_price.
-    // When exec_price < buy_order_price (common for IOC/Market orders), the difference
-    // must be returned: pm_locked -> available_balance.
-    let margin_at_order_price = (match_amount as u128)
-        .checked_mul(buy_order_price as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
-    
-    let excess_margin = margin_at_order_price.saturating_sub(trade_cost);
-    
-    if excess_margin > 0 {
-        msg!("CPI: MultiOutcome release excess margin {} (order_price={}, exec_price={}, margin_at_order={}, trade_cost={})",
-             excess_margin, buy_order_price, exec_price, margin_at_order_price, trade_cost);
+    // Better-priced levels and a cancelled IOC/dust remainder return margin
+    let release_amount = buy_order.settle_locked_margin(filled, buyer_debit, config.trade_fee_bps)?;
+    if release_amount > 0 {
+        msg!("CPI: Release excess margin {} to buyer", release_amount);
         cpi_release_from_prediction(
             vault_program_info,
             vault_config_info,
             buyer_vault_info,
             buyer_pm_user_info,
             config_info,
-            excess_margin,
+            release_amount,
             config_seeds,
         )?;
-    } else {
-        msg!("MultiOutcome: No excess margin: order_price={} == exec_price={}, trade_cost={}",
-             buy_order_price, exec_price, trade_cost);
     }
     
-    // Step 2: CPI - Settle seller (add to pending_settlement)
-    cpi_prediction_settle_with_auto_init(
+    if fund_fee > 0 {
+        let fund_pm_user_info = find_pm_user_account(
+            accounts,
+            &config.fund_program,
+            vault_program_info.key,
+            PredictionMarketError::MissingFundAccount,
+        )?;
+        msg!("CPI: Credit trade fees {} to Fund", fund_fee);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            fund_pm_user_info,
+            config_info,
+            0,
+            fund_fee,
+            config_seeds,
+        )?;
+    }
+    
+    for (referrer_pm_user_info, reward) in referral_payouts.iter() {
+        msg!("CPI: Credit referral reward {} to {}", reward, referrer_pm_user_info.key);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            referrer_pm_user_info,
+            config_info,
+            0,
+            *reward,
+            config_seeds,
+        )?;
+    }
+    
+    // Load or create the buyer Position (auto-init like ExecuteTradeV2)
+    let mut buyer_position = if buyer_position_info.data_is_empty() {
+        msg!("Creating buyer Position PDA (auto-init for ExecuteTradeMultiV2)");
+        let rent = Rent::get()?;
+        let space = Position::SIZE;
+        let position_seeds: &[&[u8]] = &[
+            POSITION_SEED,
+            &market_id_bytes,
+            buy_order.owner.as_ref(),
+            &[buyer_position_bump],
+        ];
+        invoke_signed(
+            &system_instruction::create_account(
+                relayer_info.key,
+                buyer_position_info.key,
+                rent.minimum_balance(space),
+                space as u64,
+                program_id,
+            ),
+            &[relayer_info.clone(), buyer_position_info.clone(), system_program_info.clone()],
+            &[position_seeds],
+        )?;
+        Position::new(market.market_id, buy_order.owner, buyer_position_bump, current_time)
+    } else {
+        let position = deserialize_account::<Position>(&buyer_position_info.data.borrow())?;
+        if position.discriminator != POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        position
+    };
+    
+    for (amount, price) in &fills {
+        buyer_position.add_tokens(outcome, *amount, *price, current_time)?;
+    }
+    // The debit was settled from pm_locked above; ClaimWinnings must not release it again
+    buyer_position.settled_cost_e6 = buyer_position.settled_cost_e6.saturating_add(buyer_debit);
+    buyer_position.serialize(&mut &mut buyer_position_info.data.borrow_mut()[..])?;
+    buy_order.serialize(&mut &mut buy_order_info.data.borrow_mut()[..])?;
+    if let Some(stats) = buyer_stats {
+        record_trader_volume(program_id, stats, &buy_order.owner, relayer_info, system_program_info, total_cost, current_time)?;
+    }
+    
+    accumulate_volume(&mut market, &mut config, total_cost);
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ ExecuteTradeMultiV2 completed");
+    msg!("Taker order: {}, Levels filled: {}, Amount: {}, Cost: {}",
+         args.taker_order_id, fills.len(), filled, total_cost);
+    if buy_cancelled > 0 {
+        msg!("order_cancelled:{},{}", args.market_id, args.taker_order_id);
+        PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.taker_order_id }).emit()?;
+    }
+    for (referrer_pm_user_info, reward) in referral_payouts.iter() {
+        msg!("referral_reward:{},{},{}", args.market_id, referrer_pm_user_info.key, reward);
+    }
+    
+    Ok(())
+}
+
+/// V2: ExecuteMultiOutcomeTrade using Vault CPI (no SPL Token)
+/// 
+/// Direct trade between buyer and seller for multi-outcome markets:
+/// - Buyer has USDC locked in pm_locked (from RelayerPlaceMultiOutcomeOrderV2)
+/// - Seller has virtual shares in MultiOutcomePosition PDA
+/// - Trade transfers USDC (buyer → seller) and shares (seller → buyer)
+/// 
+/// Key differences from ExecuteTradeV2:
+/// 1. Uses MULTI_OUTCOME_POSITION_SEED for Position PDA derivation
+/// 2. Deserializes MultiOutcomePosition (893 bytes) instead of Position (154 bytes)
+/// 3. Uses holdings[outcome_index] / locked[outcome_index] instead of yes_amount/no_amount
+/// 
+/// Flow:
+/// 1. Validate orders (same outcome_index, price compatible, sufficient amounts)
+/// 2. Validate seller has sufficient locked shares in MultiOutcomePosition
+/// 3. CPI: Settle buyer (locked=cost, settlement=0) - deduct from buyer's pm_locked
+/// 4. CPI: Settle seller (locked=0, settlement=cost) - add to seller's pending_settlement  
+/// 5. Update MultiOutcomePositions: transfer shares from seller to buyer
+/// 6. Update Orders: mark filled/partial_filled
+fn process_execute_multi_outcome_trade_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ExecuteMultiOutcomeTradeV2Args,
+) -> ProgramResult {
+    use crate::state::{MULTI_OUTCOME_POSITION_SEED};
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    let config_info = next_account_info(account_info_iter)?;
+    let market_info = next_account_info(account_info_iter)?;
+    let buy_order_info = next_account_info(account_info_iter)?;
+    let sell_order_info = next_account_info(account_info_iter)?;
+    let buyer_position_info = next_account_info(account_info_iter)?;
+    let seller_position_info = next_account_info(account_info_iter)?;
+    let buyer_vault_info = next_account_info(account_info_iter)?;  // Account 7: for excess margin refund
+    let buyer_pm_user_info = next_account_info(account_info_iter)?;
+    let _seller_vault_info = next_account_info(account_info_iter)?;
+    let seller_pm_user_info = next_account_info(account_info_iter)?;
+    let vault_config_info = next_account_info(account_info_iter)?;
+    let vault_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let buyer_wallet_info = next_account_info(account_info_iter)?;
+    let seller_wallet_info = next_account_info(account_info_iter)?;
+    
+    // Load config (small struct, ok on stack)
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
+    }
+    
+    // Verify Market PDA and load market in a scope to limit lifetime
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let (market_pda, _) = Pubkey::find_program_address(&[MARKET_SEED, &market_id_bytes], program_id);
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Extract market info we need, then drop the large struct
+    let (market_id, num_outcomes, tradeable, is_multi_outcome) = {
+        let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+        if market.discriminator != MARKET_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        (market.market_id, market.num_outcomes, market.check_tradeable(), market.market_type == MarketType::MultiOutcome)
+    };
+    
+    tradeable?;
+    if !is_multi_outcome {
+        return Err(PredictionMarketError::InvalidMarketType.into());
+    }
+    if args.outcome_index >= num_outcomes {
+        return Err(PredictionMarketError::InvalidOutcome.into());
+    }
+    
+    // Verify Order PDAs
+    let buy_order_id_bytes = args.buy_order_id.to_le_bytes();
+    let (buy_order_pda, _) = Pubkey::find_program_address(&[ORDER_SEED, &market_id_bytes, &buy_order_id_bytes], program_id);
+    if *buy_order_info.key != buy_order_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let sell_order_id_bytes = args.sell_order_id.to_le_bytes();
+    let (sell_order_pda, _) = Pubkey::find_program_address(&[ORDER_SEED, &market_id_bytes, &sell_order_id_bytes], program_id);
+    if *sell_order_info.key != sell_order_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load orders and extract what we need (including buy_order_price for excess margin calc
+    // and the IOC or dust remainders cancelled by this fill)
+    let (buyer_owner, seller_owner, match_amount, exec_price, trade_cost, buy_order_price,
+         buy_cancelled, sell_cancelled) = {
+        let buy_order = deserialize_account::<Order>(&buy_order_info.data.borrow())?;
+        let sell_order = deserialize_account::<Order>(&sell_order_info.data.borrow())?;
+        
+        if buy_order.discriminator != ORDER_DISCRIMINATOR || sell_order.discriminator != ORDER_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        if buy_order.side != crate::state::OrderSide::Buy {
+            return Err(PredictionMarketError::InvalidOrderSide.into());
+        }
+        if sell_order.side != crate::state::OrderSide::Sell {
+            return Err(PredictionMarketError::InvalidOrderSide.into());
+        }
+        if buy_order.outcome_index != sell_order.outcome_index || buy_order.outcome_index != args.outcome_index {
+            return Err(PredictionMarketError::OutcomeMismatch.into());
+        }
+        if !buy_order.is_active() || !sell_order.is_active() {
+            return Err(PredictionMarketError::OrderNotActive.into());
+        }
+        if buy_order.price < sell_order.price {
+            return Err(PredictionMarketError::PriceMismatch.into());
+        }
+        
+        let match_amt = args.amount.min(buy_order.remaining_amount()).min(sell_order.remaining_amount());
+        if match_amt == 0 {
+            return Err(PredictionMarketError::NoMatchableAmount.into());
+        }
+        
+        // FOK orders must be filled completely by this match
+        buy_order.check_fill_or_kill(match_amt)?;
+        sell_order.check_fill_or_kill(match_amt)?;
+        let buy_cancelled = buy_order.cancelled_remainder(match_amt, config.min_order_size);
+        let sell_cancelled = sell_order.cancelled_remainder(match_amt, config.min_order_size);
+        
+        let price = args.price;
+        validate_price(price)?;
+        if price < sell_order.price || price > buy_order.price {
+            return Err(PredictionMarketError::InvalidExecutionPrice.into());
+        }
+        
+        let cost = mul_div_floor(match_amt, price, PRICE_PRECISION)?;
+        
+        (buy_order.owner, sell_order.owner, match_amt, price, cost, buy_order.price,
+         buy_cancelled, sell_cancelled)
+    };
+    
+    let current_time = get_current_timestamp()?;
+    
+    msg!("V2 MultiOutcome DirectTrade: m={}, o={}, amt={}, cost={}", 
+         args.market_id, args.outcome_index, match_amount, trade_cost);
+    
+    // Verify Position PDAs
+    let (buyer_position_pda, buyer_position_bump) = Pubkey::find_program_address(
+        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, buyer_owner.as_ref()],
+        program_id,
+    );
+    if *buyer_position_info.key != buyer_position_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let (seller_position_pda, _) = Pubkey::find_program_address(
+        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, seller_owner.as_ref()],
+        program_id,
+    );
+    if *seller_position_info.key != seller_position_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    // Step 1a: CPI - Settle buyer (deduct trade_cost from pm_locked)
+    msg!("CPI: MultiOutcome settle buyer - deduct {} from pm_locked", trade_cost);
+    cpi_prediction_settle_with_auto_init(
+        vault_program_info, vault_config_info, buyer_pm_user_info, config_info,
+        relayer_info, system_program_info, buyer_wallet_info,
+        trade_cost, 0, config_seeds,
+    )?;
+    
+    // Step 1b: Release excess margin back to buyer's available_balance.
+    // PlaceOrder locked margin at buy_order_price, but ExecuteTrade fills at exec_price.
+    // When exec_price < buy_order_price (common for IOC/Market orders), the difference
+    // must be returned: pm_locked -> available_balance. A cancelled IOC
+    // remainder returns its whole margin as well. The order's locked_margin_e6
+    // tracks what is left so a later cancel releases exactly the rest.
+    let release_amount = {
+        let mut buy_order = deserialize_account::<Order>(&buy_order_info.data.borrow())?;
+        buy_order.record_fill(match_amount, config.min_order_size, current_time)?;
+        let release = buy_order.settle_locked_margin(match_amount, trade_cost, 0)?;
+        buy_order.serialize(&mut &mut buy_order_info.data.borrow_mut()[..])?;
+        release
+    };
+    
+    if release_amount > 0 {
+        msg!("CPI: MultiOutcome release excess margin {} (IOC/dust remainder {}, order_price={}, exec_price={}, trade_cost={})",
+             release_amount, buy_cancelled, buy_order_price, exec_price, trade_cost);
+        cpi_release_from_prediction(
+            vault_program_info,
+            vault_config_info,
+            buyer_vault_info,
+            buyer_pm_user_info,
+            config_info,
+            release_amount,
+            config_seeds,
+        )?;
+    } else {
+        msg!("MultiOutcome: No excess margin: order_price={} == exec_price={}, trade_cost={}",
+             buy_order_price, exec_price, trade_cost);
+    }
+    
+    // Step 2: CPI - Settle seller (add to pending_settlement)
+    cpi_prediction_settle_with_auto_init(
         vault_program_info, vault_config_info, seller_pm_user_info, config_info,
         relayer_info, system_program_info, seller_wallet_info,
         0, trade_cost, config_seeds,
@@ -3993,8 +5682,8 @@ fn process_execute_multi_outcome_trade_v2(
     // This ensures only one MultiOutcomePosition (893 bytes) is on stack at a time
     let outcome_idx = args.outcome_index as usize;
     
-    // Scope 1: Update seller position
-    {
+    // Scope 1: Update seller position, returning the realized (before, after) PnL
+    let seller_pnl = {
         use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR};
         let mut data = seller_position_info.data.borrow_mut();
         let mut pos = deserialize_account::<MultiOutcomePosition>(&data)?;
@@ -4004,10 +5693,15 @@ fn process_execute_multi_outcome_trade_v2(
         if pos.locked[outcome_idx] < match_amount {
             return Err(PredictionMarketError::InsufficientPosition.into());
         }
+        let pnl_before = pos.realized_pnl;
         pos.consume_locked_shares(args.outcome_index, match_amount, exec_price, current_time)
             .map_err(|_| PredictionMarketError::InsufficientPosition)?;
+        // A cancelled IOC sell returns its unfilled shares to the seller
+        pos.unlock_shares(args.outcome_index, sell_cancelled)
+            .map_err(|_| PredictionMarketError::InsufficientPosition)?;
         pos.serialize(&mut &mut data[..])?;
-    }
+        (pnl_before, pos.realized_pnl)
+    };
     
     // Scope 2: Update or create buyer position
     {
@@ -4031,55 +5725,57 @@ fn process_execute_multi_outcome_trade_v2(
                 &[position_seeds],
             )?;
             let mut pos = MultiOutcomePosition::new(market_id, num_outcomes, buyer_owner, buyer_position_bump, current_time);
-            pos.add_tokens(args.outcome_index, match_amount, exec_price, current_time);
+            pos.add_tokens(args.outcome_index, match_amount, exec_price, current_time)?;
             pos.settled_cost_e6 = pos.settled_cost_e6.saturating_add(trade_cost);
             pos.serialize(&mut *buyer_position_info.data.borrow_mut())?;
         } else {
             let mut data = buyer_position_info.data.borrow_mut();
             let mut pos = deserialize_account::<MultiOutcomePosition>(&data)?;
-            pos.add_tokens(args.outcome_index, match_amount, exec_price, current_time);
+            pos.add_tokens(args.outcome_index, match_amount, exec_price, current_time)?;
             pos.settled_cost_e6 = pos.settled_cost_e6.saturating_add(trade_cost);
             pos.serialize(&mut &mut data[..])?;
         }
     }
     
-    // Update orders
-    {
-        let mut buy_order = deserialize_account::<Order>(&buy_order_info.data.borrow())?;
-        buy_order.filled_amount += match_amount;
-        buy_order.status = if buy_order.filled_amount >= buy_order.amount {
-            OrderStatus::Filled
-        } else {
-            OrderStatus::PartialFilled
-        };
-        buy_order.updated_at = current_time;
-        buy_order.serialize(&mut *buy_order_info.data.borrow_mut())?;
-    }
-    
+    // Update the sell order (the buy order was updated with its margin above)
     {
-        let mut sell_order = deserialize_account::<Order>(&sell_order_info.data.borrow())?;
-        sell_order.filled_amount += match_amount;
-        sell_order.status = if sell_order.filled_amount >= sell_order.amount {
-            OrderStatus::Filled
-        } else {
-            OrderStatus::PartialFilled
-        };
-        sell_order.updated_at = current_time;
-        sell_order.serialize(&mut *sell_order_info.data.borrow_mut())?;
+        let mut order = deserialize_account::<Order>(&sell_order_info.data.borrow())?;
+        order.record_fill(match_amount, config.min_order_size, current_time)?;
+        order.serialize(&mut &mut sell_order_info.data.borrow_mut()[..])?;
     }
     
     // Update market stats
     {
         let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-        market.total_volume_e6 = market.total_volume_e6.saturating_add(trade_cost as i64);
+        accumulate_volume(&mut market, &mut config, trade_cost);
+        market.record_realized_pnl(seller_pnl.0, seller_pnl.1)?;
         market.updated_at = current_time;
         market.serialize(&mut *market_info.data.borrow_mut())?;
+        config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     }
     
     msg!("✅ ExecuteMultiOutcomeTradeV2: m={}, amt={}", args.market_id, match_amount);
     msg!("multi_outcome_trade_executed:{},{},{},{},{},{},{},{}", 
          args.market_id, seller_wallet_info.key, buyer_wallet_info.key, 
          exec_price, match_amount, args.sell_order_id, args.buy_order_id, 0u64);
+    PredictionMarketEvent::TradeExecuted(events::TradeExecuted {
+        market_id: args.market_id,
+        outcome_index: args.outcome_index,
+        buy_order_id: args.buy_order_id,
+        sell_order_id: args.sell_order_id,
+        buyer: *buyer_wallet_info.key,
+        seller: *seller_wallet_info.key,
+        price: exec_price,
+        amount: match_amount,
+    }).emit()?;
+    if buy_cancelled > 0 {
+        msg!("order_cancelled:{},{}", args.market_id, args.buy_order_id);
+        PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.buy_order_id }).emit()?;
+    }
+    if sell_cancelled > 0 {
+        msg!("order_cancelled:{},{}", args.market_id, args.sell_order_id);
+        PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.sell_order_id }).emit()?;
+    }
     
     Ok(())
 }
@@ -4093,6 +5789,7 @@ fn process_execute_multi_outcome_trade_v2(
 /// Multi-outcome Complete Set Mint:
 /// When sum of all outcome buy prices <= 1.0, lock buyer funds via Vault CPI
 /// and record virtual token holdings in MultiOutcomePosition PDA.
+/// Every outcome's order is validated before the first Vault CPI.
 /// 
 /// Account layout:
 /// 0. [signer] Relayer/Matcher
@@ -4123,7 +5820,7 @@ fn process_match_mint_multi_v2(
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
@@ -4133,8 +5830,13 @@ fn process_match_mint_multi_v2(
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
+    if !config.allow_match_mint() {
+        msg!("Error: Match-minting is disabled, use RelayerMintMultiOutcomeCompleteSetV2");
+        return Err(PredictionMarketError::MatchMintDisabled.into());
+    }
+    
     // Verify relayer authorization
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
     
     // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
@@ -4148,9 +5850,7 @@ fn process_match_mint_multi_v2(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    market.check_tradeable()?;
     
     // Verify market is multi-outcome type
     if market.market_type != MarketType::MultiOutcome {
@@ -4175,13 +5875,13 @@ fn process_match_mint_multi_v2(
         return Err(PredictionMarketError::InvalidArgument.into());
     }
     
-    // Validate price sum == 1.0 (exactly 100¢ for perfect fund balance)
-    // This ensures $1 locked = $1 settlement, avoiding fund shortage or excess
-    let total_price: u64 = args.orders.iter().map(|(_, _, p)| p).sum();
-    if total_price != PRICE_PRECISION {
-        msg!("Total price {} != 1.0 ({}) - must be exactly 100¢", total_price, PRICE_PRECISION);
-        return Err(PredictionMarketError::InvalidPricePair.into());
+    // Validate 1.0 - dev <= price sum <= 1.0 (exactly 100¢ for perfect fund balance,
+    // any shortfall bounded by max_price_sum_deviation)
+    for (_, _, price) in &args.orders {
+        validate_price(*price)?;
     }
+    let total_price: u64 = args.orders.iter().map(|(_, _, p)| p).sum();
+    validate_mint_price_sum(total_price, config.max_price_sum_deviation)?;
     
     // Account 3: VaultConfig
     let vault_config_info = next_account_info(account_info_iter)?;
@@ -4189,6 +5889,8 @@ fn process_match_mint_multi_v2(
     // Account 4: Vault Program
     let vault_program_info = next_account_info(account_info_iter)?;
     
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
     // Account 5: System Program
     let system_program_info = next_account_info(account_info_iter)?;
     
@@ -4197,6 +5899,7 @@ fn process_match_mint_multi_v2(
     let market_id_bytes = args.market_id.to_le_bytes();
     let current_time = get_current_timestamp()?;
     let match_amount = args.amount;
+    market.check_tvl_cap(match_amount)?;
     
     // Derive Config PDA for CPI signing
     let (config_pda, config_bump) = Pubkey::find_program_address(
@@ -4210,7 +5913,10 @@ fn process_match_mint_multi_v2(
     
     let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
-    // Process each outcome
+    // Pass 1: validate every outcome's order before any Vault CPI, so the
+    // match is all-or-nothing without relying on the transaction rollback
+    let mut legs = Vec::with_capacity(args.num_outcomes as usize);
+    let mut notional_e6 = 0u64;
     for i in 0..args.num_outcomes as usize {
         let (expected_outcome_idx, order_id, price) = args.orders[i];
         
@@ -4238,7 +5944,7 @@ fn process_match_mint_multi_v2(
         }
         
         // Load and validate order
-        let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
+        let order = deserialize_account::<Order>(&order_info.data.borrow())?;
         
         if order.discriminator != ORDER_DISCRIMINATOR {
             return Err(PredictionMarketError::InvalidAccountData.into());
@@ -4262,19 +5968,32 @@ fn process_match_mint_multi_v2(
             return Err(PredictionMarketError::OrderNotActive.into());
         }
         
+        // Buyers are never charged above their limit price
+        if price > order.price {
+            msg!("Error: Match price {} exceeds buy limit {} for order {}", price, order.price, order_id);
+            return Err(PredictionMarketError::PriceMismatch.into());
+        }
+        
         // Verify remaining amount
         let remaining = order.remaining_amount();
         if remaining < match_amount {
             msg!("Error: Order {} remaining {} < match_amount {}", order_id, remaining, match_amount);
             return Err(PredictionMarketError::InvalidAmount.into());
         }
+        order.check_fill_or_kill(match_amount)?;
         
         // Calculate buyer cost: cost = amount * price / 1_000_000
-        let buyer_cost = (match_amount as u128)
-            .checked_mul(price as u128)
-            .ok_or(PredictionMarketError::ArithmeticOverflow)?
-            .checked_div(PRICE_PRECISION as u128)
-            .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
+        let buyer_cost = mul_div_floor(match_amount, price, PRICE_PRECISION)?;
+        notional_e6 = safe_add_u64(notional_e6, buyer_cost)?;
+        
+        legs.push((order_info, position_info, user_account_info, pm_user_account_info, order, buyer_cost));
+    }
+    
+    // Pass 2: every order is fillable; settle each buyer and record holdings
+    for (i, (order_info, position_info, user_account_info, pm_user_account_info, mut order, buyer_cost))
+        in legs.into_iter().enumerate()
+    {
+        let (expected_outcome_idx, order_id, _) = args.orders[i];
         
         // CPI: Settle buyer — consume PlaceOrder's locked margin (NOT Lock!)
         // PlaceOrder already locked the margin. MatchMintMulti consumes it via Settle
@@ -4293,17 +6012,22 @@ fn process_match_mint_multi_v2(
         // Update MultiOutcomePosition: add holdings
         // Note: Position should be initialized beforehand
         // If not, initialize a new one
-        let mut position = if position_info.data_len() > 0 && position_info.data.borrow()[0] != 0 {
-            deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?
-        } else {
-            // Initialize new position using constructor
-            MultiOutcomePosition::new(
-                args.market_id,
-                args.num_outcomes,
-                order.owner,
-                0, // bump will be calculated if needed
-                current_time,
-            )
+        let existing = try_deserialize_initialized::<MultiOutcomePosition>(
+            &position_info.data.borrow(),
+            MULTI_OUTCOME_POSITION_DISCRIMINATOR,
+        )?;
+        let mut position = match existing {
+            Some(pos) => pos,
+            None => {
+                // Initialize new position using constructor
+                MultiOutcomePosition::new(
+                    args.market_id,
+                    args.num_outcomes,
+                    order.owner,
+                    0, // bump will be calculated if needed
+                    current_time,
+                )
+            }
         };
         
         // Add to holdings for this outcome
@@ -4316,17 +6040,30 @@ fn process_match_mint_multi_v2(
         // Track settled cost for ClaimWinnings (avoids double pm_locked release)
         position.settled_cost_e6 = position.settled_cost_e6.saturating_add(buyer_cost);
         position.updated_at = current_time;
-        position.serialize(&mut *position_info.data.borrow_mut())?;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
         
-        // Update order
-        order.filled_amount = order.filled_amount.saturating_add(match_amount);
-        if order.filled_amount >= order.amount {
-            order.status = OrderStatus::Filled;
-        } else {
-            order.status = OrderStatus::PartialFilled;
+        // Update order; a fill below the limit price or a cancelled IOC/dust
+        // remainder returns the margin it no longer needs
+        let cancelled = order.record_fill(match_amount, config.min_order_size, current_time)?;
+        let release = order.settle_locked_margin(match_amount, buyer_cost, 0)?;
+        order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
+        
+        if release > 0 {
+            msg!("CPI: Release {} excess margin (IOC/dust remainder {}) for order {}", release, cancelled, order_id);
+            cpi_release_from_prediction(
+                vault_program_info,
+                vault_config_info,
+                user_account_info,
+                pm_user_account_info,
+                config_info,
+                release,
+                config_seeds,
+            )?;
+        }
+        if cancelled > 0 {
+            msg!("order_cancelled:{},{}", args.market_id, order_id);
+            PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id }).emit()?;
         }
-        order.updated_at = current_time;
-        order.serialize(&mut *order_info.data.borrow_mut())?;
         
         msg!("Outcome {}: order={}, cost={}, new_holding={}", 
              expected_outcome_idx, order_id, buyer_cost, position.holdings[holding_idx]);
@@ -4334,9 +6071,11 @@ fn process_match_mint_multi_v2(
     
     // Update market stats
     market.total_minted = market.total_minted.saturating_add(match_amount);
-    market.total_volume_e6 = market.total_volume_e6.saturating_add((match_amount as i64) * (total_price as i64) / 1_000_000);
+    market.increase_open_interest(match_amount)?;
+    accumulate_volume(&mut market, &mut config, notional_e6);
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     // NOTE: Fee collection will be implemented in Vault Program layer (V2 architecture)
     
@@ -4370,7 +6109,7 @@ fn process_match_burn_multi_v2(
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
@@ -4380,7 +6119,7 @@ fn process_match_burn_multi_v2(
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
     
     // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
@@ -4394,9 +6133,7 @@ fn process_match_burn_multi_v2(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    market.check_tradeable()?;
     
     if market.market_type != MarketType::MultiOutcome {
         msg!("Error: MatchBurnMultiV2 requires MultiOutcome market type");
@@ -4416,12 +6153,12 @@ fn process_match_burn_multi_v2(
         return Err(PredictionMarketError::InvalidArgument.into());
     }
     
-    // Validate price sum >= 1.0 (price conservation for burning)
-    let total_price: u64 = args.orders.iter().map(|(_, _, p)| p).sum();
-    if total_price < PRICE_PRECISION {
-        msg!("Total price {} < 1.0 ({})", total_price, PRICE_PRECISION);
-        return Err(PredictionMarketError::InvalidPricePair.into());
+    // Validate 1.0 <= price sum <= 1.0 + dev (price conservation for burning)
+    for (_, _, price) in &args.orders {
+        validate_price(*price)?;
     }
+    let total_price: u64 = args.orders.iter().map(|(_, _, p)| p).sum();
+    validate_burn_price_sum(total_price, config.max_price_sum_deviation)?;
     
     // Account 3: VaultConfig
     let vault_config_info = next_account_info(account_info_iter)?;
@@ -4429,6 +6166,8 @@ fn process_match_burn_multi_v2(
     // Account 4: Vault Program
     let vault_program_info = next_account_info(account_info_iter)?;
     
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
     // Account 5: System Program
     let _system_program_info = next_account_info(account_info_iter)?;
     
@@ -4451,6 +6190,7 @@ fn process_match_burn_multi_v2(
     let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
     // Process each outcome
+    let mut notional_e6 = 0u64;
     for i in 0..args.num_outcomes as usize {
         let (expected_outcome_idx, order_id, price) = args.orders[i];
         
@@ -4500,6 +6240,8 @@ fn process_match_burn_multi_v2(
             msg!("Error: Order remaining {} < match_amount {}", remaining, match_amount);
             return Err(PredictionMarketError::InvalidAmount.into());
         }
+        order.check_fill_or_kill(match_amount)?;
+        let cancelled = order.record_fill(match_amount, config.min_order_size, current_time)?;
         
         // Load and validate position
         let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
@@ -4521,11 +6263,8 @@ fn process_match_burn_multi_v2(
         }
         
         // Calculate seller proceeds: proceeds = amount * price / 1_000_000
-        let seller_proceeds = (match_amount as u128)
-            .checked_mul(price as u128)
-            .ok_or(PredictionMarketError::ArithmeticOverflow)?
-            .checked_div(PRICE_PRECISION as u128)
-            .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
+        let seller_proceeds = mul_div_floor(match_amount, price, PRICE_PRECISION)?;
+        notional_e6 = safe_add_u64(notional_e6, seller_proceeds)?;
         
         // CPI: Settle seller funds via Vault (locked=0, settlement=proceeds)
         msg!("CPI: Settle {} for outcome {} seller", seller_proceeds, expected_outcome_idx);
@@ -4540,22 +6279,24 @@ fn process_match_burn_multi_v2(
         )?;
         
         // Update position: consume locked shares (unlock + reduce holdings)
+        let pnl_before = position.realized_pnl;
         position.consume_locked_shares(expected_outcome_idx, match_amount, price, current_time)
             .map_err(|_| {
                 msg!("Error: Failed to consume locked shares for outcome {}", expected_outcome_idx);
                 PredictionMarketError::InsufficientPosition
             })?;
+        market.record_realized_pnl(pnl_before, position.realized_pnl)?;
+        // A cancelled IOC/dust remainder returns its shares to the seller
+        position.unlock_shares(expected_outcome_idx, cancelled)
+            .map_err(|_| PredictionMarketError::InsufficientPosition)?;
         position.serialize(&mut *position_info.data.borrow_mut())?;
         
         // Update order
-        order.filled_amount = order.filled_amount.saturating_add(match_amount);
-        if order.filled_amount >= order.amount {
-            order.status = OrderStatus::Filled;
-        } else {
-            order.status = OrderStatus::PartialFilled;
-        }
-        order.updated_at = current_time;
         order.serialize(&mut *order_info.data.borrow_mut())?;
+        if cancelled > 0 {
+            msg!("order_cancelled:{},{}", args.market_id, order_id);
+            PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id }).emit()?;
+        }
         
         msg!("Outcome {}: order={}, proceeds={}, remaining_holding={}", 
              expected_outcome_idx, order_id, seller_proceeds, position.holdings[holding_idx]);
@@ -4563,9 +6304,11 @@ fn process_match_burn_multi_v2(
     
     // Update market stats
     market.total_minted = market.total_minted.saturating_sub(match_amount);
-    market.total_volume_e6 = market.total_volume_e6.saturating_add((match_amount as i64) * (total_price as i64) / 1_000_000);
+    market.decrease_open_interest(match_amount);
+    accumulate_volume(&mut market, &mut config, notional_e6);
     market.updated_at = current_time;
     market.serialize(&mut *market_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
     // NOTE: Fee collection will be implemented in Vault Program layer (V2 architecture)
     
@@ -4608,7 +6351,7 @@ fn process_relayer_place_order_v2(
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
     
     // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
@@ -4622,9 +6365,7 @@ fn process_relayer_place_order_v2(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    market.check_tradeable()?;
     
     // Account 3: Order PDA (writable, new)
     let order_info = next_account_info(account_info_iter)?;
@@ -4644,6 +6385,8 @@ fn process_relayer_place_order_v2(
     // Account 8: Vault Program
     let vault_program_info = next_account_info(account_info_iter)?;
     
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
     // Account 9: System Program
     let system_program_info = next_account_info(account_info_iter)?;
     
@@ -4660,15 +6403,49 @@ fn process_relayer_place_order_v2(
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
+    // Account 10: Instructions sysvar (Market orders only). A Market order
+    // never rests on the book: the next instruction must take it against
+    // the makers the relayer picked, and its price is the slippage cap the
+    // margin is locked at.
+    if args.order_type == crate::state::OrderType::Market {
+        // The fills take a Buy taker against resting Sell makers
+        if args.side != crate::state::OrderSide::Buy {
+            msg!("Error: Market orders must be Buy orders");
+            return Err(PredictionMarketError::InvalidOrderSide.into());
+        }
+        let instructions_info = next_account_info(account_info_iter)?;
+        verify_market_order_fill(program_id, instructions_info, args.market_id, order_id)?;
+    }
+    
+    // Account 10: Best opposing order (PostOnly orders only). An empty
+    // account stands for an empty opposing side of the book.
+    if args.order_type == crate::state::OrderType::PostOnly {
+        let best_opposing_info = next_account_info(account_info_iter)?;
+        check_post_only(program_id, best_opposing_info, args.market_id, args.side, args.outcome, args.price)?;
+    }
+    
+    config.check_order_minimum(args.amount, args.price)?;
+    
+    if args.reduce_only {
+        check_reduce_only(
+            program_id,
+            position_info,
+            args.market_id,
+            &args.user_wallet,
+            args.side,
+            args.outcome,
+            args.amount,
+        )?;
+    }
+    
     // Calculate margin requirement (in e6 precision)
     // margin_e6 = amount_e6 × price_e6 / PRICE_PRECISION
     // Example: 100_000_000 (100 shares) × 500_000 (50¢) / 1_000_000 = 50_000_000 ($50)
     // All amounts are in e6 precision (1 share = 1_000_000 units).
-    let margin = (args.amount as u128)
-        .checked_mul(args.price as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
+    // A Buy is always the taker of ExecuteTradeV2, so it also locks the
+    // full taker fee on that cost: a fill at the limit price must fit in it.
+    let mut margin = buy_margin(args.amount, args.price, config.trade_fee_bps)?;
+    let mut amount = args.amount;
     
     let current_time = get_current_timestamp()?;
     
@@ -4687,7 +6464,7 @@ fn process_relayer_place_order_v2(
     // For Buy orders: Lock margin in Vault
     if args.side == crate::state::OrderSide::Buy {
         msg!("CPI: Lock margin {} for Buy order", margin);
-        cpi_lock_for_prediction(
+        let locked = cpi_lock_for_prediction(
             vault_program_info,
             vault_config_info,
             user_vault_info,
@@ -4698,6 +6475,18 @@ fn process_relayer_place_order_v2(
             margin,
             config_seeds,
         )?;
+        
+        // The Vault clamped to the available balance: size the order to
+        // the margin it actually locked, fee headroom included
+        if locked < margin {
+            amount = buy_amount_for_margin(locked, args.price, config.trade_fee_bps)?;
+            margin = locked;
+            if amount == 0 {
+                return Err(PredictionMarketError::InsufficientFunds.into());
+            }
+            config.check_order_minimum(amount, args.price)?;
+            msg!("Order resized to {} for locked margin {}", amount, locked);
+        }
     } else {
         // For Sell orders: Verify Position has sufficient AVAILABLE holdings and LOCK them
         let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
@@ -4724,7 +6513,7 @@ fn process_relayer_place_order_v2(
             .map_err(|_| PredictionMarketError::InsufficientPosition)?;
         
         position.updated_at = current_time;
-        position.serialize(&mut *position_info.data.borrow_mut())?;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
         
         msg!("📊 Position locked: {} {:?} shares", args.amount, args.outcome);
     }
@@ -4765,7 +6554,7 @@ fn process_relayer_place_order_v2(
         outcome: args.outcome,
         outcome_index,
         price: args.price,
-        amount: args.amount,
+        amount,
         filled_amount: 0,
         status: OrderStatus::Open,
         order_type: args.order_type,
@@ -4774,24 +6563,42 @@ fn process_relayer_place_order_v2(
         updated_at: current_time,
         bump: order_bump,
         escrow_token_account: None, // V2: No SPL token escrow
-        reserved: [0u8; 30],
+        locks_released: false,
+        margin_locked: args.side == crate::state::OrderSide::Buy,
+        locked_margin_e6: if args.side == crate::state::OrderSide::Buy { margin } else { 0 },
+        reduce_only: args.reduce_only,
+        reserved: [0u8; 19],
     };
-    order.serialize(&mut *order_info.data.borrow_mut())?;
+    order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
+    
+    // The first order naming a referrer records it for the user
+    if let Some(referrer) = args.referrer {
+        record_referrer(program_id, accounts, &args.user_wallet, referrer, relayer_info, system_program_info, current_time)?;
+    }
     
     // Update market
     market.next_order_id = market.next_order_id.saturating_add(1);
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     msg!("✅ RelayerPlaceOrderV2 completed");
     msg!("User: {}", args.user_wallet);
     msg!("Order ID: {}, Market: {}", order_id, args.market_id);
     msg!("Side: {:?}, Outcome: {:?}", args.side, args.outcome);
-    msg!("Price: {}, Amount: {}, Margin: {}", args.price, args.amount, margin);
+    msg!("Price: {}, Amount: {}, Margin: {}", args.price, amount, margin);
 
     let side_u8 = args.side as u8;
     let outcome_u8 = args.outcome as u8;
-    msg!("order_placed:{},{},{},{},{},{},{}", args.market_id, order_id, args.user_wallet, side_u8, outcome_u8, args.price, args.amount);
+    msg!("order_placed:{},{},{},{},{},{},{}", args.market_id, order_id, args.user_wallet, side_u8, outcome_u8, args.price, amount);
+    PredictionMarketEvent::OrderPlaced(events::OrderPlaced {
+        market_id: args.market_id,
+        order_id,
+        owner: args.user_wallet,
+        side: args.side,
+        outcome_index: outcome_u8,
+        price: args.price,
+        amount,
+    }).emit()?;
     
     Ok(())
 }
@@ -4822,7 +6629,7 @@ fn process_relayer_cancel_order_v2(
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
     
     // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
@@ -4862,7 +6669,7 @@ fn process_relayer_cancel_order_v2(
     }
     
     // Verify order is cancellable
-    if !order.is_active() {
+    if !order.is_cancellable() {
         return Err(PredictionMarketError::OrderNotActive.into());
     }
     
@@ -4881,18 +6688,16 @@ fn process_relayer_cancel_order_v2(
     // Account 8: Vault Program
     let vault_program_info = next_account_info(account_info_iter)?;
     
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
     // Account 9: System Program
     let _system_program_info = next_account_info(account_info_iter)?;
     
-    // Calculate remaining margin to unlock (in e6 precision)
-    // remaining_margin_e6 = remaining_e6 × price_e6 / PRICE_PRECISION
-    // Must use same formula as PlaceOrder margin to ensure exact release.
+    // Remaining margin to unlock: what placement locked less what fills
+    // consumed, tracked on the order so partial fills at other prices or a
+    // resized order can't leave dust locked or over-release
     let remaining = order.remaining_amount();
-    let remaining_margin = (remaining as u128)
-        .checked_mul(order.price as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
+    let remaining_margin = order.remaining_locked_margin();
     
     let current_time = get_current_timestamp()?;
     
@@ -4924,33 +6729,9 @@ fn process_relayer_cancel_order_v2(
             )?;
         }
     } else {
-        // For Sell orders: Unlock remaining shares from Position
+        // For Sell orders: Unlock remaining shares from the owner's position
         if remaining > 0 {
-            // Verify Position PDA
-            let (position_pda, _) = Pubkey::find_program_address(
-                &[POSITION_SEED, &market_id_bytes, order.owner.as_ref()],
-                program_id,
-            );
-            
-            if *position_info.key != position_pda {
-                msg!("Error: Invalid Position PDA for Sell order cancellation");
-                return Err(PredictionMarketError::InvalidPDA.into());
-            }
-            
-            let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
-            if position.discriminator != POSITION_DISCRIMINATOR {
-                return Err(PredictionMarketError::InvalidAccountData.into());
-            }
-            
-            // Unlock the remaining locked shares
-            position.unlock_shares(order.outcome, remaining)
-                .map_err(|_| {
-                    msg!("Error: Failed to unlock shares - locked amount mismatch");
-                    PredictionMarketError::InsufficientPosition
-                })?;
-            
-            position.updated_at = current_time;
-            position.serialize(&mut *position_info.data.borrow_mut())?;
+            unlock_sell_order_shares(program_id, &market, &order, position_info, remaining, current_time)?;
             
             msg!("📊 Position unlocked: {} {:?} shares for cancelled Sell order", remaining, order.outcome);
         }
@@ -4958,50 +6739,39 @@ fn process_relayer_cancel_order_v2(
     
     // Update order status
     order.status = OrderStatus::Cancelled;
+    order.locked_margin_e6 = 0;
     order.updated_at = current_time;
-    order.serialize(&mut *order_info.data.borrow_mut())?;
+    order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
     
     // Update market stats
     market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
     msg!("✅ RelayerCancelOrderV2 completed");
     msg!("User: {}", args.user_wallet);
     msg!("Order ID: {}, Market: {}", args.order_id, args.market_id);
     msg!("Remaining amount: {}, Unlocked margin: {}", remaining, remaining_margin);
     msg!("order_cancelled:{},{}", args.market_id, args.order_id);
+    PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.order_id }).emit()?;
     
     Ok(())
 }
 
-// ============================================================================
-// V2 WithFee Instructions
-// ============================================================================
-
-/// Process RelayerMintCompleteSetV2WithFee
-/// 
-/// Same as RelayerMintCompleteSetV2 but uses Vault.PredictionMarketLockWithFee
-/// to collect minting fee during the lock operation.
+/// V2: Batch RelayerCancelOrder using Vault CPI for margin unlock
 /// 
-/// Accounts:
-/// 0. `[signer]` Relayer
-/// 1. `[]` PredictionMarketConfig
-/// 2. `[writable]` Market
-/// 3. `[writable]` Position PDA
-/// 4. `[writable]` User Vault Account
-/// 5. `[writable]` PM User Account
-/// 6. `[]` Vault Config
-/// 7. `[]` Vault Program
-/// 8. `[]` System Program
-/// 9. `[writable]` Vault Token Account
-/// 10. `[writable]` PM Fee Vault
-/// 11. `[writable]` PM Fee Config PDA
-/// 12. `[]` Token Program
-fn process_relayer_mint_complete_set_v2_with_fee(
+/// Cancels up to MAX_BATCH_CANCEL_ORDERS orders of one market in one
+/// transaction. Orders that are already filled, cancelled or released are
+/// skipped so a stale entry does not abort the whole batch.
+fn process_relayer_batch_cancel_orders_v2(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: RelayerMintCompleteSetArgs,
+    args: RelayerBatchCancelOrdersV2Args,
 ) -> ProgramResult {
+    if args.order_ids.is_empty() || args.order_ids.len() > MAX_BATCH_CANCEL_ORDERS {
+        msg!("Error: Batch size {} out of range (1-{})", args.order_ids.len(), MAX_BATCH_CANCEL_ORDERS);
+        return Err(PredictionMarketError::BatchTooLarge.into());
+    }
+    
     let account_info_iter = &mut accounts.iter();
     
     // Account 0: Relayer (signer)
@@ -5014,241 +6784,242 @@ fn process_relayer_mint_complete_set_v2_with_fee(
     // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
     
-    // Account 3: Position PDA (writable)
-    let position_info = next_account_info(account_info_iter)?;
-    
-    // Account 4: User Vault Account (writable)
-    let user_vault_info = next_account_info(account_info_iter)?;
-    
-    // Account 5: PM User Account (writable)
-    let pm_user_account_info = next_account_info(account_info_iter)?;
-    
-    // Account 6: Vault Config
+    // Account 3: Vault Config
     let vault_config_info = next_account_info(account_info_iter)?;
     
-    // Account 7: Vault Program
+    // Account 4: Vault Program
     let vault_program_info = next_account_info(account_info_iter)?;
     
-    // Account 8: System Program
-    let system_program_info = next_account_info(account_info_iter)?;
-    
-    // Account 9: Vault Token Account (for fee transfer)
-    let vault_token_account_info = next_account_info(account_info_iter)?;
-    
-    // Account 10: PM Fee Vault
-    let pm_fee_vault_info = next_account_info(account_info_iter)?;
-    
-    // Account 11: PM Fee Config PDA
-    let pm_fee_config_info = next_account_info(account_info_iter)?;
-    
-    // Account 12: Token Program
-    let token_program_info = next_account_info(account_info_iter)?;
-    
-    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
-    
-    // Verify Relayer authority
-    verify_relayer(&config, relayer_info.key)?;
-    
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
-    // Load and validate market
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
     let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
-    
     if market.market_id != args.market_id {
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    let current_time = get_current_timestamp()?;
+    let mut cancelled = 0u32;
     
-    // Validate amount
-    if args.amount == 0 {
-        return Err(PredictionMarketError::InvalidAmount.into());
+    // Accounts 5..: (Order, Position, UserAccount, PM User Account) per order
+    for order_id in &args.order_ids {
+        let order_info = next_account_info(account_info_iter)?;
+        let position_info = next_account_info(account_info_iter)?;
+        let user_vault_info = next_account_info(account_info_iter)?;
+        let pm_user_info = next_account_info(account_info_iter)?;
+        
+        let (order_pda, _) = Pubkey::find_program_address(
+            &[ORDER_SEED, &market_id_bytes, &order_id.to_le_bytes()],
+            program_id,
+        );
+        if *order_info.key != order_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        
+        let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
+        if order.discriminator != ORDER_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        if !order.is_cancellable() {
+            msg!("cancel_skipped:{},{},{:?}", args.market_id, order_id, order.status);
+            continue;
+        }
+        
+        let remaining = order.remaining_amount();
+        let remaining_margin = order.remaining_locked_margin();
+        
+        if order.side == crate::state::OrderSide::Buy {
+            if remaining_margin > 0 {
+                verify_pm_user_account(pm_user_info, &order.owner, vault_program_info.key)?;
+                cpi_release_from_prediction(
+                    vault_program_info,
+                    vault_config_info,
+                    user_vault_info,
+                    pm_user_info,
+                    config_info,
+                    remaining_margin,
+                    config_seeds,
+                )?;
+            }
+        } else if remaining > 0 {
+            unlock_sell_order_shares(program_id, &market, &order, position_info, remaining, current_time)?;
+        }
+        
+        order.status = OrderStatus::Cancelled;
+        order.locked_margin_e6 = 0;
+        order.updated_at = current_time;
+        order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
+        cancelled += 1;
+        
+        msg!("order_cancelled:{},{}", args.market_id, order_id);
+        PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: *order_id }).emit()?;
     }
     
-    let current_time = get_current_timestamp()?;
-    let market_id_bytes = market.market_id.to_le_bytes();
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(
-        &[PM_CONFIG_SEED],
-        program_id,
-    );
+    msg!("✅ RelayerBatchCancelOrdersV2 completed");
+    msg!("Cancelled: {}, Skipped: {}", cancelled, args.order_ids.len() as u32 - cancelled);
     
-    if *config_info.key != config_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
-    }
+    Ok(())
+}
+
+/// Admin-only cancel of any user's order in a Paused or Cancelled market
+/// 
+/// Releases the order's remaining margin or shares back to its owner the
+/// same way RelayerCancelOrderV2 does. Works while the program is paused so
+/// operators can clear the book during an incident.
+fn process_force_cancel_order(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ForceCancelOrderArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Read PM Fee Config to calculate net_amount
-    // PM Fee Config offsets (matching Fund Program state.rs):
-    // - offset 41: minting_fee_bps (u16)
-    const PM_FEE_MINTING_BPS_OFFSET: usize = 41;
-    let pm_fee_config_data = pm_fee_config_info.try_borrow_data()?;
-    if pm_fee_config_data.len() < 50 {
-        msg!("❌ PM Fee Config not initialized");
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
-    let minting_fee_bps = u16::from_le_bytes([
-        pm_fee_config_data[PM_FEE_MINTING_BPS_OFFSET],
-        pm_fee_config_data[PM_FEE_MINTING_BPS_OFFSET + 1],
-    ]);
-    drop(pm_fee_config_data);
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
     
-    // Calculate fee and net_amount
-    let fee_amount = ((args.amount as u128) * (minting_fee_bps as u128) / 10000) as u64;
-    let net_amount = args.amount.saturating_sub(fee_amount);
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
     
-    msg!("Fee calculation: gross={}, fee_bps={}, fee={}, net={}", 
-         args.amount, minting_fee_bps, fee_amount, net_amount);
+    // Account 3: Order PDA (writable)
+    let order_info = next_account_info(account_info_iter)?;
     
-    // Step 1: CPI to Vault - PredictionMarketLockWithFee
-    // This locks the funds AND collects the minting fee
-    msg!("CPI: Vault.PredictionMarketLockWithFee gross_amount={}", args.amount);
-    cpi_lock_for_prediction_with_fee(
-        vault_program_info,
-        vault_config_info,
-        user_vault_info,
-        pm_user_account_info,
-        config_info,  // PM Config as caller program marker
-        vault_token_account_info,
-        pm_fee_vault_info,
-        pm_fee_config_info,
-        token_program_info,
-        relayer_info, // Payer for auto-init
-        system_program_info,
-        args.amount,
-        config_seeds,
-    )?;
+    // Account 4: Position PDA (for Sell order share unlock)
+    let position_info = next_account_info(account_info_iter)?;
     
-    // Step 2: Create or update Position PDA
-    let (position_pda, position_bump) = Pubkey::find_program_address(
-        &[POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
+    // Account 5: User Vault Account
+    let user_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: PM User Account
+    let pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can force-cancel orders");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    if market.status != MarketStatus::Paused && market.status != MarketStatus::Cancelled {
+        msg!("Error: Can only force-cancel orders of paused or cancelled markets");
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let (order_pda, _) = Pubkey::find_program_address(
+        &[ORDER_SEED, &market_id_bytes, &args.order_id.to_le_bytes()],
         program_id,
     );
-    
-    if *position_info.key != position_pda {
+    if *order_info.key != order_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let is_new_position = position_info.data_is_empty();
+    let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
+    if order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if !order.is_cancellable() {
+        return Err(PredictionMarketError::OrderNotActive.into());
+    }
     
-    if is_new_position {
-        // Create new Position account
-        let rent = Rent::get()?;
-        let space = Position::SIZE;
-        let lamports = rent.minimum_balance(space);
-        let position_seeds: &[&[u8]] = &[
-            POSITION_SEED, 
-            &market_id_bytes, 
-            args.user_wallet.as_ref(), 
-            &[position_bump]
-        ];
-        
-        invoke_signed(
-            &system_instruction::create_account(
-                relayer_info.key,
-                position_info.key,
-                lamports,
-                space as u64,
-                program_id,
-            ),
-            &[relayer_info.clone(), position_info.clone(), system_program_info.clone()],
-            &[position_seeds],
-        )?;
-        
-        let position = Position {
-            discriminator: POSITION_DISCRIMINATOR,
-            market_id: args.market_id,
-            owner: args.user_wallet,
-            yes_amount: net_amount,  // Use net_amount after fee
-            no_amount: net_amount,   // Use net_amount after fee
-            yes_locked: 0,
-            no_locked: 0,
-            yes_avg_cost: PRICE_PRECISION / 2, // 0.5 for complete set
-            no_avg_cost: PRICE_PRECISION / 2,
-            realized_pnl: 0,
-            total_cost_e6: args.amount,  // Record gross amount as cost basis
-            settled: false,
-            settlement_amount: 0,
-            created_at: current_time,
-            updated_at: current_time,
-            bump: position_bump,
-            settled_cost_e6: 0,
-            reserved: [0u8; 8],
-        };
-        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
-        
-        msg!("Created new Position PDA for user {} in market {}", 
-             args.user_wallet, args.market_id);
-    } else {
-        // Update existing Position
-        let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
-        
-        if position.discriminator != POSITION_DISCRIMINATOR {
-            return Err(PredictionMarketError::InvalidAccountData.into());
-        }
-        
-        if position.owner != args.user_wallet || position.market_id != args.market_id {
-            return Err(PredictionMarketError::PositionNotFound.into());
+    let remaining = order.remaining_amount();
+    let remaining_margin = order.remaining_locked_margin();
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    let current_time = get_current_timestamp()?;
+    
+    if order.side == crate::state::OrderSide::Buy {
+        if remaining_margin > 0 {
+            // The margin must go back to the order's owner, not the admin
+            verify_pm_user_account(pm_user_info, &order.owner, vault_program_info.key)?;
+            cpi_release_from_prediction(
+                vault_program_info,
+                vault_config_info,
+                user_vault_info,
+                pm_user_info,
+                config_info,
+                remaining_margin,
+                config_seeds,
+            )?;
         }
-        
-        position.yes_amount = safe_add_u64(position.yes_amount, net_amount)?;
-        position.no_amount = safe_add_u64(position.no_amount, net_amount)?;
-        position.total_cost_e6 = safe_add_u64(position.total_cost_e6, args.amount)?;
-        position.updated_at = current_time;
-        
-        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
-        
-        msg!("Updated Position: +{} YES, +{} NO shares (net after fee)", net_amount, net_amount);
+    } else if remaining > 0 {
+        unlock_sell_order_shares(program_id, &market, &order, position_info, remaining, current_time)?;
     }
     
-    // Step 3: Update market stats (use net_amount for shares)
-    market.total_minted = safe_add_u64(market.total_minted, net_amount)?;
+    order.status = OrderStatus::Cancelled;
+    order.locked_margin_e6 = 0;
+    order.updated_at = current_time;
+    order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
+    
     market.updated_at = current_time;
     market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
-    msg!("✅ RelayerMintCompleteSetV2WithFee completed");
-    msg!("User: {}, Market: {}", args.user_wallet, args.market_id);
-    msg!("Gross: {}, Fee: {}, Net shares: {}", args.amount, fee_amount, net_amount);
+    msg!("✅ ForceCancelOrder completed");
+    msg!("Admin: {}, Owner: {}", admin_info.key, order.owner);
+    msg!("Order ID: {}, Market: {}", args.order_id, args.market_id);
+    msg!("Remaining amount: {}, Unlocked margin: {}", remaining, remaining_margin);
+    msg!("order_force_cancelled:{},{},{},{}", args.market_id, args.order_id, admin_info.key, order.owner);
+    PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.order_id }).emit()?;
     
     Ok(())
 }
 
-/// Process RelayerRedeemCompleteSetV2WithFee
+/// V2: RelayerAmendOrder - reprice/resize an open order in place
 /// 
-/// Same as RelayerRedeemCompleteSetV2 but uses Vault.PredictionMarketUnlockWithFee
-/// to collect redemption fee during the unlock operation.
-/// 
-/// Accounts:
-/// 0. `[signer]` Relayer
-/// 1. `[]` PredictionMarketConfig
-/// 2. `[writable]` Market
-/// 3. `[writable]` Position PDA
-/// 4. `[writable]` User Vault Account
-/// 5. `[writable]` PM User Account
-/// 6. `[]` Vault Config
-/// 7. `[]` Vault Program
-/// 8. `[writable]` Vault Token Account
-/// 9. `[writable]` PM Fee Vault
-/// 10. `[writable]` PM Fee Config PDA
-/// 11. `[]` Token Program
-fn process_relayer_redeem_complete_set_v2_with_fee(
+/// Keeps the order_id. Buy orders lock or release the margin delta via
+/// Vault CPI, Sell orders lock or unlock the share delta in the Position.
+fn process_relayer_amend_order_v2(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: RelayerRedeemCompleteSetArgs,
+    args: RelayerAmendOrderV2Args,
 ) -> ProgramResult {
+    use std::cmp::Ordering;
+    
     let account_info_iter = &mut accounts.iter();
     
     // Account 0: Relayer (signer)
@@ -5257,52 +7028,22 @@ fn process_relayer_redeem_complete_set_v2_with_fee(
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
-    
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
-    
-    // Account 3: Position PDA (writable)
-    let position_info = next_account_info(account_info_iter)?;
-    
-    // Account 4: User Vault Account (writable)
-    let user_vault_info = next_account_info(account_info_iter)?;
-    
-    // Account 5: PM User Account (writable)
-    let pm_user_account_info = next_account_info(account_info_iter)?;
-    
-    // Account 6: Vault Config
-    let vault_config_info = next_account_info(account_info_iter)?;
-    
-    // Account 7: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
-    
-    // Account 8: Vault Token Account
-    let vault_token_account_info = next_account_info(account_info_iter)?;
-    
-    // Account 9: PM Fee Vault
-    let pm_fee_vault_info = next_account_info(account_info_iter)?;
-    
-    // Account 10: PM Fee Config PDA
-    let pm_fee_config_info = next_account_info(account_info_iter)?;
-    
-    // Account 11: Token Program
-    let token_program_info = next_account_info(account_info_iter)?;
-    
-    // Load and validate config
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Verify Relayer authority
-    verify_relayer(&config, relayer_info.key)?;
-    
     if config.is_paused {
         return Err(PredictionMarketError::ProgramPaused.into());
     }
     
-    // Load and validate market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    // Account 2: Market
+    let market_info = next_account_info(account_info_iter)?;
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
@@ -5311,164 +7052,279 @@ fn process_relayer_redeem_complete_set_v2_with_fee(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    // For redemption, we only need the market to exist and not be resolved
-    // Users should be able to redeem even from paused markets
-    if market.status == MarketStatus::Resolved {
-        return Err(PredictionMarketError::MarketAlreadyResolved.into());
-    }
+    market.check_tradeable()?;
     
-    // Validate amount
-    if args.amount == 0 {
-        return Err(PredictionMarketError::InvalidAmount.into());
-    }
+    // Account 3: Order PDA (writable)
+    let order_info = next_account_info(account_info_iter)?;
+    let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
     
-    let current_time = get_current_timestamp()?;
-    let market_id_bytes = market.market_id.to_le_bytes();
+    if order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(
-        &[PM_CONFIG_SEED],
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let (order_pda, _) = Pubkey::find_program_address(
+        &[ORDER_SEED, &market_id_bytes, &args.order_id.to_le_bytes()],
         program_id,
     );
-    
-    if *config_info.key != config_pda {
+    if *order_info.key != order_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
-    
-    // Validate and update Position
-    let (position_pda, _position_bump) = Pubkey::find_program_address(
-        &[POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
-        program_id,
-    );
+    if order.owner != args.user_wallet {
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    if *position_info.key != position_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
+    if !order.is_active() {
+        return Err(PredictionMarketError::OrderNotActive.into());
     }
     
-    let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
-    
-    if position.discriminator != POSITION_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    let current_time = get_current_timestamp()?;
+    if order.is_expired(current_time) {
+        return Err(PredictionMarketError::OrderExpired.into());
     }
     
-    if position.owner != args.user_wallet || position.market_id != args.market_id {
-        return Err(PredictionMarketError::PositionNotFound.into());
+    let new_price = args.new_price.unwrap_or(order.price);
+    let new_amount = args.new_amount.unwrap_or(order.amount);
+    validate_price(new_price)?;
+    
+    // Fully reducing the order is a cancel, not an amendment
+    if new_amount <= order.filled_amount {
+        msg!("Error: New amount {} must exceed filled amount {}", new_amount, order.filled_amount);
+        return Err(PredictionMarketError::InvalidOrderAmount.into());
     }
     
-    // Check user has enough shares to redeem
-    let available_yes = position.yes_amount.saturating_sub(position.yes_locked);
-    let available_no = position.no_amount.saturating_sub(position.no_locked);
+    // Account 4: Position PDA (writable, Sell orders)
+    let position_info = next_account_info(account_info_iter)?;
     
-    if available_yes < args.amount || available_no < args.amount {
-        msg!("Insufficient shares: need {}, have YES={}, NO={}", 
-             args.amount, available_yes, available_no);
-        return Err(PredictionMarketError::InsufficientPosition.into());
+    // Account 5: User Vault Account
+    let user_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: PM User Account
+    let pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    // Account 9: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    let old_remaining = order.remaining_amount();
+    let new_remaining = new_amount - order.filled_amount;
+    
+    // The amended order must pass the same checks as a newly placed one
+    config.check_order_minimum(new_remaining, new_price)?;
+    
+    // Account 10: Best opposing order (PostOnly orders only)
+    if order.order_type == crate::state::OrderType::PostOnly {
+        let best_opposing_info = next_account_info(account_info_iter)?;
+        check_post_only(program_id, best_opposing_info, args.market_id, order.side, order.outcome, new_price)?;
     }
     
-    // Burn virtual shares and reduce total_cost (Bug #5 fix)
-    position.yes_amount = position.yes_amount.saturating_sub(args.amount);
-    position.no_amount = position.no_amount.saturating_sub(args.amount);
-    position.total_cost_e6 = position.total_cost_e6.saturating_sub(args.amount);
-    position.updated_at = current_time;
-    position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+    // Growing a reduce-only Sell is bounded by the share lock below
+    if order.reduce_only && order.side == crate::state::OrderSide::Buy && new_remaining > old_remaining {
+        check_reduce_only(
+            program_id,
+            position_info,
+            args.market_id,
+            &order.owner,
+            order.side,
+            order.outcome,
+            new_remaining,
+        )?;
+    }
     
-    // Step 2: CPI to Vault - PredictionMarketUnlockWithFee
-    // This releases funds AND collects redemption fee
-    msg!("CPI: Vault.PredictionMarketUnlockWithFee gross_amount={}", args.amount);
-    cpi_release_from_prediction_with_fee(
-        vault_program_info,
-        vault_config_info,
-        user_vault_info,
-        pm_user_account_info,
-        config_info,
-        vault_token_account_info,
-        pm_fee_vault_info,
-        pm_fee_config_info,
-        token_program_info,
-        args.amount,
-        config_seeds,
-    )?;
+    if order.side == crate::state::OrderSide::Buy {
+        verify_pm_user_account(pm_user_info, &order.owner, &config.vault_program)?;
+        
+        // Re-lock from what is actually locked now to the remaining amount
+        // at the new price, the same formula as PlaceOrder. Only Binary
+        // fills charge the taker fee the headroom covers.
+        let fee_bps = if market.market_type == MarketType::Binary { config.trade_fee_bps } else { 0 };
+        let old_margin = order.remaining_locked_margin();
+        let new_margin = buy_margin(new_remaining, new_price, fee_bps)?;
+        
+        let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+        if *config_info.key != config_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+        
+        match new_margin.cmp(&old_margin) {
+            Ordering::Greater => {
+                let additional = new_margin - old_margin;
+                msg!("CPI: Lock additional margin {} for amended Buy order", additional);
+                let locked = cpi_lock_for_prediction(
+                    vault_program_info,
+                    vault_config_info,
+                    user_vault_info,
+                    pm_user_info,
+                    config_info,
+                    relayer_info,
+                    system_program_info,
+                    additional,
+                    config_seeds,
+                )?;
+                if locked != additional {
+                    msg!("Error: Owner could only lock {} of the {} additional margin", locked, additional);
+                    return Err(PredictionMarketError::InsufficientFunds.into());
+                }
+            }
+            Ordering::Less => {
+                msg!("CPI: Release margin {} for amended Buy order", old_margin - new_margin);
+                cpi_release_from_prediction(
+                    vault_program_info,
+                    vault_config_info,
+                    user_vault_info,
+                    pm_user_info,
+                    config_info,
+                    old_margin - new_margin,
+                    config_seeds,
+                )?;
+            }
+            Ordering::Equal => {}
+        }
+        order.locked_margin_e6 = new_margin;
+    } else if new_remaining != old_remaining {
+        if market.market_type != MarketType::Binary {
+            msg!("Error: RelayerAmendOrderV2 only amends Sell orders of Binary markets");
+            return Err(PredictionMarketError::InvalidMarketType.into());
+        }
+        
+        let (position_pda, _) = Pubkey::find_program_address(
+            &[POSITION_SEED, &market_id_bytes, order.owner.as_ref()],
+            program_id,
+        );
+        if *position_info.key != position_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        
+        let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
+        if position.discriminator != POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        
+        if new_remaining > old_remaining {
+            position.lock_shares(order.outcome, new_remaining - old_remaining)
+        } else {
+            position.unlock_shares(order.outcome, old_remaining - new_remaining)
+        }
+        .map_err(|_| {
+            msg!("Error: Insufficient available shares to amend Sell order");
+            PredictionMarketError::InsufficientPosition
+        })?;
+        
+        position.updated_at = current_time;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        
+        msg!("📊 Position locked shares: {} -> {} {:?}", old_remaining, new_remaining, order.outcome);
+    }
     
-    // Step 3: Update market stats
-    market.total_minted = market.total_minted.saturating_sub(args.amount);
-    market.updated_at = current_time;
-    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    order.price = new_price;
+    order.amount = new_amount;
+    order.updated_at = current_time;
+    order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
     
-    msg!("✅ RelayerRedeemCompleteSetV2WithFee completed");
-    msg!("User: {}, Market: {}", args.user_wallet, args.market_id);
-    msg!("Gross amount: {} (fee collected by Vault)", args.amount);
-    msg!("complete_set_redeemed:{},{},{},{}", args.market_id, args.user_wallet, args.amount, args.amount);
+    msg!("✅ RelayerAmendOrderV2 completed");
+    msg!("Order ID: {}, Market: {}", args.order_id, args.market_id);
+    msg!("Price: {}, Amount: {}, Remaining: {}", new_price, new_amount, new_remaining);
+    msg!("order_amended:{},{},{},{}", args.market_id, args.order_id, new_price, new_amount);
     
     Ok(())
 }
 
 // ============================================================================
-// Helper Functions
+// V2 WithFee Instructions
 // ============================================================================
 
-/// Verify that the caller is an authorized relayer
+/// Process RelayerMintCompleteSetV2WithFee
 /// 
-/// V2: Only admin can act as relayer (simplified model)
-fn verify_relayer(config: &PredictionMarketConfig, relayer: &Pubkey) -> ProgramResult {
-    // Check if the relayer is the admin or oracle_admin
-    if *relayer == config.admin || *relayer == config.oracle_admin {
-        return Ok(());
-    }
-    
-    msg!("Unauthorized relayer: {}", relayer);
-    Err(PredictionMarketError::Unauthorized.into())
-}
-
-// ============================================================================
-// LLM Oracle Processors (Phase 4.6)
-// ============================================================================
-
-use crate::state::{
-    MarketOracleData, OracleProposalData, ProposalType,
-    MARKET_ORACLE_DATA_SEED, ORACLE_PROPOSAL_DATA_SEED,
-    MARKET_ORACLE_DATA_DISCRIMINATOR, ORACLE_PROPOSAL_DATA_DISCRIMINATOR,
-};
-
-/// Task 4.6.1: Initialize market oracle data account
-fn process_initialize_market_oracle_data(
+/// Same as RelayerMintCompleteSetV2 but uses Vault.PredictionMarketLockWithFee
+/// to collect minting fee during the lock operation.
+/// 
+/// Accounts:
+/// 0. `[signer]` Relayer
+/// 1. `[]` PredictionMarketConfig
+/// 2. `[writable]` Market
+/// 3. `[writable]` Position PDA
+/// 4. `[writable]` User Vault Account
+/// 5. `[writable]` PM User Account
+/// 6. `[]` Vault Config
+/// 7. `[]` Vault Program
+/// 8. `[]` System Program
+/// 9. `[writable]` Vault Token Account
+/// 10. `[writable]` PM Fee Vault
+/// 11. `[writable]` PM Fee Config PDA
+/// 12. `[]` Token Program
+fn process_relayer_mint_complete_set_v2_with_fee(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: InitializeMarketOracleDataArgs,
+    args: RelayerMintCompleteSetArgs,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Admin (signer)
-    let admin_info = next_account_info(account_info_iter)?;
-    check_signer(admin_info)?;
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
     
-    // Account 2: Market
+    // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
     
-    // Account 3: MarketOracleData PDA (writable, to be created)
-    let oracle_data_info = next_account_info(account_info_iter)?;
+    // Account 3: Position PDA (writable)
+    let position_info = next_account_info(account_info_iter)?;
     
-    // Account 4: System Program
+    // Account 4: User Vault Account (writable)
+    let user_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: PM User Account (writable)
+    let pm_user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: System Program
     let system_program_info = next_account_info(account_info_iter)?;
     
+    // Account 9: Vault Token Account (for fee transfer)
+    let vault_token_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 10: PM Fee Vault
+    let pm_fee_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 11: PM Fee Config PDA
+    let pm_fee_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 12: Token Program
+    let token_program_info = next_account_info(account_info_iter)?;
+    
     // Load and validate config
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Task 4.6.2: Verify admin authority
-    if *admin_info.key != config.admin && *admin_info.key != config.oracle_admin {
-        msg!("Unauthorized: {} is not admin", admin_info.key);
-        return Err(PredictionMarketError::Unauthorized.into());
+    // Verify Relayer authority
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
     }
     
     // Load and validate market
-    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
@@ -5477,119 +7333,4380 @@ fn process_initialize_market_oracle_data(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    // Derive and validate oracle data PDA
-    let market_id_bytes = args.market_id.to_le_bytes();
-    let (oracle_data_pda, oracle_data_bump) = Pubkey::find_program_address(
-        &[MARKET_ORACLE_DATA_SEED, &market_id_bytes],
-        program_id,
-    );
+    market.check_tradeable()?;
     
-    if *oracle_data_info.key != oracle_data_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
+    // Validate amount
+    if args.amount == 0 {
+        return Err(PredictionMarketError::InvalidAmount.into());
     }
     
-    // Create the oracle data account
-    let rent = Rent::get()?;
-    let space = MarketOracleData::SIZE;
-    let lamports = rent.minimum_balance(space);
+    let current_time = get_current_timestamp()?;
+    let market_id_bytes = market.market_id.to_le_bytes();
     
-    let create_account_ix = system_instruction::create_account(
-        admin_info.key,
-        oracle_data_info.key,
-        lamports,
-        space as u64,
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(
+        &[PM_CONFIG_SEED],
         program_id,
     );
     
-    let seeds: &[&[u8]] = &[MARKET_ORACLE_DATA_SEED, &market_id_bytes, &[oracle_data_bump]];
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    invoke_signed(
-        &create_account_ix,
-        &[admin_info.clone(), oracle_data_info.clone(), system_program_info.clone()],
-        &[seeds],
-    )?;
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
     
-    // Initialize the account data
-    let current_time = get_current_timestamp()?;
-    let oracle_data = MarketOracleData::new(args.market_id, oracle_data_bump, current_time, args.challenge_duration_secs);
-    oracle_data.serialize(&mut &mut oracle_data_info.data.borrow_mut()[..])?;
+    // Read PM Fee Config to calculate net_amount
+    // PM Fee Config offsets (matching Fund Program state.rs):
+    // - offset 41: minting_fee_bps (u16)
+    const PM_FEE_MINTING_BPS_OFFSET: usize = 41;
+    let pm_fee_config_data = pm_fee_config_info.try_borrow_data()?;
+    if pm_fee_config_data.len() < 50 {
+        msg!("❌ PM Fee Config not initialized");
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    let minting_fee_bps = u16::from_le_bytes([
+        pm_fee_config_data[PM_FEE_MINTING_BPS_OFFSET],
+        pm_fee_config_data[PM_FEE_MINTING_BPS_OFFSET + 1],
+    ]);
+    drop(pm_fee_config_data);
     
-    msg!("✅ Initialized MarketOracleData for market {}", args.market_id);
+    // Calculate fee and net_amount
+    let fee_amount = ((args.amount as u128) * (minting_fee_bps as u128) / 10000) as u64;
+    let net_amount = args.amount.saturating_sub(fee_amount);
+    market.check_tvl_cap(net_amount)?;
+    
+    // The fee comes out of the gross amount, which is all the user pays
+    validate_max_cost(args.amount, args.max_cost_e6)?;
+    
+    msg!("Fee calculation: gross={}, fee_bps={}, fee={}, net={}", 
+         args.amount, minting_fee_bps, fee_amount, net_amount);
+    
+    // Step 1: CPI to Vault - PredictionMarketLockWithFee
+    // This locks the funds AND collects the minting fee
+    msg!("CPI: Vault.PredictionMarketLockWithFee gross_amount={}", args.amount);
+    cpi_lock_for_prediction_with_fee(
+        vault_program_info,
+        vault_config_info,
+        user_vault_info,
+        pm_user_account_info,
+        config_info,  // PM Config as caller program marker
+        vault_token_account_info,
+        pm_fee_vault_info,
+        pm_fee_config_info,
+        token_program_info,
+        relayer_info, // Payer for auto-init
+        system_program_info,
+        args.amount,
+        config_seeds,
+    )?;
+    
+    // Step 2: Create or update Position PDA
+    let (position_pda, position_bump) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
+        program_id,
+    );
+    
+    if *position_info.key != position_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let is_new_position = position_info.data_is_empty();
+    
+    if is_new_position {
+        // Create new Position account
+        let rent = Rent::get()?;
+        let space = Position::SIZE;
+        let lamports = rent.minimum_balance(space);
+        let position_seeds: &[&[u8]] = &[
+            POSITION_SEED, 
+            &market_id_bytes, 
+            args.user_wallet.as_ref(), 
+            &[position_bump]
+        ];
+        
+        invoke_signed(
+            &system_instruction::create_account(
+                relayer_info.key,
+                position_info.key,
+                lamports,
+                space as u64,
+                program_id,
+            ),
+            &[relayer_info.clone(), position_info.clone(), system_program_info.clone()],
+            &[position_seeds],
+        )?;
+        
+        let position = Position {
+            discriminator: POSITION_DISCRIMINATOR,
+            market_id: args.market_id,
+            owner: args.user_wallet,
+            yes_amount: net_amount,  // Use net_amount after fee
+            no_amount: net_amount,   // Use net_amount after fee
+            yes_locked: 0,
+            no_locked: 0,
+            yes_avg_cost: PRICE_PRECISION / 2, // 0.5 for complete set
+            no_avg_cost: PRICE_PRECISION / 2,
+            realized_pnl: 0,
+            total_cost_e6: args.amount,  // Record gross amount as cost basis
+            settled: false,
+            settlement_amount: 0,
+            created_at: current_time,
+            updated_at: current_time,
+            bump: position_bump,
+            settled_cost_e6: 0,
+            reserved: [0u8; 8],
+        };
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        
+        msg!("Created new Position PDA for user {} in market {}", 
+             args.user_wallet, args.market_id);
+    } else {
+        // Update existing Position
+        let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
+        
+        if position.discriminator != POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        
+        if position.owner != args.user_wallet || position.market_id != args.market_id {
+            return Err(PredictionMarketError::PositionNotFound.into());
+        }
+        
+        position.yes_amount = safe_add_u64(position.yes_amount, net_amount)?;
+        position.no_amount = safe_add_u64(position.no_amount, net_amount)?;
+        position.total_cost_e6 = safe_add_u64(position.total_cost_e6, args.amount)?;
+        position.updated_at = current_time;
+        
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        
+        msg!("Updated Position: +{} YES, +{} NO shares (net after fee)", net_amount, net_amount);
+    }
+    
+    // Step 3: Update market stats (use net_amount for shares)
+    market.total_minted = safe_add_u64(market.total_minted, net_amount)?;
+    market.increase_open_interest(net_amount)?;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ RelayerMintCompleteSetV2WithFee completed");
+    msg!("User: {}, Market: {}", args.user_wallet, args.market_id);
+    msg!("Gross: {}, Fee: {}, Net shares: {}", args.amount, fee_amount, net_amount);
+    
+    Ok(())
+}
+
+/// Process RelayerRedeemCompleteSetV2WithFee
+/// 
+/// Same as RelayerRedeemCompleteSetV2 but uses Vault.PredictionMarketUnlockWithFee
+/// to collect redemption fee during the unlock operation.
+/// 
+/// Accounts:
+/// 0. `[signer]` Relayer
+/// 1. `[]` PredictionMarketConfig
+/// 2. `[writable]` Market
+/// 3. `[writable]` Position PDA
+/// 4. `[writable]` User Vault Account
+/// 5. `[writable]` PM User Account
+/// 6. `[]` Vault Config
+/// 7. `[]` Vault Program
+/// 8. `[writable]` Vault Token Account
+/// 9. `[writable]` PM Fee Vault
+/// 10. `[writable]` PM Fee Config PDA
+/// 11. `[]` Token Program
+fn process_relayer_redeem_complete_set_v2_with_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerRedeemCompleteSetArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: Position PDA (writable)
+    let position_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: User Vault Account (writable)
+    let user_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: PM User Account (writable)
+    let pm_user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Vault Token Account
+    let vault_token_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 9: PM Fee Vault
+    let pm_fee_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 10: PM Fee Config PDA
+    let pm_fee_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 11: Token Program
+    let token_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify Relayer authority
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
+    }
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // For redemption, we only need the market to exist and not be resolved
+    // Users should be able to redeem even from paused markets
+    if market.status == MarketStatus::Resolved {
+        return Err(PredictionMarketError::MarketAlreadyResolved.into());
+    }
+    
+    // Validate amount
+    if args.amount == 0 {
+        return Err(PredictionMarketError::InvalidAmount.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    let market_id_bytes = market.market_id.to_le_bytes();
+    
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(
+        &[PM_CONFIG_SEED],
+        program_id,
+    );
+    
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    // Validate and update Position
+    let (position_pda, _position_bump) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
+        program_id,
+    );
+    
+    if *position_info.key != position_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
+    
+    if position.discriminator != POSITION_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if position.owner != args.user_wallet || position.market_id != args.market_id {
+        return Err(PredictionMarketError::PositionNotFound.into());
+    }
+    
+    // Check user has enough shares to redeem
+    let available_yes = position.yes_amount.saturating_sub(position.yes_locked);
+    let available_no = position.no_amount.saturating_sub(position.no_locked);
+    
+    if available_yes < args.amount || available_no < args.amount {
+        msg!("Insufficient shares: need {}, have YES={}, NO={}", 
+             args.amount, available_yes, available_no);
+        return Err(PredictionMarketError::InsufficientPosition.into());
+    }
+    
+    // Burn virtual shares and reduce total_cost (Bug #5 fix)
+    let pnl = position.redeem_complete_set(args.amount, current_time);
+    market.record_realized_pnl(0, pnl)?;
+    position.total_cost_e6 = position.total_cost_e6.saturating_sub(args.amount);
+    position.updated_at = current_time;
+    position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+    
+    // Step 2: CPI to Vault - PredictionMarketUnlockWithFee
+    // This releases funds AND collects redemption fee
+    msg!("CPI: Vault.PredictionMarketUnlockWithFee gross_amount={}", args.amount);
+    cpi_release_from_prediction_with_fee(
+        vault_program_info,
+        vault_config_info,
+        user_vault_info,
+        pm_user_account_info,
+        config_info,
+        vault_token_account_info,
+        pm_fee_vault_info,
+        pm_fee_config_info,
+        token_program_info,
+        args.amount,
+        config_seeds,
+    )?;
+    
+    // Step 3: Update market stats
+    market.total_minted = market.total_minted.saturating_sub(args.amount);
+    market.decrease_open_interest(args.amount);
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ RelayerRedeemCompleteSetV2WithFee completed");
+    msg!("User: {}, Market: {}", args.user_wallet, args.market_id);
+    msg!("Gross amount: {} (fee collected by Vault)", args.amount);
+    msg!("complete_set_redeemed:{},{},{},{}", args.market_id, args.user_wallet, args.amount, args.amount);
+    
+    Ok(())
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Verify that the caller is an authorized relayer
+/// 
+/// Admin and oracle_admin are always accepted. Any other signer must be listed
+/// in the AuthorizedCallers PDA, which the client passes as an extra account
+/// (position-independent, so it can trail variable-length account lists).
+/// Without the registry account only admin/oracle_admin are accepted.
+fn verify_relayer(
+    program_id: &Pubkey,
+    config: &PredictionMarketConfig,
+    relayer: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    // Check if the relayer is the admin or oracle_admin
+    if *relayer == config.admin || *relayer == config.oracle_admin {
+        return Ok(());
+    }
+    
+    // Fall back to the AuthorizedCallers registry, if supplied
+    if is_registered_caller(program_id, relayer, accounts)? {
+        return Ok(());
+    }
+    
+    msg!("Unauthorized relayer: {}", relayer);
+    Err(PredictionMarketError::Unauthorized.into())
+}
+
+/// Verify that the instruction after the current one fills Market order
+/// `order_id` of market `market_id`
+/// 
+/// The fill must be an ExecuteTradeV2 or ExecuteTradeMultiV2 of this program
+/// with the order as taker; `check_fill_or_kill` then rejects any unfilled
+/// remainder, so the whole transaction fails unless the order is filled.
+fn verify_market_order_fill(
+    program_id: &Pubkey,
+    instructions_info: &AccountInfo,
+    market_id: u64,
+    order_id: u64,
+) -> ProgramResult {
+    if !instructions_sysvar::check_id(instructions_info.key) {
+        msg!("Error: Market orders require the Instructions sysvar");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let current = instructions_sysvar::load_current_index_checked(instructions_info)?;
+    let next = instructions_sysvar::load_instruction_at_checked(current as usize + 1, instructions_info)
+        .map_err(|_| PredictionMarketError::MarketOrderNotFilled)?;
+    
+    let taker = match PredictionMarketInstruction::try_from_slice(&next.data) {
+        Ok(PredictionMarketInstruction::ExecuteTradeV2(fill)) if next.program_id == *program_id => {
+            (fill.market_id, fill.taker_order_id)
+        }
+        Ok(PredictionMarketInstruction::ExecuteTradeMultiV2(fill)) if next.program_id == *program_id => {
+            (fill.market_id, fill.taker_order_id)
+        }
+        _ => (0, 0),
+    };
+    if taker != (market_id, order_id) {
+        msg!("Error: Market order {} is not filled by the next instruction", order_id);
+        return Err(PredictionMarketError::MarketOrderNotFilled.into());
+    }
+    
+    Ok(())
+}
+
+/// Verify that an order account is order `order_id` of market `market_id`
+/// 
+/// Both the stored ids and the PDA address must match, so an order from
+/// another market cannot be substituted.
+fn verify_order_account(
+    program_id: &Pubkey,
+    order_info: &AccountInfo,
+    order: &Order,
+    market_id: u64,
+    order_id: u64,
+) -> ProgramResult {
+    if order.market_id != market_id {
+        msg!("Error: Order {} belongs to market {}, not {}", order.order_id, order.market_id, market_id);
+        return Err(PredictionMarketError::OrderMarketMismatch.into());
+    }
+    
+    if order.order_id != order_id {
+        return Err(PredictionMarketError::OrderNotFound.into());
+    }
+    
+    let (order_pda, _) = Pubkey::find_program_address(
+        &[ORDER_SEED, &market_id.to_le_bytes(), &order_id.to_le_bytes()],
+        program_id,
+    );
+    if *order_info.key != order_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    Ok(())
+}
+
+/// Reject a PostOnly order at `price` that would cross the best opposing
+/// order the relayer passed
+/// 
+/// An empty account stands for an empty opposing side of the book.
+fn check_post_only(
+    program_id: &Pubkey,
+    best_opposing_info: &AccountInfo,
+    market_id: u64,
+    side: crate::state::OrderSide,
+    outcome: Outcome,
+    price: u64,
+) -> ProgramResult {
+    if best_opposing_info.data_is_empty() {
+        return Ok(());
+    }
+    if best_opposing_info.owner != program_id {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    let best = deserialize_account::<Order>(&best_opposing_info.data.borrow())?;
+    if best.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    verify_order_account(program_id, best_opposing_info, &best, market_id, best.order_id)?;
+    if best.side == side || best.outcome != outcome {
+        return Err(PredictionMarketError::InvalidOrderSide.into());
+    }
+    if !best.is_active() {
+        return Err(PredictionMarketError::OrderNotActive.into());
+    }
+    let crosses = match side {
+        crate::state::OrderSide::Buy => price >= best.price,
+        crate::state::OrderSide::Sell => price <= best.price,
+    };
+    if crosses {
+        msg!("Error: Post-only {:?} at {} crosses order {} at {}",
+             side, price, best.order_id, best.price);
+        return Err(PredictionMarketError::PostOnlyWouldCross.into());
+    }
+    Ok(())
+}
+
+/// Reject a reduce-only order of `amount` that would grow `owner`'s position
+/// 
+/// Reduce-only orders may only unwind the position: a Sell up to the
+/// available shares of its outcome, a Buy up to the available shares of the
+/// opposite outcome it pairs with into complete sets.
+fn check_reduce_only(
+    program_id: &Pubkey,
+    position_info: &AccountInfo,
+    market_id: u64,
+    owner: &Pubkey,
+    side: crate::state::OrderSide,
+    outcome: Outcome,
+    amount: u64,
+) -> ProgramResult {
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market_id.to_le_bytes(), owner.as_ref()],
+        program_id,
+    );
+    if *position_info.key != position_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let position = try_deserialize_initialized::<Position>(
+        &position_info.data.borrow(),
+        POSITION_DISCRIMINATOR,
+    )?;
+    let reduced_outcome = match (side, outcome) {
+        (crate::state::OrderSide::Sell, outcome) => outcome,
+        (crate::state::OrderSide::Buy, Outcome::Yes) => Outcome::No,
+        (crate::state::OrderSide::Buy, Outcome::No) => Outcome::Yes,
+    };
+    let reducible = position.map_or(0, |position| position.available(reduced_outcome));
+    if amount > reducible {
+        msg!("Error: Reduce-only {:?} of {} exceeds available {:?} holdings {}",
+             side, amount, reduced_outcome, reducible);
+        return Err(PredictionMarketError::ReduceOnlyViolation.into());
+    }
+    Ok(())
+}
+
+/// Return the `remaining` shares locked by a Sell order that is being
+/// cancelled or expired to its owner's Position, or MultiOutcomePosition on
+/// a multi-outcome market
+fn unlock_sell_order_shares(
+    program_id: &Pubkey,
+    market: &Market,
+    order: &Order,
+    position_info: &AccountInfo,
+    remaining: u64,
+    current_time: i64,
+) -> ProgramResult {
+    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, MULTI_OUTCOME_POSITION_SEED};
+    
+    let multi_outcome = market.market_type == MarketType::MultiOutcome;
+    let position_seed = if multi_outcome { MULTI_OUTCOME_POSITION_SEED } else { POSITION_SEED };
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[position_seed, &market.market_id.to_le_bytes(), order.owner.as_ref()],
+        program_id,
+    );
+    if *position_info.key != position_pda {
+        msg!("Error: Invalid Position PDA for Sell order {}", order.order_id);
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let unlock_failed = |_| {
+        msg!("Error: Failed to unlock shares - locked amount mismatch");
+        PredictionMarketError::InsufficientPosition
+    };
+    if multi_outcome {
+        let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
+        if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        position.unlock_shares(order.outcome_index, remaining).map_err(unlock_failed)?;
+        position.updated_at = current_time;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+    } else {
+        let mut position = deserialize_account::<Position>(&position_info.data.borrow())?;
+        if position.discriminator != POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        position.unlock_shares(order.outcome, remaining).map_err(unlock_failed)?;
+        position.updated_at = current_time;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+    }
+    Ok(())
+}
+
+/// Read one side of an UpdateBookTop: the (price, depth) of the best order
+/// passed for `side`, or `None` for an empty account and zero depth
+fn book_side(
+    program_id: &Pubkey,
+    order_info: &AccountInfo,
+    args: &UpdateBookTopArgs,
+    side: crate::state::OrderSide,
+    depth: u64,
+) -> Result<Option<(u64, u64)>, ProgramError> {
+    if order_info.data_is_empty() {
+        if depth != 0 {
+            msg!("Error: {:?} depth {} given without a best order", side, depth);
+            return Err(PredictionMarketError::BookTopDepthTooLow.into());
+        }
+        return Ok(None);
+    }
+    
+    if order_info.owner != program_id {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    let order = deserialize_account::<Order>(&order_info.data.borrow())?;
+    if order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    verify_order_account(program_id, order_info, &order, args.market_id, order.order_id)?;
+    if order.side != side || order.outcome != args.outcome {
+        return Err(PredictionMarketError::InvalidOrderSide.into());
+    }
+    if !order.is_active() {
+        return Err(PredictionMarketError::OrderNotActive.into());
+    }
+    if depth < order.remaining_amount() {
+        msg!("Error: {:?} depth {} below the {} remaining on order {}",
+             side, depth, order.remaining_amount(), order.order_id);
+        return Err(PredictionMarketError::BookTopDepthTooLow.into());
+    }
+    
+    Ok(Some((order.price, depth)))
+}
+
+/// Add a trade's notional (USDC e6, i.e. amount * price summed over the
+/// filled legs) to the market and global volume counters
+fn accumulate_volume(market: &mut Market, config: &mut PredictionMarketConfig, notional_e6: u64) {
+    let notional = i64::try_from(notional_e6).unwrap_or(i64::MAX);
+    market.total_volume_e6 = market.total_volume_e6.saturating_add(notional);
+    config.total_volume_e6 = config.total_volume_e6.saturating_add(notional);
+}
+
+/// Check whether `caller` is listed in the AuthorizedCallers registry
+/// 
+/// The registry PDA may appear anywhere in `accounts`; if it is absent or
+/// not yet initialized the caller is treated as unregistered.
+fn is_registered_caller(
+    program_id: &Pubkey,
+    caller: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<bool, ProgramError> {
+    use crate::state::{AuthorizedCallers, AUTHORIZED_CALLERS_SEED, AUTHORIZED_CALLERS_DISCRIMINATOR};
+    
+    let (callers_pda, _) = Pubkey::find_program_address(&[AUTHORIZED_CALLERS_SEED], program_id);
+    let Some(callers_info) = accounts.iter().find(|a| *a.key == callers_pda) else {
+        return Ok(false);
+    };
+    if callers_info.owner != program_id {
+        return Ok(false);
+    }
+    let callers = try_deserialize_initialized::<AuthorizedCallers>(
+        &callers_info.data.borrow(),
+        AUTHORIZED_CALLERS_DISCRIMINATOR,
+    )?;
+    Ok(callers.is_some_and(|c| c.is_authorized(caller)))
+}
+
+/// Reject vault/fund program settings that would misroute every CPI
+/// 
+/// The two must differ and neither may be the System Program or this program.
+fn validate_program_config(
+    program_id: &Pubkey,
+    vault_program: &Pubkey,
+    fund_program: &Pubkey,
+) -> ProgramResult {
+    if vault_program == fund_program {
+        msg!("Error: Vault and Fund program must differ ({})", vault_program);
+        return Err(PredictionMarketError::InvalidProgramConfig.into());
+    }
+    for key in [vault_program, fund_program] {
+        if *key == solana_program::system_program::ID || key == program_id {
+            msg!("Error: Invalid Vault/Fund program {}", key);
+            return Err(PredictionMarketError::InvalidProgramConfig.into());
+        }
+    }
+    Ok(())
+}
+
+/// Find `owner`'s Vault PMUserAccount among `accounts`, failing with
+/// `missing` if it was not provided
+fn find_pm_user_account<'a, 'b>(
+    accounts: &'b [AccountInfo<'a>],
+    owner: &Pubkey,
+    vault_program: &Pubkey,
+    missing: PredictionMarketError,
+) -> Result<&'b AccountInfo<'a>, ProgramError> {
+    use crate::state::VAULT_PM_USER_SEED;
+    
+    let (pm_user_pda, _) = Pubkey::find_program_address(
+        &[VAULT_PM_USER_SEED, owner.as_ref()],
+        vault_program,
+    );
+    accounts.iter().find(|a| *a.key == pm_user_pda).ok_or_else(|| {
+        msg!("Error: PMUserAccount {} of {} not provided", pm_user_pda, owner);
+        missing.into()
+    })
+}
+
+/// Find the TraderStats PDA of `wallet` (and its bump) among `accounts`
+fn find_trader_stats<'a, 'b>(
+    program_id: &Pubkey,
+    accounts: &'b [AccountInfo<'a>],
+    wallet: &Pubkey,
+) -> Option<(&'b AccountInfo<'a>, u8)> {
+    use crate::state::TRADER_STATS_SEED;
+    
+    let (stats_pda, bump) = Pubkey::find_program_address(
+        &[TRADER_STATS_SEED, wallet.as_ref()],
+        program_id,
+    );
+    accounts.iter().find(|a| *a.key == stats_pda).map(|a| (a, bump))
+}
+
+/// Volume recorded in a TraderStats account, 0 before its first fill
+fn trader_volume(stats_info: &AccountInfo) -> Result<u64, ProgramError> {
+    use crate::state::{TraderStats, TRADER_STATS_DISCRIMINATOR};
+    
+    let stats = try_deserialize_initialized::<TraderStats>(
+        &stats_info.data.borrow(),
+        TRADER_STATS_DISCRIMINATOR,
+    )?;
+    Ok(stats.map_or(0, |stats| stats.volume_e6))
+}
+
+/// Add `notional_e6` to `wallet`'s TraderStats, creating the account on
+/// first use with `payer_info` paying the rent
+fn record_trader_volume<'a>(
+    program_id: &Pubkey,
+    stats: (&AccountInfo<'a>, u8),
+    wallet: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    notional_e6: u64,
+    current_time: i64,
+) -> ProgramResult {
+    use crate::state::{TraderStats, TRADER_STATS_DISCRIMINATOR, TRADER_STATS_SEED};
+    
+    let (stats_info, bump) = stats;
+    let existing = try_deserialize_initialized::<TraderStats>(
+        &stats_info.data.borrow(),
+        TRADER_STATS_DISCRIMINATOR,
+    )?;
+    let mut trader_stats = match existing {
+        Some(trader_stats) => trader_stats,
+        None => {
+            let space = TraderStats::SIZE;
+            let lamports = Rent::get()?.minimum_balance(space);
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    stats_info.key,
+                    lamports,
+                    space as u64,
+                    program_id,
+                ),
+                &[payer_info.clone(), stats_info.clone(), system_program_info.clone()],
+                &[&[TRADER_STATS_SEED, wallet.as_ref(), &[bump]]],
+            )?;
+            TraderStats::new(*wallet, bump, current_time)
+        }
+    };
+    trader_stats.record_volume(notional_e6, current_time);
+    trader_stats.serialize(&mut &mut stats_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Create the ChallengerRecord of the next escalated challenger of
+/// `proposal`, with the challenger paying the rent
+fn record_escalated_challenger<'a>(
+    program_id: &Pubkey,
+    record_info: &AccountInfo<'a>,
+    (challenger_info, system_program_info): (&AccountInfo<'a>, &AccountInfo<'a>),
+    proposal: &OracleProposal,
+    result: MarketResult,
+    bond: u64,
+    current_time: i64,
+) -> ProgramResult {
+    use crate::state::{ChallengerRecord, CHALLENGER_RECORD_DISCRIMINATOR, CHALLENGER_RECORD_SEED};
+    
+    let market_id_bytes = proposal.market_id.to_le_bytes();
+    let index = proposal.escalated_challengers;
+    let (record_pda, bump) = Pubkey::find_program_address(
+        &[CHALLENGER_RECORD_SEED, &market_id_bytes, &[index]],
+        program_id,
+    );
+    if *record_info.key != record_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let space = ChallengerRecord::SIZE;
+    let lamports = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(
+            challenger_info.key,
+            record_info.key,
+            lamports,
+            space as u64,
+            program_id,
+        ),
+        &[challenger_info.clone(), record_info.clone(), system_program_info.clone()],
+        &[&[CHALLENGER_RECORD_SEED, &market_id_bytes, &[index], &[bump]]],
+    )?;
+    
+    let record = ChallengerRecord {
+        discriminator: CHALLENGER_RECORD_DISCRIMINATOR,
+        market_id: proposal.market_id,
+        index,
+        challenger: *challenger_info.key,
+        result,
+        bond,
+        created_at: current_time,
+        bump,
+        reserved: [0u8; 16],
+    };
+    record.serialize(&mut &mut record_info.data.borrow_mut()[..])?;
+    
+    msg!("challenger_joined:{},{},{},{:?},{}", proposal.market_id, index, challenger_info.key, result, bond);
+    Ok(())
+}
+
+/// Load the escalated challengers of a dispute
+/// 
+/// Reads a (ChallengerRecord, challenger's PMUserAccount) pair per escalated
+/// challenger, in index order, and checks the records add up to the bonds
+/// the proposal tracks.
+fn load_escalated_challengers<'a, 'b>(
+    program_id: &Pubkey,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    config: &PredictionMarketConfig,
+    proposal: &OracleProposal,
+) -> Result<Vec<(crate::state::ChallengerRecord, &'b AccountInfo<'a>)>, ProgramError> {
+    use crate::state::{ChallengerRecord, CHALLENGER_RECORD_DISCRIMINATOR, CHALLENGER_RECORD_SEED};
+    
+    let market_id_bytes = proposal.market_id.to_le_bytes();
+    let mut escalated = Vec::with_capacity(proposal.escalated_challengers as usize);
+    let mut total_bonds = 0u64;
+    for index in 0..proposal.escalated_challengers {
+        let record_info = next_account_info(account_info_iter)?;
+        let pm_user_info = next_account_info(account_info_iter)?;
+        
+        let (record_pda, _) = Pubkey::find_program_address(
+            &[CHALLENGER_RECORD_SEED, &market_id_bytes, &[index]],
+            program_id,
+        );
+        if *record_info.key != record_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        let record = deserialize_account::<ChallengerRecord>(&record_info.data.borrow())?;
+        if record.discriminator != CHALLENGER_RECORD_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        verify_pm_user_account(pm_user_info, &record.challenger, &config.vault_program)?;
+        
+        total_bonds = safe_add_u64(total_bonds, record.bond)?;
+        escalated.push((record, pm_user_info));
+    }
+    if total_bonds != proposal.escalated_bonds_e6 {
+        msg!("Error: Challenger records hold {} in bonds, proposal tracks {}",
+             total_bonds, proposal.escalated_bonds_e6);
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    Ok(escalated)
+}
+
+/// Find the UserMeta PDA of `wallet` (and its bump) among `accounts`
+fn find_user_meta<'a, 'b>(
+    program_id: &Pubkey,
+    accounts: &'b [AccountInfo<'a>],
+    wallet: &Pubkey,
+) -> Result<(&'b AccountInfo<'a>, u8), ProgramError> {
+    use crate::state::USER_META_SEED;
+    
+    let (meta_pda, bump) = Pubkey::find_program_address(
+        &[USER_META_SEED, wallet.as_ref()],
+        program_id,
+    );
+    accounts.iter().find(|a| *a.key == meta_pda).map(|a| (a, bump)).ok_or_else(|| {
+        msg!("Error: UserMeta {} of {} not provided", meta_pda, wallet);
+        PredictionMarketError::MissingUserMeta.into()
+    })
+}
+
+/// Record `referrer` in `wallet`'s UserMeta unless one was recorded before,
+/// creating the account on first use with `payer_info` paying the rent
+fn record_referrer<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    wallet: &Pubkey,
+    referrer: Pubkey,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    current_time: i64,
+) -> ProgramResult {
+    use crate::state::{UserMeta, USER_META_DISCRIMINATOR, USER_META_SEED};
+    
+    if referrer == *wallet {
+        msg!("Error: {} cannot refer themselves", wallet);
+        return Err(PredictionMarketError::InvalidReferrer.into());
+    }
+    
+    let (meta_info, bump) = find_user_meta(program_id, accounts, wallet)?;
+    let existing = try_deserialize_initialized::<UserMeta>(
+        &meta_info.data.borrow(),
+        USER_META_DISCRIMINATOR,
+    )?;
+    let meta = match existing {
+        Some(meta) if meta.referrer.is_some() => {
+            msg!("Referrer already recorded for {}", wallet);
+            return Ok(());
+        }
+        Some(mut meta) => {
+            meta.referrer = Some(referrer);
+            meta
+        }
+        None => {
+            let space = UserMeta::SIZE;
+            let lamports = Rent::get()?.minimum_balance(space);
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    meta_info.key,
+                    lamports,
+                    space as u64,
+                    program_id,
+                ),
+                &[payer_info.clone(), meta_info.clone(), system_program_info.clone()],
+                &[&[USER_META_SEED, wallet.as_ref(), &[bump]]],
+            )?;
+            UserMeta::new(*wallet, Some(referrer), bump, current_time)
+        }
+    };
+    meta.serialize(&mut &mut meta_info.data.borrow_mut()[..])?;
+    
+    msg!("referrer_recorded:{},{}", wallet, referrer);
+    Ok(())
+}
+
+/// Referral rewards owed on a fill: for each (trader, protocol fee) side
+/// whose UserMeta names a referrer, the referrer's PMUserAccount and its
+/// share of that fee. Empty when no referral share is configured.
+fn referral_rewards<'a, 'b>(
+    program_id: &Pubkey,
+    accounts: &'b [AccountInfo<'a>],
+    extension: &ConfigExtension,
+    vault_program: &Pubkey,
+    sides: &[(Pubkey, u64)],
+) -> Result<Vec<(&'b AccountInfo<'a>, u64)>, ProgramError> {
+    use crate::state::{UserMeta, USER_META_DISCRIMINATOR};
+    
+    let mut rewards = Vec::new();
+    if extension.referral_share_bps == 0 {
+        return Ok(rewards);
+    }
+    for (trader, protocol_fee) in sides {
+        let (meta_info, _) = find_user_meta(program_id, accounts, trader)?;
+        let meta = try_deserialize_initialized::<UserMeta>(
+            &meta_info.data.borrow(),
+            USER_META_DISCRIMINATOR,
+        )?;
+        let Some(referrer) = meta.and_then(|meta| meta.referrer) else {
+            continue;
+        };
+        let reward = extension.referral_reward_on(*protocol_fee)?;
+        if reward > 0 {
+            let referrer_pm_user_info = find_pm_user_account(
+                accounts,
+                &referrer,
+                vault_program,
+                PredictionMarketError::MissingReferrerAccount,
+            )?;
+            rewards.push((referrer_pm_user_info, reward));
+        }
+    }
+    Ok(rewards)
+}
+
+/// Verify that settlement pays out of the configured collateral Vault
+/// 
+/// Every market is collateralized in config.usdc_mint, which is held by
+/// config.vault_program; a VaultConfig owned by any other program belongs
+/// to a different collateral ledger and would pay out in the wrong token.
+fn verify_settlement_vault(
+    vault_config_info: &AccountInfo,
+    vault_program: &Pubkey,
+) -> ProgramResult {
+    if vault_config_info.owner != vault_program {
+        msg!("Error: VaultConfig {} is not owned by Vault {}", vault_config_info.key, vault_program);
+        return Err(PredictionMarketError::InvalidVaultAccount.into());
+    }
+    Ok(())
+}
+
+/// Verify that `pm_user_info` is the Vault PMUserAccount PDA for `owner`
+fn verify_pm_user_account(
+    pm_user_info: &AccountInfo,
+    owner: &Pubkey,
+    vault_program: &Pubkey,
+) -> ProgramResult {
+    use crate::state::VAULT_PM_USER_SEED;
+    
+    let (pm_user_pda, _) = Pubkey::find_program_address(
+        &[VAULT_PM_USER_SEED, owner.as_ref()],
+        vault_program,
+    );
+    if *pm_user_info.key != pm_user_pda {
+        msg!("Error: PMUserAccount {} does not belong to {}", pm_user_info.key, owner);
+        return Err(PredictionMarketError::InvalidVaultAccount.into());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// LLM Oracle Processors (Phase 4.6)
+// ============================================================================
+
+use crate::state::{
+    MarketOracleData, OracleProposalData, ProposalType,
+    MARKET_ORACLE_DATA_SEED, ORACLE_PROPOSAL_DATA_SEED,
+    MARKET_ORACLE_DATA_DISCRIMINATOR, ORACLE_PROPOSAL_DATA_DISCRIMINATOR,
+};
+
+/// Task 4.6.1: Initialize market oracle data account
+fn process_initialize_market_oracle_data(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: InitializeMarketOracleDataArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: MarketOracleData PDA (writable, to be created)
+    let oracle_data_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Task 4.6.2: Verify admin authority
+    if *admin_info.key != config.admin && *admin_info.key != config.oracle_admin {
+        msg!("Unauthorized: {} is not admin", admin_info.key);
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    // Load and validate market
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Derive and validate oracle data PDA
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let (oracle_data_pda, oracle_data_bump) = Pubkey::find_program_address(
+        &[MARKET_ORACLE_DATA_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *oracle_data_info.key != oracle_data_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Create the oracle data account
+    let rent = Rent::get()?;
+    let space = MarketOracleData::SIZE;
+    let lamports = rent.minimum_balance(space);
+    
+    let create_account_ix = system_instruction::create_account(
+        admin_info.key,
+        oracle_data_info.key,
+        lamports,
+        space as u64,
+        program_id,
+    );
+    
+    let seeds: &[&[u8]] = &[MARKET_ORACLE_DATA_SEED, &market_id_bytes, &[oracle_data_bump]];
+    
+    invoke_signed(
+        &create_account_ix,
+        &[admin_info.clone(), oracle_data_info.clone(), system_program_info.clone()],
+        &[seeds],
+    )?;
+    
+    // Initialize the account data
+    let current_time = get_current_timestamp()?;
+    let oracle_data = MarketOracleData::new(args.market_id, oracle_data_bump, current_time, args.challenge_duration_secs);
+    oracle_data.serialize(&mut &mut oracle_data_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Initialized MarketOracleData for market {}", args.market_id);
+    
+    Ok(())
+}
+
+/// Task 4.6.1-4.6.3: Set creation data on market oracle data
+fn process_set_creation_data(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetCreationDataArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: MarketOracleData (writable)
+    let oracle_data_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify admin authority
+    if *admin_info.key != config.admin && *admin_info.key != config.oracle_admin {
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    // Load and validate market - Task 4.6.3: only Pending status
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    if market.status != MarketStatus::Pending {
+        msg!("Market status must be Pending, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    // Load and update oracle data
+    let mut oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
+    if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if oracle_data.market_id != args.market_id {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    oracle_data.set_creation_data(args.creation_data_cid, args.creation_data_hash, current_time);
+    oracle_data.serialize(&mut &mut oracle_data_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Set creation data for market {}", args.market_id);
+    
+    Ok(())
+}
+
+/// Task 4.6.4-4.6.6: Freeze oracle config
+fn process_freeze_oracle_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: FreezeOracleConfigArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: MarketOracleData (writable)
+    let oracle_data_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify admin authority
+    if *admin_info.key != config.admin && *admin_info.key != config.oracle_admin {
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    // Load and update market - Task 4.6.6: transition Pending -> Active
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Load and update oracle data
+    let mut oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
+    if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if oracle_data.market_id != args.market_id {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Require creation data to be set first
+    if !oracle_data.is_creation_data_set {
+        msg!("Creation data must be set before freezing config");
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    oracle_data.freeze_config(args.oracle_config_cid, args.oracle_config_hash, current_time);
+    oracle_data.serialize(&mut &mut oracle_data_info.data.borrow_mut()[..])?;
+    
+    // Transition market to Active if ready
+    if market.status == MarketStatus::Pending && oracle_data.is_ready_for_trading() {
+        market.status = MarketStatus::Active;
+        market.updated_at = current_time;
+        market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+        msg!("Market {} activated (config frozen)", args.market_id);
+    }
+    
+    msg!("✅ Frozen oracle config for market {}", args.market_id);
+    
+    Ok(())
+}
+
+/// Task 4.6.7-4.6.8: Halt trading on market (end time reached)
+fn process_halt_trading(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: HaltTradingArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Anyone (signer) - permissionless
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Load and update market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Task 4.6.8: Time-based check - resolution time must have passed
+    let current_time = get_current_timestamp()?;
+    if current_time < market.resolution_time {
+        msg!("Resolution time not reached: current={}, resolution={}", 
+             current_time, market.resolution_time);
+        return Err(PredictionMarketError::ResolutionTimeNotReached.into());
+    }
+    
+    // Only Active markets can be halted
+    if market.status != MarketStatus::Active {
+        msg!("Market status must be Active, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    // Transition to TradingHalted
+    market.status = MarketStatus::TradingHalted;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Halted trading for market {} (resolution time: {})", 
+         args.market_id, market.resolution_time);
+    msg!("market_status_changed:{},{},{}", args.market_id, "TradingHalted", current_time);
+    
+    Ok(())
+}
+
+/// Propose a market result and open the challenge window
+/// 
+/// Creates the OracleProposal PDA, locks the proposer bond in the Vault and
+/// moves the market to ResultProposed. Only one proposal per market.
+fn process_propose_result(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ProposeResultArgs,
+) -> ProgramResult {
+    propose_result(program_id, accounts, args.market_id, args.result, None)
+}
+
+/// Propose the winning outcome of a multi-outcome market
+/// 
+/// Records `outcome_index` on an OracleProposal with `proposed_result = Yes`,
+/// so challenge, dispute and FinalizeResult work as for binary markets.
+fn process_propose_multi_outcome_result(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ProposeMultiOutcomeResultArgs,
+) -> ProgramResult {
+    propose_result(
+        program_id,
+        accounts,
+        args.market_id,
+        MarketResult::Yes,
+        Some(args.winning_outcome_index),
+    )
+}
+
+/// Shared by ProposeResult and ProposeMultiOutcomeResult
+/// 
+/// `outcome_index` is Some exactly for multi-outcome markets.
+fn propose_result(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    market_id: u64,
+    result: MarketResult,
+    outcome_index: Option<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Proposer (signer, pays for the proposal PDA)
+    let proposer_info = next_account_info(account_info_iter)?;
+    check_signer(proposer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: Proposer's Vault UserAccount (writable)
+    let proposer_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Proposer's PMUserAccount (writable)
+    let pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Proposer must be oracle_admin or a registered caller
+    if *proposer_info.key != config.oracle_admin
+        && !is_registered_caller(program_id, proposer_info.key, accounts)?
+    {
+        msg!("Error: {} is not an authorized proposer", proposer_info.key);
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_pm_user_account(pm_user_info, proposer_info.key, &config.vault_program)?;
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.market_id != market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    if let Some(outcome_index) = outcome_index {
+        if market.market_type != MarketType::MultiOutcome {
+            msg!("Error: Market {} is not a multi-outcome market", market_id);
+            return Err(PredictionMarketError::InvalidMarketType.into());
+        }
+        if outcome_index >= market.num_outcomes {
+            msg!("Error: Outcome index {} out of range (num_outcomes: {})",
+                 outcome_index, market.num_outcomes);
+            return Err(PredictionMarketError::InvalidOutcomeIndex.into());
+        }
+    }
+    
+    // Verify OracleProposal PDA and reject a second proposal
+    let market_id_bytes = market_id.to_le_bytes();
+    let (proposal_pda, proposal_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+        program_id,
+    );
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    if proposal_info.data_len() > 0 {
+        msg!("Error: Proposal already exists for market {}", market_id);
+        return Err(PredictionMarketError::ProposalAlreadyExists.into());
+    }
+    
+    if market.status != MarketStatus::Active {
+        msg!("Error: Market must be Active to propose, got {:?}", market.status);
+        return Err(PredictionMarketError::MarketNotActive.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    require_resolvable(&market, current_time)?;
+    
+    // Create OracleProposal PDA
+    let rent = Rent::get()?;
+    let proposal_seeds: &[&[u8]] = &[ORACLE_PROPOSAL_SEED, &market_id_bytes, &[proposal_bump]];
+    invoke_signed(
+        &system_instruction::create_account(
+            proposer_info.key,
+            proposal_info.key,
+            rent.minimum_balance(OracleProposal::SIZE),
+            OracleProposal::SIZE as u64,
+            program_id,
+        ),
+        &[proposer_info.clone(), proposal_info.clone(), system_program_info.clone()],
+        &[proposal_seeds],
+    )?;
+    
+    // Lock proposer bond
+    let bond_amount = config.proposer_bond_e6;
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    msg!("CPI: Vault.PredictionMarketLock bond={}", bond_amount);
+    let locked = cpi_lock_for_prediction(
+        vault_program_info,
+        vault_config_info,
+        proposer_vault_info,
+        pm_user_info,
+        config_info,
+        proposer_info,
+        system_program_info,
+        bond_amount,
+        config_seeds,
+    )?;
+    
+    if locked != bond_amount {
+        msg!("Error: Proposer could only lock {} of the {} bond", locked, bond_amount);
+        return Err(PredictionMarketError::InsufficientFunds.into());
+    }
+    
+    let challenge_deadline = current_time
+        .checked_add(market.effective_challenge_window_secs(config.challenge_window_secs))
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    
+    let proposal = OracleProposal {
+        discriminator: ORACLE_PROPOSAL_DISCRIMINATOR,
+        market_id,
+        proposer: *proposer_info.key,
+        proposed_result: result,
+        status: ProposalStatus::Pending,
+        proposed_at: current_time,
+        challenge_deadline,
+        bond_amount,
+        challenger: None,
+        challenger_result: None,
+        challenger_bond: 0,
+        bump: proposal_bump,
+        original_challenge_deadline: challenge_deadline,
+        challenge_count: 0,
+        proposed_outcome_index: outcome_index,
+        proposal_resolution_deadline: 0,
+        escalated_challengers: 0,
+        escalated_bonds_e6: 0,
+        reserved: [0u8; 4],
+    };
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    
+    market.status = MarketStatus::ResultProposed;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Proposed {:?} for market {} (challenge deadline: {}, bond: {})",
+         result, market_id, challenge_deadline, bond_amount);
+    msg!("result_proposed:{},{},{:?},{}",
+         market_id, proposer_info.key, result, bond_amount);
+    if let Some(outcome_index) = outcome_index {
+        msg!("multi_outcome_result_proposed:{},{},{},{}",
+             market_id, proposer_info.key, outcome_index, bond_amount);
+    }
+    
+    Ok(())
+}
+
+/// Dispute a pending proposal, or join a dispute, and escrow the challenger bond
+/// 
+/// The proposal moves to Disputed and can no longer be finalized until the
+/// dispute is resolved. The first challenger bond equals
+/// `config.proposer_bond_e6`; until the resolution deadline up to
+/// MAX_DISPUTE_CHALLENGERS - 1 more challengers may join, each posting twice
+/// the previous bond and getting a ChallengerRecord.
+fn process_challenge_result(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ChallengeResultArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Challenger (signer)
+    let challenger_info = next_account_info(account_info_iter)?;
+    check_signer(challenger_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: Challenger's Vault UserAccount (writable)
+    let challenger_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Challenger's PMUserAccount (writable)
+    let pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_pm_user_account(pm_user_info, challenger_info.key, &config.vault_program)?;
+    
+    // Load and validate market
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    if !market.status.can_challenge() {
+        msg!("Error: Market is not in a challengeable state, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    // Load and validate proposal
+    let (proposal_pda, _) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let mut proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    let escalating = proposal.status == ProposalStatus::Disputed;
+    if escalating {
+        if current_time > proposal.resolution_deadline() {
+            msg!("Error: Dispute resolution deadline passed (now: {}, deadline: {})",
+                 current_time, proposal.resolution_deadline());
+            return Err(PredictionMarketError::CannotChallenge.into());
+        }
+    } else if !proposal.can_challenge(current_time) {
+        if proposal.status != ProposalStatus::Pending {
+            msg!("Error: Proposal is not Pending, got {:?}", proposal.status);
+            return Err(PredictionMarketError::CannotChallenge.into());
+        }
+        msg!("Error: Challenge window expired (now: {}, deadline: {})",
+             current_time, proposal.challenge_deadline);
+        return Err(PredictionMarketError::ChallengeWindowExpired.into());
+    }
+    
+    if args.result == proposal.proposed_result {
+        msg!("Error: Challenge agrees with the proposed result {:?}", args.result);
+        return Err(PredictionMarketError::SameResultAsProposal.into());
+    }
+    
+    // Escrow challenger bond, doubling for every challenger already in
+    // the dispute
+    let bond_amount = proposal.next_challenge_bond(config.proposer_bond_e6)?;
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    msg!("CPI: Vault.PredictionMarketLock bond={}", bond_amount);
+    let locked = cpi_lock_for_prediction(
+        vault_program_info,
+        vault_config_info,
+        challenger_vault_info,
+        pm_user_info,
+        config_info,
+        challenger_info,
+        system_program_info,
+        bond_amount,
+        config_seeds,
+    )?;
+    
+    if locked != bond_amount {
+        msg!("Error: Challenger could only lock {} of the {} bond", locked, bond_amount);
+        return Err(PredictionMarketError::InsufficientFunds.into());
+    }
+    
+    if escalating {
+        // Account 9: ChallengerRecord PDA (writable, new)
+        let record_info = next_account_info(account_info_iter)?;
+        record_escalated_challenger(
+            program_id,
+            record_info,
+            (challenger_info, system_program_info),
+            &proposal,
+            args.result,
+            bond_amount,
+            current_time,
+        )?;
+        proposal.escalated_challengers = proposal.escalated_challengers.saturating_add(1);
+        proposal.escalated_bonds_e6 = safe_add_u64(proposal.escalated_bonds_e6, bond_amount)?;
+    } else {
+        proposal.status = ProposalStatus::Disputed;
+        proposal.challenger = Some(*challenger_info.key);
+        proposal.challenger_result = Some(args.result);
+        proposal.challenger_bond = bond_amount;
+        proposal.proposal_resolution_deadline = current_time.saturating_add(DISPUTE_RESOLUTION_PERIOD_SECS);
+    }
+    proposal.challenge_count = proposal.challenge_count.saturating_add(1);
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Market {} proposal disputed: {:?} -> {:?} (bond: {})",
+         args.market_id, proposal.proposed_result, args.result, bond_amount);
+    msg!("result_challenged:{},{},{:?},{}",
+         args.market_id, challenger_info.key, args.result, bond_amount);
+    
+    Ok(())
+}
+
+/// Finalize an unchallenged proposal once its challenge window has passed
+/// 
+/// Permissionless. Writes the proposed result into the market and releases
+/// the proposer bond. Disputed proposals are rejected with ProposalUnderDispute.
+fn process_finalize_result(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Caller (signer, anyone)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: Proposer's Vault UserAccount (writable)
+    let proposer_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Proposer's PMUserAccount (writable)
+    let pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &market.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    if market.status != MarketStatus::ResultProposed {
+        msg!("Error: Market must be ResultProposed to finalize, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    // Load and validate proposal
+    let (proposal_pda, _) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let mut proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    if let Err(e) = proposal.validate_finalize(current_time) {
+        msg!("Error: Cannot finalize proposal ({:?}, now: {}, deadline: {})",
+             proposal.status, current_time, proposal.challenge_deadline);
+        return Err(e.into());
+    }
+    
+    verify_pm_user_account(pm_user_info, &proposal.proposer, &config.vault_program)?;
+    
+    // Return proposer bond
+    if proposal.bond_amount > 0 {
+        let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+        msg!("CPI: Vault.PredictionMarketUnlock bond={}", proposal.bond_amount);
+        cpi_release_from_prediction(
+            vault_program_info,
+            vault_config_info,
+            proposer_vault_info,
+            pm_user_info,
+            config_info,
+            proposal.bond_amount,
+            config_seeds,
+        )?;
+    }
+    
+    market.final_result = Some(proposal.proposed_result);
+    if proposal.proposed_outcome_index.is_some() {
+        market.winning_outcome_index = proposal.proposed_outcome_index;
+    }
+    market.status = MarketStatus::Resolved;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    proposal.status = ProposalStatus::Finalized;
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Market {} resolved as {:?} (bond returned: {})",
+         market.market_id, proposal.proposed_result, proposal.bond_amount);
+    msg!("result_finalized:{},{:?}", market.market_id, proposal.proposed_result);
+    PredictionMarketEvent::MarketResolved(events::MarketResolved {
+        market_id: market.market_id,
+        final_result: market.final_result,
+        winning_outcome_index: market.winning_outcome_index,
+    }).emit()?;
+    
+    Ok(())
+}
+
+/// Resolve a market nobody resolved by its finalization deadline as Invalid
+/// 
+/// An undisputed proposal wins over the deadline: anyone can finalize it,
+/// so invalidating here would only let a caller override a valid result.
+/// Bonds of a disputed proposal are left for ResolveDispute to settle.
+fn process_auto_invalidate_market(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: AutoInvalidateMarketArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Caller (signer, anyone)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let (market_pda, _) = Pubkey::find_program_address(&[MARKET_SEED, &market_id_bytes], program_id);
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    match market.status {
+        MarketStatus::Resolved => {
+            msg!("Error: Market {} is already resolved", args.market_id);
+            return Err(PredictionMarketError::MarketAlreadyResolved.into());
+        }
+        MarketStatus::Cancelled => {
+            msg!("Error: Market {} is cancelled, refunds are already available", args.market_id);
+            return Err(PredictionMarketError::InvalidMarketStatus.into());
+        }
+        _ => {}
+    }
+    
+    let current_time = get_current_timestamp()?;
+    if current_time <= market.finalization_deadline {
+        msg!("Error: Finalization deadline not passed (now: {}, deadline: {})",
+             current_time, market.finalization_deadline);
+        return Err(PredictionMarketError::FinalizationDeadlineNotReached.into());
+    }
+    
+    if market.status == MarketStatus::ResultProposed {
+        // Account 3: OracleProposal PDA
+        let proposal_info = next_account_info(account_info_iter)?;
+        let (proposal_pda, _) = Pubkey::find_program_address(
+            &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+            program_id,
+        );
+        if *proposal_info.key != proposal_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        let proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+        if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        if proposal.status == ProposalStatus::Pending {
+            msg!("Error: Market {} has an undisputed proposal, use FinalizeResult", args.market_id);
+            return Err(PredictionMarketError::InvalidMarketStatus.into());
+        }
+    }
+    
+    market.final_result = Some(MarketResult::Invalid);
+    market.winning_outcome_index = None;
+    market.status = MarketStatus::Resolved;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Market {} auto-invalidated after finalization deadline {}",
+         args.market_id, market.finalization_deadline);
+    msg!("market_status_changed:{},{},{}", args.market_id, "Resolved", current_time);
+    PredictionMarketEvent::MarketResolved(events::MarketResolved {
+        market_id: market.market_id,
+        final_result: market.final_result,
+        winning_outcome_index: market.winning_outcome_index,
+    }).emit()?;
+    
+    Ok(())
+}
+
+/// Load a market for AutoResolveFromFeed / ResolveFromPyth
+/// 
+/// Returns the market once it can be resolved now.
+fn load_feed_resolvable_market(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+    market_info: &AccountInfo,
+    market_id: u64,
+    current_time: i64,
+) -> Result<Market, ProgramError> {
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
+    }
+    
+    let (market_pda, _) = Pubkey::find_program_address(&[MARKET_SEED, &market_id.to_le_bytes()], program_id);
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    require_resolvable(&market, current_time)?;
+    Ok(market)
+}
+
+/// Store a feed-derived result and emit MarketResolved
+fn store_feed_resolution(
+    market_info: &AccountInfo,
+    mut market: Market,
+    result: MarketResult,
+    current_time: i64,
+) -> ProgramResult {
+    market.final_result = Some(result);
+    market.status = MarketStatus::Resolved;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("market_status_changed:{},{},{}", market.market_id, "Resolved", current_time);
+    PredictionMarketEvent::MarketResolved(events::MarketResolved {
+        market_id: market.market_id,
+        final_result: market.final_result,
+        winning_outcome_index: market.winning_outcome_index,
+    }).emit()?;
+    
+    Ok(())
+}
+
+/// Resolve a market from its Switchboard aggregator (permissionless)
+/// 
+/// The latest confirmed round must have opened at or after resolution_time,
+/// so a value observed before the event can't settle the market.
+fn process_auto_resolve_from_feed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: AutoResolveFromFeedArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Caller (signer, anyone)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: Switchboard aggregator
+    let feed_info = next_account_info(account_info_iter)?;
+    
+    let current_time = get_current_timestamp()?;
+    let market = load_feed_resolvable_market(program_id, config_info, market_info, args.market_id, current_time)?;
+    
+    let (feed, operator, threshold) = match ResolutionSource::load(&market_info.data.borrow()) {
+        ResolutionSource::Switchboard { feed, operator, threshold } => (feed, operator, threshold),
+        _ => {
+            msg!("Error: Market {} does not resolve from Switchboard", args.market_id);
+            return Err(PredictionMarketError::ResolutionSourceMismatch.into());
+        }
+    };
+    if *feed_info.key != feed {
+        msg!("Error: Feed mismatch (expected: {}, got: {})", feed, feed_info.key);
+        return Err(PredictionMarketError::ResolutionSourceMismatch.into());
+    }
+    
+    let round = read_switchboard_round(feed_info)?;
+    if round.round_open_timestamp < market.resolution_time {
+        msg!("Error: Latest round opened at {}, before resolution time {}",
+             round.round_open_timestamp, market.resolution_time);
+        return Err(PredictionMarketError::StaleOracleFeed.into());
+    }
+    
+    let result = if operator.holds(round.cmp_threshold(threshold)?) {
+        MarketResult::Yes
+    } else {
+        MarketResult::No
+    };
+    
+    msg!("✅ Market {} resolved {:?} from feed {} (value: {}e-{}, {:?} {}e-9)",
+         args.market_id, result, feed, round.mantissa, round.scale, operator, threshold);
+    store_feed_resolution(market_info, market, result, current_time)
+}
+
+/// Resolve a market from its Pyth price account (permissionless)
+/// 
+/// The aggregate price must have been published at or after resolution_time
+/// and no more than PYTH_MAX_PRICE_AGE_SECS ago. YES if it is at or above
+/// the market's threshold, NO otherwise.
+fn process_resolve_from_pyth(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ResolveFromPythArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Caller (signer, anyone)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: Pyth price account
+    let feed_info = next_account_info(account_info_iter)?;
+    
+    let current_time = get_current_timestamp()?;
+    let market = load_feed_resolvable_market(program_id, config_info, market_info, args.market_id, current_time)?;
+    
+    let (feed, threshold) = match ResolutionSource::load(&market_info.data.borrow()) {
+        ResolutionSource::Pyth { feed, threshold } => (feed, threshold),
+        _ => {
+            msg!("Error: Market {} does not resolve from Pyth", args.market_id);
+            return Err(PredictionMarketError::ResolutionSourceMismatch.into());
+        }
+    };
+    if *feed_info.key != feed {
+        msg!("Error: Feed mismatch (expected: {}, got: {})", feed, feed_info.key);
+        return Err(PredictionMarketError::ResolutionSourceMismatch.into());
+    }
+    
+    let price = read_pyth_price(feed_info)?;
+    if price.publish_time < market.resolution_time
+        || current_time.saturating_sub(price.publish_time) > PYTH_MAX_PRICE_AGE_SECS
+    {
+        msg!("Error: Price published at {} (resolution time: {}, now: {}, max age: {}s)",
+             price.publish_time, market.resolution_time, current_time, PYTH_MAX_PRICE_AGE_SECS);
+        return Err(PredictionMarketError::StaleOracleFeed.into());
+    }
+    
+    let result = if price.cmp_threshold(threshold)? != std::cmp::Ordering::Less {
+        MarketResult::Yes
+    } else {
+        MarketResult::No
+    };
+    
+    msg!("✅ Market {} resolved {:?} from Pyth feed {} (price: {}e{}, threshold: {}e-9)",
+         args.market_id, result, feed, price.price, price.expo, threshold);
+    store_feed_resolution(market_info, market, result, current_time)
+}
+
+/// Resolve a disputed proposal with the committee's result
+/// 
+/// The sides whose result matches the committee share every bond of the
+/// dispute in proportion to their own. If the committee picks a result
+/// nobody argued for all bonds are forfeited: they are settled with zero
+/// payout and stay in the Vault for the fee fund. The committee is
+/// `oracle_admin` for now.
+fn process_resolve_dispute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ResolveDisputeArgs,
+) -> ProgramResult {
+    use crate::state::split_dispute_pool;
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Committee member (signer)
+    let committee_info = next_account_info(account_info_iter)?;
+    check_signer(committee_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: Proposer's PMUserAccount (writable)
+    let proposer_pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: Challenger's PMUserAccount (writable)
+    let challenger_pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if *committee_info.key != config.oracle_admin {
+        msg!("Error: {} is not a committee member", committee_info.key);
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    if market.status.is_terminal() {
+        msg!("Error: Market is already {:?}", market.status);
+        return Err(PredictionMarketError::MarketAlreadyResolved.into());
+    }
+    
+    // Load and validate proposal
+    let (proposal_pda, _) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let mut proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if proposal.status != ProposalStatus::Disputed {
+        msg!("Error: Proposal is not disputed, got {:?}", proposal.status);
+        return Err(PredictionMarketError::ProposalNotDisputed.into());
+    }
+    let (challenger, challenger_result) = match (proposal.challenger, proposal.challenger_result) {
+        (Some(challenger), Some(result)) => (challenger, result),
+        _ => return Err(PredictionMarketError::ProposalNotDisputed.into()),
+    };
+    
+    verify_pm_user_account(proposer_pm_user_info, &proposal.proposer, &config.vault_program)?;
+    verify_pm_user_account(challenger_pm_user_info, &challenger, &config.vault_program)?;
+    
+    // Accounts 8+: (ChallengerRecord, PMUserAccount) of each escalated challenger
+    let escalated = load_escalated_challengers(program_id, account_info_iter, &config, &proposal)?;
+    
+    // The sides matching the committee share all bonds pro rata; a result
+    // nobody argued for forfeits them
+    let proposer_bond = proposal.bond_amount;
+    let challenger_bond = proposal.challenger_bond;
+    let mut stakes = vec![
+        (proposer_bond, args.result == proposal.proposed_result),
+        (challenger_bond, args.result == challenger_result),
+    ];
+    stakes.extend(escalated.iter().map(|(record, _)| (record.bond, args.result == record.result)));
+    let payouts = split_dispute_pool(&stakes)?;
+    let (proposer_payout, challenger_payout) = (payouts[0], payouts[1]);
+    // split_dispute_pool rejects a pool that overflows, and never pays out
+    // more than it
+    let forfeited = stakes.iter().map(|(bond, _)| bond).sum::<u64>() - payouts.iter().sum::<u64>();
+    
+    // Forfeited bonds are credited to the Fund Program's PMUserAccount
+    let fund_pm_user_info = if forfeited > 0 {
+        Some(find_pm_user_account(
+            accounts,
+            &config.fund_program,
+            vault_program_info.key,
+            PredictionMarketError::MissingFundAccount,
+        )?)
+    } else {
+        None
+    };
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    msg!("CPI: Vault.PredictionMarketSettle proposer bond={}, payout={}", proposer_bond, proposer_payout);
+    cpi_prediction_settle(
+        vault_program_info,
+        vault_config_info,
+        proposer_pm_user_info,
+        config_info,
+        proposer_bond,
+        proposer_payout,
+        config_seeds,
+    )?;
+    msg!("CPI: Vault.PredictionMarketSettle challenger bond={}, payout={}", challenger_bond, challenger_payout);
+    cpi_prediction_settle(
+        vault_program_info,
+        vault_config_info,
+        challenger_pm_user_info,
+        config_info,
+        challenger_bond,
+        challenger_payout,
+        config_seeds,
+    )?;
+    for ((record, pm_user_info), payout) in escalated.iter().zip(&payouts[2..]) {
+        msg!("CPI: Vault.PredictionMarketSettle challenger {} bond={}, payout={}",
+             record.challenger, record.bond, payout);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            pm_user_info,
+            config_info,
+            record.bond,
+            *payout,
+            config_seeds,
+        )?;
+    }
+    if let Some(fund_pm_user_info) = fund_pm_user_info {
+        msg!("CPI: Forfeit dispute bonds {} to Fund", forfeited);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            fund_pm_user_info,
+            config_info,
+            0,
+            forfeited,
+            config_seeds,
+        )?;
+    }
+    
+    let current_time = get_current_timestamp()?;
+    
+    proposal.status = if args.result == proposal.proposed_result {
+        ProposalStatus::Finalized
+    } else {
+        ProposalStatus::Rejected
+    };
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    
+    market.final_result = Some(args.result);
+    // An upheld multi-outcome proposal carries its winning outcome
+    if proposal.status == ProposalStatus::Finalized && proposal.proposed_outcome_index.is_some() {
+        market.winning_outcome_index = proposal.proposed_outcome_index;
+    }
+    market.status = MarketStatus::Resolved;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Dispute resolved for market {}: {:?} (proposal {:?}, proposer payout: {}, challenger payout: {})",
+         args.market_id, args.result, proposal.status, proposer_payout, challenger_payout);
+    msg!("dispute_resolved:{},{:?},{},{}",
+         args.market_id, args.result, proposer_payout, challenger_payout);
+    PredictionMarketEvent::MarketResolved(events::MarketResolved {
+        market_id: market.market_id,
+        final_result: market.final_result,
+        winning_outcome_index: market.winning_outcome_index,
+    }).emit()?;
+    
+    Ok(())
+}
+
+/// Expire a dispute the committee did not resolve in time
+/// 
+/// Permissionless once the resolution deadline has passed. Neither side is
+/// at fault, so every bond is returned in full and the market resolves as
+/// Invalid, bounding how long the bonds and market funds stay locked.
+fn process_expire_disputed_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ExpireDisputedProposalArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Caller (signer, anyone)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: Proposer's PMUserAccount (writable)
+    let proposer_pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: Challenger's PMUserAccount (writable)
+    let challenger_pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    if market.status.is_terminal() {
+        msg!("Error: Market is already {:?}", market.status);
+        return Err(PredictionMarketError::MarketAlreadyResolved.into());
+    }
+    
+    // Load and validate proposal
+    let (proposal_pda, _) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    let mut proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if proposal.status != ProposalStatus::Disputed {
+        msg!("Error: Proposal is not disputed, got {:?}", proposal.status);
+        return Err(PredictionMarketError::ProposalNotDisputed.into());
+    }
+    let challenger = proposal.challenger.ok_or(PredictionMarketError::ProposalNotDisputed)?;
+    
+    let current_time = get_current_timestamp()?;
+    if !proposal.can_expire(current_time) {
+        msg!("Error: Dispute resolution deadline not passed (now: {}, deadline: {})",
+             current_time, proposal.resolution_deadline());
+        return Err(PredictionMarketError::DisputeResolutionPending.into());
+    }
+    
+    verify_pm_user_account(proposer_pm_user_info, &proposal.proposer, &config.vault_program)?;
+    verify_pm_user_account(challenger_pm_user_info, &challenger, &config.vault_program)?;
+    
+    // Accounts 8+: (ChallengerRecord, PMUserAccount) of each escalated challenger
+    let escalated = load_escalated_challengers(program_id, account_info_iter, &config, &proposal)?;
+    
+    // Every bond is returned in full
+    let proposer_bond = proposal.bond_amount;
+    let challenger_bond = proposal.challenger_bond;
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    msg!("CPI: Vault.PredictionMarketSettle proposer bond={} returned", proposer_bond);
+    cpi_prediction_settle(
+        vault_program_info,
+        vault_config_info,
+        proposer_pm_user_info,
+        config_info,
+        proposer_bond,
+        proposer_bond,
+        config_seeds,
+    )?;
+    msg!("CPI: Vault.PredictionMarketSettle challenger bond={} returned", challenger_bond);
+    cpi_prediction_settle(
+        vault_program_info,
+        vault_config_info,
+        challenger_pm_user_info,
+        config_info,
+        challenger_bond,
+        challenger_bond,
+        config_seeds,
+    )?;
+    for (record, pm_user_info) in escalated.iter() {
+        msg!("CPI: Vault.PredictionMarketSettle challenger {} bond={} returned", record.challenger, record.bond);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            pm_user_info,
+            config_info,
+            record.bond,
+            record.bond,
+            config_seeds,
+        )?;
+    }
+    
+    proposal.status = ProposalStatus::Rejected;
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    
+    market.final_result = Some(MarketResult::Invalid);
+    market.winning_outcome_index = None;
+    market.status = MarketStatus::Resolved;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Dispute for market {} expired after deadline {}, market invalidated",
+         args.market_id, proposal.resolution_deadline());
+    msg!("dispute_expired:{},{},{}", args.market_id, proposer_bond, proposal.total_challenger_bonds());
+    PredictionMarketEvent::MarketResolved(events::MarketResolved {
+        market_id: market.market_id,
+        final_result: market.final_result,
+        winning_outcome_index: market.winning_outcome_index,
+    }).emit()?;
+    
+    Ok(())
+}
+
+/// Task 4.6.9-4.6.12: Propose result with research data
+fn process_propose_result_with_research(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ProposeResultWithResearchArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Oracle Admin (signer)
+    let oracle_admin_info = next_account_info(account_info_iter)?;
+    check_signer(oracle_admin_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: OracleProposalData PDA (writable)
+    let proposal_data_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: MarketOracleData (for config hash verification)
+    let oracle_data_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Proposer Vault Account (for bond — future use)
+    let _proposer_vault_info = next_account_info(account_info_iter)?;
+    // Account 7: Vault Config (for bond — future use)
+    let _vault_config_info = next_account_info(account_info_iter)?;
+    // Account 8: Vault Program (for bond — future use)
+    let _vault_program_info = next_account_info(account_info_iter)?;
+    // Account 9: System Program (for create_account)
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify oracle admin authority
+    if *oracle_admin_info.key != config.oracle_admin {
+        msg!("Unauthorized: {} is not oracle_admin", oracle_admin_info.key);
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    // Load and update market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Task 4.6.10: Verify oracle config hash + get dynamic challenge duration
+    // MarketOracleData may not exist if the on-chain freeze sync failed during activation.
+    let dynamic_challenge_duration: Option<u32> = if oracle_data_info.data_len() > 0 {
+        let oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
+        if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        
+        if !oracle_data.verify_config_hash(&args.oracle_config_hash) {
+            msg!("Oracle config hash mismatch");
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        msg!("Oracle config hash verified, challenge_duration={}s", oracle_data.challenge_duration_secs);
+        Some(oracle_data.challenge_duration_secs)
+    } else {
+        msg!("⚠️ MarketOracleData not found — using market duration for challenge period");
+        None
+    };
+    
+    // Market must be TradingHalted or AwaitingResult
+    if !matches!(market.status, MarketStatus::TradingHalted | MarketStatus::AwaitingResult) {
+        msg!("Market status must be TradingHalted or AwaitingResult, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    require_resolvable(&market, current_time)?;
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let rent = Rent::get()?;
+    
+    // ── Create OracleProposal PDA ──
+    let (proposal_pda, proposal_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Only create if account doesn't exist yet (allows re-propose after dispute reset)
+    if proposal_info.data_len() == 0 {
+        let proposal_space = OracleProposal::SIZE;
+        let proposal_lamports = rent.minimum_balance(proposal_space);
+        let proposal_seeds: &[&[u8]] = &[ORACLE_PROPOSAL_SEED, &market_id_bytes, &[proposal_bump]];
+        
+        invoke_signed(
+            &system_instruction::create_account(
+                oracle_admin_info.key,
+                proposal_info.key,
+                proposal_lamports,
+                proposal_space as u64,
+                program_id,
+            ),
+            &[oracle_admin_info.clone(), proposal_info.clone(), system_program_info.clone()],
+            &[proposal_seeds],
+        )?;
+        msg!("Created OracleProposal PDA: {}", proposal_pda);
+    }
+    
+    // Initialize OracleProposal with full fields
+    let proposed_result = match args.outcome_index {
+        0 => MarketResult::Yes,
+        1 => MarketResult::No,
+        _ => MarketResult::Invalid,
+    };
+    
+    // Challenge deadline: use per-market dynamic duration if available,
+    // otherwise calculate from market lifetime: min(max(market_duration, 300), 86400)
+    let challenge_duration_secs = dynamic_challenge_duration.unwrap_or_else(|| {
+        // Fallback: calculate from market creation time to resolution time
+        let market_duration = (market.resolution_time - market.created_at).max(300);
+        market_duration.min(86400) as u32
+    });
+    let challenge_deadline = current_time + (challenge_duration_secs as i64);
+    msg!("Challenge deadline: {} ({}s from now)", challenge_deadline, challenge_duration_secs);
+    
+    let proposal = OracleProposal {
+        discriminator: ORACLE_PROPOSAL_DISCRIMINATOR,
+        market_id: args.market_id,
+        proposer: *oracle_admin_info.key,
+        proposed_result,
+        status: ProposalStatus::Pending,
+        proposed_at: current_time,
+        challenge_deadline,
+        bond_amount: config.proposer_bond_e6,
+        challenger: None,
+        challenger_result: None,
+        challenger_bond: 0,
+        bump: proposal_bump,
+        original_challenge_deadline: challenge_deadline,
+        challenge_count: 0,
+        proposed_outcome_index: None,
+        proposal_resolution_deadline: 0,
+        escalated_challengers: 0,
+        escalated_bonds_e6: 0,
+        reserved: [0u8; 4],
+    };
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    
+    // ── Create OracleProposalData PDA ──
+    let (proposal_data_pda, proposal_data_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_data_info.key != proposal_data_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Only create if account doesn't exist yet
+    if proposal_data_info.data_len() == 0 {
+        let proposal_data_space = OracleProposalData::SIZE;
+        let proposal_data_lamports = rent.minimum_balance(proposal_data_space);
+        let proposal_data_seeds: &[&[u8]] = &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes, &[proposal_data_bump]];
+        
+        invoke_signed(
+            &system_instruction::create_account(
+                oracle_admin_info.key,
+                proposal_data_info.key,
+                proposal_data_lamports,
+                proposal_data_space as u64,
+                program_id,
+            ),
+            &[oracle_admin_info.clone(), proposal_data_info.clone(), system_program_info.clone()],
+            &[proposal_data_seeds],
+        )?;
+        msg!("Created OracleProposalData PDA: {}", proposal_data_pda);
+    }
+    
+    // Store research data in OracleProposalData
+    let proposal_data = OracleProposalData::new_llm(
+        args.market_id,
+        args.research_data_cid,
+        args.research_data_hash,
+        args.outcome_index,
+        args.confidence_score,
+        args.requires_manual_review,
+        proposal_data_bump,
+        current_time,
+    );
+    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
+    
+    // Update market status
+    market.status = MarketStatus::ResultProposed;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("OracleProposal: proposer={}, result={:?}, challenge_deadline={}, bond={}",
+         oracle_admin_info.key, proposed_result, challenge_deadline, config.proposer_bond_e6);
+    
+    // Structured log for chain sync parsing (must match LOG_PREFIX_RESULT_PROPOSED in sync.rs)
+    msg!("result_proposed:{},{},{},{}", 
+         args.market_id, oracle_admin_info.key, args.outcome_index, config.proposer_bond_e6);
+    
+    msg!("✅ Proposed result for market {}: outcome={}, confidence={}", 
+         args.market_id, args.outcome_index, args.confidence_score);
+    
+    Ok(())
+}
+
+/// Process manual result proposal (Admin override for UNDETERMINED cases)
+/// 
+/// Task 4.6.13-4.6.16: Manual proposal with evidence
+/// 
+/// Accounts:
+/// 0. `[signer]` Oracle Admin
+/// 1. `[]` PredictionMarketConfig
+/// 2. `[writable]` Market
+/// 3. `[writable]` OracleProposal PDA
+/// 4. `[writable]` OracleProposalData PDA
+/// 5. `[]` MarketOracleData (for original research reference)
+/// 6. `[writable]` Admin's Vault Account (for bond)
+/// 7. `[]` Vault Config
+/// 8. `[]` Vault Program
+/// 9. `[]` System Program
+fn process_propose_result_manual(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ProposeResultManualArgs,
+) -> ProgramResult {
+    msg!("ProposeResultManual: market={}, outcome={}", args.market_id, args.outcome_index);
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Oracle Admin (signer)
+    let oracle_admin_info = next_account_info(account_info_iter)?;
+    check_signer(oracle_admin_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: OracleProposalData PDA (writable)
+    let proposal_data_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: MarketOracleData (for original research reference)
+    let oracle_data_info = next_account_info(account_info_iter)?;
+    
+    // Account 6+: Vault accounts for bond (optional, skipped for now)
+    let _remaining_accounts = account_info_iter;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify oracle admin authority
+    if *oracle_admin_info.key != config.oracle_admin {
+        msg!("Unauthorized: {} is not oracle_admin", oracle_admin_info.key);
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Load MarketOracleData to get original research reference
+    let oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
+    if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Market must be TradingHalted, AwaitingResult, or ResultProposed (to override UNDETERMINED)
+    if !matches!(
+        market.status, 
+        MarketStatus::TradingHalted | MarketStatus::AwaitingResult | MarketStatus::ResultProposed
+    ) {
+        msg!("Market status must be TradingHalted, AwaitingResult, or ResultProposed for manual override, got {:?}", 
+             market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    require_resolvable(&market, current_time)?;
+    let market_id_bytes = args.market_id.to_le_bytes();
+    
+    // Validate OracleProposal PDA
+    let (proposal_pda, _proposal_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Validate OracleProposalData PDA
+    let (proposal_data_pda, proposal_data_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_data_info.key != proposal_data_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Task 4.6.14-4.6.15: Create manual proposal data with evidence
+    // Use research_data from original LLM attempt (if any)
+    let research_cid = oracle_data.oracle_config_cid; // Reference to original config/research
+    let research_hash = oracle_data.oracle_config_hash;
+    
+    let proposal_data = OracleProposalData::new_manual(
+        args.market_id,
+        research_cid,                    // Original research reference
+        research_hash,                   // Original research hash
+        args.manual_proposal_cid,        // Manual judgment IPFS CID
+        args.manual_reasoning_hash,      // Manual reasoning hash
+        args.outcome_index,              // Admin's determined outcome
+        proposal_data_bump,
+        current_time,
+    );
+    
+    // Serialize proposal data to account
+    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
+    
+    // Update market status to ResultProposed
+    market.status = MarketStatus::ResultProposed;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    // Structured log for chain sync parsing (must match LOG_PREFIX_RESULT_PROPOSED in sync.rs)
+    msg!("result_proposed:{},{},{},{}", 
+         args.market_id, oracle_admin_info.key, args.outcome_index, 0);
+    
+    msg!("✅ Manual proposal for market {}: outcome={}, manual_cid={:?}", 
+         args.market_id, 
+         args.outcome_index,
+         String::from_utf8_lossy(&args.manual_proposal_cid[0..20]));
+    
+    Ok(())
+}
+
+/// Process challenge with evidence (Task 4.6.17-4.6.20)
+/// 
+/// Allows any user to challenge a proposed result by posting a counter-bond
+/// and providing evidence (IPFS CID + hash) supporting their alternative outcome.
+/// 
+/// Accounts:
+/// 0. `[signer]` Challenger
+/// 1. `[]` PredictionMarketConfig
+/// 2. `[writable]` Market
+/// 3. `[writable]` OracleProposal PDA
+/// 4. `[writable]` OracleProposalData PDA (to record challenger's outcome)
+/// 5. `[writable]` Challenger's Vault Account (for bond)
+/// 6. `[writable]` Market Vault (to receive bond)
+/// 7. `[]` Vault Config
+/// 8. `[]` Vault Program
+fn process_challenge_result_with_evidence(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ChallengeResultWithEvidenceArgs,
+) -> ProgramResult {
+    msg!("ChallengeResultWithEvidence: market={}, challenger_outcome={}", 
+         args.market_id, args.challenger_outcome_index);
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Challenger (signer)
+    let challenger_info = next_account_info(account_info_iter)?;
+    check_signer(challenger_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: OracleProposalData PDA (writable)
+    let proposal_data_info = next_account_info(account_info_iter)?;
+    
+    // Account 5+: Vault accounts for bond transfer (handled separately)
+    let _remaining_accounts = account_info_iter;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Market must be in ResultProposed state
+    if market.status != MarketStatus::ResultProposed {
+        msg!("Market must be in ResultProposed state to challenge, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    let market_id_bytes = args.market_id.to_le_bytes();
+    
+    // Validate OracleProposal PDA
+    let (proposal_pda, _proposal_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load and validate OracleProposal to check challenge window
+    let proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify within challenge window — use stored challenge_deadline (consistent with FinalizeResultV2)
+    if current_time > proposal.challenge_deadline {
+        msg!("Challenge window has expired: current={}, deadline={}", current_time, proposal.challenge_deadline);
+        return Err(PredictionMarketError::ChallengeWindowExpired.into());
+    }
+    
+    // Validate OracleProposalData PDA
+    let (proposal_data_pda, _proposal_data_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_data_info.key != proposal_data_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load and update OracleProposalData with challenger's outcome
+    let mut proposal_data = deserialize_account::<OracleProposalData>(&proposal_data_info.data.borrow())?;
+    if proposal_data.discriminator != ORACLE_PROPOSAL_DATA_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Challenger's outcome must differ from proposed outcome
+    if args.challenger_outcome_index == proposal_data.proposed_outcome_index {
+        msg!("Challenger outcome must differ from proposed outcome");
+        return Err(PredictionMarketError::InvalidOutcome.into());
+    }
+    
+    // Record challenger's outcome and evidence hash
+    proposal_data.set_challenger(args.challenger_outcome_index, current_time);
+    
+    // Record challenger info on the OracleProposal itself (for dispute resolution)
+    let mut proposal = proposal; // make mutable
+    let challenger_result = match args.challenger_outcome_index {
+        0 => MarketResult::Yes,
+        1 => MarketResult::No,
+        _ => MarketResult::Invalid,
+    };
+    proposal.challenger = Some(*challenger_info.key);
+    proposal.challenger_result = Some(challenger_result);
+    // Note: Bond amount not set here — CPI to Vault not available in this instruction variant.
+    // Use RelayerChallengeResultV2 (Index 72) for proper bond locking.
+    
+    // Extend challenge deadline on-chain (consistent with DB-side extension)
+    let challenge_duration = config.challenge_window_secs.max(3600) as i64;
+    let new_deadline = (current_time + challenge_duration).max(proposal.challenge_deadline);
+    proposal.challenge_deadline = new_deadline;
+    proposal.challenge_count = proposal.challenge_count.saturating_add(1);
+    
+    // Update market status to Challenged
+    market.status = MarketStatus::Challenged;
+    market.updated_at = current_time;
+    
+    // Serialize ALL updated accounts (proposal + proposal_data + market)
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("Challenge recorded: challenger={}, outcome={}, new_deadline={}", 
+         challenger_info.key, args.challenger_outcome_index, new_deadline);
+    
+    msg!("✅ Challenge submitted for market {}: challenger={}, outcome={}, evidence_hash={:?}", 
+         args.market_id,
+         challenger_info.key,
+         args.challenger_outcome_index,
+         &args.evidence_hash[0..8]);
+    
+    Ok(())
+}
+
+// ============================================================================
+// V2 Multi-Outcome Order Instructions (Pure Vault Mode)
+// ============================================================================
+
+/// V2: Place order for multi-outcome market with Vault CPI
+/// Similar to RelayerPlaceOrderV2 but uses outcome_index instead of Outcome enum
+fn process_relayer_place_multi_outcome_order_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerPlaceMultiOutcomeOrderV2Args,
+) -> ProgramResult {
+    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, MAX_OUTCOMES};
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
+    }
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Verify this is a multi-outcome market
+    if market.market_type != MarketType::MultiOutcome {
+        msg!("Error: RelayerPlaceMultiOutcomeOrderV2 requires MultiOutcome market type");
+        return Err(PredictionMarketError::InvalidMarketType.into());
+    }
+    
+    // Validate outcome_index
+    if args.outcome_index >= market.num_outcomes {
+        msg!("Error: outcome_index {} >= num_outcomes {}", args.outcome_index, market.num_outcomes);
+        return Err(PredictionMarketError::InvalidOutcome.into());
+    }
+    
+    market.check_tradeable()?;
+    
+    // Account 3: Order PDA (writable, new)
+    let order_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: MultiOutcomePosition PDA
+    let position_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: User Vault Account
+    let user_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: PM User Account
+    let pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    // Account 9: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Derive and verify Order PDA
+    let order_id = market.next_order_id;
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let order_id_bytes = order_id.to_le_bytes();
+    let (order_pda, order_bump) = Pubkey::find_program_address(
+        &[ORDER_SEED, &market_id_bytes, &order_id_bytes],
+        program_id,
+    );
+    
+    if *order_info.key != order_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    config.check_order_minimum(args.amount, args.price)?;
+    
+    // Market orders need a fill in the same transaction, which only the
+    // binary ExecuteTradeV2/ExecuteTradeMultiV2 flow provides; post-only
+    // orders are only checked against a binary book
+    if matches!(args.order_type, crate::state::OrderType::Market | crate::state::OrderType::PostOnly) {
+        msg!("Error: {:?} orders are not supported on multi-outcome markets", args.order_type);
+        return Err(PredictionMarketError::InvalidOrderType.into());
+    }
+    
+    // Calculate margin requirement (in e6 precision)
+    // margin_e6 = amount_e6 × price_e6 / PRICE_PRECISION
+    // All amounts are in e6 precision (1 share = 1_000_000 units).
+    let mut margin = mul_div_floor(args.amount, args.price, PRICE_PRECISION)?;
+    let mut amount = args.amount;
+    
+    let current_time = get_current_timestamp()?;
+    
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(
+        &[PM_CONFIG_SEED],
+        program_id,
+    );
+    
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    // For Buy orders: Lock margin in Vault
+    if args.side == crate::state::OrderSide::Buy {
+        msg!("CPI: Lock margin {} for Buy order", margin);
+        let locked = cpi_lock_for_prediction(
+            vault_program_info,
+            vault_config_info,
+            user_vault_info,
+            pm_user_info,
+            config_info,
+            relayer_info,
+            system_program_info,
+            margin,
+            config_seeds,
+        )?;
+        
+        // The Vault clamped to the available balance: size the order to
+        // the margin it actually locked
+        if locked < margin {
+            amount = mul_div_floor(locked, PRICE_PRECISION, args.price)?;
+            margin = locked;
+            if amount == 0 {
+                return Err(PredictionMarketError::InsufficientFunds.into());
+            }
+            config.check_order_minimum(amount, args.price)?;
+            msg!("Order resized to {} for locked margin {}", amount, locked);
+        }
+    } else {
+        // For Sell orders: Verify MultiOutcomePosition has sufficient AVAILABLE holdings and LOCK them
+        let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
+        if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        
+        let idx = args.outcome_index as usize;
+        if idx >= MAX_OUTCOMES {
+            return Err(PredictionMarketError::InvalidOutcome.into());
+        }
+        
+        // Check available (total - locked)
+        let total = position.holdings[idx];
+        let locked = position.locked[idx];
+        let available = total.saturating_sub(locked);
+        
+        if available < args.amount {
+            msg!("Error: Insufficient available holdings: {} < {} (total: {}, locked: {})", 
+                 available, args.amount, total, locked);
+            return Err(PredictionMarketError::InsufficientPosition.into());
+        }
+        
+        // Lock shares for this Sell order
+        position.locked[idx] = position.locked[idx].saturating_add(args.amount);
+        position.updated_at = current_time;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        
+        msg!("📊 MultiOutcome Position locked: {} shares for outcome {}", args.amount, args.outcome_index);
+    }
+    
+    // Create Order
+    let order_space = Order::SIZE;
+    let rent = Rent::get()?;
+    let lamports = rent.minimum_balance(order_space);
+    
+    // Create account via CPI
+    let order_seeds: &[&[u8]] = &[ORDER_SEED, &market_id_bytes, &order_id_bytes, &[order_bump]];
+    
+    invoke_signed(
+        &system_instruction::create_account(
+            relayer_info.key,
+            order_info.key,
+            lamports,
+            order_space as u64,
+            program_id,
+        ),
+        &[relayer_info.clone(), order_info.clone(), system_program_info.clone()],
+        &[order_seeds],
+    )?;
+    
+    // Initialize Order - use outcome_index for multi-outcome
+    // Note: We use Outcome::Yes as placeholder since Order struct uses Outcome enum
+    // The actual outcome is stored in outcome_index field
+    let order = Order {
+        discriminator: ORDER_DISCRIMINATOR,
+        order_id,
+        market_id: args.market_id,
+        owner: args.user_wallet,
+        side: args.side,
+        outcome: Outcome::Yes, // Placeholder for multi-outcome
+        outcome_index: args.outcome_index,
+        price: args.price,
+        amount,
+        filled_amount: 0,
+        status: OrderStatus::Open,
+        order_type: args.order_type,
+        expiration_time: args.expiration_time,
+        created_at: current_time,
+        updated_at: current_time,
+        bump: order_bump,
+        escrow_token_account: None, // V2: No SPL token escrow
+        locks_released: false,
+        margin_locked: args.side == crate::state::OrderSide::Buy,
+        locked_margin_e6: if args.side == crate::state::OrderSide::Buy { margin } else { 0 },
+        reduce_only: false,
+        reserved: [0u8; 19],
+    };
+    order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
+    
+    // Update market
+    market.next_order_id = market.next_order_id.saturating_add(1);
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ RelayerPlaceMultiOutcomeOrderV2 completed");
+    msg!("User: {}", args.user_wallet);
+    msg!("Order ID: {}, Market: {}", order_id, args.market_id);
+    msg!("Side: {:?}, Outcome Index: {}", args.side, args.outcome_index);
+    msg!("Price: {}, Amount: {}, Margin: {}", args.price, amount, margin);
+    
+    msg!("multi_outcome_order_placed:{},{},{},{},{:?},{},{}", args.market_id, order_id, args.user_wallet, args.outcome_index, args.side, args.price, amount);
+    PredictionMarketEvent::OrderPlaced(events::OrderPlaced {
+        market_id: args.market_id,
+        order_id,
+        owner: args.user_wallet,
+        side: args.side,
+        outcome_index: args.outcome_index,
+        price: args.price,
+        amount,
+    }).emit()?;
+    
+    Ok(())
+}
+
+/// V2: Cancel order for multi-outcome market with Vault CPI
+fn process_relayer_cancel_multi_outcome_order_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerCancelMultiOutcomeOrderV2Args,
+) -> ProgramResult {
+    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, MAX_OUTCOMES};
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Verify this is a multi-outcome market
+    if market.market_type != MarketType::MultiOutcome {
+        msg!("Error: RelayerCancelMultiOutcomeOrderV2 requires MultiOutcome market type");
+        return Err(PredictionMarketError::InvalidMarketType.into());
+    }
+    
+    // Account 3: Order PDA (writable)
+    let order_info = next_account_info(account_info_iter)?;
+    let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
+    
+    if order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if order.order_id != args.order_id || order.market_id != args.market_id {
+        return Err(PredictionMarketError::OrderNotFound.into());
+    }
+    
+    if order.owner != args.user_wallet {
+        return Err(PredictionMarketError::OrderOwnerMismatch.into());
+    }
+    
+    if !order.is_cancellable() {
+        return Err(PredictionMarketError::OrderNotActive.into());
+    }
+    
+    // Account 4: MultiOutcomePosition PDA
+    let position_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: User Vault Account
+    let user_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: PM User Account
+    let pm_user_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Config
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    // Account 9: System Program
+    let _system_program_info = next_account_info(account_info_iter)?;
+    
+    // Remaining amount and the margin still locked for it (e6 precision),
+    // as tracked on the order through its fills
+    let remaining = order.amount.saturating_sub(order.filled_amount);
+    let remaining_margin = order.remaining_locked_margin();
+    
+    let current_time = get_current_timestamp()?;
+    
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(
+        &[PM_CONFIG_SEED],
+        program_id,
+    );
+    
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    // For Buy orders: Unlock margin from Vault
+    if order.side == crate::state::OrderSide::Buy {
+        msg!("CPI: Release margin {} for cancelled Buy order", remaining_margin);
+        cpi_release_from_prediction(
+            vault_program_info,
+            vault_config_info,
+            user_vault_info,
+            pm_user_info,
+            config_info,
+            remaining_margin,
+            config_seeds,
+        )?;
+    } else {
+        // For Sell orders: Unlock shares from MultiOutcomePosition
+        let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
+        if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        
+        let idx = args.outcome_index as usize;
+        if idx >= MAX_OUTCOMES {
+            return Err(PredictionMarketError::InvalidOutcome.into());
+        }
+        
+        // Unlock shares
+        position.locked[idx] = position.locked[idx].saturating_sub(remaining);
+        position.updated_at = current_time;
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        
+        msg!("📊 MultiOutcome Position unlocked: {} shares for outcome {}", remaining, args.outcome_index);
+    }
+    
+    // Update order status
+    order.status = OrderStatus::Cancelled;
+    order.locked_margin_e6 = 0;
+    order.updated_at = current_time;
+    order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
+    
+    // Update market
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ RelayerCancelMultiOutcomeOrderV2 completed");
+    msg!("User: {}", args.user_wallet);
+    msg!("Order ID: {}, Market: {}", args.order_id, args.market_id);
+    msg!("Remaining amount: {}, Unlocked margin/shares: {}", remaining, remaining_margin);
+    
+    msg!("multi_outcome_order_cancelled:{},{}", args.market_id, args.order_id);
+    PredictionMarketEvent::OrderCancelled(events::OrderCancelled { market_id: args.market_id, order_id: args.order_id }).emit()?;
+    
+    Ok(())
+}
+
+// ============================================================================
+// Multi-Outcome V2 Instructions (Vault CPI Mode)
+// ============================================================================
+
+/// V2: RelayerMintMultiOutcomeCompleteSet using Vault CPI (no SPL Token)
+/// 
+/// Mints a complete set of all outcome tokens for a multi-outcome market.
+/// 1 complete set = 1 token of each outcome
+/// Cost = amount * 1.0 USDC (locked in Vault)
+fn process_relayer_mint_multi_outcome_complete_set_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerMintMultiOutcomeCompleteSetArgs,
+) -> ProgramResult {
+    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, 
+                       MULTI_OUTCOME_POSITION_SEED, MAX_OUTCOMES, MarketType};
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: MultiOutcomePosition PDA (writable)
+    let position_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: UserAccount (Vault, writable)
+    let user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: PMUserAccount (Vault, writable)
+    let pm_user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: VaultConfig
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
+    }
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Verify this is a multi-outcome market
+    if market.market_type != MarketType::MultiOutcome {
+        msg!("❌ Expected MultiOutcome market, got {:?}", market.market_type);
+        return Err(PredictionMarketError::InvalidMarketType.into());
+    }
+    
+    market.check_tradeable()?;
+    
+    if args.amount == 0 {
+        return Err(PredictionMarketError::InvalidAmount.into());
+    }
+    
+    market.check_tvl_cap(args.amount)?;
+    
+    let current_time = get_current_timestamp()?;
+    let market_id_bytes = market.market_id.to_le_bytes();
+    
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(
+        &[PM_CONFIG_SEED],
+        program_id,
+    );
+    
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    // Verify MultiOutcomePosition PDA
+    let (position_pda, position_bump) = Pubkey::find_program_address(
+        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
+        program_id,
+    );
+    
+    if *position_info.key != position_pda {
+        msg!("❌ Invalid MultiOutcomePosition PDA");
+        msg!("Expected: {}, Got: {}", position_pda, position_info.key);
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Step 1: CPI to Vault - PredictionMarketLock
+    msg!("CPI: Vault.PredictionMarketLock amount={}", args.amount);
+    let amount = cpi_lock_for_prediction(
+        vault_program_info,
+        vault_config_info,
+        user_account_info,
+        pm_user_account_info,
+        config_info,
+        relayer_info,
+        system_program_info,
+        args.amount,
+        config_seeds,
+    )?;
+    
+    // The Vault may clamp to the available balance; mint only what it locked
+    if amount == 0 {
+        return Err(PredictionMarketError::InsufficientFunds.into());
+    }
+    
+    // Step 2: Create or update MultiOutcomePosition
+    let is_new_position = position_info.data_is_empty();
+    
+    if is_new_position {
+        // Create new MultiOutcomePosition account
+        let rent = Rent::get()?;
+        let space = MultiOutcomePosition::SIZE;
+        let lamports = rent.minimum_balance(space);
+        let position_seeds: &[&[u8]] = &[
+            MULTI_OUTCOME_POSITION_SEED,
+            &market_id_bytes,
+            args.user_wallet.as_ref(),
+            &[position_bump],
+        ];
+        
+        invoke_signed(
+            &system_instruction::create_account(
+                relayer_info.key,
+                position_info.key,
+                lamports,
+                space as u64,
+                program_id,
+            ),
+            &[
+                relayer_info.clone(),
+                position_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[position_seeds],
+        )?;
+        
+        // Initialize MultiOutcomePosition
+        let mut position = MultiOutcomePosition::new(
+            market.market_id,
+            market.num_outcomes,
+            args.user_wallet,
+            position_bump,
+            current_time,
+        );
+        
+        // Add to all outcome holdings
+        let num_outcomes = market.num_outcomes as usize;
+        for i in 0..num_outcomes {
+            position.holdings[i] = amount;
+        }
+        position.total_cost_e6 = amount;
+        
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        msg!("✅ Created new MultiOutcomePosition");
+    } else {
+        // Update existing position
+        let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
+        
+        if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        
+        let num_outcomes = market.num_outcomes as usize;
+        for i in 0..num_outcomes {
+            position.holdings[i] = position.holdings[i].saturating_add(amount);
+        }
+        position.total_cost_e6 = position.total_cost_e6.saturating_add(amount);
+        position.updated_at = current_time;
+        
+        position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+        msg!("✅ Updated existing MultiOutcomePosition");
+    }
+    
+    // Step 3: Update Market
+    market.total_minted = market.total_minted.saturating_add(amount);
+    market.increase_open_interest(amount)?;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ RelayerMintMultiOutcomeCompleteSetV2 completed");
+    msg!("User: {}", args.user_wallet);
+    msg!("Market: {}", market.market_id);
+    msg!("Amount: {}", amount);
+    msg!("Total Minted: {}", market.total_minted);
+    
+    msg!("multi_outcome_set_minted:{},{},{},{}", args.market_id, args.user_wallet, amount, amount);
+    PredictionMarketEvent::CompleteSetMinted(events::CompleteSetMinted {
+        market_id: args.market_id,
+        owner: args.user_wallet,
+        amount,
+    }).emit()?;
+    
+    Ok(())
+}
+
+/// V2: RelayerRedeemMultiOutcomeCompleteSet using Vault CPI (no SPL Token)
+/// 
+/// Redeems a complete set of all outcome tokens for multi-outcome market.
+/// User must have >= amount of ALL outcome tokens.
+/// Returns 1 USDC per complete set.
+fn process_relayer_redeem_multi_outcome_complete_set_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerRedeemMultiOutcomeCompleteSetArgs,
+) -> ProgramResult {
+    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, 
+                       MULTI_OUTCOME_POSITION_SEED, MarketType};
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: MultiOutcomePosition PDA (writable)
+    let position_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: UserAccount (Vault, writable)
+    let user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: PMUserAccount (Vault, writable)
+    let pm_user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: VaultConfig
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
+    }
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    if market.market_type != MarketType::MultiOutcome {
+        msg!("❌ Expected MultiOutcome market, got {:?}", market.market_type);
+        return Err(PredictionMarketError::InvalidMarketType.into());
+    }
+    
+    if args.amount == 0 {
+        return Err(PredictionMarketError::InvalidAmount.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    let market_id_bytes = market.market_id.to_le_bytes();
+    
+    // Verify MultiOutcomePosition PDA
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
+        program_id,
+    );
+    
+    if *position_info.key != position_pda {
+        msg!("❌ Invalid MultiOutcomePosition PDA");
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load position
+    let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
+    
+    if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify user has sufficient AVAILABLE amounts of ALL outcomes
+    let num_outcomes = market.num_outcomes as usize;
+    for i in 0..num_outcomes {
+        let available = position.holdings[i].saturating_sub(position.locked[i]);
+        if available < args.amount {
+            msg!("❌ Insufficient available outcome {} tokens: available {}, need {}", 
+                 i, available, args.amount);
+            return Err(PredictionMarketError::InsufficientPosition.into());
+        }
+    }
+    
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(
+        &[PM_CONFIG_SEED],
+        program_id,
+    );
+    
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    // Step 1: Vault CPI - Unlock funds
+    msg!("CPI: Vault.PredictionMarketUnlock amount={}", args.amount);
+    cpi_release_from_prediction(
+        vault_program_info,
+        vault_config_info,
+        user_account_info,
+        pm_user_account_info,
+        config_info,
+        args.amount,
+        config_seeds,
+    )?;
+    
+    // Step 2: Update MultiOutcomePosition - reduce all holdings
+    for i in 0..num_outcomes {
+        position.holdings[i] = position.holdings[i].saturating_sub(args.amount);
+    }
+    position.total_cost_e6 = position.total_cost_e6.saturating_sub(args.amount);
+    position.updated_at = current_time;
+    
+    position.serialize(&mut *position_info.data.borrow_mut())?;
+    
+    // Step 3: Update Market
+    market.total_minted = market.total_minted.saturating_sub(args.amount);
+    market.decrease_open_interest(args.amount);
+    market.updated_at = current_time;
+    market.serialize(&mut *market_info.data.borrow_mut())?;
+    
+    msg!("✅ RelayerRedeemMultiOutcomeCompleteSetV2 completed");
+    msg!("User: {}", args.user_wallet);
+    msg!("Market: {}", market.market_id);
+    msg!("Amount: {}", args.amount);
+    msg!("Total Minted: {}", market.total_minted);
+    
+    msg!("multi_outcome_set_redeemed:{},{},{},{}", args.market_id, args.user_wallet, args.amount, args.amount);
+    
+    Ok(())
+}
+
+/// V2: RelayerClaimMultiOutcomeWinnings using Vault CPI (no SPL Token)
+/// 
+/// Claims winnings after market resolution for multi-outcome market.
+/// Settlement = amount of winning outcome tokens * 1 USDC
+/// Losing outcomes pay nothing; an Invalid resolution refunds the remaining
+/// locked cost like a cancelled market.
+fn process_relayer_claim_multi_outcome_winnings_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RelayerClaimMultiOutcomeWinningsArgs,
+) -> ProgramResult {
+    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, 
+                       MULTI_OUTCOME_POSITION_SEED, MarketType, MarketStatus};
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: MultiOutcomePosition PDA (writable)
+    let position_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: PMUserAccount (Vault, writable)
+    let pm_user_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: VaultConfig
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+
+    // Account 7 (optional): UserAccount — if present, settle directly to available_balance
+    let user_vault_info = next_account_info(account_info_iter).ok();
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_settlement_vault(vault_config_info, vault_program_info.key)?;
+    verify_pm_user_account(pm_user_account_info, &args.user_wallet, vault_program_info.key)?;
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    if market.market_type != MarketType::MultiOutcome {
+        return Err(PredictionMarketError::InvalidMarketType.into());
+    }
+    
+    // Market must be Resolved or Cancelled
+    if market.status != MarketStatus::Resolved && market.status != MarketStatus::Cancelled {
+        msg!("❌ Market status must be Resolved or Cancelled, got {:?}", market.status);
+        return Err(PredictionMarketError::MarketNotResolved.into());
+    }
+    
+    let market_id_bytes = market.market_id.to_le_bytes();
+    let current_time = get_current_timestamp()?;
+    
+    // Verify MultiOutcomePosition PDA
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
+        program_id,
+    );
+    
+    if *position_info.key != position_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load position
+    let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
+    
+    if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if position.settled {
+        msg!("Position already settled");
+        return Err(PredictionMarketError::AlreadySettled.into());
+    }
+    
+    // Settlement only walks the first num_outcomes slots
+    if position.outcome_count().is_none() || position.num_outcomes != market.num_outcomes {
+        msg!("❌ Position num_outcomes {} invalid for market ({})", position.num_outcomes, market.num_outcomes);
+        return Err(PredictionMarketError::OutcomesMismatch.into());
+    }
+    
+    // Calculate settlement.
+    // CRITICAL: Use remaining_locked (total_cost - settled_cost) to avoid
+    // double-releasing pm_locked that was already consumed in trades.
+    let remaining_locked = position.total_cost_e6.saturating_sub(position.settled_cost_e6);
+    let locked_amount = remaining_locked;
+    
+    let settlement_amount = if market.status == MarketStatus::Cancelled
+        || market.final_result == Some(MarketResult::Invalid)
+    {
+        // Cancelled or resolved Invalid: refund only remaining locked
+        // (not already traded away)
+        locked_amount
+    } else {
+        // Get winning outcome index
+        let winning_outcome_index = market.winning_outcome_index
+            .ok_or(PredictionMarketError::MarketNotResolved)?;
+        
+        if winning_outcome_index >= position.num_outcomes {
+            return Err(PredictionMarketError::InvalidOutcomeIndex.into());
+        }
+        
+        // Winning tokens pay out 1:1 (1 share = $1 USDC in e6)
+        position.calculate_settlement(winning_outcome_index)
+    };
+    
+    // Derive Config PDA for CPI signing
+    let (config_pda, config_bump) = Pubkey::find_program_address(
+        &[PM_CONFIG_SEED],
+        program_id,
+    );
+    
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    // Vault CPI — only if there's something to settle
+    if locked_amount > 0 || settlement_amount > 0 {
+        if let Some(uvi) = user_vault_info {
+            msg!("CPI: Vault.SettleToAvailable locked={}, settlement={} (multi-outcome)", locked_amount, settlement_amount);
+            cpi_prediction_settle_to_available(
+                vault_program_info,
+                vault_config_info,
+                uvi,
+                pm_user_account_info,
+                config_info,
+                locked_amount,
+                settlement_amount,
+                config_seeds,
+            )?;
+        } else {
+            msg!("CPI: Vault.Settle locked={}, settlement={} (multi-outcome, legacy)", locked_amount, settlement_amount);
+            cpi_prediction_settle(
+                vault_program_info,
+                vault_config_info,
+                pm_user_account_info,
+                config_info,
+                locked_amount,
+                settlement_amount,
+                config_seeds,
+            )?;
+        }
+    } else {
+        msg!("Multi-outcome: loser/zero position, locked=0, settlement=0, skipping CPI");
+    }
+    
+    // Update position
+    let pnl = (settlement_amount as i64) - (locked_amount as i64);
+    position.realized_pnl = position.realized_pnl.saturating_add(pnl);
+    position.settlement_amount = settlement_amount;
+    position.settled = true;
+    // One winning share per outstanding set (outcome 0 if cancelled)
+    market.decrease_open_interest(position.calculate_settlement(market.winning_outcome_index.unwrap_or(0)));
+    // Clear holdings for the market's outcomes
+    position.clear_holdings()?;
+    position.updated_at = current_time;
+    
+    position.serialize(&mut &mut position_info.data.borrow_mut()[..])?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    record_cancelled_payouts(market_info, &market, 1)?;
+    
+    msg!("✅ RelayerClaimMultiOutcomeWinningsV2 completed");
+    msg!("User: {}", args.user_wallet);
+    msg!("Market: {}", market.market_id);
+    msg!("Settlement: {}, PnL: {}", settlement_amount, pnl);
+    
+    msg!("multi_outcome_winnings_claimed:{},{},{},{}", market.market_id, args.user_wallet, market.winning_outcome_index.unwrap_or(255), settlement_amount);
+    
+    Ok(())
+}
+
+// ============================================================================
+// V15.1: FinalizeResultV2 - Finalize result after challenge window
+// ============================================================================
+
+/// Process FinalizeResultV2 instruction
+/// 
+/// Transitions market from ResultProposed to Resolved after challenge window expires.
+/// This is permissionless - anyone can call it after the deadline.
+/// The proposer's bond is returned via Vault CPI.
+fn process_finalize_result_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: FinalizeResultV2Args,
+) -> ProgramResult {
+    use crate::state::{OracleProposal, OracleProposalData, ORACLE_PROPOSAL_DISCRIMINATOR, 
+                       ORACLE_PROPOSAL_SEED, ORACLE_PROPOSAL_DATA_DISCRIMINATOR,
+                       ORACLE_PROPOSAL_DATA_SEED, MarketStatus, ProposalStatus};
+    
+    msg!("FinalizeResultV2: market={}", args.market_id);
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Caller (signer) - permissionless
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Account 4: OracleProposalData PDA
+    let proposal_data_info = next_account_info(account_info_iter)?;
+    
+    // Account 5: Proposer's PMUserAccount (Vault, writable) - for bond return
+    let proposer_pm_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: VaultConfig
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    // Load and validate config
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    let config_bump = config.bump;
+    
+    // Load and validate market
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Market must be in ResultProposed or Challenged state
+    // Challenged markets can be finalized after the extended challenge deadline passes
+    // (dispute resolution via DB resets or admin action sets status back to ResultProposed,
+    //  but we also accept Challenged directly to handle the case where dispute resolution
+    //  uses the original proposal result as the final outcome — "upheld" scenario)
+    if market.status != MarketStatus::ResultProposed && market.status != MarketStatus::Challenged {
+        msg!("❌ Market must be in ResultProposed or Challenged state, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let current_time = get_current_timestamp()?;
+    
+    // Verify OracleProposal PDA
+    let (proposal_pda, _) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load and validate proposal
+    let mut proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify OracleProposalData PDA
+    let (proposal_data_pda, _) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_data_info.key != proposal_data_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load proposal data
+    let proposal_data = deserialize_account::<OracleProposalData>(&proposal_data_info.data.borrow())?;
+    if proposal_data.discriminator != ORACLE_PROPOSAL_DATA_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Proposal must be Pending (not disputed) and past its challenge deadline
+    if let Err(e) = proposal.validate_finalize(current_time) {
+        match e {
+            PredictionMarketError::ProposalUnderDispute => {
+                msg!("❌ Cannot finalize: proposal is under dispute");
+            }
+            PredictionMarketError::ChallengeWindowNotExpired => {
+                msg!("❌ Challenge window has not expired yet: current={}, deadline={}", 
+                     current_time, proposal.challenge_deadline);
+            }
+            _ => {
+                msg!("❌ Proposal is not in Pending status, got {:?}", proposal.status);
+            }
+        }
+        return Err(e.into());
+    }
+    
+    // Return proposer's bond via Vault CPI
+    // Bond was locked when proposal was created, now we release it
+    let bond_amount = proposal.bond_amount;
+    
+    if bond_amount > 0 {
+        msg!("📤 Returning proposer bond: {} e6", bond_amount);
+        
+        let config_seeds = &[
+            PM_CONFIG_SEED,
+            &[config_bump],
+        ];
+        
+        // Use settlement with locked=bond, settlement=bond (full return)
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            proposer_pm_account_info,
+            config_info,
+            bond_amount,  // locked_amount = bond
+            bond_amount,  // settlement_amount = bond (full return, no loss)
+            config_seeds,
+        )?;
+    }
+    
+    // Update market to Resolved
+    market.status = MarketStatus::Resolved;
+    market.final_result = Some(proposal.proposed_result);
+    market.winning_outcome_index = Some(proposal_data.proposed_outcome_index);
+    market.updated_at = current_time;
+    
+    market.serialize(&mut *market_info.data.borrow_mut())?;
+    
+    // Update proposal status to Finalized
+    proposal.status = ProposalStatus::Finalized;
+    
+    proposal.serialize(&mut *proposal_info.data.borrow_mut())?;
+    
+    msg!("✅ FinalizeResultV2 completed");
+    msg!("Market {} resolved with result {:?}, outcome index {}", 
+         market.market_id, market.final_result, proposal_data.proposed_outcome_index);
+    msg!("Bond returned: {} e6", bond_amount);
+    
+    msg!("result_finalized:{},{}", market.market_id, proposal_data.proposed_outcome_index);
+    if market.market_type == MarketType::MultiOutcome {
+        msg!("multi_outcome_result_finalized:{},{}", market.market_id, proposal_data.proposed_outcome_index);
+    }
+    PredictionMarketEvent::MarketResolved(events::MarketResolved {
+        market_id: market.market_id,
+        final_result: market.final_result,
+        winning_outcome_index: market.winning_outcome_index,
+    }).emit()?;
+    
+    Ok(())
+}
+
+// ============================================================================
+// V15.2: RelayerChallengeResultV2 - Relayer-signed challenge for Public API
+// ============================================================================
+
+/// Process RelayerChallengeResultV2 instruction
+/// 
+/// Allows relayer to submit a challenge on behalf of a user.
+/// The challenger's bond is deducted from their Vault account via CPI.
+/// This enables Public API to submit challenges without requiring user signature.
+/// 
+/// Accounts:
+/// 0. `[signer]` Relayer
+/// 1. `[]` PredictionMarketConfig
+/// 2. `[writable]` Market
+/// 3. `[writable]` OracleProposal PDA
+/// 4. `[writable]` OracleProposalData PDA
+/// 5. `[writable]` Challenger's UserAccount (Vault)
+/// 6. `[writable]` Challenger's PMUserAccount (Vault) - for bond deduction
+/// 7. `[]` VaultConfig
+/// 8. `[]` Vault Program
+/// 9. `[]` System Program
+fn process_relayer_challenge_result_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: crate::instruction::RelayerChallengeResultV2Args,
+) -> ProgramResult {
+    use crate::state::{OracleProposal, OracleProposalData, ORACLE_PROPOSAL_DISCRIMINATOR, 
+                       ORACLE_PROPOSAL_SEED, ORACLE_PROPOSAL_DATA_DISCRIMINATOR,
+                       ORACLE_PROPOSAL_DATA_SEED, MarketStatus};
+    
+    msg!("RelayerChallengeResultV2: market={}, challenger={}, outcome={}", 
+         args.market_id, args.user_wallet, args.challenger_outcome_index);
+    
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Relayer (signer)
+    let relayer_info = next_account_info(account_info_iter)?;
+    check_signer(relayer_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
+    }
+    
+    // Verify relayer is authorized
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    let config_bump = config.bump;
+    
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    if market.market_id != args.market_id {
+        return Err(PredictionMarketError::MarketNotFound.into());
+    }
+    
+    // Market must be in ResultProposed state
+    if market.status != MarketStatus::ResultProposed {
+        msg!("Market must be in ResultProposed state to challenge, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    let market_id_bytes = args.market_id.to_le_bytes();
+    
+    // Account 3: OracleProposal PDA (writable)
+    let proposal_info = next_account_info(account_info_iter)?;
+    
+    // Validate OracleProposal PDA
+    let (proposal_pda, _proposal_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load and validate OracleProposal to check challenge window
+    let proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
+    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Verify within challenge window — use stored challenge_deadline (consistent with FinalizeResultV2)
+    if current_time > proposal.challenge_deadline {
+        msg!("Challenge window has expired: current={}, deadline={}", current_time, proposal.challenge_deadline);
+        return Err(PredictionMarketError::ChallengeWindowExpired.into());
+    }
+    
+    // Account 4: OracleProposalData PDA (writable)
+    let proposal_data_info = next_account_info(account_info_iter)?;
+    
+    // Validate OracleProposalData PDA
+    let (proposal_data_pda, _proposal_data_bump) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
+        program_id,
+    );
+    
+    if *proposal_data_info.key != proposal_data_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Load and update OracleProposalData with challenger's outcome
+    let mut proposal_data = deserialize_account::<OracleProposalData>(&proposal_data_info.data.borrow())?;
+    if proposal_data.discriminator != ORACLE_PROPOSAL_DATA_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Challenger's outcome must differ from proposed outcome
+    if args.challenger_outcome_index == proposal_data.proposed_outcome_index {
+        msg!("Challenger outcome must differ from proposed outcome");
+        return Err(PredictionMarketError::InvalidOutcome.into());
+    }
+    
+    // Account 5: Challenger's UserAccount (Vault) - for bond lock
+    let challenger_vault_info = next_account_info(account_info_iter)?;
+    
+    // Account 6: Challenger's PMUserAccount (Vault)
+    let challenger_pm_account_info = next_account_info(account_info_iter)?;
+    
+    // Account 7: VaultConfig
+    let vault_config_info = next_account_info(account_info_iter)?;
+    
+    // Account 8: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    
+    // Account 9: System Program (for auto-init)
+    let system_program_info = next_account_info(account_info_iter)?;
+    
+    // Lock challenger's bond via Vault CPI
+    let bond_amount = config.proposer_bond_e6;
+    
+    if bond_amount > 0 {
+        msg!("📥 Locking challenger bond: {} e6 for user {}", bond_amount, args.user_wallet);
+        
+        let config_seeds = &[
+            PM_CONFIG_SEED,
+            &[config_bump],
+        ];
+        
+        let locked = cpi_lock_for_prediction(
+            vault_program_info,
+            vault_config_info,
+            challenger_vault_info,
+            challenger_pm_account_info,
+            config_info,
+            relayer_info,
+            system_program_info,
+            bond_amount,
+            config_seeds,
+        )?;
+        
+        if locked != bond_amount {
+            msg!("Error: Challenger could only lock {} of the {} bond", locked, bond_amount);
+            return Err(PredictionMarketError::InsufficientFunds.into());
+        }
+    }
+    
+    // Record challenger's outcome and evidence hash
+    proposal_data.set_challenger(args.challenger_outcome_index, current_time);
+    msg!("Challenge evidence_hash: {:?}", &args.evidence_hash[0..8]);
+    
+    // Record challenger info on OracleProposal (for dispute resolution tracking)
+    let mut proposal = proposal; // make mutable
+    let challenger_pubkey = Pubkey::from(args.user_wallet);
+    let challenger_result = match args.challenger_outcome_index {
+        0 => MarketResult::Yes,
+        1 => MarketResult::No,
+        _ => MarketResult::Invalid,
+    };
+    proposal.challenger = Some(challenger_pubkey);
+    proposal.challenger_result = Some(challenger_result);
+    proposal.challenger_bond = bond_amount;
+    
+    // Extend challenge deadline on-chain
+    let challenge_duration = config.challenge_window_secs.max(3600) as i64;
+    let new_deadline = (current_time + challenge_duration).max(proposal.challenge_deadline);
+    proposal.challenge_deadline = new_deadline;
+    proposal.challenge_count = proposal.challenge_count.saturating_add(1);
+    
+    // Update market status to Challenged
+    market.status = MarketStatus::Challenged;
+    market.updated_at = current_time;
+    
+    // Serialize ALL updated accounts (proposal + proposal_data + market)
+    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ RelayerChallengeResultV2 completed");
+    msg!("Market {} challenged by {} (via relayer), outcome={}, bond={} e6, new_deadline={}", 
+         args.market_id, args.user_wallet, args.challenger_outcome_index, bond_amount, new_deadline);
+    
+    // Structured log for chain sync service to detect challenge events
+    msg!("result_challenged:{},{},{},{}", 
+         args.market_id, args.user_wallet, args.challenger_outcome_index, bond_amount);
     
     Ok(())
 }
 
-/// Task 4.6.1-4.6.3: Set creation data on market oracle data
-fn process_set_creation_data(
+// =============================================================================
+// Admin Operations
+// =============================================================================
+
+/// Transfer program admin to a new pubkey
+fn process_update_admin(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: SetCreationDataArgs,
+    args: UpdateAdminArgs,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Admin (signer)
+    // Account 0: Current Admin (signer)
     let admin_info = next_account_info(account_info_iter)?;
     check_signer(admin_info)?;
     
-    // Account 1: PredictionMarketConfig
+    // Account 1: PredictionMarketConfig (writable)
     let config_info = next_account_info(account_info_iter)?;
     
-    // Account 2: Market
-    let market_info = next_account_info(account_info_iter)?;
-    
-    // Account 3: MarketOracleData (writable)
-    let oracle_data_info = next_account_info(account_info_iter)?;
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
     // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Verify admin authority
-    if *admin_info.key != config.admin && *admin_info.key != config.oracle_admin {
+    if *admin_info.key != config.admin {
+        msg!("Error: Only current admin can update admin");
         return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Load and validate market - Task 4.6.3: only Pending status
-    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    if market.discriminator != MARKET_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
-    
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
-    }
-    
-    if market.status != MarketStatus::Pending {
-        msg!("Market status must be Pending, got {:?}", market.status);
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
-    }
-    
-    // Load and update oracle data
-    let mut oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
-    if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    if args.new_admin == config.admin {
+        msg!("Error: New admin {} is already the admin", args.new_admin);
+        return Err(PredictionMarketError::AdminUnchanged.into());
     }
     
-    if oracle_data.market_id != args.market_id {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    let old_admin = config.admin;
+    config.admin = args.new_admin;
+    // A nominee from an earlier handover must not be able to take over from
+    // the new admin
+    if let Some(pending_admin) = config.pending_admin_transfer() {
+        msg!("Dropping pending admin transfer to {}", pending_admin);
+        config.pending_admin = Pubkey::default();
     }
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    let current_time = get_current_timestamp()?;
-    oracle_data.set_creation_data(args.creation_data_cid, args.creation_data_hash, current_time);
-    oracle_data.serialize(&mut &mut oracle_data_info.data.borrow_mut()[..])?;
-    
-    msg!("✅ Set creation data for market {}", args.market_id);
+    msg!("✅ Admin updated: {} -> {}", old_admin, args.new_admin);
+    msg!("admin_updated:{},{}", old_admin, args.new_admin);
     
     Ok(())
 }
 
-/// Task 4.6.4-4.6.6: Freeze oracle config
-fn process_freeze_oracle_config(
+/// Rotate the oracle admin (also trusted as a relayer by verify_relayer)
+fn process_update_oracle_admin(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: FreezeOracleConfigArgs,
+    args: UpdateOracleAdminArgs,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
@@ -5597,179 +11714,238 @@ fn process_freeze_oracle_config(
     let admin_info = next_account_info(account_info_iter)?;
     check_signer(admin_info)?;
     
-    // Account 1: PredictionMarketConfig
+    // Account 1: PredictionMarketConfig (writable)
     let config_info = next_account_info(account_info_iter)?;
     
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
-    
-    // Account 3: MarketOracleData (writable)
-    let oracle_data_info = next_account_info(account_info_iter)?;
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Verify admin authority
-    if *admin_info.key != config.admin && *admin_info.key != config.oracle_admin {
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can update oracle admin");
         return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Load and update market - Task 4.6.6: transition Pending -> Active
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    if market.discriminator != MARKET_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    if args.new_oracle_admin == Pubkey::default() {
+        msg!("Error: Oracle admin cannot be the default pubkey");
+        return Err(PredictionMarketError::InvalidArgument.into());
     }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
-    }
+    let old_oracle_admin = config.oracle_admin;
+    config.oracle_admin = args.new_oracle_admin;
+    config.serialize(&mut *config_info.data.borrow_mut())?;
     
-    // Load and update oracle data
-    let mut oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
-    if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    msg!("✅ Oracle admin updated: {} -> {}", old_oracle_admin, args.new_oracle_admin);
+    msg!("oracle_admin_updated:{},{}", old_oracle_admin, args.new_oracle_admin);
+    
+    Ok(())
+}
+
+/// Set the global pause flag (kill-switch checked by all trading instructions)
+fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetPausedArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
+    
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    if oracle_data.market_id != args.market_id {
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Require creation data to be set first
-    if !oracle_data.is_creation_data_set {
-        msg!("Creation data must be set before freezing config");
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set paused state");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    let current_time = get_current_timestamp()?;
-    oracle_data.freeze_config(args.oracle_config_cid, args.oracle_config_hash, current_time);
-    oracle_data.serialize(&mut &mut oracle_data_info.data.borrow_mut()[..])?;
-    
-    // Transition market to Active if ready
-    if market.status == MarketStatus::Pending && oracle_data.is_ready_for_trading() {
-        market.status = MarketStatus::Active;
-        market.updated_at = current_time;
-        market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
-        msg!("Market {} activated (config frozen)", args.market_id);
+    if config.is_paused == args.paused {
+        msg!("SetPaused: no change (is_paused={})", config.is_paused);
+        return Ok(());
     }
     
-    msg!("✅ Frozen oracle config for market {}", args.market_id);
+    let was_paused = config.is_paused;
+    config.is_paused = args.paused;
+    config.serialize(&mut *config_info.data.borrow_mut())?;
+    
+    msg!("✅ Program paused state: {} -> {}", was_paused, args.paused);
+    msg!("paused_set:{}", args.paused);
     
     Ok(())
 }
 
-/// Task 4.6.7-4.6.8: Halt trading on market (end time reached)
-fn process_halt_trading(
+/// Update challenge window and/or proposer bond; `None` fields are left untouched
+fn process_update_oracle_config(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: HaltTradingArgs,
+    args: UpdateOracleConfigArgs,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Anyone (signer) - permissionless
-    let caller_info = next_account_info(account_info_iter)?;
-    check_signer(caller_info)?;
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Account 1: PredictionMarketConfig
+    // Account 1: PredictionMarketConfig (writable)
     let config_info = next_account_info(account_info_iter)?;
     
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Load and update market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    if market.discriminator != MARKET_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can update oracle config");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
+    // Validate everything before applying anything
+    if let Some(window) = args.challenge_window_secs {
+        if !(MIN_CHALLENGE_WINDOW_SECS..=MAX_CHALLENGE_WINDOW_SECS).contains(&window) {
+            msg!("Error: Challenge window {}s out of bounds [{}, {}]", 
+                 window, MIN_CHALLENGE_WINDOW_SECS, MAX_CHALLENGE_WINDOW_SECS);
+            return Err(PredictionMarketError::InvalidChallengeWindow.into());
+        }
     }
     
-    // Task 4.6.8: Time-based check - resolution time must have passed
-    let current_time = get_current_timestamp()?;
-    if current_time < market.resolution_time {
-        msg!("Resolution time not reached: current={}, resolution={}", 
-             current_time, market.resolution_time);
-        return Err(PredictionMarketError::ResolutionTimeNotReached.into());
+    if args.proposer_bond_e6 == Some(0) {
+        msg!("Error: Proposer bond must be non-zero");
+        return Err(PredictionMarketError::InvalidProposerBond.into());
     }
     
-    // Only Active markets can be halted
-    if market.status != MarketStatus::Active {
-        msg!("Market status must be Active, got {:?}", market.status);
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    if let Some(window) = args.challenge_window_secs {
+        msg!("Challenge window: {} -> {} seconds", config.challenge_window_secs, window);
+        config.challenge_window_secs = window;
     }
     
-    // Transition to TradingHalted
-    market.status = MarketStatus::TradingHalted;
-    market.updated_at = current_time;
-    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    if let Some(bond) = args.proposer_bond_e6 {
+        msg!("Proposer bond: {} -> {} (e6)", config.proposer_bond_e6, bond);
+        config.proposer_bond_e6 = bond;
+    }
     
-    msg!("✅ Halted trading for market {} (resolution time: {})", 
-         args.market_id, market.resolution_time);
-    msg!("market_status_changed:{},{},{}", args.market_id, "TradingHalted", current_time);
+    config.serialize(&mut *config_info.data.borrow_mut())?;
+    
+    msg!("✅ Oracle config updated");
+    msg!("oracle_config_updated:{},{}", config.challenge_window_secs, config.proposer_bond_e6);
     
     Ok(())
 }
 
-/// Task 4.6.9-4.6.12: Propose result with research data
-fn process_propose_result_with_research(
+/// Mark past-expiration GTD orders as Expired (permissionless)
+/// 
+/// Bookkeeping only: margin and locked shares stay in place until the
+/// order is cancelled through the usual cancel path or ExpireOrder.
+/// Orders that are not active or not yet expired are skipped.
+fn process_sweep_expired_orders(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: ProposeResultWithResearchArgs,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Oracle Admin (signer)
-    let oracle_admin_info = next_account_info(account_info_iter)?;
-    check_signer(oracle_admin_info)?;
+    // Account 0: Caller (signer)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
     
-    // Account 1: PredictionMarketConfig
-    let config_info = next_account_info(account_info_iter)?;
+    let current_time = get_current_timestamp()?;
+    let mut swept = 0u32;
     
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
+    // Accounts 1..N: Order PDAs (writable)
+    for order_info in account_info_iter {
+        if order_info.owner != program_id {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
+        if order.discriminator != ORDER_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        let (order_pda, _) = Pubkey::find_program_address(
+            &[ORDER_SEED, &order.market_id.to_le_bytes(), &order.order_id.to_le_bytes()],
+            program_id,
+        );
+        if *order_info.key != order_pda {
+            return Err(PredictionMarketError::InvalidPDA.into());
+        }
+        
+        if !order.is_active() || !order.is_expired(current_time) {
+            continue;
+        }
+        
+        order.status = OrderStatus::Expired;
+        order.updated_at = current_time;
+        order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
+        swept += 1;
+        
+        msg!("order_expired:{},{}", order.market_id, order.order_id);
+    }
     
-    // Account 3: OracleProposal PDA (writable)
-    let proposal_info = next_account_info(account_info_iter)?;
+    msg!("✅ Swept {} expired orders", swept);
+    msg!("orders_swept:{}", swept);
     
-    // Account 4: OracleProposalData PDA (writable)
-    let proposal_data_info = next_account_info(account_info_iter)?;
+    Ok(())
+}
+
+/// Expire a past-expiration GTD order and release its funds (permissionless)
+/// 
+/// Buy orders return the remaining margin to the owner's Vault account,
+/// Sell orders unlock the remaining shares in the owner's position. Works on
+/// active orders and on orders already marked Expired by SweepExpiredOrders.
+fn process_expire_order(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ExpireOrderArgs,
+) -> ProgramResult {
+    use crate::state::OrderSide;
     
-    // Account 5: MarketOracleData (for config hash verification)
-    let oracle_data_info = next_account_info(account_info_iter)?;
+    let account_info_iter = &mut accounts.iter();
     
-    // Account 6: Proposer Vault Account (for bond — future use)
-    let _proposer_vault_info = next_account_info(account_info_iter)?;
-    // Account 7: Vault Config (for bond — future use)
-    let _vault_config_info = next_account_info(account_info_iter)?;
-    // Account 8: Vault Program (for bond — future use)
-    let _vault_program_info = next_account_info(account_info_iter)?;
-    // Account 9: System Program (for create_account)
-    let system_program_info = next_account_info(account_info_iter)?;
+    // Account 0: Caller (signer)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
     
-    // Load and validate config
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Verify oracle admin authority
-    if *oracle_admin_info.key != config.oracle_admin {
-        msg!("Unauthorized: {} is not oracle_admin", oracle_admin_info.key);
-        return Err(PredictionMarketError::Unauthorized.into());
+    if config.is_paused {
+        return Err(PredictionMarketError::ProgramPaused.into());
     }
     
-    // Load and update market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Account 2: Market
+    let market_info = next_account_info(account_info_iter)?;
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
@@ -5778,222 +11954,187 @@ fn process_propose_result_with_research(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    // Task 4.6.10: Verify oracle config hash + get dynamic challenge duration
-    // MarketOracleData may not exist if the on-chain freeze sync failed during activation.
-    let dynamic_challenge_duration: Option<u32> = if oracle_data_info.data_len() > 0 {
-        let oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
-        if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
-            return Err(PredictionMarketError::InvalidAccountData.into());
-        }
-        
-        if !oracle_data.verify_config_hash(&args.oracle_config_hash) {
-            msg!("Oracle config hash mismatch");
-            return Err(PredictionMarketError::InvalidAccountData.into());
-        }
-        msg!("Oracle config hash verified, challenge_duration={}s", oracle_data.challenge_duration_secs);
-        Some(oracle_data.challenge_duration_secs)
-    } else {
-        msg!("⚠️ MarketOracleData not found — using market duration for challenge period");
-        None
-    };
+    // Account 3: Order PDA (writable)
+    let order_info = next_account_info(account_info_iter)?;
+    let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
     
-    // Market must be TradingHalted or AwaitingResult
-    if !matches!(market.status, MarketStatus::TradingHalted | MarketStatus::AwaitingResult) {
-        msg!("Market status must be TradingHalted or AwaitingResult, got {:?}", market.status);
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    if order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    let current_time = get_current_timestamp()?;
     let market_id_bytes = args.market_id.to_le_bytes();
-    let rent = Rent::get()?;
-    
-    // ── Create OracleProposal PDA ──
-    let (proposal_pda, proposal_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+    let (order_pda, _) = Pubkey::find_program_address(
+        &[ORDER_SEED, &market_id_bytes, &args.order_id.to_le_bytes()],
         program_id,
     );
-    
-    if *proposal_info.key != proposal_pda {
+    if *order_info.key != order_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Only create if account doesn't exist yet (allows re-propose after dispute reset)
-    if proposal_info.data_len() == 0 {
-        let proposal_space = OracleProposal::SIZE;
-        let proposal_lamports = rent.minimum_balance(proposal_space);
-        let proposal_seeds: &[&[u8]] = &[ORACLE_PROPOSAL_SEED, &market_id_bytes, &[proposal_bump]];
-        
-        invoke_signed(
-            &system_instruction::create_account(
-                oracle_admin_info.key,
-                proposal_info.key,
-                proposal_lamports,
-                proposal_space as u64,
-                program_id,
-            ),
-            &[oracle_admin_info.clone(), proposal_info.clone(), system_program_info.clone()],
-            &[proposal_seeds],
-        )?;
-        msg!("Created OracleProposal PDA: {}", proposal_pda);
+    match order.status {
+        OrderStatus::Filled => return Err(PredictionMarketError::OrderAlreadyFilled.into()),
+        OrderStatus::Cancelled => return Err(PredictionMarketError::OrderAlreadyCancelled.into()),
+        _ if !order.is_cancellable() => return Err(PredictionMarketError::OrderNotActive.into()),
+        _ => {}
     }
     
-    // Initialize OracleProposal with full fields
-    let proposed_result = match args.outcome_index {
-        0 => MarketResult::Yes,
-        1 => MarketResult::No,
-        _ => MarketResult::Invalid,
-    };
-    
-    // Challenge deadline: use per-market dynamic duration if available,
-    // otherwise calculate from market lifetime: min(max(market_duration, 300), 86400)
-    let challenge_duration_secs = dynamic_challenge_duration.unwrap_or_else(|| {
-        // Fallback: calculate from market creation time to resolution time
-        let market_duration = (market.resolution_time - market.created_at).max(300);
-        market_duration.min(86400) as u32
-    });
-    let challenge_deadline = current_time + (challenge_duration_secs as i64);
-    msg!("Challenge deadline: {} ({}s from now)", challenge_deadline, challenge_duration_secs);
+    let current_time = get_current_timestamp()?;
+    if !order.is_expired(current_time) {
+        msg!("Error: Order {} has not expired", args.order_id);
+        return Err(PredictionMarketError::OrderNotExpired.into());
+    }
     
-    let proposal = OracleProposal {
-        discriminator: ORACLE_PROPOSAL_DISCRIMINATOR,
-        market_id: args.market_id,
-        proposer: *oracle_admin_info.key,
-        proposed_result,
-        status: ProposalStatus::Pending,
-        proposed_at: current_time,
-        challenge_deadline,
-        bond_amount: config.proposer_bond_e6,
-        challenger: None,
-        challenger_result: None,
-        challenger_bond: 0,
-        bump: proposal_bump,
-        original_challenge_deadline: challenge_deadline,
-        challenge_count: 0,
-        reserved: [0u8; 23],
-    };
-    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
+    // Account 4: Position or MultiOutcomePosition PDA (writable, Sell orders)
+    let position_info = next_account_info(account_info_iter)?;
     
-    // ── Create OracleProposalData PDA ──
-    let (proposal_data_pda, proposal_data_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
-        program_id,
-    );
+    // Account 5: Owner's Vault UserAccount (writable, Buy orders)
+    let user_vault_info = next_account_info(account_info_iter)?;
     
-    if *proposal_data_info.key != proposal_data_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
-    }
+    // Account 6: Owner's PMUserAccount (writable, Buy orders)
+    let pm_user_info = next_account_info(account_info_iter)?;
     
-    // Only create if account doesn't exist yet
-    if proposal_data_info.data_len() == 0 {
-        let proposal_data_space = OracleProposalData::SIZE;
-        let proposal_data_lamports = rent.minimum_balance(proposal_data_space);
-        let proposal_data_seeds: &[&[u8]] = &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes, &[proposal_data_bump]];
-        
-        invoke_signed(
-            &system_instruction::create_account(
-                oracle_admin_info.key,
-                proposal_data_info.key,
-                proposal_data_lamports,
-                proposal_data_space as u64,
-                program_id,
-            ),
-            &[oracle_admin_info.clone(), proposal_data_info.clone(), system_program_info.clone()],
-            &[proposal_data_seeds],
-        )?;
-        msg!("Created OracleProposalData PDA: {}", proposal_data_pda);
-    }
+    // Account 7: VaultConfig
+    let vault_config_info = next_account_info(account_info_iter)?;
     
-    // Store research data in OracleProposalData
-    let proposal_data = OracleProposalData::new_llm(
-        args.market_id,
-        args.research_data_cid,
-        args.research_data_hash,
-        args.outcome_index,
-        args.confidence_score,
-        args.requires_manual_review,
-        proposal_data_bump,
-        current_time,
-    );
-    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
+    // Account 8: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
     
-    // Update market status
-    market.status = MarketStatus::ResultProposed;
-    market.updated_at = current_time;
-    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    let remaining = order.remaining_amount();
     
-    msg!("OracleProposal: proposer={}, result={:?}, challenge_deadline={}, bond={}",
-         oracle_admin_info.key, proposed_result, challenge_deadline, config.proposer_bond_e6);
+    if order.side == OrderSide::Buy {
+        // The caller is untrusted: the released margin must go to the owner
+        verify_vault_program(vault_program_info.key, &config.vault_program)?;
+        verify_pm_user_account(pm_user_info, &order.owner, &config.vault_program)?;
+        
+        let remaining_margin = order.remaining_locked_margin();
+        if remaining_margin > 0 {
+            msg!("CPI: Release remaining margin {} for expired Buy order", remaining_margin);
+            cpi_release_from_prediction(
+                vault_program_info,
+                vault_config_info,
+                user_vault_info,
+                pm_user_info,
+                config_info,
+                remaining_margin,
+                &[PM_CONFIG_SEED, &[config_bump]],
+            )?;
+        }
+    } else if remaining > 0 {
+        unlock_sell_order_shares(program_id, &market, &order, position_info, remaining, current_time)?;
+        
+        msg!("📊 Position unlocked: {} shares for expired Sell order", remaining);
+    }
     
-    // Structured log for chain sync parsing (must match LOG_PREFIX_RESULT_PROPOSED in sync.rs)
-    msg!("result_proposed:{},{},{},{}", 
-         args.market_id, oracle_admin_info.key, args.outcome_index, config.proposer_bond_e6);
+    order.status = OrderStatus::Expired;
+    order.locks_released = true;
+    order.locked_margin_e6 = 0;
+    order.updated_at = current_time;
+    order.serialize(&mut &mut order_info.data.borrow_mut()[..])?;
     
-    msg!("✅ Proposed result for market {}: outcome={}, confidence={}", 
-         args.market_id, args.outcome_index, args.confidence_score);
+    msg!("✅ Order {} expired, remaining {} released", args.order_id, remaining);
+    msg!("order_expired:{},{}", args.market_id, args.order_id);
     
     Ok(())
 }
 
-/// Process manual result proposal (Admin override for UNDETERMINED cases)
-/// 
-/// Task 4.6.13-4.6.16: Manual proposal with evidence
+/// Close a finished order and return its rent-exempt lamports to the owner
 /// 
-/// Accounts:
-/// 0. `[signer]` Oracle Admin
-/// 1. `[]` PredictionMarketConfig
-/// 2. `[writable]` Market
-/// 3. `[writable]` OracleProposal PDA
-/// 4. `[writable]` OracleProposalData PDA
-/// 5. `[]` MarketOracleData (for original research reference)
-/// 6. `[writable]` Admin's Vault Account (for bond)
-/// 7. `[]` Vault Config
-/// 8. `[]` Vault Program
-/// 9. `[]` System Program
-fn process_propose_result_manual(
+/// The account data is zeroed and the runtime reclaims the emptied PDA at the
+/// end of the transaction.
+fn process_close_order(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: ProposeResultManualArgs,
+    args: CloseOrderArgs,
 ) -> ProgramResult {
-    msg!("ProposeResultManual: market={}, outcome={}", args.market_id, args.outcome_index);
-    
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Oracle Admin (signer)
-    let oracle_admin_info = next_account_info(account_info_iter)?;
-    check_signer(oracle_admin_info)?;
+    // Account 0: Owner or relayer (signer)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Account 3: OracleProposal PDA (writable)
-    let proposal_info = next_account_info(account_info_iter)?;
+    // Account 2: Order PDA (writable)
+    let order_info = next_account_info(account_info_iter)?;
+    let order = deserialize_account::<Order>(&order_info.data.borrow())?;
     
-    // Account 4: OracleProposalData PDA (writable)
-    let proposal_data_info = next_account_info(account_info_iter)?;
+    if order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Account 5: MarketOracleData (for original research reference)
-    let oracle_data_info = next_account_info(account_info_iter)?;
+    let (order_pda, _) = Pubkey::find_program_address(
+        &[ORDER_SEED, &args.market_id.to_le_bytes(), &args.order_id.to_le_bytes()],
+        program_id,
+    );
+    if *order_info.key != order_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    // Account 3: Order owner (writable, receives the rent)
+    let owner_info = next_account_info(account_info_iter)?;
+    if *owner_info.key != order.owner {
+        return Err(PredictionMarketError::OrderOwnerMismatch.into());
+    }
+    
+    if *caller_info.key != order.owner {
+        verify_relayer(program_id, &config, caller_info.key, accounts)?;
+    }
+    
+    if !order.is_closable() {
+        msg!("Error: Order {} is {:?} and cannot be closed", args.order_id, order.status);
+        return Err(PredictionMarketError::OrderNotClosable.into());
+    }
+    
+    let rent_lamports = order_info.lamports();
+    **owner_info.lamports.borrow_mut() = owner_info
+        .lamports()
+        .checked_add(rent_lamports)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    **order_info.lamports.borrow_mut() = 0;
+    order_info.data.borrow_mut().fill(0);
+    
+    msg!("✅ Order {} closed, {} lamports returned to {}", args.order_id, rent_lamports, order.owner);
+    msg!("order_closed:{},{}", args.market_id, args.order_id);
     
-    // Account 6+: Vault accounts for bond (optional, skipped for now)
-    let _remaining_accounts = account_info_iter;
+    Ok(())
+}
+
+/// Close an empty Position and return its rent-exempt lamports to the owner
+/// 
+/// A later trade re-creates the PDA through the usual auto-init path.
+fn process_close_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ClosePositionArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    // Load and validate config
+    // Account 0: Owner or relayer (signer)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: PredictionMarketConfig
+    let config_info = next_account_info(account_info_iter)?;
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Verify oracle admin authority
-    if *oracle_admin_info.key != config.oracle_admin {
-        msg!("Unauthorized: {} is not oracle_admin", oracle_admin_info.key);
-        return Err(PredictionMarketError::Unauthorized.into());
+    if *caller_info.key != args.user_wallet {
+        verify_relayer(program_id, &config, caller_info.key, accounts)?;
     }
     
-    // Load and validate market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    // Account 2: Market
+    let market_info = next_account_info(account_info_iter)?;
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
@@ -6002,675 +12143,453 @@ fn process_propose_result_manual(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    // Load MarketOracleData to get original research reference
-    let oracle_data = deserialize_account::<MarketOracleData>(&oracle_data_info.data.borrow())?;
-    if oracle_data.discriminator != MARKET_ORACLE_DATA_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
-    
-    // Market must be TradingHalted, AwaitingResult, or ResultProposed (to override UNDETERMINED)
-    if !matches!(
-        market.status, 
-        MarketStatus::TradingHalted | MarketStatus::AwaitingResult | MarketStatus::ResultProposed
-    ) {
-        msg!("Market status must be TradingHalted, AwaitingResult, or ResultProposed for manual override, got {:?}", 
-             market.status);
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
-    }
-    
-    let current_time = get_current_timestamp()?;
-    let market_id_bytes = args.market_id.to_le_bytes();
-    
-    // Validate OracleProposal PDA
-    let (proposal_pda, _proposal_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &args.market_id.to_le_bytes()],
         program_id,
     );
-    
-    if *proposal_info.key != proposal_pda {
+    if *market_info.key != market_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Validate OracleProposalData PDA
-    let (proposal_data_pda, proposal_data_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
+    // Account 3: Position PDA (writable)
+    let position_info = next_account_info(account_info_iter)?;
+    let position = deserialize_account::<Position>(&position_info.data.borrow())?;
+    
+    if position.discriminator != POSITION_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, &args.market_id.to_le_bytes(), args.user_wallet.as_ref()],
         program_id,
     );
-    
-    if *proposal_data_info.key != proposal_data_pda {
+    if *position_info.key != position_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Task 4.6.14-4.6.15: Create manual proposal data with evidence
-    // Use research_data from original LLM attempt (if any)
-    let research_cid = oracle_data.oracle_config_cid; // Reference to original config/research
-    let research_hash = oracle_data.oracle_config_hash;
-    
-    let proposal_data = OracleProposalData::new_manual(
-        args.market_id,
-        research_cid,                    // Original research reference
-        research_hash,                   // Original research hash
-        args.manual_proposal_cid,        // Manual judgment IPFS CID
-        args.manual_reasoning_hash,      // Manual reasoning hash
-        args.outcome_index,              // Admin's determined outcome
-        proposal_data_bump,
-        current_time,
-    );
+    // Account 4: Position owner (writable, receives the rent)
+    let owner_info = next_account_info(account_info_iter)?;
+    if *owner_info.key != position.owner || position.owner != args.user_wallet {
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    // Serialize proposal data to account
-    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
+    if !position.is_empty() || position.yes_locked != 0 || position.no_locked != 0 {
+        msg!("Error: Position still holds {} YES / {} NO shares", position.yes_amount, position.no_amount);
+        return Err(PredictionMarketError::PositionNotEmpty.into());
+    }
     
-    // Update market status to ResultProposed
-    market.status = MarketStatus::ResultProposed;
-    market.updated_at = current_time;
-    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    if !position.settled {
+        if !market.status.is_terminal() {
+            msg!("Error: Unsettled position in a {:?} market", market.status);
+            return Err(PredictionMarketError::InvalidMarketStatus.into());
+        }
+        
+        // Anything a claim would still release or pay out must go through the claim
+        let (locked, settlement_amount) = claim_settlement_amounts(&market, &position)?;
+        if locked != 0 || settlement_amount != 0 {
+            msg!("Error: Position still has {} locked / {} claimable, claim it first", locked, settlement_amount);
+            return Err(PredictionMarketError::PositionNotEmpty.into());
+        }
+    }
     
-    // Structured log for chain sync parsing (must match LOG_PREFIX_RESULT_PROPOSED in sync.rs)
-    msg!("result_proposed:{},{},{},{}", 
-         args.market_id, oracle_admin_info.key, args.outcome_index, 0);
+    let rent_lamports = position_info.lamports();
+    **owner_info.lamports.borrow_mut() = owner_info
+        .lamports()
+        .checked_add(rent_lamports)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    **position_info.lamports.borrow_mut() = 0;
+    position_info.data.borrow_mut().fill(0);
     
-    msg!("✅ Manual proposal for market {}: outcome={}, manual_cid={:?}", 
-         args.market_id, 
-         args.outcome_index,
-         String::from_utf8_lossy(&args.manual_proposal_cid[0..20]));
+    msg!("✅ Position closed, {} lamports returned to {}", rent_lamports, position.owner);
+    msg!("position_closed:{},{}", args.market_id, position.owner);
     
     Ok(())
 }
 
-/// Process challenge with evidence (Task 4.6.17-4.6.20)
-/// 
-/// Allows any user to challenge a proposed result by posting a counter-bond
-/// and providing evidence (IPFS CID + hash) supporting their alternative outcome.
-/// 
-/// Accounts:
-/// 0. `[signer]` Challenger
-/// 1. `[]` PredictionMarketConfig
-/// 2. `[writable]` Market
-/// 3. `[writable]` OracleProposal PDA
-/// 4. `[writable]` OracleProposalData PDA (to record challenger's outcome)
-/// 5. `[writable]` Challenger's Vault Account (for bond)
-/// 6. `[writable]` Market Vault (to receive bond)
-/// 7. `[]` Vault Config
-/// 8. `[]` Vault Program
-fn process_challenge_result_with_evidence(
+/// Set the tolerance applied to MatchMint/MatchBurn price sums
+fn process_set_max_price_sum_deviation(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: ChallengeResultWithEvidenceArgs,
+    args: SetMaxPriceSumDeviationArgs,
 ) -> ProgramResult {
-    msg!("ChallengeResultWithEvidence: market={}, challenger_outcome={}", 
-         args.market_id, args.challenger_outcome_index);
+    use crate::state::MAX_PRICE_SUM_DEVIATION;
     
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Challenger (signer)
-    let challenger_info = next_account_info(account_info_iter)?;
-    check_signer(challenger_info)?;
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Account 1: PredictionMarketConfig
+    // Account 1: PredictionMarketConfig (writable)
     let config_info = next_account_info(account_info_iter)?;
     
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
-    
-    // Account 3: OracleProposal PDA (writable)
-    let proposal_info = next_account_info(account_info_iter)?;
-    
-    // Account 4: OracleProposalData PDA (writable)
-    let proposal_data_info = next_account_info(account_info_iter)?;
-    
-    // Account 5+: Vault accounts for bond transfer (handled separately)
-    let _remaining_accounts = account_info_iter;
-    
-    // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
-    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load and validate market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    if market.discriminator != MARKET_DISCRIMINATOR {
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set max price sum deviation");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Market must be in ResultProposed state
-    if market.status != MarketStatus::ResultProposed {
-        msg!("Market must be in ResultProposed state to challenge, got {:?}", market.status);
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    if args.max_deviation > MAX_PRICE_SUM_DEVIATION {
+        msg!("Error: Deviation {} exceeds maximum {}", args.max_deviation, MAX_PRICE_SUM_DEVIATION);
+        return Err(PredictionMarketError::PriceSumDeviationTooHigh.into());
     }
     
-    let current_time = get_current_timestamp()?;
-    let market_id_bytes = args.market_id.to_le_bytes();
+    let old_deviation = config.max_price_sum_deviation;
+    config.max_price_sum_deviation = args.max_deviation;
+    config.serialize(&mut *config_info.data.borrow_mut())?;
     
-    // Validate OracleProposal PDA
-    let (proposal_pda, _proposal_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
-        program_id,
-    );
+    msg!("✅ Max price sum deviation: {} -> {} (e6)", old_deviation, args.max_deviation);
+    msg!("max_price_sum_deviation_set:{}", args.max_deviation);
     
-    if *proposal_info.key != proposal_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
-    }
+    Ok(())
+}
+
+/// Set the per-side trading fee charged by ExecuteTradeV2
+fn process_set_trade_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetTradeFeeArgs,
+) -> ProgramResult {
+    use crate::state::MAX_TRADE_FEE_BPS;
     
-    // Load and validate OracleProposal to check challenge window
-    let proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
-    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
+    let account_info_iter = &mut accounts.iter();
     
-    // Verify within challenge window — use stored challenge_deadline (consistent with FinalizeResultV2)
-    if current_time > proposal.challenge_deadline {
-        msg!("Challenge window has expired: current={}, deadline={}", current_time, proposal.challenge_deadline);
-        return Err(PredictionMarketError::ChallengeWindowExpired.into());
-    }
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Validate OracleProposalData PDA
-    let (proposal_data_pda, _proposal_data_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
-        program_id,
-    );
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
     
-    if *proposal_data_info.key != proposal_data_pda {
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load and update OracleProposalData with challenger's outcome
-    let mut proposal_data = deserialize_account::<OracleProposalData>(&proposal_data_info.data.borrow())?;
-    if proposal_data.discriminator != ORACLE_PROPOSAL_DATA_DISCRIMINATOR {
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Challenger's outcome must differ from proposed outcome
-    if args.challenger_outcome_index == proposal_data.proposed_outcome_index {
-        msg!("Challenger outcome must differ from proposed outcome");
-        return Err(PredictionMarketError::InvalidOutcome.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the trade fee");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Record challenger's outcome and evidence hash
-    proposal_data.set_challenger(args.challenger_outcome_index, current_time);
-    
-    // Record challenger info on the OracleProposal itself (for dispute resolution)
-    let mut proposal = proposal; // make mutable
-    let challenger_result = match args.challenger_outcome_index {
-        0 => MarketResult::Yes,
-        1 => MarketResult::No,
-        _ => MarketResult::Invalid,
-    };
-    proposal.challenger = Some(*challenger_info.key);
-    proposal.challenger_result = Some(challenger_result);
-    // Note: Bond amount not set here — CPI to Vault not available in this instruction variant.
-    // Use RelayerChallengeResultV2 (Index 72) for proper bond locking.
-    
-    // Extend challenge deadline on-chain (consistent with DB-side extension)
-    let challenge_duration = config.challenge_window_secs.max(3600) as i64;
-    let new_deadline = (current_time + challenge_duration).max(proposal.challenge_deadline);
-    proposal.challenge_deadline = new_deadline;
-    proposal.challenge_count = proposal.challenge_count.saturating_add(1);
-    
-    // Update market status to Challenged
-    market.status = MarketStatus::Challenged;
-    market.updated_at = current_time;
+    if args.trade_fee_bps > MAX_TRADE_FEE_BPS {
+        msg!("Error: Trade fee {} bps exceeds maximum {}", args.trade_fee_bps, MAX_TRADE_FEE_BPS);
+        return Err(PredictionMarketError::TradeFeeTooHigh.into());
+    }
     
-    // Serialize ALL updated accounts (proposal + proposal_data + market)
-    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
-    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
-    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    if args.trade_fee_bps < config.maker_rebate_bps {
+        msg!("Error: Trade fee {} bps below maker rebate {} bps", args.trade_fee_bps, config.maker_rebate_bps);
+        return Err(PredictionMarketError::MakerRebateTooHigh.into());
+    }
     
-    msg!("Challenge recorded: challenger={}, outcome={}, new_deadline={}", 
-         challenger_info.key, args.challenger_outcome_index, new_deadline);
+    let old_fee = config.trade_fee_bps;
+    config.trade_fee_bps = args.trade_fee_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    msg!("✅ Challenge submitted for market {}: challenger={}, outcome={}, evidence_hash={:?}", 
-         args.market_id,
-         challenger_info.key,
-         args.challenger_outcome_index,
-         &args.evidence_hash[0..8]);
+    msg!("✅ Trade fee: {} -> {} bps", old_fee, args.trade_fee_bps);
+    msg!("trade_fee_set:{}", args.trade_fee_bps);
     
     Ok(())
 }
 
-// ============================================================================
-// V2 Multi-Outcome Order Instructions (Pure Vault Mode)
-// ============================================================================
-
-/// V2: Place order for multi-outcome market with Vault CPI
-/// Similar to RelayerPlaceOrderV2 but uses outcome_index instead of Outcome enum
-fn process_relayer_place_multi_outcome_order_v2(
+/// Set the fee charged on top of MintCompleteSet collateral
+fn process_set_mint_fee(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: RelayerPlaceMultiOutcomeOrderV2Args,
+    args: SetMintFeeArgs,
 ) -> ProgramResult {
-    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, MAX_OUTCOMES};
+    use crate::state::MAX_MINT_FEE_BPS;
     
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Relayer (signer)
-    let relayer_info = next_account_info(account_info_iter)?;
-    check_signer(relayer_info)?;
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Account 1: PredictionMarketConfig
+    // Account 1: PredictionMarketConfig (writable)
     let config_info = next_account_info(account_info_iter)?;
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if config.is_paused {
-        return Err(PredictionMarketError::ProgramPaused.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the mint fee");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
-    
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    
-    if market.discriminator != MARKET_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
+    if args.mint_fee_bps > MAX_MINT_FEE_BPS {
+        msg!("Error: Mint fee {} bps exceeds maximum {}", args.mint_fee_bps, MAX_MINT_FEE_BPS);
+        return Err(PredictionMarketError::MintFeeTooHigh.into());
     }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
-    }
+    let old_fee = config.mint_fee_bps;
+    config.mint_fee_bps = args.mint_fee_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    // Verify this is a multi-outcome market
-    if market.market_type != MarketType::MultiOutcome {
-        msg!("Error: RelayerPlaceMultiOutcomeOrderV2 requires MultiOutcome market type");
-        return Err(PredictionMarketError::InvalidMarketType.into());
-    }
+    msg!("✅ Mint fee: {} -> {} bps", old_fee, args.mint_fee_bps);
+    msg!("mint_fee_set:{}", args.mint_fee_bps);
     
-    // Validate outcome_index
-    if args.outcome_index >= market.num_outcomes {
-        msg!("Error: outcome_index {} >= num_outcomes {}", args.outcome_index, market.num_outcomes);
-        return Err(PredictionMarketError::InvalidOutcome.into());
-    }
+    Ok(())
+}
+
+/// Set the bond locked from market creators
+fn process_set_market_creation_bond(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetMarketCreationBondArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    // Account 0: Admin (signer, pays for growing the config)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Account 3: Order PDA (writable, new)
-    let order_info = next_account_info(account_info_iter)?;
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
     
-    // Account 4: MultiOutcomePosition PDA
-    let position_info = next_account_info(account_info_iter)?;
+    // Account 2: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
     
-    // Account 5: User Vault Account
-    let user_vault_info = next_account_info(account_info_iter)?;
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    // Account 6: PM User Account
-    let pm_user_info = next_account_info(account_info_iter)?;
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Account 7: Vault Config
-    let vault_config_info = next_account_info(account_info_iter)?;
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the market creation bond");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    // Account 8: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
+    grow_config_for_extension(admin_info, config_info, system_program_info)?;
     
-    // Account 9: System Program
-    let system_program_info = next_account_info(account_info_iter)?;
+    let mut extension = ConfigExtension::load(&config_info.data.borrow());
+    let old_bond = extension.market_creation_bond_e6;
+    extension.market_creation_bond_e6 = args.market_creation_bond_e6;
+    extension.store(&mut config_info.data.borrow_mut())?;
     
-    // Derive and verify Order PDA
-    let order_id = market.next_order_id;
-    let market_id_bytes = args.market_id.to_le_bytes();
-    let order_id_bytes = order_id.to_le_bytes();
-    let (order_pda, order_bump) = Pubkey::find_program_address(
-        &[ORDER_SEED, &market_id_bytes, &order_id_bytes],
-        program_id,
-    );
+    msg!("✅ Market creation bond: {} -> {}", old_bond, args.market_creation_bond_e6);
+    msg!("market_creation_bond_set:{}", args.market_creation_bond_e6);
     
-    if *order_info.key != order_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
-    }
+    Ok(())
+}
+
+/// Replace every fee at once
+fn process_update_fee_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateFeeScheduleArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    // Calculate margin requirement (in e6 precision)
-    // margin_e6 = amount_e6 × price_e6 / PRICE_PRECISION
-    // All amounts are in e6 precision (1 share = 1_000_000 units).
-    let margin = (args.amount as u128)
-        .checked_mul(args.price as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
+    // Account 0: Admin (signer, pays for growing the config)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    let current_time = get_current_timestamp()?;
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
     
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(
-        &[PM_CONFIG_SEED],
-        program_id,
-    );
+    // Account 2: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
     
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
     if *config_info.key != config_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
-    
-    // For Buy orders: Lock margin in Vault
-    if args.side == crate::state::OrderSide::Buy {
-        msg!("CPI: Lock margin {} for Buy order", margin);
-        cpi_lock_for_prediction(
-            vault_program_info,
-            vault_config_info,
-            user_vault_info,
-            pm_user_info,
-            config_info,
-            relayer_info,
-            system_program_info,
-            margin,
-            config_seeds,
-        )?;
-    } else {
-        // For Sell orders: Verify MultiOutcomePosition has sufficient AVAILABLE holdings and LOCK them
-        let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
-        if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
-            return Err(PredictionMarketError::InvalidAccountData.into());
-        }
-        
-        let idx = args.outcome_index as usize;
-        if idx >= MAX_OUTCOMES {
-            return Err(PredictionMarketError::InvalidOutcome.into());
-        }
-        
-        // Check available (total - locked)
-        let total = position.holdings[idx];
-        let locked = position.locked[idx];
-        let available = total.saturating_sub(locked);
-        
-        if available < args.amount {
-            msg!("Error: Insufficient available holdings: {} < {} (total: {}, locked: {})", 
-                 available, args.amount, total, locked);
-            return Err(PredictionMarketError::InsufficientPosition.into());
-        }
-        
-        // Lock shares for this Sell order
-        position.locked[idx] = position.locked[idx].saturating_add(args.amount);
-        position.updated_at = current_time;
-        position.serialize(&mut *position_info.data.borrow_mut())?;
-        
-        msg!("📊 MultiOutcome Position locked: {} shares for outcome {}", args.amount, args.outcome_index);
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Create Order
-    let order_space = Order::SIZE;
-    let rent = Rent::get()?;
-    let lamports = rent.minimum_balance(order_space);
-    
-    // Create account via CPI
-    let order_seeds: &[&[u8]] = &[ORDER_SEED, &market_id_bytes, &order_id_bytes, &[order_bump]];
-    
-    invoke_signed(
-        &system_instruction::create_account(
-            relayer_info.key,
-            order_info.key,
-            lamports,
-            order_space as u64,
-            program_id,
-        ),
-        &[relayer_info.clone(), order_info.clone(), system_program_info.clone()],
-        &[order_seeds],
-    )?;
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can update the fee schedule");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    // Initialize Order - use outcome_index for multi-outcome
-    // Note: We use Outcome::Yes as placeholder since Order struct uses Outcome enum
-    // The actual outcome is stored in outcome_index field
-    let order = Order {
-        discriminator: ORDER_DISCRIMINATOR,
-        order_id,
-        market_id: args.market_id,
-        owner: args.user_wallet,
-        side: args.side,
-        outcome: Outcome::Yes, // Placeholder for multi-outcome
-        outcome_index: args.outcome_index,
-        price: args.price,
-        amount: args.amount,
-        filled_amount: 0,
-        status: OrderStatus::Open,
-        order_type: args.order_type,
-        expiration_time: args.expiration_time,
-        created_at: current_time,
-        updated_at: current_time,
-        bump: order_bump,
-        escrow_token_account: None, // V2: No SPL token escrow
-        reserved: [0u8; 30],
-    };
-    order.serialize(&mut *order_info.data.borrow_mut())?;
+    let schedule = args.fee_schedule;
+    if let Err(e) = schedule.validate() {
+        msg!("Error: Invalid fee schedule {:?}", schedule);
+        return Err(e.into());
+    }
     
-    // Update market
-    market.next_order_id = market.next_order_id.saturating_add(1);
-    market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    grow_config_for_extension(admin_info, config_info, system_program_info)?;
     
-    msg!("✅ RelayerPlaceMultiOutcomeOrderV2 completed");
-    msg!("User: {}", args.user_wallet);
-    msg!("Order ID: {}, Market: {}", order_id, args.market_id);
-    msg!("Side: {:?}, Outcome Index: {}", args.side, args.outcome_index);
-    msg!("Price: {}, Amount: {}, Margin: {}", args.price, args.amount, margin);
+    let mut extension = ConfigExtension::load(&config_info.data.borrow());
+    let old_schedule = config.fee_schedule(&extension);
+    config.set_fee_schedule(&mut extension, &schedule);
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    extension.store(&mut config_info.data.borrow_mut())?;
     
-    msg!("multi_outcome_order_placed:{},{},{},{},{:?},{},{}", args.market_id, order_id, args.user_wallet, args.outcome_index, args.side, args.price, args.amount);
+    msg!("✅ Fee schedule: {:?} -> {:?}", old_schedule, schedule);
+    msg!(
+        "fee_schedule_set:{},{},{},{}",
+        schedule.mint_bps, schedule.redeem_bps, schedule.trade_bps, schedule.maker_rebate_bps
+    );
     
     Ok(())
 }
 
-/// V2: Cancel order for multi-outcome market with Vault CPI
-fn process_relayer_cancel_multi_outcome_order_v2(
+/// Set the volume tiers discounting the ExecuteTradeV2 taker fee
+fn process_set_fee_tiers(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: RelayerCancelMultiOutcomeOrderV2Args,
+    args: SetFeeTiersArgs,
 ) -> ProgramResult {
-    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, MAX_OUTCOMES};
+    use crate::state::MAX_FEE_DISCOUNT_BPS;
     
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Relayer (signer)
-    let relayer_info = next_account_info(account_info_iter)?;
-    check_signer(relayer_info)?;
+    // Account 0: Admin (signer, pays for growing the config)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Account 1: PredictionMarketConfig
+    // Account 1: PredictionMarketConfig (writable)
     let config_info = next_account_info(account_info_iter)?;
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
-    
-    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
-    
-    verify_relayer(&config, relayer_info.key)?;
-    
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    
-    if market.discriminator != MARKET_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
-    }
+    // Account 2: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
     
-    // Verify this is a multi-outcome market
-    if market.market_type != MarketType::MultiOutcome {
-        msg!("Error: RelayerCancelMultiOutcomeOrderV2 requires MultiOutcome market type");
-        return Err(PredictionMarketError::InvalidMarketType.into());
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Account 3: Order PDA (writable)
-    let order_info = next_account_info(account_info_iter)?;
-    let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
-    
-    if order.discriminator != ORDER_DISCRIMINATOR {
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if order.order_id != args.order_id || order.market_id != args.market_id {
-        return Err(PredictionMarketError::OrderNotFound.into());
-    }
-    
-    if order.owner != args.user_wallet {
-        return Err(PredictionMarketError::OrderOwnerMismatch.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the fee tiers");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    if order.status != OrderStatus::Open && order.status != OrderStatus::PartialFilled {
-        return Err(PredictionMarketError::OrderNotActive.into());
+    if let Some(tier) = args.fee_tiers.iter().find(|tier| tier.discount_bps > MAX_FEE_DISCOUNT_BPS) {
+        msg!("Error: Fee tier discount {} bps exceeds maximum {}", tier.discount_bps, MAX_FEE_DISCOUNT_BPS);
+        return Err(PredictionMarketError::FeeDiscountTooHigh.into());
     }
     
-    // Account 4: MultiOutcomePosition PDA
-    let position_info = next_account_info(account_info_iter)?;
-    
-    // Account 5: User Vault Account
-    let user_vault_info = next_account_info(account_info_iter)?;
+    grow_config_for_extension(admin_info, config_info, system_program_info)?;
     
-    // Account 6: PM User Account
-    let pm_user_info = next_account_info(account_info_iter)?;
+    let mut extension = ConfigExtension::load(&config_info.data.borrow());
+    extension.fee_tiers = args.fee_tiers;
+    extension.store(&mut config_info.data.borrow_mut())?;
     
-    // Account 7: Vault Config
-    let vault_config_info = next_account_info(account_info_iter)?;
+    for tier in args.fee_tiers.iter() {
+        msg!("✅ Fee tier: volume >= {} → {} bps off the taker fee", tier.min_volume_e6, tier.discount_bps);
+        msg!("fee_tier_set:{},{}", tier.min_volume_e6, tier.discount_bps);
+    }
     
-    // Account 8: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
+    Ok(())
+}
+
+/// Set the share of referred traders' fees paid to their referrers
+fn process_set_referral_share(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetReferralShareArgs,
+) -> ProgramResult {
+    use crate::state::MAX_REFERRAL_SHARE_BPS;
     
-    // Account 9: System Program
-    let _system_program_info = next_account_info(account_info_iter)?;
+    let account_info_iter = &mut accounts.iter();
     
-    // Calculate remaining amount and margin (e6 precision)
-    // remaining_margin_e6 = remaining_e6 × price_e6 / PRICE_PRECISION
-    let remaining = order.amount.saturating_sub(order.filled_amount);
-    let remaining_margin = (remaining as u128)
-        .checked_mul(order.price as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(PredictionMarketError::ArithmeticOverflow)? as u64;
+    // Account 0: Admin (signer, pays for growing the config)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    let current_time = get_current_timestamp()?;
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
     
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(
-        &[PM_CONFIG_SEED],
-        program_id,
-    );
+    // Account 2: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
     
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
     if *config_info.key != config_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // For Buy orders: Unlock margin from Vault
-    if order.side == crate::state::OrderSide::Buy {
-        msg!("CPI: Release margin {} for cancelled Buy order", remaining_margin);
-        cpi_release_from_prediction(
-            vault_program_info,
-            vault_config_info,
-            user_vault_info,
-            pm_user_info,
-            config_info,
-            remaining_margin,
-            config_seeds,
-        )?;
-    } else {
-        // For Sell orders: Unlock shares from MultiOutcomePosition
-        let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
-        if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
-            return Err(PredictionMarketError::InvalidAccountData.into());
-        }
-        
-        let idx = args.outcome_index as usize;
-        if idx >= MAX_OUTCOMES {
-            return Err(PredictionMarketError::InvalidOutcome.into());
-        }
-        
-        // Unlock shares
-        position.locked[idx] = position.locked[idx].saturating_sub(remaining);
-        position.updated_at = current_time;
-        position.serialize(&mut *position_info.data.borrow_mut())?;
-        
-        msg!("📊 MultiOutcome Position unlocked: {} shares for outcome {}", remaining, args.outcome_index);
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the referral share");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Update order status
-    order.status = OrderStatus::Cancelled;
-    order.updated_at = current_time;
-    order.serialize(&mut *order_info.data.borrow_mut())?;
+    if args.referral_share_bps > MAX_REFERRAL_SHARE_BPS {
+        msg!("Error: Referral share {} bps exceeds maximum {}", args.referral_share_bps, MAX_REFERRAL_SHARE_BPS);
+        return Err(PredictionMarketError::ReferralShareTooHigh.into());
+    }
     
-    // Update market
-    market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    grow_config_for_extension(admin_info, config_info, system_program_info)?;
     
-    msg!("✅ RelayerCancelMultiOutcomeOrderV2 completed");
-    msg!("User: {}", args.user_wallet);
-    msg!("Order ID: {}, Market: {}", args.order_id, args.market_id);
-    msg!("Remaining amount: {}, Unlocked margin/shares: {}", remaining, remaining_margin);
+    let mut extension = ConfigExtension::load(&config_info.data.borrow());
+    let old_share = extension.referral_share_bps;
+    extension.referral_share_bps = args.referral_share_bps;
+    extension.store(&mut config_info.data.borrow_mut())?;
     
-    msg!("multi_outcome_order_cancelled:{},{}", args.market_id, args.order_id);
+    msg!("✅ Referral share: {} -> {} bps", old_share, args.referral_share_bps);
+    msg!("referral_share_set:{}", args.referral_share_bps);
     
     Ok(())
 }
 
-// ============================================================================
-// Multi-Outcome V2 Instructions (Vault CPI Mode)
-// ============================================================================
-
-/// V2: RelayerMintMultiOutcomeCompleteSet using Vault CPI (no SPL Token)
-/// 
-/// Mints a complete set of all outcome tokens for a multi-outcome market.
-/// 1 complete set = 1 token of each outcome
-/// Cost = amount * 1.0 USDC (locked in Vault)
-fn process_relayer_mint_multi_outcome_complete_set_v2(
+/// Refresh the best bid/ask snapshot of a market outcome from the best
+/// orders the relayer passes
+fn process_update_book_top(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: RelayerMintMultiOutcomeCompleteSetArgs,
+    args: UpdateBookTopArgs,
 ) -> ProgramResult {
-    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, 
-                       MULTI_OUTCOME_POSITION_SEED, MAX_OUTCOMES, MarketType};
+    use crate::state::{BookTop, OrderSide, BOOK_TOP_DISCRIMINATOR, BOOK_TOP_SEED};
     
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Relayer (signer)
+    // Account 0: Relayer (signer, pays for creating the BookTop)
     let relayer_info = next_account_info(account_info_iter)?;
     check_signer(relayer_info)?;
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
-    
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
-    
-    // Account 3: MultiOutcomePosition PDA (writable)
-    let position_info = next_account_info(account_info_iter)?;
-    
-    // Account 4: UserAccount (Vault, writable)
-    let user_account_info = next_account_info(account_info_iter)?;
-    
-    // Account 5: PMUserAccount (Vault, writable)
-    let pm_user_account_info = next_account_info(account_info_iter)?;
-    
-    // Account 6: VaultConfig
-    let vault_config_info = next_account_info(account_info_iter)?;
-    
-    // Account 7: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
-    
-    // Account 8: System Program
-    let system_program_info = next_account_info(account_info_iter)?;
-    
-    // Load and validate config
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
     
-    if config.is_paused {
-        return Err(PredictionMarketError::ProgramPaused.into());
-    }
+    // Account 2: Market
+    let market_info = next_account_info(account_info_iter)?;
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
     
-    // Load and validate market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
@@ -6679,163 +12598,98 @@ fn process_relayer_mint_multi_outcome_complete_set_v2(
         return Err(PredictionMarketError::MarketNotFound.into());
     }
     
-    // Verify this is a multi-outcome market
-    if market.market_type != MarketType::MultiOutcome {
-        msg!("❌ Expected MultiOutcome market, got {:?}", market.market_type);
-        return Err(PredictionMarketError::InvalidMarketType.into());
-    }
-    
-    if !market.is_tradeable() {
-        return Err(PredictionMarketError::MarketNotTradeable.into());
-    }
+    // Account 3: BookTop PDA (writable)
+    let book_top_info = next_account_info(account_info_iter)?;
     
-    if args.amount == 0 {
-        return Err(PredictionMarketError::InvalidAmount.into());
-    }
+    // Account 4: Best bid Order, Account 5: Best ask Order
+    let best_bid_info = next_account_info(account_info_iter)?;
+    let best_ask_info = next_account_info(account_info_iter)?;
     
-    let current_time = get_current_timestamp()?;
-    let market_id_bytes = market.market_id.to_le_bytes();
+    // Account 6: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
     
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(
-        &[PM_CONFIG_SEED],
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let outcome_bytes = [args.outcome as u8];
+    let (book_top_pda, bump) = Pubkey::find_program_address(
+        &[BOOK_TOP_SEED, &market_id_bytes, &outcome_bytes],
         program_id,
     );
-    
-    if *config_info.key != config_pda {
+    if *book_top_info.key != book_top_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
-    
-    // Verify MultiOutcomePosition PDA
-    let (position_pda, position_bump) = Pubkey::find_program_address(
-        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
-        program_id,
-    );
-    
-    if *position_info.key != position_pda {
-        msg!("❌ Invalid MultiOutcomePosition PDA");
-        msg!("Expected: {}, Got: {}", position_pda, position_info.key);
-        return Err(PredictionMarketError::InvalidPDA.into());
-    }
+    let bid = book_side(program_id, best_bid_info, &args, OrderSide::Buy, args.bid_depth)?;
+    let ask = book_side(program_id, best_ask_info, &args, OrderSide::Sell, args.ask_depth)?;
     
-    // Step 1: CPI to Vault - PredictionMarketLock
-    msg!("CPI: Vault.PredictionMarketLock amount={}", args.amount);
-    cpi_lock_for_prediction(
-        vault_program_info,
-        vault_config_info,
-        user_account_info,
-        pm_user_account_info,
-        config_info,
-        relayer_info,
-        system_program_info,
-        args.amount,
-        config_seeds,
+    let existing = try_deserialize_initialized::<BookTop>(
+        &book_top_info.data.borrow(),
+        BOOK_TOP_DISCRIMINATOR,
     )?;
-    
-    // Step 2: Create or update MultiOutcomePosition
-    let is_new_position = position_info.data_is_empty();
-    
-    if is_new_position {
-        // Create new MultiOutcomePosition account
-        let rent = Rent::get()?;
-        let space = MultiOutcomePosition::SIZE;
-        let lamports = rent.minimum_balance(space);
-        let position_seeds: &[&[u8]] = &[
-            MULTI_OUTCOME_POSITION_SEED,
-            &market_id_bytes,
-            args.user_wallet.as_ref(),
-            &[position_bump],
-        ];
-        
-        invoke_signed(
-            &system_instruction::create_account(
-                relayer_info.key,
-                position_info.key,
-                lamports,
-                space as u64,
-                program_id,
-            ),
-            &[
-                relayer_info.clone(),
-                position_info.clone(),
-                system_program_info.clone(),
-            ],
-            &[position_seeds],
-        )?;
-        
-        // Initialize MultiOutcomePosition
-        let mut position = MultiOutcomePosition::new(
-            market.market_id,
-            market.num_outcomes,
-            args.user_wallet,
-            position_bump,
-            current_time,
-        );
-        
-        // Add to all outcome holdings
-        let num_outcomes = market.num_outcomes as usize;
-        for i in 0..num_outcomes {
-            position.holdings[i] = args.amount;
-        }
-        position.total_cost_e6 = args.amount;
-        
-        position.serialize(&mut *position_info.data.borrow_mut())?;
-        msg!("✅ Created new MultiOutcomePosition");
-    } else {
-        // Update existing position
-        let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
-        
-        if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
-            return Err(PredictionMarketError::InvalidAccountData.into());
-        }
-        
-        let num_outcomes = market.num_outcomes as usize;
-        for i in 0..num_outcomes {
-            position.holdings[i] = position.holdings[i].saturating_add(args.amount);
+    let mut book_top = match existing {
+        Some(book_top) => book_top,
+        None => {
+            let space = BookTop::SIZE;
+            let lamports = Rent::get()?.minimum_balance(space);
+            invoke_signed(
+                &system_instruction::create_account(
+                    relayer_info.key,
+                    book_top_info.key,
+                    lamports,
+                    space as u64,
+                    program_id,
+                ),
+                &[relayer_info.clone(), book_top_info.clone(), system_program_info.clone()],
+                &[&[BOOK_TOP_SEED, &market_id_bytes, &outcome_bytes, &[bump]]],
+            )?;
+            BookTop::new(args.market_id, args.outcome, bump)
         }
-        position.total_cost_e6 = position.total_cost_e6.saturating_add(args.amount);
-        position.updated_at = current_time;
-        
-        position.serialize(&mut *position_info.data.borrow_mut())?;
-        msg!("✅ Updated existing MultiOutcomePosition");
-    }
-    
-    // Step 3: Update Market
-    market.total_minted = market.total_minted.saturating_add(args.amount);
-    market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
-    
-    msg!("✅ RelayerMintMultiOutcomeCompleteSetV2 completed");
-    msg!("User: {}", args.user_wallet);
-    msg!("Market: {}", market.market_id);
-    msg!("Amount: {}", args.amount);
-    msg!("Total Minted: {}", market.total_minted);
+    };
     
-    msg!("multi_outcome_set_minted:{},{},{},{}", args.market_id, args.user_wallet, args.amount, args.amount);
+    let current_time = get_current_timestamp()?;
+    book_top.update(bid, ask, current_time)?;
+    book_top.serialize(&mut &mut book_top_info.data.borrow_mut()[..])?;
+    
+    msg!("book_top:{},{:?},{},{},{},{}", args.market_id, args.outcome,
+         book_top.best_bid_price, book_top.bid_depth, book_top.best_ask_price, book_top.ask_depth);
     
     Ok(())
 }
 
-/// V2: RelayerRedeemMultiOutcomeCompleteSet using Vault CPI (no SPL Token)
-/// 
-/// Redeems a complete set of all outcome tokens for multi-outcome market.
-/// User must have >= amount of ALL outcome tokens.
-/// Returns 1 USDC per complete set.
-fn process_relayer_redeem_multi_outcome_complete_set_v2(
+/// Grow a config created before ConfigExtension existed to hold the tail;
+/// `payer_info` covers the extra rent
+fn grow_config_for_extension<'a>(
+    payer_info: &AccountInfo<'a>,
+    config_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let space = ConfigExtension::OFFSET + ConfigExtension::SIZE;
+    if config_info.data_len() >= space {
+        return Ok(());
+    }
+    msg!("📦 Migrating PredictionMarketConfig: {} bytes → {} bytes", config_info.data_len(), space);
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    let diff = required_lamports.saturating_sub(config_info.lamports());
+    if diff > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, config_info.key, diff),
+            &[payer_info.clone(), config_info.clone(), system_program_info.clone()],
+        )?;
+    }
+    config_info.realloc(space, true)
+}
+
+/// Return a market's creation bond to its creator, or forfeit it to the
+/// Fund when the market was cancelled as invalid or for regulatory reasons
+fn process_release_creation_bond(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: RelayerRedeemMultiOutcomeCompleteSetArgs,
+    args: ReleaseCreationBondArgs,
 ) -> ProgramResult {
-    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, 
-                       MULTI_OUTCOME_POSITION_SEED, MarketType};
-    
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Relayer (signer)
-    let relayer_info = next_account_info(account_info_iter)?;
-    check_signer(relayer_info)?;
+    // Account 0: Caller (signer)
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
@@ -6843,334 +12697,392 @@ fn process_relayer_redeem_multi_outcome_complete_set_v2(
     // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
     
-    // Account 3: MultiOutcomePosition PDA (writable)
-    let position_info = next_account_info(account_info_iter)?;
+    // Account 3: Vault Program
+    let vault_program_info = next_account_info(account_info_iter)?;
     
-    // Account 4: UserAccount (Vault, writable)
-    let user_account_info = next_account_info(account_info_iter)?;
+    // Account 4: VaultConfig
+    let vault_config_info = next_account_info(account_info_iter)?;
     
-    // Account 5: PMUserAccount (Vault, writable)
-    let pm_user_account_info = next_account_info(account_info_iter)?;
+    // Account 5: Creator's Vault UserAccount (writable)
+    let user_vault_info = next_account_info(account_info_iter)?;
     
-    // Account 6: VaultConfig
-    let vault_config_info = next_account_info(account_info_iter)?;
+    // Account 6: Creator's PMUserAccount (writable)
+    let pm_user_account_info = next_account_info(account_info_iter)?;
     
-    // Account 7: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    // Load and validate config
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
-    
-    if config.is_paused {
-        return Err(PredictionMarketError::ProgramPaused.into());
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load and validate market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
+    let mut extension = MarketExtension::load(&market_info.data.borrow());
+    let bond = extension.creation_bond_e6;
+    if bond == 0 {
+        msg!("Error: Market {} has no creation bond locked", args.market_id);
+        return Err(PredictionMarketError::NoCreationBond.into());
     }
     
-    if market.market_type != MarketType::MultiOutcome {
-        msg!("❌ Expected MultiOutcome market, got {:?}", market.market_type);
-        return Err(PredictionMarketError::InvalidMarketType.into());
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_pm_user_account(pm_user_account_info, &market.creator, vault_program_info.key)?;
+    
+    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    
+    if market.forfeits_creation_bond() {
+        // Account 7: Fund PMUserAccount (writable)
+        let fund_pm_user_info = find_pm_user_account(
+            accounts,
+            &config.fund_program,
+            vault_program_info.key,
+            PredictionMarketError::MissingFundAccount,
+        )?;
+        
+        msg!("CPI: Forfeit creation bond {} to Fund", bond);
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            pm_user_account_info,
+            config_info,
+            bond, // locked_amount: consume the bond
+            0,    // settlement_amount: nothing back to the creator
+            config_seeds,
+        )?;
+        cpi_prediction_settle(
+            vault_program_info,
+            vault_config_info,
+            fund_pm_user_info,
+            config_info,
+            0,
+            bond,
+            config_seeds,
+        )?;
+        
+        msg!("creation_bond_forfeited:{},{},{}", args.market_id, market.creator, bond);
+    } else if market.status == MarketStatus::Resolved || market.status == MarketStatus::Cancelled {
+        msg!("CPI: Return creation bond {} to creator", bond);
+        cpi_release_from_prediction(
+            vault_program_info,
+            vault_config_info,
+            user_vault_info,
+            pm_user_account_info,
+            config_info,
+            bond,
+            config_seeds,
+        )?;
+        
+        msg!("creation_bond_returned:{},{},{}", args.market_id, market.creator, bond);
+    } else {
+        msg!("Error: Market {} is not resolved or cancelled ({:?})", args.market_id, market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
     }
     
-    if args.amount == 0 {
-        return Err(PredictionMarketError::InvalidAmount.into());
+    extension.creation_bond_e6 = 0;
+    if market.status == MarketStatus::Cancelled {
+        extension.cancelled_payouts = extension.cancelled_payouts.saturating_add(1);
     }
+    extension.store(&mut market_info.data.borrow_mut())?;
     
-    let current_time = get_current_timestamp()?;
-    let market_id_bytes = market.market_id.to_le_bytes();
+    Ok(())
+}
+
+/// Set the maker rebate paid out of ExecuteTradeV2 trading fees
+fn process_set_maker_rebate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetMakerRebateArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    // Verify MultiOutcomePosition PDA
-    let (position_pda, _) = Pubkey::find_program_address(
-        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
-        program_id,
-    );
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    if *position_info.key != position_pda {
-        msg!("❌ Invalid MultiOutcomePosition PDA");
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load position
-    let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
-    
-    if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Verify user has sufficient AVAILABLE amounts of ALL outcomes
-    let num_outcomes = market.num_outcomes as usize;
-    for i in 0..num_outcomes {
-        let available = position.holdings[i].saturating_sub(position.locked[i]);
-        if available < args.amount {
-            msg!("❌ Insufficient available outcome {} tokens: available {}, need {}", 
-                 i, available, args.amount);
-            return Err(PredictionMarketError::InsufficientPosition.into());
-        }
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the maker rebate");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(
-        &[PM_CONFIG_SEED],
-        program_id,
-    );
+    // The rebate is paid out of the fees, so it cannot exceed them
+    if args.maker_rebate_bps > config.trade_fee_bps {
+        msg!("Error: Maker rebate {} bps exceeds trade fee {} bps", args.maker_rebate_bps, config.trade_fee_bps);
+        return Err(PredictionMarketError::MakerRebateTooHigh.into());
+    }
+    
+    let old_rebate = config.maker_rebate_bps;
+    config.maker_rebate_bps = args.maker_rebate_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Maker rebate: {} -> {} bps", old_rebate, args.maker_rebate_bps);
+    msg!("maker_rebate_set:{}", args.maker_rebate_bps);
+    
+    Ok(())
+}
+
+/// Allow or forbid complete-set creation through order matching
+fn process_set_allow_match_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetAllowMatchMintArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
+    
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
     
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
     if *config_info.key != config_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Step 1: Vault CPI - Unlock funds
-    msg!("CPI: Vault.PredictionMarketUnlock amount={}", args.amount);
-    cpi_release_from_prediction(
-        vault_program_info,
-        vault_config_info,
-        user_account_info,
-        pm_user_account_info,
-        config_info,
-        args.amount,
-        config_seeds,
-    )?;
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can toggle match-minting");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    // Step 2: Update MultiOutcomePosition - reduce all holdings
-    for i in 0..num_outcomes {
-        position.holdings[i] = position.holdings[i].saturating_sub(args.amount);
+    config.match_mint_disabled = !args.allow_match_mint;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Match-mint {}", if args.allow_match_mint { "enabled" } else { "disabled" });
+    msg!("allow_match_mint_set:{}", args.allow_match_mint);
+    
+    Ok(())
+}
+
+/// Set the minimum order size (0 = disabled)
+/// 
+/// Only affects later placements and fills; resting orders already below
+/// the minimum are left alone until their next fill.
+fn process_set_min_order_size(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetMinOrderSizeArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
+    
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
     }
-    position.total_cost_e6 = position.total_cost_e6.saturating_sub(args.amount);
-    position.updated_at = current_time;
     
-    position.serialize(&mut *position_info.data.borrow_mut())?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Step 3: Update Market
-    market.total_minted = market.total_minted.saturating_sub(args.amount);
-    market.updated_at = current_time;
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the minimum order size");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    msg!("✅ RelayerRedeemMultiOutcomeCompleteSetV2 completed");
-    msg!("User: {}", args.user_wallet);
-    msg!("Market: {}", market.market_id);
-    msg!("Amount: {}", args.amount);
-    msg!("Total Minted: {}", market.total_minted);
+    let old_size = config.min_order_size;
+    config.min_order_size = args.min_order_size;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    msg!("multi_outcome_set_redeemed:{},{},{},{}", args.market_id, args.user_wallet, args.amount, args.amount);
+    msg!("✅ Min order size: {} -> {}", old_size, args.min_order_size);
+    msg!("min_order_size_set:{}", args.min_order_size);
     
     Ok(())
 }
 
-/// V2: RelayerClaimMultiOutcomeWinnings using Vault CPI (no SPL Token)
+/// Set the minimum notional of a new order (0 = disabled)
 /// 
-/// Claims winnings after market resolution for multi-outcome market.
-/// Settlement = amount of winning outcome tokens * 1 USDC
-fn process_relayer_claim_multi_outcome_winnings_v2(
+/// Resting orders are not affected.
+fn process_set_min_notional(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: RelayerClaimMultiOutcomeWinningsArgs,
+    args: SetMinNotionalArgs,
 ) -> ProgramResult {
-    use crate::state::{MultiOutcomePosition, MULTI_OUTCOME_POSITION_DISCRIMINATOR, 
-                       MULTI_OUTCOME_POSITION_SEED, MAX_OUTCOMES, MarketType, MarketStatus};
-    
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Relayer (signer)
-    let relayer_info = next_account_info(account_info_iter)?;
-    check_signer(relayer_info)?;
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Account 1: PredictionMarketConfig
+    // Account 1: PredictionMarketConfig (writable)
     let config_info = next_account_info(account_info_iter)?;
     
-    // Account 2: Market
-    let market_info = next_account_info(account_info_iter)?;
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    // Account 3: MultiOutcomePosition PDA (writable)
-    let position_info = next_account_info(account_info_iter)?;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Account 4: PMUserAccount (Vault, writable)
-    let pm_user_account_info = next_account_info(account_info_iter)?;
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set the minimum order notional");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    // Account 5: VaultConfig
-    let vault_config_info = next_account_info(account_info_iter)?;
+    let old_notional = config.min_notional_e6;
+    config.min_notional_e6 = args.min_notional_e6;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    // Account 6: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
+    msg!("✅ Min order notional: {} -> {}", old_notional, args.min_notional_e6);
+    msg!("min_notional_set:{}", args.min_notional_e6);
+    
+    Ok(())
+}
 
-    // Account 7 (optional): UserAccount — if present, settle directly to available_balance
-    let user_vault_info = next_account_info(account_info_iter).ok();
+/// Write a MarketSummary as return data (read-only)
+fn process_query_market(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: QueryMarketArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    // Load and validate config
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
-    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+    // Account 0: Market
+    let market_info = next_account_info(account_info_iter)?;
+    
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    if market_info.owner != program_id {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    verify_relayer(&config, relayer_info.key)?;
-    
-    // Load and validate market
     let market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
-    }
+    set_return_data(&market.summary().try_to_vec()?);
     
-    if market.market_type != MarketType::MultiOutcome {
-        return Err(PredictionMarketError::InvalidMarketType.into());
-    }
+    Ok(())
+}
+
+/// Write the ClaimableSummary of a position as return data (read-only)
+fn process_query_claimable(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: QueryClaimableArgs,
+) -> ProgramResult {
+    use crate::state::ClaimableSummary;
     
-    // Market must be Resolved or Cancelled
-    if market.status != MarketStatus::Resolved && market.status != MarketStatus::Cancelled {
-        msg!("❌ Market status must be Resolved or Cancelled, got {:?}", market.status);
-        return Err(PredictionMarketError::MarketNotResolved.into());
-    }
+    let account_info_iter = &mut accounts.iter();
     
-    let market_id_bytes = market.market_id.to_le_bytes();
-    let current_time = get_current_timestamp()?;
+    // Account 0: Market
+    let market_info = next_account_info(account_info_iter)?;
     
-    // Verify MultiOutcomePosition PDA
-    let (position_pda, _) = Pubkey::find_program_address(
-        &[MULTI_OUTCOME_POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
-        program_id,
-    );
+    // Account 1: Position PDA
+    let position_info = next_account_info(account_info_iter)?;
     
-    if *position_info.key != position_pda {
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let (market_pda, _) = Pubkey::find_program_address(&[MARKET_SEED, &market_id_bytes], program_id);
+    if *market_info.key != market_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
-    
-    // Load position
-    let mut position = deserialize_account::<MultiOutcomePosition>(&position_info.data.borrow())?;
-    
-    if position.discriminator != MULTI_OUTCOME_POSITION_DISCRIMINATOR {
+    if market_info.owner != program_id {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if position.settled {
-        msg!("Position already settled");
-        return Err(PredictionMarketError::AlreadySettled.into());
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    if market.status != MarketStatus::Resolved && market.status != MarketStatus::Cancelled {
+        return Err(PredictionMarketError::MarketNotResolved.into());
     }
     
-    // Calculate settlement.
-    // CRITICAL: Use remaining_locked (total_cost - settled_cost) to avoid
-    // double-releasing pm_locked that was already consumed in trades.
-    let remaining_locked = position.total_cost_e6.saturating_sub(position.settled_cost_e6);
-    let locked_amount = remaining_locked;
-    
-    let settlement_amount = if market.status == MarketStatus::Cancelled {
-        // Cancelled: refund only remaining locked (not already traded away)
-        locked_amount
-    } else {
-        // Get winning outcome index
-        let winning_outcome_index = market.winning_outcome_index
-            .ok_or(PredictionMarketError::MarketNotResolved)?;
-        
-        // Winning tokens pay out 1:1 (1 share = $1 USDC in e6)
-        position.holdings[winning_outcome_index as usize]
-    };
-    
-    // Derive Config PDA for CPI signing
-    let (config_pda, config_bump) = Pubkey::find_program_address(
-        &[PM_CONFIG_SEED],
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, &market_id_bytes, args.user_wallet.as_ref()],
         program_id,
     );
-    
-    if *config_info.key != config_pda {
+    if *position_info.key != position_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    let config_seeds: &[&[u8]] = &[PM_CONFIG_SEED, &[config_bump]];
-    
-    // Vault CPI — only if there's something to settle
-    if locked_amount > 0 || settlement_amount > 0 {
-        if let Some(uvi) = user_vault_info {
-            msg!("CPI: Vault.SettleToAvailable locked={}, settlement={} (multi-outcome)", locked_amount, settlement_amount);
-            cpi_prediction_settle_to_available(
-                vault_program_info,
-                vault_config_info,
-                uvi,
-                pm_user_account_info,
-                config_info,
-                locked_amount,
-                settlement_amount,
-                config_seeds,
-            )?;
-        } else {
-            msg!("CPI: Vault.Settle locked={}, settlement={} (multi-outcome, legacy)", locked_amount, settlement_amount);
-            cpi_prediction_settle(
-                vault_program_info,
-                vault_config_info,
-                pm_user_account_info,
-                config_info,
-                locked_amount,
-                settlement_amount,
-                config_seeds,
-            )?;
+    let position = try_deserialize_initialized::<Position>(
+        &position_info.data.borrow(),
+        POSITION_DISCRIMINATOR,
+    )?;
+    let summary = match position {
+        Some(position) if position.settled => ClaimableSummary { settled: true, ..ClaimableSummary::default() },
+        Some(position) => {
+            // Same amounts RelayerClaimWinningsV2 settles
+            let (locked_amount, gross_settlement) = claim_settlement_amounts(&market, &position)?;
+            let creator_fee = market.creator_fee_on(gross_settlement);
+            ClaimableSummary {
+                settled: false,
+                claimable_e6: gross_settlement - creator_fee,
+                creator_fee_e6: creator_fee,
+                locked_e6: locked_amount,
+            }
         }
-    } else {
-        msg!("Multi-outcome: loser/zero position, locked=0, settlement=0, skipping CPI");
-    }
-    
-    // Update position
-    let pnl = (settlement_amount as i64) - (locked_amount as i64);
-    position.realized_pnl = position.realized_pnl.saturating_add(pnl);
-    position.settlement_amount = settlement_amount;
-    position.settled = true;
-    // Clear all holdings
-    for i in 0..MAX_OUTCOMES {
-        position.holdings[i] = 0;
-        position.locked[i] = 0;
-    }
-    position.updated_at = current_time;
-    
-    position.serialize(&mut *position_info.data.borrow_mut())?;
-    
-    msg!("✅ RelayerClaimMultiOutcomeWinningsV2 completed");
-    msg!("User: {}", args.user_wallet);
-    msg!("Market: {}", market.market_id);
-    msg!("Settlement: {}, PnL: {}", settlement_amount, pnl);
+        None => ClaimableSummary::default(),
+    };
     
-    msg!("multi_outcome_winnings_claimed:{},{},{},{}", market.market_id, args.user_wallet, market.winning_outcome_index.unwrap_or(255), settlement_amount);
+    set_return_data(&summary.try_to_vec()?);
     
     Ok(())
 }
 
-// ============================================================================
-// V15.1: FinalizeResultV2 - Finalize result after challenge window
-// ============================================================================
-
-/// Process FinalizeResultV2 instruction
+/// Push back the schedule of a market whose event was postponed
 /// 
-/// Transitions market from ResultProposed to Resolved after challenge window expires.
-/// This is permissionless - anyone can call it after the deadline.
-/// The proposer's bond is returned via Vault CPI.
-fn process_finalize_result_v2(
+/// Admin only. Both times move by the same amount so the finalization
+/// window is kept; the result is validated as on creation.
+fn process_extend_resolution_time(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: FinalizeResultV2Args,
+    args: ExtendResolutionTimeArgs,
 ) -> ProgramResult {
-    use crate::state::{OracleProposal, OracleProposalData, ORACLE_PROPOSAL_DISCRIMINATOR, 
-                       ORACLE_PROPOSAL_SEED, ORACLE_PROPOSAL_DATA_DISCRIMINATOR,
-                       ORACLE_PROPOSAL_DATA_SEED, MarketStatus, ProposalStatus};
-    
-    msg!("FinalizeResultV2: market={}", args.market_id);
-    
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Caller (signer) - permissionless
-    let caller_info = next_account_info(account_info_iter)?;
-    check_signer(caller_info)?;
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
@@ -7178,357 +13090,273 @@ fn process_finalize_result_v2(
     // Account 2: Market (writable)
     let market_info = next_account_info(account_info_iter)?;
     
-    // Account 3: OracleProposal PDA (writable)
+    // Account 3: OracleProposal PDA
     let proposal_info = next_account_info(account_info_iter)?;
     
-    // Account 4: OracleProposalData PDA
-    let proposal_data_info = next_account_info(account_info_iter)?;
-    
-    // Account 5: Proposer's PMUserAccount (Vault, writable) - for bond return
-    let proposer_pm_account_info = next_account_info(account_info_iter)?;
-    
-    // Account 6: VaultConfig
-    let vault_config_info = next_account_info(account_info_iter)?;
-    
-    // Account 7: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    // Load and validate config
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    let config_bump = config.bump;
-    
-    // Load and validate market
-    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    if market.discriminator != MARKET_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
-    
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
-    }
-    
-    // Market must be in ResultProposed or Challenged state
-    // Challenged markets can be finalized after the extended challenge deadline passes
-    // (dispute resolution via DB resets or admin action sets status back to ResultProposed,
-    //  but we also accept Challenged directly to handle the case where dispute resolution
-    //  uses the original proposal result as the final outcome — "upheld" scenario)
-    if market.status != MarketStatus::ResultProposed && market.status != MarketStatus::Challenged {
-        msg!("❌ Market must be in ResultProposed or Challenged state, got {:?}", market.status);
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can extend the resolution time");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
     let market_id_bytes = args.market_id.to_le_bytes();
-    let current_time = get_current_timestamp()?;
-    
-    // Verify OracleProposal PDA
-    let (proposal_pda, _) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
-        program_id,
-    );
-    
-    if *proposal_info.key != proposal_pda {
-        return Err(PredictionMarketError::InvalidPDA.into());
-    }
-    
-    // Load and validate proposal
-    let mut proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
-    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
-        return Err(PredictionMarketError::InvalidAccountData.into());
-    }
-    
-    // Verify OracleProposalData PDA
-    let (proposal_data_pda, _) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &market_id_bytes],
         program_id,
     );
-    
-    if *proposal_data_info.key != proposal_data_pda {
+    if *market_info.key != market_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load proposal data
-    let proposal_data = deserialize_account::<OracleProposalData>(&proposal_data_info.data.borrow())?;
-    if proposal_data.discriminator != ORACLE_PROPOSAL_DATA_DISCRIMINATOR {
+    let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Check if challenge window has expired (use proposal.challenge_deadline)
-    if current_time < proposal.challenge_deadline {
-        msg!("❌ Challenge window has not expired yet: current={}, deadline={}", 
-             current_time, proposal.challenge_deadline);
-        return Err(PredictionMarketError::ChallengeWindowNotExpired.into());
-    }
-    
-    // Proposal must not be disputed (check status)
-    if proposal.status == ProposalStatus::Disputed {
-        msg!("❌ Cannot finalize: proposal has been disputed");
-        return Err(PredictionMarketError::OracleDisputeInProgress.into());
-    }
-    
-    // Proposal must be in Pending status
-    if proposal.status != ProposalStatus::Pending {
-        msg!("❌ Proposal is not in Pending status, got {:?}", proposal.status);
-        return Err(PredictionMarketError::CannotFinalize.into());
+    if market.status != MarketStatus::Active && market.status != MarketStatus::Pending {
+        msg!("Error: Only Active or Pending markets can be extended, got {:?}", market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
     }
     
-    // Return proposer's bond via Vault CPI
-    // Bond was locked when proposal was created, now we release it
-    let bond_amount = proposal.bond_amount;
-    
-    if bond_amount > 0 {
-        msg!("📤 Returning proposer bond: {} e6", bond_amount);
-        
-        let config_seeds = &[
-            PM_CONFIG_SEED,
-            &[config_bump],
-        ];
-        
-        // Use settlement with locked=bond, settlement=bond (full return)
-        cpi_prediction_settle(
-            vault_program_info,
-            vault_config_info,
-            proposer_pm_account_info,
-            config_info,
-            bond_amount,  // locked_amount = bond
-            bond_amount,  // settlement_amount = bond (full return, no loss)
-            config_seeds,
-        )?;
+    let (proposal_pda, _) = Pubkey::find_program_address(
+        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
+        program_id,
+    );
+    if *proposal_info.key != proposal_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    if proposal_info.data_len() > 0 {
+        msg!("Error: A result was already proposed for market {}", args.market_id);
+        return Err(PredictionMarketError::ProposalAlreadyExists.into());
     }
     
-    // Update market to Resolved
-    market.status = MarketStatus::Resolved;
-    market.final_result = Some(proposal.proposed_result);
-    market.winning_outcome_index = Some(proposal_data.proposed_outcome_index);
-    market.updated_at = current_time;
-    
-    market.serialize(&mut *market_info.data.borrow_mut())?;
+    if args.extension_secs <= 0 || args.extension_secs > MAX_RESOLUTION_EXTENSION_SECS {
+        msg!("Error: Extension must be in (0, {}] seconds", MAX_RESOLUTION_EXTENSION_SECS);
+        return Err(PredictionMarketError::InvalidResolutionTime.into());
+    }
     
-    // Update proposal status to Finalized
-    proposal.status = ProposalStatus::Finalized;
+    let resolution_time = market.resolution_time
+        .checked_add(args.extension_secs)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    let finalization_deadline = market.finalization_deadline
+        .checked_add(args.extension_secs)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
     
-    proposal.serialize(&mut *proposal_info.data.borrow_mut())?;
+    let current_time = get_current_timestamp()?;
+    validate_market_schedule(resolution_time, finalization_deadline, current_time)?;
     
-    msg!("✅ FinalizeResultV2 completed");
-    msg!("Market {} resolved with result {:?}, outcome index {}", 
-         market.market_id, market.final_result, proposal_data.proposed_outcome_index);
-    msg!("Bond returned: {} e6", bond_amount);
+    market.resolution_time = resolution_time;
+    market.finalization_deadline = finalization_deadline;
+    market.updated_at = current_time;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
-    msg!("result_finalized:{},{}", market.market_id, proposal_data.proposed_outcome_index);
-    if market.market_type == MarketType::MultiOutcome {
-        msg!("multi_outcome_result_finalized:{},{}", market.market_id, proposal_data.proposed_outcome_index);
-    }
+    msg!("✅ Market {} resolution time extended by {}s", args.market_id, args.extension_secs);
+    msg!("market_schedule_updated:{},{},{}", args.market_id, resolution_time, finalization_deadline);
     
     Ok(())
 }
 
-// ============================================================================
-// V15.2: RelayerChallengeResultV2 - Relayer-signed challenge for Public API
-// ============================================================================
-
-/// Process RelayerChallengeResultV2 instruction
-/// 
-/// Allows relayer to submit a challenge on behalf of a user.
-/// The challenger's bond is deducted from their Vault account via CPI.
-/// This enables Public API to submit challenges without requiring user signature.
+/// Set the per-market TVL cap (0 = disabled)
 /// 
-/// Accounts:
-/// 0. `[signer]` Relayer
-/// 1. `[]` PredictionMarketConfig
-/// 2. `[writable]` Market
-/// 3. `[writable]` OracleProposal PDA
-/// 4. `[writable]` OracleProposalData PDA
-/// 5. `[writable]` Challenger's UserAccount (Vault)
-/// 6. `[writable]` Challenger's PMUserAccount (Vault) - for bond deduction
-/// 7. `[]` VaultConfig
-/// 8. `[]` Vault Program
-/// 9. `[]` System Program
-fn process_relayer_challenge_result_v2(
+/// A cap below the current locked collateral only blocks further minting;
+/// existing positions are unaffected.
+fn process_set_market_tvl_cap(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: crate::instruction::RelayerChallengeResultV2Args,
+    args: SetMarketTvlCapArgs,
 ) -> ProgramResult {
-    use crate::state::{OracleProposal, OracleProposalData, ORACLE_PROPOSAL_DISCRIMINATOR, 
-                       ORACLE_PROPOSAL_SEED, ORACLE_PROPOSAL_DATA_DISCRIMINATOR,
-                       ORACLE_PROPOSAL_DATA_SEED, MarketStatus};
-    
-    msg!("RelayerChallengeResultV2: market={}, challenger={}, outcome={}", 
-         args.market_id, args.user_wallet, args.challenger_outcome_index);
-    
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Relayer (signer)
-    let relayer_info = next_account_info(account_info_iter)?;
-    check_signer(relayer_info)?;
+    // Account 0: Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
-    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     
+    // Account 2: Market (writable)
+    let market_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if config.is_paused {
-        return Err(PredictionMarketError::ProgramPaused.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only admin can set market TVL cap");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Verify relayer is authorized
-    verify_relayer(&config, relayer_info.key)?;
-    
-    let config_bump = config.bump;
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &args.market_id.to_le_bytes()],
+        program_id,
+    );
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
     
-    // Account 2: Market (writable)
-    let market_info = next_account_info(account_info_iter)?;
     let mut market = deserialize_account::<Market>(&market_info.data.borrow())?;
-    
     if market.discriminator != MARKET_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    if market.market_id != args.market_id {
-        return Err(PredictionMarketError::MarketNotFound.into());
-    }
+    let old_cap = market.max_tvl_e6;
+    market.max_tvl_e6 = args.max_tvl_e6;
+    market.updated_at = get_current_timestamp()?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
     
-    // Market must be in ResultProposed state
-    if market.status != MarketStatus::ResultProposed {
-        msg!("Market must be in ResultProposed state to challenge, got {:?}", market.status);
-        return Err(PredictionMarketError::InvalidMarketStatus.into());
-    }
+    msg!("✅ Market {} TVL cap: {} -> {} (e6)", args.market_id, old_cap, args.max_tvl_e6);
+    msg!("market_tvl_cap_set:{},{}", args.market_id, args.max_tvl_e6);
     
-    let current_time = get_current_timestamp()?;
-    let market_id_bytes = args.market_id.to_le_bytes();
+    Ok(())
+}
+
+/// Step 1 of admin handover: current admin nominates a pending admin
+fn process_initiate_admin_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: InitiateAdminTransferArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    // Account 3: OracleProposal PDA (writable)
-    let proposal_info = next_account_info(account_info_iter)?;
+    // Account 0: Current Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Validate OracleProposal PDA
-    let (proposal_pda, _proposal_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_SEED, &market_id_bytes],
-        program_id,
-    );
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
     
-    if *proposal_info.key != proposal_pda {
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load and validate OracleProposal to check challenge window
-    let proposal = deserialize_account::<OracleProposal>(&proposal_info.data.borrow())?;
-    if proposal.discriminator != ORACLE_PROPOSAL_DISCRIMINATOR {
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Verify within challenge window — use stored challenge_deadline (consistent with FinalizeResultV2)
-    if current_time > proposal.challenge_deadline {
-        msg!("Challenge window has expired: current={}, deadline={}", current_time, proposal.challenge_deadline);
-        return Err(PredictionMarketError::ChallengeWindowExpired.into());
+    if *admin_info.key != config.admin {
+        msg!("Error: Only current admin can initiate admin transfer");
+        return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // Account 4: OracleProposalData PDA (writable)
-    let proposal_data_info = next_account_info(account_info_iter)?;
+    if args.new_admin == config.admin {
+        msg!("Error: New admin {} is already the admin", args.new_admin);
+        return Err(PredictionMarketError::AdminUnchanged.into());
+    }
     
-    // Validate OracleProposalData PDA
-    let (proposal_data_pda, _proposal_data_bump) = Pubkey::find_program_address(
-        &[ORACLE_PROPOSAL_DATA_SEED, &market_id_bytes],
-        program_id,
-    );
+    // The default key marks "no pending transfer" and can't be nominated
+    if args.new_admin == Pubkey::default() {
+        msg!("Error: New admin must not be the default pubkey");
+        return Err(PredictionMarketError::InvalidArgument.into());
+    }
     
-    if *proposal_data_info.key != proposal_data_pda {
+    config.pending_admin = args.new_admin;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ Admin transfer initiated: {} -> {} (pending acceptance)", config.admin, args.new_admin);
+    msg!("admin_transfer_initiated:{},{}", config.admin, args.new_admin);
+    
+    Ok(())
+}
+
+/// Step 2 of admin handover: pending admin signs to promote itself
+fn process_accept_admin_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Pending Admin (signer)
+    let pending_info = next_account_info(account_info_iter)?;
+    check_signer(pending_info)?;
+    
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
+    
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
         return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Load and update OracleProposalData with challenger's outcome
-    let mut proposal_data = deserialize_account::<OracleProposalData>(&proposal_data_info.data.borrow())?;
-    if proposal_data.discriminator != ORACLE_PROPOSAL_DATA_DISCRIMINATOR {
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
     
-    // Challenger's outcome must differ from proposed outcome
-    if args.challenger_outcome_index == proposal_data.proposed_outcome_index {
-        msg!("Challenger outcome must differ from proposed outcome");
-        return Err(PredictionMarketError::InvalidOutcome.into());
-    }
+    let pending_admin = config.pending_admin_transfer().ok_or_else(|| {
+        msg!("Error: No pending admin transfer");
+        PredictionMarketError::NoPendingAdminTransfer
+    })?;
     
-    // Account 5: Challenger's UserAccount (Vault) - for bond lock
-    let challenger_vault_info = next_account_info(account_info_iter)?;
+    if *pending_info.key != pending_admin {
+        msg!("Error: Signer {} is not the pending admin", pending_info.key);
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    // Account 6: Challenger's PMUserAccount (Vault)
-    let challenger_pm_account_info = next_account_info(account_info_iter)?;
+    let old_admin = config.admin;
+    config.admin = pending_admin;
+    config.pending_admin = Pubkey::default();
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    // Account 7: VaultConfig
-    let vault_config_info = next_account_info(account_info_iter)?;
+    msg!("✅ Admin transfer accepted: {} -> {}", old_admin, pending_admin);
+    msg!("admin_updated:{},{}", old_admin, pending_admin);
     
-    // Account 8: Vault Program
-    let vault_program_info = next_account_info(account_info_iter)?;
+    Ok(())
+}
+
+/// Cancel a pending admin handover (current admin only)
+fn process_cancel_admin_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
     
-    // Account 9: System Program (for auto-init)
-    let system_program_info = next_account_info(account_info_iter)?;
+    // Account 0: Current Admin (signer)
+    let admin_info = next_account_info(account_info_iter)?;
+    check_signer(admin_info)?;
     
-    // Lock challenger's bond via Vault CPI
-    let bond_amount = config.proposer_bond_e6;
+    // Account 1: PredictionMarketConfig (writable)
+    let config_info = next_account_info(account_info_iter)?;
     
-    if bond_amount > 0 {
-        msg!("📥 Locking challenger bond: {} e6 for user {}", bond_amount, args.user_wallet);
-        
-        let config_seeds = &[
-            PM_CONFIG_SEED,
-            &[config_bump],
-        ];
-        
-        cpi_lock_for_prediction(
-            vault_program_info,
-            vault_config_info,
-            challenger_vault_info,
-            challenger_pm_account_info,
-            config_info,
-            relayer_info,
-            system_program_info,
-            bond_amount,
-            config_seeds,
-        )?;
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
     }
     
-    // Record challenger's outcome and evidence hash
-    proposal_data.set_challenger(args.challenger_outcome_index, current_time);
-    msg!("Challenge evidence_hash: {:?}", &args.evidence_hash[0..8]);
-    
-    // Record challenger info on OracleProposal (for dispute resolution tracking)
-    let mut proposal = proposal; // make mutable
-    let challenger_pubkey = Pubkey::from(args.user_wallet);
-    let challenger_result = match args.challenger_outcome_index {
-        0 => MarketResult::Yes,
-        1 => MarketResult::No,
-        _ => MarketResult::Invalid,
-    };
-    proposal.challenger = Some(challenger_pubkey);
-    proposal.challenger_result = Some(challenger_result);
-    proposal.challenger_bond = bond_amount;
-    
-    // Extend challenge deadline on-chain
-    let challenge_duration = config.challenge_window_secs.max(3600) as i64;
-    let new_deadline = (current_time + challenge_duration).max(proposal.challenge_deadline);
-    proposal.challenge_deadline = new_deadline;
-    proposal.challenge_count = proposal.challenge_count.saturating_add(1);
-    
-    // Update market status to Challenged
-    market.status = MarketStatus::Challenged;
-    market.updated_at = current_time;
+    let mut config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
+    if config.discriminator != PM_CONFIG_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
     
-    // Serialize ALL updated accounts (proposal + proposal_data + market)
-    proposal.serialize(&mut &mut proposal_info.data.borrow_mut()[..])?;
-    proposal_data.serialize(&mut &mut proposal_data_info.data.borrow_mut()[..])?;
-    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    if *admin_info.key != config.admin {
+        msg!("Error: Only current admin can cancel admin transfer");
+        return Err(PredictionMarketError::Unauthorized.into());
+    }
     
-    msg!("✅ RelayerChallengeResultV2 completed");
-    msg!("Market {} challenged by {} (via relayer), outcome={}, bond={} e6, new_deadline={}", 
-         args.market_id, args.user_wallet, args.challenger_outcome_index, bond_amount, new_deadline);
+    let pending_admin = config.pending_admin_transfer().ok_or_else(|| {
+        msg!("Error: No pending admin transfer");
+        PredictionMarketError::NoPendingAdminTransfer
+    })?;
+    config.pending_admin = Pubkey::default();
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
     
-    // Structured log for chain sync service to detect challenge events
-    msg!("result_challenged:{},{},{},{}", 
-         args.market_id, args.user_wallet, args.challenger_outcome_index, bond_amount);
+    msg!("✅ Admin transfer to {} cancelled", pending_admin);
     
     Ok(())
 }
@@ -7538,21 +13366,42 @@ fn process_relayer_challenge_result_v2(
 // =============================================================================
 
 /// Add an authorized caller to the matching engine
+/// 
+/// The AuthorizedCallers PDA is created on first use, with the admin paying rent.
 fn process_add_authorized_caller(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     args: AddAuthorizedCallerArgs,
 ) -> ProgramResult {
+    use crate::state::{
+        AuthorizedCallers, AUTHORIZED_CALLERS_SEED, AUTHORIZED_CALLERS_DISCRIMINATOR,
+        MAX_AUTHORIZED_CALLERS,
+    };
+    
     let account_info_iter = &mut accounts.iter();
     
-    // Account 0: Admin (signer)
+    // Account 0: Admin (signer, payer)
     let admin_info = next_account_info(account_info_iter)?;
     check_signer(admin_info)?;
     
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
     
+    // Account 2: AuthorizedCallers PDA (writable)
+    let callers_info = next_account_info(account_info_iter)?;
+    
+    // Account 3: System Program
+    let system_program_info = next_account_info(account_info_iter)?;
+    
     // Load and validate config
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    if config_info.owner != program_id {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
@@ -7564,10 +13413,62 @@ fn process_add_authorized_caller(
         return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    // For now, authorized callers are stored in the config
-    // Future: use AuthorizedCallers PDA for more callers
-    msg!("✅ AddAuthorizedCaller: {}", args.caller);
-    msg!("Note: Authorized callers are managed via config.authorized_caller or AuthorizedCallers PDA");
+    let (callers_pda, callers_bump) = Pubkey::find_program_address(
+        &[AUTHORIZED_CALLERS_SEED],
+        program_id,
+    );
+    if *callers_info.key != callers_pda {
+        msg!("Error: Invalid AuthorizedCallers PDA");
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    
+    let mut callers = if callers_info.data_is_empty() {
+        // First use: create the registry
+        let rent = Rent::get()?;
+        let space = AuthorizedCallers::SIZE;
+        let lamports = rent.minimum_balance(space);
+        let callers_seeds: &[&[u8]] = &[AUTHORIZED_CALLERS_SEED, &[callers_bump]];
+        
+        msg!("Creating AuthorizedCallers registry");
+        invoke_signed(
+            &system_instruction::create_account(
+                admin_info.key,
+                callers_info.key,
+                lamports,
+                space as u64,
+                program_id,
+            ),
+            &[admin_info.clone(), callers_info.clone(), system_program_info.clone()],
+            &[callers_seeds],
+        )?;
+        
+        AuthorizedCallers::new(callers_bump, current_time)
+    } else {
+        let callers = deserialize_account::<AuthorizedCallers>(&callers_info.data.borrow())?;
+        if callers.discriminator != AUTHORIZED_CALLERS_DISCRIMINATOR {
+            return Err(PredictionMarketError::InvalidAccountData.into());
+        }
+        callers
+    };
+    
+    if callers.is_authorized(&args.caller) {
+        msg!("Error: {} is already authorized", args.caller);
+        return Err(PredictionMarketError::CallerAlreadyAuthorized.into());
+    }
+    
+    if (callers.count as usize) >= MAX_AUTHORIZED_CALLERS {
+        msg!("Error: Authorized callers list is full ({} max)", MAX_AUTHORIZED_CALLERS);
+        return Err(PredictionMarketError::AuthorizedCallersFull.into());
+    }
+    
+    callers.add_caller(args.caller, current_time)
+        .map_err(|_| PredictionMarketError::AuthorizedCallersFull)?;
+    callers.serialize(&mut &mut callers_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ AddAuthorizedCaller: {} ({}/{})", args.caller, callers.count, MAX_AUTHORIZED_CALLERS);
+    msg!("authorized_caller_added:{}", args.caller);
     
     Ok(())
 }
@@ -7578,6 +13479,11 @@ fn process_remove_authorized_caller(
     accounts: &[AccountInfo],
     args: RemoveAuthorizedCallerArgs,
 ) -> ProgramResult {
+    use crate::state::{
+        AuthorizedCallers, AUTHORIZED_CALLERS_SEED, AUTHORIZED_CALLERS_DISCRIMINATOR,
+        MAX_AUTHORIZED_CALLERS,
+    };
+    
     let account_info_iter = &mut accounts.iter();
     
     // Account 0: Admin (signer)
@@ -7587,7 +13493,18 @@ fn process_remove_authorized_caller(
     // Account 1: PredictionMarketConfig
     let config_info = next_account_info(account_info_iter)?;
     
+    // Account 2: AuthorizedCallers PDA (writable)
+    let callers_info = next_account_info(account_info_iter)?;
+    
     // Load and validate config
+    let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+    if *config_info.key != config_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    if config_info.owner != program_id {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
     let config = deserialize_account::<PredictionMarketConfig>(&config_info.data.borrow())?;
     if config.discriminator != PM_CONFIG_DISCRIMINATOR {
         return Err(PredictionMarketError::InvalidAccountData.into());
@@ -7599,8 +13516,116 @@ fn process_remove_authorized_caller(
         return Err(PredictionMarketError::Unauthorized.into());
     }
     
-    msg!("✅ RemoveAuthorizedCaller: {}", args.caller);
-    msg!("Note: Authorized callers are managed via config.authorized_caller or AuthorizedCallers PDA");
+    let (callers_pda, _) = Pubkey::find_program_address(
+        &[AUTHORIZED_CALLERS_SEED],
+        program_id,
+    );
+    if *callers_info.key != callers_pda {
+        msg!("Error: Invalid AuthorizedCallers PDA");
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    if callers_info.data_is_empty() {
+        msg!("Error: AuthorizedCallers registry not initialized");
+        return Err(PredictionMarketError::AccountNotInitialized.into());
+    }
+    
+    let mut callers = deserialize_account::<AuthorizedCallers>(&callers_info.data.borrow())?;
+    if callers.discriminator != AUTHORIZED_CALLERS_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let current_time = get_current_timestamp()?;
+    
+    if callers.remove_caller(&args.caller, current_time).is_err() {
+        msg!("Error: {} is not an authorized caller", args.caller);
+        return Err(PredictionMarketError::CallerNotAuthorized.into());
+    }
+    callers.serialize(&mut &mut callers_info.data.borrow_mut()[..])?;
+    
+    msg!("✅ RemoveAuthorizedCaller: {} ({}/{})", args.caller, callers.count, MAX_AUTHORIZED_CALLERS);
+    if callers.count == 0 {
+        // Keep the account allocated so AddAuthorizedCaller can reuse it
+        msg!("AuthorizedCallers registry is now empty");
+    }
+    msg!("authorized_caller_removed:{}", args.caller);
+    
+    Ok(())
+}
+
+// ============================================================================
+// Maintenance
+// ============================================================================
+
+/// Repair outcome_index on legacy binary-market orders (permissionless)
+/// 
+/// Orders created before `outcome_index` existed read it as 0 from the old
+/// reserved bytes, making NO orders look like YES to index-aware code.
+fn process_sync_order_outcome_index(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SyncOrderOutcomeIndexArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Account 0: Caller (signer) - permissionless
+    let caller_info = next_account_info(account_info_iter)?;
+    check_signer(caller_info)?;
+    
+    // Account 1: Market PDA
+    let market_info = next_account_info(account_info_iter)?;
+    
+    // Account 2: Order PDA (writable)
+    let order_info = next_account_info(account_info_iter)?;
+    
+    let market_id_bytes = args.market_id.to_le_bytes();
+    let (market_pda, _) = Pubkey::find_program_address(
+        &[MARKET_SEED, &market_id_bytes],
+        program_id,
+    );
+    if *market_info.key != market_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    let order_id_bytes = args.order_id.to_le_bytes();
+    let (order_pda, _) = Pubkey::find_program_address(
+        &[ORDER_SEED, &market_id_bytes, &order_id_bytes],
+        program_id,
+    );
+    if *order_info.key != order_pda {
+        return Err(PredictionMarketError::InvalidPDA.into());
+    }
+    
+    if order_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    let market = deserialize_account::<Market>(&market_info.data.borrow())?;
+    if market.discriminator != MARKET_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    // Only binary markets derive outcome_index from outcome
+    if !market.is_binary() {
+        msg!("Error: SyncOrderOutcomeIndex only applies to Binary markets");
+        return Err(PredictionMarketError::InvalidMarketType.into());
+    }
+    
+    let mut order = deserialize_account::<Order>(&order_info.data.borrow())?;
+    if order.discriminator != ORDER_DISCRIMINATOR {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let old_index = order.outcome_index;
+    if !order.sync_binary_outcome_index() {
+        msg!("Order {} outcome_index already in sync ({})", args.order_id, old_index);
+        return Ok(());
+    }
+    
+    order.serialize(&mut *order_info.data.borrow_mut())?;
+    
+    msg!("✅ Order {} outcome_index synced: {} -> {} ({:?})", 
+         args.order_id, old_index, order.outcome_index, order.outcome);
     
     Ok(())
 }
@@ -7630,7 +13655,11 @@ fn process_relayer_settle_prediction(
         return Err(PredictionMarketError::InvalidAccountData.into());
     }
 
-    verify_relayer(&config, relayer_info.key)?;
+    verify_relayer(program_id, &config, relayer_info.key, accounts)?;
+    
+    verify_vault_program(vault_program_info.key, &config.vault_program)?;
+    verify_settlement_vault(vault_config_info, vault_program_info.key)?;
+    verify_pm_user_account(pm_user_account_info, &args.user_wallet, vault_program_info.key)?;
 
     let market = deserialize_account::<Market>(&market_info.data.borrow())?;
     if market.discriminator != MARKET_DISCRIMINATOR {
@@ -7679,4 +13708,662 @@ fn process_relayer_settle_prediction(
          args.user_wallet, args.market_id, args.locked_amount, args.settlement_amount);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Owned backing storage for an AccountInfo used in processor tests
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        is_signer: bool,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey, owner: Pubkey, data: Vec<u8>, is_signer: bool) -> Self {
+            Self { key, owner, lamports: 1_000_000_000, data, is_signer }
+        }
+
+        fn signer(key: Pubkey) -> Self {
+            Self::new(key, solana_program::system_program::id(), vec![], true)
+        }
+
+        fn readonly(key: Pubkey) -> Self {
+            Self::new(key, solana_program::system_program::id(), vec![], false)
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                self.is_signer,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    fn config_account(program_id: &Pubkey, config: &PredictionMarketConfig) -> TestAccount {
+        let (config_pda, _) = Pubkey::find_program_address(&[PM_CONFIG_SEED], program_id);
+        let mut data = Vec::with_capacity(PredictionMarketConfig::SIZE);
+        config.serialize(&mut data).unwrap();
+        data.resize(PredictionMarketConfig::SIZE, 0);
+        TestAccount::new(config_pda, *program_id, data, false)
+    }
+
+    fn test_config(admin: Pubkey) -> PredictionMarketConfig {
+        PredictionMarketConfig::new(
+            admin,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            255,
+        )
+    }
+
+    fn run(
+        program_id: &Pubkey,
+        accounts: &mut [&mut TestAccount],
+        ix: PredictionMarketInstruction,
+    ) -> ProgramResult {
+        let data = borsh::to_vec(&ix).unwrap();
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(|a| a.info()).collect();
+        process_instruction(program_id, &infos, &data)
+    }
+
+    fn test_market(market_id: u64, market_type: MarketType) -> Market {
+        Market {
+            discriminator: MARKET_DISCRIMINATOR,
+            market_id,
+            market_type,
+            num_outcomes: 2,
+            creator: Pubkey::new_unique(),
+            question_hash: [1u8; 32],
+            resolution_spec_hash: [2u8; 32],
+            yes_mint: Pubkey::default(),
+            no_mint: Pubkey::default(),
+            market_vault: Pubkey::default(),
+            status: MarketStatus::Active,
+            review_status: ReviewStatus::None,
+            resolution_time: 2_000_000_000,
+            finalization_deadline: 2_100_000_000,
+            final_result: None,
+            winning_outcome_index: None,
+            created_at: 1000,
+            updated_at: 1000,
+            total_minted: 0,
+            total_volume_e6: 0,
+            open_interest: 0,
+            creator_fee_bps: 0,
+            next_order_id: 1,
+            bump: 255,
+            max_tvl_e6: 0,
+            total_realized_pnl_e6: 0,
+            challenge_window_secs: 0,
+            category: MarketCategory::Other,
+            tags_hash: [0u8; 32],
+            pause_reason: 0,
+            reserved: [0u8; 2],
+        }
+    }
+
+    /// Serialize a program-owned PDA account padded to `size`
+    fn pda_account<T: BorshSerialize>(
+        program_id: &Pubkey,
+        seeds: &[&[u8]],
+        value: &T,
+        size: usize,
+    ) -> TestAccount {
+        let (pda, _) = Pubkey::find_program_address(seeds, program_id);
+        let mut data = borsh::to_vec(value).unwrap();
+        data.resize(size, 0);
+        TestAccount::new(pda, *program_id, data, false)
+    }
+
+    fn market_account(program_id: &Pubkey, market: &Market) -> TestAccount {
+        pda_account(program_id, &[MARKET_SEED, &market.market_id.to_le_bytes()], market, Market::SIZE)
+    }
+
+    fn test_order(market_id: u64, order_id: u64, side: crate::state::OrderSide, outcome: Outcome, outcome_index: u8) -> Order {
+        Order {
+            discriminator: ORDER_DISCRIMINATOR,
+            order_id,
+            market_id,
+            owner: Pubkey::new_unique(),
+            side,
+            outcome,
+            outcome_index,
+            price: 500_000,
+            amount: 100,
+            filled_amount: 0,
+            status: OrderStatus::Open,
+            order_type: crate::state::OrderType::GTC,
+            expiration_time: None,
+            created_at: 1000,
+            updated_at: 1000,
+            bump: 255,
+            escrow_token_account: None,
+            locks_released: false,
+            margin_locked: false,
+            locked_margin_e6: 0,
+            reduce_only: false,
+            reserved: [0u8; 19],
+        }
+    }
+
+    fn order_account(program_id: &Pubkey, order: &Order) -> TestAccount {
+        pda_account(
+            program_id,
+            &[ORDER_SEED, &order.market_id.to_le_bytes(), &order.order_id.to_le_bytes()],
+            order,
+            Order::SIZE,
+        )
+    }
+
+    fn load_config(account: &TestAccount) -> PredictionMarketConfig {
+        deserialize_account::<PredictionMarketConfig>(&account.data).unwrap()
+    }
+
+    fn custom(e: PredictionMarketError) -> ProgramError {
+        e.into()
+    }
+
+    #[test]
+    fn test_update_admin() {
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let new_admin = Pubkey::new_unique();
+        let mut config = config_account(&program_id, &test_config(admin));
+
+        // Unauthorized signer is rejected
+        let mut attacker = TestAccount::signer(Pubkey::new_unique());
+        let result = run(
+            &program_id,
+            &mut [&mut attacker, &mut config],
+            PredictionMarketInstruction::UpdateAdmin(UpdateAdminArgs { new_admin }),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::Unauthorized)));
+        assert_eq!(load_config(&config).admin, admin);
+
+        // No-op update is rejected
+        let mut admin_account = TestAccount::signer(admin);
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::UpdateAdmin(UpdateAdminArgs { new_admin: admin }),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::AdminUnchanged)));
+
+        // Authorized path updates the field
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::UpdateAdmin(UpdateAdminArgs { new_admin }),
+        );
+        assert!(result.is_ok());
+        assert_eq!(load_config(&config).admin, new_admin);
+    }
+
+    #[test]
+    fn test_two_step_admin_transfer() {
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let new_admin = Pubkey::new_unique();
+        let mut config = config_account(&program_id, &test_config(admin));
+        let mut admin_account = TestAccount::signer(admin);
+        let mut new_admin_account = TestAccount::signer(new_admin);
+
+        // Accept without a pending transfer fails
+        let result = run(
+            &program_id,
+            &mut [&mut new_admin_account, &mut config],
+            PredictionMarketInstruction::AcceptAdminTransfer,
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::NoPendingAdminTransfer)));
+
+        // Initiate: only the current admin may nominate
+        let result = run(
+            &program_id,
+            &mut [&mut new_admin_account, &mut config],
+            PredictionMarketInstruction::InitiateAdminTransfer(InitiateAdminTransferArgs { new_admin }),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::Unauthorized)));
+
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::InitiateAdminTransfer(InitiateAdminTransferArgs { new_admin }),
+        );
+        assert!(result.is_ok());
+        let state = load_config(&config);
+        assert_eq!(state.admin, admin);
+        assert_eq!(state.pending_admin_transfer(), Some(new_admin));
+
+        // Cancel: clears pending, admin unchanged
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::CancelAdminTransfer,
+        );
+        assert!(result.is_ok());
+        let state = load_config(&config);
+        assert_eq!(state.admin, admin);
+        assert_eq!(state.pending_admin_transfer(), None);
+
+        // Re-initiate, then accept: wrong signer rejected
+        run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::InitiateAdminTransfer(InitiateAdminTransferArgs { new_admin }),
+        ).unwrap();
+        let mut stranger = TestAccount::signer(Pubkey::new_unique());
+        let result = run(
+            &program_id,
+            &mut [&mut stranger, &mut config],
+            PredictionMarketInstruction::AcceptAdminTransfer,
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::Unauthorized)));
+
+        // Pending admin accepts: promoted and pending cleared
+        let result = run(
+            &program_id,
+            &mut [&mut new_admin_account, &mut config],
+            PredictionMarketInstruction::AcceptAdminTransfer,
+        );
+        assert!(result.is_ok());
+        let state = load_config(&config);
+        assert_eq!(state.admin, new_admin);
+        assert_eq!(state.pending_admin_transfer(), None);
+    }
+
+    #[test]
+    fn test_reinitialize_config_reset_guard() {
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let mut state = test_config(admin);
+        state.next_market_id = 4;
+        state.total_markets = 3;
+        let mut config = config_account(&program_id, &state);
+        let mut admin_account = TestAccount::signer(admin);
+        let mut usdc_mint = TestAccount::readonly(state.usdc_mint);
+        let mut vault_program = TestAccount::readonly(state.vault_program);
+        let mut fund_program = TestAccount::readonly(state.fund_program);
+        let reinit = |reset_counters| PredictionMarketInstruction::ReinitializeConfig(ReinitializeConfigArgs {
+            oracle_admin: Pubkey::new_unique(),
+            challenge_window_secs: 3600,
+            proposer_bond_e6: 1_000_000,
+            reset_counters,
+        });
+
+        // Reset with existing markets is rejected and counters are untouched
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config, &mut usdc_mint, &mut vault_program, &mut fund_program],
+            reinit(true),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::CannotResetWithActiveMarkets)));
+        assert_eq!(load_config(&config).next_market_id, 4);
+
+        // Reinitialize without reset preserves counters
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config, &mut usdc_mint, &mut vault_program, &mut fund_program],
+            reinit(false),
+        );
+        assert!(result.is_ok());
+        let loaded = load_config(&config);
+        assert_eq!(loaded.next_market_id, 4);
+        assert_eq!(loaded.total_markets, 3);
+        assert_eq!(loaded.challenge_window_secs, 3600);
+    }
+
+    #[test]
+    fn test_set_paused_blocks_place_order() {
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let mut config = config_account(&program_id, &test_config(admin));
+        let mut admin_account = TestAccount::signer(admin);
+
+        // Non-admin cannot pause
+        let mut stranger = TestAccount::signer(Pubkey::new_unique());
+        let result = run(
+            &program_id,
+            &mut [&mut stranger, &mut config],
+            PredictionMarketInstruction::SetPaused(SetPausedArgs { paused: true }),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::Unauthorized)));
+
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::SetPaused(SetPausedArgs { paused: true }),
+        );
+        assert!(result.is_ok());
+        assert!(load_config(&config).is_paused);
+
+        // Idempotent: setting the same value succeeds
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::SetPaused(SetPausedArgs { paused: true }),
+        );
+        assert!(result.is_ok());
+        assert!(load_config(&config).is_paused);
+
+        // Trading is rejected while paused
+        let mut user = TestAccount::signer(Pubkey::new_unique());
+        let mut market = TestAccount::readonly(Pubkey::new_unique());
+        let mut order = TestAccount::readonly(Pubkey::new_unique());
+        let mut system_program = TestAccount::readonly(solana_program::system_program::id());
+        let result = run(
+            &program_id,
+            &mut [&mut user, &mut config, &mut market, &mut order, &mut system_program],
+            PredictionMarketInstruction::PlaceOrder(PlaceOrderArgs {
+                market_id: 1,
+                side: crate::state::OrderSide::Buy,
+                outcome: Outcome::Yes,
+                price: 500_000,
+                amount: 100,
+                order_type: crate::state::OrderType::GTC,
+                expiration_time: None,
+                reduce_only: false,
+            }),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::ProgramPaused)));
+
+        // Unpause
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::SetPaused(SetPausedArgs { paused: false }),
+        );
+        assert!(result.is_ok());
+        assert!(!load_config(&config).is_paused);
+    }
+
+    #[test]
+    fn test_place_order_untradeable_market_errors() {
+        let program_id = crate::id();
+        let mut config = config_account(&program_id, &test_config(Pubkey::new_unique()));
+        let place_order = PredictionMarketInstruction::PlaceOrder(PlaceOrderArgs {
+            market_id: 1,
+            side: crate::state::OrderSide::Buy,
+            outcome: Outcome::Yes,
+            price: 500_000,
+            amount: 100,
+            order_type: crate::state::OrderType::GTC,
+            expiration_time: None,
+            reduce_only: false,
+        });
+
+        for (status, expected) in [
+            (MarketStatus::Pending, PredictionMarketError::MarketNotActivated),
+            (MarketStatus::Paused, PredictionMarketError::MarketPaused),
+            (MarketStatus::Resolved, PredictionMarketError::MarketAlreadyResolved),
+        ] {
+            let mut state = test_market(1, MarketType::Binary);
+            state.status = status;
+            let mut market = market_account(&program_id, &state);
+            let mut user = TestAccount::signer(Pubkey::new_unique());
+            let mut order = TestAccount::readonly(Pubkey::new_unique());
+            let mut system_program = TestAccount::readonly(solana_program::system_program::id());
+            let result = run(
+                &program_id,
+                &mut [&mut user, &mut config, &mut market, &mut order, &mut system_program],
+                place_order.clone(),
+            );
+            assert_eq!(result, Err(custom(expected)), "status {:?}", status);
+        }
+    }
+
+    #[test]
+    fn test_update_oracle_config() {
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let mut config = config_account(&program_id, &test_config(admin));
+        let mut admin_account = TestAccount::signer(admin);
+        let update = |challenge_window_secs, proposer_bond_e6| {
+            PredictionMarketInstruction::UpdateOracleConfig(UpdateOracleConfigArgs {
+                challenge_window_secs,
+                proposer_bond_e6,
+            })
+        };
+
+        // Only window
+        run(&program_id, &mut [&mut admin_account, &mut config], update(Some(7200), None)).unwrap();
+        let state = load_config(&config);
+        assert_eq!(state.challenge_window_secs, 7200);
+        assert_eq!(state.proposer_bond_e6, crate::state::DEFAULT_PROPOSER_BOND);
+
+        // Only bond
+        run(&program_id, &mut [&mut admin_account, &mut config], update(None, Some(50_000_000))).unwrap();
+        let state = load_config(&config);
+        assert_eq!(state.challenge_window_secs, 7200);
+        assert_eq!(state.proposer_bond_e6, 50_000_000);
+
+        // Both
+        run(&program_id, &mut [&mut admin_account, &mut config], update(Some(86_400 * 3), Some(1_000_000))).unwrap();
+        let state = load_config(&config);
+        assert_eq!(state.challenge_window_secs, 86_400 * 3);
+        assert_eq!(state.proposer_bond_e6, 1_000_000);
+
+        // 10-second window is rejected and nothing is applied
+        let result = run(&program_id, &mut [&mut admin_account, &mut config], update(Some(10), Some(2_000_000)));
+        assert_eq!(result, Err(custom(PredictionMarketError::InvalidChallengeWindow)));
+        let state = load_config(&config);
+        assert_eq!(state.challenge_window_secs, 86_400 * 3);
+        assert_eq!(state.proposer_bond_e6, 1_000_000);
+
+        // Zero bond and non-admin are rejected
+        let result = run(&program_id, &mut [&mut admin_account, &mut config], update(None, Some(0)));
+        assert_eq!(result, Err(custom(PredictionMarketError::InvalidProposerBond)));
+        let mut stranger = TestAccount::signer(Pubkey::new_unique());
+        let result = run(&program_id, &mut [&mut stranger, &mut config], update(Some(7200), None));
+        assert_eq!(result, Err(custom(PredictionMarketError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_set_max_price_sum_deviation() {
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let mut config = config_account(&program_id, &test_config(admin));
+        let mut admin_account = TestAccount::signer(admin);
+        let set = |max_deviation| {
+            PredictionMarketInstruction::SetMaxPriceSumDeviation(SetMaxPriceSumDeviationArgs {
+                max_deviation,
+            })
+        };
+        assert_eq!(load_config(&config).max_price_sum_deviation, 0);
+
+        // Upper bound is accepted
+        run(&program_id, &mut [&mut admin_account, &mut config], set(crate::state::MAX_PRICE_SUM_DEVIATION)).unwrap();
+        assert_eq!(load_config(&config).max_price_sum_deviation, crate::state::MAX_PRICE_SUM_DEVIATION);
+
+        // Above the bound is rejected and nothing is applied
+        let result = run(&program_id, &mut [&mut admin_account, &mut config], set(crate::state::MAX_PRICE_SUM_DEVIATION + 1));
+        assert_eq!(result, Err(custom(PredictionMarketError::PriceSumDeviationTooHigh)));
+        assert_eq!(load_config(&config).max_price_sum_deviation, crate::state::MAX_PRICE_SUM_DEVIATION);
+
+        // Non-admin is rejected
+        let mut stranger = TestAccount::signer(Pubkey::new_unique());
+        let result = run(&program_id, &mut [&mut stranger, &mut config], set(0));
+        assert_eq!(result, Err(custom(PredictionMarketError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_update_oracle_admin() {
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let new_oracle_admin = Pubkey::new_unique();
+        let mut config = config_account(&program_id, &test_config(admin));
+        let mut admin_account = TestAccount::signer(admin);
+        assert!(verify_relayer(&program_id, &load_config(&config), &new_oracle_admin, &[]).is_err());
+
+        // Default pubkey is rejected
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::UpdateOracleAdmin(UpdateOracleAdminArgs {
+                new_oracle_admin: Pubkey::default(),
+            }),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::InvalidArgument)));
+
+        let result = run(
+            &program_id,
+            &mut [&mut admin_account, &mut config],
+            PredictionMarketInstruction::UpdateOracleAdmin(UpdateOracleAdminArgs { new_oracle_admin }),
+        );
+        assert!(result.is_ok());
+
+        // New oracle admin is now trusted as a relayer
+        let state = load_config(&config);
+        assert_eq!(state.oracle_admin, new_oracle_admin);
+        assert!(verify_relayer(&program_id, &state, &new_oracle_admin, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_verify_relayer_authorized_callers() {
+        use crate::state::{AuthorizedCallers, AUTHORIZED_CALLERS_SEED};
+
+        let program_id = crate::id();
+        let config = test_config(Pubkey::new_unique());
+        let keeper = Pubkey::new_unique();
+        let mut registry = AuthorizedCallers::new(255, 1000);
+        registry.add_caller(keeper, 1000).unwrap();
+        let mut callers = pda_account(&program_id, &[AUTHORIZED_CALLERS_SEED], &registry, AuthorizedCallers::SIZE);
+        let mut other = TestAccount::readonly(Pubkey::new_unique());
+
+        // Without the registry only admin/oracle_admin are accepted
+        assert!(verify_relayer(&program_id, &config, &config.admin, &[]).is_ok());
+        assert!(verify_relayer(&program_id, &config, &keeper, &[]).is_err());
+
+        // Registry is found regardless of its position in the account list
+        let infos = [other.info(), callers.info()];
+        assert!(verify_relayer(&program_id, &config, &keeper, &infos).is_ok());
+        assert!(verify_relayer(&program_id, &config, &Pubkey::new_unique(), &infos).is_err());
+        drop(infos);
+
+        // A registry not owned by the program is ignored
+        callers.owner = Pubkey::new_unique();
+        let infos = [callers.info()];
+        assert!(verify_relayer(&program_id, &config, &keeper, &infos).is_err());
+    }
+
+    #[test]
+    fn test_execute_trade_v2_rejects_mismatched_pm_user_accounts() {
+        use crate::state::{OrderSide, VAULT_PM_USER_SEED};
+
+        let program_id = crate::id();
+        let admin = Pubkey::new_unique();
+        let config_state = test_config(admin);
+        let vault_program = config_state.vault_program;
+        let market_state = test_market(1, MarketType::Binary);
+        let buy_order_state = test_order(1, 1, OrderSide::Buy, Outcome::Yes, 0);
+        let sell_order_state = test_order(1, 2, OrderSide::Sell, Outcome::Yes, 0);
+        let pm_user = |owner: &Pubkey| {
+            TestAccount::readonly(
+                Pubkey::find_program_address(&[VAULT_PM_USER_SEED, owner.as_ref()], &vault_program).0,
+            )
+        };
+
+        let execute = |buyer_pm: &mut TestAccount, seller_pm: &mut TestAccount, vault: &mut TestAccount| {
+            let mut relayer = TestAccount::signer(admin);
+            let mut config = config_account(&program_id, &config_state);
+            let mut market = market_account(&program_id, &market_state);
+            let mut buy_order = order_account(&program_id, &buy_order_state);
+            let mut sell_order = order_account(&program_id, &sell_order_state);
+            let mut buyer_position = TestAccount::readonly(Pubkey::new_unique());
+            let mut seller_position = TestAccount::readonly(Pubkey::new_unique());
+            let mut buyer_vault = TestAccount::readonly(Pubkey::new_unique());
+            let mut seller_vault = TestAccount::readonly(Pubkey::new_unique());
+            let mut vault_config = TestAccount::readonly(Pubkey::new_unique());
+            let mut system = TestAccount::readonly(solana_program::system_program::id());
+            let mut buyer_wallet = TestAccount::readonly(buy_order_state.owner);
+            let mut seller_wallet = TestAccount::readonly(sell_order_state.owner);
+            run(
+                &program_id,
+                &mut [
+                    &mut relayer, &mut config, &mut market, &mut buy_order, &mut sell_order,
+                    &mut buyer_position, &mut seller_position,
+                    &mut buyer_vault, buyer_pm, &mut seller_vault, seller_pm,
+                    &mut vault_config, vault, &mut system, &mut buyer_wallet, &mut seller_wallet,
+                ],
+                PredictionMarketInstruction::ExecuteTradeV2(ExecuteTradeArgs {
+                    market_id: 1,
+                    taker_order_id: 1,
+                    maker_order_id: 2,
+                    amount: 10,
+                    price: 500_000,
+                }),
+            )
+        };
+
+        // Buyer's PM user account swapped for the seller's
+        let result = execute(
+            &mut pm_user(&sell_order_state.owner),
+            &mut pm_user(&sell_order_state.owner),
+            &mut TestAccount::readonly(vault_program),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::InvalidVaultAccount)));
+
+        // Seller's PM user account belongs to someone else
+        let result = execute(
+            &mut pm_user(&buy_order_state.owner),
+            &mut pm_user(&Pubkey::new_unique()),
+            &mut TestAccount::readonly(vault_program),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::InvalidVaultAccount)));
+
+        // PDAs derived under a substitute vault program are rejected up front
+        let result = execute(
+            &mut pm_user(&buy_order_state.owner),
+            &mut pm_user(&sell_order_state.owner),
+            &mut TestAccount::readonly(Pubkey::new_unique()),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::VaultProgramMismatch)));
+    }
+
+    #[test]
+    fn test_sync_order_outcome_index() {
+        let program_id = crate::id();
+        let mut caller = TestAccount::signer(Pubkey::new_unique());
+        let mut market = market_account(&program_id, &test_market(5, MarketType::Binary));
+
+        // Legacy NO order with outcome_index left at 0
+        let legacy = test_order(5, 9, crate::state::OrderSide::Buy, Outcome::No, 0);
+        let mut order = order_account(&program_id, &legacy);
+        let result = run(
+            &program_id,
+            &mut [&mut caller, &mut market, &mut order],
+            PredictionMarketInstruction::SyncOrderOutcomeIndex(SyncOrderOutcomeIndexArgs {
+                market_id: 5,
+                order_id: 9,
+            }),
+        );
+        assert!(result.is_ok());
+        let synced = deserialize_account::<Order>(&order.data).unwrap();
+        assert_eq!(synced.outcome_index, 1);
+        assert_eq!(synced.outcome, Outcome::No);
+
+        // Multi-outcome markets are rejected
+        let mut multi = market_account(&program_id, &test_market(5, MarketType::MultiOutcome));
+        let result = run(
+            &program_id,
+            &mut [&mut caller, &mut multi, &mut order],
+            PredictionMarketInstruction::SyncOrderOutcomeIndex(SyncOrderOutcomeIndexArgs {
+                market_id: 5,
+                order_id: 9,
+            }),
+        );
+        assert_eq!(result, Err(custom(PredictionMarketError::InvalidMarketType)));
+    }
+}
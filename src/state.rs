@@ -5,6 +5,8 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
+use crate::error::PredictionMarketError;
+
 // ============================================================================
 // Discriminators
 // ============================================================================
@@ -15,6 +17,10 @@ pub const ORDER_DISCRIMINATOR: u64 = 0x4F524445525F5F5F; // "ORDER___"
 pub const POSITION_DISCRIMINATOR: u64 = 0x504F534954494F4E; // "POSITION"
 pub const ORACLE_PROPOSAL_DISCRIMINATOR: u64 = 0x4F5241434C455F50; // "ORACLE_P"
 pub const MULTI_OUTCOME_POSITION_DISCRIMINATOR: u64 = 0x4D554C54494F5054; // "MULTIOPT"
+pub const TRADER_STATS_DISCRIMINATOR: u64 = 0x5452414445525354; // "TRADERST"
+pub const USER_META_DISCRIMINATOR: u64 = 0x555345524D455441; // "USERMETA"
+pub const BOOK_TOP_DISCRIMINATOR: u64 = 0x424F4F4B544F505F; // "BOOKTOP_"
+pub const CHALLENGER_RECORD_DISCRIMINATOR: u64 = 0x4348414C4C524543; // "CHALLREC"
 
 // ============================================================================
 // PDA Seeds
@@ -32,6 +38,13 @@ pub const NO_MINT_SEED: &[u8] = b"no_mint";
 pub const ORACLE_PROPOSAL_SEED: &[u8] = b"oracle_proposal";
 pub const OUTCOME_MINT_SEED: &[u8] = b"outcome_mint"; // For multi-outcome markets
 pub const AUTHORIZED_CALLERS_SEED: &[u8] = b"authorized_callers"; // For matching engine callers
+pub const TRADER_STATS_SEED: &[u8] = b"trader_stats";
+pub const USER_META_SEED: &[u8] = b"user_meta";
+pub const BOOK_TOP_SEED: &[u8] = b"book_top";
+pub const CHALLENGER_RECORD_SEED: &[u8] = b"challenger_record";
+/// Vault Program PMUserAccount PDA: ["prediction_market_user", wallet] under vault_program.
+/// Must stay in sync with the Vault Program.
+pub const VAULT_PM_USER_SEED: &[u8] = b"prediction_market_user";
 
 // ============================================================================
 // Constants
@@ -45,6 +58,20 @@ pub const MAX_OUTCOMES: usize = 32;
 /// Formula: 6 fixed accounts + 3 * num_outcomes = 54 accounts for 16 outcomes
 pub const MAX_OUTCOMES_FOR_MATCH: u8 = 16;
 
+/// Maximum users per RelayerBatchClaimWinningsV2
+/// 2 accounts per user (Position + PMUserAccount) plus 5 fixed accounts
+pub const MAX_BATCH_CLAIM_USERS: usize = 8;
+
+/// Maximum orders per RelayerBatchCancelOrdersV2
+/// 4 accounts per order (Order, Position, UserAccount, PMUserAccount) plus
+/// 5 fixed accounts
+pub const MAX_BATCH_CANCEL_ORDERS: usize = 12;
+
+/// Maximum maker orders per ExecuteTradeMultiV2
+/// 4 accounts per maker (Sell Order, Position, PMUserAccount, Wallet) plus
+/// 11 fixed accounts and the Fund PMUserAccount
+pub const MAX_TRADE_MAKERS: usize = 8;
+
 /// Maximum length of market question (bytes)
 pub const MAX_QUESTION_LEN: usize = 256;
 
@@ -54,6 +81,31 @@ pub const MAX_RESOLUTION_SPEC_LEN: usize = 512;
 /// Price precision (1 USDC = 1_000_000)
 pub const PRICE_PRECISION: u64 = 1_000_000;
 
+/// Share precision (1 share = 1_000_000 base units, same decimals as USDC)
+/// One complete set (1 YES + 1 NO share) is backed by exactly 1 USDC.
+pub const SHARE_PRECISION: u64 = 1_000_000;
+
+/// Upper bound for config.max_price_sum_deviation (0.05 = 5¢)
+pub const MAX_PRICE_SUM_DEVIATION: u64 = 50_000;
+
+/// Upper bound for config.trade_fee_bps (5%)
+pub const MAX_TRADE_FEE_BPS: u16 = 500;
+
+/// Upper bound for config.mint_fee_bps (5%)
+pub const MAX_MINT_FEE_BPS: u16 = 500;
+
+/// Upper bound for each FeeSchedule field (10%)
+pub const MAX_FEE_SCHEDULE_BPS: u16 = 1_000;
+
+/// Number of volume tiers in ConfigExtension.fee_tiers
+pub const FEE_TIER_COUNT: usize = 2;
+
+/// Upper bound for FeeTier.discount_bps (waives the whole taker fee)
+pub const MAX_FEE_DISCOUNT_BPS: u16 = 10_000;
+
+/// Upper bound for ConfigExtension.referral_share_bps (the whole protocol fee)
+pub const MAX_REFERRAL_SHARE_BPS: u16 = 10_000;
+
 /// Minimum price (0.01 = 1%)
 pub const MIN_PRICE: u64 = 10_000;
 
@@ -63,6 +115,29 @@ pub const MAX_PRICE: u64 = 990_000;
 /// Default challenge window (24 hours)
 pub const DEFAULT_CHALLENGE_WINDOW_SECS: i64 = 24 * 60 * 60;
 
+/// Minimum configurable challenge window (1 hour)
+pub const MIN_CHALLENGE_WINDOW_SECS: i64 = 60 * 60;
+
+/// Maximum configurable challenge window (30 days)
+pub const MAX_CHALLENGE_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Maximum single extension of a market's schedule (90 days)
+pub const MAX_RESOLUTION_EXTENSION_SECS: i64 = 90 * 24 * 60 * 60;
+
+/// How long after cancellation ReopenMarket may still revert it (24 hours)
+pub const REOPEN_GRACE_PERIOD_SECS: i64 = 24 * 60 * 60;
+
+/// How long the committee has to resolve a dispute before anyone may
+/// expire it with ExpireDisputedProposal (14 days)
+pub const DISPUTE_RESOLUTION_PERIOD_SECS: i64 = 14 * 24 * 60 * 60;
+
+/// Most bonded challengers a dispute accepts, the first one included; each
+/// one after the first posts twice the bond of the previous
+pub const MAX_DISPUTE_CHALLENGERS: u8 = 4;
+
+/// Oldest Pyth price ResolveFromPyth accepts (60 seconds)
+pub const PYTH_MAX_PRICE_AGE_SECS: i64 = 60;
+
 /// Default proposer bond (100 USDC)
 pub const DEFAULT_PROPOSER_BOND: u64 = 100_000_000;
 
@@ -109,6 +184,11 @@ pub enum MarketStatus {
     AwaitingResult = 6,
     /// Result has been proposed, in challenge window
     /// Task 4.2.4: Added for LLM Oracle flow
+    /// 
+    /// This is also the pending-resolution state of an on-chain ProposeResult:
+    /// the OracleProposal is open and the bond locked until FinalizeResult.
+    /// No separate PendingResolution variant exists, so every oracle flow
+    /// shares the same challenge/finalize status checks.
     ResultProposed = 7,
     /// Result was challenged, awaiting dispute resolution
     /// Task 4.2.5: Added for LLM Oracle flow
@@ -181,6 +261,19 @@ impl Default for ReviewStatus {
     }
 }
 
+/// Market category, for client-side filtering
+///
+/// `Other` is variant 0 so markets created before categories existed read
+/// back as `Other`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketCategory {
+    Other = 0,
+    Sports = 1,
+    Politics = 2,
+    Crypto = 3,
+    Entertainment = 4,
+}
+
 /// Order side (buy/sell)
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OrderSide {
@@ -227,6 +320,12 @@ pub enum OrderType {
     IOC = 2,
     /// Fill Or Kill - fill completely or cancel entirely
     FOK = 3,
+    /// Market - filled completely in the transaction that places it, at any
+    /// price up to the order price (the slippage cap)
+    Market = 4,
+    /// Post Only - rests like GTC, rejected at placement if it would cross
+    /// the best opposing order
+    PostOnly = 5,
 }
 
 impl Default for OrderType {
@@ -522,7 +621,7 @@ pub struct PredictionMarketConfig {
     /// Currently active markets
     pub active_markets: u64,
     
-    /// Total trading volume (e6)
+    /// Total trading volume (USDC notional, e6)
     pub total_volume_e6: i64,
     
     /// Total minted complete sets
@@ -540,9 +639,50 @@ pub struct PredictionMarketConfig {
     /// PDA bump
     pub bump: u8,
     
+    /// Pending admin for two-step handover (set by InitiateAdminTransfer,
+    /// promoted by AcceptAdminTransfer); `Pubkey::default()` when none.
+    /// A plain Pubkey so the fields after it keep fixed offsets.
+    pub pending_admin: Pubkey,
+    
+    /// Tolerance (e6) for matched price sums: mints accept
+    /// `1.0 - dev <= sum <= 1.0`, burns accept `1.0 <= sum <= 1.0 + dev`.
+    /// 0 (the default for existing configs) requires an exact 1.0 sum.
+    pub max_price_sum_deviation: u64,
+    
+    /// Trading fee (bps) charged on each side of an ExecuteTradeV2 fill and
+    /// credited to the Fund Program's PMUserAccount. 0 disables the fee.
+    pub trade_fee_bps: u16,
+    
+    /// Rebate (bps of notional) paid to the resting maker of an
+    /// ExecuteTradeV2 fill out of the collected fee. Never above trade_fee_bps.
+    pub maker_rebate_bps: u16,
+    
+    /// When set, complete sets can only be created by the explicit mint
+    /// instructions and MatchMintV2/MatchMintMultiV2 are rejected.
+    /// Stored inverted so existing configs (zeroed reserved bytes) keep
+    /// match-minting enabled.
+    pub match_mint_disabled: bool,
+    
+    /// Smallest amount an order may be placed with or left with after a
+    /// fill; a smaller (dust) remainder is cancelled and returned. 0 disables.
+    pub min_order_size: u64,
+    
+    /// Smallest notional (amount × price, e6) an order may be placed with.
+    /// 0 disables.
+    pub min_notional_e6: u64,
+    
+    /// Fee (bps of the collateral) charged on top of a MintCompleteSet and
+    /// sent to the Fund Program's USDC account. 0 disables the fee.
+    /// 
+    /// Takes reserved bytes (after 32 for pending_admin, 8 for
+    /// max_price_sum_deviation, 2 each for trade_fee_bps and
+    /// maker_rebate_bps, 1 for match_mint_disabled, 8 each for
+    /// min_order_size and min_notional_e6).
+    pub mint_fee_bps: u16,
+    
     /// Reserved for future use
-    /// Note: 64 bytes to match existing on-chain data size (290 total)
-    pub reserved: [u8; 64],
+    /// Note: total kept at 290 bytes to match existing on-chain data size
+    pub reserved: [u8; 1],
 }
 
 impl PredictionMarketConfig {
@@ -561,7 +701,15 @@ impl PredictionMarketConfig {
         + 8   // proposer_bond_e6
         + 1   // is_paused
         + 1   // bump
-        + 64; // reserved (= 290 total)
+        + 32  // pending_admin
+        + 8   // max_price_sum_deviation
+        + 2   // trade_fee_bps
+        + 2   // maker_rebate_bps
+        + 1   // match_mint_disabled
+        + 8   // min_order_size
+        + 8   // min_notional_e6
+        + 2   // mint_fee_bps
+        + 1;  // reserved (= 290 total)
     
     /// PDA seeds
     pub fn seeds() -> Vec<Vec<u8>> {
@@ -593,9 +741,258 @@ impl PredictionMarketConfig {
             proposer_bond_e6: DEFAULT_PROPOSER_BOND,
             is_paused: false,
             bump,
-            reserved: [0u8; 64],
+            pending_admin: Pubkey::default(),
+            max_price_sum_deviation: 0,
+            trade_fee_bps: 0,
+            maker_rebate_bps: 0,
+            match_mint_disabled: false,
+            min_order_size: 0,
+            min_notional_e6: 0,
+            mint_fee_bps: 0,
+            reserved: [0u8; 1],
+        }
+    }
+    
+    /// Admin nominated by InitiateAdminTransfer, if a handover is pending
+    pub fn pending_admin_transfer(&self) -> Option<Pubkey> {
+        (self.pending_admin != Pubkey::default()).then_some(self.pending_admin)
+    }
+    
+    /// Trading fee owed by each side of a fill costing `trade_cost`
+    pub fn trade_fee_on(&self, trade_cost: u64) -> Result<u64, PredictionMarketError> {
+        bps_of(trade_cost, self.trade_fee_bps)
+    }
+    
+    /// Minting fee owed on top of `collateral` by a MintCompleteSet
+    pub fn mint_fee_on(&self, collateral: u64) -> Result<u64, PredictionMarketError> {
+        bps_of(collateral, self.mint_fee_bps)
+    }
+    
+    /// Whether MatchMintV2/MatchMintMultiV2 may create complete sets
+    pub fn allow_match_mint(&self) -> bool {
+        !self.match_mint_disabled
+    }
+    
+    /// Rebate owed to the maker of a fill costing `trade_cost`
+    pub fn maker_rebate_on(&self, trade_cost: u64) -> Result<u64, PredictionMarketError> {
+        bps_of(trade_cost, self.maker_rebate_bps)
+    }
+    
+    /// All fees currently charged; the redeem fee is read from `extension`
+    pub fn fee_schedule(&self, extension: &ConfigExtension) -> FeeSchedule {
+        FeeSchedule {
+            mint_bps: self.mint_fee_bps,
+            redeem_bps: extension.redeem_fee_bps,
+            trade_bps: self.trade_fee_bps,
+            maker_rebate_bps: self.maker_rebate_bps,
+        }
+    }
+    
+    /// Replace every fee with `schedule` (validated by the caller)
+    pub fn set_fee_schedule(&mut self, extension: &mut ConfigExtension, schedule: &FeeSchedule) {
+        self.mint_fee_bps = schedule.mint_bps;
+        extension.redeem_fee_bps = schedule.redeem_bps;
+        self.trade_fee_bps = schedule.trade_bps;
+        self.maker_rebate_bps = schedule.maker_rebate_bps;
+    }
+    
+    /// Reject a new or amended order below min_order_size or min_notional_e6
+    pub fn check_order_minimum(&self, amount: u64, price: u64) -> Result<(), PredictionMarketError> {
+        if amount < self.min_order_size || cost_e6(amount, price)? < self.min_notional_e6 {
+            return Err(PredictionMarketError::OrderBelowMinimum);
+        }
+        Ok(())
+    }
+}
+
+/// Config settings stored past the fixed `PredictionMarketConfig` layout
+/// 
+/// The config's reserved bytes are used up and its 290-byte layout is kept
+/// for existing deployments, so later settings live in this tail. The
+/// account is grown to hold it by the first setter that writes it; a config
+/// without the tail reads as all zeros.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigExtension {
+    /// USDC (e6) locked from the creator by CreateMarket and returned once
+    /// the market resolves. Forfeited to the Fund if the market is cancelled
+    /// as invalid or for regulatory reasons. 0 disables the bond.
+    pub market_creation_bond_e6: u64,
+    
+    /// Fee (bps of the redeemed USDC) withheld from a RedeemCompleteSet
+    /// payout and sent to the Fund Program's USDC account. 0 disables the fee.
+    pub redeem_fee_bps: u16,
+    
+    /// Taker fee discounts by cumulative TraderStats volume. A tier with a
+    /// zero discount is unused; all zero disables the discounts.
+    pub fee_tiers: [FeeTier; FEE_TIER_COUNT],
+    
+    /// Share (bps) of the protocol fee a referred trader pays on an
+    /// ExecuteTradeV2 fill that is credited to their referrer instead of
+    /// the Fund. 0 disables referral rewards. Takes the last reserved bytes.
+    pub referral_share_bps: u16,
+}
+
+impl ConfigExtension {
+    /// Offset of the tail in the config account
+    pub const OFFSET: usize = PredictionMarketConfig::SIZE;
+    
+    pub const SIZE: usize = 8 // market_creation_bond_e6
+        + 2   // redeem_fee_bps
+        + FeeTier::SIZE * FEE_TIER_COUNT // fee_tiers
+        + 2;  // referral_share_bps
+    
+    /// Read the tail from config account data
+    pub fn load(data: &[u8]) -> Self {
+        load_extension(data, Self::OFFSET)
+    }
+    
+    /// Write the tail; the account must already be OFFSET + SIZE bytes
+    pub fn store(&self, data: &mut [u8]) -> std::io::Result<()> {
+        self.serialize(&mut data.get_mut(Self::OFFSET..).unwrap_or_default())
+    }
+    
+    /// Redemption fee withheld from a RedeemCompleteSet paying out `amount`
+    pub fn redeem_fee_on(&self, amount: u64) -> Result<u64, PredictionMarketError> {
+        bps_of(amount, self.redeem_fee_bps)
+    }
+    
+    /// Whether any volume tier grants a discount
+    pub fn fee_tiers_enabled(&self) -> bool {
+        self.fee_tiers.iter().any(|tier| tier.discount_bps > 0)
+    }
+    
+    /// Largest discount (bps of the fee) among the tiers `volume_e6` reaches
+    pub fn fee_discount_bps(&self, volume_e6: u64) -> u16 {
+        self.fee_tiers
+            .iter()
+            .filter(|tier| volume_e6 >= tier.min_volume_e6)
+            .map(|tier| tier.discount_bps)
+            .max()
+            .unwrap_or(0)
+    }
+    
+    /// `trade_fee` less the discount of a taker with `volume_e6` traded so far
+    pub fn discounted_taker_fee(&self, trade_fee: u64, volume_e6: u64) -> Result<u64, PredictionMarketError> {
+        let discount = bps_of(trade_fee, self.fee_discount_bps(volume_e6))?;
+        Ok(trade_fee - discount)
+    }
+    
+    /// Referrer's cut of `protocol_fee` paid by a referred trader
+    pub fn referral_reward_on(&self, protocol_fee: u64) -> Result<u64, PredictionMarketError> {
+        bps_of(protocol_fee, self.referral_share_bps)
+    }
+}
+
+/// A taker fee discount unlocked by cumulative trading volume
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    /// TraderStats.volume_e6 needed before a trade to get the discount
+    pub min_volume_e6: u64,
+    
+    /// Share of the taker fee waived (bps of the fee, at most
+    /// MAX_FEE_DISCOUNT_BPS)
+    pub discount_bps: u16,
+}
+
+impl FeeTier {
+    pub const SIZE: usize = 8 // min_volume_e6
+        + 2;  // discount_bps
+}
+
+/// Every fee the program charges, set at once by UpdateFeeSchedule
+/// 
+/// Not stored as one block: the mint, trade and maker rebate fees keep
+/// their `PredictionMarketConfig` fields (still writable by SetMintFee,
+/// SetTradeFee and SetMakerRebate) and the redeem fee lives in
+/// `ConfigExtension`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    /// MintCompleteSet fee (bps of the collateral, paid on top)
+    pub mint_bps: u16,
+    
+    /// RedeemCompleteSet fee (bps of the payout, withheld from it)
+    pub redeem_bps: u16,
+    
+    /// Trading fee (bps of notional) charged on each side of a fill
+    pub trade_bps: u16,
+    
+    /// Maker rebate (bps of notional) paid out of the trading fee
+    pub maker_rebate_bps: u16,
+}
+
+impl FeeSchedule {
+    pub const SIZE: usize = 2 // mint_bps
+        + 2   // redeem_bps
+        + 2   // trade_bps
+        + 2;  // maker_rebate_bps
+    
+    /// Reject fees above MAX_FEE_SCHEDULE_BPS or a rebate above the trade fee
+    pub fn validate(&self) -> Result<(), PredictionMarketError> {
+        let fees = [self.mint_bps, self.redeem_bps, self.trade_bps, self.maker_rebate_bps];
+        if fees.iter().any(|&bps| bps > MAX_FEE_SCHEDULE_BPS) {
+            return Err(PredictionMarketError::FeeScheduleTooHigh);
         }
+        // The rebate is paid out of the trading fee
+        if self.maker_rebate_bps > self.trade_bps {
+            return Err(PredictionMarketError::MakerRebateTooHigh);
+        }
+        Ok(())
+    }
+}
+
+/// Read a tail struct stored at `offset`, defaulting when it is absent
+fn load_extension<T: BorshDeserialize + Default>(data: &[u8], offset: usize) -> T {
+    data.get(offset..)
+        .and_then(|mut tail| T::deserialize(&mut tail).ok())
+        .unwrap_or_default()
+}
+
+/// `amount * bps / 10_000` with checked arithmetic
+fn bps_of(amount: u64, bps: u16) -> Result<u64, PredictionMarketError> {
+    let value = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?
+        / 10_000;
+    u64::try_from(value).map_err(|_| PredictionMarketError::ArithmeticOverflow)
+}
+
+/// `amount * price / PRICE_PRECISION` with checked arithmetic
+fn cost_e6(amount: u64, price: u64) -> Result<u64, PredictionMarketError> {
+    let value = (amount as u128) * (price as u128) / (PRICE_PRECISION as u128);
+    u64::try_from(value).map_err(|_| PredictionMarketError::ArithmeticOverflow)
+}
+
+/// Margin a Buy of `amount` at `price` locks: its cost plus headroom for a
+/// taker fee of `fee_bps` on that cost
+pub fn buy_margin(amount: u64, price: u64, fee_bps: u16) -> Result<u64, PredictionMarketError> {
+    let cost = cost_e6(amount, price)?;
+    cost.checked_add(bps_of(cost, fee_bps)?)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)
+}
+
+/// Largest Buy amount at `price` whose [`buy_margin`] fits in `margin`
+pub fn buy_amount_for_margin(margin: u64, price: u64, fee_bps: u16) -> Result<u64, PredictionMarketError> {
+    let value = (margin as u128) * (PRICE_PRECISION as u128) * 10_000
+        / ((price as u128) * (10_000 + fee_bps as u128));
+    u64::try_from(value).map_err(|_| PredictionMarketError::ArithmeticOverflow)
+}
+
+/// Weighted average cost after adding `amount` at `price` to `held` at
+/// `avg_cost`; `new_total` is `held + amount`
+fn weighted_avg_cost(
+    held: u64,
+    avg_cost: u64,
+    amount: u64,
+    price: u64,
+    new_total: u64,
+) -> Result<u64, PredictionMarketError> {
+    if new_total == 0 {
+        return Ok(avg_cost);
     }
+    let total = ((held as u128) * (avg_cost as u128))
+        .checked_add((amount as u128) * (price as u128))
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+    u64::try_from(total / new_total as u128).map_err(|_| PredictionMarketError::ArithmeticOverflow)
 }
 
 /// A single prediction market
@@ -660,10 +1057,15 @@ pub struct Market {
     /// Total complete sets minted
     pub total_minted: u64,
     
-    /// Total trading volume (e6)
+    /// Total trading volume (USDC notional, e6)
     pub total_volume_e6: i64,
     
-    /// Total open interest (active positions)
+    /// Open interest: complete sets outstanding
+    ///
+    /// Incremented when complete sets are minted and decremented when they
+    /// are burned or when winning shares are paid out at settlement, so it
+    /// reaches zero once every position has been settled. Unlike
+    /// `total_minted` it is not a lifetime counter.
     pub open_interest: u64,
     
     /// Creator fee rate (basis points, e.g., 100 = 1%)
@@ -675,8 +1077,30 @@ pub struct Market {
     /// PDA bump
     pub bump: u8,
     
+    /// Circuit breaker: max locked collateral (e6), 0 = no cap
+    /// (carved from reserved)
+    pub max_tvl_e6: u64,
+    
+    /// Sum of PnL realized by sellers trading in this market (e6)
+    /// (carved from reserved)
+    pub total_realized_pnl_e6: i64,
+    
+    /// Challenge window for proposals on this market (seconds),
+    /// 0 = use `config.challenge_window_secs` (carved from reserved)
+    pub challenge_window_secs: i64,
+    
+    /// Category for discovery tooling (carved from reserved)
+    pub category: MarketCategory,
+    
+    /// Hash of the off-chain tag list (carved from reserved)
+    pub tags_hash: [u8; 32],
+    
+    /// Operator reason code given to PauseMarket, 0 when not paused
+    /// (carved from reserved)
+    pub pause_reason: u8,
+    
     /// Reserved for future use
-    pub reserved: [u8; 60],
+    pub reserved: [u8; 2],
 }
 
 impl Market {
@@ -704,7 +1128,13 @@ impl Market {
         + 2   // creator_fee_bps
         + 8   // next_order_id
         + 1   // bump
-        + 60; // reserved (reduced by 4)
+        + 8   // max_tvl_e6
+        + 8   // total_realized_pnl_e6
+        + 8   // challenge_window_secs
+        + 1   // category
+        + 32  // tags_hash
+        + 1   // pause_reason
+        + 2;  // reserved (reduced by 8 for max_tvl_e6, 8 for total_realized_pnl_e6, 8 for challenge_window_secs, 33 for category + tags_hash, 1 for pause_reason)
     
     /// PDA seeds
     pub fn seeds(market_id: u64) -> Vec<Vec<u8>> {
@@ -714,11 +1144,101 @@ impl Market {
         ]
     }
     
+    /// Challenge window for a new proposal: the market's own window, or
+    /// the global one when unset
+    pub fn effective_challenge_window_secs(&self, global_window_secs: i64) -> i64 {
+        if self.challenge_window_secs > 0 {
+            self.challenge_window_secs
+        } else {
+            global_window_secs
+        }
+    }
+    
     /// Check if market is tradeable
     pub fn is_tradeable(&self) -> bool {
         self.status == MarketStatus::Active && self.review_status == ReviewStatus::None
     }
     
+    /// Whether settlements of this market pay the creator fee
+    /// 
+    /// Only winning payouts of a Yes/No resolution are charged; Invalid
+    /// refunds and cancelled markets are fee-free.
+    pub fn charges_creator_fee(&self) -> bool {
+        self.creator_fee_bps > 0
+            && self.status == MarketStatus::Resolved
+            && matches!(self.final_result, Some(MarketResult::Yes | MarketResult::No))
+    }
+    
+    /// Creator fee owed on a settlement payout (0 if not charged)
+    pub fn creator_fee_on(&self, settlement_amount: u64) -> u64 {
+        if !self.charges_creator_fee() {
+            return 0;
+        }
+        ((settlement_amount as u128) * (self.creator_fee_bps as u128) / 10_000) as u64
+    }
+    
+    /// Check that minting `additional_sets` more complete sets keeps the
+    /// locked collateral within `max_tvl_e6` (1 complete set = 1 USDC)
+    pub fn check_tvl_cap(&self, additional_sets: u64) -> Result<(), PredictionMarketError> {
+        if self.max_tvl_e6 == 0 {
+            return Ok(());
+        }
+        let new_total = self
+            .total_minted
+            .checked_add(additional_sets)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        let collateral = (new_total as u128) * (PRICE_PRECISION as u128) / (SHARE_PRECISION as u128);
+        if collateral > self.max_tvl_e6 as u128 {
+            return Err(PredictionMarketError::TvlCapExceeded);
+        }
+        Ok(())
+    }
+    
+    /// Record `sets` newly minted complete sets as open interest
+    pub fn increase_open_interest(&mut self, sets: u64) -> Result<(), PredictionMarketError> {
+        self.open_interest = self
+            .open_interest
+            .checked_add(sets)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        Ok(())
+    }
+    
+    /// Remove `sets` burned or settled complete sets from open interest
+    /// 
+    /// Saturates so markets created before open interest was tracked can
+    /// still burn and settle.
+    pub fn decrease_open_interest(&mut self, sets: u64) {
+        self.open_interest = self.open_interest.saturating_sub(sets);
+    }
+    
+    /// Add the PnL a fill realized on a seller's position to the market rollup
+    pub fn record_realized_pnl(&mut self, pnl_before: i64, pnl_after: i64) -> Result<(), PredictionMarketError> {
+        let realized = pnl_after
+            .checked_sub(pnl_before)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        self.total_realized_pnl_e6 = self
+            .total_realized_pnl_e6
+            .checked_add(realized)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        Ok(())
+    }
+    
+    /// Check if market is tradeable, returning the specific reason on failure
+    /// 
+    /// Pending markets report MarketNotActivated so clients can ask the admin
+    /// to activate instead of telling users to wait.
+    pub fn check_tradeable(&self) -> Result<(), PredictionMarketError> {
+        match self.status {
+            MarketStatus::Active if self.review_status == ReviewStatus::None => Ok(()),
+            MarketStatus::Active => Err(PredictionMarketError::MarketUnderReview),
+            MarketStatus::Pending => Err(PredictionMarketError::MarketNotActivated),
+            MarketStatus::Paused => Err(PredictionMarketError::MarketPaused),
+            MarketStatus::Resolved => Err(PredictionMarketError::MarketAlreadyResolved),
+            MarketStatus::Cancelled => Err(PredictionMarketError::MarketCancelled),
+            _ => Err(PredictionMarketError::MarketNotTradeable),
+        }
+    }
+    
     /// Check if market can be resolved
     pub fn can_resolve(&self, current_time: i64) -> bool {
         self.status == MarketStatus::Active && current_time >= self.resolution_time
@@ -741,6 +1261,173 @@ impl Market {
     pub fn is_multi_outcome(&self) -> bool {
         self.market_type == MarketType::MultiOutcome
     }
+    
+    /// Summary returned by QueryMarket
+    pub fn summary(&self) -> MarketSummary {
+        MarketSummary {
+            status: self.status,
+            total_minted: self.total_minted,
+            open_interest: self.open_interest,
+            total_volume_e6: self.total_volume_e6,
+            resolution_time: self.resolution_time,
+            final_result: self.final_result,
+            pause_reason: self.pause_reason,
+        }
+    }
+    
+    /// Whether the market was cancelled as invalid or for regulatory
+    /// reasons, which forfeits the creator's creation bond
+    pub fn forfeits_creation_bond(&self) -> bool {
+        self.status == MarketStatus::Cancelled
+            && matches!(
+                self.review_status,
+                ReviewStatus::CancelledInvalid | ReviewStatus::CancelledRegulatory
+            )
+    }
+}
+
+/// Market fields stored past the fixed `Market` layout
+/// 
+/// Markets are created with room for this tail; markets created before it
+/// existed read as all zeros.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarketExtension {
+    /// Creation bond (e6) still locked in the creator's Vault account.
+    /// Zeroed once ReleaseCreationBond returns or forfeits it.
+    pub creation_bond_e6: u64,
+    
+    /// When CancelMarket cancelled the market (0 if never or reopened)
+    pub cancelled_at: i64,
+    
+    /// Refunds and claims paid out of the market since it was cancelled
+    pub cancelled_payouts: u32,
+    
+    /// Reserved for future use
+    pub reserved: [u8; 12],
+}
+
+impl MarketExtension {
+    /// Offset of the tail in the market account
+    pub const OFFSET: usize = Market::SIZE;
+    
+    pub const SIZE: usize = 8 // creation_bond_e6
+        + 8  // cancelled_at
+        + 4  // cancelled_payouts
+        + 12; // reserved = 32 bytes (unchanged)
+    
+    /// Read the tail from market account data
+    pub fn load(data: &[u8]) -> Self {
+        load_extension(data, Self::OFFSET)
+    }
+    
+    /// Whether the account was created with room for the tail
+    pub fn fits(data: &[u8]) -> bool {
+        data.len() >= Self::OFFSET + Self::SIZE
+    }
+    
+    /// Write the tail; the account must already be OFFSET + SIZE bytes
+    pub fn store(&self, data: &mut [u8]) -> std::io::Result<()> {
+        self.serialize(&mut data.get_mut(Self::OFFSET..).unwrap_or_default())
+    }
+}
+
+/// How a feed value is compared against a market's threshold
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOperator {
+    GreaterThan = 0,
+    GreaterOrEqual = 1,
+    LessThan = 2,
+    LessOrEqual = 3,
+}
+
+impl ComparisonOperator {
+    /// Whether `value.cmp(threshold)` satisfies the operator
+    pub fn holds(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering;
+        match self {
+            ComparisonOperator::GreaterThan => ordering == Ordering::Greater,
+            ComparisonOperator::GreaterOrEqual => ordering != Ordering::Less,
+            ComparisonOperator::LessThan => ordering == Ordering::Less,
+            ComparisonOperator::LessOrEqual => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// Where a binary market's result comes from
+/// 
+/// Stored in a fixed-size slot after the MarketExtension tail. Markets
+/// created before it existed read as `Manual`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResolutionSource {
+    /// Resolved by the oracle proposal / admin flow
+    #[default]
+    Manual,
+    /// Resolved YES by AutoResolveFromFeed when the Switchboard aggregator's
+    /// latest confirmed value satisfies `operator threshold`, NO otherwise
+    Switchboard {
+        /// Aggregator account
+        feed: Pubkey,
+        operator: ComparisonOperator,
+        /// Threshold scaled by 1e9
+        threshold: i128,
+    },
+    /// Resolved YES by ResolveFromPyth when the Pyth aggregate price is at
+    /// or above `threshold`, NO otherwise
+    Pyth {
+        /// Pyth price account
+        feed: Pubkey,
+        /// Threshold scaled by 1e9
+        threshold: i128,
+    },
+}
+
+impl ResolutionSource {
+    /// Offset of the slot in the market account
+    pub const OFFSET: usize = MarketExtension::OFFSET + MarketExtension::SIZE;
+    
+    /// Fixed slot size; the largest variant (Switchboard) takes 50 bytes
+    pub const SIZE: usize = 64;
+    
+    /// Read the source from market account data
+    pub fn load(data: &[u8]) -> Self {
+        load_extension(data, Self::OFFSET)
+    }
+    
+    /// Write the source; the account must already be OFFSET + SIZE bytes
+    pub fn store(&self, data: &mut [u8]) -> std::io::Result<()> {
+        self.serialize(&mut data.get_mut(Self::OFFSET..).unwrap_or_default())
+    }
+}
+
+/// Market status summary written as return data by QueryMarket
+/// 
+/// Clients simulate QueryMarket and borsh-decode this instead of the raw
+/// Market account layout.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketSummary {
+    pub status: MarketStatus,
+    pub total_minted: u64,
+    pub open_interest: u64,
+    pub total_volume_e6: i64,
+    pub resolution_time: i64,
+    pub final_result: Option<MarketResult>,
+    pub pause_reason: u8,
+}
+
+/// A position's claim written as return data by QueryClaimable
+/// 
+/// Amounts are what RelayerClaimWinningsV2 would settle now; all zero once
+/// the position is settled.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClaimableSummary {
+    /// Whether the position was already settled
+    pub settled: bool,
+    /// Payout to the user after the creator fee (e6)
+    pub claimable_e6: u64,
+    /// Creator fee withheld from the payout (e6)
+    pub creator_fee_e6: u64,
+    /// Locked funds the claim releases (e6)
+    pub locked_e6: u64,
 }
 
 // ============================================================================
@@ -900,14 +1587,29 @@ impl MultiOutcomePosition {
         }
     }
     
+    /// Number of outcome slots in use
+    /// 
+    /// Returns None if `num_outcomes` exceeds MAX_OUTCOMES (corrupt account).
+    pub fn outcome_count(&self) -> Option<usize> {
+        let count = self.num_outcomes as usize;
+        (count <= MAX_OUTCOMES).then_some(count)
+    }
+    
     /// Check if position is empty (no tokens in any outcome)
     pub fn is_empty(&self) -> bool {
-        for i in 0..self.num_outcomes as usize {
-            if self.holdings[i] > 0 {
-                return false;
-            }
-        }
-        true
+        let count = (self.num_outcomes as usize).min(MAX_OUTCOMES);
+        self.holdings[..count].iter().all(|&h| h == 0)
+    }
+    
+    /// Clear holdings and locked shares on settlement
+    /// 
+    /// Only the first `num_outcomes` slots are touched; the rest of the
+    /// MAX_OUTCOMES arrays are never written for a market's position.
+    pub fn clear_holdings(&mut self) -> Result<(), PredictionMarketError> {
+        let count = self.outcome_count().ok_or(PredictionMarketError::TooManyOutcomes)?;
+        self.holdings[..count].fill(0);
+        self.locked[..count].fill(0);
+        Ok(())
     }
     
     /// Get holdings for a specific outcome
@@ -920,24 +1622,30 @@ impl MultiOutcomePosition {
     }
     
     /// Add tokens for a specific outcome
-    pub fn add_tokens(&mut self, outcome_index: u8, amount: u64, price: u64, current_time: i64) {
+    pub fn add_tokens(
+        &mut self,
+        outcome_index: u8,
+        amount: u64,
+        price: u64,
+        current_time: i64,
+    ) -> Result<(), PredictionMarketError> {
         let idx = outcome_index as usize;
         if idx >= MAX_OUTCOMES {
-            return;
+            return Ok(());
         }
         
-        // Update weighted average cost
-        let total_prev = self.holdings[idx] * self.avg_costs[idx];
-        let total_new = amount * price;
-        let new_total_amount = self.holdings[idx] + amount;
-        if new_total_amount > 0 {
-            self.avg_costs[idx] = (total_prev + total_new) / new_total_amount;
-        }
-        self.holdings[idx] += amount;
+        let new_total_amount = self.holdings[idx]
+            .checked_add(amount)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        self.avg_costs[idx] =
+            weighted_avg_cost(self.holdings[idx], self.avg_costs[idx], amount, price, new_total_amount)?;
+        self.holdings[idx] = new_total_amount;
         
-        let cost = ((amount as u128) * (price as u128) / (PRICE_PRECISION as u128)) as u64;
-        self.total_cost_e6 += cost;
+        self.total_cost_e6 = self.total_cost_e6
+            .checked_add(cost_e6(amount, price)?)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
         self.updated_at = current_time;
+        Ok(())
     }
     
     /// Calculate settlement value based on winning outcome
@@ -1123,8 +1831,27 @@ pub struct Order {
     /// This holds the tokens that the seller is offering
     pub escrow_token_account: Option<Pubkey>,
     
-    /// Reserved for future use (reduced by 1 byte for outcome_index)
-    pub reserved: [u8; 30],
+    /// Remaining margin/shares already released by ExpireOrder
+    pub locks_released: bool,
+    
+    /// Buy margin was locked in the Vault at placement (RelayerPlaceOrderV2)
+    /// Matching settles from that lock instead of locking again. Orders
+    /// placed before this flag existed read as false.
+    pub margin_locked: bool,
+    
+    /// Margin (USDC e6) still locked in the Vault for the unfilled part of a
+    /// Buy order. Set at placement to what the Vault actually locked and
+    /// reduced as fills consume it, so cancellation releases exactly what is
+    /// left. Orders placed before this field existed read 0.
+    pub locked_margin_e6: u64,
+    
+    /// Reduce-only: the order may only unwind an existing position, never
+    /// grow or flip it. Orders placed before this flag existed read as false.
+    pub reduce_only: bool,
+    
+    /// Reserved for future use (reduced by 1 byte for outcome_index, 1 each for
+    /// locks_released, margin_locked and reduce_only, 8 for locked_margin_e6)
+    pub reserved: [u8; 19],
 }
 
 impl Order {
@@ -1145,7 +1872,11 @@ impl Order {
         + 8   // updated_at
         + 1   // bump
         + 1 + 32 // escrow_token_account (Option<Pubkey>)
-        + 30; // reserved (reduced by 1 for outcome_index)
+        + 1   // locks_released
+        + 1   // margin_locked
+        + 8   // locked_margin_e6
+        + 1   // reduce_only
+        + 19; // reserved (reduced by 1 for outcome_index, 1 each for locks_released/margin_locked/reduce_only, 8 for locked_margin_e6)
     
     /// PDA seeds
     pub fn seeds(market_id: u64, order_id: u64) -> Vec<Vec<u8>> {
@@ -1181,6 +1912,82 @@ impl Order {
         matches!(self.status, OrderStatus::Open | OrderStatus::PartialFilled)
     }
     
+    /// Check if order can still be cancelled to release its margin/shares
+    /// 
+    /// Expired orders marked by SweepExpiredOrders keep their funds locked
+    /// until this cancel path runs; ExpireOrder releases them itself.
+    pub fn is_cancellable(&self) -> bool {
+        self.is_active() || (self.status == OrderStatus::Expired && !self.locks_released)
+    }
+    
+    /// Check if the order account can be closed to reclaim its rent
+    /// 
+    /// The order must be finished with nothing left to release. A legacy sell
+    /// order's escrow token account is only closed by cancellation.
+    pub fn is_closable(&self) -> bool {
+        let finished = matches!(
+            self.status,
+            OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Expired
+        );
+        finished
+            && !self.is_cancellable()
+            && self.locked_margin_e6 == 0
+            && (!self.has_escrow() || self.status == OrderStatus::Cancelled)
+    }
+    
+    /// Reject a fill that would leave a Fill-Or-Kill or Market order
+    /// partially filled
+    pub fn check_fill_or_kill(&self, fill_amount: u64) -> Result<(), PredictionMarketError> {
+        if fill_amount >= self.remaining_amount() {
+            return Ok(());
+        }
+        match self.order_type {
+            OrderType::FOK => Err(PredictionMarketError::FillOrKillNotFilled),
+            OrderType::Market => Err(PredictionMarketError::MarketOrderNotFilled),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Unfilled amount cancelled after a fill of `fill_amount`
+    /// 
+    /// An Immediate-Or-Cancel order cancels whatever is left; any other order
+    /// only cancels a dust remainder below `min_order_size` (0 disables).
+    pub fn cancelled_remainder(&self, fill_amount: u64, min_order_size: u64) -> u64 {
+        let left = self.remaining_amount().saturating_sub(fill_amount);
+        if self.order_type == OrderType::IOC || left < min_order_size {
+            left
+        } else {
+            0
+        }
+    }
+    
+    /// Record a fill of `fill_amount` and update the status
+    /// 
+    /// An IOC order that is not completely filled, or any order left with a
+    /// dust remainder, is cancelled. Returns the cancelled remainder, whose
+    /// margin or locked shares the caller must release in the same instruction.
+    pub fn record_fill(
+        &mut self,
+        fill_amount: u64,
+        min_order_size: u64,
+        current_time: i64,
+    ) -> Result<u64, PredictionMarketError> {
+        let cancelled = self.cancelled_remainder(fill_amount, min_order_size);
+        self.filled_amount = self
+            .filled_amount
+            .checked_add(fill_amount)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        self.status = if self.filled_amount >= self.amount {
+            OrderStatus::Filled
+        } else if cancelled > 0 {
+            OrderStatus::Cancelled
+        } else {
+            OrderStatus::PartialFilled
+        };
+        self.updated_at = current_time;
+        Ok(cancelled)
+    }
+    
     /// Check if order is expired
     pub fn is_expired(&self, current_time: i64) -> bool {
         if let Some(exp_time) = self.expiration_time {
@@ -1196,6 +2003,48 @@ impl Order {
         ((token_amount as u128) * (self.price as u128) / (PRICE_PRECISION as u128)) as u64
     }
     
+    /// Margin still locked for the unfilled part of a Buy order
+    /// 
+    /// Orders placed before locked_margin_e6 existed read 0 and fall back to
+    /// the remaining amount at the order price.
+    pub fn remaining_locked_margin(&self) -> u64 {
+        if self.locked_margin_e6 > 0 {
+            self.locked_margin_e6
+        } else {
+            self.calculate_cost(self.remaining_amount())
+        }
+    }
+    
+    /// Take a Buy fill of `fill_amount` that consumed `debit` from the Vault
+    /// lock off the locked margin; call after `record_fill`
+    /// 
+    /// Returns the surplus to release back to the buyer: everything left
+    /// once the order is no longer active, otherwise whatever exceeds the
+    /// [`buy_margin`] of the remaining amount at `fee_bps` (fills below the
+    /// limit price or under the full fee, rounding).
+    pub fn settle_locked_margin(
+        &mut self,
+        fill_amount: u64,
+        debit: u64,
+        fee_bps: u16,
+    ) -> Result<u64, PredictionMarketError> {
+        let locked = if self.locked_margin_e6 > 0 {
+            self.locked_margin_e6
+        } else {
+            self.calculate_cost(self.remaining_amount().saturating_add(fill_amount))
+        };
+        let left = locked
+            .checked_sub(debit)
+            .ok_or(PredictionMarketError::InsufficientFunds)?;
+        let keep = if self.is_active() {
+            buy_margin(self.remaining_amount(), self.price, fee_bps)?.min(left)
+        } else {
+            0
+        };
+        self.locked_margin_e6 = keep;
+        Ok(left - keep)
+    }
+    
     /// Calculate USDC proceeds for selling tokens at this order's price
     pub fn calculate_proceeds(&self, token_amount: u64) -> u64 {
         self.calculate_cost(token_amount)
@@ -1212,6 +2061,35 @@ impl Order {
     pub fn is_binary_market_order(&self) -> bool {
         self.outcome_index <= 1
     }
+    
+    /// Binary outcome_index implied by the `outcome` field (Yes=0, No=1)
+    pub fn binary_outcome_index(&self) -> u8 {
+        match self.outcome {
+            Outcome::Yes => 0,
+            Outcome::No => 1,
+        }
+    }
+    
+    /// Repair `outcome_index` on a legacy binary-market order
+    /// 
+    /// Returns true if the field was out of sync and has been updated.
+    pub fn sync_binary_outcome_index(&mut self) -> bool {
+        let expected = self.binary_outcome_index();
+        if self.outcome_index == expected {
+            return false;
+        }
+        self.outcome_index = expected;
+        true
+    }
+    
+    /// Check if two orders trade the same outcome
+    /// 
+    /// Compares `outcome_index` (meaningful for all market types) as well
+    /// as the binary `outcome`, so multi-outcome orders that share the same
+    /// `Outcome` mapping but differ in index never cross.
+    pub fn is_same_outcome(&self, other: &Order) -> bool {
+        self.outcome_index == other.outcome_index && self.outcome == other.outcome
+    }
 }
 
 /// User's position in a market
@@ -1367,32 +2245,23 @@ impl Position {
         amount: u64,
         price: u64,
         current_time: i64,
-    ) {
-        match outcome {
-            Outcome::Yes => {
-                // Update weighted average cost
-                let total_prev = self.yes_amount * self.yes_avg_cost;
-                let total_new = amount * price;
-                let new_total_amount = self.yes_amount + amount;
-                if new_total_amount > 0 {
-                    self.yes_avg_cost = (total_prev + total_new) / new_total_amount;
-                }
-                self.yes_amount += amount;
-            }
-            Outcome::No => {
-                let total_prev = self.no_amount * self.no_avg_cost;
-                let total_new = amount * price;
-                let new_total_amount = self.no_amount + amount;
-                if new_total_amount > 0 {
-                    self.no_avg_cost = (total_prev + total_new) / new_total_amount;
-                }
-                self.no_amount += amount;
-            }
-        }
+    ) -> Result<(), PredictionMarketError> {
+        let (held, avg_cost) = match outcome {
+            Outcome::Yes => (&mut self.yes_amount, &mut self.yes_avg_cost),
+            Outcome::No => (&mut self.no_amount, &mut self.no_avg_cost),
+        };
+        
+        let new_total_amount = held
+            .checked_add(amount)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
+        *avg_cost = weighted_avg_cost(*held, *avg_cost, amount, price, new_total_amount)?;
+        *held = new_total_amount;
         
-        let cost = ((amount as u128) * (price as u128) / (PRICE_PRECISION as u128)) as u64;
-        self.total_cost_e6 += cost;
+        self.total_cost_e6 = self.total_cost_e6
+            .checked_add(cost_e6(amount, price)?)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?;
         self.updated_at = current_time;
+        Ok(())
     }
     
     /// Update position after removing tokens
@@ -1415,13 +2284,36 @@ impl Position {
         };
         
         // Calculate realized PnL
-        let proceeds = ((amount as u128) * (price as u128) / (PRICE_PRECISION as u128)) as i64;
+        let proceeds = Self::sale_proceeds(amount, price) as i64;
         let cost = ((amount as u128) * (cost_basis as u128) / (PRICE_PRECISION as u128)) as i64;
         self.realized_pnl += proceeds - cost;
         
         self.updated_at = current_time;
     }
     
+    /// Proceeds (e6) for selling `amount` shares at `price`
+    pub fn sale_proceeds(amount: u64, price: u64) -> u64 {
+        ((amount as u128) * (price as u128) / (PRICE_PRECISION as u128)) as u64
+    }
+    
+    /// Remove `amount` complete sets (1 YES + 1 NO) redeemed at par
+    /// 
+    /// Each set pays exactly 1 USDC, so the realized PnL is the spread against
+    /// the combined YES + NO cost basis: zero for sets minted at par, the true
+    /// gain or loss for sets bought off-par. Returns the PnL realized.
+    pub fn redeem_complete_set(&mut self, amount: u64, current_time: i64) -> i64 {
+        self.yes_amount = self.yes_amount.saturating_sub(amount);
+        self.no_amount = self.no_amount.saturating_sub(amount);
+        
+        let set_cost_basis = self.yes_avg_cost.saturating_add(self.no_avg_cost);
+        let cost = ((amount as u128) * (set_cost_basis as u128) / (PRICE_PRECISION as u128)) as i64;
+        let pnl = amount as i64 - cost;
+        self.realized_pnl += pnl;
+        
+        self.updated_at = current_time;
+        pnl
+    }
+    
     // =========================================================================
     // Locked Shares Methods (for Sell Order Support)
     // =========================================================================
@@ -1517,6 +2409,23 @@ impl Position {
         
         Ok(())
     }
+    
+    /// Consume locked shares for a fill and return the seller's proceeds
+    /// 
+    /// The proceeds and the realized PnL are both valued at `price`, so the
+    /// amount paid out always matches what is booked against the cost basis.
+    /// Used during MatchBurnV2.
+    pub fn consume_locked_for_proceeds(
+        &mut self,
+        outcome: Outcome,
+        amount: u64,
+        price: u64,
+        current_time: i64,
+    ) -> Result<u64, PredictionMarketError> {
+        self.consume_locked_shares(outcome, amount, price, current_time)
+            .map_err(|_| PredictionMarketError::InsufficientPosition)?;
+        Ok(Self::sale_proceeds(amount, price))
+    }
 }
 
 /// Oracle result proposal
@@ -1574,8 +2483,26 @@ pub struct OracleProposal {
     /// V15.2: Incremented each time a challenge is submitted
     pub challenge_count: u8,
     
-    /// Reserved for future use (reduced by 9 bytes for new fields)
-    pub reserved: [u8; 23],
+    /// Winning outcome proposed by ProposeMultiOutcomeResult (None for
+    /// binary markets). Carved from reserved.
+    pub proposed_outcome_index: Option<u8>,
+    
+    /// Deadline for the committee to resolve the dispute, set when it
+    /// starts (0 for proposals disputed before it existed). Carved from
+    /// reserved.
+    pub proposal_resolution_deadline: i64,
+    
+    /// Challengers who joined the dispute after the first one, each with a
+    /// ChallengerRecord. Carved from reserved.
+    pub escalated_challengers: u8,
+    
+    /// Total bonds of the escalated challengers (e6). Carved from reserved.
+    pub escalated_bonds_e6: u64,
+    
+    /// Reserved for future use (reduced by 9 bytes for V15.2 fields, 2 for
+    /// proposed_outcome_index, 8 for proposal_resolution_deadline, 9 for
+    /// the escalated challengers)
+    pub reserved: [u8; 4],
 }
 
 impl OracleProposal {
@@ -1594,7 +2521,11 @@ impl OracleProposal {
         + 1   // bump
         + 8   // original_challenge_deadline (V15.2)
         + 1   // challenge_count (V15.2)
-        + 23; // reserved = 150 bytes (unchanged)
+        + 1 + 1 // proposed_outcome_index (Option<u8>)
+        + 8   // proposal_resolution_deadline
+        + 1   // escalated_challengers
+        + 8   // escalated_bonds_e6
+        + 4;  // reserved = 150 bytes (unchanged)
     
     /// PDA seeds
     pub fn seeds(market_id: u64) -> Vec<Vec<u8>> {
@@ -1609,6 +2540,23 @@ impl OracleProposal {
         self.status == ProposalStatus::Pending && current_time >= self.challenge_deadline
     }
     
+    /// Check finalization preconditions, returning the specific reason on failure
+    /// 
+    /// A disputed proposal is always rejected with ProposalUnderDispute,
+    /// even after its challenge deadline has passed.
+    pub fn validate_finalize(&self, current_time: i64) -> Result<(), PredictionMarketError> {
+        if self.status == ProposalStatus::Disputed {
+            return Err(PredictionMarketError::ProposalUnderDispute);
+        }
+        if self.status != ProposalStatus::Pending {
+            return Err(PredictionMarketError::CannotFinalize);
+        }
+        if current_time < self.challenge_deadline {
+            return Err(PredictionMarketError::ChallengeWindowNotExpired);
+        }
+        Ok(())
+    }
+    
     /// Check if proposal can be challenged
     pub fn can_challenge(&self, current_time: i64) -> bool {
         self.status == ProposalStatus::Pending && current_time < self.challenge_deadline
@@ -1630,6 +2578,123 @@ impl OracleProposal {
     pub fn is_extended(&self) -> bool {
         self.challenge_deadline > self.original_challenge_deadline
     }
+    
+    /// Deadline after which an unresolved dispute may be expired
+    /// 
+    /// Disputes started before the deadline was stored fall back to one
+    /// resolution period after the challenge deadline, the latest they can
+    /// have started.
+    pub fn resolution_deadline(&self) -> i64 {
+        if self.proposal_resolution_deadline != 0 {
+            self.proposal_resolution_deadline
+        } else {
+            self.challenge_deadline.saturating_add(DISPUTE_RESOLUTION_PERIOD_SECS)
+        }
+    }
+    
+    /// Number of challengers who posted a bond, the first one included
+    pub fn bonded_challengers(&self) -> u8 {
+        if self.challenger.is_some() {
+            self.escalated_challengers.saturating_add(1)
+        } else {
+            0
+        }
+    }
+    
+    /// Bond the next challenger must post: `base_bond` for the first one,
+    /// doubling with every challenger already in the dispute
+    pub fn next_challenge_bond(&self, base_bond: u64) -> Result<u64, PredictionMarketError> {
+        let challengers = self.bonded_challengers();
+        if challengers >= MAX_DISPUTE_CHALLENGERS {
+            return Err(PredictionMarketError::TooManyChallengers);
+        }
+        base_bond
+            .checked_mul(1u64 << challengers)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)
+    }
+    
+    /// Total bonds posted by all challengers
+    pub fn total_challenger_bonds(&self) -> u64 {
+        self.challenger_bond.saturating_add(self.escalated_bonds_e6)
+    }
+    
+    /// Check if the proposal is disputed and its resolution deadline passed
+    pub fn can_expire(&self, current_time: i64) -> bool {
+        self.status == ProposalStatus::Disputed && current_time > self.resolution_deadline()
+    }
+}
+
+/// Split the bonds of a resolved dispute
+/// 
+/// `stakes` holds each side's (bond, matched the final result). The sides
+/// that matched share every bond in proportion to their own; rounding dust
+/// and, if no side matched, all bonds are forfeited.
+pub fn split_dispute_pool(stakes: &[(u64, bool)]) -> Result<Vec<u64>, PredictionMarketError> {
+    let sum = |winners_only: bool| {
+        stakes
+            .iter()
+            .filter(|(_, won)| *won || !winners_only)
+            .try_fold(0u64, |total, (bond, _)| total.checked_add(*bond))
+            .ok_or(PredictionMarketError::ArithmeticOverflow)
+    };
+    let pool = sum(false)? as u128;
+    let winning_bonds = sum(true)? as u128;
+    Ok(stakes
+        .iter()
+        .map(|&(bond, won)| {
+            if won && winning_bonds > 0 {
+                (pool * bond as u128 / winning_bonds) as u64
+            } else {
+                0
+            }
+        })
+        .collect())
+}
+
+/// Bond of a challenger who joined a dispute after the first one
+/// 
+/// PDA Seeds: ["challenger_record", market_id, index], where index counts
+/// the escalated challengers from 0
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ChallengerRecord {
+    /// Account discriminator
+    pub discriminator: u64,
+    
+    /// Market ID
+    pub market_id: u64,
+    
+    /// Position among the escalated challengers
+    pub index: u8,
+    
+    /// Challenger address
+    pub challenger: Pubkey,
+    
+    /// Result the challenger argues for
+    pub result: MarketResult,
+    
+    /// Bond posted (e6)
+    pub bond: u64,
+    
+    /// Challenge timestamp
+    pub created_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+    
+    /// Reserved for future use
+    pub reserved: [u8; 16],
+}
+
+impl ChallengerRecord {
+    pub const SIZE: usize = 8   // discriminator
+        + 8   // market_id
+        + 1   // index
+        + 32  // challenger
+        + 1   // result
+        + 8   // bond
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
 }
 
 // ============================================================================
@@ -1924,6 +2989,203 @@ impl AuthorizedCallers {
     }
 }
 
+// ============================================================================
+// Trader Stats
+// ============================================================================
+
+/// Cumulative trading activity of one wallet, used for volume fee tiers
+/// 
+/// PDA Seeds: ["trader_stats", wallet]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TraderStats {
+    /// Account discriminator
+    pub discriminator: u64,
+    
+    /// Trader wallet
+    pub wallet: Pubkey,
+    
+    /// Notional (USDC e6) filled through ExecuteTradeV2, as taker or maker
+    pub volume_e6: u64,
+    
+    /// Last update timestamp
+    pub updated_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+    
+    /// Reserved for future use
+    pub reserved: [u8; 32],
+}
+
+impl TraderStats {
+    pub const SIZE: usize = 8   // discriminator
+        + 32  // wallet
+        + 8   // volume_e6
+        + 8   // updated_at
+        + 1   // bump
+        + 32; // reserved
+    
+    /// Create empty stats for `wallet`
+    pub fn new(wallet: Pubkey, bump: u8, current_time: i64) -> Self {
+        Self {
+            discriminator: TRADER_STATS_DISCRIMINATOR,
+            wallet,
+            volume_e6: 0,
+            updated_at: current_time,
+            bump,
+            reserved: [0u8; 32],
+        }
+    }
+    
+    /// Add a fill of `notional_e6` to the cumulative volume
+    pub fn record_volume(&mut self, notional_e6: u64, current_time: i64) {
+        self.volume_e6 = self.volume_e6.saturating_add(notional_e6);
+        self.updated_at = current_time;
+    }
+}
+
+// ============================================================================
+// User Meta
+// ============================================================================
+
+/// Per-wallet settings that are not tied to a market
+/// 
+/// PDA Seeds: ["user_meta", wallet]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct UserMeta {
+    /// Account discriminator
+    pub discriminator: u64,
+    
+    /// User wallet
+    pub wallet: Pubkey,
+    
+    /// Referrer captured from the user's first RelayerPlaceOrderV2 that
+    /// named one; never changed afterwards
+    pub referrer: Option<Pubkey>,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+    
+    /// Reserved for future use
+    pub reserved: [u8; 32],
+}
+
+impl UserMeta {
+    pub const SIZE: usize = 8   // discriminator
+        + 32  // wallet
+        + 1 + 32 // referrer (Option<Pubkey>)
+        + 8   // created_at
+        + 1   // bump
+        + 32; // reserved
+    
+    /// Create the meta of `wallet`, referred by `referrer`
+    pub fn new(wallet: Pubkey, referrer: Option<Pubkey>, bump: u8, current_time: i64) -> Self {
+        Self {
+            discriminator: USER_META_DISCRIMINATOR,
+            wallet,
+            referrer,
+            created_at: current_time,
+            bump,
+            reserved: [0u8; 32],
+        }
+    }
+}
+
+// ============================================================================
+// Book Top
+// ============================================================================
+
+/// Best bid and ask of one outcome of a market
+/// 
+/// A hint for clients and risk checks, not an authoritative book: the
+/// relayer refreshes it with UpdateBookTop after each place/cancel, and an
+/// update is only checked against the best orders passed with it.
+/// 
+/// PDA Seeds: ["book_top", market_id, outcome]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct BookTop {
+    /// Account discriminator
+    pub discriminator: u64,
+    
+    /// Market ID
+    pub market_id: u64,
+    
+    /// Outcome the book is for
+    pub outcome: Outcome,
+    
+    /// Best bid price (e6), 0 when there are no bids
+    pub best_bid_price: u64,
+    
+    /// Shares bid at the best bid price
+    pub bid_depth: u64,
+    
+    /// Best ask price (e6), 0 when there are no asks
+    pub best_ask_price: u64,
+    
+    /// Shares offered at the best ask price
+    pub ask_depth: u64,
+    
+    /// Last update timestamp
+    pub updated_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+    
+    /// Reserved for future use
+    pub reserved: [u8; 32],
+}
+
+impl BookTop {
+    pub const SIZE: usize = 8   // discriminator
+        + 8   // market_id
+        + 1   // outcome
+        + 8   // best_bid_price
+        + 8   // bid_depth
+        + 8   // best_ask_price
+        + 8   // ask_depth
+        + 8   // updated_at
+        + 1   // bump
+        + 32; // reserved
+    
+    /// Create an empty book for `outcome` of market `market_id`
+    pub fn new(market_id: u64, outcome: Outcome, bump: u8) -> Self {
+        Self {
+            discriminator: BOOK_TOP_DISCRIMINATOR,
+            market_id,
+            outcome,
+            best_bid_price: 0,
+            bid_depth: 0,
+            best_ask_price: 0,
+            ask_depth: 0,
+            updated_at: 0,
+            bump,
+            reserved: [0u8; 32],
+        }
+    }
+    
+    /// Replace the snapshot with the given (price, depth) of each side,
+    /// `None` for an empty side; a crossed book is rejected
+    pub fn update(
+        &mut self,
+        bid: Option<(u64, u64)>,
+        ask: Option<(u64, u64)>,
+        current_time: i64,
+    ) -> Result<(), PredictionMarketError> {
+        if let (Some((bid_price, _)), Some((ask_price, _))) = (bid, ask) {
+            if bid_price >= ask_price {
+                return Err(PredictionMarketError::BookTopCrossed);
+            }
+        }
+        (self.best_bid_price, self.bid_depth) = bid.unwrap_or_default();
+        (self.best_ask_price, self.ask_depth) = ask.unwrap_or_default();
+        self.updated_at = current_time;
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1937,6 +3199,203 @@ mod tests {
     fn test_config_size() {
         assert!(PredictionMarketConfig::SIZE > 0);
         println!("PredictionMarketConfig SIZE: {}", PredictionMarketConfig::SIZE);
+        assert_eq!(PredictionMarketConfig::SIZE, 290);
+        
+        // The encoding has the same length with or without a pending admin
+        let mut config = PredictionMarketConfig::new(
+            Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(),
+            Pubkey::new_unique(), Pubkey::new_unique(), 255,
+        );
+        assert_eq!(config.try_to_vec().unwrap().len(), PredictionMarketConfig::SIZE);
+        assert_eq!(config.pending_admin_transfer(), None);
+        config.pending_admin = Pubkey::new_unique();
+        assert_eq!(config.try_to_vec().unwrap().len(), PredictionMarketConfig::SIZE);
+        assert_eq!(config.pending_admin_transfer(), Some(config.pending_admin));
+    }
+
+    #[test]
+    fn test_config_extension() {
+        let extension = ConfigExtension { market_creation_bond_e6: 7, ..ConfigExtension::default() };
+        assert_eq!(extension.try_to_vec().unwrap().len(), ConfigExtension::SIZE);
+        
+        // A config without the tail reads as zeros and can't be written to
+        let mut data = vec![0u8; PredictionMarketConfig::SIZE];
+        assert_eq!(ConfigExtension::load(&data), ConfigExtension::default());
+        assert!(extension.store(&mut data).is_err());
+        
+        data.resize(ConfigExtension::OFFSET + ConfigExtension::SIZE, 0);
+        extension.store(&mut data).unwrap();
+        assert_eq!(ConfigExtension::load(&data), extension);
+        assert_eq!(&data[..8], &[0u8; 8]);
+    }
+
+    #[test]
+    fn test_fee_schedule() {
+        let schedule = FeeSchedule { mint_bps: 10, redeem_bps: 20, trade_bps: 30, maker_rebate_bps: 5 };
+        let data = schedule.try_to_vec().unwrap();
+        assert_eq!(data.len(), FeeSchedule::SIZE);
+        assert_eq!(FeeSchedule::try_from_slice(&data).unwrap(), schedule);
+        assert_eq!(schedule.validate(), Ok(()));
+        
+        // Applied to and read back from the config and its extension
+        let mut config = PredictionMarketConfig::new(
+            Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(),
+            Pubkey::new_unique(), Pubkey::new_unique(), 255,
+        );
+        let mut extension = ConfigExtension::default();
+        assert_eq!(config.fee_schedule(&extension), FeeSchedule::default());
+        config.set_fee_schedule(&mut extension, &schedule);
+        assert_eq!(config.fee_schedule(&extension), schedule);
+        assert_eq!((config.mint_fee_bps, config.trade_fee_bps, config.maker_rebate_bps), (10, 30, 5));
+        assert_eq!(extension.redeem_fee_on(1_000_000), Ok(2_000));
+        assert_eq!(extension.try_to_vec().unwrap().len(), ConfigExtension::SIZE);
+    }
+
+    #[test]
+    fn test_fee_schedule_validate() {
+        let max = FeeSchedule {
+            mint_bps: MAX_FEE_SCHEDULE_BPS,
+            redeem_bps: MAX_FEE_SCHEDULE_BPS,
+            trade_bps: MAX_FEE_SCHEDULE_BPS,
+            maker_rebate_bps: MAX_FEE_SCHEDULE_BPS,
+        };
+        assert_eq!(max.validate(), Ok(()));
+        
+        for over in [
+            FeeSchedule { mint_bps: MAX_FEE_SCHEDULE_BPS + 1, ..max },
+            FeeSchedule { redeem_bps: MAX_FEE_SCHEDULE_BPS + 1, ..max },
+            FeeSchedule { trade_bps: MAX_FEE_SCHEDULE_BPS + 1, ..max },
+        ] {
+            assert_eq!(over.validate(), Err(PredictionMarketError::FeeScheduleTooHigh));
+        }
+        
+        let rebate_above_fee = FeeSchedule { trade_bps: 20, maker_rebate_bps: 21, ..FeeSchedule::default() };
+        assert_eq!(rebate_above_fee.validate(), Err(PredictionMarketError::MakerRebateTooHigh));
+    }
+
+    #[test]
+    fn test_fee_tier_discount() {
+        let mut extension = ConfigExtension::default();
+        assert!(!extension.fee_tiers_enabled());
+        assert_eq!(extension.discounted_taker_fee(50_000, u64::MAX), Ok(50_000));
+        assert_eq!(extension.try_to_vec().unwrap().len(), ConfigExtension::SIZE);
+        
+        extension.fee_tiers = [
+            FeeTier { min_volume_e6: 1_000_000_000, discount_bps: 2_000 },
+            FeeTier { min_volume_e6: 10_000_000_000, discount_bps: 5_000 },
+        ];
+        assert!(extension.fee_tiers_enabled());
+        assert_eq!(extension.fee_discount_bps(999_999_999), 0);
+        assert_eq!(extension.fee_discount_bps(1_000_000_000), 2_000);
+        assert_eq!(extension.fee_discount_bps(10_000_000_000), 5_000);
+        assert_eq!(extension.discounted_taker_fee(50_000, 1_000_000_000), Ok(40_000));
+        assert_eq!(extension.discounted_taker_fee(50_000, 10_000_000_000), Ok(25_000));
+    }
+
+    #[test]
+    fn test_trader_stats() {
+        let wallet = Pubkey::new_unique();
+        let mut stats = TraderStats::new(wallet, 254, 1000);
+        assert_eq!(stats.try_to_vec().unwrap().len(), TraderStats::SIZE);
+        
+        stats.record_volume(5_000_000, 1001);
+        stats.record_volume(u64::MAX, 1002);
+        assert_eq!(stats.volume_e6, u64::MAX);
+        assert_eq!(stats.updated_at, 1002);
+        assert_eq!(stats.wallet, wallet);
+    }
+
+    #[test]
+    fn test_user_meta_and_referral_reward() {
+        let referrer = Pubkey::new_unique();
+        let meta = UserMeta::new(Pubkey::new_unique(), Some(referrer), 253, 1000);
+        assert_eq!(meta.try_to_vec().unwrap().len(), UserMeta::SIZE);
+        assert_eq!(meta.referrer, Some(referrer));
+        
+        let mut extension = ConfigExtension::default();
+        assert_eq!(extension.referral_reward_on(50_000), Ok(0));
+        extension.referral_share_bps = 2_500;
+        assert_eq!(extension.referral_reward_on(50_000), Ok(12_500));
+        assert_eq!(extension.try_to_vec().unwrap().len(), ConfigExtension::SIZE);
+    }
+
+    #[test]
+    fn test_book_top_update() {
+        let mut book = BookTop::new(1, Outcome::Yes, 254);
+        assert_eq!(book.try_to_vec().unwrap().len(), BookTop::SIZE);
+        
+        book.update(Some((400_000, 10)), Some((450_000, 5)), 100).unwrap();
+        assert_eq!((book.best_bid_price, book.bid_depth), (400_000, 10));
+        assert_eq!((book.best_ask_price, book.ask_depth), (450_000, 5));
+        
+        // An emptied side reads as zero
+        book.update(Some((420_000, 3)), None, 200).unwrap();
+        assert_eq!((book.best_ask_price, book.ask_depth, book.updated_at), (0, 0, 200));
+        
+        assert_eq!(
+            book.update(Some((450_000, 1)), Some((450_000, 1)), 300),
+            Err(PredictionMarketError::BookTopCrossed)
+        );
+        assert_eq!(book.best_bid_price, 420_000);
+    }
+
+    #[test]
+    fn test_resolution_source_slot() {
+        let source = ResolutionSource::Switchboard {
+            feed: Pubkey::new_unique(),
+            operator: ComparisonOperator::GreaterOrEqual,
+            threshold: i128::MAX,
+        };
+        assert!(source.try_to_vec().unwrap().len() <= ResolutionSource::SIZE);
+        let pyth = ResolutionSource::Pyth { feed: Pubkey::new_unique(), threshold: i128::MAX };
+        assert!(pyth.try_to_vec().unwrap().len() <= ResolutionSource::SIZE);
+        
+        // Markets without the slot, or with a zeroed one, resolve manually
+        let mut data = vec![0u8; MarketExtension::OFFSET + MarketExtension::SIZE];
+        assert_eq!(ResolutionSource::load(&data), ResolutionSource::Manual);
+        assert!(source.store(&mut data).is_err());
+        
+        data.resize(ResolutionSource::OFFSET + ResolutionSource::SIZE, 0);
+        assert_eq!(ResolutionSource::load(&data), ResolutionSource::Manual);
+        source.store(&mut data).unwrap();
+        assert_eq!(ResolutionSource::load(&data), source);
+        assert_eq!(MarketExtension::load(&data), MarketExtension::default());
+    }
+
+    #[test]
+    fn test_comparison_operator_holds() {
+        use std::cmp::Ordering;
+        assert!(ComparisonOperator::GreaterThan.holds(Ordering::Greater));
+        assert!(!ComparisonOperator::GreaterThan.holds(Ordering::Equal));
+        assert!(ComparisonOperator::GreaterOrEqual.holds(Ordering::Equal));
+        assert!(!ComparisonOperator::GreaterOrEqual.holds(Ordering::Less));
+        assert!(ComparisonOperator::LessThan.holds(Ordering::Less));
+        assert!(!ComparisonOperator::LessThan.holds(Ordering::Equal));
+        assert!(ComparisonOperator::LessOrEqual.holds(Ordering::Equal));
+        assert!(!ComparisonOperator::LessOrEqual.holds(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_config_check_order_minimum() {
+        let mut config = PredictionMarketConfig::new(
+            Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(),
+            Pubkey::new_unique(), Pubkey::new_unique(), 255,
+        );
+        // Both floors disabled by default
+        assert!(config.check_order_minimum(1, 1).is_ok());
+        
+        config.min_order_size = 2_000_000;
+        config.min_notional_e6 = 1_000_000;
+        // 2 shares at 0.50 = 1 USDC sits exactly on both floors
+        assert!(config.check_order_minimum(2_000_000, 500_000).is_ok());
+        assert_eq!(
+            config.check_order_minimum(1_999_999, 900_000),
+            Err(PredictionMarketError::OrderBelowMinimum)
+        );
+        assert_eq!(
+            config.check_order_minimum(2_000_000, 499_999),
+            Err(PredictionMarketError::OrderBelowMinimum)
+        );
     }
 
     #[test]
@@ -2010,17 +3469,631 @@ mod tests {
         let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
         
         // Add 100 YES tokens at $0.50
-        position.add_tokens(Outcome::Yes, 100, 500_000, 1001);
+        position.add_tokens(Outcome::Yes, 100, 500_000, 1001).unwrap();
         assert_eq!(position.yes_amount, 100);
         assert_eq!(position.yes_avg_cost, 500_000);
         
         // Add 50 more YES tokens at $0.60
-        position.add_tokens(Outcome::Yes, 50, 600_000, 1002);
+        position.add_tokens(Outcome::Yes, 50, 600_000, 1002).unwrap();
         assert_eq!(position.yes_amount, 150);
         // Weighted average: (100 * 0.5 + 50 * 0.6) / 150 = 0.533...
         assert!(position.yes_avg_cost > 500_000 && position.yes_avg_cost < 600_000);
     }
 
+    #[test]
+    fn test_position_add_tokens_large_amounts() {
+        // Each product below is close to u64::MAX and their sum exceeds it
+        let amount = u64::MAX / PRICE_PRECISION;
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::Yes, amount, 900_000, 1001).unwrap();
+        position.add_tokens(Outcome::Yes, amount, 999_999, 1002).unwrap();
+
+        assert_eq!(position.yes_amount, 2 * amount);
+        assert_eq!(position.yes_avg_cost, 949_999);
+        let expected_cost =
+            (amount as u128 * 900_000 + amount as u128 * 999_999) / PRICE_PRECISION as u128;
+        assert!((position.total_cost_e6 as u128).abs_diff(expected_cost) <= 1);
+
+        assert_eq!(
+            position.add_tokens(Outcome::Yes, u64::MAX, 500_000, 1003),
+            Err(PredictionMarketError::ArithmeticOverflow)
+        );
+        assert_eq!(position.yes_amount, 2 * amount);
+
+        let mut multi = MultiOutcomePosition::new(1, 3, Pubkey::new_unique(), 255, 1000);
+        multi.add_tokens(1, amount, 900_000, 1001).unwrap();
+        multi.add_tokens(1, amount, 999_999, 1002).unwrap();
+        assert_eq!(multi.holdings[1], 2 * amount);
+        assert_eq!(multi.avg_costs[1], 949_999);
+        assert_eq!(
+            multi.add_tokens(1, u64::MAX, 500_000, 1003),
+            Err(PredictionMarketError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_position_redeem_complete_set() {
+        // Minted at par: no PnL, even for amounts that do not split evenly
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::Yes, 7, 500_000, 1001).unwrap();
+        position.add_tokens(Outcome::No, 7, 500_000, 1001).unwrap();
+        assert_eq!(position.redeem_complete_set(7, 1002), 0);
+        assert_eq!((position.yes_amount, position.no_amount), (0, 0));
+        assert_eq!(position.realized_pnl, 0);
+        
+        // Bought YES at $0.60 and NO at $0.30: each set gains $0.10
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::Yes, 100_000_000, 600_000, 1001).unwrap();
+        position.add_tokens(Outcome::No, 100_000_000, 300_000, 1001).unwrap();
+        assert_eq!(position.redeem_complete_set(40_000_000, 1002), 4_000_000);
+        assert_eq!((position.yes_amount, position.no_amount), (60_000_000, 60_000_000));
+        
+        // Bought YES at $0.70 and NO at $0.45: each set loses $0.15
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::Yes, 100_000_000, 700_000, 1001).unwrap();
+        position.add_tokens(Outcome::No, 100_000_000, 450_000, 1001).unwrap();
+        assert_eq!(position.redeem_complete_set(100_000_000, 1002), -15_000_000);
+        assert_eq!(position.realized_pnl, -15_000_000);
+    }
+
+    #[test]
+    fn test_position_lock_unlock_shares() {
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::Yes, 100, 500_000, 1001).unwrap();
+        position.add_tokens(Outcome::No, 40, 500_000, 1001).unwrap();
+
+        // Lock 60 YES for a Sell order
+        assert!(position.lock_shares(Outcome::Yes, 60).is_ok());
+        assert_eq!(position.locked(Outcome::Yes), 60);
+        assert_eq!(position.available(Outcome::Yes), 40);
+        assert_eq!(position.available(Outcome::No), 40);
+
+        // Cancel the order: unlock restores availability
+        assert!(position.unlock_shares(Outcome::Yes, 60).is_ok());
+        assert_eq!(position.yes_locked, 0);
+        assert_eq!(position.available_yes(), 100);
+        assert_eq!(position.yes_amount, 100);
+
+        // Unlocking more than locked fails
+        assert!(position.unlock_shares(Outcome::No, 1).is_err());
+    }
+
+    #[test]
+    fn test_position_over_lock_fails() {
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::No, 50, 400_000, 1001).unwrap();
+
+        assert!(position.lock_shares(Outcome::No, 30).is_ok());
+        // Only 20 available now
+        assert!(position.lock_shares(Outcome::No, 21).is_err());
+        assert_eq!(position.no_locked, 30);
+        assert!(position.lock_shares(Outcome::No, 20).is_ok());
+        assert_eq!(position.available_no(), 0);
+
+        // No YES holdings at all
+        assert!(position.lock_shares(Outcome::Yes, 1).is_err());
+    }
+
+    #[test]
+    fn test_position_consume_locked_shares() {
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::Yes, 100, 500_000, 1001).unwrap();
+        assert!(position.lock_shares(Outcome::Yes, 80).is_ok());
+
+        // Fill 50 of the locked shares at $0.60
+        assert!(position.consume_locked_shares(Outcome::Yes, 50, 600_000, 1002).is_ok());
+        assert_eq!(position.yes_amount, 50);
+        assert_eq!(position.yes_locked, 30);
+        assert_eq!(position.available_yes(), 20);
+        // PnL: 50 * (0.60 - 0.50) = 5
+        assert_eq!(position.realized_pnl, 5);
+
+        // Cannot consume more than is locked
+        assert!(position.consume_locked_shares(Outcome::Yes, 31, 600_000, 1003).is_err());
+        assert_eq!(position.yes_amount, 50);
+    }
+
+    #[test]
+    fn test_position_consume_locked_for_proceeds() {
+        let mut position = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        position.add_tokens(Outcome::Yes, 100_000_000, 400_000, 1001).unwrap();
+        assert!(position.lock_shares(Outcome::Yes, 80_000_000).is_ok());
+
+        // Sell 50 shares at $0.65 against a $0.40 cost basis
+        let proceeds = position
+            .consume_locked_for_proceeds(Outcome::Yes, 50_000_000, 650_000, 1002)
+            .unwrap();
+        assert_eq!(proceeds, 50_000_000 * 650_000 / PRICE_PRECISION);
+        let cost_basis = 50_000_000 * 400_000 / PRICE_PRECISION;
+        assert_eq!(position.realized_pnl, proceeds as i64 - cost_basis as i64);
+        assert_eq!(position.yes_locked, 30_000_000);
+
+        // Over-consuming locked shares is rejected without paying proceeds
+        assert_eq!(
+            position.consume_locked_for_proceeds(Outcome::Yes, 30_000_001, 650_000, 1003),
+            Err(PredictionMarketError::InsufficientPosition)
+        );
+        assert_eq!(position.yes_amount, 50_000_000);
+    }
+
+    #[test]
+    fn test_market_realized_pnl_rollup() {
+        let mut market = Market {
+            discriminator: MARKET_DISCRIMINATOR,
+            market_id: 1,
+            market_type: MarketType::Binary,
+            num_outcomes: 2,
+            creator: Pubkey::new_unique(),
+            question_hash: [0u8; 32],
+            resolution_spec_hash: [0u8; 32],
+            yes_mint: Pubkey::default(),
+            no_mint: Pubkey::default(),
+            market_vault: Pubkey::default(),
+            status: MarketStatus::Active,
+            review_status: ReviewStatus::None,
+            resolution_time: 0,
+            finalization_deadline: 0,
+            final_result: None,
+            winning_outcome_index: None,
+            created_at: 1000,
+            updated_at: 1000,
+            total_minted: 0,
+            total_volume_e6: 0,
+            open_interest: 0,
+            creator_fee_bps: 0,
+            next_order_id: 1,
+            bump: 255,
+            max_tvl_e6: 0,
+            total_realized_pnl_e6: 0,
+            challenge_window_secs: 0,
+            category: MarketCategory::Other,
+            tags_hash: [0u8; 32],
+            pause_reason: 0,
+            reserved: [0u8; 2],
+        };
+        let mut alice = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        let mut bob = Position::new(1, Pubkey::new_unique(), 255, 1000);
+        alice.add_tokens(Outcome::Yes, 100_000_000, 400_000, 1001).unwrap();
+        bob.add_tokens(Outcome::No, 50_000_000, 700_000, 1001).unwrap();
+        assert!(alice.lock_shares(Outcome::Yes, 100_000_000).is_ok());
+        assert!(bob.lock_shares(Outcome::No, 50_000_000).is_ok());
+
+        // Several fills, at a gain and at a loss
+        let fills = [
+            (true, Outcome::Yes, 30_000_000, 650_000),
+            (false, Outcome::No, 20_000_000, 500_000),
+            (true, Outcome::Yes, 50_000_000, 300_000),
+            (false, Outcome::No, 30_000_000, 900_000),
+        ];
+        for (is_alice, outcome, amount, price) in fills {
+            let position = if is_alice { &mut alice } else { &mut bob };
+            let before = position.realized_pnl;
+            assert!(position.consume_locked_shares(outcome, amount, price, 1002).is_ok());
+            assert!(market.record_realized_pnl(before, position.realized_pnl).is_ok());
+        }
+
+        assert_ne!(alice.realized_pnl, 0);
+        assert_ne!(bob.realized_pnl, 0);
+        assert_eq!(market.total_realized_pnl_e6, alice.realized_pnl + bob.realized_pnl);
+
+        // Overflow is reported instead of wrapping
+        market.total_realized_pnl_e6 = i64::MAX;
+        assert_eq!(market.record_realized_pnl(0, 1), Err(PredictionMarketError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn test_market_open_interest() {
+        let mut market = Market {
+            discriminator: MARKET_DISCRIMINATOR,
+            market_id: 1,
+            market_type: MarketType::Binary,
+            num_outcomes: 2,
+            creator: Pubkey::new_unique(),
+            question_hash: [0u8; 32],
+            resolution_spec_hash: [0u8; 32],
+            yes_mint: Pubkey::default(),
+            no_mint: Pubkey::default(),
+            market_vault: Pubkey::default(),
+            status: MarketStatus::Active,
+            review_status: ReviewStatus::None,
+            resolution_time: 0,
+            finalization_deadline: 0,
+            final_result: None,
+            winning_outcome_index: None,
+            created_at: 1000,
+            updated_at: 1000,
+            total_minted: 0,
+            total_volume_e6: 0,
+            open_interest: 0,
+            creator_fee_bps: 0,
+            next_order_id: 1,
+            bump: 255,
+            max_tvl_e6: 0,
+            total_realized_pnl_e6: 0,
+            challenge_window_secs: 0,
+            category: MarketCategory::Other,
+            tags_hash: [0u8; 32],
+            pause_reason: 0,
+            reserved: [0u8; 2],
+        };
+        assert!(market.increase_open_interest(10_000_000).is_ok());
+        market.decrease_open_interest(4_000_000);
+        assert_eq!(market.open_interest, 6_000_000);
+
+        // Untracked legacy markets floor at zero instead of failing settlement
+        market.decrease_open_interest(7_000_000);
+        assert_eq!(market.open_interest, 0);
+
+        market.open_interest = u64::MAX;
+        assert_eq!(market.increase_open_interest(1), Err(PredictionMarketError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn test_market_category_serialization() {
+        // Accounts written before categories existed have zeros there
+        let mut market = Market::deserialize(&mut &[0u8; Market::SIZE][..]).unwrap();
+        assert_eq!(market.category, MarketCategory::Other);
+        assert_eq!(market.tags_hash, [0u8; 32]);
+
+        market.category = MarketCategory::Politics;
+        market.tags_hash = [9u8; 32];
+        market.final_result = Some(MarketResult::Yes);
+        market.winning_outcome_index = Some(0);
+        let data = market.try_to_vec().unwrap();
+        assert_eq!(data.len(), Market::SIZE);
+        let decoded = Market::try_from_slice(&data).unwrap();
+        assert_eq!(decoded.category, MarketCategory::Politics);
+        assert_eq!(decoded.tags_hash, [9u8; 32]);
+
+        assert!(MarketCategory::try_from_slice(&[MarketCategory::Entertainment as u8]).is_ok());
+        assert!(MarketCategory::try_from_slice(&[5]).is_err());
+    }
+
+    #[test]
+    fn test_market_pause_reason_serialization() {
+        // Accounts written before pause reasons existed read as not paused
+        let mut market = Market::deserialize(&mut &[0u8; Market::SIZE][..]).unwrap();
+        assert_eq!(market.pause_reason, 0);
+
+        market.status = MarketStatus::Paused;
+        market.pause_reason = 2;
+        market.final_result = Some(MarketResult::Yes);
+        market.winning_outcome_index = Some(0);
+        let data = market.try_to_vec().unwrap();
+        assert_eq!(data.len(), Market::SIZE);
+        let decoded = Market::try_from_slice(&data).unwrap();
+        assert_eq!(decoded.pause_reason, 2);
+        assert_eq!(decoded.summary().pause_reason, 2);
+        assert_eq!(decoded.reserved, [0u8; 2]);
+    }
+
+        #[test]
+    fn test_multi_outcome_position_lock_and_consume() {
+        let mut position = MultiOutcomePosition::new(1, 4, Pubkey::new_unique(), 255, 1000);
+        assert!(position.locked.iter().all(|&l| l == 0));
+
+        position.add_tokens(2, 100, 250_000, 1001).unwrap();
+
+        // Lock 70 of outcome 2 for a Sell order
+        assert!(position.lock_shares(2, 70).is_ok());
+        assert_eq!(position.get_locked(2), 70);
+        assert_eq!(position.available(2), 30);
+        assert!(position.lock_shares(2, 31).is_err());
+
+        // Partial fill consumes 40 locked shares
+        assert!(position.consume_locked_shares(2, 40, 300_000, 1002).is_ok());
+        assert_eq!(position.holdings[2], 60);
+        assert_eq!(position.locked[2], 30);
+        assert_eq!(position.available(2), 30);
+
+        // Cancel the rest of the order
+        assert!(position.unlock_shares(2, 30).is_ok());
+        assert_eq!(position.locked[2], 0);
+        assert_eq!(position.available(2), 60);
+
+        // Other outcomes untouched, out-of-range index rejected
+        assert_eq!(position.holdings[1], 0);
+        assert!(position.consume_locked_shares(2, 1, 300_000, 1003).is_err());
+        assert!(position.lock_shares(MAX_OUTCOMES as u8, 1).is_err());
+    }
+
+    #[test]
+    fn test_multi_outcome_position_clear_holdings_bounded() {
+        let mut position = MultiOutcomePosition::new(1, 3, Pubkey::new_unique(), 255, 1000);
+        for i in 0..3u8 {
+            position.add_tokens(i, 10, 300_000, 1001).unwrap();
+        }
+        assert!(position.lock_shares(1, 5).is_ok());
+        // Sentinels beyond num_outcomes must survive settlement
+        position.holdings[3] = 7;
+        position.locked[MAX_OUTCOMES - 1] = 9;
+
+        assert!(position.clear_holdings().is_ok());
+        assert!(position.is_empty());
+        assert!(position.holdings[..3].iter().all(|&h| h == 0));
+        assert!(position.locked[..3].iter().all(|&l| l == 0));
+        assert_eq!(position.holdings[3], 7);
+        assert_eq!(position.locked[MAX_OUTCOMES - 1], 9);
+
+        // num_outcomes beyond MAX_OUTCOMES is rejected without touching anything
+        position.num_outcomes = MAX_OUTCOMES as u8 + 1;
+        assert_eq!(position.outcome_count(), None);
+        assert!(position.clear_holdings().is_err());
+        assert_eq!(position.holdings[3], 7);
+    }
+
+    #[test]
+    fn test_split_dispute_pool() {
+        // A single winner takes everything
+        assert_eq!(split_dispute_pool(&[(5, true), (3, false)]), Ok(vec![8, 0]));
+        // Winners share pro rata; the remainder of the division is forfeited
+        assert_eq!(
+            split_dispute_pool(&[(10, false), (10, true), (20, true)]),
+            Ok(vec![0, 13, 26])
+        );
+        // Nobody matched: all forfeited
+        assert_eq!(split_dispute_pool(&[(5, false), (3, false)]), Ok(vec![0, 0]));
+        assert_eq!(
+            split_dispute_pool(&[(u64::MAX, true), (1, false)]),
+            Err(PredictionMarketError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_oracle_proposal_validate_finalize() {
+        let mut proposal = OracleProposal {
+            discriminator: ORACLE_PROPOSAL_DISCRIMINATOR,
+            market_id: 1,
+            proposer: Pubkey::new_unique(),
+            proposed_result: MarketResult::Yes,
+            status: ProposalStatus::Pending,
+            proposed_at: 1000,
+            challenge_deadline: 2000,
+            bond_amount: 100_000_000,
+            challenger: None,
+            challenger_result: None,
+            challenger_bond: 0,
+            bump: 255,
+            original_challenge_deadline: 2000,
+            challenge_count: 0,
+            proposed_outcome_index: None,
+            proposal_resolution_deadline: 0,
+            escalated_challengers: 0,
+            escalated_bonds_e6: 0,
+            reserved: [0u8; 4],
+        };
+        
+        assert_eq!(proposal.validate_finalize(1999), Err(PredictionMarketError::ChallengeWindowNotExpired));
+        assert_eq!(proposal.validate_finalize(2000), Ok(()));
+        
+        // Disputed proposal is rejected with the specific error, even past the deadline
+        proposal.status = ProposalStatus::Disputed;
+        assert_eq!(proposal.validate_finalize(1999), Err(PredictionMarketError::ProposalUnderDispute));
+        assert_eq!(proposal.validate_finalize(5000), Err(PredictionMarketError::ProposalUnderDispute));
+        
+        proposal.status = ProposalStatus::Finalized;
+        assert_eq!(proposal.validate_finalize(5000), Err(PredictionMarketError::CannotFinalize));
+    }
+
+    fn test_order(side: OrderSide, outcome: Outcome, outcome_index: u8) -> Order {
+        Order {
+            discriminator: ORDER_DISCRIMINATOR,
+            order_id: 1,
+            market_id: 1,
+            owner: Pubkey::new_unique(),
+            side,
+            outcome,
+            outcome_index,
+            price: 500_000,
+            amount: 100,
+            filled_amount: 0,
+            status: OrderStatus::Open,
+            order_type: OrderType::GTC,
+            expiration_time: None,
+            created_at: 1000,
+            updated_at: 1000,
+            bump: 255,
+            escrow_token_account: None,
+            locks_released: false,
+            margin_locked: false,
+            locked_margin_e6: 0,
+            reduce_only: false,
+            reserved: [0u8; 19],
+        }
+    }
+
+    #[test]
+    fn test_order_is_same_outcome() {
+        // Binary market
+        let buy_yes = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        let sell_yes = test_order(OrderSide::Sell, Outcome::Yes, 0);
+        let sell_no = test_order(OrderSide::Sell, Outcome::No, 1);
+        assert!(buy_yes.is_same_outcome(&sell_yes));
+        assert!(!buy_yes.is_same_outcome(&sell_no));
+        
+        // Multi-outcome: same Outcome mapping, different index must not cross
+        let buy_idx2 = test_order(OrderSide::Buy, Outcome::Yes, 2);
+        let sell_idx3 = test_order(OrderSide::Sell, Outcome::Yes, 3);
+        let sell_idx2 = test_order(OrderSide::Sell, Outcome::Yes, 2);
+        assert!(!buy_idx2.is_same_outcome(&sell_idx3));
+        assert!(buy_idx2.is_same_outcome(&sell_idx2));
+    }
+
+    #[test]
+    fn test_order_sync_binary_outcome_index() {
+        // Legacy NO order created before outcome_index existed
+        let mut order = test_order(OrderSide::Buy, Outcome::No, 0);
+        assert!(order.sync_binary_outcome_index());
+        assert_eq!(order.outcome_index, 1);
+        
+        // Already in sync: no change
+        assert!(!order.sync_binary_outcome_index());
+        let mut yes = test_order(OrderSide::Sell, Outcome::Yes, 0);
+        assert!(!yes.sync_binary_outcome_index());
+        assert_eq!(yes.outcome_index, 0);
+    }
+
+    #[test]
+    fn test_order_record_fill_time_in_force() {
+        // GTC: partial fill stays on the book
+        let mut gtc = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        assert_eq!(gtc.record_fill(40, 0, 2000), Ok(0));
+        assert_eq!(gtc.status, OrderStatus::PartialFilled);
+        assert_eq!(gtc.updated_at, 2000);
+
+        // IOC: unfilled remainder is cancelled
+        let mut ioc = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        ioc.order_type = OrderType::IOC;
+        assert_eq!(ioc.record_fill(40, 0, 2000), Ok(60));
+        assert_eq!(ioc.filled_amount, 40);
+        assert_eq!(ioc.status, OrderStatus::Cancelled);
+
+        // FOK: only a complete fill is accepted
+        let mut fok = test_order(OrderSide::Sell, Outcome::Yes, 0);
+        fok.order_type = OrderType::FOK;
+        assert_eq!(fok.check_fill_or_kill(99), Err(PredictionMarketError::FillOrKillNotFilled));
+        assert_eq!(fok.check_fill_or_kill(100), Ok(()));
+        assert_eq!(fok.record_fill(100, 0, 2000), Ok(0));
+        assert_eq!(fok.status, OrderStatus::Filled);
+
+        // Market: same as FOK, with its own error
+        let mut market = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        market.order_type = OrderType::Market;
+        assert_eq!(market.check_fill_or_kill(99), Err(PredictionMarketError::MarketOrderNotFilled));
+        assert_eq!(market.check_fill_or_kill(100), Ok(()));
+    }
+
+    #[test]
+    fn test_order_is_closable() {
+        let mut order = test_order(OrderSide::Sell, Outcome::Yes, 0);
+        assert!(!order.is_closable());
+
+        order.status = OrderStatus::Expired;
+        assert!(!order.is_closable());
+        order.locks_released = true;
+        assert!(order.is_closable());
+
+        // A filled legacy order still references its escrow token account
+        order.status = OrderStatus::Filled;
+        order.escrow_token_account = Some(Pubkey::new_unique());
+        assert!(!order.is_closable());
+        order.status = OrderStatus::Cancelled;
+        assert!(order.is_closable());
+
+        // Margin still tracked on the order keeps it open
+        order.locked_margin_e6 = 1;
+        assert!(!order.is_closable());
+    }
+
+    #[test]
+    fn test_order_locked_margin_tracks_fills() {
+        let mut order = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        order.amount = 10_000_000;
+        order.margin_locked = true;
+        order.locked_margin_e6 = 5_000_000;
+
+        // At the order price the lock follows the remaining amount exactly
+        for fill in [1_000_000, 2_500_000, 3_000_000] {
+            order.record_fill(fill, 0, 2000).unwrap();
+            assert_eq!(order.settle_locked_margin(fill, order.calculate_cost(fill), 0), Ok(0));
+            assert_eq!(order.locked_margin_e6, order.calculate_cost(order.remaining_amount()));
+        }
+
+        // A fill below the limit returns the difference
+        order.record_fill(1_000_000, 0, 2000).unwrap();
+        assert_eq!(order.settle_locked_margin(1_000_000, 400_000, 0), Ok(100_000));
+        assert_eq!(order.remaining_locked_margin(), 1_250_000);
+
+        // Filling the rest leaves nothing locked
+        order.record_fill(2_500_000, 0, 2000).unwrap();
+        assert_eq!(order.settle_locked_margin(2_500_000, 1_250_000, 0), Ok(0));
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.locked_margin_e6, 0);
+    }
+
+    #[test]
+    fn test_order_locked_margin_keeps_fee_headroom() {
+        // 10 @ 0.50 with a 1% taker fee locks 5 USDC + 0.05 USDC
+        let mut order = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        order.amount = 10_000_000;
+        order.margin_locked = true;
+        order.locked_margin_e6 = buy_margin(order.amount, order.price, 100).unwrap();
+        assert_eq!(order.locked_margin_e6, 5_050_000);
+        assert_eq!(buy_amount_for_margin(5_050_000, order.price, 100), Ok(order.amount));
+
+        // A fill at the limit paying the full fee keeps the rest's headroom
+        order.record_fill(4_000_000, 0, 2000).unwrap();
+        assert_eq!(order.settle_locked_margin(4_000_000, 2_020_000, 100), Ok(0));
+        assert_eq!(order.locked_margin_e6, 3_030_000);
+
+        // A fill at 0.40 returns the price improvement and its fee share
+        order.record_fill(2_000_000, 0, 2000).unwrap();
+        assert_eq!(order.settle_locked_margin(2_000_000, 808_000, 100), Ok(202_000));
+        assert_eq!(order.locked_margin_e6, 2_020_000);
+    }
+
+    #[test]
+    fn test_order_locked_margin_never_over_releases() {
+        // The Vault locked less than the full cost at placement
+        let mut order = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        order.amount = 10_000_000;
+        order.locked_margin_e6 = 4_000_000;
+
+        order.record_fill(4_000_000, 0, 2000).unwrap();
+        assert_eq!(order.settle_locked_margin(4_000_000, 1_600_000, 0), Ok(0));
+        assert_eq!(order.remaining_locked_margin(), 2_400_000);
+
+        // A debit above what is locked is rejected
+        order.record_fill(1_000_000, 0, 2000).unwrap();
+        assert_eq!(
+            order.settle_locked_margin(1_000_000, 2_500_000, 0),
+            Err(PredictionMarketError::InsufficientFunds)
+        );
+
+        // Orders placed before the field existed fall back to the order price
+        let mut legacy = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        legacy.amount = 10_000_000;
+        legacy.record_fill(4_000_000, 0, 2000).unwrap();
+        assert_eq!(legacy.remaining_locked_margin(), 3_000_000);
+        assert_eq!(legacy.settle_locked_margin(4_000_000, 1_600_000, 0), Ok(400_000));
+        assert_eq!(legacy.locked_margin_e6, 3_000_000);
+    }
+
+    #[test]
+    fn test_order_record_fill_near_u64_max() {
+        let mut order = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        order.amount = u64::MAX;
+        order.filled_amount = u64::MAX - 5;
+        order.status = OrderStatus::PartialFilled;
+
+        // A fill that would wrap is rejected and leaves the order untouched
+        assert_eq!(order.record_fill(6, 0, 2000), Err(PredictionMarketError::ArithmeticOverflow));
+        assert_eq!(order.filled_amount, u64::MAX - 5);
+        assert_eq!(order.status, OrderStatus::PartialFilled);
+
+        assert_eq!(order.record_fill(5, 0, 2000), Ok(0));
+        assert_eq!(order.filled_amount, u64::MAX);
+        assert_eq!(order.status, OrderStatus::Filled);
+    }
+
+    #[test]
+    fn test_order_record_fill_cancels_dust() {
+        // 95 of 100 filled leaves 5, below the minimum of 10
+        let mut order = test_order(OrderSide::Buy, Outcome::Yes, 0);
+        assert_eq!(order.cancelled_remainder(95, 10), 5);
+        assert_eq!(order.record_fill(95, 10, 2000), Ok(5));
+        assert_eq!(order.status, OrderStatus::Cancelled);
+
+        // A remainder at the minimum stays on the book
+        let mut order = test_order(OrderSide::Sell, Outcome::Yes, 0);
+        assert_eq!(order.record_fill(90, 10, 2000), Ok(0));
+        assert_eq!(order.status, OrderStatus::PartialFilled);
+
+        // A complete fill leaves nothing to cancel
+        assert_eq!(order.record_fill(10, 10, 2001), Ok(0));
+        assert_eq!(order.status, OrderStatus::Filled);
+    }
+
     #[test]
     fn test_order_calculate_cost() {
         let order = Order {
@@ -2041,7 +4114,11 @@ mod tests {
             updated_at: 1000,
             bump: 255,
             escrow_token_account: None,
-            reserved: [0u8; 30],
+            locks_released: false,
+            margin_locked: false,
+            locked_margin_e6: 0,
+            reduce_only: false,
+            reserved: [0u8; 19],
         };
         
         // Cost of 100 tokens at $0.65 = $65 USDC
@@ -9,11 +9,20 @@
 
 use solana_program::{
     account_info::AccountInfo,
+    clock::Clock,
     entrypoint::ProgramResult,
     program::{invoke, invoke_signed},
+    program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
+    sysvar::Sysvar,
 };
+use spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, transfer_hook, BaseStateWithExtensions, ExtensionType,
+    StateWithExtensions,
+};
+
+use crate::error::PredictionMarketError;
 
 /// SPL Token Program ID (v1)
 pub const TOKEN_PROGRAM_V1: Pubkey = spl_token::id();
@@ -40,6 +49,128 @@ pub fn get_mint_size(_token_program_id: &Pubkey) -> usize {
     spl_token::state::Mint::LEN
 }
 
+/// Token account size for `mint`
+///
+/// 165 bytes for Token-v1 mints; Token-2022 mints may require account
+/// extensions (e.g. TransferFeeAmount for transfer-fee mints).
+pub fn get_token_account_size_for_mint(mint: &AccountInfo) -> Result<usize, ProgramError> {
+    if *mint.owner != TOKEN_PROGRAM_V2 {
+        return Ok(spl_token::state::Account::LEN);
+    }
+    let data = mint.data.borrow();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let account_extensions =
+        ExtensionType::get_required_init_account_extensions(&state.get_extension_types()?);
+    ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&account_extensions)
+}
+
+/// Reject mints whose transfers this program can't perform
+///
+/// Transfer hooks need extra accounts resolved per hook program, which the
+/// fixed account lists here don't carry.
+pub fn check_supported_mint(mint: &AccountInfo) -> ProgramResult {
+    if *mint.owner != TOKEN_PROGRAM_V2 {
+        return Ok(());
+    }
+    let data = mint.data.borrow();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    if transfer_hook::get_program_id(&state).is_some() {
+        solana_program::msg!("Error: Mint {} has a transfer hook", mint.key);
+        return Err(PredictionMarketError::UnsupportedTokenExtension.into());
+    }
+    Ok(())
+}
+
+/// Fee a TransferChecked of `amount` withholds (0 without a transfer fee)
+pub fn get_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+    if *mint.owner != TOKEN_PROGRAM_V2 {
+        return Ok(0);
+    }
+    let data = mint.data.borrow();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| PredictionMarketError::ArithmeticOverflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Amount to send so that `net_amount` arrives after the transfer fee
+pub fn get_gross_amount(mint: &AccountInfo, net_amount: u64) -> Result<u64, ProgramError> {
+    if *mint.owner != TOKEN_PROGRAM_V2 {
+        return Ok(net_amount);
+    }
+    let data = mint.data.borrow();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let fee = match state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+            .ok_or(PredictionMarketError::ArithmeticOverflow)?,
+        Err(_) => 0,
+    };
+    net_amount
+        .checked_add(fee)
+        .ok_or_else(|| PredictionMarketError::ArithmeticOverflow.into())
+}
+
+/// Decimals of a Token-v1 or Token-2022 mint
+pub fn get_mint_decimals(mint: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = mint.data.borrow();
+    Ok(StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?.base.decimals)
+}
+
+/// Create `wallet`'s associated token account for `mint`, funded by `payer`
+///
+/// `account` must be the associated token address derived for the wallet,
+/// mint and token program.
+pub fn create_associated_token_account<'a>(
+    payer: &AccountInfo<'a>,
+    wallet: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    associated_token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    if *associated_token_program.key != spl_associated_token_account::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let expected = spl_associated_token_account::get_associated_token_address_with_program_id(
+        wallet.key,
+        mint.key,
+        token_program.key,
+    );
+    if *account.key != expected {
+        solana_program::msg!(
+            "Error: {} is not the associated token account {} for mint {}",
+            account.key,
+            expected,
+            mint.key
+        );
+        return Err(PredictionMarketError::InvalidTokenAccount.into());
+    }
+
+    let ix = spl_associated_token_account::instruction::create_associated_token_account(
+        payer.key,
+        wallet.key,
+        mint.key,
+        token_program.key,
+    );
+    invoke(
+        &ix,
+        &[
+            payer.clone(),
+            account.clone(),
+            wallet.clone(),
+            mint.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            associated_token_program.clone(),
+        ],
+    )
+}
+
 /// Create an InitializeAccount3 instruction (works for both v1 and v2)
 /// InitializeAccount3 doesn't require rent sysvar
 pub fn create_initialize_account3_instruction(
@@ -194,8 +325,46 @@ pub fn transfer<'a>(
     }
 }
 
+/// TransferChecked with dynamic program support
+///
+/// Token-2022 requires the mint for transfers between accounts of a
+/// transfer-fee mint; the destination receives `amount` minus the fee.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: Option<&[&[u8]]>,
+) -> ProgramResult {
+    let ix = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        source.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        &[],
+        amount,
+        get_mint_decimals(mint)?,
+    )?;
+
+    let account_infos = vec![
+        source.clone(),
+        mint.clone(),
+        destination.clone(),
+        authority.clone(),
+    ];
+
+    if let Some(seeds) = signer_seeds {
+        invoke_signed(&ix, &account_infos, &[seeds])
+    } else {
+        invoke(&ix, &account_infos)
+    }
+}
+
 /// Mint tokens with dynamic program support
-#[allow(dead_code)]
 pub fn mint_to<'a>(
     token_program: &AccountInfo<'a>,
     mint: &AccountInfo<'a>,
@@ -226,7 +395,6 @@ pub fn mint_to<'a>(
 }
 
 /// Burn tokens with dynamic program support
-#[allow(dead_code)]
 pub fn burn<'a>(
     token_program: &AccountInfo<'a>,
     account: &AccountInfo<'a>,
@@ -273,6 +441,20 @@ mod tests {
         assert_eq!(get_token_account_size(&TOKEN_PROGRAM_V2), 165);
     }
 
+    #[test]
+    fn test_token_v1_mint_has_no_transfer_fee() {
+        let key = Pubkey::new_unique();
+        let owner = TOKEN_PROGRAM_V1;
+        let mut lamports = 0;
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        let mint = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        
+        assert_eq!(get_token_account_size_for_mint(&mint).unwrap(), 165);
+        assert_eq!(get_transfer_fee(&mint, 1_000_000).unwrap(), 0);
+        assert_eq!(get_gross_amount(&mint, 1_000_000).unwrap(), 1_000_000);
+        assert!(check_supported_mint(&mint).is_ok());
+    }
+
     #[test]
     fn test_get_mint_size() {
         assert_eq!(get_mint_size(&TOKEN_PROGRAM_V1), 82);
@@ -15,7 +15,7 @@ use solana_program::{
 };
 
 use crate::error::PredictionMarketError;
-use crate::state::PRICE_PRECISION;
+use crate::state::{Market, MarketStatus, PRICE_PRECISION, SHARE_PRECISION};
 
 /// Safely deserialize account data using BorshDeserialize::deserialize
 /// This does NOT require the slice to be fully consumed, which is important
@@ -25,6 +25,35 @@ pub fn deserialize_account<T: BorshDeserialize>(data: &[u8]) -> Result<T, Progra
         .map_err(|_| ProgramError::InvalidAccountData)
 }
 
+/// Deserialize an account that may not have been initialized yet
+/// 
+/// - `Ok(None)`: account is empty or still zero-filled (discriminator == 0)
+/// - `Ok(Some(T))`: discriminator matches and data deserializes
+/// - `Err(InvalidAccountData)`: wrong discriminator or corrupt data
+pub fn try_deserialize_initialized<T: BorshDeserialize>(
+    data: &[u8],
+    discriminator: u64,
+) -> Result<Option<T>, ProgramError> {
+    if data.len() < 8 {
+        if data.iter().all(|b| *b == 0) {
+            return Ok(None);
+        }
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    let mut disc_bytes = [0u8; 8];
+    disc_bytes.copy_from_slice(&data[..8]);
+    let stored = u64::from_le_bytes(disc_bytes);
+    if stored == 0 {
+        return Ok(None);
+    }
+    if stored != discriminator {
+        return Err(PredictionMarketError::InvalidAccountData.into());
+    }
+    
+    deserialize_account::<T>(data).map(Some)
+}
+
 /// Check if a signer is authorized
 pub fn check_signer(account: &AccountInfo) -> ProgramResult {
     if !account.is_signer {
@@ -129,6 +158,30 @@ pub fn safe_div_u64(a: u64, b: u64) -> Result<u64, ProgramError> {
     Ok(a / b)
 }
 
+/// `a * b / denominator`, rounded down, with overflow-checked u128 math
+/// 
+/// The cost formula for shares at a price: `mul_div_floor(amount, price, PRICE_PRECISION)`.
+pub fn mul_div_floor(a: u64, b: u64, denominator: u64) -> Result<u64, ProgramError> {
+    if denominator == 0 {
+        return Err(PredictionMarketError::ArithmeticOverflow.into());
+    }
+    let value = (a as u128) * (b as u128) / (denominator as u128);
+    u64::try_from(value).map_err(|_| PredictionMarketError::ArithmeticOverflow.into())
+}
+
+/// `a * b / denominator`, rounded up, with overflow-checked u128 math
+/// 
+/// For amounts that must fully cover a cost. Margin locked at placement and
+/// released on cancel must round the same way, so those paths keep using
+/// `mul_div_floor`.
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64, ProgramError> {
+    if denominator == 0 {
+        return Err(PredictionMarketError::ArithmeticOverflow.into());
+    }
+    let value = ((a as u128) * (b as u128)).div_ceil(denominator as u128);
+    u64::try_from(value).map_err(|_| PredictionMarketError::ArithmeticOverflow.into())
+}
+
 /// Calculate fee amount from total and basis points
 pub fn calculate_fee(amount: u64, fee_bps: u16) -> u64 {
     ((amount as u128) * (fee_bps as u128) / 10000) as u64
@@ -164,6 +217,114 @@ pub fn validate_price_pair(yes_price: u64, no_price: u64) -> ProgramResult {
     Ok(())
 }
 
+/// Check a MatchMint price sum: `1.0 - max_deviation <= sum <= 1.0`
+/// 
+/// Exactly 1.0 is valid. Buyers may never pay more than the 1 USDC a set is
+/// backed by (`MintPriceSumTooHigh`); a sum below 1.0 is collateral they did
+/// not pay for, so it is bounded by the configured tolerance
+/// (`InvalidPricePair`).
+pub fn validate_mint_price_sum(sum: u64, max_deviation: u64) -> ProgramResult {
+    if sum > PRICE_PRECISION {
+        msg!("Mint price sum {} exceeds {}", sum, PRICE_PRECISION);
+        return Err(PredictionMarketError::MintPriceSumTooHigh.into());
+    }
+    let min_sum = PRICE_PRECISION.saturating_sub(max_deviation);
+    if sum < min_sum {
+        msg!("Price sum {} outside mint range [{}, {}]", sum, min_sum, PRICE_PRECISION);
+        return Err(PredictionMarketError::InvalidPricePair.into());
+    }
+    Ok(())
+}
+
+/// Check a MatchBurn price sum: `1.0 <= sum <= 1.0 + max_deviation`
+/// 
+/// Exactly 1.0 is valid. Sellers must ask at least the 1 USDC a burned set
+/// releases (`BurnPriceSumTooLow`); a sum above 1.0 would pay them more than
+/// that, so it is bounded by the configured tolerance (`InvalidPricePair`)
+/// and then scaled down to par with [`par_burn_prices`].
+pub fn validate_burn_price_sum(sum: u64, max_deviation: u64) -> ProgramResult {
+    if sum < PRICE_PRECISION {
+        msg!("Burn price sum {} is below {}", sum, PRICE_PRECISION);
+        return Err(PredictionMarketError::BurnPriceSumTooLow.into());
+    }
+    let max_sum = PRICE_PRECISION.saturating_add(max_deviation);
+    if sum > max_sum {
+        msg!("Price sum {} outside burn range [{}, {}]", sum, PRICE_PRECISION, max_sum);
+        return Err(PredictionMarketError::InvalidPricePair.into());
+    }
+    Ok(())
+}
+
+/// Scale a MatchBurn price pair down to par
+/// 
+/// A burned set is backed by exactly 1 USDC, so its sellers are paid at most
+/// that. A pair summing above 1.0 is scaled to sum to exactly 1.0: the YES leg
+/// rounds down and the NO leg takes the remainder. Each leg's proceeds are
+/// then floored, so the two together never exceed the burned amount.
+pub fn par_burn_prices(yes_price: u64, no_price: u64) -> Result<(u64, u64), ProgramError> {
+    let sum = safe_add_u64(yes_price, no_price)?;
+    if sum <= PRICE_PRECISION {
+        return Ok((yes_price, no_price));
+    }
+    let yes_price = mul_div_floor(yes_price, PRICE_PRECISION, sum)?;
+    Ok((yes_price, PRICE_PRECISION - yes_price))
+}
+
+/// Check a market schedule: resolution in the future, finalization after it
+pub fn validate_market_schedule(
+    resolution_time: i64,
+    finalization_deadline: i64,
+    current_time: i64,
+) -> ProgramResult {
+    if resolution_time <= current_time {
+        msg!("Error: Resolution time must be in the future");
+        return Err(PredictionMarketError::InvalidResolutionTime.into());
+    }
+    
+    if finalization_deadline <= resolution_time {
+        msg!("Error: Finalization deadline must be after resolution time");
+        return Err(PredictionMarketError::InvalidFinalizationDeadline.into());
+    }
+    Ok(())
+}
+
+/// Check that a result may be proposed for `market` at `now`
+/// 
+/// The market must still be awaiting a result (trading, halted, or with an
+/// overridable proposal) and its resolution_time must have passed, so no
+/// oracle can resolve a market before the event has happened.
+pub fn require_resolvable(market: &Market, now: i64) -> ProgramResult {
+    if !matches!(
+        market.status,
+        MarketStatus::Active
+            | MarketStatus::TradingHalted
+            | MarketStatus::AwaitingResult
+            | MarketStatus::ResultProposed
+    ) {
+        msg!("Error: Market {} cannot be resolved from {:?}", market.market_id, market.status);
+        return Err(PredictionMarketError::InvalidMarketStatus.into());
+    }
+    
+    if now < market.resolution_time {
+        msg!("Error: Resolution time not reached (now: {}, resolution: {})",
+             now, market.resolution_time);
+        return Err(PredictionMarketError::ResolutionTimeNotReached.into());
+    }
+    Ok(())
+}
+
+/// Check that a market's question and resolution spec hashes are set
+pub fn validate_market_metadata(
+    question_hash: &[u8; 32],
+    resolution_spec_hash: &[u8; 32],
+) -> ProgramResult {
+    if *question_hash == [0u8; 32] || *resolution_spec_hash == [0u8; 32] {
+        msg!("Error: Question and resolution spec hashes must be non-zero");
+        return Err(PredictionMarketError::InvalidMarketMetadata.into());
+    }
+    Ok(())
+}
+
 /// Calculate USDC cost for buying tokens
 pub fn calculate_buy_cost(amount: u64, price: u64) -> u64 {
     ((amount as u128) * (price as u128) / (PRICE_PRECISION as u128)) as u64
@@ -174,6 +335,29 @@ pub fn calculate_sell_proceeds(amount: u64, price: u64) -> u64 {
     calculate_buy_cost(amount, price)
 }
 
+/// Calculate USDC (e6) collateral required to mint `amount` complete sets
+/// 
+/// `amount` is in share base units (SHARE_PRECISION per whole share).
+/// Each whole set is backed by exactly 1 USDC (PRICE_PRECISION).
+pub fn calculate_complete_set_collateral(amount: u64) -> Result<u64, ProgramError> {
+    let collateral = (amount as u128)
+        .checked_mul(PRICE_PRECISION as u128)
+        .ok_or(PredictionMarketError::ArithmeticOverflow)?
+        / (SHARE_PRECISION as u128);
+    u64::try_from(collateral).map_err(|_| PredictionMarketError::ArithmeticOverflow.into())
+}
+
+/// Check a mint's total USDC cost against the user's optional cap
+pub fn validate_max_cost(cost: u64, max_cost_e6: Option<u64>) -> ProgramResult {
+    match max_cost_e6 {
+        Some(max_cost) if cost > max_cost => {
+            msg!("Mint cost {} exceeds maximum {}", cost, max_cost);
+            Err(PredictionMarketError::SlippageExceeded.into())
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Calculate tokens receivable for USDC amount
 pub fn calculate_tokens_for_usdc(usdc_amount: u64, price: u64) -> u64 {
     if price == 0 {
@@ -301,6 +485,20 @@ pub fn verify_escrow_pda(
     Ok(bump)
 }
 
+/// Get the (mint, owner) of a token account
+pub fn get_token_mint_and_owner(token_account: &AccountInfo) -> Result<(Pubkey, Pubkey), ProgramError> {
+    let data = token_account.try_borrow_data()?;
+    if data.len() < 64 {
+        return Err(PredictionMarketError::InvalidTokenAccount.into());
+    }
+    
+    let mint = Pubkey::try_from(&data[0..32])
+        .map_err(|_| PredictionMarketError::InvalidTokenAccount)?;
+    let owner = Pubkey::try_from(&data[32..64])
+        .map_err(|_| PredictionMarketError::InvalidTokenAccount)?;
+    Ok((mint, owner))
+}
+
 /// Get the token balance from a token account
 /// 
 /// # Arguments
@@ -324,6 +522,39 @@ pub fn get_token_balance(token_account: &AccountInfo) -> Result<u64, ProgramErro
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_try_deserialize_initialized() {
+        use borsh::BorshSerialize;
+        use crate::state::{Position, POSITION_DISCRIMINATOR, ORDER_DISCRIMINATOR};
+        
+        // Zero-length account: treated as new, no panic
+        let empty: [u8; 0] = [];
+        assert!(try_deserialize_initialized::<Position>(&empty, POSITION_DISCRIMINATOR)
+            .unwrap().is_none());
+        
+        // Freshly created, zero-filled account
+        let zeroed = vec![0u8; Position::SIZE];
+        assert!(try_deserialize_initialized::<Position>(&zeroed, POSITION_DISCRIMINATOR)
+            .unwrap().is_none());
+        
+        // Initialized account
+        let position = Position::new(7, Pubkey::new_unique(), 255, 1000);
+        let mut data = Vec::new();
+        position.serialize(&mut data).unwrap();
+        let loaded = try_deserialize_initialized::<Position>(&data, POSITION_DISCRIMINATOR)
+            .unwrap().unwrap();
+        assert_eq!(loaded.market_id, 7);
+        
+        // Wrong discriminator is corrupt, not new
+        assert!(try_deserialize_initialized::<Position>(&data, ORDER_DISCRIMINATOR).is_err());
+        
+        // Short non-zero data is corrupt
+        assert!(try_deserialize_initialized::<Position>(&[1u8, 0, 0], POSITION_DISCRIMINATOR).is_err());
+        
+        // Right discriminator but truncated body is corrupt
+        assert!(try_deserialize_initialized::<Position>(&data[..16], POSITION_DISCRIMINATOR).is_err());
+    }
+
     #[test]
     fn test_calculate_fee() {
         // 100 USDC with 1% fee = 1 USDC
@@ -342,6 +573,36 @@ mod tests {
         assert_eq!(amount_after_fee(100_000_000, 100), 99_000_000);
     }
 
+    #[test]
+    fn test_mul_div_rounding() {
+        // 3 shares (e6) at 33.3333¢ = 0.999999 USDC exactly
+        assert_eq!(mul_div_floor(3_000_000, 333_333, PRICE_PRECISION).unwrap(), 999_999);
+        assert_eq!(mul_div_ceil(3_000_000, 333_333, PRICE_PRECISION).unwrap(), 999_999);
+
+        // 1 base unit at 50¢ is half a micro-USDC
+        assert_eq!(mul_div_floor(1, 500_000, PRICE_PRECISION).unwrap(), 0);
+        assert_eq!(mul_div_ceil(1, 500_000, PRICE_PRECISION).unwrap(), 1);
+        assert_eq!(mul_div_floor(7, 650_000, PRICE_PRECISION).unwrap(), 4);
+        assert_eq!(mul_div_ceil(7, 650_000, PRICE_PRECISION).unwrap(), 5);
+
+        assert_eq!(mul_div_floor(0, 650_000, PRICE_PRECISION).unwrap(), 0);
+        assert_eq!(mul_div_ceil(0, 650_000, PRICE_PRECISION).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_mul_div_overflow() {
+        // The u128 product never wraps; only a result above u64::MAX fails
+        assert_eq!(mul_div_floor(u64::MAX, PRICE_PRECISION, PRICE_PRECISION).unwrap(), u64::MAX);
+        assert_eq!(
+            mul_div_ceil(u64::MAX, 999_999, PRICE_PRECISION).unwrap(),
+            u64::MAX - u64::MAX / PRICE_PRECISION
+        );
+        assert!(mul_div_floor(u64::MAX, 2, 1).is_err());
+        assert!(mul_div_ceil(u64::MAX, u64::MAX, 2).is_err());
+        assert!(mul_div_floor(1, 1, 0).is_err());
+        assert!(mul_div_ceil(1, 1, 0).is_err());
+    }
+
     #[test]
     fn test_calculate_buy_cost() {
         // Buy 100 tokens at $0.65 = $65
@@ -351,6 +612,18 @@ mod tests {
         assert_eq!(calculate_buy_cost(1000, 500_000), 500);
     }
 
+    #[test]
+    fn test_complete_set_collateral() {
+        // 1.0 USDC backs exactly one whole complete set
+        assert_eq!(calculate_complete_set_collateral(SHARE_PRECISION).unwrap(), 1_000_000);
+        
+        // 2.5 sets require 2.5 USDC
+        assert_eq!(calculate_complete_set_collateral(2_500_000).unwrap(), 2_500_000);
+        
+        // Smallest share unit is backed by the smallest USDC unit
+        assert_eq!(calculate_complete_set_collateral(1).unwrap(), 1);
+    }
+
     #[test]
     fn test_calculate_tokens_for_usdc() {
         // $65 at $0.65 = 100 tokens
@@ -387,6 +660,128 @@ mod tests {
         assert!(validate_price_pair(600_000, 600_000).is_err());
     }
 
+    #[test]
+    fn test_validate_mint_price_sum() {
+        // Exact sum only when no tolerance is configured
+        assert!(validate_mint_price_sum(1_000_000, 0).is_ok());
+        assert!(validate_mint_price_sum(999_999, 0).is_err());
+        
+        // Tolerance edges
+        assert!(validate_mint_price_sum(990_000, 10_000).is_ok());
+        assert!(validate_mint_price_sum(989_999, 10_000).is_err());
+        assert!(validate_mint_price_sum(1_000_000, 10_000).is_ok());
+        assert!(validate_mint_price_sum(1_000_001, 10_000).is_err());
+    }
+
+    #[test]
+    fn test_validate_burn_price_sum() {
+        // Exact sum only when no tolerance is configured
+        assert!(validate_burn_price_sum(1_000_000, 0).is_ok());
+        assert!(validate_burn_price_sum(1_000_001, 0).is_err());
+        
+        // Tolerance edges
+        assert!(validate_burn_price_sum(1_010_000, 10_000).is_ok());
+        assert!(validate_burn_price_sum(1_010_001, 10_000).is_err());
+        assert!(validate_burn_price_sum(1_000_000, 10_000).is_ok());
+        assert!(validate_burn_price_sum(999_999, 10_000).is_err());
+    }
+
+    #[test]
+    fn test_price_sum_par_boundary() {
+        // Exactly 1.0 is valid for both; one unit past par gets its own error
+        let deviation = 50_000;
+        assert_eq!(validate_mint_price_sum(999_999, deviation), Ok(()));
+        assert_eq!(validate_mint_price_sum(1_000_000, deviation), Ok(()));
+        assert_eq!(
+            validate_mint_price_sum(1_000_001, deviation),
+            Err(PredictionMarketError::MintPriceSumTooHigh.into())
+        );
+        
+        assert_eq!(
+            validate_burn_price_sum(999_999, deviation),
+            Err(PredictionMarketError::BurnPriceSumTooLow.into())
+        );
+        assert_eq!(validate_burn_price_sum(1_000_000, deviation), Ok(()));
+        assert_eq!(validate_burn_price_sum(1_000_001, deviation), Ok(()));
+        
+        // Without a tolerance only exactly 1.0 passes either check
+        assert_eq!(
+            validate_mint_price_sum(999_999, 0),
+            Err(PredictionMarketError::InvalidPricePair.into())
+        );
+        assert_eq!(
+            validate_burn_price_sum(1_000_001, 0),
+            Err(PredictionMarketError::InvalidPricePair.into())
+        );
+    }
+
+    #[test]
+    fn test_par_burn_prices() {
+        assert_eq!(par_burn_prices(600_000, 400_000).unwrap(), (600_000, 400_000));
+        assert_eq!(par_burn_prices(600_000, 600_000).unwrap(), (500_000, 500_000));
+        // 530_000 / 1.05 = 504_761.9 rounds down, NO takes the remainder
+        assert_eq!(par_burn_prices(530_000, 520_000).unwrap(), (504_761, 495_239));
+        
+        // Floored proceeds of a par pair never exceed the burned amount
+        for amount in [1, 3, 7, 999_999, 1_000_001, 123_456_789] {
+            let (yes_price, no_price) = par_burn_prices(530_000, 520_000).unwrap();
+            let proceeds = mul_div_floor(amount, yes_price, PRICE_PRECISION).unwrap()
+                + mul_div_floor(amount, no_price, PRICE_PRECISION).unwrap();
+            assert!(proceeds <= amount);
+        }
+    }
+
+    #[test]
+    fn test_validate_market_schedule() {
+        assert!(validate_market_schedule(2000, 3000, 1000).is_ok());
+        assert_eq!(
+            validate_market_schedule(1000, 3000, 1000),
+            Err(PredictionMarketError::InvalidResolutionTime.into())
+        );
+        assert_eq!(
+            validate_market_schedule(2000, 2000, 1000),
+            Err(PredictionMarketError::InvalidFinalizationDeadline.into())
+        );
+    }
+
+    #[test]
+    fn test_require_resolvable() {
+        let mut market = Market::deserialize(&mut &[0u8; Market::SIZE][..]).unwrap();
+        market.status = MarketStatus::Active;
+        market.resolution_time = 1000;
+        
+        assert_eq!(
+            require_resolvable(&market, 999),
+            Err(PredictionMarketError::ResolutionTimeNotReached.into())
+        );
+        assert!(require_resolvable(&market, 1000).is_ok());
+        assert!(require_resolvable(&market, 1001).is_ok());
+        
+        market.status = MarketStatus::TradingHalted;
+        assert!(require_resolvable(&market, 1000).is_ok());
+        
+        for status in [MarketStatus::Pending, MarketStatus::Paused, MarketStatus::Resolved, MarketStatus::Cancelled] {
+            market.status = status;
+            assert_eq!(
+                require_resolvable(&market, 1000),
+                Err(PredictionMarketError::InvalidMarketStatus.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_market_metadata() {
+        assert!(validate_market_metadata(&[1u8; 32], &[2u8; 32]).is_ok());
+        assert_eq!(
+            validate_market_metadata(&[0u8; 32], &[2u8; 32]),
+            Err(PredictionMarketError::InvalidMarketMetadata.into())
+        );
+        assert_eq!(
+            validate_market_metadata(&[1u8; 32], &[0u8; 32]),
+            Err(PredictionMarketError::InvalidMarketMetadata.into())
+        );
+    }
+
     #[test]
     fn test_safe_arithmetic() {
         // Safe add
@@ -406,6 +801,16 @@ mod tests {
         assert!(safe_div_u64(100, 0).is_err());
     }
 
+    #[test]
+    fn test_validate_max_cost() {
+        assert!(validate_max_cost(1_000_000, None).is_ok());
+        assert!(validate_max_cost(1_000_000, Some(1_000_000)).is_ok());
+        assert_eq!(
+            validate_max_cost(1_000_001, Some(1_000_000)),
+            Err(PredictionMarketError::SlippageExceeded.into())
+        );
+    }
+
     #[test]
     fn test_is_order_expired() {
         use crate::state::OrderType;
@@ -429,16 +834,16 @@ mod tests {
 
 /// Check if an order is expired based on its type and expiration time
 /// 
-/// - GTC: Never expires
+/// - GTC/PostOnly: Never expires
 /// - GTD: Expires at specified time
-/// - IOC/FOK: Handled by matching engine (returns false, let matching engine decide)
+/// - IOC/FOK/Market: Handled by matching engine (returns false, let matching engine decide)
 pub fn is_order_expired_by_type(
     order_type: crate::state::OrderType,
     expiration_time: Option<i64>,
     current_time: i64,
 ) -> bool {
     match order_type {
-        crate::state::OrderType::GTC => false,
+        crate::state::OrderType::GTC | crate::state::OrderType::PostOnly => false,
         crate::state::OrderType::GTD => {
             if let Some(exp_time) = expiration_time {
                 current_time >= exp_time
@@ -446,8 +851,10 @@ pub fn is_order_expired_by_type(
                 false
             }
         }
-        // IOC/FOK are not time-based expiration - matching engine handles them
-        crate::state::OrderType::IOC | crate::state::OrderType::FOK => false,
+        // IOC/FOK/Market are not time-based expiration - matching engine handles them
+        crate::state::OrderType::IOC
+        | crate::state::OrderType::FOK
+        | crate::state::OrderType::Market => false,
     }
 }
 
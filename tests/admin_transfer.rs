@@ -0,0 +1,69 @@
+//! Integration tests for the admin handover instructions
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{InitiateAdminTransferArgs, PredictionMarketInstruction, UpdateAdminArgs},
+    PredictionMarketConfig, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+async fn setup() -> (ProgramTestContext, Keypair) {
+    let mut program_test = program_test();
+
+    let admin = Keypair::new();
+    add_config(&mut program_test, &test_config(admin.pubkey(), Pubkey::new_unique()));
+
+    (program_test.start_with_context().await, admin)
+}
+
+fn admin_ix(signer: &Pubkey, instruction: PredictionMarketInstruction) -> Instruction {
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &instruction.try_to_vec().unwrap(),
+        vec![
+            AccountMeta::new_readonly(*signer, true),
+            AccountMeta::new(config_pda(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_update_admin_drops_pending_transfer() {
+    let (mut context, admin) = setup().await;
+    let nominee = Keypair::new();
+    let new_admin = Keypair::new();
+
+    let initiate = PredictionMarketInstruction::InitiateAdminTransfer(InitiateAdminTransferArgs {
+        new_admin: nominee.pubkey(),
+    });
+    send(&mut context, &[admin_ix(&admin.pubkey(), initiate)], &[&admin])
+        .await
+        .unwrap();
+
+    let update = PredictionMarketInstruction::UpdateAdmin(UpdateAdminArgs {
+        new_admin: new_admin.pubkey(),
+    });
+    send(&mut context, &[admin_ix(&admin.pubkey(), update)], &[&admin])
+        .await
+        .unwrap();
+
+    let config: PredictionMarketConfig = load(&mut context.banks_client, config_pda()).await;
+    assert_eq!(config.admin, new_admin.pubkey());
+    assert_eq!(config.pending_admin_transfer(), None);
+
+    // The stale nominee can no longer take over
+    let accept = PredictionMarketInstruction::AcceptAdminTransfer;
+    let result = send(&mut context, &[admin_ix(&nominee.pubkey(), accept)], &[&nominee]).await;
+    assert_custom_error(result, PredictionMarketError::NoPendingAdminTransfer);
+
+    let config: PredictionMarketConfig = load(&mut context.banks_client, config_pda()).await;
+    assert_eq!(config.admin, new_admin.pubkey());
+}
@@ -0,0 +1,287 @@
+//! Integration tests for RelayerAmendOrderV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerAmendOrderV2Args},
+    Market, Order, OrderSide, OrderStatus, OrderType, Outcome, Position, PredictionMarketConfig,
+    PredictionMarketError,
+};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 21;
+const ORDER_ID: u64 = 1;
+const AMOUNT: u64 = 10_000_000;
+const FILLED: u64 = 4_000_000;
+const PRICE: u64 = 500_000;
+const HOLDINGS: u64 = 20_000_000;
+/// Resting Sell at 0.55 opposing the Buy order
+const ASK_ORDER_ID: u64 = 2;
+const ASK_PRICE: u64 = 550_000;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: HOLDINGS,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(owner: Pubkey, side: OrderSide) -> Order {
+    Order {
+        price: PRICE,
+        amount: AMOUNT,
+        filled_amount: FILLED,
+        status: OrderStatus::PartialFilled,
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, ORDER_ID, owner, side)
+    }
+}
+
+/// Seller still holds the unfilled part of the order locked
+fn test_position(owner: Pubkey) -> Position {
+    Position {
+        yes_amount: HOLDINGS,
+        yes_locked: AMOUNT - FILLED,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+    pm_user: Pubkey,
+}
+
+async fn setup(side: OrderSide) -> Setup {
+    setup_with(side, add_recording_vault, |_, _| {}).await
+}
+
+/// `customize` adjusts the config and the order before they are stored
+async fn setup_with(
+    side: OrderSide,
+    add_vault: fn(&mut ProgramTest) -> Pubkey,
+    customize: impl FnOnce(&mut PredictionMarketConfig, &mut Order),
+) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let user = Pubkey::new_unique();
+    let mut config = test_config(relayer.pubkey(), vault_program);
+    let mut order = test_order(user, side);
+    customize(&mut config, &mut order);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    add_order(&mut program_test, &order);
+    add_order(
+        &mut program_test,
+        &Order {
+            price: ASK_PRICE,
+            ..common::test_order(MARKET_ID, ASK_ORDER_ID, Pubkey::new_unique(), OrderSide::Sell)
+        },
+    );
+    add_position(&mut program_test, &test_position(user));
+
+    let pm_user = add_pm_user(&mut program_test, &user, &vault_program);
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        user,
+        pm_user,
+    }
+}
+
+async fn try_amend(
+    setup: &mut Setup,
+    new_price: Option<u64>,
+    new_amount: Option<u64>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let pm_user = setup.pm_user;
+    try_amend_with(setup, new_price, new_amount, pm_user, None).await
+}
+
+/// `best_opposing` is appended for PostOnly orders
+async fn try_amend_with(
+    setup: &mut Setup,
+    new_price: Option<u64>,
+    new_amount: Option<u64>,
+    pm_user: Pubkey,
+    best_opposing: Option<Pubkey>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::RelayerAmendOrderV2(RelayerAmendOrderV2Args {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+        order_id: ORDER_ID,
+        new_price,
+        new_amount,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new_readonly(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(pm_user, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(best_opposing) = best_opposing {
+        metas.push(AccountMeta::new_readonly(best_opposing, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, &setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+/// Returns (locked, released) recorded by the Vault stub
+async fn recorded(setup: &mut Setup) -> (u64, u64) {
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.pm_user).await;
+    (ledger.locked, ledger.unlocked)
+}
+
+#[tokio::test]
+async fn test_amend_buy_order_increases_notional() {
+    let mut setup = setup(OrderSide::Buy).await;
+
+    try_amend(&mut setup, Some(600_000), Some(12_000_000)).await.unwrap();
+
+    // Remaining 6 @ 0.50 = 3 USDC -> 8 @ 0.60 = 4.8 USDC
+    assert_eq!(recorded(&mut setup).await, (1_800_000, 0));
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, ORDER_ID)).await;
+    assert_eq!(order.order_id, ORDER_ID);
+    assert_eq!(order.price, 600_000);
+    assert_eq!(order.amount, 12_000_000);
+    assert_eq!(order.filled_amount, FILLED);
+    assert_eq!(order.status, OrderStatus::PartialFilled);
+}
+
+#[tokio::test]
+async fn test_amend_buy_order_decreases_notional() {
+    let mut setup = setup(OrderSide::Buy).await;
+
+    try_amend(&mut setup, None, Some(6_000_000)).await.unwrap();
+
+    // Remaining 6 @ 0.50 = 3 USDC -> 2 @ 0.50 = 1 USDC
+    assert_eq!(recorded(&mut setup).await, (0, 2_000_000));
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, ORDER_ID)).await;
+    assert_eq!(order.price, PRICE);
+    assert_eq!(order.amount, 6_000_000);
+}
+
+#[tokio::test]
+async fn test_amend_below_filled_amount_rejected() {
+    let mut setup = setup(OrderSide::Buy).await;
+
+    let result = try_amend(&mut setup, None, Some(FILLED)).await;
+    assert_custom_error(result, PredictionMarketError::InvalidOrderAmount);
+    assert_eq!(recorded(&mut setup).await, (0, 0));
+}
+
+#[tokio::test]
+async fn test_amend_sell_order_locks_share_delta() {
+    let mut setup = setup(OrderSide::Sell).await;
+
+    try_amend(&mut setup, Some(550_000), Some(15_000_000)).await.unwrap();
+
+    let position_address = position_pda(MARKET_ID, &setup.user);
+    let position: Position = load(&mut setup.context.banks_client, position_address).await;
+    assert_eq!(position.yes_locked, 15_000_000 - FILLED);
+    assert_eq!(recorded(&mut setup).await, (0, 0));
+
+    // Cannot lock more shares than the seller holds
+    let result = try_amend(&mut setup, None, Some(HOLDINGS + FILLED + 1)).await;
+    assert_custom_error(result, PredictionMarketError::InsufficientPosition);
+}
+
+#[tokio::test]
+async fn test_amend_rejects_pm_user_of_other_wallet() {
+    let mut setup = setup(OrderSide::Buy).await;
+
+    let other_pm_user = pm_user_pda(&Pubkey::new_unique(), &setup.vault_program);
+    let result = try_amend_with(&mut setup, None, Some(12_000_000), other_pm_user, None).await;
+    assert_custom_error(result, PredictionMarketError::InvalidVaultAccount);
+    assert_eq!(recorded(&mut setup).await, (0, 0));
+}
+
+#[tokio::test]
+async fn test_amend_rejects_clamped_margin_lock() {
+    let mut setup = setup_with(OrderSide::Buy, add_clamping_vault, |_, _| {}).await;
+
+    // Remaining 6 @ 0.50 = 3 USDC -> 16 @ 0.60 = 9.6 USDC needs 6.6 more
+    let result = try_amend(&mut setup, Some(600_000), Some(20_000_000)).await;
+    assert_custom_error(result, PredictionMarketError::InsufficientFunds);
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, ORDER_ID)).await;
+    assert_eq!((order.price, order.amount), (PRICE, AMOUNT));
+}
+
+#[tokio::test]
+async fn test_amend_below_order_minimum_rejected() {
+    let mut setup = setup_with(OrderSide::Buy, add_recording_vault, |config, _| {
+        config.min_order_size = 5_000_000;
+    })
+    .await;
+
+    // A remainder of 4 shares is below the 5 share minimum
+    let result = try_amend(&mut setup, None, Some(FILLED + 4_000_000)).await;
+    assert_custom_error(result, PredictionMarketError::OrderBelowMinimum);
+
+    try_amend(&mut setup, None, Some(FILLED + 5_000_000)).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_amend_post_only_rejects_crossing_price() {
+    let mut setup = setup_with(OrderSide::Buy, add_recording_vault, |_, order| {
+        order.order_type = OrderType::PostOnly;
+    })
+    .await;
+    let pm_user = setup.pm_user;
+    let ask = Some(order_pda(MARKET_ID, ASK_ORDER_ID));
+
+    let result = try_amend_with(&mut setup, Some(ASK_PRICE), None, pm_user, ask).await;
+    assert_custom_error(result, PredictionMarketError::PostOnlyWouldCross);
+
+    try_amend_with(&mut setup, Some(ASK_PRICE - 10_000), None, pm_user, ask).await.unwrap();
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, ORDER_ID)).await;
+    assert_eq!(order.price, ASK_PRICE - 10_000);
+}
+
+#[tokio::test]
+async fn test_amend_reduce_only_buy_cannot_grow_past_position() {
+    let mut setup = setup_with(OrderSide::Buy, add_recording_vault, |_, order| {
+        order.reduce_only = true;
+        order.outcome = Outcome::Yes;
+    })
+    .await;
+
+    // A reduce-only YES Buy pairs with NO shares, and the owner holds none
+    let result = try_amend(&mut setup, None, Some(AMOUNT + 1_000_000)).await;
+    assert_custom_error(result, PredictionMarketError::ReduceOnlyViolation);
+
+    // Shrinking it is still allowed
+    try_amend(&mut setup, None, Some(AMOUNT - 1_000_000)).await.unwrap();
+}
@@ -0,0 +1,270 @@
+//! Integration tests for the AuthorizedCallers registry
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        AddAuthorizedCallerArgs, PredictionMarketInstruction, RemoveAuthorizedCallerArgs,
+    },
+    AuthorizedCallers, PredictionMarketError, PredictionMarketConfig, AUTHORIZED_CALLERS_SEED,
+};
+use solana_program_test::{BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+fn callers_pda() -> Pubkey {
+    Pubkey::find_program_address(&[AUTHORIZED_CALLERS_SEED], &prediction_market_program::id()).0
+}
+
+async fn setup(admin: &Pubkey) -> (BanksClient, Keypair, Hash) {
+    let program_id = prediction_market_program::id();
+    let mut program_test = program_test();
+
+    let config = test_config(*admin, Pubkey::new_unique());
+    let mut data = config.try_to_vec().unwrap();
+    data.resize(PredictionMarketConfig::SIZE, 0);
+    program_test.add_account(
+        config_pda(),
+        Account {
+            lamports: 10_000_000,
+            data,
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    program_test.add_account(
+        *admin,
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    program_test.start().await
+}
+
+/// Pre-load a program-owned config at a non-PDA address naming `admin`
+fn add_spoofed_config(program_test: &mut ProgramTest, admin: &Pubkey) -> Pubkey {
+    let spoofed = Pubkey::new_unique();
+    program_test.add_account(
+        spoofed,
+        program_account(&test_config(*admin, Pubkey::new_unique()), PredictionMarketConfig::SIZE),
+    );
+    spoofed
+}
+
+fn with_config(mut ix: Instruction, config: Pubkey) -> Instruction {
+    ix.accounts[1] = AccountMeta::new_readonly(config, false);
+    ix
+}
+
+fn add_caller_ix(admin: &Pubkey, caller: Pubkey) -> Instruction {
+    let data = PredictionMarketInstruction::AddAuthorizedCaller(AddAuthorizedCallerArgs { caller })
+        .try_to_vec()
+        .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(callers_pda(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn remove_caller_ix(admin: &Pubkey, caller: Pubkey) -> Instruction {
+    let data =
+        PredictionMarketInstruction::RemoveAuthorizedCaller(RemoveAuthorizedCallerArgs { caller })
+            .try_to_vec()
+            .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(callers_pda(), false),
+        ],
+    )
+}
+
+async fn send(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, signer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+async fn load_callers(banks_client: &mut BanksClient) -> AuthorizedCallers {
+    let account = banks_client.get_account(callers_pda()).await.unwrap().unwrap();
+    AuthorizedCallers::deserialize(&mut &account.data[..]).unwrap()
+}
+
+#[tokio::test]
+async fn test_add_authorized_callers_creates_registry() {
+    let admin = Keypair::new();
+    let (mut banks_client, payer, _) = setup(&admin.pubkey()).await;
+
+    let caller1 = Pubkey::new_unique();
+    let caller2 = Pubkey::new_unique();
+
+    // First add creates the registry
+    send(&mut banks_client, &payer, &admin, add_caller_ix(&admin.pubkey(), caller1))
+        .await
+        .unwrap();
+    let callers = load_callers(&mut banks_client).await;
+    assert_eq!(callers.count, 1);
+    assert!(callers.is_authorized(&caller1));
+
+    // Second distinct caller reuses it
+    send(&mut banks_client, &payer, &admin, add_caller_ix(&admin.pubkey(), caller2))
+        .await
+        .unwrap();
+    let callers = load_callers(&mut banks_client).await;
+    assert_eq!(callers.count, 2);
+    assert!(callers.is_authorized(&caller1));
+    assert!(callers.is_authorized(&caller2));
+
+    // Duplicate is rejected (admin pays so the replay is not deduplicated
+    // as the identical first transaction)
+    let result = send(&mut banks_client, &admin, &admin, add_caller_ix(&admin.pubkey(), caller1))
+        .await;
+    assert_custom_error(result, PredictionMarketError::CallerAlreadyAuthorized);
+    assert_eq!(load_callers(&mut banks_client).await.count, 2);
+}
+
+#[tokio::test]
+async fn test_add_authorized_caller_requires_admin() {
+    let admin = Keypair::new();
+    let (mut banks_client, payer, _) = setup(&admin.pubkey()).await;
+
+    let result = send(
+        &mut banks_client,
+        &payer,
+        &payer,
+        add_caller_ix(&payer.pubkey(), Pubkey::new_unique()),
+    )
+    .await;
+    assert!(result.is_err());
+    assert!(banks_client.get_account(callers_pda()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_remove_authorized_caller() {
+    let admin = Keypair::new();
+    let (mut banks_client, payer, _) = setup(&admin.pubkey()).await;
+
+    let caller1 = Pubkey::new_unique();
+    let caller2 = Pubkey::new_unique();
+    for caller in [caller1, caller2] {
+        send(&mut banks_client, &payer, &admin, add_caller_ix(&admin.pubkey(), caller))
+            .await
+            .unwrap();
+    }
+
+    send(&mut banks_client, &payer, &admin, remove_caller_ix(&admin.pubkey(), caller1))
+        .await
+        .unwrap();
+    let callers = load_callers(&mut banks_client).await;
+    assert_eq!(callers.count, 1);
+    assert!(!callers.is_authorized(&caller1));
+    assert!(callers.is_authorized(&caller2));
+
+    // Removing the last caller leaves an empty, still-allocated registry
+    send(&mut banks_client, &payer, &admin, remove_caller_ix(&admin.pubkey(), caller2))
+        .await
+        .unwrap();
+    let callers = load_callers(&mut banks_client).await;
+    assert_eq!(callers.count, 0);
+    assert!(!callers.is_authorized(&caller2));
+}
+
+#[tokio::test]
+async fn test_remove_authorized_caller_not_found() {
+    let admin = Keypair::new();
+    let (mut banks_client, payer, _) = setup(&admin.pubkey()).await;
+
+    let caller = Pubkey::new_unique();
+    send(&mut banks_client, &payer, &admin, add_caller_ix(&admin.pubkey(), caller))
+        .await
+        .unwrap();
+
+    let result = send(
+        &mut banks_client,
+        &payer,
+        &admin,
+        remove_caller_ix(&admin.pubkey(), Pubkey::new_unique()),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::CallerNotAuthorized);
+    assert_eq!(load_callers(&mut banks_client).await.count, 1);
+}
+
+#[tokio::test]
+async fn test_add_authorized_caller_rejects_spoofed_config() {
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+    let mut program_test = program_test();
+    add_config(&mut program_test, &test_config(admin.pubkey(), Pubkey::new_unique()));
+    let spoofed = add_spoofed_config(&mut program_test, &attacker.pubkey());
+    program_test.add_account(
+        attacker.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let ix = with_config(add_caller_ix(&attacker.pubkey(), attacker.pubkey()), spoofed);
+    let result = send(&mut banks_client, &payer, &attacker, ix).await;
+    assert_custom_error(result, PredictionMarketError::InvalidPDA);
+    assert!(banks_client.get_account(callers_pda()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_remove_authorized_caller_rejects_spoofed_config() {
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+    let mut program_test = program_test();
+    add_config(&mut program_test, &test_config(admin.pubkey(), Pubkey::new_unique()));
+    let spoofed = add_spoofed_config(&mut program_test, &attacker.pubkey());
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+    let (mut banks_client, payer, _) = program_test.start().await;
+
+    let relayer = Pubkey::new_unique();
+    send(&mut banks_client, &payer, &admin, add_caller_ix(&admin.pubkey(), relayer))
+        .await
+        .unwrap();
+
+    let ix = with_config(remove_caller_ix(&attacker.pubkey(), relayer), spoofed);
+    let result = send(&mut banks_client, &payer, &attacker, ix).await;
+    assert_custom_error(result, PredictionMarketError::InvalidPDA);
+    assert!(load_callers(&mut banks_client).await.is_authorized(&relayer));
+}
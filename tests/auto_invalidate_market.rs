@@ -0,0 +1,139 @@
+//! Integration tests for AutoInvalidateMarket
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{AutoInvalidateMarketArgs, PredictionMarketInstruction},
+    Market, MarketResult, MarketStatus, OracleProposal, PredictionMarketError, ProposalStatus,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 42;
+const FINALIZATION_DEADLINE: i64 = 1_800_000_000;
+
+fn test_market(status: MarketStatus) -> Market {
+    Market {
+        status,
+        resolution_time: FINALIZATION_DEADLINE - 86_400,
+        finalization_deadline: FINALIZATION_DEADLINE,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_proposal(status: ProposalStatus) -> OracleProposal {
+    OracleProposal {
+        status,
+        challenge_deadline: FINALIZATION_DEADLINE - 3_600,
+        bond_amount: 5_000_000,
+        original_challenge_deadline: FINALIZATION_DEADLINE - 3_600,
+        ..common::test_proposal(MARKET_ID, Pubkey::new_unique())
+    }
+}
+
+async fn setup(status: MarketStatus, proposal: Option<ProposalStatus>, now: i64) -> ProgramTestContext {
+    let mut program_test = program_test();
+
+    let config = test_config(Pubkey::new_unique(), Pubkey::new_unique());
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(status));
+    if let Some(proposal_status) = proposal {
+        add_proposal(&mut program_test, &test_proposal(proposal_status));
+    }
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, now).await;
+    context
+}
+
+/// Sends AutoInvalidateMarket signed by a fresh, unprivileged caller
+async fn auto_invalidate(context: &mut ProgramTestContext) -> Result<(), solana_program_test::BanksClientError> {
+    let caller = Keypair::new();
+    let data = PredictionMarketInstruction::AutoInvalidateMarket(AutoInvalidateMarketArgs {
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(caller.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new_readonly(proposal_pda(MARKET_ID), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &caller],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_auto_invalidate_after_deadline() {
+    let mut context = setup(MarketStatus::TradingHalted, None, FINALIZATION_DEADLINE + 1).await;
+
+    auto_invalidate(&mut context).await.unwrap();
+
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::Invalid));
+    assert_eq!(market.winning_outcome_index, None);
+}
+
+#[tokio::test]
+async fn test_auto_invalidate_disputed_proposal() {
+    let mut context = setup(
+        MarketStatus::ResultProposed,
+        Some(ProposalStatus::Disputed),
+        FINALIZATION_DEADLINE + 1,
+    )
+    .await;
+
+    auto_invalidate(&mut context).await.unwrap();
+
+    let market: Market = load(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.final_result, Some(MarketResult::Invalid));
+}
+
+#[tokio::test]
+async fn test_auto_invalidate_rejected_before_deadline() {
+    let mut context = setup(MarketStatus::Active, None, FINALIZATION_DEADLINE).await;
+
+    assert_custom_error(
+        auto_invalidate(&mut context).await,
+        PredictionMarketError::FinalizationDeadlineNotReached,
+    );
+    assert_eq!(load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await.status, MarketStatus::Active);
+}
+
+#[tokio::test]
+async fn test_auto_invalidate_rejected_with_undisputed_proposal() {
+    let mut context = setup(
+        MarketStatus::ResultProposed,
+        Some(ProposalStatus::Pending),
+        FINALIZATION_DEADLINE + 1,
+    )
+    .await;
+
+    assert_custom_error(auto_invalidate(&mut context).await, PredictionMarketError::InvalidMarketStatus);
+}
+
+#[tokio::test]
+async fn test_auto_invalidate_rejected_when_resolved() {
+    let mut context = setup(MarketStatus::Resolved, None, FINALIZATION_DEADLINE + 1).await;
+
+    assert_custom_error(auto_invalidate(&mut context).await, PredictionMarketError::MarketAlreadyResolved);
+}
@@ -0,0 +1,180 @@
+//! Integration tests for AutoResolveFromFeed against a mocked Switchboard aggregator
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{AutoResolveFromFeedArgs, PredictionMarketInstruction},
+    oracle_feed::{
+        AGGREGATOR_DISCRIMINATOR, AGGREGATOR_MIN_LEN, ROUND_NUM_SUCCESS_OFFSET,
+        ROUND_OPEN_TIMESTAMP_OFFSET, ROUND_RESULT_MANTISSA_OFFSET, ROUND_RESULT_SCALE_OFFSET,
+        SWITCHBOARD_V2_PROGRAM_ID,
+    },
+    ComparisonOperator, Market, MarketCategory, MarketResult, MarketStatus, PredictionMarketError,
+    ResolutionSource,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 44;
+const RESOLUTION_TIME: i64 = 1_700_000_000;
+/// "BTC above $65,000" scaled by 1e9
+const THRESHOLD: i128 = 65_000_000_000_000;
+
+fn test_market() -> Market {
+    Market {
+        resolution_time: RESOLUTION_TIME,
+        finalization_deadline: RESOLUTION_TIME + 86_400,
+        category: MarketCategory::Crypto,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Market account carrying `source` in its resolution source slot
+fn market_account(source: ResolutionSource) -> Account {
+    let mut account =
+        program_account(&test_market(), ResolutionSource::OFFSET + ResolutionSource::SIZE);
+    source.store(&mut account.data).unwrap();
+    account
+}
+
+/// Aggregator whose latest confirmed round opened at `opened_at` with
+/// result `mantissa / 10^scale`
+fn aggregator_account(opened_at: i64, mantissa: i128, scale: u32) -> Account {
+    let mut data = vec![0u8; AGGREGATOR_MIN_LEN];
+    data[..8].copy_from_slice(&AGGREGATOR_DISCRIMINATOR);
+    data[ROUND_NUM_SUCCESS_OFFSET..][..4].copy_from_slice(&3u32.to_le_bytes());
+    data[ROUND_OPEN_TIMESTAMP_OFFSET..][..8].copy_from_slice(&opened_at.to_le_bytes());
+    data[ROUND_RESULT_MANTISSA_OFFSET..][..16].copy_from_slice(&mantissa.to_le_bytes());
+    data[ROUND_RESULT_SCALE_OFFSET..][..4].copy_from_slice(&scale.to_le_bytes());
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner: SWITCHBOARD_V2_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn setup(source: ResolutionSource, feed: Pubkey, aggregator: Account) -> ProgramTestContext {
+    let mut program_test = program_test();
+
+    let config = test_config(Pubkey::new_unique(), Pubkey::new_unique());
+    add_config(&mut program_test, &config);
+    program_test.add_account(market_pda(MARKET_ID), market_account(source));
+    program_test.add_account(feed, aggregator);
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, RESOLUTION_TIME + 60).await;
+    context
+}
+
+fn switchboard_source(feed: Pubkey) -> ResolutionSource {
+    ResolutionSource::Switchboard {
+        feed,
+        operator: ComparisonOperator::GreaterThan,
+        threshold: THRESHOLD,
+    }
+}
+
+/// Sends AutoResolveFromFeed signed by a fresh, unprivileged caller
+async fn auto_resolve(
+    context: &mut ProgramTestContext,
+    feed: Pubkey,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let caller = Keypair::new();
+    let data = PredictionMarketInstruction::AutoResolveFromFeed(AutoResolveFromFeedArgs {
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(caller.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new_readonly(feed, false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &caller],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_auto_resolve_yes_above_threshold() {
+    let feed = Pubkey::new_unique();
+    // $65,123.45
+    let aggregator = aggregator_account(RESOLUTION_TIME + 30, 6_512_345, 2);
+    let mut context = setup(switchboard_source(feed), feed, aggregator).await;
+
+    auto_resolve(&mut context, feed).await.unwrap();
+
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::Yes));
+}
+
+#[tokio::test]
+async fn test_auto_resolve_no_at_threshold() {
+    let feed = Pubkey::new_unique();
+    // Exactly $65,000 does not satisfy GreaterThan
+    let aggregator = aggregator_account(RESOLUTION_TIME, 65_000, 0);
+    let mut context = setup(switchboard_source(feed), feed, aggregator).await;
+
+    auto_resolve(&mut context, feed).await.unwrap();
+
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::No));
+}
+
+#[tokio::test]
+async fn test_auto_resolve_rejects_round_before_resolution_time() {
+    let feed = Pubkey::new_unique();
+    let aggregator = aggregator_account(RESOLUTION_TIME - 1, 6_512_345, 2);
+    let mut context = setup(switchboard_source(feed), feed, aggregator).await;
+
+    assert_custom_error(auto_resolve(&mut context, feed).await, PredictionMarketError::StaleOracleFeed);
+    assert_eq!(load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await.status, MarketStatus::Active);
+}
+
+#[tokio::test]
+async fn test_auto_resolve_rejects_other_feed() {
+    let feed = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let aggregator = aggregator_account(RESOLUTION_TIME, 6_512_345, 2);
+    let mut context = setup(switchboard_source(feed), other, aggregator).await;
+
+    assert_custom_error(
+        auto_resolve(&mut context, other).await,
+        PredictionMarketError::ResolutionSourceMismatch,
+    );
+}
+
+#[tokio::test]
+async fn test_auto_resolve_rejects_manual_market() {
+    let feed = Pubkey::new_unique();
+    let aggregator = aggregator_account(RESOLUTION_TIME, 6_512_345, 2);
+    let mut context = setup(ResolutionSource::Manual, feed, aggregator).await;
+
+    assert_custom_error(
+        auto_resolve(&mut context, feed).await,
+        PredictionMarketError::ResolutionSourceMismatch,
+    );
+    assert_eq!(load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await.final_result, None);
+}
@@ -0,0 +1,201 @@
+//! Integration tests for RelayerBatchCancelOrdersV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerBatchCancelOrdersV2Args},
+    Market, MarketType, MultiOutcomePosition, Order, OrderSide, OrderStatus,
+    Outcome, Position, PredictionMarketError, MAX_BATCH_CANCEL_ORDERS,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 48;
+const AMOUNT: u64 = 10_000_000;
+const FILLED: u64 = 4_000_000;
+const PRICE: u64 = 500_000;
+
+/// Open Buy, partially filled Sell, filled Buy
+const BUY_ORDER: u64 = 1;
+const SELL_ORDER: u64 = 2;
+const FILLED_ORDER: u64 = 3;
+
+/// Outcome count of the multi-outcome variant; its Sell order is on the last
+const NUM_OUTCOMES: u8 = 3;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: AMOUNT,
+        next_order_id: 4,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, filled_amount: u64, status: OrderStatus) -> Order {
+    Order {
+        price: PRICE,
+        amount: AMOUNT,
+        filled_amount,
+        status,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    pm_user: Pubkey,
+    /// The user's Position, or MultiOutcomePosition on a multi-outcome market
+    position: Pubkey,
+}
+
+async fn setup() -> Setup {
+    setup_with(MarketType::Binary).await
+}
+
+async fn setup_with(market_type: MarketType) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    let multi_outcome = market_type == MarketType::MultiOutcome;
+    add_market(&mut program_test, &Market {
+        market_type,
+        num_outcomes: if multi_outcome { NUM_OUTCOMES } else { 2 },
+        ..test_market()
+    });
+
+    let user = Pubkey::new_unique();
+    let sell_outcome_index = if multi_outcome { NUM_OUTCOMES - 1 } else { 0 };
+    for order in [
+        test_order(BUY_ORDER, user, OrderSide::Buy, 0, OrderStatus::Open),
+        Order {
+            outcome_index: sell_outcome_index,
+            ..test_order(SELL_ORDER, user, OrderSide::Sell, FILLED, OrderStatus::PartialFilled)
+        },
+        test_order(FILLED_ORDER, user, OrderSide::Buy, AMOUNT, OrderStatus::Filled),
+    ] {
+        add_order(&mut program_test, &order);
+    }
+
+    // The seller still holds the unfilled part of the Sell order locked
+    let position = if multi_outcome {
+        let mut position = MultiOutcomePosition::new(MARKET_ID, NUM_OUTCOMES, user, 255, 0);
+        position.holdings[sell_outcome_index as usize] = AMOUNT;
+        position.locked[sell_outcome_index as usize] = AMOUNT - FILLED;
+        let address = multi_position_pda(MARKET_ID, &user);
+        program_test.add_account(address, program_account(&position, MultiOutcomePosition::SIZE));
+        address
+    } else {
+        let mut position = Position::new(MARKET_ID, user, 255, 0);
+        position.add_tokens(Outcome::Yes, AMOUNT, PRICE, 0).unwrap();
+        position.lock_shares(Outcome::Yes, AMOUNT - FILLED).unwrap();
+        add_position(&mut program_test, &position);
+        position_pda(MARKET_ID, &user)
+    };
+
+    let pm_user = add_pm_user(&mut program_test, &user, &vault_program);
+
+    Setup { context: program_test.start_with_context().await, relayer, vault_program, pm_user, position }
+}
+
+async fn try_batch_cancel(
+    setup: &mut Setup,
+    order_ids: Vec<u64>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let mut metas = vec![
+        AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+    ];
+    for order_id in &order_ids {
+        metas.push(AccountMeta::new(order_pda(MARKET_ID, *order_id), false));
+        metas.push(AccountMeta::new(setup.position, false));
+        metas.push(AccountMeta::new(Pubkey::new_unique(), false));
+        metas.push(AccountMeta::new(setup.pm_user, false));
+    }
+    let data = PredictionMarketInstruction::RelayerBatchCancelOrdersV2(RelayerBatchCancelOrdersV2Args {
+        market_id: MARKET_ID,
+        order_ids,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, &setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+/// Returns (unlock count, total released) recorded by the Vault stub
+async fn recorded_unlocks(setup: &mut Setup) -> (u64, u64) {
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.pm_user).await;
+    (ledger.calls, ledger.unlocked)
+}
+
+#[tokio::test]
+async fn test_batch_cancel_skips_filled_order() {
+    let mut setup = setup().await;
+
+    try_batch_cancel(&mut setup, vec![BUY_ORDER, SELL_ORDER, FILLED_ORDER]).await.unwrap();
+
+    // Only the open Buy order releases margin: 10 @ 0.50 = 5 USDC
+    assert_eq!(recorded_unlocks(&mut setup).await, (1, 5_000_000));
+
+    let buy: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, BUY_ORDER)).await;
+    assert_eq!(buy.status, OrderStatus::Cancelled);
+    let sell: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, SELL_ORDER)).await;
+    assert_eq!(sell.status, OrderStatus::Cancelled);
+    assert_eq!(sell.filled_amount, FILLED);
+    let filled: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, FILLED_ORDER)).await;
+    assert_eq!(filled.status, OrderStatus::Filled);
+
+    let position: Position = load(&mut setup.context.banks_client, setup.position).await;
+    assert_eq!(position.yes_locked, 0);
+    assert_eq!(position.yes_amount, AMOUNT);
+
+    // Re-sending the batch finds nothing left to cancel
+    try_batch_cancel(&mut setup, vec![BUY_ORDER, SELL_ORDER, FILLED_ORDER]).await.unwrap();
+    assert_eq!(recorded_unlocks(&mut setup).await, (1, 5_000_000));
+}
+
+#[tokio::test]
+async fn test_batch_cancel_unlocks_multi_outcome_sell_shares() {
+    let mut setup = setup_with(MarketType::MultiOutcome).await;
+
+    try_batch_cancel(&mut setup, vec![BUY_ORDER, SELL_ORDER]).await.unwrap();
+
+    let sell: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, SELL_ORDER)).await;
+    assert_eq!(sell.status, OrderStatus::Cancelled);
+    let position: MultiOutcomePosition = load(&mut setup.context.banks_client, setup.position).await;
+    let last = (NUM_OUTCOMES - 1) as usize;
+    assert_eq!((position.holdings[last], position.locked[last]), (AMOUNT, 0));
+    assert_eq!(recorded_unlocks(&mut setup).await, (1, 5_000_000));
+}
+
+#[tokio::test]
+async fn test_batch_cancel_size_limits() {
+    let mut setup = setup().await;
+
+    for order_ids in [vec![], vec![BUY_ORDER; MAX_BATCH_CANCEL_ORDERS + 1]] {
+        let result = try_batch_cancel(&mut setup, order_ids).await;
+        assert_custom_error(result, PredictionMarketError::BatchTooLarge);
+    }
+    assert_eq!(recorded_unlocks(&mut setup).await, (0, 0));
+}
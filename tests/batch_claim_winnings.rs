@@ -0,0 +1,170 @@
+//! Integration tests for RelayerBatchClaimWinningsV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerBatchClaimWinningsArgs},
+    Market, MarketResult, MarketStatus, Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 13;
+const TOTAL_COST: u64 = 4_000_000;
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::Resolved,
+        resolution_time: 0,
+        final_result: Some(MarketResult::Yes),
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_position(user: Pubkey, yes_amount: u64, settled: bool) -> Position {
+    Position {
+        yes_amount,
+        no_amount: 1_000_000,
+        yes_avg_cost: 500_000,
+        no_avg_cost: 500_000,
+        total_cost_e6: TOTAL_COST,
+        settled,
+        ..common::test_position(MARKET_ID, user)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct User {
+    wallet: Pubkey,
+    yes_amount: u64,
+    settled: bool,
+    pm_user: Pubkey,
+}
+
+/// Returns (context, relayer, vault_program, users)
+async fn setup(users: &[(u64, bool)]) -> (ProgramTestContext, Keypair, Pubkey, Vec<User>) {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let users: Vec<User> = users
+        .iter()
+        .map(|&(yes_amount, settled)| {
+            let wallet = Pubkey::new_unique();
+            let pm_user = pm_user_pda(&wallet, &vault_program);
+            User { wallet, yes_amount, settled, pm_user }
+        })
+        .collect();
+    for user in &users {
+        add_position(&mut program_test, &test_position(user.wallet, user.yes_amount, user.settled));
+        add_pm_user(&mut program_test, &user.wallet, &vault_program);
+    }
+
+    (program_test.start_with_context().await, relayer, vault_program, users)
+}
+
+fn batch_claim_ix(relayer: &Pubkey, vault_program: &Pubkey, users: &[User]) -> Instruction {
+    let data = PredictionMarketInstruction::RelayerBatchClaimWinningsV2(
+        RelayerBatchClaimWinningsArgs {
+            market_id: MARKET_ID,
+            user_wallets: users.iter().map(|u| u.wallet).collect(),
+        },
+    )
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new_readonly(*relayer, true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new_readonly(vault_config_pda(vault_program), false),
+        AccountMeta::new_readonly(*vault_program, false),
+    ];
+    for user in users {
+        metas.push(AccountMeta::new(position_pda(MARKET_ID, &user.wallet), false));
+        metas.push(AccountMeta::new(user.pm_user, false));
+    }
+    Instruction::new_with_bytes(prediction_market_program::id(), &data, metas)
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    relayer: &Keypair,
+    ix: Instruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, relayer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_batch_claim_settles_three_users() {
+    let (mut context, relayer, vault_program, users) = setup(&[
+        (6_000_000, false),
+        (0, false),
+        (2_500_000, false),
+        (9_000_000, true),
+    ])
+    .await;
+
+    send(&mut context, &relayer, batch_claim_ix(&relayer.pubkey(), &vault_program, &users))
+        .await
+        .unwrap();
+
+    for user in &users[..3] {
+        // YES won: winning shares pay out 1:1, remaining cost is released
+        let ledger = vault_ledger(&mut context.banks_client, user.pm_user).await;
+        assert_eq!(ledger.settlement(), (TOTAL_COST, user.yes_amount));
+        let position = load::<Position>(&mut context.banks_client, position_pda(MARKET_ID, &user.wallet)).await;
+        assert!(position.settled);
+        assert_eq!(position.settlement_amount, user.yes_amount);
+        assert_eq!(position.yes_amount, 0);
+        assert_eq!(position.no_amount, 0);
+    }
+
+    // Already-settled user is skipped without a Vault CPI
+    assert_eq!(vault_ledger(&mut context.banks_client, users[3].pm_user).await.calls, 0);
+    let position: Position = load(&mut context.banks_client, position_pda(MARKET_ID, &users[3].wallet)).await;
+    assert_eq!(position.yes_amount, 9_000_000);
+}
+
+#[tokio::test]
+async fn test_batch_claim_rejects_oversized_batch() {
+    let (mut context, relayer, vault_program, users) = setup(&[(1_000_000, false); 9]).await;
+
+    let result =
+        send(&mut context, &relayer, batch_claim_ix(&relayer.pubkey(), &vault_program, &users))
+            .await;
+    assert_custom_error(result, PredictionMarketError::BatchTooLarge);
+    assert!(!load::<Position>(&mut context.banks_client, position_pda(MARKET_ID, &users[0].wallet)).await.settled);
+}
+
+#[tokio::test]
+async fn test_batch_claim_settles_repeated_user_once() {
+    let (mut context, relayer, vault_program, mut users) = setup(&[(6_000_000, false)]).await;
+    users.push(users[0]);
+
+    send(&mut context, &relayer, batch_claim_ix(&relayer.pubkey(), &vault_program, &users))
+        .await
+        .unwrap();
+
+    // The second entry sees the Position already settled by the first
+    let ledger = vault_ledger(&mut context.banks_client, users[0].pm_user).await;
+    assert_eq!(ledger.calls, 1);
+    assert_eq!(ledger.settlement(), (TOTAL_COST, 6_000_000));
+}
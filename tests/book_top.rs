@@ -0,0 +1,227 @@
+//! Integration tests for the UpdateBookTop best bid/ask snapshot
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerPlaceOrderV2Args, UpdateBookTopArgs},
+    BookTop, Market, Order, OrderSide,
+    OrderType, Outcome, Position, PredictionMarketError,
+    BOOK_TOP_SEED,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 57;
+/// Resting orders: a bid of 10 shares at 0.40 and an ask of 4 at 0.55
+const BID_ORDER_ID: u64 = 1;
+const ASK_ORDER_ID: u64 = 2;
+const BID_PRICE: u64 = 400_000;
+const ASK_PRICE: u64 = 550_000;
+const BID_AMOUNT: u64 = 10_000_000;
+const ASK_AMOUNT: u64 = 4_000_000;
+
+fn book_top_pda() -> Pubkey {
+    pda(&[BOOK_TOP_SEED, &MARKET_ID.to_le_bytes(), &[Outcome::Yes as u8]])
+}
+
+fn test_market() -> Market {
+    Market {
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, side: OrderSide, price: u64, amount: u64) -> Order {
+    Order {
+        price,
+        amount,
+        margin_locked: true,
+        ..common::test_order(MARKET_ID, order_id, Pubkey::new_unique(), side)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+    add_order(&mut program_test, &test_order(BID_ORDER_ID, OrderSide::Buy, BID_PRICE, BID_AMOUNT));
+    add_order(&mut program_test, &test_order(ASK_ORDER_ID, OrderSide::Sell, ASK_PRICE, ASK_AMOUNT));
+
+    let user = Pubkey::new_unique();
+    add_position(&mut program_test, &Position::new(MARKET_ID, user, 255, 0));
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        user,
+    }
+}
+
+/// UpdateBookTop with the given best orders (`None` passes an empty account)
+fn update_book_top_ix(
+    relayer: &Pubkey,
+    best_bid: Option<u64>,
+    best_ask: Option<u64>,
+    bid_depth: u64,
+    ask_depth: u64,
+) -> Instruction {
+    let data = PredictionMarketInstruction::UpdateBookTop(UpdateBookTopArgs {
+        market_id: MARKET_ID,
+        outcome: Outcome::Yes,
+        bid_depth,
+        ask_depth,
+    })
+    .try_to_vec()
+    .unwrap();
+    let order_meta = |order_id: Option<u64>| {
+        AccountMeta::new_readonly(order_id.map_or_else(Pubkey::new_unique, |id| order_pda(MARKET_ID, id)), false)
+    };
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*relayer, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new_readonly(market_pda(MARKET_ID), false),
+            AccountMeta::new(book_top_pda(), false),
+            order_meta(best_bid),
+            order_meta(best_ask),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// RelayerPlaceOrderV2 of a GTC YES bid for the user; it becomes order 3
+fn place_bid_ix(setup: &Setup, price: u64, amount: u64) -> Instruction {
+    let data = PredictionMarketInstruction::RelayerPlaceOrderV2(RelayerPlaceOrderV2Args {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+        side: OrderSide::Buy,
+        outcome: Outcome::Yes,
+        price,
+        amount,
+        order_type: OrderType::GTC,
+        expiration_time: None,
+        reduce_only: false,
+        referrer: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, 3), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+async fn send(setup: &mut Setup, instructions: &[Instruction]) -> Result<(), BanksClientError> {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+/// Returns the snapshot as ((best bid, depth), (best ask, depth))
+async fn book_top(context: &mut ProgramTestContext) -> ((u64, u64), (u64, u64)) {
+    let account = context.banks_client.get_account(book_top_pda()).await.unwrap().unwrap();
+    let book = BookTop::deserialize(&mut &account.data[..]).unwrap();
+    ((book.best_bid_price, book.bid_depth), (book.best_ask_price, book.ask_depth))
+}
+
+#[tokio::test]
+async fn test_better_bid_updates_book_top() {
+    let mut setup = setup().await;
+    let relayer = setup.relayer.pubkey();
+
+    let ix = update_book_top_ix(&relayer, Some(BID_ORDER_ID), Some(ASK_ORDER_ID), BID_AMOUNT, ASK_AMOUNT);
+    send(&mut setup, &[ix]).await.unwrap();
+    assert_eq!(book_top(&mut setup.context).await, ((BID_PRICE, BID_AMOUNT), (ASK_PRICE, ASK_AMOUNT)));
+
+    // The relayer places a better bid and refreshes the snapshot with it
+    let place = place_bid_ix(&setup, 450_000, 2_000_000);
+    let update = update_book_top_ix(&relayer, Some(3), Some(ASK_ORDER_ID), 2_000_000, ASK_AMOUNT);
+    send(&mut setup, &[place, update]).await.unwrap();
+    assert_eq!(book_top(&mut setup.context).await, ((450_000, 2_000_000), (ASK_PRICE, ASK_AMOUNT)));
+
+    // Emptying the ask side
+    let ix = update_book_top_ix(&relayer, Some(3), None, 2_000_000, 0);
+    send(&mut setup, &[ix]).await.unwrap();
+    assert_eq!(book_top(&mut setup.context).await, ((450_000, 2_000_000), (0, 0)));
+}
+
+#[tokio::test]
+async fn test_book_top_depth_must_cover_best_order() {
+    let mut setup = setup().await;
+    let relayer = setup.relayer.pubkey();
+
+    let ix = update_book_top_ix(&relayer, Some(BID_ORDER_ID), None, BID_AMOUNT - 1, 0);
+    assert_custom_error(send(&mut setup, &[ix]).await, PredictionMarketError::BookTopDepthTooLow);
+
+    // A depth without an order behind it is rejected too
+    let ix = update_book_top_ix(&relayer, None, None, 0, 1);
+    assert_custom_error(send(&mut setup, &[ix]).await, PredictionMarketError::BookTopDepthTooLow);
+}
+
+#[tokio::test]
+async fn test_book_top_rejects_crossed_book() {
+    let mut setup = setup().await;
+    let relayer = setup.relayer.pubkey();
+
+    let place = place_bid_ix(&setup, ASK_PRICE, 1_000_000);
+    send(&mut setup, &[place]).await.unwrap();
+
+    let ix = update_book_top_ix(&relayer, Some(3), Some(ASK_ORDER_ID), 1_000_000, ASK_AMOUNT);
+    assert_custom_error(send(&mut setup, &[ix]).await, PredictionMarketError::BookTopCrossed);
+}
+
+#[tokio::test]
+async fn test_book_top_rejects_order_on_wrong_side() {
+    let mut setup = setup().await;
+    let relayer = setup.relayer.pubkey();
+
+    let ix = update_book_top_ix(&relayer, Some(ASK_ORDER_ID), None, ASK_AMOUNT, 0);
+    assert_custom_error(send(&mut setup, &[ix]).await, PredictionMarketError::InvalidOrderSide);
+}
@@ -0,0 +1,172 @@
+//! Integration tests for ChallengeResult
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{ChallengeResultArgs, PredictionMarketInstruction},
+    Market, MarketResult, MarketStatus, OracleProposal,
+    PredictionMarketConfig, PredictionMarketError, ProposalStatus,
+    DISPUTE_RESOLUTION_PERIOD_SECS, VAULT_PM_USER_SEED,
+};
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 8;
+const PROPOSER_BOND: u64 = 5_000_000;
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::ResultProposed,
+        resolution_time: 0,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_proposal(proposer: Pubkey, challenge_deadline: i64) -> OracleProposal {
+    OracleProposal {
+        challenge_deadline,
+        bond_amount: PROPOSER_BOND,
+        original_challenge_deadline: challenge_deadline,
+        ..common::test_proposal(MARKET_ID, proposer)
+    }
+}
+
+fn challenge_ix(challenger: &Pubkey, vault_program: &Pubkey, result: MarketResult) -> Instruction {
+    let data = PredictionMarketInstruction::ChallengeResult(ChallengeResultArgs {
+        market_id: MARKET_ID,
+        result,
+    })
+    .try_to_vec()
+    .unwrap();
+    let pm_user =
+        Pubkey::find_program_address(&[VAULT_PM_USER_SEED, challenger.as_ref()], vault_program).0;
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*challenger, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new_readonly(market_pda(MARKET_ID), false),
+            AccountMeta::new(proposal_pda(MARKET_ID), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(pm_user, false),
+        ],
+    )
+}
+
+/// Returns (banks_client, challenger, vault_program)
+async fn setup(challenge_deadline: i64) -> (BanksClient, Keypair, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let oracle_admin = Pubkey::new_unique();
+    let config = PredictionMarketConfig {
+        oracle_admin,
+        proposer_bond_e6: PROPOSER_BOND,
+        ..test_config(Pubkey::new_unique(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+    add_proposal(&mut program_test, &test_proposal(oracle_admin, challenge_deadline));
+
+    let challenger = Keypair::new();
+    program_test.add_account(
+        challenger.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let (banks_client, _payer, _) = program_test.start().await;
+    (banks_client, challenger, vault_program)
+}
+
+async fn try_send(
+    banks_client: &mut BanksClient,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+async fn load_proposal(banks_client: &mut BanksClient) -> OracleProposal {
+    let account = banks_client.get_account(proposal_pda(MARKET_ID)).await.unwrap().unwrap();
+    OracleProposal::deserialize(&mut &account.data[..]).unwrap()
+}
+
+#[tokio::test]
+async fn test_challenge_result_disputes_proposal() {
+    let (mut banks_client, challenger, vault_program) = setup(i64::MAX).await;
+
+    try_send(
+        &mut banks_client,
+        &challenger,
+        challenge_ix(&challenger.pubkey(), &vault_program, MarketResult::No),
+    )
+    .await
+    .unwrap();
+
+    let proposal = load_proposal(&mut banks_client).await;
+    assert_eq!(proposal.status, ProposalStatus::Disputed);
+    assert_eq!(proposal.challenger, Some(challenger.pubkey()));
+    assert_eq!(proposal.challenger_result, Some(MarketResult::No));
+    assert_eq!(proposal.challenger_bond, PROPOSER_BOND);
+    assert_eq!(proposal.proposed_result, MarketResult::Yes);
+    assert!(proposal.proposal_resolution_deadline >= DISPUTE_RESOLUTION_PERIOD_SECS);
+    assert!(proposal.can_expire(proposal.proposal_resolution_deadline + 1));
+    assert_eq!(
+        proposal.validate_finalize(i64::MAX),
+        Err(PredictionMarketError::ProposalUnderDispute)
+    );
+}
+
+#[tokio::test]
+async fn test_challenge_result_after_deadline() {
+    let (mut banks_client, challenger, vault_program) = setup(0).await;
+
+    let result = try_send(
+        &mut banks_client,
+        &challenger,
+        challenge_ix(&challenger.pubkey(), &vault_program, MarketResult::No),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::ChallengeWindowExpired);
+
+    let proposal = load_proposal(&mut banks_client).await;
+    assert_eq!(proposal.status, ProposalStatus::Pending);
+    assert_eq!(proposal.challenger, None);
+}
+
+#[tokio::test]
+async fn test_challenge_result_same_result() {
+    let (mut banks_client, challenger, vault_program) = setup(i64::MAX).await;
+
+    let result = try_send(
+        &mut banks_client,
+        &challenger,
+        challenge_ix(&challenger.pubkey(), &vault_program, MarketResult::Yes),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::SameResultAsProposal);
+    assert_eq!(load_proposal(&mut banks_client).await.status, ProposalStatus::Pending);
+}
@@ -0,0 +1,149 @@
+//! Integration tests for RelayerClaimMultiOutcomeWinningsV2
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerClaimMultiOutcomeWinningsArgs},
+    Market, MarketResult, MarketStatus, MarketType, MultiOutcomePosition,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 36;
+const NUM_OUTCOMES: u8 = 4;
+const TOTAL_COST: u64 = 3_000_000;
+
+fn test_market(final_result: MarketResult, winning_outcome_index: Option<u8>) -> Market {
+    Market {
+        market_type: MarketType::MultiOutcome,
+        num_outcomes: NUM_OUTCOMES,
+        status: MarketStatus::Resolved,
+        resolution_time: 0,
+        final_result: Some(final_result),
+        winning_outcome_index,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Returns (context, relayer, vault_program, user wallet, PMUserAccount)
+async fn setup(
+    market: Market,
+    holdings: &[(u8, u64)],
+) -> (ProgramTestContext, Keypair, Pubkey, Pubkey, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &market);
+
+    let user = Pubkey::new_unique();
+    let mut position = MultiOutcomePosition::new(MARKET_ID, NUM_OUTCOMES, user, 255, 0);
+    for &(outcome_index, amount) in holdings {
+        position.holdings[outcome_index as usize] = amount;
+    }
+    position.total_cost_e6 = TOTAL_COST;
+    program_test.add_account(
+        multi_position_pda(MARKET_ID, &user),
+        program_account(&position, MultiOutcomePosition::SIZE),
+    );
+
+    let pm_user = add_pm_user(&mut program_test, &user, &vault_program);
+
+    (program_test.start_with_context().await, relayer, vault_program, user, pm_user)
+}
+
+async fn claim(
+    context: &mut ProgramTestContext,
+    relayer: &Keypair,
+    vault_program: &Pubkey,
+    user: &Pubkey,
+    pm_user: &Pubkey,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::RelayerClaimMultiOutcomeWinningsV2(
+        RelayerClaimMultiOutcomeWinningsArgs { user_wallet: *user, market_id: MARKET_ID },
+    )
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(multi_position_pda(MARKET_ID, user), false),
+            AccountMeta::new(*pm_user, false),
+            AccountMeta::new_readonly(vault_config_pda(vault_program), false),
+            AccountMeta::new_readonly(*vault_program, false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, relayer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn load_position(context: &mut ProgramTestContext, user: &Pubkey) -> MultiOutcomePosition {
+    let account = context.banks_client.get_account(multi_position_pda(MARKET_ID, user)).await.unwrap().unwrap();
+    MultiOutcomePosition::deserialize(&mut &account.data[..]).unwrap()
+}
+
+#[tokio::test]
+async fn test_claim_winning_outcome() {
+    let (mut context, relayer, vault_program, user, pm_user) =
+        setup(test_market(MarketResult::Yes, Some(2)), &[(1, 1_000_000), (2, 3_000_000)]).await;
+
+    claim(&mut context, &relayer, &vault_program, &user, &pm_user).await.unwrap();
+
+    // Only the winning outcome pays out 1:1; the cost is released from lock
+    let ledger = vault_ledger(&mut context.banks_client, pm_user).await;
+    assert_eq!(ledger.settlement(), (TOTAL_COST, 3_000_000));
+    let position = load_position(&mut context, &user).await;
+    assert!(position.settled);
+    assert_eq!(position.settlement_amount, 3_000_000);
+    assert!(position.is_empty());
+}
+
+#[tokio::test]
+async fn test_claim_losing_outcome_pays_zero() {
+    let (mut context, relayer, vault_program, user, pm_user) =
+        setup(test_market(MarketResult::Yes, Some(2)), &[(0, 3_000_000)]).await;
+
+    claim(&mut context, &relayer, &vault_program, &user, &pm_user).await.unwrap();
+
+    let ledger = vault_ledger(&mut context.banks_client, pm_user).await;
+
+    assert_eq!(ledger.settlement(), (TOTAL_COST, 0));
+    let position = load_position(&mut context, &user).await;
+    assert!(position.settled);
+    assert_eq!(position.settlement_amount, 0);
+    assert_eq!(position.realized_pnl, -(TOTAL_COST as i64));
+}
+
+#[tokio::test]
+async fn test_claim_invalid_resolution_refunds_cost() {
+    let (mut context, relayer, vault_program, user, pm_user) =
+        setup(test_market(MarketResult::Invalid, None), &[(0, 3_000_000)]).await;
+
+    claim(&mut context, &relayer, &vault_program, &user, &pm_user).await.unwrap();
+
+    let ledger = vault_ledger(&mut context.banks_client, pm_user).await;
+
+    assert_eq!(ledger.settlement(), (TOTAL_COST, TOTAL_COST));
+    let position = load_position(&mut context, &user).await;
+    assert!(position.settled);
+    assert_eq!(position.settlement_amount, TOTAL_COST);
+}
@@ -0,0 +1,148 @@
+//! Integration tests for creator fees charged by RelayerClaimWinningsV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerClaimWinningsArgs},
+    Market, MarketResult, MarketStatus, Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 15;
+const CREATOR_FEE_BPS: u16 = 100;
+const YES_AMOUNT: u64 = 10_000_000;
+const TOTAL_COST: u64 = 6_000_000;
+
+fn test_market(creator: Pubkey, final_result: MarketResult) -> Market {
+    Market {
+        creator,
+        status: MarketStatus::Resolved,
+        resolution_time: 0,
+        final_result: Some(final_result),
+        creator_fee_bps: CREATOR_FEE_BPS,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_position(user: Pubkey) -> Position {
+    Position {
+        yes_amount: YES_AMOUNT,
+        yes_avg_cost: 600_000,
+        total_cost_e6: TOTAL_COST,
+        ..common::test_position(MARKET_ID, user)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    user: Pubkey,
+    vault_program: Pubkey,
+    user_pm: Pubkey,
+    creator_pm: Pubkey,
+}
+
+async fn setup(final_result: MarketResult) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+
+    let creator = Pubkey::new_unique();
+    add_market(&mut program_test, &test_market(creator, final_result));
+
+    let user = Pubkey::new_unique();
+    add_position(&mut program_test, &test_position(user));
+
+    let user_pm = add_pm_user(&mut program_test, &user, &vault_program);
+    let creator_pm = add_pm_user(&mut program_test, &creator, &vault_program);
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        user,
+        vault_program,
+        user_pm,
+        creator_pm,
+    }
+}
+
+async fn try_claim(
+    setup: &mut Setup,
+    with_creator: bool,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::RelayerClaimWinningsV2(RelayerClaimWinningsArgs {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+        AccountMeta::new(setup.user_pm, false),
+        AccountMeta::new_readonly(vault_config_pda(&setup.vault_program), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+    ];
+    if with_creator {
+        metas.push(AccountMeta::new(setup.creator_pm, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, &setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_claim_winnings_pays_creator_fee() {
+    let mut setup = setup(MarketResult::Yes).await;
+
+    try_claim(&mut setup, true).await.unwrap();
+
+    // 1% of the 10 USDC payout goes to the creator
+    let fee = YES_AMOUNT / 100;
+    let banks_client = &mut setup.context.banks_client;
+    assert_eq!(vault_ledger(banks_client, setup.user_pm).await.settlement(), (TOTAL_COST, YES_AMOUNT - fee));
+    assert_eq!(vault_ledger(banks_client, setup.creator_pm).await.settlement(), (0, fee));
+
+    let position: Position = load(banks_client, position_pda(MARKET_ID, &setup.user)).await;
+    assert!(position.settled);
+    assert_eq!(position.settlement_amount, YES_AMOUNT - fee);
+    assert_eq!(position.realized_pnl, (YES_AMOUNT - fee) as i64 - TOTAL_COST as i64);
+}
+
+#[tokio::test]
+async fn test_claim_winnings_requires_creator_account() {
+    let mut setup = setup(MarketResult::Yes).await;
+
+    let result = try_claim(&mut setup, false).await;
+    assert_custom_error(result, PredictionMarketError::MissingCreatorAccount);
+}
+
+#[tokio::test]
+async fn test_invalid_market_refund_has_no_creator_fee() {
+    let mut setup = setup(MarketResult::Invalid).await;
+
+    try_claim(&mut setup, false).await.unwrap();
+
+    let banks_client = &mut setup.context.banks_client;
+    assert_eq!(vault_ledger(banks_client, setup.user_pm).await.settlement(), (TOTAL_COST, TOTAL_COST));
+    assert_eq!(vault_ledger(banks_client, setup.creator_pm).await.calls, 0);
+}
@@ -0,0 +1,128 @@
+//! Integration tests for RelayerClaimWinningsV2 settling a Position once
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerClaimWinningsArgs},
+    Market, MarketResult, MarketStatus, Outcome, Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+const MARKET_ID: u64 = 43;
+const SHARES: u64 = 4_000_000;
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::Resolved,
+        resolution_time: 0,
+        final_result: Some(MarketResult::Yes),
+        total_minted: SHARES,
+        open_interest: SHARES,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+    pm_user: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let user = Pubkey::new_unique();
+    let mut position = Position::new(MARKET_ID, user, 255, 0);
+    position.add_tokens(Outcome::Yes, SHARES, 600_000, 0).unwrap();
+    add_position(&mut program_test, &position);
+
+    let pm_user = add_pm_user(&mut program_test, &user, &vault_program);
+
+    Setup { context: program_test.start_with_context().await, relayer, vault_program, user, pm_user }
+}
+
+fn claim_ix(setup: &Setup) -> Instruction {
+    let data = PredictionMarketInstruction::RelayerClaimWinningsV2(RelayerClaimWinningsArgs {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+            AccountMeta::new(setup.pm_user, false),
+            AccountMeta::new_readonly(vault_config_pda(&setup.vault_program), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+        ],
+    )
+}
+
+async fn claim_tx(setup: &mut Setup, claims: usize) -> Transaction {
+    let ixs = vec![claim_ix(setup); claims];
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, &setup.relayer],
+        recent_blockhash,
+    )
+}
+
+/// Returns (settlement count, total settled) recorded by the Vault stub
+async fn recorded_settles(setup: &mut Setup) -> (u64, u64) {
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.pm_user).await;
+    (ledger.calls, ledger.settled)
+}
+
+#[tokio::test]
+async fn test_duplicate_claim_in_one_transaction_settles_once() {
+    let mut setup = setup().await;
+
+    let tx = claim_tx(&mut setup, 2).await;
+    let simulation = setup.context.banks_client.simulate_transaction(tx.clone()).await.unwrap();
+    assert_eq!(
+        simulation.result.unwrap().unwrap_err(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(PredictionMarketError::AlreadySettled as u32)
+        )
+    );
+    // The second claim is rejected before reaching the Vault
+    let logs = simulation.simulation_details.unwrap().logs;
+    let settle_cpis = logs.iter().filter(|log| log.contains("CPI: Vault.Settle")).count();
+    assert_eq!(settle_cpis, 1);
+
+    // The failed bundle is rolled back as a whole
+    assert!(setup.context.banks_client.process_transaction(tx).await.is_err());
+    assert_eq!(recorded_settles(&mut setup).await, (0, 0));
+
+    let tx = claim_tx(&mut setup, 1).await;
+    setup.context.banks_client.process_transaction(tx).await.unwrap();
+    assert_eq!(recorded_settles(&mut setup).await, (1, SHARES));
+
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &setup.user)).await;
+    assert!(position.settled);
+    assert_eq!(position.settlement_amount, SHARES);
+}
@@ -0,0 +1,141 @@
+//! Integration tests for CloseOrder
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{CloseOrderArgs, PredictionMarketInstruction},
+    Order, OrderSide, OrderStatus, PredictionMarketConfig,
+    PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 22;
+const ORDER_ID: u64 = 1;
+const ORDER_LAMPORTS: u64 = 2_000_000;
+const OWNER_LAMPORTS: u64 = 1_000_000_000;
+
+fn test_order(owner: Pubkey, status: OrderStatus) -> Order {
+    Order {
+        amount: 10_000_000,
+        filled_amount: 4_000_000,
+        status,
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, ORDER_ID, owner, OrderSide::Buy)
+    }
+}
+
+/// Returns (context, owner)
+async fn setup(status: OrderStatus) -> (ProgramTestContext, Keypair) {
+    let program_id = prediction_market_program::id();
+    let mut program_test = program_test();
+
+    let config = test_config(Pubkey::new_unique(), Pubkey::new_unique());
+    let mut data = config.try_to_vec().unwrap();
+    data.resize(PredictionMarketConfig::SIZE, 0);
+    program_test.add_account(
+        config_pda(),
+        Account {
+            lamports: 10_000_000,
+            data,
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let owner = Keypair::new();
+    let mut data = test_order(owner.pubkey(), status).try_to_vec().unwrap();
+    data.resize(Order::SIZE, 0);
+    program_test.add_account(
+        order_pda(MARKET_ID, ORDER_ID),
+        Account {
+            lamports: ORDER_LAMPORTS,
+            data,
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    program_test.add_account(
+        owner.pubkey(),
+        Account {
+            lamports: OWNER_LAMPORTS,
+            ..Account::default()
+        },
+    );
+
+    (program_test.start_with_context().await, owner)
+}
+
+async fn try_close(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    owner: Pubkey,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::CloseOrder(CloseOrderArgs {
+        market_id: MARKET_ID,
+        order_id: ORDER_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(signer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(order_pda(MARKET_ID, ORDER_ID), false),
+            AccountMeta::new(owner, false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    context.banks_client.get_balance(address).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_close_cancelled_order_returns_rent_to_owner() {
+    let (mut context, owner) = setup(OrderStatus::Cancelled).await;
+
+    try_close(&mut context, &owner, owner.pubkey()).await.unwrap();
+
+    assert_eq!(lamports(&mut context, owner.pubkey()).await, OWNER_LAMPORTS + ORDER_LAMPORTS);
+    assert!(context.banks_client.get_account(order_pda(MARKET_ID, ORDER_ID)).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_close_active_order_rejected() {
+    let (mut context, owner) = setup(OrderStatus::PartialFilled).await;
+
+    let result = try_close(&mut context, &owner, owner.pubkey()).await;
+    assert_custom_error(result, PredictionMarketError::OrderNotClosable);
+    assert_eq!(lamports(&mut context, order_pda(MARKET_ID, ORDER_ID)).await, ORDER_LAMPORTS);
+}
+
+#[tokio::test]
+async fn test_close_order_by_outsider_rejected() {
+    let (mut context, owner) = setup(OrderStatus::Filled).await;
+
+    let outsider = Keypair::new();
+    let result = try_close(&mut context, &outsider, owner.pubkey()).await;
+    assert_custom_error(result, PredictionMarketError::Unauthorized);
+    assert_eq!(lamports(&mut context, order_pda(MARKET_ID, ORDER_ID)).await, ORDER_LAMPORTS);
+}
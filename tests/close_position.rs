@@ -0,0 +1,176 @@
+//! Integration tests for ClosePosition
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ClosePositionArgs, PredictionMarketInstruction},
+    Market, MarketResult, MarketStatus, Position,
+    PredictionMarketConfig, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 24;
+const POSITION_LAMPORTS: u64 = 3_000_000;
+const OWNER_LAMPORTS: u64 = 1_000_000_000;
+
+fn program_account<T: BorshSerialize>(value: &T, size: usize, lamports: u64) -> Account {
+    let mut data = value.try_to_vec().unwrap();
+    data.resize(size, 0);
+    Account {
+        lamports,
+        data,
+        owner: prediction_market_program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn test_market(status: MarketStatus) -> Market {
+    Market {
+        status,
+        final_result: (status == MarketStatus::Resolved).then_some(MarketResult::Yes),
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_position(owner: Pubkey, yes_amount: u64, settled: bool) -> Position {
+    Position {
+        yes_amount,
+        settled,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+/// Returns (context, owner)
+async fn setup(status: MarketStatus, position: fn(Pubkey) -> Position) -> (ProgramTestContext, Keypair) {
+    let mut program_test = program_test();
+
+    let config = test_config(Pubkey::new_unique(), Pubkey::new_unique());
+    program_test.add_account(
+        config_pda(),
+        program_account(&config, PredictionMarketConfig::SIZE, 10_000_000),
+    );
+    program_test.add_account(
+        market_pda(MARKET_ID),
+        program_account(&test_market(status), Market::SIZE, 10_000_000),
+    );
+
+    let owner = Keypair::new();
+    program_test.add_account(
+        position_pda(MARKET_ID, &owner.pubkey()),
+        program_account(&position(owner.pubkey()), Position::SIZE, POSITION_LAMPORTS),
+    );
+    program_test.add_account(
+        owner.pubkey(),
+        Account {
+            lamports: OWNER_LAMPORTS,
+            ..Account::default()
+        },
+    );
+
+    (program_test.start_with_context().await, owner)
+}
+
+async fn try_close(
+    context: &mut ProgramTestContext,
+    owner: &Keypair,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::ClosePosition(ClosePositionArgs {
+        market_id: MARKET_ID,
+        user_wallet: owner.pubkey(),
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(owner.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new_readonly(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &owner.pubkey()), false),
+            AccountMeta::new(owner.pubkey(), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, owner],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_close_settled_position_refunds_rent() {
+    // Claimed: settled with the shares zeroed
+    let (mut context, owner) = setup(MarketStatus::Resolved, |owner| test_position(owner, 0, true)).await;
+
+    try_close(&mut context, &owner).await.unwrap();
+
+    let balance = context.banks_client.get_balance(owner.pubkey()).await.unwrap();
+    assert_eq!(balance, OWNER_LAMPORTS + POSITION_LAMPORTS);
+    let position = context.banks_client.get_account(position_pda(MARKET_ID, &owner.pubkey())).await.unwrap();
+    assert!(position.is_none());
+}
+
+#[tokio::test]
+async fn test_close_live_position_with_shares_rejected() {
+    let (mut context, owner) =
+        setup(MarketStatus::Active, |owner| test_position(owner, 5_000_000, false)).await;
+
+    let result = try_close(&mut context, &owner).await;
+    assert_custom_error(result, PredictionMarketError::PositionNotEmpty);
+}
+
+#[tokio::test]
+async fn test_close_unsettled_position_in_live_market_rejected() {
+    let (mut context, owner) = setup(MarketStatus::Active, |owner| test_position(owner, 0, false)).await;
+
+    let result = try_close(&mut context, &owner).await;
+    assert_custom_error(result, PredictionMarketError::InvalidMarketStatus);
+    let balance = context.banks_client.get_balance(position_pda(MARKET_ID, &owner.pubkey())).await.unwrap();
+    assert_eq!(balance, POSITION_LAMPORTS);
+}
+
+#[tokio::test]
+async fn test_close_unsettled_position_with_refund_due_rejected() {
+    // Cancelled market: the unconsumed cost is still refundable through a claim
+    let (mut context, owner) = setup(MarketStatus::Cancelled, |owner| Position {
+        total_cost_e6: 3_000_000,
+        settled_cost_e6: 1_000_000,
+        ..test_position(owner, 0, false)
+    })
+    .await;
+
+    let result = try_close(&mut context, &owner).await;
+    assert_custom_error(result, PredictionMarketError::PositionNotEmpty);
+    let balance = context.banks_client.get_balance(position_pda(MARKET_ID, &owner.pubkey())).await.unwrap();
+    assert_eq!(balance, POSITION_LAMPORTS);
+}
+
+#[tokio::test]
+async fn test_close_unsettled_position_fully_consumed_in_cancelled_market() {
+    // Every cost already went to counterparties, so a claim would refund nothing
+    let (mut context, owner) = setup(MarketStatus::Cancelled, |owner| Position {
+        total_cost_e6: 3_000_000,
+        settled_cost_e6: 3_000_000,
+        ..test_position(owner, 0, false)
+    })
+    .await;
+
+    try_close(&mut context, &owner).await.unwrap();
+
+    let balance = context.banks_client.get_balance(owner.pubkey()).await.unwrap();
+    assert_eq!(balance, OWNER_LAMPORTS + POSITION_LAMPORTS);
+}
@@ -0,0 +1,474 @@
+//! Fixtures shared by the integration tests
+//!
+//! Each test file pulls these in with `mod common;` and only uses a subset.
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use prediction_market_program::{
+    processor::process_instruction, Market, MarketCategory, MarketStatus, MarketType,
+    OracleProposal, Order, OrderSide, OrderStatus, OrderType, Outcome, Position,
+    PredictionMarketConfig, PredictionMarketError, ProposalStatus, MarketResult,
+    ReviewStatus, MARKET_DISCRIMINATOR, MARKET_SEED, MULTI_OUTCOME_POSITION_SEED,
+    ORACLE_PROPOSAL_DISCRIMINATOR, ORACLE_PROPOSAL_SEED, ORDER_DISCRIMINATOR, ORDER_SEED,
+    PM_CONFIG_SEED, POSITION_SEED, VAULT_PM_USER_SEED,
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::set_return_data,
+    program_error::ProgramError, program_option::COption, program_pack::Pack,
+};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// Size of the ledger the recording Vault keeps in a PMUserAccount
+pub const VAULT_LEDGER_SIZE: usize = 40;
+
+/// Available balance the clamping Vault locks at most
+pub const CLAMPED_BALANCE: u64 = 3_000_000;
+
+// ============================================================================
+// Addresses
+// ============================================================================
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &prediction_market_program::id()).0
+}
+
+pub fn config_pda() -> Pubkey {
+    pda(&[PM_CONFIG_SEED])
+}
+
+pub fn market_pda(market_id: u64) -> Pubkey {
+    pda(&[MARKET_SEED, &market_id.to_le_bytes()])
+}
+
+pub fn order_pda(market_id: u64, order_id: u64) -> Pubkey {
+    pda(&[ORDER_SEED, &market_id.to_le_bytes(), &order_id.to_le_bytes()])
+}
+
+pub fn position_pda(market_id: u64, owner: &Pubkey) -> Pubkey {
+    pda(&[POSITION_SEED, &market_id.to_le_bytes(), owner.as_ref()])
+}
+
+pub fn multi_position_pda(market_id: u64, owner: &Pubkey) -> Pubkey {
+    pda(&[MULTI_OUTCOME_POSITION_SEED, &market_id.to_le_bytes(), owner.as_ref()])
+}
+
+pub fn proposal_pda(market_id: u64) -> Pubkey {
+    pda(&[ORACLE_PROPOSAL_SEED, &market_id.to_le_bytes()])
+}
+
+pub fn pm_user_pda(owner: &Pubkey, vault_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_PM_USER_SEED, owner.as_ref()], vault_program).0
+}
+
+/// Address the test Vaults keep their VaultConfig at
+pub fn vault_config_pda(vault_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_config"], vault_program).0
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+/// `value` padded to `size` bytes in an account owned by this program
+pub fn program_account<T: BorshSerialize>(value: &T, size: usize) -> Account {
+    let mut data = value.try_to_vec().unwrap();
+    data.resize(size, 0);
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner: prediction_market_program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Zeroed account owned by the Vault, e.g. a PMUserAccount for the
+/// recording Vault to write its ledger into
+pub fn vault_account(vault_program: &Pubkey) -> Account {
+    Account {
+        lamports: 10_000_000,
+        data: vec![0u8; VAULT_LEDGER_SIZE],
+        owner: *vault_program,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// SPL Token account holding the packed `value`
+pub fn packed_account<T: Pack>(value: T) -> Account {
+    let mut data = vec![0u8; T::LEN];
+    value.pack_into_slice(&mut data);
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Initialized 6-decimal mint with `authority` as mint authority
+pub fn mint_account(authority: Pubkey) -> Account {
+    packed_account(spl_token::state::Mint {
+        mint_authority: COption::Some(authority),
+        supply: 0,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    })
+}
+
+/// Initialized token account of `mint` holding `amount`
+pub fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    packed_account(spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    })
+}
+
+/// Active binary market with no trading history
+pub fn test_market(market_id: u64) -> Market {
+    Market {
+        discriminator: MARKET_DISCRIMINATOR,
+        market_id,
+        market_type: MarketType::Binary,
+        num_outcomes: 2,
+        creator: Pubkey::new_unique(),
+        question_hash: [1u8; 32],
+        resolution_spec_hash: [2u8; 32],
+        yes_mint: Pubkey::default(),
+        no_mint: Pubkey::default(),
+        market_vault: Pubkey::default(),
+        status: MarketStatus::Active,
+        review_status: ReviewStatus::None,
+        resolution_time: i64::MAX,
+        finalization_deadline: i64::MAX,
+        final_result: None,
+        winning_outcome_index: None,
+        created_at: 0,
+        updated_at: 0,
+        total_minted: 0,
+        total_volume_e6: 0,
+        open_interest: 0,
+        creator_fee_bps: 0,
+        next_order_id: 1,
+        bump: 255,
+        max_tvl_e6: 0,
+        total_realized_pnl_e6: 0,
+        challenge_window_secs: 0,
+        category: MarketCategory::Other,
+        tags_hash: [0u8; 32],
+        pause_reason: 0,
+        reserved: [0u8; 2],
+    }
+}
+
+/// Open, unfilled GTC order for YES at 0.50; Buy orders have their margin
+/// locked
+pub fn test_order(market_id: u64, order_id: u64, owner: Pubkey, side: OrderSide) -> Order {
+    Order {
+        discriminator: ORDER_DISCRIMINATOR,
+        order_id,
+        market_id,
+        owner,
+        side,
+        outcome: Outcome::Yes,
+        outcome_index: 0,
+        price: 500_000,
+        amount: 1_000_000,
+        filled_amount: 0,
+        status: OrderStatus::Open,
+        order_type: OrderType::GTC,
+        expiration_time: None,
+        created_at: 0,
+        updated_at: 0,
+        bump: 255,
+        escrow_token_account: None,
+        locks_released: false,
+        margin_locked: side == OrderSide::Buy,
+        locked_margin_e6: 0,
+        reduce_only: false,
+        reserved: [0u8; 19],
+    }
+}
+
+/// Empty position
+pub fn test_position(market_id: u64, owner: Pubkey) -> Position {
+    Position::new(market_id, owner, 255, 0)
+}
+
+/// Pending, unchallenged proposal of YES
+pub fn test_proposal(market_id: u64, proposer: Pubkey) -> OracleProposal {
+    OracleProposal {
+        discriminator: ORACLE_PROPOSAL_DISCRIMINATOR,
+        market_id,
+        proposer,
+        proposed_result: MarketResult::Yes,
+        status: ProposalStatus::Pending,
+        proposed_at: 0,
+        challenge_deadline: i64::MAX,
+        bond_amount: 0,
+        challenger: None,
+        challenger_result: None,
+        challenger_bond: 0,
+        bump: 255,
+        original_challenge_deadline: i64::MAX,
+        challenge_count: 0,
+        proposed_outcome_index: None,
+        proposal_resolution_deadline: 0,
+        escalated_challengers: 0,
+        escalated_bonds_e6: 0,
+        reserved: [0u8; 4],
+    }
+}
+
+/// Config at its PDA, with `authority` as admin and relayer
+pub fn test_config(authority: Pubkey, vault_program: Pubkey) -> PredictionMarketConfig {
+    let (_, bump) = Pubkey::find_program_address(&[PM_CONFIG_SEED], &prediction_market_program::id());
+    PredictionMarketConfig::new(
+        authority,
+        Pubkey::new_unique(),
+        vault_program,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        bump,
+    )
+}
+
+// ============================================================================
+// Program test
+// ============================================================================
+
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new(
+        "prediction_market_program",
+        prediction_market_program::id(),
+        processor!(process_instruction),
+    )
+}
+
+/// Register the recording Vault under a fresh program id, along with its
+/// VaultConfig
+pub fn add_recording_vault(program_test: &mut ProgramTest) -> Pubkey {
+    let vault_program = Pubkey::new_unique();
+    program_test.add_program("recording_vault", vault_program, processor!(recording_vault));
+    add_vault_config(program_test, &vault_program);
+    vault_program
+}
+
+/// Register a Vault that accepts every CPI without recording it, along
+/// with its VaultConfig
+pub fn add_stub_vault(program_test: &mut ProgramTest) -> Pubkey {
+    let vault_program = Pubkey::new_unique();
+    program_test.add_program("stub_vault", vault_program, processor!(stub_vault));
+    add_vault_config(program_test, &vault_program);
+    vault_program
+}
+
+/// Register the clamping Vault under a fresh program id, along with its
+/// VaultConfig
+pub fn add_clamping_vault(program_test: &mut ProgramTest) -> Pubkey {
+    let vault_program = Pubkey::new_unique();
+    program_test.add_program("clamping_vault", vault_program, processor!(clamping_vault));
+    add_vault_config(program_test, &vault_program);
+    vault_program
+}
+
+/// Add a VaultConfig owned by `vault_program` at [`vault_config_pda`]
+pub fn add_vault_config(program_test: &mut ProgramTest, vault_program: &Pubkey) -> Pubkey {
+    let vault_config = vault_config_pda(vault_program);
+    program_test.add_account(vault_config, vault_account(vault_program));
+    vault_config
+}
+
+pub fn add_config(program_test: &mut ProgramTest, config: &PredictionMarketConfig) {
+    program_test.add_account(config_pda(), program_account(config, PredictionMarketConfig::SIZE));
+}
+
+pub fn add_market(program_test: &mut ProgramTest, market: &Market) {
+    program_test.add_account(market_pda(market.market_id), program_account(market, Market::SIZE));
+}
+
+pub fn add_order(program_test: &mut ProgramTest, order: &Order) {
+    program_test.add_account(
+        order_pda(order.market_id, order.order_id),
+        program_account(order, Order::SIZE),
+    );
+}
+
+pub fn add_position(program_test: &mut ProgramTest, position: &Position) {
+    program_test.add_account(
+        position_pda(position.market_id, &position.owner),
+        program_account(position, Position::SIZE),
+    );
+}
+
+pub fn add_proposal(program_test: &mut ProgramTest, proposal: &OracleProposal) {
+    program_test.add_account(
+        proposal_pda(proposal.market_id),
+        program_account(proposal, OracleProposal::SIZE),
+    );
+}
+
+/// Add `owner`'s PMUserAccount and return its address
+pub fn add_pm_user(program_test: &mut ProgramTest, owner: &Pubkey, vault_program: &Pubkey) -> Pubkey {
+    let pm_user = pm_user_pda(owner, vault_program);
+    program_test.add_account(pm_user, vault_account(vault_program));
+    pm_user
+}
+
+pub async fn set_clock(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+/// Send `instructions` paid by the context payer and signed by `signers`
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn load<T: BorshDeserialize>(banks_client: &mut BanksClient, address: Pubkey) -> T {
+    let account = banks_client.get_account(address).await.unwrap().unwrap();
+    T::deserialize(&mut &account.data[..]).unwrap()
+}
+
+pub fn assert_custom_error(result: Result<(), BanksClientError>, expected: PredictionMarketError) {
+    assert_custom_error_at(result, 0, expected);
+}
+
+/// Like [`assert_custom_error`] for the instruction at `index`
+pub fn assert_custom_error_at(
+    result: Result<(), BanksClientError>,
+    index: u8,
+    expected: PredictionMarketError,
+) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(i, InstructionError::Custom(code)) => {
+            assert_eq!((i, code), (index, expected as u32))
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+// ============================================================================
+// Vault stand-ins
+// ============================================================================
+
+/// Stand-in for the Vault Program: accepts every CPI
+pub fn stub_vault(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+/// Stand-in for the Vault Program: PredictionMarketLock (16) locks at most
+/// [`CLAMPED_BALANCE`] and reports the locked amount as return data
+pub fn clamping_vault(_program_id: &Pubkey, _accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data[0] == 16 {
+        let requested = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        set_return_data(&requested.min(CLAMPED_BALANCE).to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Totals the recording Vault keeps at the start of each PMUserAccount
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VaultLedger {
+    /// Sum of PredictionMarketLock amounts
+    pub locked: u64,
+    /// Sum of PredictionMarketUnlock amounts
+    pub unlocked: u64,
+    /// Sum of the locked amounts released by PredictionMarketSettle
+    pub settled_locked: u64,
+    /// Sum of the settlement amounts paid by PredictionMarketSettle
+    pub settled: u64,
+    /// Number of Vault instructions that touched the account
+    pub calls: u64,
+}
+
+impl VaultLedger {
+    /// `(settled_locked, settled)`, the totals of PredictionMarketSettle
+    pub fn settlement(&self) -> (u64, u64) {
+        (self.settled_locked, self.settled)
+    }
+
+    /// `(settled_locked, settled, unlocked)`, every leg of a trade
+    pub fn trade_legs(&self) -> (u64, u64, u64) {
+        (self.settled_locked, self.settled, self.unlocked)
+    }
+
+    fn read(data: &[u8]) -> Self {
+        let word = |i: usize| u64::from_le_bytes(data[i * 8..(i + 1) * 8].try_into().unwrap());
+        Self { locked: word(0), unlocked: word(1), settled_locked: word(2), settled: word(3), calls: word(4) }
+    }
+
+    fn write(&self, data: &mut [u8]) {
+        for (i, value) in [self.locked, self.unlocked, self.settled_locked, self.settled, self.calls]
+            .iter()
+            .enumerate()
+        {
+            data[i * 8..(i + 1) * 8].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+/// Stand-in for the Vault Program that records every call in the
+/// PMUserAccount's [`VaultLedger`]
+///
+/// PredictionMarketLock (16) and PredictionMarketUnlock (17) carry the
+/// amount in data[1..9] and pass the PMUserAccount third;
+/// PredictionMarketSettle (18) carries (locked, settlement) in data[1..17]
+/// and passes it second.
+pub fn recording_vault(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let word = |i: usize| u64::from_le_bytes(data[1 + i * 8..9 + i * 8].try_into().unwrap());
+    let pm_user = match data.first() {
+        Some(16) | Some(17) => &accounts[2],
+        Some(18) => &accounts[1],
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let mut pm_user_data = pm_user.data.borrow_mut();
+    let mut ledger = VaultLedger::read(&pm_user_data);
+    match data[0] {
+        16 => ledger.locked += word(0),
+        17 => ledger.unlocked += word(0),
+        _ => {
+            ledger.settled_locked += word(0);
+            ledger.settled += word(1);
+        }
+    }
+    ledger.calls += 1;
+    ledger.write(&mut pm_user_data);
+    Ok(())
+}
+
+/// Ledger the recording Vault kept in the PMUserAccount at `address`
+pub async fn vault_ledger(banks_client: &mut BanksClient, address: Pubkey) -> VaultLedger {
+    let account = banks_client.get_account(address).await.unwrap().unwrap();
+    VaultLedger::read(&account.data)
+}
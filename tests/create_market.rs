@@ -0,0 +1,123 @@
+//! Integration tests for CreateMarket argument validation
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{CreateMarketArgs, PredictionMarketInstruction},
+    ComparisonOperator, MarketCategory, PredictionMarketConfig, PredictionMarketError,
+    ResolutionSource,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    system_program, sysvar,
+    transaction::Transaction,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+/// Returns (context, usdc_mint)
+async fn setup() -> (ProgramTestContext, Pubkey) {
+    let program_id = prediction_market_program::id();
+    let mut program_test = program_test();
+
+    let usdc_mint = Pubkey::new_unique();
+    let config = PredictionMarketConfig {
+        usdc_mint,
+        ..test_config(Pubkey::new_unique(), Pubkey::new_unique())
+    };
+    let mut data = config.try_to_vec().unwrap();
+    data.resize(PredictionMarketConfig::SIZE, 0);
+    program_test.add_account(
+        config_pda(),
+        Account {
+            lamports: 10_000_000,
+            data,
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+    (context, usdc_mint)
+}
+
+fn create_args(question_hash: [u8; 32], resolution_spec_hash: [u8; 32]) -> CreateMarketArgs {
+    CreateMarketArgs {
+        question_hash,
+        resolution_spec_hash,
+        resolution_time: NOW + 86_400,
+        finalization_deadline: NOW + 2 * 86_400,
+        creator_fee_bps: 0,
+        challenge_window_secs: 0,
+        category: MarketCategory::Other,
+        tags_hash: [0u8; 32],
+        resolution_source: ResolutionSource::Manual,
+    }
+}
+
+async fn try_create(
+    context: &mut ProgramTestContext,
+    usdc_mint: Pubkey,
+    args: CreateMarketArgs,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::CreateMarket(args).try_to_vec().unwrap();
+    let creator = context.payer.pubkey();
+    // Validation fails before the market, mint and vault accounts are touched
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(creator, true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(usdc_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&creator),
+        &[&context.payer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_create_market_rejects_empty_question_hash() {
+    let (mut context, usdc_mint) = setup().await;
+
+    let result = try_create(&mut context, usdc_mint, create_args([0u8; 32], [2u8; 32])).await;
+    assert_custom_error(result, PredictionMarketError::InvalidMarketMetadata);
+
+    let result = try_create(&mut context, usdc_mint, create_args([1u8; 32], [0u8; 32])).await;
+    assert_custom_error(result, PredictionMarketError::InvalidMarketMetadata);
+}
+
+#[tokio::test]
+async fn test_create_market_rejects_switchboard_source_without_feed() {
+    let (mut context, usdc_mint) = setup().await;
+
+    let mut args = create_args([1u8; 32], [2u8; 32]);
+    args.resolution_source = ResolutionSource::Switchboard {
+        feed: Pubkey::default(),
+        operator: ComparisonOperator::GreaterThan,
+        threshold: 0,
+    };
+    let result = try_create(&mut context, usdc_mint, args).await;
+    assert_custom_error(result, PredictionMarketError::InvalidOracleFeed);
+}
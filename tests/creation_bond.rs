@@ -0,0 +1,307 @@
+//! Integration tests for the market creation bond
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        CreateMarketArgs, PredictionMarketInstruction, ReleaseCreationBondArgs,
+        SetMarketCreationBondArgs,
+    },
+    ConfigExtension, Market, MarketCategory, MarketExtension, MarketResult, MarketStatus,
+    PredictionMarketConfig, PredictionMarketError, ResolutionSource, ReviewStatus,
+    MARKET_VAULT_SEED, NO_MINT_SEED, YES_MINT_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 35;
+const BOND: u64 = 25_000_000;
+const NOW: i64 = 1_700_000_000;
+
+fn account_with_data(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn config_account(config: &PredictionMarketConfig, bond: Option<u64>) -> Account {
+    let mut data = config.try_to_vec().unwrap();
+    data.resize(PredictionMarketConfig::SIZE, 0);
+    if let Some(bond) = bond {
+        let extension = ConfigExtension {
+            market_creation_bond_e6: bond,
+            ..ConfigExtension::default()
+        };
+        data.extend(extension.try_to_vec().unwrap());
+    }
+    account_with_data(data, prediction_market_program::id())
+}
+
+fn test_market(creator: Pubkey, status: MarketStatus, review_status: ReviewStatus) -> Market {
+    Market {
+        creator,
+        status,
+        review_status,
+        resolution_time: 0,
+        finalization_deadline: 0,
+        final_result: if status == MarketStatus::Resolved { Some(MarketResult::Yes) } else { None },
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn market_account(market: &Market, bond: u64) -> Account {
+    let mut data = market.try_to_vec().unwrap();
+    data.resize(Market::SIZE, 0);
+    let extension = MarketExtension {
+        creation_bond_e6: bond,
+        ..MarketExtension::default()
+    };
+    data.extend(extension.try_to_vec().unwrap());
+    account_with_data(data, prediction_market_program::id())
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    admin: Keypair,
+    creator: Keypair,
+    usdc_mint: Pubkey,
+    vault_program: Pubkey,
+    creator_vault: Pubkey,
+    creator_pm_user: Pubkey,
+    fund_pm_user: Pubkey,
+}
+
+/// `config_bond`: None leaves the config at its original size
+async fn setup(config_bond: Option<u64>, market: Option<(MarketStatus, ReviewStatus)>) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let usdc_mint = Pubkey::new_unique();
+    let fund_program = Pubkey::new_unique();
+    let config = PredictionMarketConfig {
+        usdc_mint,
+        fund_program,
+        next_market_id: MARKET_ID,
+        ..test_config(admin.pubkey(), vault_program)
+    };
+    program_test.add_account(config_pda(), config_account(&config, config_bond));
+
+    if let Some((status, review_status)) = market {
+        let market = test_market(creator.pubkey(), status, review_status);
+        program_test.add_account(market_pda(MARKET_ID), market_account(&market, BOND));
+    }
+
+    program_test.add_account(usdc_mint, mint_account(Pubkey::new_unique()));
+
+    let creator_vault = Pubkey::new_unique();
+    program_test.add_account(creator_vault, vault_account(&vault_program));
+    let creator_pm_user = add_pm_user(&mut program_test, &creator.pubkey(), &vault_program);
+    let fund_pm_user = add_pm_user(&mut program_test, &fund_program, &vault_program);
+    for signer in [&admin, &creator] {
+        program_test.add_account(
+            signer.pubkey(),
+            Account {
+                lamports: 10_000_000_000,
+                ..Account::default()
+            },
+        );
+    }
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+
+    Setup {
+        context,
+        admin,
+        creator,
+        usdc_mint,
+        vault_program,
+        creator_vault,
+        creator_pm_user,
+        fund_pm_user,
+    }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    data: Vec<u8>,
+    accounts: Vec<AccountMeta>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, accounts);
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn try_release(
+    setup: &mut Setup,
+    fund_pm_user: Option<Pubkey>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::ReleaseCreationBond(ReleaseCreationBondArgs {
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut accounts = vec![
+        AccountMeta::new_readonly(setup.creator.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.creator_vault, false),
+        AccountMeta::new(setup.creator_pm_user, false),
+    ];
+    if let Some(fund_pm_user) = fund_pm_user {
+        accounts.push(AccountMeta::new(fund_pm_user, false));
+    }
+    let creator = setup.creator.insecure_clone();
+    send(&mut setup.context, &creator, data, accounts).await
+}
+
+async fn account_data(context: &mut ProgramTestContext, address: Pubkey) -> Vec<u8> {
+    context.banks_client.get_account(address).await.unwrap().unwrap().data
+}
+
+#[tokio::test]
+async fn test_create_market_locks_bond() {
+    let mut setup = setup(Some(BOND), None).await;
+
+    let data = PredictionMarketInstruction::CreateMarket(CreateMarketArgs {
+        question_hash: [1u8; 32],
+        resolution_spec_hash: [2u8; 32],
+        resolution_time: NOW + 86_400,
+        finalization_deadline: NOW + 2 * 86_400,
+        creator_fee_bps: 0,
+        challenge_window_secs: 0,
+        category: MarketCategory::Other,
+        tags_hash: [0u8; 32],
+        resolution_source: ResolutionSource::Manual,
+    })
+    .try_to_vec()
+    .unwrap();
+    let market_id_bytes = MARKET_ID.to_le_bytes();
+    let accounts = vec![
+        AccountMeta::new(setup.creator.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(pda(&[YES_MINT_SEED, &market_id_bytes]), false),
+        AccountMeta::new(pda(&[NO_MINT_SEED, &market_id_bytes]), false),
+        AccountMeta::new(pda(&[MARKET_VAULT_SEED, &market_id_bytes]), false),
+        AccountMeta::new_readonly(setup.usdc_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.creator_vault, false),
+        AccountMeta::new(setup.creator_pm_user, false),
+    ];
+    let creator = setup.creator.insecure_clone();
+    send(&mut setup.context, &creator, data.clone(), accounts[..10].to_vec())
+        .await
+        .unwrap_err();
+    send(&mut setup.context, &creator, data, accounts).await.unwrap();
+
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.creator_pm_user).await;
+    assert_eq!(ledger.locked, BOND);
+
+    let market_data = account_data(&mut setup.context, market_pda(MARKET_ID)).await;
+    assert_eq!(market_data.len(), Market::SIZE + MarketExtension::SIZE + ResolutionSource::SIZE);
+    assert_eq!(ResolutionSource::load(&market_data), ResolutionSource::Manual);
+    assert_eq!(MarketExtension::load(&market_data).creation_bond_e6, BOND);
+    let market = Market::deserialize(&mut &market_data[..]).unwrap();
+    assert_eq!(market.creator, setup.creator.pubkey());
+}
+
+#[tokio::test]
+async fn test_release_bond_after_resolution() {
+    let mut setup = setup(Some(BOND), Some((MarketStatus::Resolved, ReviewStatus::None))).await;
+
+    try_release(&mut setup, None).await.unwrap();
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.creator_pm_user).await;
+    assert_eq!(ledger.unlocked, BOND);
+    let market_data = account_data(&mut setup.context, market_pda(MARKET_ID)).await;
+    assert_eq!(MarketExtension::load(&market_data).creation_bond_e6, 0);
+
+    // Released once
+    let result = try_release(&mut setup, None).await;
+    assert_custom_error(result, PredictionMarketError::NoCreationBond);
+}
+
+#[tokio::test]
+async fn test_forfeit_bond_on_invalid_cancel() {
+    let mut setup = setup(
+        Some(BOND),
+        Some((MarketStatus::Cancelled, ReviewStatus::CancelledInvalid)),
+    )
+    .await;
+
+    let result = try_release(&mut setup, None).await;
+    assert_custom_error(result, PredictionMarketError::MissingFundAccount);
+
+    let fund_pm_user = setup.fund_pm_user;
+    try_release(&mut setup, Some(fund_pm_user)).await.unwrap();
+    let creator = vault_ledger(&mut setup.context.banks_client, setup.creator_pm_user).await;
+    assert_eq!(creator.settlement(), (BOND, 0));
+    let fund = vault_ledger(&mut setup.context.banks_client, fund_pm_user).await;
+    assert_eq!(fund.settlement(), (0, BOND));
+}
+
+#[tokio::test]
+async fn test_release_rejected_before_resolution() {
+    let mut setup = setup(Some(BOND), Some((MarketStatus::Active, ReviewStatus::None))).await;
+
+    let result = try_release(&mut setup, None).await;
+    assert_custom_error(result, PredictionMarketError::InvalidMarketStatus);
+}
+
+#[tokio::test]
+async fn test_set_market_creation_bond_grows_config() {
+    let mut setup = setup(None, None).await;
+
+    let data = PredictionMarketInstruction::SetMarketCreationBond(SetMarketCreationBondArgs {
+        market_creation_bond_e6: BOND,
+    })
+    .try_to_vec()
+    .unwrap();
+    let accounts = |admin: Pubkey| {
+        vec![
+            AccountMeta::new(admin, true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]
+    };
+
+    let creator = setup.creator.insecure_clone();
+    let result = send(&mut setup.context, &creator, data.clone(), accounts(creator.pubkey())).await;
+    assert_custom_error(result, PredictionMarketError::Unauthorized);
+
+    let admin = setup.admin.insecure_clone();
+    send(&mut setup.context, &admin, data, accounts(admin.pubkey())).await.unwrap();
+    let config_data = account_data(&mut setup.context, config_pda()).await;
+    assert_eq!(config_data.len(), ConfigExtension::OFFSET + ConfigExtension::SIZE);
+    assert_eq!(ConfigExtension::load(&config_data).market_creation_bond_e6, BOND);
+    let config = PredictionMarketConfig::deserialize(&mut &config_data[..]).unwrap();
+    assert_eq!(config.next_market_id, MARKET_ID);
+}
@@ -0,0 +1,297 @@
+//! Integration tests for the ExecuteTradeV2 trading fee and maker rebate
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExecuteTradeArgs, PredictionMarketInstruction, SetMakerRebateArgs, SetTradeFeeArgs},
+    buy_margin, Market, Order, OrderSide, OrderStatus, Position, PredictionMarketConfig,
+    PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 16;
+const BUY_ORDER_ID: u64 = 1;
+const SELL_ORDER_ID: u64 = 2;
+const AMOUNT: u64 = 10_000_000;
+const SELL_PRICE: u64 = 500_000;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: AMOUNT,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, price: u64) -> Order {
+    Order {
+        price,
+        amount: AMOUNT,
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+fn test_position(owner: Pubkey, yes_locked: u64) -> Position {
+    Position {
+        yes_amount: yes_locked,
+        yes_locked,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    admin: Keypair,
+    vault_program: Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+    buyer_pm: Pubkey,
+    seller_pm: Pubkey,
+    fund_pm: Pubkey,
+}
+
+async fn setup(buy_price: u64) -> Setup {
+    let fund_program = Pubkey::new_unique();
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let admin = Keypair::new();
+    let config = PredictionMarketConfig {
+        fund_program,
+        ..test_config(admin.pubkey(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let buyer = Pubkey::new_unique();
+    let seller = Pubkey::new_unique();
+    add_order(&mut program_test, &test_order(BUY_ORDER_ID, buyer, OrderSide::Buy, buy_price));
+    add_order(&mut program_test, &test_order(SELL_ORDER_ID, seller, OrderSide::Sell, SELL_PRICE));
+    add_position(&mut program_test, &test_position(buyer, 0));
+    add_position(&mut program_test, &test_position(seller, AMOUNT));
+
+    let buyer_pm = add_pm_user(&mut program_test, &buyer, &vault_program);
+    let seller_pm = add_pm_user(&mut program_test, &seller, &vault_program);
+    let fund_pm = add_pm_user(&mut program_test, &fund_program, &vault_program);
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        admin,
+        vault_program,
+        buyer,
+        seller,
+        buyer_pm,
+        seller_pm,
+        fund_pm,
+    }
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.admin.pubkey()),
+        &[&setup.admin],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+async fn set_trade_fee(
+    setup: &mut Setup,
+    trade_fee_bps: u16,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let instruction = PredictionMarketInstruction::SetTradeFee(SetTradeFeeArgs { trade_fee_bps });
+    send_admin(setup, instruction).await
+}
+
+async fn set_maker_rebate(
+    setup: &mut Setup,
+    maker_rebate_bps: u16,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let instruction =
+        PredictionMarketInstruction::SetMakerRebate(SetMakerRebateArgs { maker_rebate_bps });
+    send_admin(setup, instruction).await
+}
+
+/// Send a config setter; both share the (admin, config) account layout
+async fn send_admin(
+    setup: &mut Setup,
+    instruction: PredictionMarketInstruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = instruction.try_to_vec().unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.admin.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+        ],
+    );
+    send(setup, ix).await
+}
+
+async fn execute_trade(
+    setup: &mut Setup,
+    price: u64,
+    with_fund: bool,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::ExecuteTradeV2(ExecuteTradeArgs {
+        market_id: MARKET_ID,
+        taker_order_id: BUY_ORDER_ID,
+        maker_order_id: SELL_ORDER_ID,
+        amount: AMOUNT,
+        price,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(setup.admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, BUY_ORDER_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, SELL_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.seller), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.buyer_pm, false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.seller_pm, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(setup.seller, false),
+    ];
+    if with_fund {
+        metas.push(AccountMeta::new(setup.fund_pm, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await
+}
+
+/// Returns (locked, settlement, released) recorded by the Vault stub
+async fn recorded(context: &mut ProgramTestContext, pm_user: Pubkey) -> (u64, u64, u64) {
+    vault_ledger(&mut context.banks_client, pm_user).await.trade_legs()
+}
+
+#[tokio::test]
+async fn test_trade_fee_charged_on_both_sides() {
+    let mut setup = setup(600_000).await;
+    set_trade_fee(&mut setup, 100).await.unwrap();
+
+    execute_trade(&mut setup, SELL_PRICE, true).await.unwrap();
+
+    // 10 shares at 0.50: cost 5 USDC, 1% fee per side
+    let cost = 5_000_000;
+    let fee = 50_000;
+    let margin = 6_000_000;
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (cost + fee, 0, margin - cost - fee));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, cost - fee, 0));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, 2 * fee, 0));
+}
+
+#[tokio::test]
+async fn test_zero_trade_fee_preserves_settlement() {
+    let mut setup = setup(600_000).await;
+
+    // No Fund account is needed when the fee is disabled
+    execute_trade(&mut setup, SELL_PRICE, false).await.unwrap();
+
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (5_000_000, 0, 1_000_000));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, 5_000_000, 0));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, 0, 0));
+}
+
+#[tokio::test]
+async fn test_trade_fee_requires_fund_account() {
+    let mut setup = setup(600_000).await;
+    set_trade_fee(&mut setup, 100).await.unwrap();
+
+    let result = execute_trade(&mut setup, SELL_PRICE, false).await;
+    assert_custom_error(result, PredictionMarketError::MissingFundAccount);
+}
+
+#[tokio::test]
+async fn test_trade_fee_fits_margin_locked_at_limit_price() {
+    // Buy order priced at the execution price; placement at a 1% fee
+    // locked the cost plus the full taker fee
+    let mut setup = setup(SELL_PRICE).await;
+    set_trade_fee(&mut setup, 100).await.unwrap();
+    let buy_order = Order {
+        locked_margin_e6: buy_margin(AMOUNT, SELL_PRICE, 100).unwrap(),
+        ..test_order(BUY_ORDER_ID, setup.buyer, OrderSide::Buy, SELL_PRICE)
+    };
+    let address = order_pda(MARKET_ID, BUY_ORDER_ID);
+    setup.context.set_account(&address, &program_account(&buy_order, Order::SIZE).into());
+
+    execute_trade(&mut setup, SELL_PRICE, true).await.unwrap();
+
+    // The fill consumes the whole lock, leaving nothing to release
+    let cost = 5_000_000;
+    let fee = 50_000;
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (cost + fee, 0, 0));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, cost - fee, 0));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, 2 * fee, 0));
+    let order: Order = load(&mut setup.context.banks_client, address).await;
+    assert_eq!(order.status, OrderStatus::Filled);
+}
+
+#[tokio::test]
+async fn test_set_trade_fee_bounds() {
+    let mut setup = setup(600_000).await;
+
+    let result = set_trade_fee(&mut setup, 501).await;
+    assert_custom_error(result, PredictionMarketError::TradeFeeTooHigh);
+    set_trade_fee(&mut setup, 500).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_maker_rebate_paid_to_seller() {
+    let mut setup = setup(600_000).await;
+    set_trade_fee(&mut setup, 100).await.unwrap();
+    set_maker_rebate(&mut setup, 50).await.unwrap();
+
+    execute_trade(&mut setup, SELL_PRICE, true).await.unwrap();
+
+    // The resting sell order gets 0.5% of the 5 USDC cost back; the taker
+    // still pays the full fee
+    let cost = 5_000_000;
+    let fee = 50_000;
+    let rebate = 25_000;
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (cost + fee, 0, 1_000_000 - fee));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, cost - fee + rebate, 0));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, 2 * fee - rebate, 0));
+}
+
+#[tokio::test]
+async fn test_maker_rebate_bounded_by_trade_fee() {
+    let mut setup = setup(600_000).await;
+    set_trade_fee(&mut setup, 100).await.unwrap();
+
+    let result = set_maker_rebate(&mut setup, 101).await;
+    assert_custom_error(result, PredictionMarketError::MakerRebateTooHigh);
+
+    // Lowering the fee below an existing rebate is rejected as well
+    set_maker_rebate(&mut setup, 100).await.unwrap();
+    let result = set_trade_fee(&mut setup, 99).await;
+    assert_custom_error(result, PredictionMarketError::MakerRebateTooHigh);
+}
@@ -0,0 +1,220 @@
+//! Integration tests for ExecuteTradeMultiV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExecuteTradeMultiArgs, PredictionMarketInstruction},
+    Market, Order, OrderSide, OrderStatus,
+    Outcome, Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 49;
+const TAKER_ORDER_ID: u64 = 10;
+const TAKER_AMOUNT: u64 = 10_000_000;
+const TAKER_PRICE: u64 = 500_000;
+const LEVEL_AMOUNT: u64 = 4_000_000;
+
+/// Maker (order_id, price), one seller each; the last one is above the
+/// taker's limit
+const MAKERS: [(u64, u64); 4] = [(1, 400_000), (2, 450_000), (3, 500_000), (4, 550_000)];
+
+fn test_market() -> Market {
+    Market {
+        total_minted: 4 * LEVEL_AMOUNT,
+        next_order_id: TAKER_ORDER_ID + 1,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, price: u64, amount: u64) -> Order {
+    Order {
+        price,
+        amount,
+        created_at: order_id as i64,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    buyer: Pubkey,
+    sellers: Vec<Pubkey>,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    // The buyer has no Position yet; the sweep creates it
+    let buyer = Pubkey::new_unique();
+    program_test.add_account(
+        order_pda(MARKET_ID, TAKER_ORDER_ID),
+        program_account(
+            &test_order(TAKER_ORDER_ID, buyer, OrderSide::Buy, TAKER_PRICE, TAKER_AMOUNT),
+            Order::SIZE,
+        ),
+    );
+
+    let mut sellers = Vec::new();
+    for (order_id, price) in MAKERS {
+        let seller = Pubkey::new_unique();
+        program_test.add_account(
+            order_pda(MARKET_ID, order_id),
+            program_account(
+                &test_order(order_id, seller, OrderSide::Sell, price, LEVEL_AMOUNT),
+                Order::SIZE,
+            ),
+        );
+        let mut position = Position::new(MARKET_ID, seller, 255, 0);
+        position.add_tokens(Outcome::Yes, LEVEL_AMOUNT, 300_000, 0).unwrap();
+        position.lock_shares(Outcome::Yes, LEVEL_AMOUNT).unwrap();
+        add_position(&mut program_test, &position);
+        sellers.push(seller);
+    }
+
+    for owner in sellers.iter().chain([&buyer]) {
+        add_pm_user(&mut program_test, owner, &vault_program);
+    }
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup { context: program_test.start_with_context().await, relayer, vault_program, buyer, sellers }
+}
+
+/// Sweeps the makers at the given MAKERS indices, in that order
+async fn execute_trade_multi(
+    setup: &mut Setup,
+    makers: &[usize],
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::ExecuteTradeMultiV2(ExecuteTradeMultiArgs {
+        market_id: MARKET_ID,
+        taker_order_id: TAKER_ORDER_ID,
+        maker_order_ids: makers.iter().map(|&i| MAKERS[i].0).collect(),
+        amount: TAKER_AMOUNT,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, TAKER_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(pm_user_pda(&setup.buyer, &setup.vault_program), false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for &i in makers {
+        let seller = setup.sellers[i];
+        metas.push(AccountMeta::new(order_pda(MARKET_ID, MAKERS[i].0), false));
+        metas.push(AccountMeta::new(position_pda(MARKET_ID, &seller), false));
+        metas.push(AccountMeta::new(pm_user_pda(&seller, &setup.vault_program), false));
+        metas.push(AccountMeta::new_readonly(seller, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+/// Returns (locked settled, settlement, released) recorded for `owner`
+async fn recorded(setup: &mut Setup, owner: Pubkey) -> (u64, u64, u64) {
+    let address = pm_user_pda(&owner, &setup.vault_program);
+    vault_ledger(&mut setup.context.banks_client, address).await.trade_legs()
+}
+
+#[tokio::test]
+async fn test_taker_sweeps_three_levels() {
+    let mut setup = setup().await;
+
+    execute_trade_multi(&mut setup, &[0, 1, 2, 3]).await.unwrap();
+
+    // 4 @ 0.40 + 4 @ 0.45 + 2 @ 0.50 = 4.4 USDC of the 5 USDC locked at 0.50
+    let buyer = setup.buyer;
+    assert_eq!(recorded(&mut setup, buyer).await, (4_400_000, 0, 600_000));
+    let taker: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, TAKER_ORDER_ID)).await;
+    assert_eq!(taker.status, OrderStatus::Filled);
+    assert_eq!(taker.filled_amount, TAKER_AMOUNT);
+
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &buyer)).await;
+    assert_eq!(position.yes_amount, TAKER_AMOUNT);
+    assert_eq!(position.settled_cost_e6, 4_400_000);
+
+    let expected = [
+        (1_600_000, LEVEL_AMOUNT, OrderStatus::Filled),
+        (1_800_000, LEVEL_AMOUNT, OrderStatus::Filled),
+        (1_000_000, 2_000_000, OrderStatus::PartialFilled),
+        (0, 0, OrderStatus::Open),
+    ];
+    for (i, (credit, filled, status)) in expected.into_iter().enumerate() {
+        let seller = setup.sellers[i];
+        assert_eq!(recorded(&mut setup, seller).await, (0, credit, 0));
+        let maker: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, MAKERS[i].0)).await;
+        assert_eq!((maker.filled_amount, maker.status), (filled, status));
+        let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &seller)).await;
+        assert_eq!(position.yes_amount, LEVEL_AMOUNT - filled);
+        assert_eq!(position.yes_locked, LEVEL_AMOUNT - filled);
+    }
+
+    let market: Market = load(&mut setup.context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.total_volume_e6, 4_400_000);
+}
+
+#[tokio::test]
+async fn test_sweep_stops_at_taker_limit_price() {
+    let mut setup = setup().await;
+
+    // The 0.55 level is above the taker's 0.50 limit
+    execute_trade_multi(&mut setup, &[0, 3]).await.unwrap();
+
+    let taker: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, TAKER_ORDER_ID)).await;
+    assert_eq!(taker.status, OrderStatus::PartialFilled);
+    assert_eq!(taker.filled_amount, LEVEL_AMOUNT);
+    let maker: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, MAKERS[3].0)).await;
+    assert_eq!(maker.status, OrderStatus::Open);
+
+    // Only the filled part's margin is settled; the rest stays locked
+    let buyer = setup.buyer;
+    assert_eq!(recorded(&mut setup, buyer).await, (1_600_000, 0, 400_000));
+}
+
+#[tokio::test]
+async fn test_makers_out_of_priority_rejected() {
+    let mut setup = setup().await;
+
+    let result = execute_trade_multi(&mut setup, &[1, 0]).await;
+    assert_custom_error(result, PredictionMarketError::MakerOrdersOutOfPriority);
+    let taker: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, TAKER_ORDER_ID)).await;
+    assert_eq!(taker.filled_amount, 0);
+}
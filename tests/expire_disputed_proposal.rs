@@ -0,0 +1,176 @@
+//! Integration tests for ExpireDisputedProposal
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExpireDisputedProposalArgs, PredictionMarketInstruction},
+    Market, MarketResult, MarketStatus, OracleProposal, PredictionMarketError, ProposalStatus,
+    DISPUTE_RESOLUTION_PERIOD_SECS,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 58;
+const CHALLENGE_DEADLINE: i64 = 7200;
+/// Committee deadline of a dispute started at the challenge deadline
+const RESOLUTION_DEADLINE: i64 = CHALLENGE_DEADLINE + DISPUTE_RESOLUTION_PERIOD_SECS;
+const PROPOSER_BOND: u64 = 5_000_000;
+const CHALLENGER_BOND: u64 = 3_000_000;
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::ResultProposed,
+        resolution_time: 0,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_proposal(proposer: Pubkey, challenger: Pubkey, resolution_deadline: i64) -> OracleProposal {
+    OracleProposal {
+        status: ProposalStatus::Disputed,
+        challenge_deadline: CHALLENGE_DEADLINE,
+        bond_amount: PROPOSER_BOND,
+        challenger: Some(challenger),
+        challenger_result: Some(MarketResult::No),
+        challenger_bond: CHALLENGER_BOND,
+        original_challenge_deadline: CHALLENGE_DEADLINE,
+        challenge_count: 1,
+        proposal_resolution_deadline: resolution_deadline,
+        ..common::test_proposal(MARKET_ID, proposer)
+    }
+}
+
+struct Dispute {
+    context: ProgramTestContext,
+    caller: Keypair,
+    vault_program: Pubkey,
+    proposer_pm_user: Pubkey,
+    challenger_pm_user: Pubkey,
+}
+
+/// A disputed proposal with the given stored resolution deadline, with the
+/// clock at `now`
+async fn setup(resolution_deadline: i64, now: i64) -> Dispute {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let config = test_config(Pubkey::new_unique(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let proposer = Pubkey::new_unique();
+    let challenger = Pubkey::new_unique();
+    add_proposal(&mut program_test, &test_proposal(proposer, challenger, resolution_deadline));
+    let proposer_pm_user = add_pm_user(&mut program_test, &proposer, &vault_program);
+    let challenger_pm_user = add_pm_user(&mut program_test, &challenger, &vault_program);
+
+    // Anyone may expire the dispute
+    let caller = Keypair::new();
+    program_test.add_account(
+        caller.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, now).await;
+    Dispute {
+        context,
+        caller,
+        vault_program,
+        proposer_pm_user,
+        challenger_pm_user,
+    }
+}
+
+async fn expire(dispute: &mut Dispute) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ExpireDisputedProposal(ExpireDisputedProposalArgs {
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(dispute.caller.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(proposal_pda(MARKET_ID), false),
+            AccountMeta::new(dispute.proposer_pm_user, false),
+            AccountMeta::new(dispute.challenger_pm_user, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(dispute.vault_program, false),
+        ],
+    );
+    let recent_blockhash = dispute.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&dispute.caller.pubkey()),
+        &[&dispute.caller],
+        recent_blockhash,
+    );
+    dispute.context.banks_client.process_transaction(tx).await
+}
+
+/// Returns the settled (locked, settlement) for proposer and challenger
+/// along with the market and proposal
+async fn load_dispute(dispute: &mut Dispute) -> ((u64, u64), (u64, u64), Market, OracleProposal) {
+    let banks_client = &mut dispute.context.banks_client;
+    (
+        vault_ledger(banks_client, dispute.proposer_pm_user).await.settlement(),
+        vault_ledger(banks_client, dispute.challenger_pm_user).await.settlement(),
+        load(banks_client, market_pda(MARKET_ID)).await,
+        load(banks_client, proposal_pda(MARKET_ID)).await,
+    )
+}
+
+#[tokio::test]
+async fn test_expire_disputed_proposal_returns_both_bonds() {
+    let mut dispute = setup(RESOLUTION_DEADLINE, RESOLUTION_DEADLINE + 1).await;
+
+    expire(&mut dispute).await.unwrap();
+
+    let (proposer, challenger, market, proposal) = load_dispute(&mut dispute).await;
+    assert_eq!(proposer, (PROPOSER_BOND, PROPOSER_BOND));
+    assert_eq!(challenger, (CHALLENGER_BOND, CHALLENGER_BOND));
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::Invalid));
+}
+
+#[tokio::test]
+async fn test_expire_disputed_proposal_before_deadline() {
+    let mut dispute = setup(RESOLUTION_DEADLINE, RESOLUTION_DEADLINE).await;
+
+    let result = expire(&mut dispute).await;
+    assert_custom_error(result, PredictionMarketError::DisputeResolutionPending);
+
+    let (proposer, _, market, proposal) = load_dispute(&mut dispute).await;
+    assert_eq!(proposer, (0, 0));
+    assert_eq!(proposal.status, ProposalStatus::Disputed);
+    assert_eq!(market.status, MarketStatus::ResultProposed);
+}
+
+#[tokio::test]
+async fn test_expire_legacy_dispute_after_fallback_deadline() {
+    // Disputed before the deadline was stored: one resolution period after
+    // the challenge deadline
+    let mut dispute = setup(0, RESOLUTION_DEADLINE + 1).await;
+
+    expire(&mut dispute).await.unwrap();
+
+    let (_, challenger, market, _) = load_dispute(&mut dispute).await;
+    assert_eq!(challenger, (CHALLENGER_BOND, CHALLENGER_BOND));
+    assert_eq!(market.final_result, Some(MarketResult::Invalid));
+}
@@ -0,0 +1,169 @@
+//! Integration tests for ExpireOrder
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExpireOrderArgs, PredictionMarketInstruction},
+    Market, Order, OrderSide, OrderStatus, OrderType, Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 19;
+const ORDER_ID: u64 = 1;
+const NOW: i64 = 1_700_000_000;
+const AMOUNT: u64 = 10_000_000;
+const FILLED: u64 = 4_000_000;
+const PRICE: u64 = 600_000;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: AMOUNT,
+        next_order_id: 2,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(owner: Pubkey, side: OrderSide, status: OrderStatus, expiration_time: i64) -> Order {
+    Order {
+        price: PRICE,
+        amount: AMOUNT,
+        filled_amount: if status == OrderStatus::Filled { AMOUNT } else { FILLED },
+        status,
+        order_type: OrderType::GTD,
+        expiration_time: Some(expiration_time),
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, ORDER_ID, owner, side)
+    }
+}
+
+fn test_position(owner: Pubkey) -> Position {
+    let remaining = AMOUNT - FILLED;
+    Position {
+        yes_amount: remaining,
+        yes_locked: remaining,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    vault_program: Pubkey,
+    owner: Pubkey,
+    pm_user: Pubkey,
+}
+
+async fn setup(side: OrderSide, status: OrderStatus, expiration_time: i64) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let config = test_config(Pubkey::new_unique(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let owner = Pubkey::new_unique();
+    add_order(&mut program_test, &test_order(owner, side, status, expiration_time));
+    add_position(&mut program_test, &test_position(owner));
+
+    let pm_user = add_pm_user(&mut program_test, &owner, &vault_program);
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+
+    Setup {
+        context,
+        vault_program,
+        owner,
+        pm_user,
+    }
+}
+
+/// Expire the order, signed by an arbitrary keeper (the payer)
+async fn try_expire(setup: &mut Setup) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::ExpireOrder(ExpireOrderArgs {
+        market_id: MARKET_ID,
+        order_id: ORDER_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.context.payer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new_readonly(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, ORDER_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.owner), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(setup.pm_user, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+        ],
+    );
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_expire_buy_order_returns_margin() {
+    let mut setup = setup(OrderSide::Buy, OrderStatus::PartialFilled, NOW - 10).await;
+
+    try_expire(&mut setup).await.unwrap();
+
+    // 6 unfilled shares at 0.60
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.pm_user).await;
+    assert_eq!(ledger.unlocked, 3_600_000);
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, ORDER_ID)).await;
+    assert_eq!(order.status, OrderStatus::Expired);
+    assert!(order.locks_released);
+    assert!(!order.is_cancellable());
+
+    // The margin is only returned once
+    let result = try_expire(&mut setup).await;
+    assert_custom_error(result, PredictionMarketError::OrderNotActive);
+}
+
+#[tokio::test]
+async fn test_expire_swept_sell_order_unlocks_shares() {
+    // Already marked Expired by SweepExpiredOrders, shares still locked
+    let mut setup = setup(OrderSide::Sell, OrderStatus::Expired, NOW - 10).await;
+
+    try_expire(&mut setup).await.unwrap();
+
+    let position_address = position_pda(MARKET_ID, &setup.owner);
+    let position: Position = load(&mut setup.context.banks_client, position_address).await;
+    assert_eq!(position.yes_locked, 0);
+    assert_eq!(position.yes_amount, AMOUNT - FILLED);
+    assert_eq!(vault_ledger(&mut setup.context.banks_client, setup.pm_user).await.calls, 0);
+}
+
+#[tokio::test]
+async fn test_expire_before_expiration_rejected() {
+    let mut setup = setup(OrderSide::Buy, OrderStatus::Open, NOW + 3600).await;
+
+    let result = try_expire(&mut setup).await;
+    assert_custom_error(result, PredictionMarketError::OrderNotExpired);
+    assert_eq!(vault_ledger(&mut setup.context.banks_client, setup.pm_user).await.calls, 0);
+}
+
+#[tokio::test]
+async fn test_expire_filled_order_rejected() {
+    let mut setup = setup(OrderSide::Buy, OrderStatus::Filled, NOW - 10).await;
+
+    let result = try_expire(&mut setup).await;
+    assert_custom_error(result, PredictionMarketError::OrderAlreadyFilled);
+}
@@ -0,0 +1,117 @@
+//! Integration tests for ExtendResolutionTime
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExtendResolutionTimeArgs, PredictionMarketInstruction},
+    Market, MarketStatus, OracleProposal, PredictionMarketError, MAX_RESOLUTION_EXTENSION_SECS,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 33;
+const NOW: i64 = 1_700_000_000;
+const RESOLUTION_TIME: i64 = NOW + 86_400;
+const FINALIZATION_DEADLINE: i64 = NOW + 2 * 86_400;
+const ONE_WEEK: i64 = 7 * 86_400;
+
+fn test_market() -> Market {
+    Market {
+        resolution_time: RESOLUTION_TIME,
+        finalization_deadline: FINALIZATION_DEADLINE,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_proposal() -> OracleProposal {
+    OracleProposal {
+        proposed_at: NOW,
+        challenge_deadline: NOW + 86_400,
+        original_challenge_deadline: NOW + 86_400,
+        ..common::test_proposal(MARKET_ID, Pubkey::new_unique())
+    }
+}
+
+/// Returns (context, admin)
+async fn setup(with_proposal: bool) -> (ProgramTestContext, Keypair) {
+    let mut program_test = program_test();
+
+    let admin = Keypair::new();
+    let config = test_config(admin.pubkey(), Pubkey::new_unique());
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+    if with_proposal {
+        add_proposal(&mut program_test, &test_proposal());
+    }
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+    (context, admin)
+}
+
+async fn try_extend(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    extension_secs: i64,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::ExtendResolutionTime(ExtendResolutionTimeArgs {
+        market_id: MARKET_ID,
+        extension_secs,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(signer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new_readonly(proposal_pda(MARKET_ID), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_extend_resolution_time() {
+    let (mut context, admin) = setup(false).await;
+
+    try_extend(&mut context, &admin, ONE_WEEK).await.unwrap();
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.resolution_time, RESOLUTION_TIME + ONE_WEEK);
+    assert_eq!(market.finalization_deadline, FINALIZATION_DEADLINE + ONE_WEEK);
+    assert_eq!(market.status, MarketStatus::Active);
+
+    // Bounded per call
+    let result = try_extend(&mut context, &admin, MAX_RESOLUTION_EXTENSION_SECS + 1).await;
+    assert_custom_error(result, PredictionMarketError::InvalidResolutionTime);
+
+    // Admin only
+    let result = try_extend(&mut context, &Keypair::new(), ONE_WEEK).await;
+    assert_custom_error(result, PredictionMarketError::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_extend_rejected_once_proposed() {
+    let (mut context, admin) = setup(true).await;
+
+    let result = try_extend(&mut context, &admin, ONE_WEEK).await;
+    assert_custom_error(result, PredictionMarketError::ProposalAlreadyExists);
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.resolution_time, RESOLUTION_TIME);
+}
@@ -0,0 +1,133 @@
+//! Integration tests for UpdateFeeSchedule
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, UpdateFeeScheduleArgs},
+    ConfigExtension, FeeSchedule, PredictionMarketConfig, PredictionMarketError,
+    MAX_FEE_SCHEDULE_BPS,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+/// Config in the 290-byte layout, without a ConfigExtension
+async fn setup() -> (ProgramTestContext, Keypair) {
+    let program_id = prediction_market_program::id();
+    let mut program_test = program_test();
+
+    let admin = Keypair::new();
+    let mut config = test_config(admin.pubkey(), Pubkey::new_unique());
+    config.trade_fee_bps = 30;
+    config.maker_rebate_bps = 10;
+    let mut data = config.try_to_vec().unwrap();
+    data.resize(PredictionMarketConfig::SIZE, 0);
+    program_test.add_account(
+        config_pda(),
+        Account {
+            lamports: 10_000_000,
+            data,
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // The config already holds enough lamports for the grown size
+    (program_test.start_with_context().await, admin)
+}
+
+async fn update_fee_schedule(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    fee_schedule: FeeSchedule,
+) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::UpdateFeeSchedule(UpdateFeeScheduleArgs { fee_schedule })
+        .try_to_vec()
+        .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(signer.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Returns the stored (fee schedule, config account length)
+async fn stored_fee_schedule(context: &mut ProgramTestContext) -> (FeeSchedule, usize) {
+    let account = context.banks_client.get_account(config_pda()).await.unwrap().unwrap();
+    let config = PredictionMarketConfig::deserialize(&mut &account.data[..]).unwrap();
+    let extension = ConfigExtension::load(&account.data);
+    (config.fee_schedule(&extension), account.data.len())
+}
+
+#[tokio::test]
+async fn test_update_fee_schedule_grows_legacy_config() {
+    let (mut context, admin) = setup().await;
+    let schedule = FeeSchedule { mint_bps: 20, redeem_bps: 15, trade_bps: 50, maker_rebate_bps: 20 };
+
+    update_fee_schedule(&mut context, &admin, schedule).await.unwrap();
+
+    assert_eq!(
+        stored_fee_schedule(&mut context).await,
+        (schedule, ConfigExtension::OFFSET + ConfigExtension::SIZE)
+    );
+
+    // Fees can be switched off again
+    update_fee_schedule(&mut context, &admin, FeeSchedule::default()).await.unwrap();
+    assert_eq!(stored_fee_schedule(&mut context).await.0, FeeSchedule::default());
+}
+
+#[tokio::test]
+async fn test_update_fee_schedule_rejects_rebate_above_trade_fee() {
+    let (mut context, admin) = setup().await;
+    let schedule = FeeSchedule { mint_bps: 0, redeem_bps: 0, trade_bps: 20, maker_rebate_bps: 25 };
+
+    assert_custom_error(
+        update_fee_schedule(&mut context, &admin, schedule).await,
+        PredictionMarketError::MakerRebateTooHigh,
+    );
+
+    let legacy = FeeSchedule { trade_bps: 30, maker_rebate_bps: 10, ..FeeSchedule::default() };
+    assert_eq!(stored_fee_schedule(&mut context).await, (legacy, PredictionMarketConfig::SIZE));
+}
+
+#[tokio::test]
+async fn test_update_fee_schedule_rejects_fee_above_maximum() {
+    let (mut context, admin) = setup().await;
+    let schedule = FeeSchedule { redeem_bps: MAX_FEE_SCHEDULE_BPS + 1, ..FeeSchedule::default() };
+
+    assert_custom_error(
+        update_fee_schedule(&mut context, &admin, schedule).await,
+        PredictionMarketError::FeeScheduleTooHigh,
+    );
+}
+
+#[tokio::test]
+async fn test_update_fee_schedule_rejects_non_admin() {
+    let (mut context, _admin) = setup().await;
+
+    assert_custom_error(
+        update_fee_schedule(&mut context, &Keypair::new(), FeeSchedule::default()).await,
+        PredictionMarketError::Unauthorized,
+    );
+}
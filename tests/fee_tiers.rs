@@ -0,0 +1,355 @@
+//! Integration tests for volume-tiered ExecuteTradeV2 / ExecuteTradeMultiV2
+//! taker fee discounts
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        ExecuteTradeArgs, ExecuteTradeMultiArgs, PredictionMarketInstruction, SetFeeTiersArgs,
+        SetTradeFeeArgs,
+    },
+    FeeTier, Market, Order, OrderSide, Position, PredictionMarketConfig, PredictionMarketError,
+    TraderStats, TRADER_STATS_SEED,
+};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 55;
+const BUY_ORDER_ID: u64 = 1;
+const SELL_ORDER_ID: u64 = 2;
+/// A second, better-priced maker for ExecuteTradeMultiV2 sweeps
+const SELL_ORDER_2_ID: u64 = 3;
+const PRICE_2: u64 = 450_000;
+/// Each trade fills 10 shares at 0.50: cost 5 USDC
+const FILL: u64 = 10_000_000;
+const PRICE: u64 = 500_000;
+const COST: u64 = 5_000_000;
+/// 1% trading fee per side
+const FEE: u64 = 50_000;
+
+fn trader_stats_pda(wallet: &Pubkey) -> Pubkey {
+    pda(&[TRADER_STATS_SEED, wallet.as_ref()])
+}
+
+fn test_market() -> Market {
+    Market {
+        total_minted: 3 * FILL,
+        next_order_id: 4,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Orders sized for two fills; the buy is priced above PRICE to leave
+/// margin for the fee
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, price: u64) -> Order {
+    Order {
+        price,
+        amount: 2 * FILL,
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+fn test_position(owner: Pubkey, yes_locked: u64) -> Position {
+    Position {
+        yes_amount: yes_locked,
+        yes_locked,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    admin: Keypair,
+    vault_program: Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+    seller_2: Pubkey,
+    buyer_pm: Pubkey,
+    seller_pm: Pubkey,
+    seller_2_pm: Pubkey,
+    fund_pm: Pubkey,
+}
+
+async fn setup() -> Setup {
+    setup_with(|_, _| {}).await
+}
+
+/// Like [`setup`], with `customize` adding accounts for the buyer
+async fn setup_with(customize: impl FnOnce(&mut ProgramTest, Pubkey)) -> Setup {
+    let fund_program = Pubkey::new_unique();
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let admin = Keypair::new();
+    let config = PredictionMarketConfig {
+        fund_program,
+        ..test_config(admin.pubkey(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let buyer = Pubkey::new_unique();
+    let seller = Pubkey::new_unique();
+    add_order(&mut program_test, &test_order(BUY_ORDER_ID, buyer, OrderSide::Buy, 600_000));
+    add_order(&mut program_test, &test_order(SELL_ORDER_ID, seller, OrderSide::Sell, PRICE));
+    add_position(&mut program_test, &test_position(buyer, 0));
+    add_position(&mut program_test, &test_position(seller, 2 * FILL));
+    let seller_2 = Pubkey::new_unique();
+    add_order(&mut program_test, &Order {
+        amount: FILL,
+        ..test_order(SELL_ORDER_2_ID, seller_2, OrderSide::Sell, PRICE_2)
+    });
+    add_position(&mut program_test, &test_position(seller_2, FILL));
+    customize(&mut program_test, buyer);
+
+    let buyer_pm = add_pm_user(&mut program_test, &buyer, &vault_program);
+    let seller_pm = add_pm_user(&mut program_test, &seller, &vault_program);
+    let seller_2_pm = add_pm_user(&mut program_test, &seller_2, &vault_program);
+    let fund_pm = add_pm_user(&mut program_test, &fund_program, &vault_program);
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let mut setup = Setup {
+        context: program_test.start_with_context().await,
+        admin,
+        vault_program,
+        buyer,
+        seller,
+        seller_2,
+        buyer_pm,
+        seller_pm,
+        seller_2_pm,
+        fund_pm,
+    };
+    let set_fee = PredictionMarketInstruction::SetTradeFee(SetTradeFeeArgs { trade_fee_bps: 100 });
+    send_admin(&mut setup, set_fee, false).await.unwrap();
+    setup
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), BanksClientError> {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.admin.pubkey()),
+        &[&setup.admin],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+/// Send a config setter; `grows` setters also take the System Program
+async fn send_admin(
+    setup: &mut Setup,
+    instruction: PredictionMarketInstruction,
+    grows: bool,
+) -> Result<(), BanksClientError> {
+    let mut metas = vec![
+        AccountMeta::new(setup.admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+    ];
+    if grows {
+        metas.push(AccountMeta::new_readonly(system_program::id(), false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &instruction.try_to_vec().unwrap(), metas);
+    send(setup, ix).await
+}
+
+/// Half the taker fee off once a trader has filled COST of volume
+async fn set_fee_tiers(setup: &mut Setup, discount_bps: u16) -> Result<(), BanksClientError> {
+    let fee_tiers = [
+        FeeTier { min_volume_e6: COST, discount_bps },
+        FeeTier::default(),
+    ];
+    let instruction = PredictionMarketInstruction::SetFeeTiers(SetFeeTiersArgs { fee_tiers });
+    send_admin(setup, instruction, true).await
+}
+
+async fn execute_trade(setup: &mut Setup, with_stats: bool) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ExecuteTradeV2(ExecuteTradeArgs {
+        market_id: MARKET_ID,
+        taker_order_id: BUY_ORDER_ID,
+        maker_order_id: SELL_ORDER_ID,
+        amount: FILL,
+        price: PRICE,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(setup.admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, BUY_ORDER_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, SELL_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.seller), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.buyer_pm, false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.seller_pm, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(setup.seller, false),
+        AccountMeta::new(setup.fund_pm, false),
+    ];
+    if with_stats {
+        metas.push(AccountMeta::new(trader_stats_pda(&setup.buyer), false));
+        metas.push(AccountMeta::new(trader_stats_pda(&setup.seller), false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await
+}
+
+/// Sweep the second maker, then the first, for the whole taker order
+async fn execute_trade_multi(setup: &mut Setup) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ExecuteTradeMultiV2(ExecuteTradeMultiArgs {
+        market_id: MARKET_ID,
+        taker_order_id: BUY_ORDER_ID,
+        maker_order_ids: vec![SELL_ORDER_2_ID, SELL_ORDER_ID],
+        amount: 2 * FILL,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(setup.admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, BUY_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.buyer_pm, false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (order_id, seller, seller_pm) in [
+        (SELL_ORDER_2_ID, setup.seller_2, setup.seller_2_pm),
+        (SELL_ORDER_ID, setup.seller, setup.seller_pm),
+    ] {
+        metas.push(AccountMeta::new(order_pda(MARKET_ID, order_id), false));
+        metas.push(AccountMeta::new(position_pda(MARKET_ID, &seller), false));
+        metas.push(AccountMeta::new(seller_pm, false));
+        metas.push(AccountMeta::new_readonly(seller, false));
+    }
+    metas.push(AccountMeta::new(setup.fund_pm, false));
+    for wallet in [setup.buyer, setup.seller, setup.seller_2] {
+        metas.push(AccountMeta::new(trader_stats_pda(&wallet), false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await
+}
+
+/// Returns the (locked, settlement) recorded by the Vault stub
+async fn recorded(context: &mut ProgramTestContext, pm_user: Pubkey) -> (u64, u64) {
+    vault_ledger(&mut context.banks_client, pm_user).await.settlement()
+}
+
+async fn trader_volume(context: &mut ProgramTestContext, wallet: Pubkey) -> Option<u64> {
+    let account = context.banks_client.get_account(trader_stats_pda(&wallet)).await.unwrap()?;
+    Some(TraderStats::deserialize(&mut &account.data[..]).unwrap().volume_e6)
+}
+
+#[tokio::test]
+async fn test_taker_fee_discounted_after_crossing_volume_tier() {
+    let mut setup = setup().await;
+    set_fee_tiers(&mut setup, 5_000).await.unwrap();
+
+    // First fill: no volume yet, full fee; the stats are created
+    execute_trade(&mut setup, true).await.unwrap();
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (COST + FEE, 0));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, 2 * FEE));
+    assert_eq!(trader_volume(&mut setup.context, setup.buyer).await, Some(COST));
+    assert_eq!(trader_volume(&mut setup.context, setup.seller).await, Some(COST));
+
+    // Second fill: the buyer reached the tier and pays half the taker fee;
+    // the maker side is unchanged
+    execute_trade(&mut setup, true).await.unwrap();
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (2 * COST + FEE + FEE / 2, 0));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, 2 * (COST - FEE)));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, 3 * FEE + FEE / 2));
+    assert_eq!(trader_volume(&mut setup.context, setup.buyer).await, Some(2 * COST));
+}
+
+#[tokio::test]
+async fn test_fee_tiers_require_trader_stats() {
+    let mut setup = setup().await;
+    set_fee_tiers(&mut setup, 5_000).await.unwrap();
+
+    assert_custom_error(execute_trade(&mut setup, false).await, PredictionMarketError::MissingTraderStats);
+}
+
+#[tokio::test]
+async fn test_trader_stats_optional_without_fee_tiers() {
+    let mut setup = setup().await;
+
+    // Without tiers the stats are still recorded when passed
+    execute_trade(&mut setup, true).await.unwrap();
+    assert_eq!(trader_volume(&mut setup.context, setup.buyer).await, Some(COST));
+
+    execute_trade(&mut setup, false).await.unwrap();
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (2 * (COST + FEE), 0));
+    assert_eq!(trader_volume(&mut setup.context, setup.buyer).await, Some(COST));
+}
+
+#[tokio::test]
+async fn test_multi_maker_sweep_discounts_taker_fee_and_records_volume() {
+    // The buyer already traded COST, reaching the half-fee tier
+    let mut setup = setup_with(|program_test, buyer| {
+        let (stats_pda, bump) = Pubkey::find_program_address(
+            &[TRADER_STATS_SEED, buyer.as_ref()],
+            &prediction_market_program::id(),
+        );
+        let stats = TraderStats {
+            volume_e6: COST,
+            ..TraderStats::new(buyer, bump, 0)
+        };
+        program_test.add_account(stats_pda, program_account(&stats, TraderStats::SIZE));
+    })
+    .await;
+    set_fee_tiers(&mut setup, 5_000).await.unwrap();
+
+    execute_trade_multi(&mut setup).await.unwrap();
+
+    // 10 @ 0.45 then 10 @ 0.50, each level's taker fee halved
+    let cost_2 = 4_500_000;
+    let fee_2 = 45_000;
+    assert_eq!(
+        recorded(&mut setup.context, setup.buyer_pm).await,
+        (cost_2 + fee_2 / 2 + COST + FEE / 2, 0)
+    );
+    assert_eq!(recorded(&mut setup.context, setup.seller_2_pm).await, (0, cost_2 - fee_2));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, COST - FEE));
+    assert_eq!(
+        recorded(&mut setup.context, setup.fund_pm).await,
+        (0, fee_2 + fee_2 / 2 + FEE + FEE / 2)
+    );
+
+    assert_eq!(trader_volume(&mut setup.context, setup.buyer).await, Some(COST + cost_2 + COST));
+    assert_eq!(trader_volume(&mut setup.context, setup.seller_2).await, Some(cost_2));
+    assert_eq!(trader_volume(&mut setup.context, setup.seller).await, Some(COST));
+}
+
+#[tokio::test]
+async fn test_set_fee_tiers_rejects_discount_above_fee() {
+    let mut setup = setup().await;
+
+    assert_custom_error(set_fee_tiers(&mut setup, 10_001).await, PredictionMarketError::FeeDiscountTooHigh);
+    set_fee_tiers(&mut setup, 10_000).await.unwrap();
+}
@@ -0,0 +1,162 @@
+//! Integration tests for FinalizeResult
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::PredictionMarketInstruction,
+    Market, MarketResult, MarketStatus, MarketType, OracleProposal,
+    PredictionMarketConfig, PredictionMarketError, ProposalStatus,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 9;
+const PROPOSER_BOND: u64 = 5_000_000;
+const CHALLENGE_DEADLINE: i64 = 4_000_000_000;
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::ResultProposed,
+        resolution_time: 0,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_proposal(proposer: Pubkey, status: ProposalStatus) -> OracleProposal {
+    OracleProposal {
+        proposed_result: MarketResult::No,
+        status,
+        challenge_deadline: CHALLENGE_DEADLINE,
+        bond_amount: PROPOSER_BOND,
+        original_challenge_deadline: CHALLENGE_DEADLINE,
+        ..common::test_proposal(MARKET_ID, proposer)
+    }
+}
+
+fn finalize_ix(caller: &Pubkey, vault_program: &Pubkey, pm_user: &Pubkey) -> Instruction {
+    let data = PredictionMarketInstruction::FinalizeResult.try_to_vec().unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*caller, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(proposal_pda(MARKET_ID), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+            AccountMeta::new(*pm_user, false),
+        ],
+    )
+}
+
+/// Returns (context, vault_program, proposer PMUserAccount)
+async fn setup(status: ProposalStatus) -> (ProgramTestContext, Pubkey, Pubkey) {
+    setup_with(status, None).await
+}
+
+/// `multi_outcome`: (num_outcomes, proposed outcome index) for a
+/// multi-outcome market
+async fn setup_with(
+    status: ProposalStatus,
+    multi_outcome: Option<(u8, u8)>,
+) -> (ProgramTestContext, Pubkey, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let proposer = Pubkey::new_unique();
+    let config = PredictionMarketConfig {
+        oracle_admin: proposer,
+        ..test_config(Pubkey::new_unique(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    let mut market = test_market();
+    let mut proposal = test_proposal(proposer, status);
+    if let Some((num_outcomes, outcome_index)) = multi_outcome {
+        market.market_type = MarketType::MultiOutcome;
+        market.num_outcomes = num_outcomes;
+        proposal.proposed_result = MarketResult::Yes;
+        proposal.proposed_outcome_index = Some(outcome_index);
+    }
+    add_market(&mut program_test, &market);
+    add_proposal(&mut program_test, &proposal);
+
+    let pm_user = add_pm_user(&mut program_test, &proposer, &vault_program);
+
+    (program_test.start_with_context().await, vault_program, pm_user)
+}
+
+async fn try_finalize(
+    context: &mut ProgramTestContext,
+    vault_program: &Pubkey,
+    pm_user: &Pubkey,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[finalize_ix(&context.payer.pubkey(), vault_program, pm_user)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_finalize_result_after_challenge_window() {
+    let (mut context, vault_program, pm_user) = setup(ProposalStatus::Pending).await;
+
+    // Still inside the challenge window
+    let result = try_finalize(&mut context, &vault_program, &pm_user).await;
+    assert_custom_error(result, PredictionMarketError::ChallengeWindowNotExpired);
+
+    set_clock(&mut context, CHALLENGE_DEADLINE).await;
+    try_finalize(&mut context, &vault_program, &pm_user).await.unwrap();
+
+    let account = context.banks_client.get_account(market_pda(MARKET_ID)).await.unwrap().unwrap();
+    let market = Market::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::No));
+
+    let account = context.banks_client.get_account(proposal_pda(MARKET_ID)).await.unwrap().unwrap();
+    let proposal = OracleProposal::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Finalized);
+
+    // Bond released to the proposer through the Vault
+    assert_eq!(vault_ledger(&mut context.banks_client, pm_user).await.unlocked, PROPOSER_BOND);
+}
+
+#[tokio::test]
+async fn test_finalize_result_rejects_disputed_proposal() {
+    let (mut context, vault_program, pm_user) = setup(ProposalStatus::Disputed).await;
+
+    set_clock(&mut context, CHALLENGE_DEADLINE).await;
+    let result = try_finalize(&mut context, &vault_program, &pm_user).await;
+    assert_custom_error(result, PredictionMarketError::ProposalUnderDispute);
+
+    let account = context.banks_client.get_account(market_pda(MARKET_ID)).await.unwrap().unwrap();
+    let market = Market::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(market.status, MarketStatus::ResultProposed);
+    assert_eq!(market.final_result, None);
+}
+
+#[tokio::test]
+async fn test_finalize_multi_outcome_result() {
+    let (mut context, vault_program, pm_user) =
+        setup_with(ProposalStatus::Pending, Some((4, 2))).await;
+
+    set_clock(&mut context, CHALLENGE_DEADLINE).await;
+    try_finalize(&mut context, &vault_program, &pm_user).await.unwrap();
+
+    let account = context.banks_client.get_account(market_pda(MARKET_ID)).await.unwrap().unwrap();
+    let market = Market::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.winning_outcome_index, Some(2));
+}
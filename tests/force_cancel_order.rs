@@ -0,0 +1,239 @@
+//! Integration tests for ForceCancelOrder
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ForceCancelOrderArgs, PredictionMarketInstruction},
+    Market, MarketStatus, MarketType, MultiOutcomePosition, Order, OrderSide, OrderStatus,
+    Outcome, Position, PredictionMarketError,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 61;
+/// Multi-outcome market with a Sell order on its last outcome
+const MULTI_MARKET_ID: u64 = 62;
+const NUM_OUTCOMES: u8 = 3;
+const AMOUNT: u64 = 10_000_000;
+const FILLED: u64 = 4_000_000;
+const PRICE: u64 = 500_000;
+
+const BUY_ORDER: u64 = 1;
+const SELL_ORDER: u64 = 2;
+
+fn test_market(status: MarketStatus) -> Market {
+    Market {
+        status,
+        total_minted: AMOUNT,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, filled_amount: u64) -> Order {
+    Order {
+        price: PRICE,
+        amount: AMOUNT,
+        filled_amount,
+        status: if filled_amount > 0 { OrderStatus::PartialFilled } else { OrderStatus::Open },
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    admin: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+    pm_user: Pubkey,
+}
+
+async fn setup(status: MarketStatus) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let admin = Keypair::new();
+    let config = test_config(admin.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(status));
+    add_market(&mut program_test, &Market {
+        status,
+        market_type: MarketType::MultiOutcome,
+        num_outcomes: NUM_OUTCOMES,
+        ..common::test_market(MULTI_MARKET_ID)
+    });
+
+    let user = Pubkey::new_unique();
+    for order in [
+        test_order(BUY_ORDER, user, OrderSide::Buy, 0),
+        test_order(SELL_ORDER, user, OrderSide::Sell, FILLED),
+    ] {
+        add_order(&mut program_test, &order);
+    }
+
+    // The seller still holds the unfilled part of the Sell order locked
+    let mut position = Position::new(MARKET_ID, user, 255, 0);
+    position.add_tokens(Outcome::Yes, AMOUNT, PRICE, 0).unwrap();
+    position.lock_shares(Outcome::Yes, AMOUNT - FILLED).unwrap();
+    add_position(&mut program_test, &position);
+
+    // Same for a Sell order on the multi-outcome market
+    let outcome_index = NUM_OUTCOMES - 1;
+    add_order(&mut program_test, &Order {
+        market_id: MULTI_MARKET_ID,
+        outcome_index,
+        ..test_order(SELL_ORDER, user, OrderSide::Sell, FILLED)
+    });
+    let mut multi_position = MultiOutcomePosition::new(MULTI_MARKET_ID, NUM_OUTCOMES, user, 255, 0);
+    multi_position.holdings[outcome_index as usize] = AMOUNT;
+    multi_position.locked[outcome_index as usize] = AMOUNT - FILLED;
+    program_test.add_account(
+        multi_position_pda(MULTI_MARKET_ID, &user),
+        program_account(&multi_position, MultiOutcomePosition::SIZE),
+    );
+
+    let pm_user = add_pm_user(&mut program_test, &user, &vault_program);
+
+    Setup { context: program_test.start_with_context().await, admin, vault_program, user, pm_user }
+}
+
+async fn force_cancel(setup: &mut Setup, signer: &Keypair, order_id: u64) -> Result<(), BanksClientError> {
+    let position = position_pda(MARKET_ID, &setup.user);
+    force_cancel_in(setup, signer, MARKET_ID, order_id, position).await
+}
+
+/// Force-cancel `order_id` of `market_id`, passing `position` as the
+/// owner's position account
+async fn force_cancel_in(
+    setup: &mut Setup,
+    signer: &Keypair,
+    market_id: u64,
+    order_id: u64,
+    position: Pubkey,
+) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ForceCancelOrder(ForceCancelOrderArgs {
+        market_id,
+        order_id,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(signer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(market_id), false),
+            AccountMeta::new(order_pda(market_id, order_id), false),
+            AccountMeta::new(position, false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(setup.pm_user, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+        ],
+    );
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, signer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+async fn released_margin(setup: &mut Setup) -> u64 {
+    vault_ledger(&mut setup.context.banks_client, setup.pm_user).await.unlocked
+}
+
+#[tokio::test]
+async fn test_admin_force_cancels_orders_in_paused_market() {
+    let mut setup = setup(MarketStatus::Paused).await;
+    let admin = setup.admin.insecure_clone();
+
+    // The Buy order's margin goes back to its owner: 10 @ 0.50 = 5 USDC
+    force_cancel(&mut setup, &admin, BUY_ORDER).await.unwrap();
+    assert_eq!(released_margin(&mut setup).await, 5_000_000);
+    let buy: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, BUY_ORDER)).await;
+    assert_eq!(buy.status, OrderStatus::Cancelled);
+    assert_eq!(buy.locked_margin_e6, 0);
+
+    // The Sell order's unfilled shares are unlocked
+    force_cancel(&mut setup, &admin, SELL_ORDER).await.unwrap();
+    let sell: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, SELL_ORDER)).await;
+    assert_eq!(sell.status, OrderStatus::Cancelled);
+    let position_address = position_pda(MARKET_ID, &setup.user);
+    let position: Position = load(&mut setup.context.banks_client, position_address).await;
+    assert_eq!(position.yes_locked, 0);
+    assert_eq!(position.yes_amount, AMOUNT);
+
+    // A cancelled order cannot be released twice
+    assert_custom_error(
+        force_cancel(&mut setup, &admin, BUY_ORDER).await,
+        PredictionMarketError::OrderNotActive,
+    );
+    assert_eq!(released_margin(&mut setup).await, 5_000_000);
+}
+
+#[tokio::test]
+async fn test_force_cancel_unlocks_multi_outcome_sell_shares() {
+    let mut setup = setup(MarketStatus::Paused).await;
+    let admin = setup.admin.insecure_clone();
+
+    // A binary Position is not the owner's MultiOutcomePosition
+    let binary_position = position_pda(MULTI_MARKET_ID, &setup.user);
+    assert_custom_error(
+        force_cancel_in(&mut setup, &admin, MULTI_MARKET_ID, SELL_ORDER, binary_position).await,
+        PredictionMarketError::InvalidPDA,
+    );
+
+    let position_address = multi_position_pda(MULTI_MARKET_ID, &setup.user);
+    force_cancel_in(&mut setup, &admin, MULTI_MARKET_ID, SELL_ORDER, position_address)
+        .await
+        .unwrap();
+    let sell: Order = load(&mut setup.context.banks_client, order_pda(MULTI_MARKET_ID, SELL_ORDER)).await;
+    assert_eq!(sell.status, OrderStatus::Cancelled);
+    let position: MultiOutcomePosition = load(&mut setup.context.banks_client, position_address).await;
+    let last = (NUM_OUTCOMES - 1) as usize;
+    assert_eq!((position.holdings[last], position.locked[last]), (AMOUNT, 0));
+}
+
+#[tokio::test]
+async fn test_force_cancel_allowed_in_cancelled_market() {
+    let mut setup = setup(MarketStatus::Cancelled).await;
+    let admin = setup.admin.insecure_clone();
+
+    force_cancel(&mut setup, &admin, BUY_ORDER).await.unwrap();
+    assert_eq!(released_margin(&mut setup).await, 5_000_000);
+}
+
+#[tokio::test]
+async fn test_force_cancel_rejects_active_market() {
+    let mut setup = setup(MarketStatus::Active).await;
+    let admin = setup.admin.insecure_clone();
+
+    assert_custom_error(
+        force_cancel(&mut setup, &admin, BUY_ORDER).await,
+        PredictionMarketError::InvalidMarketStatus,
+    );
+    let buy: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, BUY_ORDER)).await;
+    assert_eq!(buy.status, OrderStatus::Open);
+}
+
+#[tokio::test]
+async fn test_force_cancel_rejects_non_admin() {
+    let mut setup = setup(MarketStatus::Paused).await;
+
+    assert_custom_error(
+        force_cancel(&mut setup, &Keypair::new(), BUY_ORDER).await,
+        PredictionMarketError::Unauthorized,
+    );
+    assert_eq!(released_margin(&mut setup).await, 0);
+}
@@ -0,0 +1,98 @@
+//! Integration tests for Initialize program configuration checks
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{InitializeArgs, PredictionMarketInstruction},
+    PredictionMarketError,
+};
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+async fn setup() -> (BanksClient, Keypair) {
+    let program_test = program_test();
+    let (banks_client, payer, _) = program_test.start().await;
+    (banks_client, payer)
+}
+
+async fn try_initialize(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    vault_program: Pubkey,
+    fund_program: Pubkey,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::Initialize(InitializeArgs {
+        oracle_admin: Pubkey::new_unique(),
+        challenge_window_secs: 24 * 60 * 60,
+        proposer_bond_e6: 100_000_000,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(vault_program, false),
+            AccountMeta::new_readonly(fund_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+async fn assert_rejected(vault_program: Pubkey, fund_program: Pubkey) {
+    let (mut banks_client, payer) = setup().await;
+    let result = try_initialize(&mut banks_client, &payer, vault_program, fund_program).await;
+    assert_custom_error(result, PredictionMarketError::InvalidProgramConfig);
+    assert!(banks_client.get_account(config_pda()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_initialize_accepts_distinct_programs() {
+    let (mut banks_client, payer) = setup().await;
+    let vault_program = Pubkey::new_unique();
+    let fund_program = Pubkey::new_unique();
+
+    try_initialize(&mut banks_client, &payer, vault_program, fund_program)
+        .await
+        .unwrap();
+
+    let account = banks_client.get_account(config_pda()).await.unwrap().unwrap();
+    assert_eq!(account.owner, prediction_market_program::id());
+}
+
+#[tokio::test]
+async fn test_initialize_rejects_same_vault_and_fund() {
+    let program = Pubkey::new_unique();
+    assert_rejected(program, program).await;
+}
+
+#[tokio::test]
+async fn test_initialize_rejects_system_program() {
+    assert_rejected(system_program::id(), Pubkey::new_unique()).await;
+    assert_rejected(Pubkey::new_unique(), system_program::id()).await;
+}
+
+#[tokio::test]
+async fn test_initialize_rejects_self_as_vault_or_fund() {
+    assert_rejected(prediction_market_program::id(), Pubkey::new_unique()).await;
+    assert_rejected(Pubkey::new_unique(), prediction_market_program::id()).await;
+}
@@ -0,0 +1,254 @@
+//! Integration tests for Market orders placed through RelayerPlaceOrderV2
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{ExecuteTradeMultiArgs, PredictionMarketInstruction, RelayerPlaceOrderV2Args},
+    Market, Order, OrderSide, OrderStatus, OrderType,
+    Outcome, Position, PredictionMarketConfig, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 53;
+const TAKER_ORDER_ID: u64 = 10;
+const TAKER_AMOUNT: u64 = 8_000_000;
+const MAX_PRICE: u64 = 500_000;
+const LEVEL_AMOUNT: u64 = 4_000_000;
+
+/// Maker (order_id, price), one seller each; the last one is above the
+/// market order's slippage cap
+const MAKERS: [(u64, u64); 3] = [(1, 400_000), (2, 450_000), (3, 550_000)];
+
+fn test_market() -> Market {
+    Market {
+        total_minted: 3 * LEVEL_AMOUNT,
+        next_order_id: TAKER_ORDER_ID,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn maker_order(order_id: u64, owner: Pubkey, price: u64) -> Order {
+    Order {
+        price,
+        amount: LEVEL_AMOUNT,
+        created_at: order_id as i64,
+        ..test_order(MARKET_ID, order_id, owner, OrderSide::Sell)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    buyer: Pubkey,
+    sellers: Vec<Pubkey>,
+    fund_pm: Pubkey,
+}
+
+async fn setup() -> Setup {
+    setup_with_fee(0).await
+}
+
+async fn setup_with_fee(trade_fee_bps: u16) -> Setup {
+    let fund_program = Pubkey::new_unique();
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = PredictionMarketConfig {
+        fund_program,
+        trade_fee_bps,
+        ..test_config(relayer.pubkey(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    // The buyer has no Position or order yet; the order is placed as part
+    // of each test and the sweep creates the Position
+    let buyer = Pubkey::new_unique();
+
+    let mut sellers = Vec::new();
+    for (order_id, price) in MAKERS {
+        let seller = Pubkey::new_unique();
+        program_test.add_account(
+            order_pda(MARKET_ID, order_id),
+            program_account(
+                &maker_order(order_id, seller, price),
+                Order::SIZE,
+            ),
+        );
+        let mut position = Position::new(MARKET_ID, seller, 255, 0);
+        position.add_tokens(Outcome::Yes, LEVEL_AMOUNT, 300_000, 0).unwrap();
+        position.lock_shares(Outcome::Yes, LEVEL_AMOUNT).unwrap();
+        add_position(&mut program_test, &position);
+        sellers.push(seller);
+    }
+
+    for owner in sellers.iter().chain([&buyer]) {
+        add_pm_user(&mut program_test, owner, &vault_program);
+    }
+    let fund_pm = add_pm_user(&mut program_test, &fund_program, &vault_program);
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup { context: program_test.start_with_context().await, relayer, vault_program, buyer, sellers, fund_pm }
+}
+
+fn place_market_order_ix(setup: &Setup, max_price: u64) -> Instruction {
+    let data = PredictionMarketInstruction::RelayerPlaceOrderV2(RelayerPlaceOrderV2Args {
+        user_wallet: setup.buyer,
+        market_id: MARKET_ID,
+        side: OrderSide::Buy,
+        outcome: Outcome::Yes,
+        price: max_price,
+        amount: TAKER_AMOUNT,
+        order_type: OrderType::Market,
+        expiration_time: None,
+        reduce_only: false,
+        referrer: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, TAKER_ORDER_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(pm_user_pda(&setup.buyer, &setup.vault_program), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+/// Sweeps the makers at the given MAKERS indices with the market order
+fn execute_trade_multi_ix(setup: &Setup, makers: &[usize]) -> Instruction {
+    let data = PredictionMarketInstruction::ExecuteTradeMultiV2(ExecuteTradeMultiArgs {
+        market_id: MARKET_ID,
+        taker_order_id: TAKER_ORDER_ID,
+        maker_order_ids: makers.iter().map(|&i| MAKERS[i].0).collect(),
+        amount: TAKER_AMOUNT,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, TAKER_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(pm_user_pda(&setup.buyer, &setup.vault_program), false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for &i in makers {
+        let seller = setup.sellers[i];
+        metas.push(AccountMeta::new(order_pda(MARKET_ID, MAKERS[i].0), false));
+        metas.push(AccountMeta::new(position_pda(MARKET_ID, &seller), false));
+        metas.push(AccountMeta::new(pm_user_pda(&seller, &setup.vault_program), false));
+        metas.push(AccountMeta::new_readonly(seller, false));
+    }
+    metas.push(AccountMeta::new(setup.fund_pm, false));
+    Instruction::new_with_bytes(prediction_market_program::id(), &data, metas)
+}
+
+async fn send(setup: &mut Setup, ixs: &[Instruction]) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        ixs,
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+/// Returns (locked settled, settlement, released) recorded for `owner`
+async fn recorded(setup: &mut Setup, owner: Pubkey) -> (u64, u64, u64) {
+    let address = pm_user_pda(&owner, &setup.vault_program);
+    vault_ledger(&mut setup.context.banks_client, address).await.trade_legs()
+}
+
+#[tokio::test]
+async fn test_market_buy_filled_across_two_makers() {
+    let mut setup = setup().await;
+
+    let ixs = [place_market_order_ix(&setup, MAX_PRICE), execute_trade_multi_ix(&setup, &[0, 1])];
+    send(&mut setup, &ixs).await.unwrap();
+
+    // 4 @ 0.40 + 4 @ 0.45 = 3.4 USDC of the 4.0 locked at the 0.50 cap
+    let buyer = setup.buyer;
+    assert_eq!(recorded(&mut setup, buyer).await, (3_400_000, 0, 600_000));
+
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, TAKER_ORDER_ID)).await;
+    assert_eq!(order.order_type, OrderType::Market);
+    assert_eq!(order.status, OrderStatus::Filled);
+    assert_eq!(order.locked_margin_e6, 0);
+
+    let account = setup.context.banks_client.get_account(position_pda(MARKET_ID, &buyer)).await.unwrap().unwrap();
+    let position = Position::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(position.yes_amount, TAKER_AMOUNT);
+}
+
+#[tokio::test]
+async fn test_market_buy_beyond_slippage_cap_rejected() {
+    let mut setup = setup().await;
+
+    // Only 4 are offered at or below the 0.50 cap; the 0.55 level is not taken
+    let ixs = [place_market_order_ix(&setup, MAX_PRICE), execute_trade_multi_ix(&setup, &[0, 2])];
+    let result = send(&mut setup, &ixs).await;
+    assert_custom_error_at(result, 1, PredictionMarketError::MarketOrderNotFilled);
+
+    // Nothing rests on the book
+    let order = setup.context.banks_client.get_account(order_pda(MARKET_ID, TAKER_ORDER_ID)).await.unwrap();
+    assert!(order.is_none());
+}
+
+#[tokio::test]
+async fn test_market_order_without_fill_rejected() {
+    let mut setup = setup().await;
+
+    let ixs = [place_market_order_ix(&setup, MAX_PRICE)];
+    let result = send(&mut setup, &ixs).await;
+    assert_custom_error_at(result, 0, PredictionMarketError::MarketOrderNotFilled);
+}
+
+#[tokio::test]
+async fn test_market_buy_locks_taker_fee_headroom() {
+    let mut setup = setup_with_fee(100).await;
+
+    let ixs = [place_market_order_ix(&setup, MAX_PRICE), execute_trade_multi_ix(&setup, &[0, 1])];
+    send(&mut setup, &ixs).await.unwrap();
+
+    // 4.0 USDC at the 0.50 cap plus its 1% fee is locked; 3.4 USDC plus
+    // 0.034 USDC of fees is taken and the rest released
+    let buyer = setup.buyer;
+    let ledger = vault_ledger(&mut setup.context.banks_client, pm_user_pda(&buyer, &setup.vault_program)).await;
+    assert_eq!(ledger.locked, 4_040_000);
+    assert_eq!(ledger.trade_legs(), (3_434_000, 0, 606_000));
+}
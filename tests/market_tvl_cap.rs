@@ -0,0 +1,162 @@
+//! Integration tests for the per-market TVL cap circuit breaker
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerMintCompleteSetArgs, SetMarketTvlCapArgs},
+    Market, PredictionMarketError, POSITION_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 14;
+const ONE_USDC: u64 = 1_000_000;
+
+fn test_market(total_minted: u64, max_tvl_e6: u64) -> Market {
+    Market {
+        total_minted,
+        max_tvl_e6,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Returns (context, admin/relayer, vault_program)
+async fn setup(total_minted: u64, max_tvl_e6: u64) -> (ProgramTestContext, Keypair, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let admin = Keypair::new();
+    let config = test_config(admin.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(total_minted, max_tvl_e6));
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    (program_test.start_with_context().await, admin, vault_program)
+}
+
+fn mint_ix(relayer: &Pubkey, vault_program: &Pubkey, amount: u64) -> Instruction {
+    let user_wallet = Pubkey::new_unique();
+    let data = PredictionMarketInstruction::RelayerMintCompleteSetV2(RelayerMintCompleteSetArgs {
+        user_wallet,
+        market_id: MARKET_ID,
+        amount,
+        max_cost_e6: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*relayer, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(
+                pda(&[POSITION_SEED, &MARKET_ID.to_le_bytes(), user_wallet.as_ref()]),
+                false,
+            ),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn set_cap_ix(admin: &Pubkey, max_tvl_e6: u64) -> Instruction {
+    let data = PredictionMarketInstruction::SetMarketTvlCap(SetMarketTvlCapArgs {
+        market_id: MARKET_ID,
+        max_tvl_e6,
+    })
+    .try_to_vec()
+    .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+        ],
+    )
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_mint_up_to_tvl_cap() {
+    let (mut context, admin, vault_program) = setup(7 * ONE_USDC, 10 * ONE_USDC).await;
+
+    send(&mut context, &admin, mint_ix(&admin.pubkey(), &vault_program, 3 * ONE_USDC))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_mint_past_tvl_cap_rejected() {
+    let (mut context, admin, vault_program) = setup(7 * ONE_USDC, 10 * ONE_USDC).await;
+
+    let result =
+        send(&mut context, &admin, mint_ix(&admin.pubkey(), &vault_program, 3 * ONE_USDC + 1))
+            .await;
+    assert_custom_error(result, PredictionMarketError::TvlCapExceeded);
+}
+
+#[tokio::test]
+async fn test_zero_tvl_cap_disables_check() {
+    let (mut context, admin, vault_program) = setup(u64::MAX / 2, 0).await;
+
+    send(&mut context, &admin, mint_ix(&admin.pubkey(), &vault_program, 100 * ONE_USDC))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_admin_raises_tvl_cap() {
+    let (mut context, admin, vault_program) = setup(10 * ONE_USDC, 10 * ONE_USDC).await;
+
+    let result =
+        send(&mut context, &admin, mint_ix(&admin.pubkey(), &vault_program, ONE_USDC)).await;
+    assert_custom_error(result, PredictionMarketError::TvlCapExceeded);
+
+    // Only the admin may change the cap
+    let outsider = context.payer.insecure_clone();
+    let result = send(&mut context, &outsider, set_cap_ix(&outsider.pubkey(), 20 * ONE_USDC)).await;
+    assert_custom_error(result, PredictionMarketError::Unauthorized);
+
+    send(&mut context, &admin, set_cap_ix(&admin.pubkey(), 20 * ONE_USDC))
+        .await
+        .unwrap();
+    send(&mut context, &admin, mint_ix(&admin.pubkey(), &vault_program, ONE_USDC))
+        .await
+        .unwrap();
+}
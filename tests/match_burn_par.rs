@@ -0,0 +1,233 @@
+//! Integration tests for MatchBurnV2 proceeds never exceeding par and its
+//! seller account checks
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{MatchBurnArgs, PredictionMarketInstruction},
+    Market, Order, OrderSide,
+    Outcome, Position, PredictionMarketError, MAX_PRICE_SUM_DEVIATION,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 39;
+const SHARES: u64 = 7_000_001;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: SHARES,
+        open_interest: SHARES,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn sell_order(order_id: u64, owner: Pubkey, outcome: Outcome) -> Order {
+    Order {
+        outcome,
+        outcome_index: if outcome == Outcome::Yes { 0 } else { 1 },
+        price: 480_000,
+        amount: SHARES,
+        ..test_order(MARKET_ID, order_id, owner, OrderSide::Sell)
+    }
+}
+
+fn seller_position(owner: Pubkey, outcome: Outcome) -> Position {
+    let mut position = Position::new(MARKET_ID, owner, 255, 0);
+    position.add_tokens(outcome, SHARES, 500_000, 0).unwrap();
+    position.lock_shares(outcome, SHARES).unwrap();
+    position
+}
+
+struct Seller {
+    wallet: Pubkey,
+    pm_user: Pubkey,
+}
+
+/// Returns (context, relayer, vault_program, YES seller, NO seller)
+async fn setup() -> (ProgramTestContext, Keypair, Pubkey, Seller, Seller) {
+    setup_with(false).await
+}
+
+/// Like [`setup`]; with `one_wallet` a single seller placed both orders out
+/// of one Position and is returned twice
+async fn setup_with(one_wallet: bool) -> (ProgramTestContext, Keypair, Pubkey, Seller, Seller) {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let mut config = test_config(relayer.pubkey(), vault_program);
+    config.max_price_sum_deviation = MAX_PRICE_SUM_DEVIATION;
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    if one_wallet {
+        let wallet = Pubkey::new_unique();
+        add_order(&mut program_test, &sell_order(1, wallet, Outcome::Yes));
+        add_order(&mut program_test, &sell_order(2, wallet, Outcome::No));
+        let mut position = seller_position(wallet, Outcome::Yes);
+        position.add_tokens(Outcome::No, SHARES, 500_000, 0).unwrap();
+        position.lock_shares(Outcome::No, SHARES).unwrap();
+        add_position(&mut program_test, &position);
+        let pm_user = add_pm_user(&mut program_test, &wallet, &vault_program);
+        let context = program_test.start_with_context().await;
+        return (context, relayer, vault_program, Seller { wallet, pm_user }, Seller { wallet, pm_user });
+    }
+
+    let sellers = [Outcome::Yes, Outcome::No].map(|outcome| {
+        let wallet = Pubkey::new_unique();
+        let order_id = if outcome == Outcome::Yes { 1 } else { 2 };
+        add_order(&mut program_test, &sell_order(order_id, wallet, outcome));
+        add_position(&mut program_test, &seller_position(wallet, outcome));
+        Seller { wallet, pm_user: add_pm_user(&mut program_test, &wallet, &vault_program) }
+    });
+    let [yes_seller, no_seller] = sellers;
+
+    (program_test.start_with_context().await, relayer, vault_program, yes_seller, no_seller)
+}
+
+async fn try_burn(
+    context: &mut ProgramTestContext,
+    relayer: &Keypair,
+    vault_program: &Pubkey,
+    sellers: (&Seller, &Seller),
+    yes_price: u64,
+    no_price: u64,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::MatchBurnV2(MatchBurnArgs {
+        market_id: MARKET_ID,
+        yes_order_id: 1,
+        no_order_id: 2,
+        amount: SHARES,
+        yes_price,
+        no_price,
+    })
+    .try_to_vec()
+    .unwrap();
+    let (yes_seller, no_seller) = sellers;
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(relayer.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, 1), false),
+            AccountMeta::new(order_pda(MARKET_ID, 2), false),
+            AccountMeta::new(position_pda(MARKET_ID, &yes_seller.wallet), false),
+            AccountMeta::new(position_pda(MARKET_ID, &no_seller.wallet), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(yes_seller.pm_user, false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(no_seller.pm_user, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, relayer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Amount released to a seller through the Vault
+async fn released(context: &mut ProgramTestContext, seller: &Seller) -> u64 {
+    vault_ledger(&mut context.banks_client, seller.pm_user).await.unlocked
+}
+
+#[tokio::test]
+async fn test_burn_proceeds_capped_at_par() {
+    let (mut context, relayer, vault_program, yes_seller, no_seller) = setup().await;
+
+    // 0.53 + 0.52 is within the 5¢ tolerance but would pay out 1.05 per set
+    try_burn(&mut context, &relayer, &vault_program, (&yes_seller, &no_seller), 530_000, 520_000)
+        .await
+        .unwrap();
+
+    let yes_proceeds = released(&mut context, &yes_seller).await;
+    let no_proceeds = released(&mut context, &no_seller).await;
+    assert!(yes_proceeds + no_proceeds <= SHARES);
+    // Scaled to 0.504761 / 0.495239, each leg floored
+    assert_eq!(yes_proceeds, SHARES * 504_761 / 1_000_000);
+    assert_eq!(no_proceeds, SHARES * 495_239 / 1_000_000);
+}
+
+#[tokio::test]
+async fn test_burn_rejects_price_pair_far_above_par() {
+    let (mut context, relayer, vault_program, yes_seller, no_seller) = setup().await;
+
+    let result =
+        try_burn(&mut context, &relayer, &vault_program, (&yes_seller, &no_seller), 600_000, 600_000)
+            .await;
+    assert_custom_error(result, PredictionMarketError::InvalidPricePair);
+    assert_eq!(released(&mut context, &yes_seller).await, 0);
+    assert_eq!(released(&mut context, &no_seller).await, 0);
+}
+
+#[tokio::test]
+async fn test_burn_price_sum_par_boundary() {
+    // Exactly 1.0 and just above it are valid
+    for (yes_price, no_price) in [(500_000, 500_000), (500_001, 500_000)] {
+        let (mut context, relayer, vault_program, yes_seller, no_seller) = setup().await;
+        try_burn(&mut context, &relayer, &vault_program, (&yes_seller, &no_seller), yes_price, no_price)
+            .await
+            .unwrap();
+        let proceeds = released(&mut context, &yes_seller).await + released(&mut context, &no_seller).await;
+        assert!(proceeds <= SHARES);
+    }
+
+    // Just below 1.0 is BurnPriceSumTooLow
+    let (mut context, relayer, vault_program, yes_seller, no_seller) = setup().await;
+    let result =
+        try_burn(&mut context, &relayer, &vault_program, (&yes_seller, &no_seller), 499_999, 500_000)
+            .await;
+    assert_custom_error(result, PredictionMarketError::BurnPriceSumTooLow);
+}
+
+#[tokio::test]
+async fn test_burn_one_wallet_consumes_both_sides() {
+    let (mut context, relayer, vault_program, seller, same_seller) = setup_with(true).await;
+
+    try_burn(&mut context, &relayer, &vault_program, (&seller, &same_seller), 500_000, 500_000)
+        .await
+        .unwrap();
+
+    // Both sides left the shared Position; the NO write kept the YES update
+    let position: Position = load(&mut context.banks_client, position_pda(MARKET_ID, &seller.wallet)).await;
+    assert_eq!((position.yes_amount, position.yes_locked), (0, 0));
+    assert_eq!((position.no_amount, position.no_locked), (0, 0));
+    // Both legs' proceeds, each floored at 0.50
+    assert_eq!(released(&mut context, &seller).await, 2 * (SHARES / 2));
+}
+
+#[tokio::test]
+async fn test_burn_rejects_accounts_of_another_seller() {
+    let (mut context, relayer, vault_program, yes_seller, no_seller) = setup().await;
+
+    // The NO seller's PMUserAccount in the YES seller's slot
+    let impostor = Seller { wallet: yes_seller.wallet, pm_user: no_seller.pm_user };
+    let result =
+        try_burn(&mut context, &relayer, &vault_program, (&impostor, &no_seller), 500_000, 500_000)
+            .await;
+    assert_custom_error(result, PredictionMarketError::InvalidVaultAccount);
+
+    // The NO seller's Position in the YES seller's slot
+    let impostor = Seller { wallet: no_seller.wallet, pm_user: yes_seller.pm_user };
+    let result =
+        try_burn(&mut context, &relayer, &vault_program, (&impostor, &no_seller), 500_000, 500_000)
+            .await;
+    assert_custom_error(result, PredictionMarketError::InvalidPDA);
+    assert_eq!(released(&mut context, &yes_seller).await, 0);
+}
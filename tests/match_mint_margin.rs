@@ -0,0 +1,169 @@
+//! Integration tests for MatchMintV2 funding pre-locked and non-pre-locked
+//! buy orders
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{MatchMintArgs, PredictionMarketInstruction},
+    Market, Order, OrderSide,
+    Outcome,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 23;
+const AMOUNT: u64 = 10_000_000;
+const YES_PRICE: u64 = 600_000;
+const NO_PRICE: u64 = 400_000;
+
+fn test_market() -> Market {
+    Market {
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, outcome: Outcome, price: u64, margin_locked: bool) -> Order {
+    Order {
+        outcome,
+        outcome_index: if outcome == Outcome::Yes { 0 } else { 1 },
+        price,
+        amount: AMOUNT,
+        margin_locked,
+        ..common::test_order(MARKET_ID, order_id, owner, OrderSide::Buy)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    yes_buyer: Pubkey,
+    no_buyer: Pubkey,
+    yes_pm: Pubkey,
+    no_pm: Pubkey,
+}
+
+async fn setup(yes_margin_locked: bool, no_margin_locked: bool) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let yes_buyer = Pubkey::new_unique();
+    let no_buyer = Pubkey::new_unique();
+    let yes_order = test_order(1, yes_buyer, Outcome::Yes, YES_PRICE, yes_margin_locked);
+    let no_order = test_order(2, no_buyer, Outcome::No, NO_PRICE, no_margin_locked);
+    add_order(&mut program_test, &yes_order);
+    add_order(&mut program_test, &no_order);
+
+    let yes_pm = add_pm_user(&mut program_test, &yes_buyer, &vault_program);
+    let no_pm = add_pm_user(&mut program_test, &no_buyer, &vault_program);
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        yes_buyer,
+        no_buyer,
+        yes_pm,
+        no_pm,
+    }
+}
+
+async fn match_mint(setup: &mut Setup) {
+    let data = PredictionMarketInstruction::MatchMintV2(MatchMintArgs {
+        market_id: MARKET_ID,
+        yes_order_id: 1,
+        no_order_id: 2,
+        amount: AMOUNT,
+        yes_price: YES_PRICE,
+        no_price: NO_PRICE,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.relayer.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, 1), false),
+            AccountMeta::new(order_pda(MARKET_ID, 2), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.yes_buyer), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.no_buyer), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(setup.yes_pm, false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(setup.no_pm, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+/// Returns (locked, settled) recorded by the Vault stub
+async fn recorded(context: &mut ProgramTestContext, pm_user: Pubkey) -> (u64, u64) {
+    let ledger = vault_ledger(&mut context.banks_client, pm_user).await;
+    (ledger.locked, ledger.settled_locked)
+}
+
+#[tokio::test]
+async fn test_match_mint_pre_locked_orders_settle_without_locking() {
+    let mut setup = setup(true, true).await;
+
+    match_mint(&mut setup).await;
+
+    assert_eq!(recorded(&mut setup.context, setup.yes_pm).await, (0, 6_000_000));
+    assert_eq!(recorded(&mut setup.context, setup.no_pm).await, (0, 4_000_000));
+}
+
+#[tokio::test]
+async fn test_match_mint_funds_orders_without_pre_lock() {
+    let mut setup = setup(false, false).await;
+
+    match_mint(&mut setup).await;
+
+    // Locked now, then consumed by the same settle
+    assert_eq!(recorded(&mut setup.context, setup.yes_pm).await, (6_000_000, 6_000_000));
+    assert_eq!(recorded(&mut setup.context, setup.no_pm).await, (4_000_000, 4_000_000));
+}
+
+#[tokio::test]
+async fn test_match_mint_mixed_funding() {
+    let mut setup = setup(true, false).await;
+
+    match_mint(&mut setup).await;
+
+    assert_eq!(recorded(&mut setup.context, setup.yes_pm).await, (0, 6_000_000));
+    assert_eq!(recorded(&mut setup.context, setup.no_pm).await, (4_000_000, 4_000_000));
+}
@@ -0,0 +1,149 @@
+//! Integration tests for MatchMintMultiV2
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{MatchMintMultiV2Args, PredictionMarketInstruction},
+    Market, MarketType, MultiOutcomePosition, Order, OrderSide,
+    OrderStatus, PredictionMarketConfig, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const MARKET_ID: u64 = 37;
+const NUM_OUTCOMES: u8 = 3;
+const PRICES: [u64; 3] = [300_000, 300_000, 400_000];
+const AMOUNT: u64 = 10;
+
+fn test_market() -> Market {
+    Market {
+        market_type: MarketType::MultiOutcome,
+        num_outcomes: NUM_OUTCOMES,
+        resolution_time: 2_000_000_000,
+        finalization_deadline: 2_100_000_000,
+        created_at: 1000,
+        updated_at: 1000,
+        next_order_id: NUM_OUTCOMES as u64 + 1,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(outcome_index: u8, owner: Pubkey, status: OrderStatus) -> Order {
+    Order {
+        outcome_index,
+        price: PRICES[outcome_index as usize],
+        amount: 100,
+        status,
+        created_at: 1000,
+        updated_at: 1000,
+        ..common::test_order(MARKET_ID, outcome_index as u64 + 1, owner, OrderSide::Buy)
+    }
+}
+
+/// Returns (context, relayer, vault_program, buyer per outcome)
+async fn setup(last_order_status: OrderStatus) -> (ProgramTestContext, Keypair, Pubkey, Vec<Pubkey>) {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = PredictionMarketConfig {
+        oracle_admin: relayer.pubkey(),
+        ..test_config(relayer.pubkey(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let buyers: Vec<Pubkey> = (0..NUM_OUTCOMES).map(|_| Pubkey::new_unique()).collect();
+    for (i, buyer) in buyers.iter().enumerate() {
+        let status = if i + 1 == buyers.len() { last_order_status } else { OrderStatus::Open };
+        add_order(&mut program_test, &test_order(i as u8, *buyer, status));
+        program_test.add_account(
+            multi_position_pda(MARKET_ID, buyer),
+            program_account(&(), MultiOutcomePosition::SIZE),
+        );
+    }
+
+    (program_test.start_with_context().await, relayer, vault_program, buyers)
+}
+
+fn match_mint_multi_tx(
+    context: &ProgramTestContext,
+    relayer: &Keypair,
+    vault_program: &Pubkey,
+    buyers: &[Pubkey],
+) -> Transaction {
+    let data = PredictionMarketInstruction::MatchMintMultiV2(MatchMintMultiV2Args {
+        market_id: MARKET_ID,
+        num_outcomes: NUM_OUTCOMES,
+        amount: AMOUNT,
+        orders: (0..NUM_OUTCOMES)
+            .map(|i| (i, i as u64 + 1, PRICES[i as usize]))
+            .collect(),
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new_readonly(relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(*vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (i, buyer) in buyers.iter().enumerate() {
+        metas.push(AccountMeta::new(order_pda(MARKET_ID, i as u64 + 1), false));
+        metas.push(AccountMeta::new(multi_position_pda(MARKET_ID, buyer), false));
+        metas.push(AccountMeta::new(Pubkey::new_unique(), false));
+        metas.push(AccountMeta::new(Pubkey::new_unique(), false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, relayer],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn test_match_mint_multi_credits_every_outcome() {
+    let (mut context, relayer, vault_program, buyers) = setup(OrderStatus::Open).await;
+
+    let tx = match_mint_multi_tx(&context, &relayer, &vault_program, &buyers);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    for (i, buyer) in buyers.iter().enumerate() {
+        let account = context.banks_client.get_account(multi_position_pda(MARKET_ID, buyer)).await.unwrap().unwrap();
+        let position = MultiOutcomePosition::deserialize(&mut &account.data[..]).unwrap();
+        assert_eq!(position.holdings[i], AMOUNT);
+        assert_eq!(position.settled_cost_e6, AMOUNT * PRICES[i] / 1_000_000);
+    }
+}
+
+#[tokio::test]
+async fn test_match_mint_multi_inactive_last_order_moves_no_funds() {
+    let (mut context, relayer, vault_program, buyers) = setup(OrderStatus::Cancelled).await;
+
+    let tx = match_mint_multi_tx(&context, &relayer, &vault_program, &buyers);
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    assert_eq!(
+        simulation.result.unwrap().unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(PredictionMarketError::OrderNotActive as u32)
+        )
+    );
+
+    // Rejected in the validation pass, before any Vault CPI for earlier outcomes
+    let logs = simulation.simulation_details.unwrap().logs;
+    assert!(logs.iter().all(|log| !log.contains("CPI: Settle")));
+    assert!(logs.iter().all(|log| !log.contains(&vault_program.to_string())));
+}
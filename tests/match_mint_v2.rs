@@ -0,0 +1,413 @@
+//! Integration tests for MatchMintV2 position crediting
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        MatchMintArgs, PredictionMarketInstruction, RelayerMintCompleteSetArgs,
+        SetAllowMatchMintArgs,
+    },
+    AuthorizedCallers, Market, Order, OrderSide, Outcome, Position, PredictionMarketConfig,
+    PredictionMarketError, POSITION_DISCRIMINATOR, POSITION_SEED, AUTHORIZED_CALLERS_SEED,
+};
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 1;
+
+fn test_market() -> Market {
+    Market {
+        resolution_time: 2_000_000_000,
+        finalization_deadline: 2_100_000_000,
+        created_at: 1000,
+        updated_at: 1000,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, outcome: Outcome, price: u64) -> Order {
+    Order {
+        outcome,
+        outcome_index: if outcome == Outcome::Yes { 0 } else { 1 },
+        price,
+        amount: 100,
+        created_at: 1000,
+        updated_at: 1000,
+        ..common::test_order(MARKET_ID, order_id, owner, OrderSide::Buy)
+    }
+}
+
+fn match_mint_ix(
+    relayer: &Pubkey,
+    vault_program: &Pubkey,
+    yes_owner: &Pubkey,
+    no_owner: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    match_mint_ix_at(relayer, vault_program, yes_owner, no_owner, amount, 600_000, 400_000)
+}
+
+fn match_mint_ix_at(
+    relayer: &Pubkey,
+    vault_program: &Pubkey,
+    yes_owner: &Pubkey,
+    no_owner: &Pubkey,
+    amount: u64,
+    yes_price: u64,
+    no_price: u64,
+) -> Instruction {
+    let data = PredictionMarketInstruction::MatchMintV2(MatchMintArgs {
+        market_id: MARKET_ID,
+        yes_order_id: 1,
+        no_order_id: 2,
+        amount,
+        yes_price,
+        no_price,
+    })
+    .try_to_vec()
+    .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*relayer, true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, 1), false),
+            AccountMeta::new(order_pda(MARKET_ID, 2), false),
+            AccountMeta::new(position_pda(MARKET_ID, yes_owner), false),
+            AccountMeta::new(position_pda(MARKET_ID, no_owner), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+async fn setup(
+    yes_owner: Pubkey,
+    no_owner: Pubkey,
+    yes_position: Option<Position>,
+) -> (BanksClient, Keypair, Pubkey) {
+    setup_with_deviation(yes_owner, no_owner, yes_position, 0).await
+}
+
+async fn setup_with_deviation(
+    yes_owner: Pubkey,
+    no_owner: Pubkey,
+    yes_position: Option<Position>,
+    max_price_sum_deviation: u64,
+) -> (BanksClient, Keypair, Pubkey) {
+    setup_full(yes_owner, no_owner, yes_position, max_price_sum_deviation, false).await
+}
+
+/// With `keeper`, the relayer is neither admin nor oracle_admin and is only
+/// listed in the AuthorizedCallers registry
+async fn setup_full(
+    yes_owner: Pubkey,
+    no_owner: Pubkey,
+    yes_position: Option<Position>,
+    max_price_sum_deviation: u64,
+    keeper: bool,
+) -> (BanksClient, Keypair, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let admin = if keeper { Pubkey::new_unique() } else { relayer.pubkey() };
+    let config = PredictionMarketConfig {
+        oracle_admin: admin,
+        max_price_sum_deviation,
+        ..test_config(admin, vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+    add_order(&mut program_test, &test_order(1, yes_owner, Outcome::Yes, 600_000));
+    add_order(&mut program_test, &test_order(2, no_owner, Outcome::No, 400_000));
+    if keeper {
+        let mut registry = AuthorizedCallers::new(255, 1000);
+        registry.add_caller(relayer.pubkey(), 1000).unwrap();
+        program_test.add_account(
+            pda(&[AUTHORIZED_CALLERS_SEED]),
+            program_account(&registry, AuthorizedCallers::SIZE),
+        );
+    }
+    if let Some(position) = yes_position {
+        add_position(&mut program_test, &position);
+    }
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let (banks_client, _payer, _) = program_test.start().await;
+    (banks_client, relayer, vault_program)
+}
+
+async fn try_send(
+    banks_client: &mut BanksClient,
+    relayer: &Keypair,
+    ix: Instruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&relayer.pubkey()),
+        &[relayer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+async fn send(banks_client: &mut BanksClient, relayer: &Keypair, ix: Instruction) {
+    try_send(banks_client, relayer, ix).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_same_wallet_creates_one_position() {
+    let wallet = Pubkey::new_unique();
+    let (mut banks_client, relayer, vault_program) = setup(wallet, wallet, None).await;
+
+    send(
+        &mut banks_client,
+        &relayer,
+        match_mint_ix(&relayer.pubkey(), &vault_program, &wallet, &wallet, 40),
+    )
+    .await;
+
+    let position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &wallet)).await;
+    assert_eq!(position.yes_amount, 40);
+    assert_eq!(position.no_amount, 40);
+    assert_eq!(position.settled_cost_e6, 24 + 16);
+    assert_eq!(position.total_cost_e6, position.settled_cost_e6);
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_same_wallet_existing_position() {
+    let wallet = Pubkey::new_unique();
+    let (_, bump) = Pubkey::find_program_address(
+        &[POSITION_SEED, &MARKET_ID.to_le_bytes(), wallet.as_ref()],
+        &prediction_market_program::id(),
+    );
+    let mut existing = Position::new(MARKET_ID, wallet, bump, 1000);
+    existing.add_tokens(Outcome::Yes, 10, 500_000, 1000).unwrap();
+    let (mut banks_client, relayer, vault_program) = setup(wallet, wallet, Some(existing)).await;
+
+    send(
+        &mut banks_client,
+        &relayer,
+        match_mint_ix(&relayer.pubkey(), &vault_program, &wallet, &wallet, 60),
+    )
+    .await;
+
+    let position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &wallet)).await;
+    assert_eq!(position.yes_amount, 70);
+    assert_eq!(position.no_amount, 60);
+    assert_eq!(position.settled_cost_e6, 36 + 24);
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_distinct_wallets() {
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let (mut banks_client, relayer, vault_program) = setup(yes_owner, no_owner, None).await;
+
+    send(
+        &mut banks_client,
+        &relayer,
+        match_mint_ix(&relayer.pubkey(), &vault_program, &yes_owner, &no_owner, 50),
+    )
+    .await;
+
+    let yes_position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &yes_owner)).await;
+    assert_eq!((yes_position.yes_amount, yes_position.no_amount), (50, 0));
+    let no_position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &no_owner)).await;
+    assert_eq!((no_position.yes_amount, no_position.no_amount), (0, 50));
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_price_sum_tolerance_edges() {
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let (mut banks_client, relayer, vault) =
+        setup_with_deviation(yes_owner, no_owner, None, 10_000).await;
+    let ix = |amount, yes_price, no_price| {
+        let relayer = relayer.pubkey();
+        match_mint_ix_at(&relayer, &vault, &yes_owner, &no_owner, amount, yes_price, no_price)
+    };
+
+    // One unit past either edge is rejected
+    let result = try_send(&mut banks_client, &relayer, ix(10, 589_999, 400_000)).await;
+    assert_custom_error(result, PredictionMarketError::InvalidPricePair);
+    let result = try_send(&mut banks_client, &relayer, ix(10, 600_001, 400_000)).await;
+    assert_custom_error(result, PredictionMarketError::MintPriceSumTooHigh);
+
+    // Sum of exactly 1.0 - dev is accepted
+    send(&mut banks_client, &relayer, ix(10, 590_000, 400_000)).await;
+    let yes_position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &yes_owner)).await;
+    assert_eq!(yes_position.yes_amount, 10);
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_price_sum_par_boundary() {
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let (mut banks_client, relayer, vault) =
+        setup_with_deviation(yes_owner, no_owner, None, 10_000).await;
+    let ix = |amount, yes_price, no_price| {
+        let relayer = relayer.pubkey();
+        match_mint_ix_at(&relayer, &vault, &yes_owner, &no_owner, amount, yes_price, no_price)
+    };
+
+    // Exactly 1.0 and just below it are valid; just above is MintPriceSumTooHigh
+    send(&mut banks_client, &relayer, ix(10, 599_999, 400_000)).await;
+    send(&mut banks_client, &relayer, ix(10, 600_000, 400_000)).await;
+    let result = try_send(&mut banks_client, &relayer, ix(10, 600_000, 400_001)).await;
+    assert_custom_error(result, PredictionMarketError::MintPriceSumTooHigh);
+
+    let yes_position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &yes_owner)).await;
+    assert_eq!(yes_position.yes_amount, 20);
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_rejects_prices_out_of_bounds() {
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let (mut banks_client, relayer, vault) = setup(yes_owner, no_owner, None).await;
+    let ix = |yes_price, no_price| {
+        let relayer = relayer.pubkey();
+        match_mint_ix_at(&relayer, &vault, &yes_owner, &no_owner, 10, yes_price, no_price)
+    };
+
+    // Both pairs sum to exactly 1.0 but settle one leg at $0.00
+    let result = try_send(&mut banks_client, &relayer, ix(0, 1_000_000)).await;
+    assert_custom_error(result, PredictionMarketError::InvalidOrderPrice);
+    let result = try_send(&mut banks_client, &relayer, ix(1_000_000, 0)).await;
+    assert_custom_error(result, PredictionMarketError::InvalidOrderPrice);
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_registered_keeper() {
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let (mut banks_client, keeper, vault) = setup_full(yes_owner, no_owner, None, 0, true).await;
+
+    // Without the registry account the keeper is not trusted
+    let ix = match_mint_ix(&keeper.pubkey(), &vault, &yes_owner, &no_owner, 30);
+    let result = try_send(&mut banks_client, &keeper, ix).await;
+    assert_custom_error(result, PredictionMarketError::Unauthorized);
+
+    // Appending the registry authorizes it
+    let mut ix = match_mint_ix(&keeper.pubkey(), &vault, &yes_owner, &no_owner, 30);
+    ix.accounts.push(AccountMeta::new_readonly(pda(&[AUTHORIZED_CALLERS_SEED]), false));
+    send(&mut banks_client, &keeper, ix).await;
+    let yes_position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &yes_owner)).await;
+    assert_eq!(yes_position.yes_amount, 30);
+    let no_position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &no_owner)).await;
+    assert_eq!(no_position.no_amount, 30);
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_disabled_direct_mint_still_works() {
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let (mut banks_client, relayer, vault) = setup(yes_owner, no_owner, None).await;
+
+    let data = PredictionMarketInstruction::SetAllowMatchMint(SetAllowMatchMintArgs {
+        allow_match_mint: false,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(relayer.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+        ],
+    );
+    send(&mut banks_client, &relayer, ix).await;
+
+    let result = try_send(
+        &mut banks_client,
+        &relayer,
+        match_mint_ix(&relayer.pubkey(), &vault, &yes_owner, &no_owner, 30),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::MatchMintDisabled);
+
+    let data = PredictionMarketInstruction::RelayerMintCompleteSetV2(RelayerMintCompleteSetArgs {
+        user_wallet: yes_owner,
+        market_id: MARKET_ID,
+        amount: 30,
+        max_cost_e6: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &yes_owner), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(vault, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    send(&mut banks_client, &relayer, ix).await;
+    let position = load::<Position>(&mut banks_client, position_pda(MARKET_ID, &yes_owner)).await;
+    assert_eq!((position.yes_amount, position.no_amount), (30, 30));
+}
+
+#[tokio::test]
+async fn test_match_mint_v2_initializes_first_time_buyers() {
+    // Neither buyer has ever held a position in this market
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let (mut banks_client, relayer, vault_program) = setup(yes_owner, no_owner, None).await;
+    let relayer_before = banks_client.get_balance(relayer.pubkey()).await.unwrap();
+
+    send(
+        &mut banks_client,
+        &relayer,
+        match_mint_ix(&relayer.pubkey(), &vault_program, &yes_owner, &no_owner, 20),
+    )
+    .await;
+
+    let rent = banks_client.get_rent().await.unwrap().minimum_balance(Position::SIZE);
+    for (owner, expected) in [(yes_owner, (20, 0)), (no_owner, (0, 20))] {
+        let account = banks_client.get_account(position_pda(MARKET_ID, &owner)).await.unwrap().unwrap();
+        assert_eq!(account.owner, prediction_market_program::id());
+        assert_eq!(account.lamports, rent);
+        let position = Position::deserialize(&mut &account.data[..]).unwrap();
+        assert_eq!(position.discriminator, POSITION_DISCRIMINATOR);
+        assert_eq!((position.market_id, position.owner), (MARKET_ID, owner));
+        assert_eq!((position.yes_amount, position.no_amount), expected);
+    }
+
+    // The relayer paid both rents (plus the transaction fee)
+    let relayer_after = banks_client.get_balance(relayer.pubkey()).await.unwrap();
+    assert!(relayer_before - relayer_after >= 2 * rent);
+}
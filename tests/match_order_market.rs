@@ -0,0 +1,186 @@
+//! Integration tests for order validation in MatchMintV2 and MatchBurnV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{MatchBurnArgs, MatchMintArgs, PredictionMarketInstruction},
+    Market, Order, OrderSide,
+    Outcome, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 25;
+const OTHER_MARKET_ID: u64 = 26;
+
+fn test_market() -> Market {
+    Market {
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(
+    market_id: u64,
+    order_id: u64,
+    owner: Pubkey,
+    side: OrderSide,
+    outcome: Outcome,
+    price: u64,
+) -> Order {
+    Order {
+        outcome,
+        outcome_index: if outcome == Outcome::Yes { 0 } else { 1 },
+        price,
+        amount: 10_000_000,
+        ..common::test_order(market_id, order_id, owner, side)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    owner: Pubkey,
+    no_order_market: u64,
+}
+
+/// One wallet's YES at 0.60 and NO at 0.40; the NO order belongs to
+/// `no_order_market`
+async fn setup(side: OrderSide, no_order_market: u64) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let owner = Pubkey::new_unique();
+    let yes_order = test_order(MARKET_ID, 1, owner, side, Outcome::Yes, 600_000);
+    let no_order = test_order(no_order_market, 2, owner, side, Outcome::No, 400_000);
+    add_order(&mut program_test, &yes_order);
+    add_order(&mut program_test, &no_order);
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        owner,
+        no_order_market,
+    }
+}
+
+async fn send(
+    setup: &mut Setup,
+    data: Vec<u8>,
+    with_system_program: bool,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let mut metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, 1), false),
+        AccountMeta::new(order_pda(setup.no_order_market, 2), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.owner), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.owner), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+    ];
+    if with_system_program {
+        metas.push(AccountMeta::new_readonly(system_program::id(), false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+fn match_mint_data(yes_price: u64, no_price: u64) -> Vec<u8> {
+    PredictionMarketInstruction::MatchMintV2(MatchMintArgs {
+        market_id: MARKET_ID,
+        yes_order_id: 1,
+        no_order_id: 2,
+        amount: 10_000_000,
+        yes_price,
+        no_price,
+    })
+    .try_to_vec()
+    .unwrap()
+}
+
+fn match_burn_data(yes_price: u64, no_price: u64) -> Vec<u8> {
+    PredictionMarketInstruction::MatchBurnV2(MatchBurnArgs {
+        market_id: MARKET_ID,
+        yes_order_id: 1,
+        no_order_id: 2,
+        amount: 10_000_000,
+        yes_price,
+        no_price,
+    })
+    .try_to_vec()
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_match_mint_rejects_cross_market_order() {
+    let mut setup = setup(OrderSide::Buy, OTHER_MARKET_ID).await;
+
+    let result = send(&mut setup, match_mint_data(600_000, 400_000), true).await;
+    assert_custom_error(result, PredictionMarketError::OrderMarketMismatch);
+}
+
+#[tokio::test]
+async fn test_match_burn_rejects_cross_market_order() {
+    let mut setup = setup(OrderSide::Sell, OTHER_MARKET_ID).await;
+
+    let result = send(&mut setup, match_burn_data(600_000, 400_000), false).await;
+    assert_custom_error(result, PredictionMarketError::OrderMarketMismatch);
+}
+
+#[tokio::test]
+async fn test_match_mint_never_charges_above_buy_limit() {
+    let mut setup = setup(OrderSide::Buy, MARKET_ID).await;
+
+    // The pair still sums to 1.0, but YES would pay 0.61 against a 0.60 limit
+    let result = send(&mut setup, match_mint_data(610_000, 390_000), true).await;
+    assert_custom_error(result, PredictionMarketError::PriceMismatch);
+    let result = send(&mut setup, match_mint_data(590_000, 410_000), true).await;
+    assert_custom_error(result, PredictionMarketError::PriceMismatch);
+
+    // At the limits the match goes through
+    send(&mut setup, match_mint_data(600_000, 400_000), true).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_match_burn_never_pays_below_sell_limit() {
+    let mut setup = setup(OrderSide::Sell, MARKET_ID).await;
+
+    let result = send(&mut setup, match_burn_data(590_000, 410_000), false).await;
+    assert_custom_error(result, PredictionMarketError::PriceMismatch);
+}
@@ -0,0 +1,208 @@
+//! Integration tests for MintCompleteSet creating missing YES/NO token accounts
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{MintCompleteSetArgs, PredictionMarketInstruction},
+    Market, PredictionMarketConfig,
+    PredictionMarketError, MARKET_SEED,
+    POSITION_SEED,
+};
+use solana_program::program_pack::Pack;
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+const MARKET_ID: u64 = 47;
+const USER_USDC: u64 = 20_000_000;
+const SETS: u64 = 5_000_000;
+
+struct Accounts {
+    user: Keypair,
+    usdc_mint: Pubkey,
+    market_vault: Pubkey,
+    user_usdc: Pubkey,
+    yes_mint: Pubkey,
+    no_mint: Pubkey,
+}
+
+async fn setup() -> (BanksClient, Accounts) {
+    let program_id = prediction_market_program::id();
+    let mut program_test = program_test();
+
+    let accounts = Accounts {
+        user: Keypair::new(),
+        usdc_mint: Pubkey::new_unique(),
+        market_vault: Pubkey::new_unique(),
+        user_usdc: Pubkey::new_unique(),
+        yes_mint: Pubkey::new_unique(),
+        no_mint: Pubkey::new_unique(),
+    };
+    let config = PredictionMarketConfig {
+        usdc_mint: accounts.usdc_mint,
+        ..test_config(Pubkey::new_unique(), Pubkey::new_unique())
+    };
+    add_config(&mut program_test, &config);
+
+    let (market_address, market_bump) = Pubkey::find_program_address(
+        &[MARKET_SEED, &MARKET_ID.to_le_bytes()],
+        &program_id,
+    );
+    let market = Market {
+        yes_mint: accounts.yes_mint,
+        no_mint: accounts.no_mint,
+        market_vault: accounts.market_vault,
+        bump: market_bump,
+        ..test_market(MARKET_ID)
+    };
+    program_test.add_account(market_address, program_account(&market, Market::SIZE));
+
+    let usdc = accounts.usdc_mint;
+    let user = accounts.user.pubkey();
+    program_test.add_account(usdc, mint_account(Pubkey::new_unique()));
+    program_test.add_account(accounts.yes_mint, mint_account(market_address));
+    program_test.add_account(accounts.no_mint, mint_account(market_address));
+    program_test.add_account(accounts.market_vault, token_account(usdc, market_address, 0));
+    program_test.add_account(accounts.user_usdc, token_account(usdc, user, USER_USDC));
+    program_test.add_account(
+        user,
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let (banks_client, _payer, _) = program_test.start().await;
+    (banks_client, accounts)
+}
+
+/// Mints with the optional fund account (unused without a mint fee), USDC
+/// mint and, if given, the Associated Token Account Program appended
+async fn try_mint(
+    banks_client: &mut BanksClient,
+    accounts: &Accounts,
+    user_yes: Pubkey,
+    user_no: Pubkey,
+    ata_program: Option<Pubkey>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::MintCompleteSet(MintCompleteSetArgs {
+        market_id: MARKET_ID,
+        amount: SETS,
+    })
+    .try_to_vec()
+    .unwrap();
+    let user = accounts.user.pubkey();
+    let mut metas = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(accounts.market_vault, false),
+        AccountMeta::new(accounts.user_usdc, false),
+        AccountMeta::new(accounts.yes_mint, false),
+        AccountMeta::new(accounts.no_mint, false),
+        AccountMeta::new(user_yes, false),
+        AccountMeta::new(user_no, false),
+        AccountMeta::new(
+            pda(&[POSITION_SEED, &MARKET_ID.to_le_bytes(), user.as_ref()]),
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(accounts.usdc_mint, false),
+    ];
+    if let Some(ata_program) = ata_program {
+        metas.push(AccountMeta::new_readonly(ata_program, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&user),
+        &[&accounts.user],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+async fn token_state(banks_client: &mut BanksClient, address: Pubkey) -> spl_token::state::Account {
+    let account = banks_client.get_account(address).await.unwrap().unwrap();
+    assert_eq!(account.owner, spl_token::id());
+    spl_token::state::Account::unpack(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_mint_complete_set_creates_missing_atas() {
+    let (mut banks_client, accounts) = setup().await;
+    let user = accounts.user.pubkey();
+    let user_yes = get_associated_token_address(&user, &accounts.yes_mint);
+    let user_no = get_associated_token_address(&user, &accounts.no_mint);
+    assert!(banks_client.get_account(user_yes).await.unwrap().is_none());
+    assert!(banks_client.get_account(user_no).await.unwrap().is_none());
+
+    try_mint(
+        &mut banks_client,
+        &accounts,
+        user_yes,
+        user_no,
+        Some(spl_associated_token_account::id()),
+    )
+    .await
+    .unwrap();
+
+    for (address, mint) in [(user_yes, accounts.yes_mint), (user_no, accounts.no_mint)] {
+        let state = token_state(&mut banks_client, address).await;
+        assert_eq!(state.mint, mint);
+        assert_eq!(state.owner, user);
+        assert_eq!(state.amount, SETS);
+    }
+
+    // A second mint reuses the now-existing accounts
+    try_mint(&mut banks_client, &accounts, user_yes, user_no, None)
+        .await
+        .unwrap();
+    assert_eq!(token_state(&mut banks_client, user_yes).await.amount, 2 * SETS);
+    assert_eq!(token_state(&mut banks_client, user_no).await.amount, 2 * SETS);
+}
+
+#[tokio::test]
+async fn test_mint_complete_set_rejects_non_ata_address() {
+    let (mut banks_client, accounts) = setup().await;
+    let user = accounts.user.pubkey();
+    let user_yes = get_associated_token_address(&user, &accounts.yes_mint);
+
+    // An arbitrary empty address cannot be created as the NO account
+    let result = try_mint(
+        &mut banks_client,
+        &accounts,
+        user_yes,
+        Pubkey::new_unique(),
+        Some(spl_associated_token_account::id()),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::InvalidTokenAccount);
+    assert!(banks_client.get_account(user_yes).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_mint_complete_set_requires_ata_program_for_missing_accounts() {
+    let (mut banks_client, accounts) = setup().await;
+    let user = accounts.user.pubkey();
+    let user_yes = get_associated_token_address(&user, &accounts.yes_mint);
+    let user_no = get_associated_token_address(&user, &accounts.no_mint);
+
+    let result = try_mint(&mut banks_client, &accounts, user_yes, user_no, None).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+}
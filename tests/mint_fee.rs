@@ -0,0 +1,181 @@
+//! Integration tests for the MintCompleteSet fee
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{MintCompleteSetArgs, PredictionMarketInstruction},
+    Market, PredictionMarketConfig,
+    PredictionMarketError, MARKET_SEED,
+    POSITION_SEED,
+};
+use solana_program::program_pack::Pack;
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 34;
+const MINT_FEE_BPS: u16 = 50;
+const USER_USDC: u64 = 20_000_000;
+
+struct Accounts {
+    user: Keypair,
+    usdc_mint: Pubkey,
+    fund_program: Pubkey,
+    market_vault: Pubkey,
+    user_usdc: Pubkey,
+    yes_mint: Pubkey,
+    no_mint: Pubkey,
+    user_yes: Pubkey,
+    user_no: Pubkey,
+    fund_usdc: Pubkey,
+}
+
+async fn setup() -> (BanksClient, Accounts) {
+    let program_id = prediction_market_program::id();
+    let mut program_test = program_test();
+
+    let accounts = Accounts {
+        user: Keypair::new(),
+        usdc_mint: Pubkey::new_unique(),
+        fund_program: Pubkey::new_unique(),
+        market_vault: Pubkey::new_unique(),
+        user_usdc: Pubkey::new_unique(),
+        yes_mint: Pubkey::new_unique(),
+        no_mint: Pubkey::new_unique(),
+        user_yes: Pubkey::new_unique(),
+        user_no: Pubkey::new_unique(),
+        fund_usdc: Pubkey::new_unique(),
+    };
+    let config = PredictionMarketConfig {
+        usdc_mint: accounts.usdc_mint,
+        fund_program: accounts.fund_program,
+        mint_fee_bps: MINT_FEE_BPS,
+        ..test_config(Pubkey::new_unique(), Pubkey::new_unique())
+    };
+    add_config(&mut program_test, &config);
+
+    let (market_address, market_bump) = Pubkey::find_program_address(
+        &[MARKET_SEED, &MARKET_ID.to_le_bytes()],
+        &program_id,
+    );
+    let market = Market {
+        yes_mint: accounts.yes_mint,
+        no_mint: accounts.no_mint,
+        market_vault: accounts.market_vault,
+        bump: market_bump,
+        ..test_market(MARKET_ID)
+    };
+    program_test.add_account(market_address, program_account(&market, Market::SIZE));
+
+    let usdc = accounts.usdc_mint;
+    let user = accounts.user.pubkey();
+    program_test.add_account(usdc, mint_account(Pubkey::new_unique()));
+    program_test.add_account(accounts.yes_mint, mint_account(market_address));
+    program_test.add_account(accounts.no_mint, mint_account(market_address));
+    program_test.add_account(accounts.market_vault, token_account(usdc, market_address, 0));
+    program_test.add_account(accounts.user_usdc, token_account(usdc, user, USER_USDC));
+    program_test.add_account(accounts.user_yes, token_account(accounts.yes_mint, user, 0));
+    program_test.add_account(accounts.user_no, token_account(accounts.no_mint, user, 0));
+    program_test.add_account(
+        accounts.fund_usdc,
+        token_account(usdc, accounts.fund_program, 0),
+    );
+    program_test.add_account(
+        user,
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let (banks_client, _payer, _) = program_test.start().await;
+    (banks_client, accounts)
+}
+
+async fn try_mint(
+    banks_client: &mut BanksClient,
+    accounts: &Accounts,
+    amount: u64,
+    fee_account: Option<Pubkey>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::MintCompleteSet(MintCompleteSetArgs {
+        market_id: MARKET_ID,
+        amount,
+    })
+    .try_to_vec()
+    .unwrap();
+    let user = accounts.user.pubkey();
+    let mut metas = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(accounts.market_vault, false),
+        AccountMeta::new(accounts.user_usdc, false),
+        AccountMeta::new(accounts.yes_mint, false),
+        AccountMeta::new(accounts.no_mint, false),
+        AccountMeta::new(accounts.user_yes, false),
+        AccountMeta::new(accounts.user_no, false),
+        AccountMeta::new(
+            pda(&[POSITION_SEED, &MARKET_ID.to_le_bytes(), user.as_ref()]),
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(fee_account) = fee_account {
+        metas.push(AccountMeta::new(fee_account, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&user),
+        &[&accounts.user],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+async fn balance(banks_client: &mut BanksClient, address: Pubkey) -> u64 {
+    let account = banks_client.get_account(address).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+#[tokio::test]
+async fn test_mint_complete_set_collects_fee() {
+    let (mut banks_client, accounts) = setup().await;
+
+    // 10 sets at 0.5%: 10 USDC collateral + 0.05 USDC fee
+    try_mint(&mut banks_client, &accounts, 10_000_000, Some(accounts.fund_usdc))
+        .await
+        .unwrap();
+
+    assert_eq!(balance(&mut banks_client, accounts.market_vault).await, 10_000_000);
+    assert_eq!(balance(&mut banks_client, accounts.fund_usdc).await, 50_000);
+    assert_eq!(
+        balance(&mut banks_client, accounts.user_usdc).await,
+        USER_USDC - 10_050_000
+    );
+    assert_eq!(balance(&mut banks_client, accounts.user_yes).await, 10_000_000);
+    assert_eq!(balance(&mut banks_client, accounts.user_no).await, 10_000_000);
+}
+
+#[tokio::test]
+async fn test_mint_complete_set_fee_account_checked() {
+    let (mut banks_client, accounts) = setup().await;
+
+    let result = try_mint(&mut banks_client, &accounts, 10_000_000, None).await;
+    assert_custom_error(result, PredictionMarketError::MissingFundAccount);
+
+    // The user's own USDC account cannot stand in for the Fund's
+    let result = try_mint(&mut banks_client, &accounts, 10_000_000, Some(accounts.user_usdc)).await;
+    assert_custom_error(result, PredictionMarketError::InvalidTokenAccount);
+}
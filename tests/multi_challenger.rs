@@ -0,0 +1,251 @@
+//! Integration tests for disputes joined by several challengers
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ChallengeResultArgs, PredictionMarketInstruction, ResolveDisputeArgs},
+    ChallengerRecord, Market, MarketResult, MarketStatus,
+    OracleProposal, PredictionMarketConfig, PredictionMarketError, ProposalStatus,
+    CHALLENGER_RECORD_SEED,
+    MAX_DISPUTE_CHALLENGERS,
+};
+use solana_program_test::{BanksClient, BanksClientError};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 59;
+const PROPOSER_BOND: u64 = 5_000_000;
+/// The proposer, the most challengers a dispute accepts and one more
+const PARTIES: usize = 2 + MAX_DISPUTE_CHALLENGERS as usize;
+
+fn record_pda(index: u8) -> Pubkey {
+    pda(&[CHALLENGER_RECORD_SEED, &MARKET_ID.to_le_bytes(), &[index]])
+}
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::ResultProposed,
+        resolution_time: 0,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_proposal(proposer: Pubkey) -> OracleProposal {
+    OracleProposal {
+        bond_amount: PROPOSER_BOND,
+        ..common::test_proposal(MARKET_ID, proposer)
+    }
+}
+
+struct Dispute {
+    banks_client: BanksClient,
+    committee: Keypair,
+    vault_program: Pubkey,
+    /// The proposer followed by the challengers, in challenge order
+    parties: Vec<Keypair>,
+    fund_pm_user: Pubkey,
+}
+
+impl Dispute {
+    fn pm_user(&self, party: usize) -> Pubkey {
+        pm_user_pda(&self.parties[party].pubkey(), &self.vault_program)
+    }
+}
+
+/// A pending proposal by party 0, the oracle admin acting as committee
+async fn setup() -> Dispute {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let committee = Keypair::new();
+    let parties: Vec<Keypair> = (0..PARTIES).map(|_| Keypair::new()).collect();
+    let fund_program = Pubkey::new_unique();
+    let config = PredictionMarketConfig {
+        oracle_admin: committee.pubkey(),
+        fund_program,
+        proposer_bond_e6: PROPOSER_BOND,
+        ..test_config(Pubkey::new_unique(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+    add_proposal(&mut program_test, &test_proposal(parties[0].pubkey()));
+
+    for party in parties.iter().chain([&committee]) {
+        program_test.add_account(
+            party.pubkey(),
+            Account {
+                lamports: 10_000_000_000,
+                ..Account::default()
+            },
+        );
+        add_pm_user(&mut program_test, &party.pubkey(), &vault_program);
+    }
+    let fund_pm_user = add_pm_user(&mut program_test, &fund_program, &vault_program);
+
+    let (banks_client, _payer, _) = program_test.start().await;
+    Dispute {
+        banks_client,
+        committee,
+        vault_program,
+        parties,
+        fund_pm_user,
+    }
+}
+
+async fn try_send(
+    banks_client: &mut BanksClient,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+/// Party `party` challenges for `result`; every challenger after the first
+/// gets the next ChallengerRecord
+async fn challenge(dispute: &mut Dispute, party: usize, result: MarketResult) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ChallengeResult(ChallengeResultArgs {
+        market_id: MARKET_ID,
+        result,
+    })
+    .try_to_vec()
+    .unwrap();
+    let challenger = dispute.parties[party].pubkey();
+    let mut metas = vec![
+        AccountMeta::new(challenger, true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new_readonly(market_pda(MARKET_ID), false),
+        AccountMeta::new(proposal_pda(MARKET_ID), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(dispute.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(dispute.pm_user(party), false),
+    ];
+    if party > 1 {
+        metas.push(AccountMeta::new(record_pda(party as u8 - 2), false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    try_send(&mut dispute.banks_client, &dispute.parties[party], ix).await
+}
+
+/// ResolveDispute with `result`, passing the `escalated` challengers' records
+async fn resolve(dispute: &mut Dispute, result: MarketResult, escalated: usize) {
+    let data = PredictionMarketInstruction::ResolveDispute(ResolveDisputeArgs {
+        market_id: MARKET_ID,
+        result,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(dispute.committee.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(proposal_pda(MARKET_ID), false),
+        AccountMeta::new(dispute.pm_user(0), false),
+        AccountMeta::new(dispute.pm_user(1), false),
+        AccountMeta::new_readonly(vault_config_pda(&dispute.vault_program), false),
+        AccountMeta::new_readonly(dispute.vault_program, false),
+    ];
+    for index in 0..escalated {
+        metas.push(AccountMeta::new_readonly(record_pda(index as u8), false));
+        metas.push(AccountMeta::new(dispute.pm_user(index + 2), false));
+    }
+    metas.push(AccountMeta::new(dispute.fund_pm_user, false));
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    try_send(&mut dispute.banks_client, &dispute.committee, ix).await.unwrap();
+}
+
+/// Returns the settled (locked, settlement) and the locked bond recorded
+/// for `party`
+async fn recorded(dispute: &mut Dispute, party: usize) -> ((u64, u64), u64) {
+    let address = dispute.pm_user(party);
+    let ledger = vault_ledger(&mut dispute.banks_client, address).await;
+    (ledger.settlement(), ledger.locked)
+}
+
+#[tokio::test]
+async fn test_second_challenger_posts_doubled_bond() {
+    let mut dispute = setup().await;
+
+    challenge(&mut dispute, 1, MarketResult::No).await.unwrap();
+    challenge(&mut dispute, 2, MarketResult::Invalid).await.unwrap();
+
+    assert_eq!(recorded(&mut dispute, 1).await.1, PROPOSER_BOND);
+    assert_eq!(recorded(&mut dispute, 2).await.1, 2 * PROPOSER_BOND);
+
+    let record: ChallengerRecord = load(&mut dispute.banks_client, record_pda(0)).await;
+    assert_eq!(record.challenger, dispute.parties[2].pubkey());
+    assert_eq!(record.result, MarketResult::Invalid);
+    assert_eq!(record.bond, 2 * PROPOSER_BOND);
+
+    let proposal: OracleProposal = load(&mut dispute.banks_client, proposal_pda(MARKET_ID)).await;
+    assert_eq!(proposal.status, ProposalStatus::Disputed);
+    assert_eq!(proposal.challenger, Some(dispute.parties[1].pubkey()));
+    assert_eq!(proposal.bonded_challengers(), 2);
+    assert_eq!(proposal.total_challenger_bonds(), 3 * PROPOSER_BOND);
+    assert_eq!(proposal.challenge_count, 2);
+}
+
+#[tokio::test]
+async fn test_matching_challengers_share_pool_by_bond() {
+    let mut dispute = setup().await;
+    challenge(&mut dispute, 1, MarketResult::No).await.unwrap();
+    challenge(&mut dispute, 2, MarketResult::No).await.unwrap();
+
+    resolve(&mut dispute, MarketResult::No, 1).await;
+
+    // Pool of 4 bonds split 1:2 between the challengers; the remainder of
+    // the division is forfeited to the Fund
+    let pool = 4 * PROPOSER_BOND;
+    assert_eq!(recorded(&mut dispute, 0).await.0, (PROPOSER_BOND, 0));
+    assert_eq!(recorded(&mut dispute, 1).await.0, (PROPOSER_BOND, pool / 3));
+    assert_eq!(recorded(&mut dispute, 2).await.0, (2 * PROPOSER_BOND, pool * 2 / 3));
+    let fund = vault_ledger(&mut dispute.banks_client, dispute.fund_pm_user).await;
+    assert_eq!(fund.settlement(), (0, pool - pool / 3 - pool * 2 / 3));
+
+    let market: Market = load(&mut dispute.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.final_result, Some(MarketResult::No));
+    let proposal: OracleProposal = load(&mut dispute.banks_client, proposal_pda(MARKET_ID)).await;
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+}
+
+#[tokio::test]
+async fn test_upheld_proposal_takes_every_challenger_bond() {
+    let mut dispute = setup().await;
+    challenge(&mut dispute, 1, MarketResult::No).await.unwrap();
+    challenge(&mut dispute, 2, MarketResult::Invalid).await.unwrap();
+
+    resolve(&mut dispute, MarketResult::Yes, 1).await;
+
+    assert_eq!(recorded(&mut dispute, 0).await.0, (PROPOSER_BOND, 4 * PROPOSER_BOND));
+    assert_eq!(recorded(&mut dispute, 1).await.0, (PROPOSER_BOND, 0));
+    assert_eq!(recorded(&mut dispute, 2).await.0, (2 * PROPOSER_BOND, 0));
+    assert_eq!(vault_ledger(&mut dispute.banks_client, dispute.fund_pm_user).await.calls, 0);
+}
+
+#[tokio::test]
+async fn test_challengers_capped_with_escalating_bonds() {
+    let mut dispute = setup().await;
+    for party in 1..PARTIES - 1 {
+        challenge(&mut dispute, party, MarketResult::No).await.unwrap();
+    }
+    assert_eq!(recorded(&mut dispute, PARTIES - 2).await.1, 8 * PROPOSER_BOND);
+
+    let result = challenge(&mut dispute, PARTIES - 1, MarketResult::No).await;
+    assert_custom_error(result, PredictionMarketError::TooManyChallengers);
+}
@@ -0,0 +1,201 @@
+//! Integration tests for Order::locked_margin_e6 across MatchMintMultiV2 and
+//! RelayerCancelMultiOutcomeOrderV2
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{MatchMintMultiV2Args, PredictionMarketInstruction, RelayerCancelMultiOutcomeOrderV2Args},
+    Market, MarketType, MultiOutcomePosition, Order, OrderSide,
+    OrderStatus, PredictionMarketConfig,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 51;
+const NUM_OUTCOMES: u8 = 3;
+const ORDER_AMOUNT: u64 = 10_000_000;
+const MATCH_AMOUNT: u64 = 4_000_000;
+
+fn test_market() -> Market {
+    Market {
+        market_type: MarketType::MultiOutcome,
+        num_outcomes: NUM_OUTCOMES,
+        next_order_id: NUM_OUTCOMES as u64 + 1,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// A Buy order for `outcome_index` holding the margin placement locked
+fn test_order(outcome_index: u8, owner: Pubkey, price: u64) -> Order {
+    Order {
+        outcome_index,
+        price,
+        amount: ORDER_AMOUNT,
+        locked_margin_e6: ORDER_AMOUNT * price / 1_000_000,
+        ..common::test_order(MARKET_ID, outcome_index as u64 + 1, owner, OrderSide::Buy)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    buyers: Vec<Pubkey>,
+    pm_users: Vec<Pubkey>,
+}
+
+async fn setup(order_prices: [u64; 3]) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = PredictionMarketConfig {
+        oracle_admin: relayer.pubkey(),
+        ..test_config(relayer.pubkey(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let mut buyers = Vec::new();
+    let mut pm_users = Vec::new();
+    for (i, price) in order_prices.into_iter().enumerate() {
+        let buyer = Pubkey::new_unique();
+        add_order(&mut program_test, &test_order(i as u8, buyer, price));
+        program_test.add_account(
+            multi_position_pda(MARKET_ID, &buyer),
+            program_account(&(), MultiOutcomePosition::SIZE),
+        );
+        let pm_user = add_pm_user(&mut program_test, &buyer, &vault_program);
+        buyers.push(buyer);
+        pm_users.push(pm_user);
+    }
+
+    Setup { context: program_test.start_with_context().await, relayer, vault_program, buyers, pm_users }
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, &setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+/// Mints MATCH_AMOUNT complete sets at the given per-outcome prices
+async fn match_mint_multi(setup: &mut Setup, match_prices: [u64; 3]) {
+    let data = PredictionMarketInstruction::MatchMintMultiV2(MatchMintMultiV2Args {
+        market_id: MARKET_ID,
+        num_outcomes: NUM_OUTCOMES,
+        amount: MATCH_AMOUNT,
+        orders: (0..NUM_OUTCOMES)
+            .map(|i| (i, i as u64 + 1, match_prices[i as usize]))
+            .collect(),
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (i, buyer) in setup.buyers.iter().enumerate() {
+        metas.push(AccountMeta::new(order_pda(MARKET_ID, i as u64 + 1), false));
+        metas.push(AccountMeta::new(multi_position_pda(MARKET_ID, buyer), false));
+        metas.push(AccountMeta::new(Pubkey::new_unique(), false));
+        metas.push(AccountMeta::new(setup.pm_users[i], false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await;
+}
+
+async fn cancel(setup: &mut Setup, outcome_index: u8) {
+    let i = outcome_index as usize;
+    let data = PredictionMarketInstruction::RelayerCancelMultiOutcomeOrderV2(RelayerCancelMultiOutcomeOrderV2Args {
+        user_wallet: setup.buyers[i],
+        market_id: MARKET_ID,
+        order_id: i as u64 + 1,
+        outcome_index,
+    })
+    .try_to_vec()
+    .unwrap();
+    let metas = vec![
+        AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, i as u64 + 1), false),
+        AccountMeta::new(multi_position_pda(MARKET_ID, &setup.buyers[i]), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.pm_users[i], false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await;
+}
+
+/// Returns (locked settled, released) recorded for the outcome's buyer
+async fn recorded(setup: &mut Setup, outcome_index: usize) -> (u64, u64) {
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.pm_users[outcome_index]).await;
+    (ledger.settled_locked, ledger.unlocked)
+}
+
+async fn load_order(setup: &mut Setup, outcome_index: usize) -> Order {
+    let address = order_pda(MARKET_ID, outcome_index as u64 + 1);
+    let account = setup.context.banks_client.get_account(address).await.unwrap().unwrap();
+    Order::deserialize(&mut &account.data[..]).unwrap()
+}
+
+#[tokio::test]
+async fn test_locked_margin_matches_remaining_at_order_price() {
+    let prices = [300_000, 300_000, 400_000];
+    let mut setup = setup(prices).await;
+
+    match_mint_multi(&mut setup, prices).await;
+
+    for (i, price) in prices.into_iter().enumerate() {
+        let order = load_order(&mut setup, i).await;
+        assert_eq!(order.status, OrderStatus::PartialFilled);
+        assert_eq!(order.locked_margin_e6, order.remaining_amount() * price / 1_000_000);
+        assert_eq!(recorded(&mut setup, i).await, (MATCH_AMOUNT * price / 1_000_000, 0));
+    }
+
+    // The cancel releases exactly what the order still tracks
+    cancel(&mut setup, 2).await;
+    assert_eq!(recorded(&mut setup, 2).await, (1_600_000, 2_400_000));
+    let order = load_order(&mut setup, 2).await;
+    assert_eq!(order.status, OrderStatus::Cancelled);
+    assert_eq!(order.locked_margin_e6, 0);
+}
+
+#[tokio::test]
+async fn test_fill_below_limit_releases_excess_before_cancel() {
+    // Outcome 2 bids 0.50 but the complete set mints it at 0.40
+    let mut setup = setup([300_000, 300_000, 500_000]).await;
+
+    match_mint_multi(&mut setup, [300_000, 300_000, 400_000]).await;
+
+    // 4 @ 0.40 settles 1.6; 0.4 of the 2.0 locked for them is released
+    // and 3.0 stays locked for the 6 still open at 0.50
+    assert_eq!(recorded(&mut setup, 2).await, (1_600_000, 400_000));
+    assert_eq!(load_order(&mut setup, 2).await.locked_margin_e6, 3_000_000);
+
+    cancel(&mut setup, 2).await;
+    let (settled, released) = recorded(&mut setup, 2).await;
+    assert_eq!(released, 3_400_000);
+    assert_eq!(settled + released, ORDER_AMOUNT * 500_000 / 1_000_000);
+}
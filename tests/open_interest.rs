@@ -0,0 +1,207 @@
+//! Integration tests for Market.open_interest across mint, burn and settlement
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{MatchBurnArgs, MatchMintArgs, PredictionMarketInstruction, RelayerClaimWinningsArgs},
+    Market, MarketResult, MarketStatus, Order, OrderSide, Outcome, Position,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 27;
+const MINTED: u64 = 10_000_000;
+const BURNED: u64 = 4_000_000;
+
+fn test_market() -> Market {
+    Market {
+        next_order_id: 5,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, outcome: Outcome, amount: u64) -> Order {
+    Order {
+        outcome,
+        outcome_index: if outcome == Outcome::Yes { 0 } else { 1 },
+        price: if outcome == Outcome::Yes { 600_000 } else { 400_000 },
+        amount,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    yes_owner: Pubkey,
+    no_owner: Pubkey,
+}
+
+/// Buy orders 1 (YES) / 2 (NO) and sell orders 3 (YES) / 4 (NO); one wallet
+/// trades the YES side and another the NO side
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let yes_owner = Pubkey::new_unique();
+    let no_owner = Pubkey::new_unique();
+    let orders = [
+        test_order(1, yes_owner, OrderSide::Buy, Outcome::Yes, MINTED),
+        test_order(2, no_owner, OrderSide::Buy, Outcome::No, MINTED),
+        test_order(3, yes_owner, OrderSide::Sell, Outcome::Yes, BURNED),
+        test_order(4, no_owner, OrderSide::Sell, Outcome::No, BURNED),
+    ];
+    for order in &orders {
+        program_test.add_account(order_pda(MARKET_ID, order.order_id), program_account(order, Order::SIZE));
+    }
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        yes_owner,
+        no_owner,
+    }
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+fn match_ix(setup: &Setup, data: Vec<u8>, orders: (u64, u64), with_system_program: bool) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, orders.0), false),
+        AccountMeta::new(order_pda(MARKET_ID, orders.1), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.yes_owner), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.no_owner), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(pm_user_pda(&setup.yes_owner, &setup.vault_program), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(pm_user_pda(&setup.no_owner, &setup.vault_program), false),
+        AccountMeta::new_readonly(vault_config_pda(&setup.vault_program), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+    ];
+    if with_system_program {
+        metas.push(AccountMeta::new_readonly(system_program::id(), false));
+    }
+    Instruction::new_with_bytes(prediction_market_program::id(), &data, metas)
+}
+
+fn claim_ix(setup: &Setup, user_wallet: Pubkey) -> Instruction {
+    let data = PredictionMarketInstruction::RelayerClaimWinningsV2(RelayerClaimWinningsArgs {
+        user_wallet,
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &user_wallet), false),
+            AccountMeta::new(pm_user_pda(&user_wallet, &setup.vault_program), false),
+            AccountMeta::new_readonly(vault_config_pda(&setup.vault_program), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+        ],
+    )
+}
+
+/// Overwrite a program account in place, keeping its size
+fn store<T: BorshSerialize>(setup: &mut Setup, address: Pubkey, value: &T, size: usize) {
+    setup.context.set_account(&address, &program_account(value, size).into());
+}
+
+#[tokio::test]
+async fn test_open_interest_follows_mint_burn_and_settlement() {
+    let mut setup = setup().await;
+
+    let data = PredictionMarketInstruction::MatchMintV2(MatchMintArgs {
+        market_id: MARKET_ID,
+        yes_order_id: 1,
+        no_order_id: 2,
+        amount: MINTED,
+        yes_price: 600_000,
+        no_price: 400_000,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = match_ix(&setup, data, (1, 2), true);
+    send(&mut setup, ix).await;
+    let market: Market = load(&mut setup.context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.open_interest, MINTED);
+
+    // Sell orders 3/4 lock shares on placement
+    for (owner, yes_locked, no_locked) in [(setup.yes_owner, BURNED, 0), (setup.no_owner, 0, BURNED)] {
+        let mut position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &owner)).await;
+        position.yes_locked = yes_locked;
+        position.no_locked = no_locked;
+        store(&mut setup, position_pda(MARKET_ID, &owner), &position, Position::SIZE);
+    }
+
+    let data = PredictionMarketInstruction::MatchBurnV2(MatchBurnArgs {
+        market_id: MARKET_ID,
+        yes_order_id: 3,
+        no_order_id: 4,
+        amount: BURNED,
+        yes_price: 600_000,
+        no_price: 400_000,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = match_ix(&setup, data, (3, 4), false);
+    send(&mut setup, ix).await;
+    let mut market: Market = load(&mut setup.context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.open_interest, MINTED - BURNED);
+
+    market.status = MarketStatus::Resolved;
+    market.final_result = Some(MarketResult::Yes);
+    store(&mut setup, market_pda(MARKET_ID), &market, Market::SIZE);
+
+    // The losing side retires nothing; the winning shares retire the rest
+    let ix = claim_ix(&setup, setup.no_owner);
+    send(&mut setup, ix).await;
+    let market: Market = load(&mut setup.context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.open_interest, MINTED - BURNED);
+
+    let ix = claim_ix(&setup, setup.yes_owner);
+    send(&mut setup, ix).await;
+    let market: Market = load(&mut setup.context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.open_interest, 0);
+    // Settlement leaves the minted counter alone
+    assert_eq!(market.total_minted, MINTED - BURNED);
+}
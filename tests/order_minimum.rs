@@ -0,0 +1,136 @@
+//! Integration tests for the minimum order size and notional
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerPlaceOrderV2Args},
+    Order, OrderSide, OrderType, Outcome, PredictionMarketError, POSITION_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 31;
+const MIN_ORDER_SIZE: u64 = 2_000_000;
+const MIN_NOTIONAL: u64 = 1_000_000;
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let mut config = test_config(relayer.pubkey(), vault_program);
+    config.min_order_size = MIN_ORDER_SIZE;
+    config.min_notional_e6 = MIN_NOTIONAL;
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(MARKET_ID));
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+    }
+}
+
+async fn try_place(
+    setup: &mut Setup,
+    price: u64,
+    amount: u64,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let user_wallet = Pubkey::new_unique();
+    let data = PredictionMarketInstruction::RelayerPlaceOrderV2(RelayerPlaceOrderV2Args {
+        user_wallet,
+        market_id: MARKET_ID,
+        side: OrderSide::Buy,
+        outcome: Outcome::Yes,
+        price,
+        amount,
+        order_type: OrderType::GTC,
+        expiration_time: None,
+        reduce_only: false,
+        referrer: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, 1), false),
+            AccountMeta::new(
+                pda(&[POSITION_SEED, &MARKET_ID.to_le_bytes(), user_wallet.as_ref()]),
+                false,
+            ),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+fn assert_below_minimum(result: Result<(), solana_program_test::BanksClientError>) {
+    assert_custom_error(result, PredictionMarketError::OrderBelowMinimum);
+}
+
+#[tokio::test]
+async fn test_order_at_minimum_accepted() {
+    let mut setup = setup().await;
+
+    // 2 shares at 0.50 = 1 USDC: exactly on both floors
+    try_place(&mut setup, 500_000, MIN_ORDER_SIZE).await.unwrap();
+
+    let account = setup.context.banks_client.get_account(order_pda(MARKET_ID, 1)).await.unwrap().unwrap();
+    let order = Order::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(order.amount, MIN_ORDER_SIZE);
+}
+
+#[tokio::test]
+async fn test_order_below_minimum_size_rejected() {
+    let mut setup = setup().await;
+
+    let result = try_place(&mut setup, 900_000, MIN_ORDER_SIZE - 1).await;
+    assert_below_minimum(result);
+}
+
+#[tokio::test]
+async fn test_order_below_minimum_notional_rejected() {
+    let mut setup = setup().await;
+
+    // 2 shares at 0.40 = 0.80 USDC
+    let result = try_place(&mut setup, 400_000, MIN_ORDER_SIZE).await;
+    assert_below_minimum(result);
+}
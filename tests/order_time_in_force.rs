@@ -0,0 +1,220 @@
+//! Integration tests for IOC and FOK order types and dust remainders in
+//! ExecuteTradeV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExecuteTradeArgs, PredictionMarketInstruction},
+    Market, Order, OrderSide, OrderType, Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 18;
+const BUY_ORDER_ID: u64 = 1;
+const SELL_ORDER_ID: u64 = 2;
+const BUY_AMOUNT: u64 = 10_000_000;
+const SELL_AMOUNT: u64 = 4_000_000;
+const BUY_PRICE: u64 = 600_000;
+const SELL_PRICE: u64 = 500_000;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: SELL_AMOUNT,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(
+    order_id: u64,
+    owner: Pubkey,
+    side: OrderSide,
+    price: u64,
+    amount: u64,
+    order_type: OrderType,
+) -> Order {
+    Order {
+        price,
+        amount,
+        order_type,
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+fn test_position(owner: Pubkey, yes_locked: u64) -> Position {
+    Position {
+        yes_amount: yes_locked,
+        yes_locked,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+    buyer_pm: Pubkey,
+    seller_pm: Pubkey,
+}
+
+/// Buy 10 YES at 0.60 against a resting sell of 4 YES at 0.50
+async fn setup(buy_type: OrderType) -> Setup {
+    setup_with_min_order_size(buy_type, 0).await
+}
+
+async fn setup_with_min_order_size(buy_type: OrderType, min_order_size: u64) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let mut config = test_config(relayer.pubkey(), vault_program);
+    config.min_order_size = min_order_size;
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let buyer = Pubkey::new_unique();
+    let seller = Pubkey::new_unique();
+    let buy_order = test_order(BUY_ORDER_ID, buyer, OrderSide::Buy, BUY_PRICE, BUY_AMOUNT, buy_type);
+    let sell_order =
+        test_order(SELL_ORDER_ID, seller, OrderSide::Sell, SELL_PRICE, SELL_AMOUNT, OrderType::GTC);
+    add_order(&mut program_test, &buy_order);
+    add_order(&mut program_test, &sell_order);
+    add_position(&mut program_test, &test_position(buyer, 0));
+    add_position(&mut program_test, &test_position(seller, SELL_AMOUNT));
+
+    let buyer_pm = add_pm_user(&mut program_test, &buyer, &vault_program);
+    let seller_pm = add_pm_user(&mut program_test, &seller, &vault_program);
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        buyer,
+        seller,
+        buyer_pm,
+        seller_pm,
+    }
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+async fn execute_trade(setup: &mut Setup) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::ExecuteTradeV2(ExecuteTradeArgs {
+        market_id: MARKET_ID,
+        taker_order_id: BUY_ORDER_ID,
+        maker_order_id: SELL_ORDER_ID,
+        amount: BUY_AMOUNT,
+        price: SELL_PRICE,
+    })
+    .try_to_vec()
+    .unwrap();
+    let metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, BUY_ORDER_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, SELL_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.seller), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.buyer_pm, false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.seller_pm, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(setup.seller, false),
+    ];
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await
+}
+
+/// Returns (locked, settlement, released) recorded by the Vault stub
+async fn recorded(context: &mut ProgramTestContext, pm_user: Pubkey) -> (u64, u64, u64) {
+    vault_ledger(&mut context.banks_client, pm_user).await.trade_legs()
+}
+
+#[tokio::test]
+async fn test_gtc_partial_fill_keeps_remainder_locked() {
+    let mut setup = setup(OrderType::GTC).await;
+
+    execute_trade(&mut setup).await.unwrap();
+
+    // 4 shares at 0.50 settle 2 USDC; only the 0.10/share price improvement
+    // on the filled part is released
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (2_000_000, 0, 400_000));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, 2_000_000, 0));
+}
+
+#[tokio::test]
+async fn test_ioc_fills_part_and_cancels_rest() {
+    let mut setup = setup(OrderType::IOC).await;
+
+    execute_trade(&mut setup).await.unwrap();
+
+    // The 6 unfilled shares are cancelled and their 3.6 USDC margin released
+    // together with the price improvement
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (2_000_000, 0, 400_000 + 3_600_000));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, 2_000_000, 0));
+}
+
+#[tokio::test]
+async fn test_fok_partial_fill_rejected() {
+    let mut setup = setup(OrderType::FOK).await;
+
+    let result = execute_trade(&mut setup).await;
+    assert_custom_error(result, PredictionMarketError::FillOrKillNotFilled);
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (0, 0, 0));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, 0, 0));
+}
+
+#[tokio::test]
+async fn test_dust_remainder_below_min_order_size_cancelled() {
+    // The fill would leave 6 shares on a GTC order with a 7 share minimum
+    let mut setup = setup_with_min_order_size(OrderType::GTC, 7_000_000).await;
+
+    execute_trade(&mut setup).await.unwrap();
+
+    // The dust is cancelled and its 3.6 USDC margin returned to the buyer
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (2_000_000, 0, 400_000 + 3_600_000));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, 2_000_000, 0));
+}
+
+#[tokio::test]
+async fn test_remainder_at_min_order_size_stays_open() {
+    let mut setup = setup_with_min_order_size(OrderType::GTC, 6_000_000).await;
+
+    execute_trade(&mut setup).await.unwrap();
+
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (2_000_000, 0, 400_000));
+}
@@ -0,0 +1,211 @@
+//! Integration tests for cancelling a partially filled Buy order
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExecuteTradeMultiArgs, PredictionMarketInstruction, RelayerCancelOrderV2Args},
+    Market, Order, OrderSide, OrderStatus,
+    Outcome, Position,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 50;
+const TAKER_ORDER_ID: u64 = 10;
+const TAKER_AMOUNT: u64 = 10_000_000;
+const TAKER_PRICE: u64 = 500_000;
+const MAKER_ORDER_ID: u64 = 1;
+const MAKER_PRICE: u64 = 400_000;
+const MAKER_AMOUNT: u64 = 4_000_000;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: MAKER_AMOUNT,
+        next_order_id: TAKER_ORDER_ID + 1,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, price: u64, amount: u64) -> Order {
+    Order {
+        price,
+        amount,
+        created_at: order_id as i64,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+}
+
+/// A 10 @ 0.50 Buy order that the Vault locked `locked_margin_e6` for,
+/// facing a single 4 @ 0.40 Sell order
+async fn setup(locked_margin_e6: u64) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let buyer = Pubkey::new_unique();
+    let mut taker = test_order(TAKER_ORDER_ID, buyer, OrderSide::Buy, TAKER_PRICE, TAKER_AMOUNT);
+    taker.locked_margin_e6 = locked_margin_e6;
+    add_order(&mut program_test, &taker);
+
+    let seller = Pubkey::new_unique();
+    program_test.add_account(
+        order_pda(MARKET_ID, MAKER_ORDER_ID),
+        program_account(
+            &test_order(MAKER_ORDER_ID, seller, OrderSide::Sell, MAKER_PRICE, MAKER_AMOUNT),
+            Order::SIZE,
+        ),
+    );
+    let mut position = Position::new(MARKET_ID, seller, 255, 0);
+    position.add_tokens(Outcome::Yes, MAKER_AMOUNT, 300_000, 0).unwrap();
+    position.lock_shares(Outcome::Yes, MAKER_AMOUNT).unwrap();
+    add_position(&mut program_test, &position);
+
+    for owner in [&seller, &buyer] {
+        add_pm_user(&mut program_test, owner, &vault_program);
+    }
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup { context: program_test.start_with_context().await, relayer, vault_program, buyer, seller }
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+/// Fills 4 of the Buy order's 10 against the 0.40 maker
+async fn partial_fill(setup: &mut Setup) {
+    let data = PredictionMarketInstruction::ExecuteTradeMultiV2(ExecuteTradeMultiArgs {
+        market_id: MARKET_ID,
+        taker_order_id: TAKER_ORDER_ID,
+        maker_order_ids: vec![MAKER_ORDER_ID],
+        amount: TAKER_AMOUNT,
+    })
+    .try_to_vec()
+    .unwrap();
+    let metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, TAKER_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(pm_user_pda(&setup.buyer, &setup.vault_program), false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(order_pda(MARKET_ID, MAKER_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.seller), false),
+        AccountMeta::new(pm_user_pda(&setup.seller, &setup.vault_program), false),
+        AccountMeta::new_readonly(setup.seller, false),
+    ];
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await;
+}
+
+async fn cancel(setup: &mut Setup) {
+    let data = PredictionMarketInstruction::RelayerCancelOrderV2(RelayerCancelOrderV2Args {
+        user_wallet: setup.buyer,
+        market_id: MARKET_ID,
+        order_id: TAKER_ORDER_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, TAKER_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(pm_user_pda(&setup.buyer, &setup.vault_program), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await;
+}
+
+/// Returns (locked settled, settlement, released) recorded for the buyer
+async fn recorded(setup: &mut Setup) -> (u64, u64, u64) {
+    let address = pm_user_pda(&setup.buyer, &setup.vault_program);
+    vault_ledger(&mut setup.context.banks_client, address).await.trade_legs()
+}
+
+async fn load_taker(setup: &mut Setup) -> Order {
+    load(&mut setup.context.banks_client, order_pda(MARKET_ID, TAKER_ORDER_ID)).await
+}
+
+#[tokio::test]
+async fn test_cancel_after_price_improved_fill_releases_the_rest() {
+    let mut setup = setup(5_000_000).await;
+
+    // 4 @ 0.40 settles 1.6 USDC; 0.4 of price improvement is released
+    // and 3.0 stays locked for the 6 still open at 0.50
+    partial_fill(&mut setup).await;
+    assert_eq!(recorded(&mut setup).await, (1_600_000, 0, 400_000));
+    assert_eq!(load_taker(&mut setup).await.locked_margin_e6, 3_000_000);
+
+    cancel(&mut setup).await;
+    let (settled, _, released) = recorded(&mut setup).await;
+    assert_eq!(released, 3_400_000);
+    assert_eq!(settled + released, 5_000_000);
+
+    let taker = load_taker(&mut setup).await;
+    assert_eq!(taker.status, OrderStatus::Cancelled);
+    assert_eq!(taker.locked_margin_e6, 0);
+}
+
+#[tokio::test]
+async fn test_cancel_never_releases_more_than_was_locked() {
+    // The Vault only locked 4.0 of the 5.0 requested
+    let mut setup = setup(4_000_000).await;
+
+    // Nothing is released at fill time; the 2.4 left is all the
+    // remaining 6 can claim
+    partial_fill(&mut setup).await;
+    assert_eq!(recorded(&mut setup).await, (1_600_000, 0, 0));
+    assert_eq!(load_taker(&mut setup).await.locked_margin_e6, 2_400_000);
+
+    // Recomputing from the remaining amount would release 3.0 here
+    cancel(&mut setup).await;
+    let (settled, _, released) = recorded(&mut setup).await;
+    assert_eq!(released, 2_400_000);
+    assert_eq!(settled + released, 4_000_000);
+    assert_eq!(load_taker(&mut setup).await.locked_margin_e6, 0);
+}
@@ -0,0 +1,79 @@
+//! Integration tests for PauseMarket / ResumeMarket pause reasons
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PauseMarketArgs, PredictionMarketInstruction, ResumeMarketArgs},
+    Market, MarketStatus,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 41;
+const INCIDENT: u8 = 2;
+
+async fn setup() -> (ProgramTestContext, Keypair) {
+    let mut program_test = program_test();
+
+    let admin = Keypair::new();
+    let mut config = test_config(admin.pubkey(), Pubkey::new_unique());
+    config.active_markets = 1;
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(MARKET_ID));
+
+    (program_test.start_with_context().await, admin)
+}
+
+async fn send(context: &mut ProgramTestContext, admin: &Keypair, instruction: PredictionMarketInstruction) {
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &instruction.try_to_vec().unwrap(),
+        vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, admin],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pause_records_reason() {
+    let (mut context, admin) = setup().await;
+
+    let pause = PauseMarketArgs { market_id: MARKET_ID, reason: INCIDENT };
+    send(&mut context, &admin, PredictionMarketInstruction::PauseMarket(pause)).await;
+
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::Paused);
+    assert_eq!(market.pause_reason, INCIDENT);
+    assert_eq!(market.summary().pause_reason, INCIDENT);
+}
+
+#[tokio::test]
+async fn test_resume_clears_reason() {
+    let (mut context, admin) = setup().await;
+
+    let pause = PauseMarketArgs { market_id: MARKET_ID, reason: INCIDENT };
+    send(&mut context, &admin, PredictionMarketInstruction::PauseMarket(pause)).await;
+    let resume = ResumeMarketArgs { market_id: MARKET_ID };
+    send(&mut context, &admin, PredictionMarketInstruction::ResumeMarket(resume)).await;
+
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::Active);
+    assert_eq!(market.pause_reason, 0);
+}
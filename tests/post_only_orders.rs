@@ -0,0 +1,146 @@
+//! Integration tests for post-only orders placed through RelayerPlaceOrderV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerPlaceOrderV2Args},
+    Market, Order, OrderSide, OrderStatus, OrderType,
+    Outcome, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 54;
+const BEST_ASK_ID: u64 = 1;
+const BEST_ASK_PRICE: u64 = 450_000;
+
+fn test_market() -> Market {
+    Market {
+        next_order_id: BEST_ASK_ID + 1,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn best_ask() -> Order {
+    Order {
+        price: BEST_ASK_PRICE,
+        amount: 4_000_000,
+        ..test_order(MARKET_ID, BEST_ASK_ID, Pubkey::new_unique(), OrderSide::Sell)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+    add_order(&mut program_test, &best_ask());
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        user: Pubkey::new_unique(),
+    }
+}
+
+/// Places a post-only YES Buy checked against `best_opposing`
+async fn try_place(
+    setup: &mut Setup,
+    price: u64,
+    best_opposing: Pubkey,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::RelayerPlaceOrderV2(RelayerPlaceOrderV2Args {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+        side: OrderSide::Buy,
+        outcome: Outcome::Yes,
+        price,
+        amount: 2_000_000,
+        order_type: OrderType::PostOnly,
+        expiration_time: None,
+        reduce_only: false,
+        referrer: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, BEST_ASK_ID + 1), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(best_opposing, false),
+        ],
+    );
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_post_only_buy_below_best_ask_accepted() {
+    let mut setup = setup().await;
+
+    try_place(&mut setup, 400_000, order_pda(MARKET_ID, BEST_ASK_ID)).await.unwrap();
+
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, BEST_ASK_ID + 1)).await;
+    assert_eq!(order.order_type, OrderType::PostOnly);
+    assert_eq!(order.status, OrderStatus::Open);
+}
+
+#[tokio::test]
+async fn test_post_only_buy_crossing_best_ask_rejected() {
+    let mut setup = setup().await;
+
+    let result = try_place(&mut setup, 500_000, order_pda(MARKET_ID, BEST_ASK_ID)).await;
+    assert_custom_error(result, PredictionMarketError::PostOnlyWouldCross);
+    let order = setup.context.banks_client.get_account(order_pda(MARKET_ID, BEST_ASK_ID + 1)).await.unwrap();
+    assert!(order.is_none());
+}
+
+#[tokio::test]
+async fn test_post_only_buy_against_empty_book_accepted() {
+    let mut setup = setup().await;
+
+    try_place(&mut setup, 900_000, Pubkey::new_unique()).await.unwrap();
+}
@@ -0,0 +1,273 @@
+//! Integration tests for ProposeResult
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, ProposeMultiOutcomeResultArgs, ProposeResultArgs},
+    Market, MarketResult, MarketStatus, MarketType, OracleProposal,
+    PredictionMarketConfig, PredictionMarketError, ProposalStatus,
+    DEFAULT_CHALLENGE_WINDOW_SECS, ORACLE_PROPOSAL_DISCRIMINATOR, VAULT_PM_USER_SEED,
+};
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 7;
+const CHALLENGE_WINDOW_SECS: i64 = 7200;
+const PROPOSER_BOND: u64 = 5_000_000;
+
+fn test_market(resolution_time: i64) -> Market {
+    Market {
+        resolution_time,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_multi_outcome_market(num_outcomes: u8) -> Market {
+    Market {
+        market_type: MarketType::MultiOutcome,
+        num_outcomes,
+        ..test_market(0)
+    }
+}
+
+fn propose_ix(proposer: &Pubkey, vault_program: &Pubkey, result: MarketResult) -> Instruction {
+    let instruction = PredictionMarketInstruction::ProposeResult(ProposeResultArgs {
+        market_id: MARKET_ID,
+        result,
+    });
+    proposal_ix(proposer, vault_program, instruction)
+}
+
+fn propose_multi_ix(proposer: &Pubkey, vault_program: &Pubkey, winning_outcome_index: u8) -> Instruction {
+    let instruction =
+        PredictionMarketInstruction::ProposeMultiOutcomeResult(ProposeMultiOutcomeResultArgs {
+            market_id: MARKET_ID,
+            winning_outcome_index,
+        });
+    proposal_ix(proposer, vault_program, instruction)
+}
+
+fn proposal_ix(
+    proposer: &Pubkey,
+    vault_program: &Pubkey,
+    instruction: PredictionMarketInstruction,
+) -> Instruction {
+    let data = instruction.try_to_vec().unwrap();
+    let pm_user =
+        Pubkey::find_program_address(&[VAULT_PM_USER_SEED, proposer.as_ref()], vault_program).0;
+    Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(*proposer, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(proposal_pda(MARKET_ID), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(pm_user, false),
+        ],
+    )
+}
+
+/// Returns (banks_client, oracle_admin, vault_program)
+async fn setup(resolution_time: i64, existing_proposal: bool) -> (BanksClient, Keypair, Pubkey) {
+    setup_with(test_market(resolution_time), CHALLENGE_WINDOW_SECS, existing_proposal).await
+}
+
+async fn setup_with(
+    market: Market,
+    challenge_window_secs: i64,
+    existing_proposal: bool,
+) -> (BanksClient, Keypair, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let oracle_admin = Keypair::new();
+    let config = PredictionMarketConfig {
+        oracle_admin: oracle_admin.pubkey(),
+        challenge_window_secs,
+        proposer_bond_e6: PROPOSER_BOND,
+        ..test_config(Pubkey::new_unique(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &market);
+    if existing_proposal {
+        let proposal = OracleProposal {
+            proposed_result: MarketResult::No,
+            challenge_deadline: CHALLENGE_WINDOW_SECS,
+            bond_amount: PROPOSER_BOND,
+            original_challenge_deadline: CHALLENGE_WINDOW_SECS,
+            ..test_proposal(MARKET_ID, oracle_admin.pubkey())
+        };
+        add_proposal(&mut program_test, &proposal);
+    }
+    program_test.add_account(
+        oracle_admin.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let (banks_client, _payer, _) = program_test.start().await;
+    (banks_client, oracle_admin, vault_program)
+}
+
+async fn try_send(
+    banks_client: &mut BanksClient,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_propose_result_creates_proposal() {
+    let (mut banks_client, oracle_admin, vault_program) = setup(0, false).await;
+
+    try_send(
+        &mut banks_client,
+        &oracle_admin,
+        propose_ix(&oracle_admin.pubkey(), &vault_program, MarketResult::Yes),
+    )
+    .await
+    .unwrap();
+
+    let account = banks_client.get_account(proposal_pda(MARKET_ID)).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), OracleProposal::SIZE);
+    let proposal = OracleProposal::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(proposal.discriminator, ORACLE_PROPOSAL_DISCRIMINATOR);
+    assert_eq!(proposal.market_id, MARKET_ID);
+    assert_eq!(proposal.proposer, oracle_admin.pubkey());
+    assert_eq!(proposal.proposed_result, MarketResult::Yes);
+    assert_eq!(proposal.status, ProposalStatus::Pending);
+    assert_eq!(proposal.bond_amount, PROPOSER_BOND);
+    assert_eq!(proposal.challenge_deadline, proposal.proposed_at + CHALLENGE_WINDOW_SECS);
+    assert!(proposal.can_challenge(proposal.proposed_at));
+
+    // ResultProposed doubles as the requested pending-resolution status
+    let market = load::<Market>(&mut banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::ResultProposed);
+    assert!(market.status.can_challenge());
+    assert!(!market.status.is_tradeable() && !market.status.is_terminal());
+}
+
+#[tokio::test]
+async fn test_market_challenge_window_overrides_global() {
+    let seven_days = 7 * 86_400;
+    let mut market = test_market(0);
+    market.challenge_window_secs = seven_days;
+    let (mut banks_client, oracle_admin, vault_program) =
+        setup_with(market, DEFAULT_CHALLENGE_WINDOW_SECS, false).await;
+
+    try_send(
+        &mut banks_client,
+        &oracle_admin,
+        propose_ix(&oracle_admin.pubkey(), &vault_program, MarketResult::Yes),
+    )
+    .await
+    .unwrap();
+
+    let account = banks_client.get_account(proposal_pda(MARKET_ID)).await.unwrap().unwrap();
+    let proposal = OracleProposal::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(proposal.challenge_deadline, proposal.proposed_at + seven_days);
+    assert_eq!(proposal.original_challenge_deadline, proposal.challenge_deadline);
+}
+
+#[tokio::test]
+async fn test_propose_result_too_early() {
+    let (mut banks_client, oracle_admin, vault_program) = setup(i64::MAX, false).await;
+
+    let result = try_send(
+        &mut banks_client,
+        &oracle_admin,
+        propose_ix(&oracle_admin.pubkey(), &vault_program, MarketResult::Yes),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::ResolutionTimeNotReached);
+
+    assert!(banks_client.get_account(proposal_pda(MARKET_ID)).await.unwrap().is_none());
+    assert_eq!(load::<Market>(&mut banks_client, market_pda(MARKET_ID)).await.status, MarketStatus::Active);
+}
+
+#[tokio::test]
+async fn test_propose_result_rejects_second_proposal() {
+    let (mut banks_client, oracle_admin, vault_program) = setup(0, true).await;
+
+    let result = try_send(
+        &mut banks_client,
+        &oracle_admin,
+        propose_ix(&oracle_admin.pubkey(), &vault_program, MarketResult::Yes),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::ProposalAlreadyExists);
+}
+
+#[tokio::test]
+async fn test_propose_multi_outcome_result() {
+    let (mut banks_client, oracle_admin, vault_program) =
+        setup_with(test_multi_outcome_market(4), CHALLENGE_WINDOW_SECS, false).await;
+
+    try_send(
+        &mut banks_client,
+        &oracle_admin,
+        propose_multi_ix(&oracle_admin.pubkey(), &vault_program, 2),
+    )
+    .await
+    .unwrap();
+
+    let account = banks_client.get_account(proposal_pda(MARKET_ID)).await.unwrap().unwrap();
+    let proposal = OracleProposal::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(proposal.proposed_outcome_index, Some(2));
+    assert_eq!(proposal.proposed_result, MarketResult::Yes);
+    assert_eq!(proposal.bond_amount, PROPOSER_BOND);
+    assert_eq!(proposal.status, ProposalStatus::Pending);
+
+    let market = load::<Market>(&mut banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::ResultProposed);
+    assert_eq!(market.winning_outcome_index, None);
+}
+
+#[tokio::test]
+async fn test_propose_multi_outcome_rejects_out_of_range_index() {
+    let (mut banks_client, oracle_admin, vault_program) =
+        setup_with(test_multi_outcome_market(4), CHALLENGE_WINDOW_SECS, false).await;
+
+    let result = try_send(
+        &mut banks_client,
+        &oracle_admin,
+        propose_multi_ix(&oracle_admin.pubkey(), &vault_program, 4),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::InvalidOutcomeIndex);
+    assert!(banks_client.get_account(proposal_pda(MARKET_ID)).await.unwrap().is_none());
+
+    // Binary markets go through ProposeResult
+    let (mut banks_client, oracle_admin, vault_program) = setup(0, false).await;
+    let result = try_send(
+        &mut banks_client,
+        &oracle_admin,
+        propose_multi_ix(&oracle_admin.pubkey(), &vault_program, 0),
+    )
+    .await;
+    assert_custom_error(result, PredictionMarketError::InvalidMarketType);
+}
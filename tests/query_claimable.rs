@@ -0,0 +1,149 @@
+//! Integration tests for QueryClaimable
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, QueryClaimableArgs},
+    ClaimableSummary, Market, MarketResult, MarketStatus, Outcome,
+    Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+const MARKET_ID: u64 = 60;
+/// The user bought 10 YES at 0.60 and 4 NO at 0.30
+const YES_AMOUNT: u64 = 10_000_000;
+const NO_AMOUNT: u64 = 4_000_000;
+const COST: u64 = 6_000_000 + 1_200_000;
+
+fn test_market(status: MarketStatus, final_result: Option<MarketResult>) -> Market {
+    Market {
+        status,
+        resolution_time: 0,
+        final_result,
+        total_minted: YES_AMOUNT,
+        open_interest: YES_AMOUNT,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_position(user: Pubkey, settled: bool) -> Position {
+    let mut position = Position::new(MARKET_ID, user, 255, 0);
+    position.add_tokens(Outcome::Yes, YES_AMOUNT, 600_000, 0).unwrap();
+    position.add_tokens(Outcome::No, NO_AMOUNT, 300_000, 0).unwrap();
+    position.settled = settled;
+    position
+}
+
+/// Returns the context and the user holding the test position
+async fn setup(market: Market, settled: bool) -> (ProgramTestContext, Pubkey) {
+    let mut program_test = program_test();
+    add_market(&mut program_test, &market);
+    let user = Pubkey::new_unique();
+    add_position(&mut program_test, &test_position(user, settled));
+    (program_test.start_with_context().await, user)
+}
+
+/// Simulate QueryClaimable for `user` and decode its return data
+async fn query(context: &mut ProgramTestContext, user: Pubkey) -> Result<ClaimableSummary, TransactionError> {
+    let data = PredictionMarketInstruction::QueryClaimable(QueryClaimableArgs {
+        market_id: MARKET_ID,
+        user_wallet: user,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(market_pda(MARKET_ID), false),
+            AccountMeta::new_readonly(position_pda(MARKET_ID, &user), false),
+        ],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    simulation.result.unwrap()?;
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, prediction_market_program::id());
+    Ok(ClaimableSummary::try_from_slice(&return_data.data).unwrap())
+}
+
+#[tokio::test]
+async fn test_query_claimable_winning_position() {
+    let mut market = test_market(MarketStatus::Resolved, Some(MarketResult::Yes));
+    market.creator_fee_bps = 100;
+    let (mut context, user) = setup(market, false).await;
+
+    // Winning YES shares pay 1:1, less the 1% creator fee
+    let summary = query(&mut context, user).await.unwrap();
+    assert_eq!(
+        summary,
+        ClaimableSummary {
+            settled: false,
+            claimable_e6: YES_AMOUNT - YES_AMOUNT / 100,
+            creator_fee_e6: YES_AMOUNT / 100,
+            locked_e6: COST,
+        }
+    );
+
+    // Nothing was modified
+    let account = context.banks_client.get_account(position_pda(MARKET_ID, &user)).await.unwrap().unwrap();
+    assert!(!Position::deserialize(&mut &account.data[..]).unwrap().settled);
+}
+
+#[tokio::test]
+async fn test_query_claimable_losing_position() {
+    let (mut context, user) = setup(test_market(MarketStatus::Resolved, Some(MarketResult::No)), false).await;
+
+    // Only the NO shares pay out; the YES cost is lost
+    let summary = query(&mut context, user).await.unwrap();
+    assert_eq!(summary.claimable_e6, NO_AMOUNT);
+    assert_eq!(summary.locked_e6, COST);
+    assert!(!summary.settled);
+}
+
+#[tokio::test]
+async fn test_query_claimable_invalid_market_refunds_cost() {
+    let (mut context, user) =
+        setup(test_market(MarketStatus::Resolved, Some(MarketResult::Invalid)), false).await;
+
+    let summary = query(&mut context, user).await.unwrap();
+    assert_eq!(summary.claimable_e6, COST);
+    assert_eq!(summary.creator_fee_e6, 0);
+}
+
+#[tokio::test]
+async fn test_query_claimable_settled_and_missing_positions() {
+    let (mut context, user) = setup(test_market(MarketStatus::Resolved, Some(MarketResult::Yes)), true).await;
+
+    let settled = query(&mut context, user).await.unwrap();
+    assert_eq!(settled, ClaimableSummary { settled: true, ..ClaimableSummary::default() });
+
+    let missing = query(&mut context, Pubkey::new_unique()).await.unwrap();
+    assert_eq!(missing, ClaimableSummary::default());
+}
+
+#[tokio::test]
+async fn test_query_claimable_requires_resolution() {
+    let (mut context, user) = setup(test_market(MarketStatus::Active, None), false).await;
+
+    assert_eq!(
+        query(&mut context, user).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(PredictionMarketError::MarketNotResolved as u32)
+        )
+    );
+}
@@ -0,0 +1,111 @@
+//! Integration tests for QueryMarket
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, QueryMarketArgs},
+    Market, MarketResult, MarketStatus, MarketSummary,
+    PredictionMarketError, MARKET_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+const MARKET_ID: u64 = 32;
+
+fn market_pda(market_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MARKET_SEED, &market_id.to_le_bytes()],
+        &prediction_market_program::id(),
+    )
+    .0
+}
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::Resolved,
+        resolution_time: 1_700_000_000,
+        final_result: Some(MarketResult::No),
+        total_minted: 12_000_000,
+        total_volume_e6: 34_500_000,
+        open_interest: 7_000_000,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+async fn setup() -> ProgramTestContext {
+    let mut program_test = program_test();
+    let mut data = test_market().try_to_vec().unwrap();
+    data.resize(Market::SIZE, 0);
+    program_test.add_account(
+        market_pda(MARKET_ID),
+        Account {
+            lamports: 10_000_000,
+            data,
+            owner: prediction_market_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    program_test.start_with_context().await
+}
+
+fn query_tx(context: &ProgramTestContext, market_id: u64, market: Pubkey) -> Transaction {
+    let data = PredictionMarketInstruction::QueryMarket(QueryMarketArgs { market_id })
+        .try_to_vec()
+        .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![AccountMeta::new_readonly(market, false)],
+    );
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn test_query_market_returns_summary() {
+    let mut context = setup().await;
+
+    let tx = query_tx(&context, MARKET_ID, market_pda(MARKET_ID));
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    simulation.result.unwrap().unwrap();
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, prediction_market_program::id());
+
+    let summary = MarketSummary::try_from_slice(&return_data.data).unwrap();
+    assert_eq!(summary, test_market().summary());
+    assert_eq!(summary.status, MarketStatus::Resolved);
+    assert_eq!(summary.total_minted, 12_000_000);
+    assert_eq!(summary.open_interest, 7_000_000);
+    assert_eq!(summary.total_volume_e6, 34_500_000);
+    assert_eq!(summary.resolution_time, 1_700_000_000);
+    assert_eq!(summary.final_result, Some(MarketResult::No));
+}
+
+#[tokio::test]
+async fn test_query_market_rejects_wrong_pda() {
+    let mut context = setup().await;
+
+    // The account is the PDA of MARKET_ID, not of the requested market
+    let tx = query_tx(&context, MARKET_ID + 1, market_pda(MARKET_ID));
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    assert_eq!(
+        simulation.result.unwrap().unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(PredictionMarketError::InvalidPDA as u32)
+        )
+    );
+}
@@ -0,0 +1,116 @@
+//! Integration tests for RelayerRedeemCompleteSetV2 PnL bookkeeping
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerRedeemCompleteSetArgs},
+    Market, Outcome, Position,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 38;
+const SETS: u64 = 10_000_000;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: SETS,
+        open_interest: SETS,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Returns (context, relayer, vault_program) with `user` holding SETS YES
+/// and NO shares bought at `yes_price` / `no_price`
+async fn setup(user: Pubkey, yes_price: u64, no_price: u64) -> (ProgramTestContext, Keypair, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let mut position = Position::new(MARKET_ID, user, 255, 0);
+    position.add_tokens(Outcome::Yes, SETS, yes_price, 0).unwrap();
+    position.add_tokens(Outcome::No, SETS, no_price, 0).unwrap();
+    add_position(&mut program_test, &position);
+
+    (program_test.start_with_context().await, relayer, vault_program)
+}
+
+async fn redeem(
+    context: &mut ProgramTestContext,
+    relayer: &Keypair,
+    vault_program: &Pubkey,
+    user_wallet: Pubkey,
+    amount: u64,
+) {
+    let data = PredictionMarketInstruction::RelayerRedeemCompleteSetV2(RelayerRedeemCompleteSetArgs {
+        user_wallet,
+        market_id: MARKET_ID,
+        amount,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &user_wallet), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, relayer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+/// Returns (position realized PnL, market realized PnL rollup)
+async fn realized_pnl(context: &mut ProgramTestContext, user: &Pubkey) -> (i64, i64) {
+    let account = context.banks_client.get_account(position_pda(MARKET_ID, user)).await.unwrap().unwrap();
+    let position = Position::deserialize(&mut &account.data[..]).unwrap();
+    let account = context.banks_client.get_account(market_pda(MARKET_ID)).await.unwrap().unwrap();
+    let market = Market::deserialize(&mut &account.data[..]).unwrap();
+    (position.realized_pnl, market.total_realized_pnl_e6)
+}
+
+#[tokio::test]
+async fn test_redeem_par_minted_sets_realizes_no_pnl() {
+    let user = Pubkey::new_unique();
+    let (mut context, relayer, vault_program) = setup(user, 500_000, 500_000).await;
+
+    redeem(&mut context, &relayer, &vault_program, user, SETS).await;
+
+    assert_eq!(realized_pnl(&mut context, &user).await, (0, 0));
+}
+
+#[tokio::test]
+async fn test_redeem_market_acquired_sets_realizes_spread() {
+    let user = Pubkey::new_unique();
+    // Sets cost $0.55 + $0.35 = $0.90 and redeem for $1.00
+    let (mut context, relayer, vault_program) = setup(user, 550_000, 350_000).await;
+
+    redeem(&mut context, &relayer, &vault_program, user, SETS / 2).await;
+
+    let gain = (SETS / 2 / 10) as i64;
+    assert_eq!(realized_pnl(&mut context, &user).await, (gain, gain));
+}
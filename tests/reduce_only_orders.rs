@@ -0,0 +1,148 @@
+//! Integration tests for reduce-only orders placed through RelayerPlaceOrderV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerPlaceOrderV2Args}, Order, OrderSide, OrderType, Outcome,
+    Position, PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 52;
+const YES_HOLDINGS: u64 = 5_000_000;
+const NO_HOLDINGS: u64 = 2_000_000;
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(MARKET_ID));
+
+    let user = Pubkey::new_unique();
+    let mut position = Position::new(MARKET_ID, user, 255, 0);
+    position.add_tokens(Outcome::Yes, YES_HOLDINGS, 400_000, 0).unwrap();
+    position.add_tokens(Outcome::No, NO_HOLDINGS, 600_000, 0).unwrap();
+    add_position(&mut program_test, &position);
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        user,
+    }
+}
+
+/// Places a reduce-only YES order for the user holding the test position
+async fn try_place(
+    setup: &mut Setup,
+    side: OrderSide,
+    amount: u64,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::RelayerPlaceOrderV2(RelayerPlaceOrderV2Args {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+        side,
+        outcome: Outcome::Yes,
+        price: 500_000,
+        amount,
+        order_type: OrderType::GTC,
+        expiration_time: None,
+        reduce_only: true,
+        referrer: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, 1), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+fn assert_reduce_only_violation(result: Result<(), solana_program_test::BanksClientError>) {
+    assert_custom_error(result, PredictionMarketError::ReduceOnlyViolation);
+}
+
+#[tokio::test]
+async fn test_reduce_only_sell_larger_than_holdings_rejected() {
+    let mut setup = setup().await;
+    let user = setup.user;
+
+    assert_reduce_only_violation(try_place(&mut setup, OrderSide::Sell, YES_HOLDINGS + 1).await);
+
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &user)).await;
+    assert_eq!(position.yes_locked, 0);
+}
+
+#[tokio::test]
+async fn test_reduce_only_sell_of_whole_holding_accepted() {
+    let mut setup = setup().await;
+    let user = setup.user;
+
+    try_place(&mut setup, OrderSide::Sell, YES_HOLDINGS).await.unwrap();
+
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, 1)).await;
+    assert!(order.reduce_only);
+    assert_eq!(order.amount, YES_HOLDINGS);
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &user)).await;
+    assert_eq!(position.yes_locked, YES_HOLDINGS);
+}
+
+#[tokio::test]
+async fn test_reduce_only_buy_limited_by_opposite_holdings() {
+    let mut setup = setup().await;
+
+    // Buying YES only unwinds the NO side
+    assert_reduce_only_violation(try_place(&mut setup, OrderSide::Buy, NO_HOLDINGS + 1).await);
+
+    try_place(&mut setup, OrderSide::Buy, NO_HOLDINGS).await.unwrap();
+    let order: Order = load(&mut setup.context.banks_client, order_pda(MARKET_ID, 1)).await;
+    assert!(order.reduce_only);
+    assert_eq!(order.amount, NO_HOLDINGS);
+}
@@ -0,0 +1,376 @@
+//! Integration tests for referral fee shares
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        ExecuteTradeArgs, ExecuteTradeMultiArgs, PredictionMarketInstruction, RelayerPlaceOrderV2Args,
+        SetReferralShareArgs,
+        SetTradeFeeArgs,
+    },
+    Market, Order, OrderSide,
+    OrderType, Outcome, Position, PredictionMarketConfig, PredictionMarketError,
+    UserMeta, USER_META_SEED,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 56;
+const BUY_ORDER_ID: u64 = 1;
+const SELL_ORDER_ID: u64 = 2;
+/// Each trade fills 10 shares at 0.50: cost 5 USDC
+const FILL: u64 = 10_000_000;
+const PRICE: u64 = 500_000;
+const COST: u64 = 5_000_000;
+/// 1% trading fee per side
+const FEE: u64 = 50_000;
+
+fn user_meta_pda(wallet: &Pubkey) -> Pubkey {
+    pda(&[USER_META_SEED, wallet.as_ref()])
+}
+
+fn test_market() -> Market {
+    Market {
+        total_minted: 2 * FILL,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Orders sized for two fills; the buy is priced above PRICE to leave
+/// margin for the fee
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide, price: u64) -> Order {
+    Order {
+        price,
+        amount: 2 * FILL,
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+fn test_position(owner: Pubkey, yes_locked: u64) -> Position {
+    Position {
+        yes_amount: yes_locked,
+        yes_locked,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    admin: Keypair,
+    vault_program: Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+    referrer: Pubkey,
+    buyer_pm: Pubkey,
+    seller_pm: Pubkey,
+    referrer_pm: Pubkey,
+    fund_pm: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let fund_program = Pubkey::new_unique();
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let admin = Keypair::new();
+    let config = PredictionMarketConfig {
+        fund_program,
+        ..test_config(admin.pubkey(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let buyer = Pubkey::new_unique();
+    let seller = Pubkey::new_unique();
+    add_order(&mut program_test, &test_order(BUY_ORDER_ID, buyer, OrderSide::Buy, 600_000));
+    add_order(&mut program_test, &test_order(SELL_ORDER_ID, seller, OrderSide::Sell, PRICE));
+    add_position(&mut program_test, &test_position(buyer, 0));
+    add_position(&mut program_test, &test_position(seller, 2 * FILL));
+
+    // The buyer was referred; the seller was not
+    let referrer = Pubkey::new_unique();
+    program_test.add_account(
+        user_meta_pda(&buyer),
+        program_account(&UserMeta::new(buyer, Some(referrer), 255, 0), UserMeta::SIZE),
+    );
+
+    let buyer_pm = add_pm_user(&mut program_test, &buyer, &vault_program);
+    let seller_pm = add_pm_user(&mut program_test, &seller, &vault_program);
+    let referrer_pm = add_pm_user(&mut program_test, &referrer, &vault_program);
+    let fund_pm = add_pm_user(&mut program_test, &fund_program, &vault_program);
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let mut setup = Setup {
+        context: program_test.start_with_context().await,
+        admin,
+        vault_program,
+        buyer,
+        seller,
+        referrer,
+        buyer_pm,
+        seller_pm,
+        referrer_pm,
+        fund_pm,
+    };
+    let set_fee = PredictionMarketInstruction::SetTradeFee(SetTradeFeeArgs { trade_fee_bps: 100 });
+    send_admin(&mut setup, set_fee, false).await.unwrap();
+    setup
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), BanksClientError> {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.admin.pubkey()),
+        &[&setup.admin],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+/// Send a config setter; `grows` setters also take the System Program
+async fn send_admin(
+    setup: &mut Setup,
+    instruction: PredictionMarketInstruction,
+    grows: bool,
+) -> Result<(), BanksClientError> {
+    let mut metas = vec![
+        AccountMeta::new(setup.admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+    ];
+    if grows {
+        metas.push(AccountMeta::new_readonly(system_program::id(), false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &instruction.try_to_vec().unwrap(), metas);
+    send(setup, ix).await
+}
+
+async fn set_referral_share(setup: &mut Setup, referral_share_bps: u16) -> Result<(), BanksClientError> {
+    let instruction = PredictionMarketInstruction::SetReferralShare(SetReferralShareArgs { referral_share_bps });
+    send_admin(setup, instruction, true).await
+}
+
+/// Fill the orders; the referral accounts are the traders' UserMeta PDAs
+/// followed by the referrer's PMUserAccount
+async fn execute_trade(setup: &mut Setup, with_referral_accounts: bool) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ExecuteTradeV2(ExecuteTradeArgs {
+        market_id: MARKET_ID,
+        taker_order_id: BUY_ORDER_ID,
+        maker_order_id: SELL_ORDER_ID,
+        amount: FILL,
+        price: PRICE,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(setup.admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, BUY_ORDER_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, SELL_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.seller), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.buyer_pm, false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.seller_pm, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(setup.seller, false),
+        AccountMeta::new(setup.fund_pm, false),
+    ];
+    if with_referral_accounts {
+        metas.push(AccountMeta::new_readonly(user_meta_pda(&setup.buyer), false));
+        metas.push(AccountMeta::new_readonly(user_meta_pda(&setup.seller), false));
+        metas.push(AccountMeta::new(setup.referrer_pm, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await
+}
+
+/// Fill the buy order against the sell order through ExecuteTradeMultiV2,
+/// with the referral accounts appended
+async fn execute_trade_multi(setup: &mut Setup) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ExecuteTradeMultiV2(ExecuteTradeMultiArgs {
+        market_id: MARKET_ID,
+        taker_order_id: BUY_ORDER_ID,
+        maker_order_ids: vec![SELL_ORDER_ID],
+        amount: FILL,
+    })
+    .try_to_vec()
+    .unwrap();
+    let metas = vec![
+        AccountMeta::new(setup.admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_pda(MARKET_ID, BUY_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.buyer), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(setup.buyer_pm, false),
+        AccountMeta::new_readonly(setup.buyer, false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(order_pda(MARKET_ID, SELL_ORDER_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.seller), false),
+        AccountMeta::new(setup.seller_pm, false),
+        AccountMeta::new_readonly(setup.seller, false),
+        AccountMeta::new(setup.fund_pm, false),
+        AccountMeta::new_readonly(user_meta_pda(&setup.buyer), false),
+        AccountMeta::new_readonly(user_meta_pda(&setup.seller), false),
+        AccountMeta::new(setup.referrer_pm, false),
+    ];
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    send(setup, ix).await
+}
+
+/// Place a GTC buy for `user` through RelayerPlaceOrderV2
+async fn place_order(
+    setup: &mut Setup,
+    user: Pubkey,
+    order_id: u64,
+    referrer: Option<Pubkey>,
+) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::RelayerPlaceOrderV2(RelayerPlaceOrderV2Args {
+        user_wallet: user,
+        market_id: MARKET_ID,
+        side: OrderSide::Buy,
+        outcome: Outcome::Yes,
+        price: PRICE,
+        amount: FILL,
+        order_type: OrderType::GTC,
+        expiration_time: None,
+        reduce_only: false,
+        referrer,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.admin.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, order_id), false),
+            AccountMeta::new(position_pda(MARKET_ID, &user), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(pm_user_pda(&user, &setup.vault_program), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(user_meta_pda(&user), false),
+        ],
+    );
+    send(setup, ix).await
+}
+
+/// Returns the (locked, settlement) recorded by the Vault stub
+async fn recorded(context: &mut ProgramTestContext, pm_user: Pubkey) -> (u64, u64) {
+    vault_ledger(&mut context.banks_client, pm_user).await.settlement()
+}
+
+async fn recorded_referrer(context: &mut ProgramTestContext, wallet: Pubkey) -> Option<Pubkey> {
+    let account = context.banks_client.get_account(user_meta_pda(&wallet)).await.unwrap()?;
+    UserMeta::deserialize(&mut &account.data[..]).unwrap().referrer
+}
+
+#[tokio::test]
+async fn test_referrer_paid_share_of_referred_taker_fee() {
+    let mut setup = setup().await;
+    set_referral_share(&mut setup, 5_000).await.unwrap();
+
+    let buyer = setup.buyer;
+    assert_eq!(recorded_referrer(&mut setup.context, buyer).await, Some(setup.referrer));
+    execute_trade(&mut setup, true).await.unwrap();
+
+    // The buyer pays the full fee; half of it goes to the referrer instead
+    // of the Fund. The unreferred seller's fee goes to the Fund in full.
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (COST + FEE, 0));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, COST - FEE));
+    assert_eq!(recorded(&mut setup.context, setup.referrer_pm).await, (0, FEE / 2));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, FEE + FEE / 2));
+}
+
+#[tokio::test]
+async fn test_referrer_paid_on_multi_maker_fill() {
+    let mut setup = setup().await;
+    set_referral_share(&mut setup, 5_000).await.unwrap();
+
+    execute_trade_multi(&mut setup).await.unwrap();
+
+    // Same split as ExecuteTradeV2: buyer debit = seller credit + Fund fee
+    // + referral reward
+    assert_eq!(recorded(&mut setup.context, setup.buyer_pm).await, (COST + FEE, 0));
+    assert_eq!(recorded(&mut setup.context, setup.seller_pm).await, (0, COST - FEE));
+    assert_eq!(recorded(&mut setup.context, setup.referrer_pm).await, (0, FEE / 2));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, FEE + FEE / 2));
+}
+
+#[tokio::test]
+async fn test_no_referral_reward_without_share() {
+    let mut setup = setup().await;
+
+    // No share configured: the referral accounts are not needed
+    execute_trade(&mut setup, false).await.unwrap();
+    assert_eq!(recorded(&mut setup.context, setup.referrer_pm).await, (0, 0));
+    assert_eq!(recorded(&mut setup.context, setup.fund_pm).await, (0, 2 * FEE));
+}
+
+#[tokio::test]
+async fn test_referral_share_requires_user_meta() {
+    let mut setup = setup().await;
+    set_referral_share(&mut setup, 5_000).await.unwrap();
+
+    assert_custom_error(execute_trade(&mut setup, false).await, PredictionMarketError::MissingUserMeta);
+}
+
+#[tokio::test]
+async fn test_first_referrer_is_kept() {
+    let mut setup = setup().await;
+    let seller = setup.seller;
+    let first = Pubkey::new_unique();
+
+    place_order(&mut setup, seller, 3, Some(first)).await.unwrap();
+    assert_eq!(recorded_referrer(&mut setup.context, seller).await, Some(first));
+
+    place_order(&mut setup, seller, 4, Some(Pubkey::new_unique())).await.unwrap();
+    assert_eq!(recorded_referrer(&mut setup.context, seller).await, Some(first));
+}
+
+#[tokio::test]
+async fn test_self_referral_rejected() {
+    let mut setup = setup().await;
+    let seller = setup.seller;
+
+    let result = place_order(&mut setup, seller, 3, Some(seller)).await;
+    assert_custom_error(result, PredictionMarketError::InvalidReferrer);
+    assert_eq!(recorded_referrer(&mut setup.context, seller).await, None);
+}
+
+#[tokio::test]
+async fn test_set_referral_share_rejects_share_above_fee() {
+    let mut setup = setup().await;
+
+    assert_custom_error(set_referral_share(&mut setup, 10_001).await, PredictionMarketError::ReferralShareTooHigh);
+    set_referral_share(&mut setup, 10_000).await.unwrap();
+}
@@ -0,0 +1,171 @@
+//! Integration tests for RelayerRefundCancelledMarketV2
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        PredictionMarketInstruction, RelayerClaimWinningsArgs, RelayerRefundCancelledMarketArgs,
+    },
+    Market, MarketStatus, Position,
+    PredictionMarketError,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 12;
+const TOTAL_COST: u64 = 8_000_000;
+const SETTLED_COST: u64 = 3_000_000;
+
+fn test_market(status: MarketStatus) -> Market {
+    Market {
+        status,
+        resolution_time: 0,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_position(user: Pubkey) -> Position {
+    Position {
+        yes_amount: 5_000_000,
+        no_amount: 5_000_000,
+        yes_avg_cost: 500_000,
+        no_avg_cost: 500_000,
+        total_cost_e6: TOTAL_COST,
+        settled_cost_e6: SETTLED_COST,
+        ..common::test_position(MARKET_ID, user)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    user: Pubkey,
+    vault_program: Pubkey,
+    pm_user: Pubkey,
+}
+
+async fn setup(status: MarketStatus) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(status));
+
+    let user = Pubkey::new_unique();
+    add_position(&mut program_test, &test_position(user));
+
+    let pm_user = add_pm_user(&mut program_test, &user, &vault_program);
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        user,
+        vault_program,
+        pm_user,
+    }
+}
+
+async fn try_refund(setup: &mut Setup) -> Result<(), solana_program_test::BanksClientError> {
+    let instruction = PredictionMarketInstruction::RelayerRefundCancelledMarketV2(
+        RelayerRefundCancelledMarketArgs {
+            user_wallet: setup.user,
+            market_id: MARKET_ID,
+        },
+    );
+    try_settle(setup, instruction).await
+}
+
+async fn try_claim(setup: &mut Setup) -> Result<(), solana_program_test::BanksClientError> {
+    let instruction = PredictionMarketInstruction::RelayerClaimWinningsV2(RelayerClaimWinningsArgs {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+    });
+    try_settle(setup, instruction).await
+}
+
+/// Send a refund/claim instruction; both share the same fixed account layout
+async fn try_settle(
+    setup: &mut Setup,
+    instruction: PredictionMarketInstruction,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = instruction.try_to_vec().unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+            AccountMeta::new(setup.pm_user, false),
+            AccountMeta::new_readonly(vault_config_pda(&setup.vault_program), false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+        ],
+    );
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, &setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_refund_cancelled_market_once() {
+    let mut setup = setup(MarketStatus::Cancelled).await;
+
+    try_refund(&mut setup).await.unwrap();
+
+    // Only the still-locked cost is refunded
+    let refund = TOTAL_COST - SETTLED_COST;
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.pm_user).await;
+    assert_eq!(ledger.settlement(), (refund, refund));
+
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &setup.user)).await;
+    assert!(position.settled);
+    assert_eq!(position.settlement_amount, refund);
+    assert_eq!(position.yes_amount, 0);
+    assert_eq!(position.no_amount, 0);
+
+    // Second refund is rejected and no second settlement happens
+    let result = try_refund(&mut setup).await;
+    assert_custom_error(result, PredictionMarketError::AlreadySettled);
+    assert_eq!(vault_ledger(&mut setup.context.banks_client, setup.pm_user).await.calls, 1);
+}
+
+#[tokio::test]
+async fn test_refund_requires_cancelled_market() {
+    let mut setup = setup(MarketStatus::Resolved).await;
+
+    let result = try_refund(&mut setup).await;
+    assert_custom_error(result, PredictionMarketError::MarketNotCancelled);
+
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &setup.user)).await;
+    assert!(!position.settled);
+    assert_eq!(position.total_cost_e6, TOTAL_COST);
+}
+
+#[tokio::test]
+async fn test_claim_after_refund_rejected() {
+    let mut setup = setup(MarketStatus::Cancelled).await;
+
+    try_refund(&mut setup).await.unwrap();
+
+    // ClaimWinningsV2 also pays out cancelled markets; it must not refund twice
+    let result = try_claim(&mut setup).await;
+    assert_custom_error(result, PredictionMarketError::AlreadySettled);
+
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &setup.user)).await;
+    assert_eq!(position.settlement_amount, TOTAL_COST - SETTLED_COST);
+}
@@ -0,0 +1,140 @@
+//! Integration tests for RelayerMintCompleteSetV2 position validation and cost cap
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerMintCompleteSetArgs}, Position,
+    PredictionMarketError,
+    POSITION_DISCRIMINATOR,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 17;
+const ONE_USDC: u64 = 1_000_000;
+
+fn test_position(market_id: u64, owner: Pubkey) -> Position {
+    let mut position = Position::new(market_id, owner, 255, 0);
+    position.yes_amount = ONE_USDC;
+    position.no_amount = ONE_USDC;
+    position.total_cost_e6 = ONE_USDC;
+    position
+}
+
+/// Returns (context, relayer, vault_program) with `user`'s Position PDA
+/// pre-populated with data for `stored_market_id`
+async fn setup(user: Pubkey, stored_market_id: u64) -> (ProgramTestContext, Keypair, Pubkey) {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(MARKET_ID));
+    program_test.add_account(
+        position_pda(MARKET_ID, &user),
+        program_account(&test_position(stored_market_id, user), Position::SIZE),
+    );
+
+    (program_test.start_with_context().await, relayer, vault_program)
+}
+
+async fn try_mint(
+    context: &mut ProgramTestContext,
+    relayer: &Keypair,
+    vault_program: &Pubkey,
+    user_wallet: Pubkey,
+    max_cost_e6: Option<u64>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::RelayerMintCompleteSetV2(RelayerMintCompleteSetArgs {
+        user_wallet,
+        market_id: MARKET_ID,
+        amount: ONE_USDC,
+        max_cost_e6,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &user_wallet), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(*vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, relayer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_mint_tops_up_existing_position() {
+    let user = Pubkey::new_unique();
+    let (mut context, relayer, vault_program) = setup(user, MARKET_ID).await;
+
+    try_mint(&mut context, &relayer, &vault_program, user, None).await.unwrap();
+
+    let position = load::<Position>(&mut context.banks_client, position_pda(MARKET_ID, &user)).await;
+    assert_eq!(position.discriminator, POSITION_DISCRIMINATOR);
+    assert_eq!(position.yes_amount, 2 * ONE_USDC);
+    assert_eq!(position.no_amount, 2 * ONE_USDC);
+}
+
+#[tokio::test]
+async fn test_mint_rejects_position_of_other_market() {
+    let user = Pubkey::new_unique();
+    let (mut context, relayer, vault_program) = setup(user, MARKET_ID + 1).await;
+
+    let result = try_mint(&mut context, &relayer, &vault_program, user, None).await;
+    assert_custom_error(result, PredictionMarketError::InvalidAccountData);
+
+    let position = load::<Position>(&mut context.banks_client, position_pda(MARKET_ID, &user)).await;
+    assert_eq!(position.market_id, MARKET_ID + 1);
+    assert_eq!(position.yes_amount, ONE_USDC);
+}
+
+#[tokio::test]
+async fn test_mint_within_max_cost() {
+    let user = Pubkey::new_unique();
+    let (mut context, relayer, vault_program) = setup(user, MARKET_ID).await;
+
+    // A cap of exactly the minted amount is enough
+    try_mint(&mut context, &relayer, &vault_program, user, Some(ONE_USDC)).await.unwrap();
+
+    let position = load::<Position>(&mut context.banks_client, position_pda(MARKET_ID, &user)).await;
+    assert_eq!(position.yes_amount, 2 * ONE_USDC);
+    assert_eq!(position.total_cost_e6, 2 * ONE_USDC);
+}
+
+#[tokio::test]
+async fn test_mint_rejects_cost_above_max() {
+    let user = Pubkey::new_unique();
+    let (mut context, relayer, vault_program) = setup(user, MARKET_ID).await;
+
+    let result = try_mint(&mut context, &relayer, &vault_program, user, Some(ONE_USDC - 1)).await;
+    assert_custom_error(result, PredictionMarketError::SlippageExceeded);
+
+    let position = load::<Position>(&mut context.banks_client, position_pda(MARKET_ID, &user)).await;
+    assert_eq!(position.yes_amount, ONE_USDC);
+    assert_eq!(position.total_cost_e6, ONE_USDC);
+}
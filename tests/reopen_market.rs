@@ -0,0 +1,203 @@
+//! Integration tests for ReopenMarket
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        CancelMarketArgs, PredictionMarketInstruction, RelayerRefundCancelledMarketArgs,
+        ReopenMarketArgs,
+    },
+    Market, MarketExtension, MarketStatus, Outcome, Position, PredictionMarketConfig,
+    PredictionMarketError,
+    REOPEN_GRACE_PERIOD_SECS, VAULT_PM_USER_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 40;
+const NOW: i64 = 1_700_000_000;
+
+struct Setup {
+    context: ProgramTestContext,
+    admin: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+    /// Program-owned config at a non-PDA address naming `impostor` as admin
+    spoofed_config: Pubkey,
+    impostor: Keypair,
+}
+
+/// Active market with the MarketExtension tail and one user position
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let admin = Keypair::new();
+    let mut config = test_config(admin.pubkey(), vault_program);
+    config.active_markets = 1;
+    add_config(&mut program_test, &config);
+    let impostor = Keypair::new();
+    let spoofed_config = Pubkey::new_unique();
+    program_test.add_account(
+        spoofed_config,
+        program_account(
+            &test_config(impostor.pubkey(), vault_program),
+            PredictionMarketConfig::SIZE,
+        ),
+    );
+    program_test.add_account(
+        market_pda(MARKET_ID),
+        program_account(&test_market(MARKET_ID), Market::SIZE + MarketExtension::SIZE),
+    );
+
+    let user = Pubkey::new_unique();
+    let mut position = Position::new(MARKET_ID, user, 255, 0);
+    position.add_tokens(Outcome::Yes, 2_000_000, 500_000, 0).unwrap();
+    add_position(&mut program_test, &position);
+    program_test.add_account(
+        Pubkey::find_program_address(&[VAULT_PM_USER_SEED, user.as_ref()], &vault_program).0,
+        Account {
+            lamports: 10_000_000,
+            data: vec![0u8; 16],
+            owner: vault_program,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+
+    Setup { context, admin, vault_program, user, spoofed_config, impostor }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    instruction: PredictionMarketInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &instruction.try_to_vec().unwrap(),
+        accounts,
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+fn admin_accounts(admin: &Keypair) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(admin.pubkey(), true),
+        AccountMeta::new(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+    ]
+}
+
+async fn cancel(setup: &mut Setup) {
+    let instruction =
+        PredictionMarketInstruction::CancelMarket(CancelMarketArgs { market_id: MARKET_ID, reason: 0 });
+    let accounts = admin_accounts(&setup.admin);
+    send(&mut setup.context, &setup.admin, instruction, accounts).await.unwrap();
+}
+
+async fn reopen(setup: &mut Setup) -> Result<(), solana_program_test::BanksClientError> {
+    let instruction = PredictionMarketInstruction::ReopenMarket(ReopenMarketArgs { market_id: MARKET_ID });
+    let accounts = admin_accounts(&setup.admin);
+    send(&mut setup.context, &setup.admin, instruction, accounts).await
+}
+
+async fn refund(setup: &mut Setup) {
+    let instruction = PredictionMarketInstruction::RelayerRefundCancelledMarketV2(
+        RelayerRefundCancelledMarketArgs { user_wallet: setup.user, market_id: MARKET_ID },
+    );
+    let pm_user =
+        Pubkey::find_program_address(&[VAULT_PM_USER_SEED, setup.user.as_ref()], &setup.vault_program).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(setup.admin.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+        AccountMeta::new(pm_user, false),
+        AccountMeta::new_readonly(vault_config_pda(&setup.vault_program), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+    ];
+    send(&mut setup.context, &setup.admin, instruction, accounts).await.unwrap();
+}
+
+async fn load_market(context: &mut ProgramTestContext) -> (Market, MarketExtension) {
+    let account = context.banks_client.get_account(market_pda(MARKET_ID)).await.unwrap().unwrap();
+    (
+        Market::deserialize(&mut &account.data[..]).unwrap(),
+        MarketExtension::load(&account.data),
+    )
+}
+
+#[tokio::test]
+async fn test_reopen_within_grace_period() {
+    let mut setup = setup().await;
+    cancel(&mut setup).await;
+
+    let (_, extension) = load_market(&mut setup.context).await;
+    assert_eq!(extension.cancelled_at, NOW);
+
+    set_clock(&mut setup.context, NOW + REOPEN_GRACE_PERIOD_SECS).await;
+    reopen(&mut setup).await.unwrap();
+
+    let (market, extension) = load_market(&mut setup.context).await;
+    assert_eq!(market.status, MarketStatus::Paused);
+    assert_eq!(extension.cancelled_at, 0);
+}
+
+#[tokio::test]
+async fn test_reopen_rejected_after_refund() {
+    let mut setup = setup().await;
+    cancel(&mut setup).await;
+    refund(&mut setup).await;
+
+    let (_, extension) = load_market(&mut setup.context).await;
+    assert_eq!(extension.cancelled_payouts, 1);
+
+    assert_custom_error(reopen(&mut setup).await, PredictionMarketError::RefundsAlreadyStarted);
+    let (market, _) = load_market(&mut setup.context).await;
+    assert_eq!(market.status, MarketStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn test_reopen_rejected_after_grace_period() {
+    let mut setup = setup().await;
+    cancel(&mut setup).await;
+
+    set_clock(&mut setup.context, NOW + REOPEN_GRACE_PERIOD_SECS + 1).await;
+    assert_custom_error(reopen(&mut setup).await, PredictionMarketError::ReopenWindowExpired);
+}
+
+#[tokio::test]
+async fn test_reopen_rejects_spoofed_config() {
+    let mut setup = setup().await;
+    cancel(&mut setup).await;
+
+    let instruction = PredictionMarketInstruction::ReopenMarket(ReopenMarketArgs { market_id: MARKET_ID });
+    let accounts = vec![
+        AccountMeta::new_readonly(setup.impostor.pubkey(), true),
+        AccountMeta::new(setup.spoofed_config, false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+    ];
+    let result = send(&mut setup.context, &setup.impostor, instruction, accounts).await;
+    assert_custom_error(result, PredictionMarketError::InvalidPDA);
+    assert_eq!(load_market(&mut setup.context).await.0.status, MarketStatus::Cancelled);
+}
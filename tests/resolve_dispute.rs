@@ -0,0 +1,193 @@
+//! Integration tests for ResolveDispute
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, ResolveDisputeArgs},
+    Market, MarketResult, MarketStatus, OracleProposal,
+    PredictionMarketConfig, PredictionMarketError, ProposalStatus,
+};
+use solana_program_test::{BanksClient, BanksClientError};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 10;
+const PROPOSER_BOND: u64 = 5_000_000;
+const CHALLENGER_BOND: u64 = 3_000_000;
+
+fn test_market() -> Market {
+    Market {
+        status: MarketStatus::ResultProposed,
+        resolution_time: 0,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_proposal(proposer: Pubkey, challenger: Pubkey) -> OracleProposal {
+    OracleProposal {
+        status: ProposalStatus::Disputed,
+        challenge_deadline: 7200,
+        bond_amount: PROPOSER_BOND,
+        challenger: Some(challenger),
+        challenger_result: Some(MarketResult::No),
+        challenger_bond: CHALLENGER_BOND,
+        original_challenge_deadline: 7200,
+        challenge_count: 1,
+        ..common::test_proposal(MARKET_ID, proposer)
+    }
+}
+
+struct Dispute {
+    banks_client: BanksClient,
+    committee: Keypair,
+    vault_program: Pubkey,
+    proposer_pm_user: Pubkey,
+    challenger_pm_user: Pubkey,
+    fund_pm_user: Pubkey,
+}
+
+async fn setup() -> Dispute {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+
+    let committee = Keypair::new();
+    let fund_program = Pubkey::new_unique();
+    let config = PredictionMarketConfig {
+        oracle_admin: committee.pubkey(),
+        fund_program,
+        ..test_config(Pubkey::new_unique(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let proposer = Pubkey::new_unique();
+    let challenger = Pubkey::new_unique();
+    add_proposal(&mut program_test, &test_proposal(proposer, challenger));
+    let proposer_pm_user = add_pm_user(&mut program_test, &proposer, &vault_program);
+    let challenger_pm_user = add_pm_user(&mut program_test, &challenger, &vault_program);
+    let fund_pm_user = add_pm_user(&mut program_test, &fund_program, &vault_program);
+    program_test.add_account(
+        committee.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    let (banks_client, _payer, _) = program_test.start().await;
+    Dispute {
+        banks_client,
+        committee,
+        vault_program,
+        proposer_pm_user,
+        challenger_pm_user,
+        fund_pm_user,
+    }
+}
+
+async fn try_resolve(
+    dispute: &mut Dispute,
+    result: MarketResult,
+    with_fund: bool,
+) -> Result<(), BanksClientError> {
+    let data = PredictionMarketInstruction::ResolveDispute(ResolveDisputeArgs {
+        market_id: MARKET_ID,
+        result,
+    })
+    .try_to_vec()
+    .unwrap();
+    let mut metas = vec![
+        AccountMeta::new(dispute.committee.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(proposal_pda(MARKET_ID), false),
+        AccountMeta::new(dispute.proposer_pm_user, false),
+        AccountMeta::new(dispute.challenger_pm_user, false),
+        AccountMeta::new_readonly(vault_config_pda(&dispute.vault_program), false),
+        AccountMeta::new_readonly(dispute.vault_program, false),
+    ];
+    if with_fund {
+        metas.push(AccountMeta::new(dispute.fund_pm_user, false));
+    }
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = dispute.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&dispute.committee.pubkey()),
+        &[&dispute.committee],
+        recent_blockhash,
+    );
+    dispute.banks_client.process_transaction(tx).await
+}
+
+/// Resolve with `result`; returns the settled (locked, settlement) for
+/// proposer, challenger and Fund along with the final market and proposal
+async fn resolve(
+    result: MarketResult,
+) -> ((u64, u64), (u64, u64), (u64, u64), Market, OracleProposal) {
+    let mut dispute = setup().await;
+    try_resolve(&mut dispute, result, true).await.unwrap();
+
+    let banks_client = &mut dispute.banks_client;
+    (
+        vault_ledger(banks_client, dispute.proposer_pm_user).await.settlement(),
+        vault_ledger(banks_client, dispute.challenger_pm_user).await.settlement(),
+        vault_ledger(banks_client, dispute.fund_pm_user).await.settlement(),
+        load(banks_client, market_pda(MARKET_ID)).await,
+        load(banks_client, proposal_pda(MARKET_ID)).await,
+    )
+}
+
+#[tokio::test]
+async fn test_resolve_dispute_upholds_proposal() {
+    let (proposer, challenger, fund, market, proposal) = resolve(MarketResult::Yes).await;
+
+    assert_eq!(proposer, (PROPOSER_BOND, PROPOSER_BOND + CHALLENGER_BOND));
+    assert_eq!(challenger, (CHALLENGER_BOND, 0));
+    assert_eq!(fund, (0, 0));
+    assert_eq!(proposal.status, ProposalStatus::Finalized);
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::Yes));
+}
+
+#[tokio::test]
+async fn test_resolve_dispute_sides_with_challenger() {
+    let (proposer, challenger, fund, market, proposal) = resolve(MarketResult::No).await;
+
+    assert_eq!(proposer, (PROPOSER_BOND, 0));
+    assert_eq!(challenger, (CHALLENGER_BOND, PROPOSER_BOND + CHALLENGER_BOND));
+    assert_eq!(fund, (0, 0));
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::No));
+}
+
+#[tokio::test]
+async fn test_resolve_dispute_third_result_forfeits_both_bonds() {
+    let (proposer, challenger, fund, market, proposal) = resolve(MarketResult::Invalid).await;
+
+    assert_eq!(proposer, (PROPOSER_BOND, 0));
+    assert_eq!(challenger, (CHALLENGER_BOND, 0));
+    assert_eq!(fund, (0, PROPOSER_BOND + CHALLENGER_BOND));
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::Invalid));
+}
+
+#[tokio::test]
+async fn test_resolve_dispute_forfeit_requires_fund_account() {
+    let mut dispute = setup().await;
+
+    let result = try_resolve(&mut dispute, MarketResult::Invalid, false).await;
+    assert_custom_error(result, PredictionMarketError::MissingFundAccount);
+
+    // A result one side argued for forfeits nothing
+    try_resolve(&mut dispute, MarketResult::Yes, false).await.unwrap();
+}
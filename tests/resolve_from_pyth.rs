@@ -0,0 +1,189 @@
+//! Integration tests for ResolveFromPyth against mocked Pyth price accounts
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, ResolveFromPythArgs},
+    oracle_feed::{
+        PYTH_ACCOUNT_TYPE_OFFSET, PYTH_AGG_PRICE_OFFSET, PYTH_AGG_STATUS_OFFSET, PYTH_EXPO_OFFSET,
+        PYTH_MAGIC, PYTH_MAGIC_OFFSET, PYTH_ORACLE_PROGRAM_ID, PYTH_PRICE_ACCOUNT_TYPE,
+        PYTH_PRICE_MIN_LEN, PYTH_STATUS_TRADING, PYTH_TIMESTAMP_OFFSET, PYTH_VERSION,
+        PYTH_VERSION_OFFSET,
+    },
+    ComparisonOperator, Market, MarketCategory, MarketResult, MarketStatus, PredictionMarketError,
+    ResolutionSource, PYTH_MAX_PRICE_AGE_SECS,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 45;
+const RESOLUTION_TIME: i64 = 1_700_000_000;
+/// "BTC above $65,000" scaled by 1e9
+const THRESHOLD: i128 = 65_000_000_000_000;
+const NOW: i64 = RESOLUTION_TIME + 30;
+
+fn test_market() -> Market {
+    Market {
+        resolution_time: RESOLUTION_TIME,
+        finalization_deadline: RESOLUTION_TIME + 86_400,
+        category: MarketCategory::Crypto,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Market account carrying `source` in its resolution source slot
+fn market_account(source: ResolutionSource) -> Account {
+    let mut account =
+        program_account(&test_market(), ResolutionSource::OFFSET + ResolutionSource::SIZE);
+    source.store(&mut account.data).unwrap();
+    account
+}
+
+/// Trading Pyth price account with aggregate `price * 10^-8` published at
+/// `published_at`
+fn pyth_account(published_at: i64, price: i64) -> Account {
+    let mut data = vec![0u8; PYTH_PRICE_MIN_LEN];
+    data[PYTH_MAGIC_OFFSET..][..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[PYTH_VERSION_OFFSET..][..4].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+    data[PYTH_ACCOUNT_TYPE_OFFSET..][..4].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[PYTH_EXPO_OFFSET..][..4].copy_from_slice(&(-8i32).to_le_bytes());
+    data[PYTH_TIMESTAMP_OFFSET..][..8].copy_from_slice(&published_at.to_le_bytes());
+    data[PYTH_AGG_PRICE_OFFSET..][..8].copy_from_slice(&price.to_le_bytes());
+    data[PYTH_AGG_STATUS_OFFSET..][..4].copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner: PYTH_ORACLE_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn setup(source: ResolutionSource, feed: Pubkey, price_account: Account) -> ProgramTestContext {
+    let mut program_test = program_test();
+
+    let config = test_config(Pubkey::new_unique(), Pubkey::new_unique());
+    add_config(&mut program_test, &config);
+    program_test.add_account(market_pda(MARKET_ID), market_account(source));
+    program_test.add_account(feed, price_account);
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+    context
+}
+
+fn pyth_source(feed: Pubkey) -> ResolutionSource {
+    ResolutionSource::Pyth { feed, threshold: THRESHOLD }
+}
+
+/// Sends ResolveFromPyth signed by a fresh, unprivileged caller
+async fn resolve(
+    context: &mut ProgramTestContext,
+    feed: Pubkey,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let caller = Keypair::new();
+    let data = PredictionMarketInstruction::ResolveFromPyth(ResolveFromPythArgs {
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(caller.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new_readonly(feed, false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &caller],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Resolves a Pyth market against `price` published at `published_at`
+async fn resolve_at(
+    published_at: i64,
+    price: i64,
+) -> (ProgramTestContext, Result<(), solana_program_test::BanksClientError>) {
+    let feed = Pubkey::new_unique();
+    let mut context = setup(pyth_source(feed), feed, pyth_account(published_at, price)).await;
+    let result = resolve(&mut context, feed).await;
+    (context, result)
+}
+
+#[tokio::test]
+async fn test_resolve_from_pyth_yes_above_threshold() {
+    // $65,123.45
+    let (mut context, result) = resolve_at(NOW - 5, 6_512_345_000_000).await;
+    result.unwrap();
+
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::Yes));
+}
+
+#[tokio::test]
+async fn test_resolve_from_pyth_yes_at_threshold() {
+    let (mut context, result) = resolve_at(RESOLUTION_TIME, 6_500_000_000_000).await;
+    result.unwrap();
+
+    let market: Market = load(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.final_result, Some(MarketResult::Yes));
+}
+
+#[tokio::test]
+async fn test_resolve_from_pyth_no_below_threshold() {
+    // $64,999.99999999
+    let (mut context, result) = resolve_at(NOW, 6_499_999_999_999).await;
+    result.unwrap();
+
+    let market = load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.status, MarketStatus::Resolved);
+    assert_eq!(market.final_result, Some(MarketResult::No));
+}
+
+#[tokio::test]
+async fn test_resolve_from_pyth_rejects_price_before_resolution_time() {
+    let (mut context, result) = resolve_at(RESOLUTION_TIME - 1, 6_512_345_000_000).await;
+    assert_custom_error(result, PredictionMarketError::StaleOracleFeed);
+    assert_eq!(load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await.status, MarketStatus::Active);
+}
+
+#[tokio::test]
+async fn test_resolve_from_pyth_rejects_stale_price() {
+    let feed = Pubkey::new_unique();
+    let mut context = setup(pyth_source(feed), feed, pyth_account(RESOLUTION_TIME, 6_512_345_000_000)).await;
+
+    set_clock(&mut context, RESOLUTION_TIME + PYTH_MAX_PRICE_AGE_SECS + 1).await;
+
+    assert_custom_error(resolve(&mut context, feed).await, PredictionMarketError::StaleOracleFeed);
+    assert_eq!(load::<Market>(&mut context.banks_client, market_pda(MARKET_ID)).await.status, MarketStatus::Active);
+}
+
+#[tokio::test]
+async fn test_resolve_from_pyth_rejects_switchboard_market() {
+    let feed = Pubkey::new_unique();
+    let source = ResolutionSource::Switchboard {
+        feed,
+        operator: ComparisonOperator::GreaterOrEqual,
+        threshold: THRESHOLD,
+    };
+    let mut context = setup(source, feed, pyth_account(NOW, 6_512_345_000_000)).await;
+
+    assert_custom_error(resolve(&mut context, feed).await, PredictionMarketError::ResolutionSourceMismatch);
+}
@@ -0,0 +1,159 @@
+//! Integration tests for the settlement Vault checks on claims and refunds
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerClaimWinningsArgs, RelayerRefundCancelledMarketArgs},
+    Market, MarketResult, MarketStatus, Outcome, Position, PredictionMarketError,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const MARKET_ID: u64 = 71;
+const SHARES: u64 = 4_000_000;
+
+fn test_market(status: MarketStatus) -> Market {
+    Market {
+        status,
+        resolution_time: 0,
+        final_result: (status == MarketStatus::Resolved).then_some(MarketResult::Yes),
+        total_minted: SHARES,
+        open_interest: SHARES,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+    pm_user: Pubkey,
+    /// VaultConfig-shaped account owned by another program
+    foreign_vault_config: Pubkey,
+    /// PMUserAccount of a wallet without a position
+    other_pm_user: Pubkey,
+}
+
+async fn setup(status: MarketStatus) -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_recording_vault(&mut program_test);
+    let foreign_vault_config = add_vault_config(&mut program_test, &Pubkey::new_unique());
+
+    let relayer = Keypair::new();
+    add_config(&mut program_test, &test_config(relayer.pubkey(), vault_program));
+    add_market(&mut program_test, &test_market(status));
+
+    let user = Pubkey::new_unique();
+    let mut position = test_position(MARKET_ID, user);
+    position.add_tokens(Outcome::Yes, SHARES, 600_000, 0).unwrap();
+    add_position(&mut program_test, &position);
+
+    let pm_user = add_pm_user(&mut program_test, &user, &vault_program);
+    let other_pm_user = add_pm_user(&mut program_test, &Pubkey::new_unique(), &vault_program);
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        user,
+        pm_user,
+        foreign_vault_config,
+        other_pm_user,
+    }
+}
+
+/// Send a claim or refund; both share the same fixed account layout
+async fn try_settle(
+    setup: &mut Setup,
+    instruction: PredictionMarketInstruction,
+    pm_user: Pubkey,
+    vault_config: Pubkey,
+) -> Result<(), BanksClientError> {
+    let data = instruction.try_to_vec().unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+            AccountMeta::new(pm_user, false),
+            AccountMeta::new_readonly(vault_config, false),
+            AccountMeta::new_readonly(setup.vault_program, false),
+        ],
+    );
+    let relayer = setup.relayer.insecure_clone();
+    send(&mut setup.context, &[ix], &[&relayer]).await
+}
+
+fn claim(setup: &Setup) -> PredictionMarketInstruction {
+    PredictionMarketInstruction::RelayerClaimWinningsV2(RelayerClaimWinningsArgs {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+    })
+}
+
+fn refund(setup: &Setup) -> PredictionMarketInstruction {
+    PredictionMarketInstruction::RelayerRefundCancelledMarketV2(RelayerRefundCancelledMarketArgs {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+    })
+}
+
+#[tokio::test]
+async fn test_claim_settles_through_configured_vault() {
+    let mut setup = setup(MarketStatus::Resolved).await;
+
+    let (instruction, pm_user) = (claim(&setup), setup.pm_user);
+    let vault_config = vault_config_pda(&setup.vault_program);
+    try_settle(&mut setup, instruction, pm_user, vault_config).await.unwrap();
+
+    let ledger = vault_ledger(&mut setup.context.banks_client, setup.pm_user).await;
+    assert_eq!(ledger.settled, SHARES);
+}
+
+#[tokio::test]
+async fn test_claim_rejects_vault_config_of_other_program() {
+    let mut setup = setup(MarketStatus::Resolved).await;
+
+    let (instruction, pm_user) = (claim(&setup), setup.pm_user);
+    let vault_config = setup.foreign_vault_config;
+    let result = try_settle(&mut setup, instruction, pm_user, vault_config).await;
+    assert_custom_error(result, PredictionMarketError::InvalidVaultAccount);
+
+    assert_eq!(vault_ledger(&mut setup.context.banks_client, setup.pm_user).await.calls, 0);
+    let position: Position = load(&mut setup.context.banks_client, position_pda(MARKET_ID, &setup.user)).await;
+    assert!(!position.settled);
+}
+
+#[tokio::test]
+async fn test_claim_rejects_pm_user_of_other_wallet() {
+    let mut setup = setup(MarketStatus::Resolved).await;
+
+    let (instruction, pm_user) = (claim(&setup), setup.other_pm_user);
+    let vault_config = vault_config_pda(&setup.vault_program);
+    let result = try_settle(&mut setup, instruction, pm_user, vault_config).await;
+    assert_custom_error(result, PredictionMarketError::InvalidVaultAccount);
+
+    assert_eq!(vault_ledger(&mut setup.context.banks_client, setup.other_pm_user).await.calls, 0);
+}
+
+#[tokio::test]
+async fn test_refund_rejects_vault_config_of_other_program() {
+    let mut setup = setup(MarketStatus::Cancelled).await;
+
+    let (instruction, pm_user) = (refund(&setup), setup.pm_user);
+    let vault_config = setup.foreign_vault_config;
+    let result = try_settle(&mut setup, instruction, pm_user, vault_config).await;
+    assert_custom_error(result, PredictionMarketError::InvalidVaultAccount);
+
+    assert_eq!(vault_ledger(&mut setup.context.banks_client, setup.pm_user).await.calls, 0);
+}
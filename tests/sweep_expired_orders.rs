@@ -0,0 +1,107 @@
+//! Integration tests for SweepExpiredOrders
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::PredictionMarketInstruction,
+    Order, OrderSide, OrderStatus, OrderType,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 11;
+const NOW: i64 = 1_700_000_000;
+
+fn test_order(order_id: u64, status: OrderStatus, expiration_time: Option<i64>) -> Order {
+    Order {
+        amount: 100,
+        filled_amount: if status == OrderStatus::PartialFilled { 40 } else { 0 },
+        status,
+        order_type: if expiration_time.is_some() { OrderType::GTD } else { OrderType::GTC },
+        expiration_time,
+        margin_locked: false,
+        ..common::test_order(MARKET_ID, order_id, Pubkey::new_unique(), OrderSide::Buy)
+    }
+}
+
+fn order_account(order: &Order) -> Account {
+    let mut data = order.try_to_vec().unwrap();
+    data.resize(Order::SIZE, 0);
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner: prediction_market_program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn setup(orders: &[Order]) -> ProgramTestContext {
+    let mut program_test = program_test();
+    for order in orders {
+        program_test.add_account(order_pda(MARKET_ID, order.order_id), order_account(order));
+    }
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+    context
+}
+
+async fn load_order(context: &mut ProgramTestContext, order_id: u64) -> Order {
+    let account = context.banks_client.get_account(order_pda(MARKET_ID, order_id)).await.unwrap().unwrap();
+    Order::deserialize(&mut &account.data[..]).unwrap()
+}
+
+#[tokio::test]
+async fn test_sweep_expired_orders_batch() {
+    let orders = [
+        test_order(1, OrderStatus::Open, Some(NOW - 10)),
+        test_order(2, OrderStatus::PartialFilled, Some(NOW)),
+        test_order(3, OrderStatus::Open, Some(NOW + 3600)),
+        test_order(4, OrderStatus::Open, None),
+        test_order(5, OrderStatus::Filled, Some(NOW - 10)),
+        test_order(6, OrderStatus::Cancelled, Some(NOW - 10)),
+    ];
+    let mut context = setup(&orders).await;
+
+    let data = PredictionMarketInstruction::SweepExpiredOrders.try_to_vec().unwrap();
+    let mut metas = vec![AccountMeta::new(context.payer.pubkey(), true)];
+    metas.extend(orders.iter().map(|o| AccountMeta::new(order_pda(MARKET_ID, o.order_id), false)));
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let expected = [
+        OrderStatus::Expired,
+        OrderStatus::Expired,
+        OrderStatus::Open,
+        OrderStatus::Open,
+        OrderStatus::Filled,
+        OrderStatus::Cancelled,
+    ];
+    for (order, status) in orders.iter().zip(expected) {
+        let swept = load_order(&mut context, order.order_id).await;
+        assert_eq!(swept.status, status, "order {}", order.order_id);
+        // Fill state is untouched; funds are released on cancel
+        assert_eq!(swept.filled_amount, order.filled_amount);
+        assert_eq!(
+            swept.is_cancellable(),
+            !matches!(status, OrderStatus::Filled | OrderStatus::Cancelled)
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Integration tests for markets denominated in a Token-2022 USDC mint
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        ActivateMarketArgs, CreateMarketArgs, MintCompleteSetArgs, PredictionMarketInstruction,
+        RedeemCompleteSetArgs,
+    },
+    Market, MarketCategory, PredictionMarketConfig, ResolutionSource,
+    MARKET_VAULT_SEED, NO_MINT_SEED, POSITION_SEED, YES_MINT_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use spl_token_2022::{
+    extension::{transfer_fee, BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::{Account as TokenAccount, Mint},
+};
+
+const MARKET_ID: u64 = 46;
+const NOW: i64 = 1_700_000_000;
+/// 1% transfer fee on USDC
+const TRANSFER_FEE_BPS: u16 = 100;
+const USER_USDC: u64 = 100_000_000;
+const SETS: u64 = 10_000_000;
+
+fn yes_mint() -> Pubkey {
+    pda(&[YES_MINT_SEED, &MARKET_ID.to_le_bytes()])
+}
+
+fn no_mint() -> Pubkey {
+    pda(&[NO_MINT_SEED, &MARKET_ID.to_le_bytes()])
+}
+
+fn market_vault() -> Pubkey {
+    pda(&[MARKET_VAULT_SEED, &MARKET_ID.to_le_bytes()])
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    admin: Keypair,
+    usdc_mint: Keypair,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+
+    let admin = Keypair::new();
+    let usdc_mint = Keypair::new();
+    let config = PredictionMarketConfig {
+        usdc_mint: usdc_mint.pubkey(),
+        next_market_id: MARKET_ID,
+        ..test_config(admin.pubkey(), Pubkey::new_unique())
+    };
+    add_config(&mut program_test, &config);
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+
+    let mut setup = Setup {
+        context,
+        admin,
+        usdc_mint,
+    };
+    create_usdc_mint(&mut setup).await;
+    setup
+}
+
+async fn send(context: &mut ProgramTestContext, ixs: &[Instruction], signers: &[&Keypair]) {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(
+        ixs,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+/// Token-2022 USDC mint with a TransferFeeConfig, mint authority = payer
+async fn create_usdc_mint(setup: &mut Setup) {
+    let payer = setup.context.payer.pubkey();
+    let mint = setup.usdc_mint.pubkey();
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+            .unwrap();
+    let rent = setup.context.banks_client.get_rent().await.unwrap();
+    let ixs = [
+        system_instruction::create_account(
+            &payer,
+            &mint,
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::id(),
+        ),
+        transfer_fee::instruction::initialize_transfer_fee_config(
+            &spl_token_2022::id(),
+            &mint,
+            Some(&payer),
+            Some(&payer),
+            TRANSFER_FEE_BPS,
+            u64::MAX,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::id(),
+            &mint,
+            &payer,
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+    let usdc_mint = setup.usdc_mint.insecure_clone();
+    send(&mut setup.context, &ixs, &[&usdc_mint]).await;
+}
+
+/// Token-2022 account for `mint` owned by `owner`, sized for the mint's extensions
+async fn create_token_account(
+    setup: &mut Setup,
+    mint: Pubkey,
+    owner: Pubkey,
+    extensions: &[ExtensionType],
+) -> Pubkey {
+    let payer = setup.context.payer.pubkey();
+    let account = Keypair::new();
+    let space = ExtensionType::try_calculate_account_len::<TokenAccount>(extensions).unwrap();
+    let rent = setup.context.banks_client.get_rent().await.unwrap();
+    let ixs = [
+        system_instruction::create_account(
+            &payer,
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::id(),
+        ),
+        spl_token_2022::instruction::initialize_account3(
+            &spl_token_2022::id(),
+            &account.pubkey(),
+            &mint,
+            &owner,
+        )
+        .unwrap(),
+    ];
+    send(&mut setup.context, &ixs, &[&account]).await;
+    account.pubkey()
+}
+
+async fn create_market(setup: &mut Setup) {
+    let data = PredictionMarketInstruction::CreateMarket(CreateMarketArgs {
+        question_hash: [1u8; 32],
+        resolution_spec_hash: [2u8; 32],
+        resolution_time: NOW + 86_400,
+        finalization_deadline: NOW + 2 * 86_400,
+        creator_fee_bps: 0,
+        challenge_window_secs: 0,
+        category: MarketCategory::Crypto,
+        tags_hash: [0u8; 32],
+        resolution_source: ResolutionSource::Manual,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new(setup.context.payer.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(yes_mint(), false),
+            AccountMeta::new(no_mint(), false),
+            AccountMeta::new(market_vault(), false),
+            AccountMeta::new_readonly(setup.usdc_mint.pubkey(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    send(&mut setup.context, &[ix], &[]).await;
+}
+
+async fn activate_market(setup: &mut Setup) {
+    let data = PredictionMarketInstruction::ActivateMarket(ActivateMarketArgs {
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.admin.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+        ],
+    );
+    let admin = setup.admin.insecure_clone();
+    send(&mut setup.context, &[ix], &[&admin]).await;
+}
+
+async fn account(setup: &mut Setup, address: Pubkey) -> Account {
+    setup
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+async fn token_balance(setup: &mut Setup, address: Pubkey) -> u64 {
+    let account = account(setup, address).await;
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+/// Fee Token-2022 withholds on a transfer of `amount` (rounded up)
+fn transfer_fee(amount: u64) -> u64 {
+    (amount * TRANSFER_FEE_BPS as u64).div_ceil(10_000)
+}
+
+#[tokio::test]
+async fn test_create_market_with_token_2022() {
+    let mut setup = setup().await;
+    create_market(&mut setup).await;
+
+    // Outcome mints live under the passed Token-2022 program
+    for mint in [yes_mint(), no_mint()] {
+        let account = account(&mut setup, mint).await;
+        assert_eq!(account.owner, spl_token_2022::id());
+        let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+        assert_eq!(state.base.mint_authority.unwrap(), market_pda(MARKET_ID));
+        assert_eq!(state.base.decimals, 6);
+    }
+
+    // The vault carries the TransferFeeAmount extension the fee mint requires
+    let vault = account(&mut setup, market_vault()).await;
+    assert_eq!(vault.owner, spl_token_2022::id());
+    let expected_len = ExtensionType::try_calculate_account_len::<TokenAccount>(&[
+        ExtensionType::TransferFeeAmount,
+    ])
+    .unwrap();
+    assert_eq!(vault.data.len(), expected_len);
+    let vault_state = StateWithExtensions::<TokenAccount>::unpack(&vault.data).unwrap();
+    assert_eq!(vault_state.base.owner, market_pda(MARKET_ID));
+    assert!(vault_state
+        .get_extension::<transfer_fee::TransferFeeAmount>()
+        .is_ok());
+
+    let market = account(&mut setup, market_pda(MARKET_ID)).await;
+    let market = Market::deserialize(&mut &market.data[..]).unwrap();
+    assert_eq!(market.market_vault, market_vault());
+}
+
+#[tokio::test]
+async fn test_complete_set_round_trip_with_transfer_fee() {
+    let mut setup = setup().await;
+    create_market(&mut setup).await;
+    activate_market(&mut setup).await;
+
+    let user = setup.context.payer.pubkey();
+    let usdc_mint = setup.usdc_mint.pubkey();
+    let user_usdc = create_token_account(
+        &mut setup,
+        usdc_mint,
+        user,
+        &[ExtensionType::TransferFeeAmount],
+    )
+    .await;
+    let user_yes = create_token_account(&mut setup, yes_mint(), user, &[]).await;
+    let user_no = create_token_account(&mut setup, no_mint(), user, &[]).await;
+    let mint_usdc = spl_token_2022::instruction::mint_to(
+        &spl_token_2022::id(),
+        &usdc_mint,
+        &user_usdc,
+        &user,
+        &[],
+        USER_USDC,
+    )
+    .unwrap();
+    send(&mut setup.context, &[mint_usdc], &[]).await;
+
+    let position = pda(&[POSITION_SEED, &MARKET_ID.to_le_bytes(), user.as_ref()]);
+    let mint_ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &PredictionMarketInstruction::MintCompleteSet(MintCompleteSetArgs {
+            market_id: MARKET_ID,
+            amount: SETS,
+        })
+        .try_to_vec()
+        .unwrap(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(market_vault(), false),
+            AccountMeta::new(user_usdc, false),
+            AccountMeta::new(yes_mint(), false),
+            AccountMeta::new(no_mint(), false),
+            AccountMeta::new(user_yes, false),
+            AccountMeta::new(user_no, false),
+            AccountMeta::new(position, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            // No mint fee is configured, so the Fund account is unused
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(usdc_mint, false),
+        ],
+    );
+    send(&mut setup.context, &[mint_ix], &[]).await;
+
+    // The vault nets exactly the collateral; the user pays the fee on top
+    assert_eq!(token_balance(&mut setup, market_vault()).await, SETS);
+    let paid = USER_USDC - token_balance(&mut setup, user_usdc).await;
+    assert_eq!(paid - transfer_fee(paid), SETS);
+    assert_eq!(token_balance(&mut setup, user_yes).await, SETS);
+    assert_eq!(token_balance(&mut setup, user_no).await, SETS);
+
+    let balance_before_redeem = token_balance(&mut setup, user_usdc).await;
+    let redeem_ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &PredictionMarketInstruction::RedeemCompleteSet(RedeemCompleteSetArgs {
+            market_id: MARKET_ID,
+            amount: SETS,
+        })
+        .try_to_vec()
+        .unwrap(),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(market_vault(), false),
+            AccountMeta::new(user_usdc, false),
+            AccountMeta::new(yes_mint(), false),
+            AccountMeta::new(no_mint(), false),
+            AccountMeta::new(user_yes, false),
+            AccountMeta::new(user_no, false),
+            AccountMeta::new(position, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(usdc_mint, false),
+        ],
+    );
+    send(&mut setup.context, &[redeem_ix], &[]).await;
+
+    // The vault pays out the full collateral; the fee comes out of what the user receives
+    assert_eq!(token_balance(&mut setup, market_vault()).await, 0);
+    assert_eq!(
+        token_balance(&mut setup, user_usdc).await,
+        balance_before_redeem + SETS - transfer_fee(SETS)
+    );
+    assert_eq!(token_balance(&mut setup, user_yes).await, 0);
+    assert_eq!(token_balance(&mut setup, user_no).await, 0);
+}
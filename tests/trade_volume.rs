@@ -0,0 +1,134 @@
+//! Integration tests for market and global trading volume
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{ExecuteTradeArgs, PredictionMarketInstruction},
+    Market, Order, OrderSide, Position, PredictionMarketConfig,
+    SHARE_PRECISION,
+};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 28;
+const BUY_ORDER_ID: u64 = 1;
+const SELL_ORDER_ID: u64 = 2;
+/// One contract
+const AMOUNT: u64 = SHARE_PRECISION;
+const PRICE: u64 = 650_000;
+
+fn test_market() -> Market {
+    Market {
+        total_minted: AMOUNT,
+        open_interest: AMOUNT,
+        next_order_id: 3,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(order_id: u64, owner: Pubkey, side: OrderSide) -> Order {
+    Order {
+        price: PRICE,
+        amount: AMOUNT,
+        ..common::test_order(MARKET_ID, order_id, owner, side)
+    }
+}
+
+fn test_position(owner: Pubkey, yes_locked: u64) -> Position {
+    Position {
+        yes_amount: yes_locked,
+        yes_locked,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+#[tokio::test]
+async fn test_single_contract_trade_adds_notional_volume() {
+    let program_id = prediction_market_program::id();
+    let mut program_test = program_test();
+    let vault_program = add_stub_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market());
+
+    let buyer = Pubkey::new_unique();
+    let seller = Pubkey::new_unique();
+    add_order(&mut program_test, &test_order(BUY_ORDER_ID, buyer, OrderSide::Buy));
+    add_order(&mut program_test, &test_order(SELL_ORDER_ID, seller, OrderSide::Sell));
+    add_position(&mut program_test, &test_position(buyer, 0));
+    add_position(&mut program_test, &test_position(seller, AMOUNT));
+    for owner in [buyer, seller] {
+        program_test.add_account(
+            pm_user_pda(&owner, &vault_program),
+            Account {
+                lamports: 10_000_000,
+                owner: vault_program,
+                ..Account::default()
+            },
+        );
+    }
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let data = PredictionMarketInstruction::ExecuteTradeV2(ExecuteTradeArgs {
+        market_id: MARKET_ID,
+        taker_order_id: BUY_ORDER_ID,
+        maker_order_id: SELL_ORDER_ID,
+        amount: AMOUNT,
+        price: PRICE,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![
+            AccountMeta::new(relayer.pubkey(), true),
+            AccountMeta::new(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, BUY_ORDER_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, SELL_ORDER_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &buyer), false),
+            AccountMeta::new(position_pda(MARKET_ID, &seller), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(pm_user_pda(&buyer, &vault_program), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(pm_user_pda(&seller, &vault_program), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(vault_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(buyer, false),
+            AccountMeta::new_readonly(seller, false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&relayer.pubkey()),
+        &[&relayer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // 1 contract at $0.65 is $0.65 of notional
+    let market: Market = load(&mut context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.total_volume_e6, 650_000);
+    let config: PredictionMarketConfig = load(&mut context.banks_client, config_pda()).await;
+    assert_eq!(config.total_volume_e6, 650_000);
+}
@@ -0,0 +1,151 @@
+//! Integration tests for UpdateMarketSchedule
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, UpdateMarketScheduleArgs},
+    Market, MarketStatus, PredictionMarketError, MARKET_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 20;
+const NOW: i64 = 1_700_000_000;
+const RESOLUTION_TIME: i64 = NOW + 86_400;
+const FINALIZATION_DEADLINE: i64 = NOW + 2 * 86_400;
+
+fn market_pda() -> Pubkey {
+    Pubkey::find_program_address(
+        &[MARKET_SEED, &MARKET_ID.to_le_bytes()],
+        &prediction_market_program::id(),
+    )
+    .0
+}
+
+fn test_market(creator: Pubkey, status: MarketStatus) -> Market {
+    Market {
+        creator,
+        status,
+        resolution_time: RESOLUTION_TIME,
+        finalization_deadline: FINALIZATION_DEADLINE,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+/// Returns (context, creator)
+async fn setup(status: MarketStatus) -> (ProgramTestContext, Keypair) {
+    let mut program_test = program_test();
+
+    let creator = Keypair::new();
+    let mut data = test_market(creator.pubkey(), status).try_to_vec().unwrap();
+    data.resize(Market::SIZE, 0);
+    program_test.add_account(
+        market_pda(),
+        Account {
+            lamports: 10_000_000,
+            data,
+            owner: prediction_market_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, NOW).await;
+    (context, creator)
+}
+
+async fn try_update(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    resolution_time: Option<i64>,
+    finalization_deadline: Option<i64>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let data = PredictionMarketInstruction::UpdateMarketSchedule(UpdateMarketScheduleArgs {
+        market_id: MARKET_ID,
+        resolution_time,
+        finalization_deadline,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(signer.pubkey(), true),
+            AccountMeta::new(market_pda(), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn load_market(context: &mut ProgramTestContext) -> Market {
+    let account = context.banks_client.get_account(market_pda()).await.unwrap().unwrap();
+    Market::deserialize(&mut &account.data[..]).unwrap()
+}
+
+#[tokio::test]
+async fn test_update_pending_market_schedule() {
+    let (mut context, creator) = setup(MarketStatus::Pending).await;
+
+    // Only the resolution time moves; the deadline is kept
+    let new_resolution = RESOLUTION_TIME + 3600;
+    try_update(&mut context, &creator, Some(new_resolution), None).await.unwrap();
+    let market = load_market(&mut context).await;
+    assert_eq!(market.resolution_time, new_resolution);
+    assert_eq!(market.finalization_deadline, FINALIZATION_DEADLINE);
+    assert_eq!(market.updated_at, NOW);
+
+    try_update(&mut context, &creator, None, Some(FINALIZATION_DEADLINE + 3600)).await.unwrap();
+    let market = load_market(&mut context).await;
+    assert_eq!(market.resolution_time, new_resolution);
+    assert_eq!(market.finalization_deadline, FINALIZATION_DEADLINE + 3600);
+}
+
+#[tokio::test]
+async fn test_update_schedule_validated_as_on_creation() {
+    let (mut context, creator) = setup(MarketStatus::Pending).await;
+
+    let result = try_update(&mut context, &creator, Some(NOW), None).await;
+    assert_custom_error(result, PredictionMarketError::InvalidResolutionTime);
+
+    // Moving resolution past the kept deadline breaks the ordering
+    let result = try_update(&mut context, &creator, Some(FINALIZATION_DEADLINE), None).await;
+    assert_custom_error(result, PredictionMarketError::InvalidFinalizationDeadline);
+
+    let market = load_market(&mut context).await;
+    assert_eq!(market.resolution_time, RESOLUTION_TIME);
+}
+
+#[tokio::test]
+async fn test_update_schedule_rejected_after_activation() {
+    let (mut context, creator) = setup(MarketStatus::Active).await;
+
+    let result = try_update(&mut context, &creator, Some(RESOLUTION_TIME + 3600), None).await;
+    assert_custom_error(result, PredictionMarketError::InvalidMarketStatus);
+    assert_eq!(load_market(&mut context).await.resolution_time, RESOLUTION_TIME);
+}
+
+#[tokio::test]
+async fn test_update_schedule_creator_only() {
+    let (mut context, _creator) = setup(MarketStatus::Pending).await;
+
+    let outsider = Keypair::new();
+    let result = try_update(&mut context, &outsider, Some(RESOLUTION_TIME + 3600), None).await;
+    assert_custom_error(result, PredictionMarketError::Unauthorized);
+}
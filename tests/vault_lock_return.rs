@@ -0,0 +1,249 @@
+//! Integration tests for Vault locks clamped to the available balance
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use prediction_market_program::{
+    instruction::{
+        PredictionMarketInstruction, ProposeResultArgs, RelayerMintCompleteSetArgs,
+        RelayerMintMultiOutcomeCompleteSetArgs, RelayerPlaceOrderV2Args,
+    },
+    Market, MarketResult, MarketType, MultiOutcomePosition, Order, OrderSide, OrderType, Outcome,
+    Position, PredictionMarketConfig, PredictionMarketError, ORDER_SEED,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 30;
+const MULTI_MARKET_ID: u64 = 31;
+/// Market past its resolution time, open for proposals
+const EXPIRED_MARKET_ID: u64 = 32;
+/// The user's available Vault balance
+const AVAILABLE: u64 = CLAMPED_BALANCE;
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    user: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = program_test();
+    let vault_program = add_clamping_vault(&mut program_test);
+
+    let relayer = Keypair::new();
+    let user = Pubkey::new_unique();
+    let config = PredictionMarketConfig {
+        oracle_admin: relayer.pubkey(),
+        proposer_bond_e6: AVAILABLE + 1,
+        ..test_config(relayer.pubkey(), vault_program)
+    };
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(MARKET_ID));
+    add_market(
+        &mut program_test,
+        &Market {
+            market_type: MarketType::MultiOutcome,
+            num_outcomes: 3,
+            ..test_market(MULTI_MARKET_ID)
+        },
+    );
+    add_market(
+        &mut program_test,
+        &Market {
+            resolution_time: 0,
+            ..test_market(EXPIRED_MARKET_ID)
+        },
+    );
+    program_test.add_account(
+        multi_position_pda(MULTI_MARKET_ID, &user),
+        program_account(
+            &MultiOutcomePosition::new(MULTI_MARKET_ID, 3, user, 255, 0),
+            MultiOutcomePosition::SIZE,
+        ),
+    );
+    program_test.add_account(
+        relayer.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        user,
+    }
+}
+
+async fn try_send(
+    setup: &mut Setup,
+    data: Vec<u8>,
+    metas: Vec<AccountMeta>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let ix = Instruction::new_with_bytes(prediction_market_program::id(), &data, metas);
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.relayer.pubkey()),
+        &[&setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+async fn send(setup: &mut Setup, data: Vec<u8>, metas: Vec<AccountMeta>) {
+    try_send(setup, data, metas).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mint_complete_set_credits_clamped_lock() {
+    let mut setup = setup().await;
+
+    let data = PredictionMarketInstruction::RelayerMintCompleteSetV2(RelayerMintCompleteSetArgs {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+        amount: 5_000_000,
+        max_cost_e6: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    let metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    send(&mut setup, data, metas).await;
+
+    let position_address = position_pda(MARKET_ID, &setup.user);
+    let position: Position = load(&mut setup.context.banks_client, position_address).await;
+    assert_eq!(position.yes_amount, AVAILABLE);
+    assert_eq!(position.no_amount, AVAILABLE);
+    assert_eq!(position.total_cost_e6, AVAILABLE);
+    let market: Market = load(&mut setup.context.banks_client, market_pda(MARKET_ID)).await;
+    assert_eq!(market.total_minted, AVAILABLE);
+    assert_eq!(market.open_interest, AVAILABLE);
+}
+
+#[tokio::test]
+async fn test_buy_order_sized_to_clamped_margin() {
+    let mut setup = setup().await;
+
+    // 10 shares at 0.50 need 5 USDC of margin
+    let data = PredictionMarketInstruction::RelayerPlaceOrderV2(RelayerPlaceOrderV2Args {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+        side: OrderSide::Buy,
+        outcome: Outcome::Yes,
+        price: 500_000,
+        amount: 10_000_000,
+        order_type: OrderType::GTC,
+        expiration_time: None,
+        reduce_only: false,
+        referrer: None,
+    })
+    .try_to_vec()
+    .unwrap();
+    let order_address = pda(&[ORDER_SEED, &MARKET_ID.to_le_bytes(), &1u64.to_le_bytes()]);
+    let metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MARKET_ID), false),
+        AccountMeta::new(order_address, false),
+        AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    send(&mut setup, data, metas).await;
+
+    // 3 USDC of margin covers 6 shares
+    let order: Order = load(&mut setup.context.banks_client, order_address).await;
+    assert_eq!(order.amount, 6_000_000);
+    assert_eq!(order.calculate_cost(order.amount), AVAILABLE);
+    assert!(order.margin_locked);
+}
+
+#[tokio::test]
+async fn test_multi_outcome_mint_credits_clamped_lock() {
+    let mut setup = setup().await;
+
+    let data = PredictionMarketInstruction::RelayerMintMultiOutcomeCompleteSetV2(
+        RelayerMintMultiOutcomeCompleteSetArgs {
+            user_wallet: setup.user,
+            market_id: MULTI_MARKET_ID,
+            amount: 5_000_000,
+        },
+    )
+    .try_to_vec()
+    .unwrap();
+    let position_address = multi_position_pda(MULTI_MARKET_ID, &setup.user);
+    let metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(MULTI_MARKET_ID), false),
+        AccountMeta::new(position_address, false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    send(&mut setup, data, metas).await;
+
+    let account = setup.context.banks_client.get_account(position_address).await.unwrap().unwrap();
+    let position = MultiOutcomePosition::deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(&position.holdings[..3], &[AVAILABLE; 3]);
+    assert_eq!(position.total_cost_e6, AVAILABLE);
+    let market: Market = load(&mut setup.context.banks_client, market_pda(MULTI_MARKET_ID)).await;
+    assert_eq!(market.total_minted, AVAILABLE);
+    assert_eq!(market.open_interest, AVAILABLE);
+}
+
+#[tokio::test]
+async fn test_propose_result_rejects_clamped_bond() {
+    let mut setup = setup().await;
+
+    let data = PredictionMarketInstruction::ProposeResult(ProposeResultArgs {
+        market_id: EXPIRED_MARKET_ID,
+        result: MarketResult::Yes,
+    })
+    .try_to_vec()
+    .unwrap();
+    let metas = vec![
+        AccountMeta::new(setup.relayer.pubkey(), true),
+        AccountMeta::new_readonly(config_pda(), false),
+        AccountMeta::new(market_pda(EXPIRED_MARKET_ID), false),
+        AccountMeta::new(proposal_pda(EXPIRED_MARKET_ID), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(setup.vault_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(pm_user_pda(&setup.relayer.pubkey(), &setup.vault_program), false),
+    ];
+    let result = try_send(&mut setup, data, metas).await;
+    assert_custom_error(result, PredictionMarketError::InsufficientFunds);
+
+    // The bond shortfall leaves no proposal behind
+    let proposal = setup.context.banks_client.get_account(proposal_pda(EXPIRED_MARKET_ID)).await.unwrap();
+    assert!(proposal.is_none());
+}
@@ -0,0 +1,192 @@
+//! Integration tests that Vault CPIs only go to config.vault_program
+
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use prediction_market_program::{
+    instruction::{PredictionMarketInstruction, RelayerCancelOrderV2Args, RelayerClaimWinningsArgs},
+    Market, MarketResult, MarketStatus, Order, OrderSide, Position, PredictionMarketError,
+};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+use solana_program_test::{processor, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const MARKET_ID: u64 = 29;
+const ORDER_ID: u64 = 1;
+const AMOUNT: u64 = 5_000_000;
+
+/// Stand-in for any Vault Program: records the instruction tag in every
+/// passed account it owns so tests can tell whether funds moved
+fn recording_vault(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    for account in accounts.iter().filter(|a| a.owner == program_id) {
+        account.data.borrow_mut()[0] = data[0];
+    }
+    Ok(())
+}
+
+fn test_market(status: MarketStatus) -> Market {
+    Market {
+        status,
+        final_result: (status == MarketStatus::Resolved).then_some(MarketResult::Yes),
+        total_minted: AMOUNT,
+        open_interest: AMOUNT,
+        next_order_id: 2,
+        ..common::test_market(MARKET_ID)
+    }
+}
+
+fn test_order(owner: Pubkey) -> Order {
+    Order {
+        amount: AMOUNT,
+        ..common::test_order(MARKET_ID, ORDER_ID, owner, OrderSide::Buy)
+    }
+}
+
+fn test_position(owner: Pubkey) -> Position {
+    Position {
+        yes_amount: AMOUNT,
+        ..common::test_position(MARKET_ID, owner)
+    }
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    vault_program: Pubkey,
+    impostor: Pubkey,
+    user: Pubkey,
+    /// PMUserAccount owned by the impostor; its first byte is the recorded tag
+    witness: Pubkey,
+}
+
+async fn setup(status: MarketStatus) -> Setup {
+    let vault_program = Pubkey::new_unique();
+    let impostor = Pubkey::new_unique();
+    let mut program_test = program_test();
+    program_test.add_program("recording_vault", vault_program, processor!(recording_vault));
+    program_test.add_program("impostor_vault", impostor, processor!(recording_vault));
+    add_vault_config(&mut program_test, &vault_program);
+
+    let relayer = Keypair::new();
+    let config = test_config(relayer.pubkey(), vault_program);
+    add_config(&mut program_test, &config);
+    add_market(&mut program_test, &test_market(status));
+
+    let user = Pubkey::new_unique();
+    add_order(&mut program_test, &test_order(user));
+    add_position(&mut program_test, &test_position(user));
+
+    let witness = Pubkey::new_unique();
+    program_test.add_account(
+        witness,
+        Account {
+            lamports: 10_000_000,
+            data: vec![0u8; 1],
+            owner: impostor,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    Setup {
+        context: program_test.start_with_context().await,
+        relayer,
+        vault_program,
+        impostor,
+        user,
+        witness,
+    }
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = setup.context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&setup.context.payer.pubkey()),
+        &[&setup.context.payer, &setup.relayer],
+        recent_blockhash,
+    );
+    setup.context.banks_client.process_transaction(tx).await
+}
+
+async fn witnessed_tag(setup: &mut Setup) -> u8 {
+    let account = setup.context.banks_client.get_account(setup.witness).await.unwrap().unwrap();
+    account.data[0]
+}
+
+#[tokio::test]
+async fn test_cancel_order_rejects_wrong_vault_program() {
+    let mut setup = setup(MarketStatus::Active).await;
+
+    let data = PredictionMarketInstruction::RelayerCancelOrderV2(RelayerCancelOrderV2Args {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+        order_id: ORDER_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let ix = Instruction::new_with_bytes(
+        prediction_market_program::id(),
+        &data,
+        vec![
+            AccountMeta::new_readonly(setup.relayer.pubkey(), true),
+            AccountMeta::new_readonly(config_pda(), false),
+            AccountMeta::new(market_pda(MARKET_ID), false),
+            AccountMeta::new(order_pda(MARKET_ID, ORDER_ID), false),
+            AccountMeta::new(position_pda(MARKET_ID, &setup.user), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(setup.witness, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(setup.impostor, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let result = send(&mut setup, ix).await;
+    assert_custom_error(result, PredictionMarketError::VaultProgramMismatch);
+    assert_eq!(witnessed_tag(&mut setup).await, 0);
+}
+
+#[tokio::test]
+async fn test_claim_winnings_rejects_wrong_vault_program() {
+    let mut setup = setup(MarketStatus::Resolved).await;
+
+    let data = PredictionMarketInstruction::RelayerClaimWinningsV2(RelayerClaimWinningsArgs {
+        user_wallet: setup.user,
+        market_id: MARKET_ID,
+    })
+    .try_to_vec()
+    .unwrap();
+    let (relayer, user) = (setup.relayer.pubkey(), setup.user);
+    let claim_ix = |vault_program: Pubkey, pm_user: Pubkey| {
+        Instruction::new_with_bytes(
+            prediction_market_program::id(),
+            &data,
+            vec![
+                AccountMeta::new_readonly(relayer, true),
+                AccountMeta::new_readonly(config_pda(), false),
+                AccountMeta::new(market_pda(MARKET_ID), false),
+                AccountMeta::new(position_pda(MARKET_ID, &user), false),
+                AccountMeta::new(pm_user, false),
+                AccountMeta::new_readonly(vault_config_pda(&vault_program), false),
+                AccountMeta::new_readonly(vault_program, false),
+            ],
+        )
+    };
+
+    let ix = claim_ix(setup.impostor, setup.witness);
+    let result = send(&mut setup, ix).await;
+    assert_custom_error(result, PredictionMarketError::VaultProgramMismatch);
+    assert_eq!(witnessed_tag(&mut setup).await, 0);
+
+    // The configured Vault is accepted
+    let ix = claim_ix(setup.vault_program, pm_user_pda(&user, &setup.vault_program));
+    send(&mut setup, ix).await.unwrap();
+}